@@ -34,14 +34,82 @@ pub enum Error {
     #[error("bad AniList response ({code}): {message}")]
     BadAniListResponse { code: u16, message: String },
 
+    #[error("AniList is currently unavailable ({code})")]
+    ServiceUnavailable { code: u16 },
+
     #[error("must be authorized to make this request")]
     NeedAuthentication,
 
     #[error("requested series is not an anime")]
     NotAnAnime,
+
+    #[error("no series with ID {id}")]
+    UnknownSeries { id: u32 },
+
+    #[error("unknown status: {value}")]
+    UnknownStatus { value: String },
+}
+
+/// The general category of an `Error`, for deciding how to handle it without matching every variant.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The remote service couldn't be reached, or is down.
+    Network,
+    /// The request needs a user to be logged in, or the user isn't allowed to make it.
+    Auth,
+    /// The requested series doesn't exist.
+    NotFound,
+    /// Something couldn't be decoded, such as a response or the filename of an episode.
+    Parse,
+    /// Too many requests were made recently, and later ones should be delayed.
+    RateLimited,
+    /// Anything else, such as a file that couldn't be read.
+    Other,
+}
+
+impl ErrorKind {
+    fn from_http_code(code: u16) -> Self {
+        match code {
+            401 | 403 => Self::Auth,
+            404 => Self::NotFound,
+            429 => Self::RateLimited,
+            500..=599 => Self::Network,
+            _ => Self::Other,
+        }
+    }
 }
 
 impl Error {
+    /// Returns the general category of the error.
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        use attohttpc::ErrorKind as HttpKind;
+
+        match self {
+            Self::IO(_) | Self::NeedExistingSeriesData => ErrorKind::Other,
+            Self::Base64Decode(_)
+            | Self::UTF8Decode(_)
+            | Self::JsonDecode(_)
+            | Self::EpisodeParseFailed { .. }
+            | Self::MultipleTitles { .. }
+            | Self::UnknownStatus { .. } => ErrorKind::Parse,
+            Self::Http(source) => match source.kind() {
+                HttpKind::StatusCode(status) => ErrorKind::from_http_code(status.as_u16()),
+                HttpKind::Json(_) | HttpKind::InvalidResponse(_) => ErrorKind::Parse,
+                HttpKind::Io(_)
+                | HttpKind::ConnectError { .. }
+                | HttpKind::TooManyRedirections
+                | HttpKind::Tls(_) => ErrorKind::Network,
+                _ => ErrorKind::Other,
+            },
+            Self::BadAniListResponse { code, .. } => ErrorKind::from_http_code(*code),
+            Self::ServiceUnavailable { .. } => ErrorKind::Network,
+            Self::NeedAuthentication => ErrorKind::Auth,
+            Self::NotAnAnime | Self::UnknownSeries { .. } => ErrorKind::NotFound,
+        }
+    }
+
     #[must_use]
     pub fn is_http_code(&self, http_code: u16) -> bool {
         use attohttpc::ErrorKind;
@@ -55,4 +123,51 @@ impl Error {
             _ => false,
         }
     }
+
+    /// Returns true if the error was caused by the requested series not existing on the remote service.
+    #[must_use]
+    pub fn is_not_found(&self) -> bool {
+        self.is_http_code(404)
+    }
+
+    /// Returns true if the error was caused by the remote service being down or under maintenance.
+    #[must_use]
+    pub fn is_service_unavailable(&self) -> bool {
+        use attohttpc::ErrorKind;
+
+        match self {
+            Error::ServiceUnavailable { .. } => true,
+            Error::BadAniListResponse { code, .. } => (500..600).contains(code),
+            Error::Http(source) => match source.kind() {
+                ErrorKind::StatusCode(status) => status.is_server_error(),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_kinds() {
+        let kind = |code| {
+            Error::BadAniListResponse {
+                code,
+                message: String::new(),
+            }
+            .kind()
+        };
+
+        assert_eq!(kind(401), ErrorKind::Auth);
+        assert_eq!(kind(404), ErrorKind::NotFound);
+        assert_eq!(kind(429), ErrorKind::RateLimited);
+        assert_eq!(kind(503), ErrorKind::Network);
+        assert_eq!(kind(400), ErrorKind::Other);
+
+        assert_eq!(Error::NeedAuthentication.kind(), ErrorKind::Auth);
+        assert_eq!(Error::UnknownSeries { id: 1 }.kind(), ErrorKind::NotFound);
+    }
 }
@@ -15,12 +15,16 @@ extern crate diesel;
 
 pub mod err;
 pub mod local;
+pub mod numbering;
 pub mod remote;
+pub mod sync;
 
-pub use err::{Error, Result};
+pub use err::{Error, ErrorKind, Result};
+
+use serde_derive::{Deserialize, Serialize};
 
 /// Represents the type of a series.
-#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
 pub enum SeriesKind {
     /// TV episodes.
     Season,
@@ -83,3 +87,81 @@ where
 
     best_match
 }
+
+/// Long vowels that are romanized in different ways, and the single vowel they are reduced to.
+///
+/// Macrons are expanded before these are applied, so a title like "Kōkaku" becomes "kokaku".
+const LONG_VOWELS: [(&str, &str); 6] = [
+    ("ou", "o"),
+    ("oo", "o"),
+    ("uu", "u"),
+    ("aa", "a"),
+    ("ii", "i"),
+    ("ee", "e"),
+];
+
+/// Normalize `title` so different romanizations of the same title can be compared.
+///
+/// This lowercases the title, expands macrons and circumflexes, replaces punctuation with a single space,
+/// and reduces long vowels to a single vowel. For example, "Yuu Yuu Hakusho" and "Yū Yū Hakusho!" both
+/// become "yu yu hakusho".
+#[must_use]
+pub fn normalize_title(title: &str) -> String {
+    let mut normalized = String::with_capacity(title.len());
+
+    for ch in title.chars().flat_map(char::to_lowercase) {
+        match ch {
+            'ā' | 'â' => normalized.push_str("aa"),
+            'ī' | 'î' => normalized.push_str("ii"),
+            'ū' | 'û' => normalized.push_str("uu"),
+            'ē' | 'ê' => normalized.push_str("ee"),
+            'ō' | 'ô' => normalized.push_str("ou"),
+            // Apostrophes are part of a word, so they shouldn't split it
+            '\'' | '’' => (),
+            ch if ch.is_alphanumeric() => normalized.push(ch),
+            _ if normalized.is_empty() || normalized.ends_with(' ') => (),
+            _ => normalized.push(' '),
+        }
+    }
+
+    if normalized.ends_with(' ') {
+        normalized.pop();
+    }
+
+    for (long, short) in &LONG_VOWELS {
+        if normalized.contains(long) {
+            normalized = normalized.replace(long, short);
+        }
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_title_romanizations() {
+        let matching = [
+            ("Yuu Yuu Hakusho", "Yu Yu Hakusho"),
+            ("Yū Yū Hakusho!", "yu yu hakusho"),
+            ("Kōkaku Kidōtai", "Koukaku Kidoutai"),
+            ("Re:Zero - Starting Life", "Re Zero Starting Life"),
+            ("Kino's Journey", "Kinos Journey"),
+            ("  Shoujo  ", "shojo"),
+        ];
+
+        for (left, right) in &matching {
+            assert_eq!(
+                normalize_title(left),
+                normalize_title(right),
+                "{} should match {}",
+                left,
+                right
+            );
+        }
+
+        assert_eq!(normalize_title("Yū Yū Hakusho!"), "yu yu hakusho");
+    }
+}
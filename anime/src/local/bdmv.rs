@@ -0,0 +1,236 @@
+//! Detection of episodes in Blu-ray folder structures, where every video is stored as a numbered `.m2ts` clip in
+//! `BDMV/STREAM` and the order they're played in is stored in the playlists in `BDMV/PLAYLIST`.
+
+use super::{Episode, SortedEpisodes};
+use crate::err::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Clips smaller than this fraction of the largest clip are assumed to be menus, previews, or creditless
+/// openings and endings rather than episodes.
+const MIN_EPISODE_SIZE_RATIO: u64 = 2;
+
+/// Returns true if `dir` is a Blu-ray folder, or contains one for each disc.
+#[must_use]
+pub fn is_bdmv<P>(dir: P) -> bool
+where
+    P: AsRef<Path>,
+{
+    !disc_dirs(dir.as_ref()).is_empty()
+}
+
+/// Returns the episodes in the Blu-ray folder `dir`.
+///
+/// Episodes are numbered in the order the longest playlist of each disc plays them in, and discs are ordered
+/// by their folder name. Discs without any usable playlists have their clips numbered in filename order instead.
+///
+/// The filename of each episode is its path relative to `dir`.
+pub fn episodes<P>(dir: P) -> Result<SortedEpisodes>
+where
+    P: AsRef<Path>,
+{
+    let dir = dir.as_ref();
+    let mut episodes = Vec::new();
+
+    for disc in disc_dirs(dir) {
+        let bdmv = disc.join("BDMV");
+
+        for clip in episode_clips(&bdmv)? {
+            let path = bdmv.join("STREAM").join(clip);
+            let filename = path.strip_prefix(dir).unwrap_or(&path);
+
+            let number = episodes.len() as u32 + 1;
+            episodes.push(Episode::new(
+                number,
+                filename.to_string_lossy().into_owned(),
+            ));
+        }
+    }
+
+    Ok(SortedEpisodes::with_episodes(episodes))
+}
+
+/// Returns `dir` if it has a `BDMV/STREAM` folder, or every folder within `dir` that does, sorted by name.
+fn disc_dirs(dir: &Path) -> Vec<PathBuf> {
+    let has_stream = |dir: &Path| dir.join("BDMV").join("STREAM").is_dir();
+
+    if has_stream(dir) {
+        return vec![dir.to_path_buf()];
+    }
+
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut discs = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| has_stream(path))
+        .collect::<Vec<_>>();
+
+    discs.sort_unstable();
+    discs
+}
+
+/// Returns the filenames of every episode clip in the `BDMV` folder `bdmv`, in the order they're played in.
+fn episode_clips(bdmv: &Path) -> Result<Vec<String>> {
+    let sizes = clip_sizes(&bdmv.join("STREAM"))?;
+
+    let largest = match sizes.values().max() {
+        Some(&largest) => largest,
+        None => return Ok(Vec::new()),
+    };
+
+    let is_episode = |clip: &String| {
+        sizes
+            .get(clip)
+            .map_or(false, |&size| size >= largest / MIN_EPISODE_SIZE_RATIO)
+    };
+
+    let longest_playlist = playlists(&bdmv.join("PLAYLIST"))
+        .into_iter()
+        .map(|clips| unique_clips(clips).filter(is_episode).collect::<Vec<_>>())
+        .max_by_key(Vec::len)
+        .filter(|clips| clips.len() > 1);
+
+    if let Some(clips) = longest_playlist {
+        return Ok(clips);
+    }
+
+    let mut clips = sizes
+        .keys()
+        .filter(|clip| is_episode(clip))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    clips.sort_unstable();
+    Ok(clips)
+}
+
+/// Returns the size of every `.m2ts` clip in `stream_dir`, keyed by filename.
+fn clip_sizes(stream_dir: &Path) -> Result<HashMap<String, u64>> {
+    let mut sizes = HashMap::new();
+
+    for entry in fs::read_dir(stream_dir)? {
+        let entry = entry?;
+        let filename = entry.file_name().to_string_lossy().into_owned();
+
+        if !filename.to_ascii_lowercase().ends_with(".m2ts") {
+            continue;
+        }
+
+        sizes.insert(filename, entry.metadata()?.len());
+    }
+
+    Ok(sizes)
+}
+
+/// Returns the clips of every playlist in `playlist_dir` that could be read, sorted by playlist filename.
+fn playlists(playlist_dir: &Path) -> Vec<Vec<String>> {
+    let mut paths = match fs::read_dir(playlist_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect::<Vec<_>>(),
+        Err(_) => return Vec::new(),
+    };
+
+    paths.sort_unstable();
+
+    paths
+        .into_iter()
+        .filter_map(|path| fs::read(path).ok())
+        .filter_map(|bytes| parse_playlist(&bytes))
+        .collect()
+}
+
+/// Returns the clips in `clips` that are only played once, since clips that are played multiple times in the same
+/// playlist are usually openings and endings shared between episodes.
+fn unique_clips(clips: Vec<String>) -> impl Iterator<Item = String> {
+    let mut counts = HashMap::with_capacity(clips.len());
+
+    for clip in &clips {
+        *counts.entry(clip.clone()).or_insert(0) += 1;
+    }
+
+    clips.into_iter().filter(move |clip| counts[clip] == 1)
+}
+
+/// Parses the filename of each clip a `.mpls` playlist plays, in the order they're played in.
+fn parse_playlist(bytes: &[u8]) -> Option<Vec<String>> {
+    let read_u16 = |pos: usize| {
+        let bytes = bytes.get(pos..pos + 2)?;
+        Some(usize::from(u16::from_be_bytes([bytes[0], bytes[1]])))
+    };
+
+    if bytes.get(..4)? != b"MPLS" {
+        return None;
+    }
+
+    let start = bytes.get(8..12)?;
+    let start = u32::from_be_bytes([start[0], start[1], start[2], start[3]]) as usize;
+
+    // The playlist starts with its length, a reserved field, and then the number of play items
+    let num_items = read_u16(start + 6)?;
+    let mut pos = start + 10;
+    let mut clips = Vec::with_capacity(num_items);
+
+    for _ in 0..num_items {
+        let len = read_u16(pos)?;
+        let name = bytes.get(pos + 2..pos + 7)?;
+
+        clips.push(format!("{}.m2ts", String::from_utf8_lossy(name)));
+        pos += 2 + len;
+    }
+
+    Some(clips)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playlist(clips: &[&str]) -> Vec<u8> {
+        let mut bytes = b"MPLS0200".to_vec();
+        // Playlist start address
+        bytes.extend_from_slice(&20_u32.to_be_bytes());
+        bytes.resize(20, 0);
+
+        // Length, reserved, number of play items, and number of sub paths
+        bytes.extend_from_slice(&[0; 6]);
+        bytes.extend_from_slice(&(clips.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&[0; 2]);
+
+        for clip in clips {
+            let item = format!("{}M2TS", clip);
+            bytes.extend_from_slice(&(item.len() as u16).to_be_bytes());
+            bytes.extend_from_slice(item.as_bytes());
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn playlist_parsing() {
+        let clips = parse_playlist(&playlist(&["00002", "00001", "00003"]));
+        assert_eq!(
+            clips,
+            Some(vec![
+                "00002.m2ts".into(),
+                "00001.m2ts".into(),
+                "00003.m2ts".into()
+            ])
+        );
+
+        assert_eq!(parse_playlist(b"MPLS"), None);
+        assert_eq!(parse_playlist(&playlist(&[])), Some(Vec::new()));
+
+        let clips = vec!["00009", "00001", "00009", "00002", "00009"]
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        let unique = unique_clips(clips).collect::<Vec<_>>();
+        assert_eq!(unique, ["00001", "00002"]);
+    }
+}
@@ -10,13 +10,10 @@ where
     I: Iterator<Item = DirEntry>,
     S: Into<String>,
 {
-    let mut name = name.into();
-    name.make_ascii_lowercase();
+    let name = crate::normalize_title(&name.into());
 
     crate::closest_match(items, min_confidence, |dir| {
-        let mut dir_name = parse_title(dir.file_name())?;
-        dir_name.make_ascii_lowercase();
-
+        let dir_name = crate::normalize_title(&parse_title(dir.file_name())?);
         Some(strsim::jaro(&dir_name, &name) as f32)
     })
     .map(|(_, dir)| dir)
@@ -7,6 +7,7 @@ use crate::err::{Error, Result};
 use crate::SeriesKind;
 use smallvec::{smallvec, SmallVec};
 use std::borrow::Cow;
+use std::collections::HashSet;
 use std::ops::{Deref, DerefMut};
 use std::str;
 
@@ -302,6 +303,100 @@ impl CustomPattern {
         None
     }
 
+    /// Returns patterns that find a different episode number in every filename in `filenames`.
+    ///
+    /// Each number in the first filename is tried as the episode number, unless it comes after a character that
+    /// patterns can't match literally. Patterns that skip to it with a wildcard
+    /// come before the ones that match everything in front of it, since they're less likely to break when
+    /// the start of later filenames changes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use anime::local::detect::CustomPattern;
+    ///
+    /// let filenames = ["[Tag] Series 2 - 01.mkv", "[Tag] Series 2 - 02.mkv"];
+    /// let suggested = CustomPattern::suggest(&filenames);
+    ///
+    /// assert_eq!(suggested[0], CustomPattern::new("*- #"));
+    /// ```
+    #[must_use]
+    pub fn suggest<S>(filenames: &[S]) -> Vec<Self>
+    where
+        S: AsRef<str>,
+    {
+        let first = match filenames.first() {
+            Some(first) => first.as_ref(),
+            None => return Vec::new(),
+        };
+
+        let number_starts = first.char_indices().filter(|&(i, ch)| {
+            ch.is_ascii_digit() && !first[..i].ends_with(|prev: char| prev.is_ascii_digit())
+        });
+
+        let mut suggestions = Vec::new();
+
+        for (start, _) in number_starts {
+            let prefix = &first[..start];
+
+            // Patterns can't match these characters literally
+            if prefix.contains(&[Self::WILDCARD, Self::EPISODE_MARKER][..]) {
+                continue;
+            }
+
+            let candidates = [
+                Self::wildcard_to(prefix),
+                Some(Self(format!("{}{}", prefix, Self::EPISODE_MARKER))),
+            ];
+
+            for candidate in candidates.iter().flatten() {
+                if suggestions.contains(candidate) || !candidate.numbers_uniquely(filenames) {
+                    continue;
+                }
+
+                suggestions.push(candidate.clone());
+            }
+        }
+
+        suggestions
+    }
+
+    /// Returns a pattern that skips to the end of `prefix` with a wildcard before looking for an episode.
+    fn wildcard_to(prefix: &str) -> Option<Self> {
+        if !prefix.contains(|ch: char| ch.is_ascii_digit()) {
+            return Some(Self(format!("{}{}", Self::WILDCARD, Self::EPISODE_MARKER)));
+        }
+
+        // A wildcard stops at the first character that matches the one after it, so the text after the wildcard
+        // has to start with the last character that doesn't appear earlier in the prefix
+        let mut seen = HashSet::new();
+        let mut anchor = None;
+
+        for (i, ch) in prefix.char_indices() {
+            if seen.insert(ch.to_ascii_lowercase()) {
+                anchor = Some(i);
+            }
+        }
+
+        let anchor = &prefix[anchor.filter(|&i| i > 0)?..];
+        let pattern = format!("{}{}{}", Self::WILDCARD, anchor, Self::EPISODE_MARKER);
+
+        Some(Self(pattern))
+    }
+
+    /// Returns true if the current pattern finds a different episode number in every filename in `filenames`.
+    fn numbers_uniquely<S>(&self, filenames: &[S]) -> bool
+    where
+        S: AsRef<str>,
+    {
+        let mut found = HashSet::with_capacity(filenames.len());
+
+        filenames.iter().all(|filename| {
+            self.detect_episode(filename)
+                .map_or(false, |episode| found.insert(episode))
+        })
+    }
+
     /// Returns true if the current pattern contains the episode marker character.
     #[inline]
     #[must_use]
@@ -676,4 +771,32 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn custom_pattern_suggestions() {
+        let suggest = |filenames: &[&str]| {
+            CustomPattern::suggest(filenames)
+                .into_iter()
+                .map(|pattern| pattern.0)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            suggest(&[
+                "[Tag] Series 2 - 01 [1080p].mkv",
+                "[Tag] Series 2 - 02 [1080p].mkv"
+            ]),
+            ["*- #", "[Tag] Series 2 - #"]
+        );
+
+        assert_eq!(
+            suggest(&["Series S2 E01.mkv", "Series S2 E02.mkv"]),
+            ["*2 E#", "Series S2 E#"]
+        );
+
+        assert_eq!(suggest(&["01.mkv", "02.mkv"]), ["*#", "#"]);
+        assert!(suggest(&["Series - 01.mkv", "Series - 01v2.mkv"]).is_empty());
+        assert!(suggest(&["Series.mkv"]).is_empty());
+        assert!(suggest(&[]).is_empty());
+    }
 }
@@ -1,3 +1,4 @@
+pub mod bdmv;
 pub mod detect;
 
 pub use detect::{EpisodeParser, ParsedEpisode};
@@ -83,6 +84,16 @@ impl SortedEpisodes {
         self.0.push(episode);
     }
 
+    /// Insert `episode` while keeping every episode sorted.
+    ///
+    /// An existing episode with the same number is replaced.
+    pub fn insert(&mut self, episode: Episode) {
+        match self.0.binary_search_by_key(&episode.number, |ep| ep.number) {
+            Ok(index) => self.0[index] = episode,
+            Err(index) => self.0.insert(index, episode),
+        }
+    }
+
     /// Returns a reference to the episode with the specified `number`.
     #[inline]
     #[must_use]
@@ -99,6 +110,34 @@ impl SortedEpisodes {
         self.0.last().map_or(0, |ep| ep.number)
     }
 
+    /// Subtract `offset` from the number of every episode.
+    ///
+    /// Episodes that would end up with a number below 1 are removed.
+    #[must_use]
+    pub fn with_offset(self, offset: i32) -> Self {
+        if offset == 0 {
+            return self;
+        }
+
+        let episodes = self
+            .0
+            .into_iter()
+            .filter_map(|mut episode| {
+                let number = i64::from(episode.number) - i64::from(offset);
+
+                if number < 1 || number > i64::from(u32::MAX) {
+                    return None;
+                }
+
+                episode.number = number as u32;
+                Some(episode)
+            })
+            .collect();
+
+        // The relative order of each episode is preserved, so there is no need to sort again
+        Self(episodes)
+    }
+
     fn sort(&mut self) {
         self.0.sort_unstable();
         self.0.dedup();
@@ -162,14 +201,39 @@ impl CategorizedEpisodes {
     }
 
     /// Find the first matching series episodes in `dir` with the specified `parser`.
+    ///
+    /// If `dir` is a Blu-ray folder, its episodes are found with [`bdmv::episodes`] instead and `parser` isn't used.
     pub fn parse<P>(dir: P, parser: &EpisodeParser) -> Result<Self>
     where
         P: AsRef<Path>,
+    {
+        if bdmv::is_bdmv(&dir) {
+            let episodes = bdmv::episodes(dir)?;
+            let mut categorized = HashMap::with_capacity(1);
+
+            if !episodes.is_empty() {
+                categorized.insert(SeriesKind::Season, episodes);
+            }
+
+            return Ok(Self(categorized));
+        }
+
+        Self::parse_filenames(episode_filenames(dir)?, parser)
+    }
+
+    /// Find the first matching series episodes in `filenames` with the specified `parser`.
+    ///
+    /// This is useful when the files aren't in a local directory, such as when they're listed by a server.
+    pub fn parse_filenames<I>(filenames: I, parser: &EpisodeParser) -> Result<Self>
+    where
+        I: IntoIterator<Item = String>,
     {
         let mut last_title: Option<String> = None;
         let mut episodes = HashMap::with_capacity(1);
 
-        Self::parse_eps_in_dir_with(dir, parser, |parsed, filename| {
+        for filename in filenames {
+            let parsed = parser.parse(&filename)?;
+
             if let Some(series_name) = parsed.title {
                 match &mut last_title {
                     Some(last_title) => {
@@ -190,51 +254,46 @@ impl CategorizedEpisodes {
 
             let episode = Episode::new(parsed.episode, filename);
             cat_epsisodes.push(episode);
-
-            Ok(())
-        })?;
+        }
 
         Self::sort_all(&mut episodes);
 
         Ok(Self(episodes))
     }
 
-    fn parse_eps_in_dir_with<P, F>(dir: P, parser: &EpisodeParser, mut inserter: F) -> Result<()>
-    where
-        P: AsRef<Path>,
-        F: FnMut(ParsedEpisode, String) -> Result<()>,
-    {
-        let dir = dir.as_ref();
-        let entries = fs::read_dir(dir)?;
+    fn sort_all(episode_cats: &mut EpisodeMap) {
+        for episodes in episode_cats.values_mut() {
+            episodes.sort();
+        }
+    }
+}
 
-        for entry in entries {
-            let entry = entry?;
-            let entry_type = entry.file_type()?;
+/// Returns the name of every file in `dir` that may be an episode, without parsing them.
+#[allow(clippy::case_sensitive_file_extension_comparisons)]
+pub fn episode_filenames<P>(dir: P) -> Result<Vec<String>>
+where
+    P: AsRef<Path>,
+{
+    let mut filenames = Vec::new();
 
-            if entry_type.is_dir() {
-                continue;
-            }
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
 
-            let filename = entry.file_name();
-            let filename = filename.to_string_lossy();
+        if entry.file_type()?.is_dir() {
+            continue;
+        }
 
-            // The .part extension is commonly used to indicate that a file is incomplete
-            if filename.ends_with(".part") {
-                continue;
-            }
+        let filename = entry.file_name().to_string_lossy().into_owned();
 
-            let episode = parser.parse(filename.as_ref())?;
-            inserter(episode, filename.into_owned())?;
+        // The .part extension is commonly used to indicate that a file is incomplete
+        if filename.ends_with(".part") {
+            continue;
         }
 
-        Ok(())
+        filenames.push(filename);
     }
 
-    fn sort_all(episode_cats: &mut EpisodeMap) {
-        for episodes in episode_cats.values_mut() {
-            episodes.sort();
-        }
-    }
+    Ok(filenames)
 }
 
 impl Deref for CategorizedEpisodes {
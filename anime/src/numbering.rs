@@ -0,0 +1,144 @@
+use crate::remote::SeriesID;
+
+/// A series in a franchise and the number of episodes it has.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct NumberedSeries {
+    pub id: SeriesID,
+    /// The number of episodes the series has, or 0 if it isn't known yet (such as when it is still airing).
+    pub episodes: u32,
+}
+
+impl NumberedSeries {
+    #[inline(always)]
+    #[must_use]
+    pub fn new(id: SeriesID, episodes: u32) -> Self {
+        Self { id, episodes }
+    }
+}
+
+/// Translates between absolute episode numbers that continue across every series in a franchise and
+/// episode numbers relative to each series.
+///
+/// For example, with a first season of 12 episodes followed by a second season of 13, absolute episode 14
+/// is the second episode of the second season.
+///
+/// A series with an unknown number of episodes is assumed to have every episode after it when it is the last one.
+#[derive(Clone, Debug, Default)]
+pub struct SeasonNumbering {
+    series: Vec<NumberedSeries>,
+}
+
+impl SeasonNumbering {
+    /// Create a new `SeasonNumbering` with `series` in the order they were released.
+    #[must_use]
+    pub fn new(series: Vec<NumberedSeries>) -> Self {
+        Self { series }
+    }
+
+    /// Add a series to the end of the franchise.
+    pub fn push(&mut self, id: SeriesID, episodes: u32) {
+        self.series.push(NumberedSeries::new(id, episodes));
+    }
+
+    /// Returns every series in the franchise, in order.
+    #[inline(always)]
+    #[must_use]
+    pub fn series(&self) -> &[NumberedSeries] {
+        &self.series
+    }
+
+    /// Returns the number of episodes that come before the series with the specified `id`.
+    #[must_use]
+    pub fn offset_of(&self, id: SeriesID) -> Option<u32> {
+        let mut offset = 0;
+
+        for series in &self.series {
+            if series.id == id {
+                return Some(offset);
+            }
+
+            offset += series.episodes;
+        }
+
+        None
+    }
+
+    /// Returns the total number of episodes in the franchise, excluding any series with an unknown number of episodes.
+    #[must_use]
+    pub fn total_episodes(&self) -> u32 {
+        self.series.iter().map(|series| series.episodes).sum()
+    }
+
+    /// Convert the `absolute` episode number to the series it belongs to and its episode number relative to that series.
+    ///
+    /// Returns None if the episode is 0 or comes after every series in the franchise.
+    #[must_use]
+    pub fn to_relative(&self, absolute: u32) -> Option<(SeriesID, u32)> {
+        if absolute == 0 {
+            return None;
+        }
+
+        let last = self.series.len().checked_sub(1)?;
+        let mut offset = 0;
+
+        for (i, series) in self.series.iter().enumerate() {
+            let relative = absolute - offset;
+
+            if relative <= series.episodes || (i == last && series.episodes == 0) {
+                return Some((series.id, relative));
+            }
+
+            offset += series.episodes;
+        }
+
+        None
+    }
+
+    /// Convert the `relative` episode number of the series with the specified `id` to its absolute episode number.
+    ///
+    /// Returns None if the series isn't part of the franchise, or if it doesn't have the episode.
+    #[must_use]
+    pub fn to_absolute(&self, id: SeriesID, relative: u32) -> Option<u32> {
+        let series = self.series.iter().find(|series| series.id == id)?;
+
+        if relative == 0 || (series.episodes > 0 && relative > series.episodes) {
+            return None;
+        }
+
+        self.offset_of(id).map(|offset| offset + relative)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn absolute_episodes_round_trip() {
+        let numbering = SeasonNumbering::new(vec![
+            NumberedSeries::new(1, 12),
+            NumberedSeries::new(2, 13),
+            NumberedSeries::new(3, 0),
+        ]);
+
+        assert_eq!(numbering.to_relative(0), None);
+        assert_eq!(numbering.to_relative(1), Some((1, 1)));
+        assert_eq!(numbering.to_relative(12), Some((1, 12)));
+        assert_eq!(numbering.to_relative(14), Some((2, 2)));
+        assert_eq!(numbering.to_relative(26), Some((3, 1)));
+        assert_eq!(numbering.to_relative(40), Some((3, 15)));
+
+        for absolute in 1..=40 {
+            let (id, relative) = numbering.to_relative(absolute).unwrap();
+            assert_eq!(numbering.to_absolute(id, relative), Some(absolute));
+        }
+
+        assert_eq!(numbering.to_absolute(2, 14), None);
+        assert_eq!(numbering.to_absolute(4, 1), None);
+        assert_eq!(numbering.offset_of(3), Some(25));
+
+        let finished = SeasonNumbering::new(vec![NumberedSeries::new(1, 12)]);
+        assert_eq!(finished.to_relative(13), None);
+        assert_eq!(SeasonNumbering::default().to_relative(1), None);
+    }
+}
@@ -1,15 +1,17 @@
 #![allow(clippy::doc_markdown)]
 
 use super::{
-    AccessToken, RemoteService, ScoreParser, Sequel, SeriesDate, SeriesEntry, SeriesID, SeriesInfo,
-    SeriesKind, SeriesTitle, Status,
+    AccessToken, AiringEpisode, Capabilities, EpisodeTitle, Notification, NotificationKind,
+    NotificationSeries, RemoteService, ScoreParser, ScoreRounding, Sequel, SeriesDate, SeriesEntry,
+    SeriesID, SeriesInfo, SeriesKind, SeriesTitle, Status, UserStats,
 };
 use crate::err::{Error, Result};
+use graphql_client::GraphQLQuery;
 use serde_derive::{Deserialize, Serialize};
 use serde_json as json;
 use serde_json::json;
 use std::borrow::Cow;
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 use std::result;
 use std::time::Duration;
 
@@ -30,6 +32,13 @@ pub fn auth_url(client_id: u32) -> String {
     )
 }
 
+/// Returns the URL of the web page for the series with the specified `id`.
+#[inline]
+#[must_use]
+pub fn series_url(id: SeriesID) -> String {
+    format!("https://anilist.co/anime/{}", id)
+}
+
 // This macro tests how far you can go with const functions for things like string manipulation.
 // It is a lot more complicated than the original naive implementation, but it saves us from an O(n) operation with allocations
 // that would otherwise be performed for each API query.
@@ -39,6 +48,10 @@ macro_rules! minimize_query {
     ($value:expr) => {{
         const LEN: usize = $value.len();
 
+        const fn is_name_byte(byte: u8) -> bool {
+            byte.is_ascii_alphanumeric() || byte == b'_'
+        }
+
         // This function needs to be generated on a per-string basis so our array length can be semi-close to our minimized result.
         // If we don't do this, LLVM seems to emit assembly that uses way more stack space than necessary on Rust 1.46.0+
         const fn minimize() -> [u8; LEN] {
@@ -46,6 +59,7 @@ macro_rules! minimize_query {
             let mut result = [0; LEN];
             let mut result_index = 0;
             let mut index = 0;
+            let mut skipped_space = false;
 
             while index < bytes.len() {
                 let byte = bytes[index];
@@ -53,9 +67,21 @@ macro_rules! minimize_query {
                 index += 1;
 
                 if byte == b' ' || byte == b'\n' {
+                    skipped_space = true;
                     continue;
                 }
 
+                // Names still need to be separated from each other, like the keyword and name of an operation
+                if skipped_space
+                    && result_index > 0
+                    && is_name_byte(result[result_index - 1])
+                    && is_name_byte(byte)
+                {
+                    result[result_index] = b' ';
+                    result_index += 1;
+                }
+
+                skipped_space = false;
                 result[result_index] = byte;
                 result_index += 1;
             }
@@ -76,39 +102,57 @@ macro_rules! minimize_query {
     }};
 }
 
-/// Send an API query to AniList, without attemping to parse a response.
-macro_rules! send {
-    ($token:expr, $file:expr, {$($vars:tt)*}, $($resp_root:expr)=>*) => {{
+/// Send an API query to AniList, and parse the response into the data type generated for it.
+///
+/// `$query` is the module generated for the query, and `$vars` are its variables.
+macro_rules! query {
+    ($token:expr, $query:ident, $vars:expr) => {{
         if cfg!(debug_assertions) && cfg!(feature = "print-requests-debug") {
-            println!("DEBUG: AniList request: {}", $file);
+            println!("DEBUG: AniList request: {}", $query::OPERATION_NAME);
         }
 
-        let vars = json!({
-            $($vars)*
-        });
-
-        let query = minimize_query!(include_str!(concat!("../../graphql/anilist/", $file, ".gql")));
+        let query = minimize_query!($query::QUERY);
 
-        #[allow(unused_mut)]
-        match send_gql_request(query, &vars, $token) {
-            Ok(mut json) => {
-                $(json = json[$resp_root].take();)*
-                Ok(json)
-            },
-            Err(err) => Err(err),
-        }
+        send_gql_request(query, &$vars, $token).and_then(|mut json| {
+            json::from_value::<$query::ResponseData>(json["data"].take()).map_err(Into::into)
+        })
     }};
 }
 
-/// Send an API query to AniList, and attempt to parse the response into a specified type.
-macro_rules! query {
-    ($token:expr, $file:expr, {$($vars:tt)*}, $($resp_root:expr)=>*) => {
-        send!($token, $file, {$($vars)*}, $($resp_root)=>*).and_then(|json| {
-            json::from_value(json).map_err(Into::into)
-        })
+/// Generate the types for a query in `graphql/anilist`, checked against the subset of the AniList schema in `schema.graphql`.
+///
+/// The types end up in a module with the same name as the query file.
+/// Enums that we need to convert or send are defined by us, so they can be shared between queries.
+macro_rules! graphql_query {
+    ($name:ident, $file:tt) => {
+        // Only the module generated for the query is used
+        #[allow(dead_code)]
+        #[derive(GraphQLQuery)]
+        #[graphql(
+                    schema_path = "graphql/anilist/schema.graphql",
+                    query_path = $file,
+                    normalization = "rust",
+                    extern_enums("MediaFormat", "MediaRelation", "MediaListStatus", "ScoreFormat"),
+                    response_derives = "Debug"
+                )]
+        struct $name;
     };
 }
 
+graphql_query!(InfoById, "graphql/anilist/info_by_id.gql");
+graphql_query!(InfoByName, "graphql/anilist/info_by_name.gql");
+graphql_query!(GetListEntry, "graphql/anilist/get_list_entry.gql");
+graphql_query!(GetListEntries, "graphql/anilist/get_list_entries.gql");
+graphql_query!(UpdateListEntry, "graphql/anilist/update_list_entry.gql");
+graphql_query!(ListEntryId, "graphql/anilist/list_entry_id.gql");
+graphql_query!(DeleteListEntry, "graphql/anilist/delete_list_entry.gql");
+graphql_query!(NextAiringEpisode, "graphql/anilist/next_airing_episode.gql");
+graphql_query!(EpisodeTitles, "graphql/anilist/episode_titles.gql");
+graphql_query!(Viewer, "graphql/anilist/user.gql");
+graphql_query!(Notifications, "graphql/anilist/notifications.gql");
+graphql_query!(ViewerStats, "graphql/anilist/viewer_stats.gql");
+graphql_query!(Ping, "graphql/anilist/ping.gql");
+
 /// A connection to the AniList API.
 #[derive(Debug)]
 pub enum AniList {
@@ -144,68 +188,279 @@ impl AniList {
             Self::Unauthenticated => ScoreFormat::default(),
         }
     }
+
+    fn score_rounding(&self) -> ScoreRounding {
+        match &self {
+            Self::Authenticated(auth) => auth.score_rounding,
+            Self::Unauthenticated => ScoreRounding::default(),
+        }
+    }
 }
 
 impl RemoteService for AniList {
     fn search_info_by_name(&self, name: &str) -> Result<Vec<SeriesInfo>> {
-        let entries: Vec<Media> = query!(
+        let data = query!(
             self.auth_token().ok(),
-            "info_by_name",
-            { "name": name },
-            "data" => "Page" => "media"
+            info_by_name,
+            info_by_name::Variables {
+                name: name.to_string()
+            }
         )?;
 
-        let entries = entries
+        let entries = data
+            .page
+            .and_then(|page| page.media)
+            .unwrap_or_default()
             .into_iter()
-            .filter_map(|entry| entry.try_into().ok())
+            .flatten()
+            .filter_map(|media| media.try_into().ok())
             .collect();
 
         Ok(entries)
     }
 
     fn search_info_by_id(&self, id: SeriesID) -> Result<SeriesInfo> {
-        let info: Media =
-            query!(self.auth_token().ok(), "info_by_id", { "id": id }, "data" => "Media")?;
+        let data = query!(
+            self.auth_token().ok(),
+            info_by_id,
+            info_by_id::Variables { id: id.into() }
+        )?;
 
-        info.try_into().map_err(|_| Error::NotAnAnime)
+        data.media
+            .ok_or(Error::NotAnAnime)?
+            .try_into()
+            .map_err(|_| Error::NotAnAnime)
     }
 
     fn get_list_entry(&self, id: SeriesID) -> Result<Option<SeriesEntry>> {
         let auth = self.auth()?;
 
-        let query: Result<MediaEntry> = query!(
+        let query = query!(
             Some(&auth.token),
-            "get_list_entry",
-            { "id": id, "userID": auth.user.id },
-            "data" => "MediaList"
+            get_list_entry,
+            get_list_entry::Variables {
+                id: id.into(),
+                user_id: auth.user.id.into(),
+            }
         );
 
         match query {
-            Ok(entry) => Ok(Some(entry.into_series_entry(id))),
+            Ok(data) => Ok(data.media_list.map(Into::into)),
             Err(ref err) if err.is_http_code(404) => Ok(None),
             Err(err) => Err(err),
         }
     }
 
+    #[allow(clippy::cast_possible_wrap)]
+    fn get_list_entries(&self, ids: &[SeriesID]) -> Result<Vec<SeriesEntry>> {
+        // The maximum number of items AniList will return per page
+        const MAX_PER_PAGE: usize = 50;
+
+        let auth = self.auth()?;
+        let mut entries = Vec::with_capacity(ids.len());
+
+        for ids in ids.chunks(MAX_PER_PAGE) {
+            let data = query!(
+                Some(&auth.token),
+                get_list_entries,
+                get_list_entries::Variables {
+                    ids: Some(ids.iter().map(|&id| Some(id.into())).collect()),
+                    user_id: auth.user.id.into(),
+                    per_page: MAX_PER_PAGE as i64,
+                }
+            )?;
+
+            let page = data
+                .page
+                .and_then(|page| page.media_list)
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .map(Into::into);
+
+            entries.extend(page);
+        }
+
+        Ok(entries)
+    }
+
     fn update_list_entry(&self, entry: &SeriesEntry) -> Result<()> {
         let token = self.auth_token()?;
 
-        send!(
+        query!(
             Some(token),
-            "update_list_entry",
-            {
-                "mediaId": entry.id,
-                "watched_eps": entry.watched_eps,
-                "score": entry.score.unwrap_or(0),
-                "status": MediaStatus::from(entry.status),
-                "times_rewatched": entry.times_rewatched,
-                "start_date": entry.start_date.map(MediaDate::from),
-                "finish_date": entry.end_date.map(MediaDate::from),
-            },
+            update_list_entry,
+            update_list_entry::Variables {
+                media_id: entry.id.into(),
+                watched_eps: entry.watched_eps.into(),
+                score: entry.score.unwrap_or(0).into(),
+                status: entry.status.into(),
+                times_rewatched: entry.times_rewatched.into(),
+                priority: entry.priority.into(),
+                // Dates are sent even when missing, since a null date leaves the current one unchanged
+                start_date: Some(entry.start_date.into()),
+                finish_date: Some(entry.end_date.into()),
+            }
         )?;
 
         Ok(())
     }
+
+    fn delete_list_entry(&self, id: SeriesID) -> Result<bool> {
+        let auth = self.auth()?;
+
+        // Entries can only be deleted by the ID of the entry itself, rather than the ID of its series
+        let entry = query!(
+            Some(&auth.token),
+            list_entry_id,
+            list_entry_id::Variables {
+                id: id.into(),
+                user_id: auth.user.id.into(),
+            }
+        );
+
+        let entry_id = match entry {
+            Ok(data) => match data.media_list {
+                Some(entry) => entry.id,
+                None => return Ok(false),
+            },
+            Err(ref err) if err.is_http_code(404) => return Ok(false),
+            Err(err) => return Err(err),
+        };
+
+        let data = query!(
+            Some(&auth.token),
+            delete_list_entry,
+            delete_list_entry::Variables { id: entry_id }
+        )?;
+
+        Ok(data
+            .delete_media_list_entry
+            .and_then(|deleted| deleted.deleted)
+            .unwrap_or(false))
+    }
+
+    fn next_airing_episode(&self, id: SeriesID) -> Result<Option<AiringEpisode>> {
+        let data = query!(
+            self.auth_token().ok(),
+            next_airing_episode,
+            next_airing_episode::Variables { id: id.into() }
+        )?;
+
+        let airing = data
+            .media
+            .and_then(|media| media.next_airing_episode)
+            .map(|airing| AiringEpisode {
+                episode: airing.episode as u32,
+                airs_at: airing.airing_at,
+            });
+
+        Ok(airing)
+    }
+
+    fn episode_titles(&self, id: SeriesID) -> Result<Vec<EpisodeTitle>> {
+        let data = query!(
+            self.auth_token().ok(),
+            episode_titles,
+            episode_titles::Variables { id: id.into() }
+        )?;
+
+        let episodes = data
+            .media
+            .and_then(|media| media.streaming_episodes)
+            .unwrap_or_default();
+
+        let mut titles = episodes
+            .into_iter()
+            .flatten()
+            .filter_map(|episode| parse_episode_title(episode.title.as_deref()?))
+            .collect::<Vec<_>>();
+
+        titles.sort_unstable_by_key(|title| title.episode);
+        titles.dedup_by_key(|title| title.episode);
+
+        Ok(titles)
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn notifications(&self, mark_read: bool, limit: usize) -> Result<Vec<Notification>> {
+        // The maximum number of items AniList will return per page
+        const MAX_PER_PAGE: usize = 50;
+
+        let data = query!(
+            Some(self.auth_token()?),
+            notifications,
+            notifications::Variables {
+                per_page: Some(limit.min(MAX_PER_PAGE) as i64),
+                reset: Some(mark_read),
+            }
+        )?;
+
+        let unread = data
+            .viewer
+            .and_then(|viewer| viewer.unread_notification_count)
+            .unwrap_or(0) as usize;
+
+        let notifications = data
+            .page
+            .and_then(|page| page.notifications)
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .enumerate()
+            // Notifications are sorted from newest to oldest, so the unread ones come first
+            .filter_map(|(i, notification)| into_notification(notification, i < unread))
+            .collect();
+
+        Ok(notifications)
+    }
+
+    fn user_stats(&self) -> Result<Option<UserStats>> {
+        let data = query!(
+            Some(self.auth_token()?),
+            viewer_stats,
+            viewer_stats::Variables
+        )?;
+
+        let stats = match data
+            .viewer
+            .and_then(|viewer| viewer.statistics)
+            .and_then(|stats| stats.anime)
+        {
+            Some(stats) => stats,
+            None => return Ok(None),
+        };
+
+        let statuses = stats
+            .statuses
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .filter_map(|stat| Some((stat.status?.into(), stat.count as u32)))
+            .collect();
+
+        Ok(Some(UserStats {
+            series: stats.count as u32,
+            episodes_watched: stats.episodes_watched as u32,
+            minutes_watched: stats.minutes_watched as u32,
+            // AniList gives a mean score of 0 when nothing has been scored
+            mean_score: Some(stats.mean_score as f32).filter(|&score| score > 0.0),
+            statuses,
+        }))
+    }
+
+    fn ping(&self) -> Result<()> {
+        query!(self.auth_token().ok(), ping, ping::Variables).map(|_| ())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            rewatch_count: true,
+            notes: true,
+            custom_lists: true,
+            score_points: self.score_format().points(),
+        }
+    }
 }
 
 impl ScoreParser for AniList {
@@ -214,7 +469,10 @@ impl ScoreParser for AniList {
     }
 
     fn score_to_str(&self, score: u8) -> Cow<str> {
-        match self.score_format() {
+        let format = self.score_format();
+        let score = format.normalize(score, self.score_rounding());
+
+        match format {
             ScoreFormat::Point100 => score.to_string().into(),
             ScoreFormat::Point10 => (score / 10).to_string().into(),
             ScoreFormat::Point10Decimal => format!("{:.1}", f32::from(score) / 10.0).into(),
@@ -234,6 +492,10 @@ impl ScoreParser for AniList {
             }
         }
     }
+
+    fn normalize_score(&self, score: u8) -> u8 {
+        self.score_format().normalize(score, self.score_rounding())
+    }
 }
 
 /// An authenticated user.
@@ -242,39 +504,66 @@ pub struct Auth {
     /// The AniList user's account information.
     pub user: User,
     token: AccessToken,
+    /// How scores are rounded to the user's score format.
+    score_rounding: ScoreRounding,
 }
 
 impl Auth {
     #[inline(always)]
     #[must_use]
     pub fn new(user: User, token: AccessToken) -> Self {
-        Self { user, token }
+        Self {
+            user,
+            token,
+            score_rounding: ScoreRounding::default(),
+        }
+    }
+
+    /// Set how scores are rounded when the user's score format has fewer points than the 0 - 100 scores
+    /// used everywhere else.
+    #[inline(always)]
+    #[must_use]
+    pub fn with_score_rounding(mut self, rounding: ScoreRounding) -> Self {
+        self.score_rounding = rounding;
+        self
     }
 
     /// Retrieve the current authorization from AniList using the specified `token`.
     pub fn retrieve(token: AccessToken) -> Result<Self> {
-        let user = query!(Some(&token), "user", {}, "data" => "Viewer")?;
+        let viewer = query!(Some(&token), viewer, viewer::Variables)?
+            .viewer
+            .ok_or(Error::NeedAuthentication)?;
+
+        let score_format = viewer
+            .media_list_options
+            .and_then(|options| options.score_format)
+            .unwrap_or_default();
+
+        let user = User {
+            id: viewer.id as u32,
+            name: viewer.name,
+            options: ListOptions { score_format },
+        };
+
         Ok(Self::new(user, token))
     }
 }
 
 /// An AniList user.
-#[derive(Debug, Deserialize)]
+#[derive(Debug)]
 pub struct User {
     /// The user's account ID.
     pub id: u32,
     /// The user's account name.
     pub name: String,
     /// Settings related to the user's anime list.
-    #[serde(rename = "mediaListOptions")]
     pub options: ListOptions,
 }
 
 /// Anime list settings for a user.
-#[derive(Debug, Deserialize)]
+#[derive(Debug)]
 pub struct ListOptions {
     /// The user's preferred scoring format.
-    #[serde(rename = "scoreFormat")]
     pub score_format: ScoreFormat,
 }
 
@@ -307,6 +596,32 @@ pub enum ScoreFormat {
 }
 
 impl ScoreFormat {
+    /// Returns the number of different scores that can be given in this format.
+    fn points(self) -> u8 {
+        match self {
+            Self::Point100 | Self::Point10Decimal => 100,
+            Self::Point10 => 10,
+            Self::Point5 => 5,
+            Self::Point3 => 3,
+        }
+    }
+
+    /// Round `score` to the closest value that can be represented in this format.
+    fn normalize(self, score: u8, rounding: ScoreRounding) -> u8 {
+        match self {
+            Self::Point100 | Self::Point10Decimal => score.min(100),
+            Self::Point10 => rounding.round(score, 10),
+            Self::Point5 => rounding.round(score, 20),
+            // Each face covers a range, so the score is set to the value its face is parsed as
+            Self::Point3 => match score {
+                0 => 0,
+                1..=33 => 33,
+                34..=66 => 50,
+                _ => 100,
+            },
+        }
+    }
+
     fn points_value(self, score: &str) -> Option<u8> {
         let raw_score = match self {
             Self::Point100 => score.parse().ok()?,
@@ -340,13 +655,10 @@ impl Default for ScoreFormat {
     }
 }
 
-fn send_gql_request<S>(
-    query: S,
-    vars: &json::Value,
-    token: Option<&AccessToken>,
-) -> Result<json::Value>
+fn send_gql_request<S, V>(query: S, vars: &V, token: Option<&AccessToken>) -> Result<json::Value>
 where
     S: AsRef<str>,
+    V: serde::Serialize,
 {
     const REQ_TIMEOUT_SEC: u64 = 15;
 
@@ -363,7 +675,17 @@ where
         request = request.bearer_auth(&token.decode()?);
     }
 
-    let json: json::Value = request.send()?.json()?;
+    let response = request.send()?;
+    let status = response.status();
+
+    // AniList serves an HTML page instead of JSON while it's down or under maintenance
+    if status.is_server_error() {
+        return Err(Error::ServiceUnavailable {
+            code: status.as_u16(),
+        });
+    }
+
+    let json: json::Value = response.json()?;
 
     if json["errors"] != json::Value::Null {
         let err = &json["errors"][0];
@@ -377,102 +699,178 @@ where
     Ok(json)
 }
 
-#[derive(Debug, Deserialize)]
-struct Media {
-    id: u32,
-    title: MediaTitle,
-    episodes: Option<u32>,
-    duration: Option<u32>,
-    relations: Option<MediaRelation>,
-    format: MediaFormat,
+/// Implements the conversion to `SeriesInfo` for the `MediaInfo` fragment of each query that requests it.
+macro_rules! impl_series_info_from_media {
+    ($($query:ident),+) => {
+        $(
+            impl TryFrom<$query::MediaInfo> for SeriesInfo {
+                type Error = ();
+
+                fn try_from(media: $query::MediaInfo) -> result::Result<Self, Self::Error> {
+                    let kind = media.format.ok_or(())?.try_into()?;
+
+                    let title = media.title.ok_or(())?;
+                    let romaji = title.romaji.ok_or(())?;
+
+                    let mut sequels = Vec::new();
+                    let mut prequels = Vec::new();
+
+                    let edges = media
+                        .relations
+                        .and_then(|relations| relations.edges)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .flatten();
+
+                    for edge in edges {
+                        let (relation, node) = match (edge.relation_type, edge.node) {
+                            (Some(relation), Some(node)) => (relation, node),
+                            _ => continue,
+                        };
+
+                        let kind = match node.format.and_then(|format| format.try_into().ok()) {
+                            Some(kind) => kind,
+                            None => continue,
+                        };
+
+                        let related = Sequel::new(kind, node.id as u32);
+
+                        if relation.is_sequential() {
+                            sequels.push(related);
+                        } else if relation.is_bundled_prequel(kind) {
+                            prequels.push(related);
+                        }
+                    }
+
+                    Ok(Self {
+                        id: media.id as u32,
+                        title: SeriesTitle {
+                            preferred: title.user_preferred.unwrap_or_else(|| romaji.clone()),
+                            romaji,
+                            english: title.english,
+                            native: title.native,
+                        },
+                        episodes: media.episodes.map_or(0, |episodes| episodes as u32),
+                        episode_length: media.duration.map_or(24, |duration| duration as u32),
+                        kind,
+                        genres: media.genres.unwrap_or_default().into_iter().flatten().collect(),
+                        sequels,
+                        prequels,
+                        cover_color: media.cover_image.and_then(|image| image.color),
+                    })
+                }
+            }
+        )+
+    };
 }
 
-impl Media {
-    fn sequels(&self) -> Vec<Sequel> {
-        let relations = match self.relations.as_ref() {
-            Some(relations) => relations,
-            None => return Vec::new(),
-        };
-
-        relations
-            .edges
-            .iter()
-            .filter_map(|edge| edge.try_into().ok())
-            .collect()
-    }
+impl_series_info_from_media!(info_by_id, info_by_name);
+
+/// Implements the conversion to `SeriesEntry` for the `ListEntry` fragment of each query that requests it.
+macro_rules! impl_series_entry_from_list_entry {
+    ($($query:ident),+) => {
+        $(
+            impl From<$query::ListEntry> for SeriesEntry {
+                fn from(entry: $query::ListEntry) -> Self {
+                    let score = entry
+                        .score
+                        .map(|score| score.round() as u8)
+                        .filter(|&score| score > 0);
+
+                    Self {
+                        id: entry.media_id as u32,
+                        watched_eps: entry.progress.unwrap_or(0) as u32,
+                        score,
+                        status: entry.status.map(Into::into).unwrap_or_default(),
+                        times_rewatched: entry.repeat.unwrap_or(0) as u32,
+                        priority: entry.priority.unwrap_or(0) as u32,
+                        start_date: entry
+                            .started_at
+                            .and_then(|date| series_date(date.year, date.month, date.day)),
+                        end_date: entry
+                            .completed_at
+                            .and_then(|date| series_date(date.year, date.month, date.day)),
+                    }
+                }
+            }
+        )+
+    };
 }
 
-impl TryInto<SeriesInfo> for Media {
-    type Error = ();
+impl_series_entry_from_list_entry!(get_list_entry, get_list_entries);
 
-    fn try_into(self) -> result::Result<SeriesInfo, Self::Error> {
-        let kind = self.format.try_into()?;
-        let sequels = self.sequels();
-
-        Ok(SeriesInfo {
-            id: self.id,
-            title: self.title.into(),
-            episodes: self.episodes.unwrap_or(1),
-            episode_length: self.duration.unwrap_or(24),
-            kind,
-            sequels,
-        })
+/// Returns the date AniList represents with its fuzzy date fields, if all of them are present.
+fn series_date(year: Option<i64>, month: Option<i64>, day: Option<i64>) -> Option<SeriesDate> {
+    match (year, month, day) {
+        (Some(y), Some(m), Some(d)) => Some(SeriesDate::from_ymd(y as u16, m as u8, d as u8)),
+        _ => None,
     }
 }
 
-#[derive(Debug, Deserialize)]
-struct MediaTitle {
-    romaji: String,
-    #[serde(rename = "userPreferred")]
-    preferred: String,
-}
-
-impl Into<SeriesTitle> for MediaTitle {
-    fn into(self) -> SeriesTitle {
-        SeriesTitle {
-            romaji: self.romaji,
-            preferred: self.preferred,
+impl From<Option<SeriesDate>> for update_list_entry::FuzzyDateInput {
+    fn from(date: Option<SeriesDate>) -> Self {
+        Self {
+            year: date.map(|date| date.year.into()),
+            month: date.map(|date| date.month.into()),
+            day: date.map(|date| date.day.into()),
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
-struct MediaRelation {
-    edges: Vec<MediaEdge>,
-}
-
-#[derive(Debug, Deserialize)]
-struct MediaEdge {
-    #[serde(rename = "relationType")]
-    relation: MediaRelationType,
-    node: MediaNode,
-}
-
-impl TryInto<Sequel> for &MediaEdge {
-    type Error = ();
-
-    fn try_into(self) -> result::Result<Sequel, Self::Error> {
-        // It doesn't make sense to consider this media edge a sequel
-        // if its an alternative, source, or character relation
-        if !self.relation.is_sequential() {
-            return Err(());
-        }
-
-        let kind = match self.node.format {
-            Some(fmt) => fmt.try_into()?,
-            None => return Err(()),
-        };
+/// Converts a notification from AniList, or returns None if it's missing information we need.
+fn into_notification(
+    notification: notifications::NotificationsPageNotifications,
+    unread: bool,
+) -> Option<Notification> {
+    use notifications::NotificationsPageNotifications as AniListNotification;
+
+    let (id, kind, media, created_at) = match notification {
+        AniListNotification::AiringNotification(airing) => (
+            airing.id,
+            NotificationKind::Airing {
+                episode: airing.episode as u32,
+            },
+            airing.media,
+            airing.created_at,
+        ),
+        AniListNotification::RelatedMediaAdditionNotification(addition) => (
+            addition.id,
+            NotificationKind::RelatedMediaAdded,
+            addition.media,
+            addition.created_at,
+        ),
+        AniListNotification::FollowingNotification(following) => (
+            following.id,
+            NotificationKind::Following {
+                user: following.user?.name,
+            },
+            None,
+            following.created_at,
+        ),
+    };
 
-        let sequel = Sequel::new(kind, self.node.id);
+    let series = media.map(|media| NotificationSeries {
+        id: media.id as u32,
+        title: media
+            .title
+            .and_then(|title| title.user_preferred)
+            .unwrap_or_default(),
+    });
 
-        Ok(sequel)
-    }
+    Some(Notification {
+        id: id as u32,
+        kind,
+        series,
+        created_at: created_at.unwrap_or(0),
+        unread,
+    })
 }
 
 #[derive(Copy, Clone, Debug, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
-enum MediaRelationType {
+enum MediaRelation {
     Sequel,
+    Prequel,
     #[serde(rename = "SIDE_STORY")]
     SideStory,
     Other,
@@ -480,20 +878,21 @@ enum MediaRelationType {
     Unknown,
 }
 
-impl MediaRelationType {
+impl MediaRelation {
     /// Returns true if the relation is considered to be some kind of sequel. Ex: a second season, OVA, ONA, movie, etc
     fn is_sequential(self) -> bool {
         match self {
             Self::Sequel | Self::SideStory | Self::Other => true,
-            Self::Unknown => false,
+            Self::Prequel | Self::Unknown => false,
         }
     }
-}
 
-#[derive(Debug, Deserialize)]
-struct MediaNode {
-    id: u32,
-    format: Option<MediaFormat>,
+    /// Returns true if the relation is a prequel that isn't a season.
+    ///
+    /// OVA's and movies that take place before a season are often bundled with it as an episode 0.
+    fn is_bundled_prequel(self, kind: SeriesKind) -> bool {
+        matches!(self, Self::Prequel) && kind != SeriesKind::Season
+    }
 }
 
 #[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
@@ -527,36 +926,8 @@ impl TryInto<SeriesKind> for MediaFormat {
     }
 }
 
-#[derive(Debug, Deserialize)]
-struct MediaEntry {
-    status: MediaStatus,
-    score: u8,
-    progress: u32,
-    repeat: u32,
-    #[serde(rename = "startedAt")]
-    start_date: MediaDate,
-    #[serde(rename = "completedAt")]
-    complete_date: MediaDate,
-}
-
-impl MediaEntry {
-    fn into_series_entry(self, id: u32) -> SeriesEntry {
-        let score = (self.score > 0).then(|| self.score);
-
-        SeriesEntry {
-            id,
-            watched_eps: self.progress,
-            score,
-            status: self.status.into(),
-            times_rewatched: self.repeat,
-            start_date: self.start_date.try_into().ok(),
-            end_date: self.complete_date.try_into().ok(),
-        }
-    }
-}
-
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
-enum MediaStatus {
+enum MediaListStatus {
     #[serde(rename = "CURRENT")]
     Current,
     #[serde(rename = "COMPLETED")]
@@ -571,7 +942,7 @@ enum MediaStatus {
     Repeating,
 }
 
-impl Into<Status> for MediaStatus {
+impl Into<Status> for MediaListStatus {
     fn into(self) -> Status {
         match self {
             Self::Current => Status::Watching,
@@ -584,7 +955,7 @@ impl Into<Status> for MediaStatus {
     }
 }
 
-impl From<Status> for MediaStatus {
+impl From<Status> for MediaListStatus {
     fn from(status: Status) -> Self {
         match status {
             Status::Watching => Self::Current,
@@ -597,30 +968,79 @@ impl From<Status> for MediaStatus {
     }
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
-struct MediaDate {
-    year: Option<u16>,
-    month: Option<u8>,
-    day: Option<u8>,
+/// Parse an AniList streaming episode title, such as "Episode 8 - The Promised Day", into its episode number and
+/// title.
+///
+/// Returns None if the title doesn't start with an episode number, or if there's no title after it.
+fn parse_episode_title(title: &str) -> Option<EpisodeTitle> {
+    let rest = title.trim().strip_prefix("Episode")?.trim_start();
+    let digits = rest
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(rest.len());
+    let episode = rest[..digits].parse().ok()?;
+
+    let name = rest[digits..].trim_start();
+    let name = name
+        .strip_prefix(&['-', ':', '.'][..])
+        .unwrap_or(name)
+        .trim();
+
+    if name.is_empty() {
+        return None;
+    }
+
+    Some(EpisodeTitle {
+        episode,
+        title: name.into(),
+    })
 }
 
-impl From<SeriesDate> for MediaDate {
-    fn from(date: SeriesDate) -> Self {
-        Self {
-            year: Some(date.year),
-            month: Some(date.month),
-            day: Some(date.day),
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_normalization() {
+        let nearest = ScoreRounding::Nearest;
+
+        assert_eq!(ScoreFormat::Point100.normalize(75, nearest), 75);
+        assert_eq!(ScoreFormat::Point10.normalize(75, nearest), 80);
+        assert_eq!(ScoreFormat::Point10.normalize(74, nearest), 70);
+        assert_eq!(ScoreFormat::Point10.normalize(75, ScoreRounding::Down), 70);
+        assert_eq!(ScoreFormat::Point10.normalize(3, nearest), 10);
+        assert_eq!(ScoreFormat::Point5.normalize(69, ScoreRounding::Up), 80);
+        assert_eq!(ScoreFormat::Point3.normalize(60, nearest), 50);
+        assert_eq!(ScoreFormat::Point3.normalize(0, nearest), 0);
     }
-}
 
-impl TryInto<SeriesDate> for MediaDate {
-    type Error = ();
+    #[test]
+    fn minimize_query_keeps_names_separate() {
+        const QUERY: &str = "query Test($id: Int!) {\n    Media (id: $id) {\n        ... on Media {\n            id,\n            format\n        }\n    }\n}";
 
-    fn try_into(self) -> result::Result<SeriesDate, Self::Error> {
-        match (self.year, self.month, self.day) {
-            (Some(y), Some(m), Some(d)) => Ok(SeriesDate::from_ymd(y, m, d)),
-            _ => Err(()),
-        }
+        assert_eq!(
+            minimize_query!(QUERY),
+            "query Test($id:Int!){Media(id:$id){...on Media{id,format}}}"
+        );
+    }
+
+    #[test]
+    fn parse_streaming_episode_titles() {
+        let parsed =
+            |title| parse_episode_title(title).map(|parsed| (parsed.episode, parsed.title));
+
+        assert_eq!(
+            parsed("Episode 8 - The Promised Day"),
+            Some((8, "The Promised Day".into()))
+        );
+        assert_eq!(
+            parsed("Episode 12: Re-Start"),
+            Some((12, "Re-Start".into()))
+        );
+        assert_eq!(
+            parsed("Episode 3. -Prologue-"),
+            Some((3, "-Prologue-".into()))
+        );
+        assert_eq!(parsed("Episode 4"), None);
+        assert_eq!(parsed("Special - Beach Day"), None);
     }
 }
@@ -0,0 +1,334 @@
+use super::{
+    AiringEpisode, EpisodeTitle, RemoteService, ScoreParser, Sequel, SeriesDate, SeriesEntry,
+    SeriesID, SeriesInfo, SeriesTitle, Status,
+};
+use crate::err::{Error, Result};
+use crate::SeriesKind;
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A remote service that serves a fixed set of series and keeps its list entries in memory.
+///
+/// This service is intended for tests and demonstrations that need a remote service to behave
+/// like a real one, without connecting to the internet.
+///
+/// Entries uploaded with `update_list_entry` are returned by later calls to `get_list_entry`,
+/// but are lost once the service is dropped.
+///
+/// Requests can be made slow or unreliable with `with_conditions`, to see how an application copes with a bad connection.
+#[derive(Debug, Default)]
+pub struct Mock {
+    series: Vec<SeriesInfo>,
+    entries: Mutex<HashMap<SeriesID, SeriesEntry>>,
+    conditions: Conditions,
+    /// How close the next request is to failing, which fails once this reaches 1.0.
+    failure_progress: Mutex<f32>,
+}
+
+/// Artificial network conditions that every request to a `Mock` goes through.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Conditions {
+    /// How long each request takes.
+    pub latency: Duration,
+    /// The fraction of requests that fail as if the service were unavailable, from 0.0 to 1.0.
+    ///
+    /// Failures are spread out evenly instead of being random, so a rate of 0.25 makes every fourth request fail.
+    pub failure_rate: f32,
+}
+
+impl Mock {
+    /// Create a new `Mock` that knows about every series in `series`, with the list entries in `entries`.
+    #[must_use]
+    pub fn new<E>(series: Vec<SeriesInfo>, entries: E) -> Self
+    where
+        E: IntoIterator<Item = SeriesEntry>,
+    {
+        let entries = entries.into_iter().map(|entry| (entry.id, entry)).collect();
+
+        Self {
+            series,
+            entries: Mutex::new(entries),
+            conditions: Conditions::default(),
+            failure_progress: Mutex::new(0.0),
+        }
+    }
+
+    /// Make every request go through the specified `conditions`.
+    #[must_use]
+    pub fn with_conditions(mut self, conditions: Conditions) -> Self {
+        self.conditions = conditions;
+        self
+    }
+
+    /// Create a new `Mock` with a handful of made up series, some of which are already on the list.
+    #[must_use]
+    pub fn demo() -> Self {
+        let series = vec![
+            demo_info(
+                900_001,
+                "Demo Days",
+                12,
+                SeriesKind::Season,
+                vec![Sequel::new(SeriesKind::Season, 900_002)],
+            ),
+            demo_info(
+                900_002,
+                "Demo Days 2nd Season",
+                12,
+                SeriesKind::Season,
+                Vec::new(),
+            ),
+            demo_info(
+                900_003,
+                "Starlit Harbor",
+                24,
+                SeriesKind::Season,
+                Vec::new(),
+            ),
+            demo_info(
+                900_004,
+                "Paper Lantern Detective",
+                13,
+                SeriesKind::Season,
+                vec![Sequel::new(SeriesKind::Movie, 900_005)],
+            ),
+            demo_info(
+                900_005,
+                "Paper Lantern Detective: The Movie",
+                1,
+                SeriesKind::Movie,
+                Vec::new(),
+            ),
+        ];
+
+        let entries = vec![
+            SeriesEntry {
+                watched_eps: 12,
+                score: Some(80),
+                status: Status::Completed,
+                start_date: Some(SeriesDate::from_ymd(2021, 1, 4)),
+                end_date: Some(SeriesDate::from_ymd(2021, 3, 22)),
+                ..SeriesEntry::new(900_001)
+            },
+            SeriesEntry {
+                watched_eps: 3,
+                status: Status::Watching,
+                start_date: Some(SeriesDate::from_ymd(2021, 4, 5)),
+                ..SeriesEntry::new(900_002)
+            },
+            SeriesEntry {
+                watched_eps: 5,
+                status: Status::OnHold,
+                start_date: Some(SeriesDate::from_ymd(2020, 10, 2)),
+                ..SeriesEntry::new(900_004)
+            },
+        ];
+
+        Self::new(series, entries)
+    }
+
+    /// Returns every series the service knows about.
+    #[inline(always)]
+    #[must_use]
+    pub fn series(&self) -> &[SeriesInfo] {
+        &self.series
+    }
+
+    /// Wait for the simulated latency, and return an error if the request should fail.
+    fn simulate_request(&self) -> Result<()> {
+        if self.conditions.latency > Duration::from_secs(0) {
+            thread::sleep(self.conditions.latency);
+        }
+
+        let mut progress = self
+            .failure_progress
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        *progress += self.conditions.failure_rate.clamp(0.0, 1.0);
+
+        if *progress >= 1.0 {
+            *progress -= 1.0;
+            return Err(Error::ServiceUnavailable { code: 503 });
+        }
+
+        Ok(())
+    }
+
+    fn info(&self, id: SeriesID) -> Result<&SeriesInfo> {
+        self.series
+            .iter()
+            .find(|info| info.id == id)
+            .ok_or(Error::UnknownSeries { id })
+    }
+}
+
+impl RemoteService for Mock {
+    fn search_info_by_name(&self, name: &str) -> Result<Vec<SeriesInfo>> {
+        self.simulate_request()?;
+
+        let name = name.to_lowercase();
+
+        let matches = self
+            .series
+            .iter()
+            .filter(|info| {
+                info.title.romaji.to_lowercase().contains(&name)
+                    || info.title.preferred.to_lowercase().contains(&name)
+            })
+            .cloned()
+            .collect();
+
+        Ok(matches)
+    }
+
+    fn search_info_by_id(&self, id: SeriesID) -> Result<SeriesInfo> {
+        self.simulate_request()?;
+        self.info(id).cloned()
+    }
+
+    fn get_list_entry(&self, id: SeriesID) -> Result<Option<SeriesEntry>> {
+        self.simulate_request()?;
+        self.info(id)?;
+
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        Ok(entries.get(&id).cloned())
+    }
+
+    fn update_list_entry(&self, entry: &SeriesEntry) -> Result<()> {
+        self.simulate_request()?;
+        self.info(entry.id)?;
+
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.insert(entry.id, entry.clone());
+
+        Ok(())
+    }
+
+    fn delete_list_entry(&self, id: SeriesID) -> Result<bool> {
+        self.simulate_request()?;
+        self.info(id)?;
+
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        Ok(entries.remove(&id).is_some())
+    }
+
+    // Series that are being watched have their next episode air three days from now
+    #[allow(clippy::cast_possible_wrap)]
+    fn next_airing_episode(&self, id: SeriesID) -> Result<Option<AiringEpisode>> {
+        const AIRS_IN_SECS: i64 = 60 * 60 * 24 * 3;
+
+        self.simulate_request()?;
+
+        let info = self.info(id)?;
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let entry = match entries.get(&id) {
+            Some(entry) if entry.status == Status::Watching => entry,
+            _ => return Ok(None),
+        };
+
+        let episode = entry.watched_eps + 2;
+
+        if info.episodes > 0 && episode > info.episodes {
+            return Ok(None);
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs() as i64);
+
+        Ok(Some(AiringEpisode {
+            episode,
+            airs_at: now + AIRS_IN_SECS,
+        }))
+    }
+
+    fn episode_titles(&self, id: SeriesID) -> Result<Vec<EpisodeTitle>> {
+        self.simulate_request()?;
+
+        let info = self.info(id)?;
+
+        let titles = (1..=info.episodes)
+            .map(|episode| EpisodeTitle {
+                episode,
+                title: format!("{} Part {}", info.title.preferred, episode),
+            })
+            .collect();
+
+        Ok(titles)
+    }
+}
+
+impl ScoreParser for Mock {}
+
+fn demo_info(
+    id: SeriesID,
+    title: &str,
+    episodes: u32,
+    kind: SeriesKind,
+    sequels: Vec<Sequel>,
+) -> SeriesInfo {
+    SeriesInfo {
+        id,
+        title: SeriesTitle {
+            romaji: title.into(),
+            english: None,
+            native: None,
+            preferred: title.into(),
+        },
+        episodes,
+        episode_length: if kind == SeriesKind::Movie { 110 } else { 24 },
+        kind,
+        genres: vec!["Comedy".into(), "Slice of Life".into()],
+        sequels,
+        prequels: Vec::new(),
+        cover_color: Some("#e4a15d".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn updated_entries_are_returned() {
+        let mock = Mock::demo();
+
+        assert!(mock.get_list_entry(900_003).unwrap().is_none());
+
+        let entry = SeriesEntry {
+            watched_eps: 4,
+            status: Status::Watching,
+            ..SeriesEntry::new(900_003)
+        };
+
+        mock.update_list_entry(&entry).unwrap();
+
+        let stored = mock.get_list_entry(900_003).unwrap().unwrap();
+        assert_eq!(stored.watched_eps, 4);
+        assert_eq!(stored.status, Status::Watching);
+
+        assert!(mock.get_list_entry(1).is_err());
+    }
+
+    #[test]
+    fn failures_are_spread_out() {
+        let mock = Mock::demo().with_conditions(Conditions {
+            latency: Duration::from_secs(0),
+            failure_rate: 0.25,
+        });
+
+        let failed = (0..8)
+            .map(|_| mock.get_list_entry(900_001))
+            .map(|result| matches!(result, Err(Error::ServiceUnavailable { .. })))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            failed,
+            [false, false, false, true, false, false, false, true]
+        );
+    }
+}
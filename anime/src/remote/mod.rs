@@ -1,14 +1,17 @@
 pub mod anilist;
+pub mod mock;
 pub mod offline;
 
-use crate::err::Result;
+use crate::err::{Error, Result};
 use crate::SeriesKind;
 use anilist::AniList;
 use enum_dispatch::enum_dispatch;
+use mock::Mock;
 use offline::Offline;
 use serde_derive::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::fmt;
+use std::str::FromStr;
 
 #[cfg(feature = "diesel-support")]
 use {
@@ -28,6 +31,7 @@ pub type SeriesID = u32;
 #[derive(Debug)]
 pub enum Remote {
     AniList,
+    Mock,
     Offline,
 }
 
@@ -54,12 +58,81 @@ pub trait RemoteService: ScoreParser {
     /// `id` is the ID of the anime, which differs from service to service.
     fn get_list_entry(&self, id: SeriesID) -> Result<Option<SeriesEntry>>;
 
+    /// Retrieve the anime list entries for every series in `ids` from the currently authenticated user.
+    ///
+    /// Series that are not on the user's list will not be returned.
+    ///
+    /// By default, this will call `get_list_entry` for each ID. Services that can look up multiple entries
+    /// in a single request should override this.
+    fn get_list_entries(&self, ids: &[SeriesID]) -> Result<Vec<SeriesEntry>> {
+        let mut entries = Vec::with_capacity(ids.len());
+
+        for &id in ids {
+            if let Some(entry) = self.get_list_entry(id)? {
+                entries.push(entry);
+            }
+        }
+
+        Ok(entries)
+    }
+
     /// Upload `entry` to the currently authenticated user's anime list.
     ///
     /// Please ensure that the `SeriesEntry` you are using comes from the current service
     /// in use, or you may overwrite a completely different list entry.
     fn update_list_entry(&self, entry: &SeriesEntry) -> Result<()>;
 
+    /// Remove the anime list entry for the anime with the specified `id` from the currently authenticated user's list.
+    ///
+    /// Returns false if the anime wasn't on the user's list.
+    fn delete_list_entry(&self, id: SeriesID) -> Result<bool>;
+
+    /// Retrieve the next episode of an anime that has yet to air.
+    ///
+    /// Returns `Ok(None)` if the anime isn't currently airing, or if the service doesn't provide airing schedules.
+    fn next_airing_episode(&self, _id: SeriesID) -> Result<Option<AiringEpisode>> {
+        Ok(None)
+    }
+
+    /// Retrieve the titles of the episodes of an anime that the service knows of.
+    ///
+    /// Episodes without a title are left out, so the list may have gaps.
+    /// Returns an empty list by default, for services that don't provide episode titles.
+    fn episode_titles(&self, _id: SeriesID) -> Result<Vec<EpisodeTitle>> {
+        Ok(Vec::new())
+    }
+
+    /// Retrieve up to `limit` of the most recent notifications of the currently authenticated user, starting with the newest.
+    ///
+    /// When `mark_read` is true, every notification will be marked as read on the service afterwards.
+    /// Services may return fewer notifications than `limit` if they can't send that many at once.
+    ///
+    /// Returns an empty list by default, for services that don't send notifications.
+    fn notifications(&self, _mark_read: bool, _limit: usize) -> Result<Vec<Notification>> {
+        Ok(Vec::new())
+    }
+
+    /// Retrieve statistics about the anime list of the currently authenticated user, as calculated by the service.
+    ///
+    /// Returns `Ok(None)` by default, for services that don't provide statistics.
+    fn user_stats(&self) -> Result<Option<UserStats>> {
+        Ok(None)
+    }
+
+    /// Make the smallest request the service allows, to check that it can be reached and is answering.
+    ///
+    /// Returns `Ok(())` by default, for services that don't need a connection.
+    fn ping(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Get the parts of a list entry this service is able to store.
+    ///
+    /// Returns `Capabilities::default()` by default, which only includes the fields of `SeriesEntry`.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
     /// Indicates whether or not this service is meant to be used without an internet connection.
     ///
     /// Returns false by default.
@@ -81,29 +154,220 @@ pub trait ScoreParser {
             .and_then(|score| (score <= 100).then(|| score))
     }
 
+    /// Parse the given `score` string the same way as `parse_score`, unless it starts with
+    /// a `+` or `-`, in which case the parsed amount will be added to or subtracted from `current`.
+    ///
+    /// Relative scores are clamped between 0 - 100.
+    fn parse_relative_score(&self, score: &str, current: Option<u8>) -> Option<u8> {
+        let (sign, amount) = match score.chars().next()? {
+            '+' => (1, &score[1..]),
+            '-' => (-1, &score[1..]),
+            _ => return self.parse_score(score),
+        };
+
+        let amount = i16::from(self.parse_score(amount)?);
+        let current = i16::from(current.unwrap_or(0));
+
+        let score = (current + sign * amount).clamp(0, 100) as u8;
+        Some(self.normalize_score(score))
+    }
+
     /// Map the given `score` to its string equivalent.
     ///
     /// By default, it will simply return `score` as a string.
     fn score_to_str(&self, score: u8) -> Cow<str> {
         Cow::Owned(score.to_string())
     }
+
+    /// Round the given `score` to the closest value the service can store, so it isn't truncated once it's sent.
+    ///
+    /// By default, it will simply return `score`.
+    fn normalize_score(&self, score: u8) -> u8 {
+        score
+    }
+}
+
+/// The parts of a list entry that a remote service is able to store.
+///
+/// Fields that aren't supported are dropped by the service when an entry is updated, so they shouldn't be editable.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Whether or not the number of times a series was rewatched can be stored.
+    pub rewatch_count: bool,
+    /// Whether or not notes can be written for a list entry.
+    pub notes: bool,
+    /// Whether or not series can be added to lists the user made.
+    pub custom_lists: bool,
+    /// The number of different scores that can be given in the user's score format, or 0 if scores can't be stored.
+    pub score_points: u8,
+}
+
+impl Capabilities {
+    /// Returns true if the service can store scores.
+    #[inline(always)]
+    #[must_use]
+    pub fn supports_scores(self) -> bool {
+        self.score_points > 0
+    }
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self {
+            rewatch_count: true,
+            notes: false,
+            custom_lists: false,
+            score_points: 100,
+        }
+    }
+}
+
+/// How a score between 0 - 100 is rounded when converted to a score format with fewer points.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ScoreRounding {
+    /// Round to the closest point, and up when halfway between two points.
+    #[default]
+    #[serde(alias = "nearest")]
+    Nearest,
+    /// Round down to the next lowest point.
+    #[serde(alias = "down")]
+    Down,
+    /// Round up to the next highest point.
+    #[serde(alias = "up")]
+    Up,
+}
+
+impl ScoreRounding {
+    /// Round `score` to a multiple of `step`.
+    ///
+    /// Scores above 0 are never rounded to 0, since that would remove the score instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use anime::remote::ScoreRounding;
+    ///
+    /// assert_eq!(ScoreRounding::Nearest.round(75, 10), 80);
+    /// assert_eq!(ScoreRounding::Down.round(75, 10), 70);
+    /// assert_eq!(ScoreRounding::Up.round(71, 20), 80);
+    /// ```
+    #[must_use]
+    pub fn round(self, score: u8, step: u8) -> u8 {
+        let score = score.min(100);
+        let step = step.clamp(1, 100);
+
+        if score == 0 {
+            return 0;
+        }
+
+        let rounded = match self {
+            Self::Nearest => (score + step / 2) / step * step,
+            Self::Down => score / step * step,
+            Self::Up => score.div_ceil(step) * step,
+        };
+
+        rounded.clamp(step, 100)
+    }
+}
+
+/// An upcoming episode in the airing schedule of an anime.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct AiringEpisode {
+    /// The number of the episode that will air.
+    pub episode: u32,
+    /// The time the episode will air, as a UNIX timestamp.
+    pub airs_at: i64,
+}
+
+/// The title of a single episode of an anime.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EpisodeTitle {
+    /// The number of the episode, starting from 1.
+    pub episode: u32,
+    pub title: String,
+}
+
+/// Statistics about the anime list of a user, as calculated by a remote service.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UserStats {
+    /// The number of series on the user's list.
+    pub series: u32,
+    /// The total number of episodes the user has watched.
+    pub episodes_watched: u32,
+    /// The total time the user has spent watching episodes, in minutes.
+    pub minutes_watched: u32,
+    /// The mean score of every scored series, in the user's score format.
+    pub mean_score: Option<f32>,
+    /// The number of series with each status.
+    pub statuses: Vec<(Status, u32)>,
+}
+
+/// A notification sent to a user by a remote service.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Notification {
+    /// The ID of the notification.
+    pub id: u32,
+    /// What the notification is about.
+    pub kind: NotificationKind,
+    /// The anime the notification is about, if any.
+    pub series: Option<NotificationSeries>,
+    /// The time the notification was sent, as a UNIX timestamp.
+    pub created_at: i64,
+    /// Whether or not the notification hadn't been read before it was retrieved.
+    pub unread: bool,
+}
+
+/// The kinds of notifications a remote service can send.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NotificationKind {
+    /// An episode of an anime aired.
+    Airing {
+        /// The number of the episode that aired.
+        episode: u32,
+    },
+    /// An anime was added to the service that is related to one on the user's list.
+    RelatedMediaAdded,
+    /// Another user started following the user.
+    Following {
+        /// The name of the user that followed.
+        user: String,
+    },
+}
+
+/// The anime a notification is about.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NotificationSeries {
+    /// The ID of the anime.
+    pub id: SeriesID,
+    /// The title of the anime in the user's preferred format.
+    pub title: String,
 }
 
 /// General information for an anime series.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct SeriesInfo {
     /// The ID of the series.
     pub id: SeriesID,
     /// The titles of the series.
     pub title: SeriesTitle,
-    /// The number of episodes.
+    /// The number of episodes, or 0 if it isn't known yet (such as when it is still airing).
     pub episodes: u32,
     /// The length of a single episode in minutes.
     pub episode_length: u32,
     /// The type of series.
     pub kind: SeriesKind,
+    /// The genres the series belongs to.
+    pub genres: Vec<String>,
     /// An ID pointing to the sequel of this series.
     pub sequels: Vec<Sequel>,
+    /// Prequels of this series that aren't a season, such as an OVA or movie that takes place before it.
+    ///
+    /// These are often bundled with the series as an episode 0, but they shouldn't be followed as sequels.
+    #[serde(default)]
+    pub prequels: Vec<Sequel>,
+    /// The average color of the cover image of the series, as a hex code like `#e4a15d`.
+    #[serde(default)]
+    pub cover_color: Option<String>,
 }
 
 impl SeriesInfo {
@@ -117,11 +381,10 @@ impl SeriesInfo {
         I: Iterator<Item = Cow<'a, Self>>,
         S: Into<String>,
     {
-        let mut name = name.into();
-        name.make_ascii_lowercase();
+        let name = crate::normalize_title(&name.into());
 
         crate::closest_match(items, min_confidence, |info| {
-            let title = info.title.romaji.to_ascii_lowercase();
+            let title = crate::normalize_title(&info.title.romaji);
             Some(strsim::jaro_winkler(&title, &name) as f32)
         })
     }
@@ -141,6 +404,22 @@ impl SeriesInfo {
     pub fn sequel_by_kind(&self, kind: SeriesKind) -> Option<&Sequel> {
         self.sequels.iter().find(|sequel| sequel.kind == kind)
     }
+
+    /// Returns every sequel matching the specified `kind`, in the order they were provided by the remote service.
+    #[inline]
+    pub fn sequels_by_kind(&self, kind: SeriesKind) -> impl Iterator<Item = &Sequel> {
+        self.sequels
+            .iter()
+            .filter(move |sequel| sequel.kind == kind)
+    }
+
+    /// Returns the first sequel matching the specified `kind`, or the first prequel if there is no such sequel.
+    #[inline]
+    #[must_use]
+    pub fn related_by_kind(&self, kind: SeriesKind) -> Option<&Sequel> {
+        self.sequel_by_kind(kind)
+            .or_else(|| self.prequels.iter().find(|prequel| prequel.kind == kind))
+    }
 }
 
 impl<'a> Into<Cow<'a, Self>> for SeriesInfo {
@@ -156,7 +435,7 @@ impl<'a> Into<Cow<'a, SeriesInfo>> for &'a SeriesInfo {
 }
 
 /// A sequel to a series.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Sequel {
     /// The kind of sequel this is.
     pub kind: SeriesKind,
@@ -173,16 +452,20 @@ impl Sequel {
 }
 
 /// Various title formats for an anime series.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct SeriesTitle {
     /// The title in romaji.
     pub romaji: String,
+    /// The official English title, if the series has one.
+    pub english: Option<String>,
+    /// The title in its original language, if it's known.
+    pub native: Option<String>,
     /// The title in the user's preferred format.
     pub preferred: String,
 }
 
 /// A list entry for an anime series.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct SeriesEntry {
     /// The ID of the anime.
     pub id: u32,
@@ -194,6 +477,8 @@ pub struct SeriesEntry {
     pub status: Status,
     /// The number of times the user has rewatched the series.
     pub times_rewatched: u32,
+    /// The priority the user gave the series, where higher values should be watched sooner and 0 means it has none.
+    pub priority: u32,
     /// The date the user started watching the series.
     pub start_date: Option<SeriesDate>,
     /// The date the user finished watching the series.
@@ -211,6 +496,7 @@ impl SeriesEntry {
             score: None,
             status: Status::default(),
             times_rewatched: 0,
+            priority: 0,
             start_date: None,
             end_date: None,
         }
@@ -259,6 +545,34 @@ impl fmt::Display for Status {
     }
 }
 
+impl FromStr for Status {
+    type Err = Error;
+
+    /// Parse a status from its name, shorthand, or a common synonym, ignoring case.
+    ///
+    /// Names can be written with spaces, dashes, or underscores (such as `plan to watch` or `plan_to_watch`), and
+    /// the names used by the remote service and in Japanese are accepted as well.
+    fn from_str(value: &str) -> Result<Self> {
+        let normalized = value.trim().to_lowercase().replace(&[' ', '-'][..], "_");
+
+        match normalized.as_ref() {
+            "w" | "watch" | "watching" | "current" | "視聴中" => Ok(Self::Watching),
+            "c" | "complete" | "completed" | "done" | "finished" | "視聴完了" => {
+                Ok(Self::Completed)
+            }
+            "h" | "hold" | "on_hold" | "onhold" | "paused" | "保留" => Ok(Self::OnHold),
+            "d" | "drop" | "dropped" | "中止" => Ok(Self::Dropped),
+            "p" | "plan" | "planned" | "planning" | "plan_to_watch" | "ptw" | "視聴予定" => {
+                Ok(Self::PlanToWatch)
+            }
+            "r" | "rewatch" | "rewatching" | "repeating" | "再視聴中" => Ok(Self::Rewatching),
+            _ => Err(Error::UnknownStatus {
+                value: value.into(),
+            }),
+        }
+    }
+}
+
 #[cfg(feature = "diesel-support")]
 impl<DB> FromSql<SmallInt, DB> for Status
 where
@@ -298,7 +612,7 @@ where
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(
     feature = "diesel-support",
     derive(AsExpression, FromSqlRow),
@@ -422,6 +736,71 @@ impl AccessToken {
 
         Ok(string)
     }
+
+    /// Get the information stored inside of the `AccessToken`, such as when it expires.
+    ///
+    /// Returns `None` if the token isn't a JSON Web Token.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use anime::remote::AccessToken;
+    ///
+    /// // {"sub":"1234","iat":1600000000.5,"exp":1631536000,"scopes":[]}
+    /// let token = AccessToken::encode("header.eyJzdWIiOiIxMjM0IiwiaWF0IjoxNjAwMDAwMDAwLjUsImV4cCI6MTYzMTUzNjAwMCwic2NvcGVzIjpbXX0.signature");
+    /// let claims = token.claims().unwrap();
+    ///
+    /// assert_eq!(claims.user_id.as_deref(), Some("1234"));
+    /// assert_eq!(claims.issued_at, Some(1600000000));
+    /// assert_eq!(claims.expires_at, Some(1631536000));
+    /// assert!(claims.scopes.is_empty());
+    /// ```
+    #[must_use]
+    pub fn claims(&self) -> Option<TokenClaims> {
+        let token = self.decode().ok()?;
+        let payload = token.split('.').nth(1)?;
+
+        let bytes =
+            base64::decode_config(payload.trim_end_matches('='), base64::URL_SAFE_NO_PAD).ok()?;
+
+        serde_json::from_slice::<RawTokenClaims>(&bytes)
+            .ok()
+            .map(Into::into)
+    }
+}
+
+/// Information stored inside of an `AccessToken`.
+#[derive(Clone, Debug, Default)]
+pub struct TokenClaims {
+    /// The ID of the user the token belongs to.
+    pub user_id: Option<String>,
+    /// When the token was created, as a Unix timestamp.
+    pub issued_at: Option<i64>,
+    /// When the token expires, as a Unix timestamp.
+    pub expires_at: Option<i64>,
+    /// What the token is allowed to access. An empty list usually means it has full access.
+    pub scopes: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct RawTokenClaims {
+    sub: Option<String>,
+    // Some services use fractional timestamps
+    iat: Option<f64>,
+    exp: Option<f64>,
+    #[serde(default)]
+    scopes: Vec<String>,
+}
+
+impl From<RawTokenClaims> for TokenClaims {
+    fn from(raw: RawTokenClaims) -> Self {
+        Self {
+            user_id: raw.sub,
+            issued_at: raw.iat.map(|iat| iat as i64),
+            expires_at: raw.exp.map(|exp| exp as i64),
+            scopes: raw.scopes,
+        }
+    }
 }
 
 // Better to not accidently expose a base64 encoded token..
@@ -430,3 +809,22 @@ impl fmt::Debug for AccessToken {
         write!(f, "AccessToken {{}}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_parsing() {
+        let parse = |value: &str| value.parse::<Status>().ok();
+
+        assert_eq!(parse("w"), Some(Status::Watching));
+        assert_eq!(parse("Plan To Watch"), Some(Status::PlanToWatch));
+        assert_eq!(parse("PTW"), Some(Status::PlanToWatch));
+        assert_eq!(parse("on-hold"), Some(Status::OnHold));
+        assert_eq!(parse("Done"), Some(Status::Completed));
+        assert_eq!(parse("REPEATING"), Some(Status::Rewatching));
+        assert_eq!(parse("中止"), Some(Status::Dropped));
+        assert_eq!(parse("watched"), None);
+    }
+}
@@ -37,6 +37,10 @@ impl RemoteService for Offline {
         Ok(())
     }
 
+    fn delete_list_entry(&self, _: SeriesID) -> Result<bool> {
+        Ok(false)
+    }
+
     fn is_offline(&self) -> bool {
         true
     }
@@ -0,0 +1,254 @@
+use crate::err::Result;
+use crate::remote::{Remote, RemoteService, ScoreParser, SeriesEntry, SeriesID};
+use smallvec::SmallVec;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// The number of times in a row a list entry can fail to sync before it should stop being retried automatically.
+pub const MAX_FAILURES: u32 = 3;
+
+/// A list entry that is stored locally and kept in sync with a remote service.
+///
+/// Entries keep track of whether they have changes that haven't been sent yet, such as ones made while offline,
+/// so they can be sent later and aren't overwritten by the remote service's version in the meantime.
+pub trait SyncedEntry {
+    fn series_id(&self) -> SeriesID;
+
+    /// Returns the entry as it should be sent to the remote service.
+    fn to_remote(&self) -> SeriesEntry;
+
+    /// Replace the entry with the remote service's version of it.
+    fn replace_with_remote(&mut self, entry: SeriesEntry);
+
+    /// Returns true if the entry has changes that haven't been sent to the remote service.
+    fn needs_sync(&self) -> bool;
+
+    fn set_needs_sync(&mut self, needs_sync: bool);
+}
+
+/// What happened to an entry when pulling it from a remote service.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Pull {
+    /// The entry was replaced with the remote service's version.
+    Replaced,
+    /// The entry has changes that haven't been sent yet, which would be lost by replacing it.
+    KeptLocalChanges,
+    /// The remote service is offline, so there was nothing to pull.
+    Offline,
+}
+
+/// Send `entry` to `remote` if it has changes that haven't been sent yet.
+///
+/// Nothing is sent while offline, and the entry keeps its changes until they can be.
+pub fn push<E>(entry: &mut E, remote: &Remote) -> Result<()>
+where
+    E: SyncedEntry + ?Sized,
+{
+    if !entry.needs_sync() {
+        return Ok(());
+    }
+
+    force_push(entry, remote)
+}
+
+/// Send `entry` to `remote`, even if it doesn't have any changes.
+///
+/// The score of the entry is rounded to one that `remote` can store first, instead of letting it be truncated.
+pub fn force_push<E>(entry: &mut E, remote: &Remote) -> Result<()>
+where
+    E: SyncedEntry + ?Sized,
+{
+    if remote.is_offline() {
+        return Ok(());
+    }
+
+    let mut remote_entry = entry.to_remote();
+    remote_entry.score = remote_entry
+        .score
+        .map(|score| remote.normalize_score(score))
+        .filter(|&score| score > 0);
+
+    remote.update_list_entry(&remote_entry)?;
+    entry.set_needs_sync(false);
+
+    Ok(())
+}
+
+/// Replace `entry` with the version from `remote`, unless it has changes that haven't been sent yet.
+pub fn pull<E>(entry: &mut E, remote: &Remote) -> Result<Pull>
+where
+    E: SyncedEntry + ?Sized,
+{
+    if entry.needs_sync() {
+        return Ok(Pull::KeptLocalChanges);
+    }
+
+    force_pull(entry, remote)
+}
+
+/// Replace `entry` with the version from `remote`, discarding any changes that haven't been sent.
+///
+/// Series that aren't on the user's list on `remote` are replaced with a new entry.
+pub fn force_pull<E>(entry: &mut E, remote: &Remote) -> Result<Pull>
+where
+    E: SyncedEntry + ?Sized,
+{
+    if remote.is_offline() {
+        return Ok(Pull::Offline);
+    }
+
+    let id = entry.series_id();
+    let remote_entry = remote
+        .get_list_entry(id)?
+        .unwrap_or_else(|| SeriesEntry::new(id));
+
+    entry.replace_with_remote(remote_entry);
+    Ok(Pull::Replaced)
+}
+
+/// Coalesces rapid changes to the same list entry, so they can be sent to the remote service in a single request.
+pub struct WriteQueue<K = SeriesID> {
+    delay: Duration,
+    /// Series ID -> when its list entry can be sent.
+    pending: HashMap<K, Instant>,
+}
+
+impl<K> WriteQueue<K>
+where
+    K: Copy + Eq + Hash,
+{
+    #[must_use]
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Queue the list entry of the series with the specified `id` to be sent once it hasn't been queued again for `delay`.
+    pub fn push(&mut self, id: K) {
+        self.pending.insert(id, Instant::now() + self.delay);
+    }
+
+    /// Remove and return the ID of every series that can be sent at `now`.
+    pub fn take_ready(&mut self, now: Instant) -> SmallVec<[K; 4]> {
+        let ready = self
+            .pending
+            .iter()
+            .filter(|(_, &ready_at)| ready_at <= now)
+            .map(|(&id, _)| id)
+            .collect::<SmallVec<[_; 4]>>();
+
+        for id in &ready {
+            self.pending.remove(id);
+        }
+
+        ready
+    }
+
+    /// Remove and return the ID of every series, regardless of when it was queued.
+    pub fn take_all(&mut self) -> SmallVec<[K; 4]> {
+        self.pending.drain().map(|(id, _)| id).collect()
+    }
+
+    /// Returns true if the list entry of the series with the specified `id` is waiting to be sent.
+    #[must_use]
+    pub fn contains(&self, id: K) -> bool {
+        self.pending.contains_key(&id)
+    }
+
+    /// Returns true if a series can be sent at `now`.
+    #[must_use]
+    pub fn has_ready(&self, now: Instant) -> bool {
+        self.pending.values().any(|&ready_at| ready_at <= now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote::mock::Mock;
+    use crate::remote::Status;
+
+    struct Entry {
+        entry: SeriesEntry,
+        needs_sync: bool,
+    }
+
+    impl SyncedEntry for Entry {
+        fn series_id(&self) -> SeriesID {
+            self.entry.id
+        }
+
+        fn to_remote(&self) -> SeriesEntry {
+            self.entry.clone()
+        }
+
+        fn replace_with_remote(&mut self, entry: SeriesEntry) {
+            self.entry = entry;
+            self.needs_sync = false;
+        }
+
+        fn needs_sync(&self) -> bool {
+            self.needs_sync
+        }
+
+        fn set_needs_sync(&mut self, needs_sync: bool) {
+            self.needs_sync = needs_sync;
+        }
+    }
+
+    #[test]
+    fn local_changes_are_kept_until_pushed() {
+        let remote = Remote::from(Mock::demo());
+
+        let mut entry = Entry {
+            entry: SeriesEntry {
+                watched_eps: 6,
+                status: Status::Watching,
+                ..SeriesEntry::new(900_002)
+            },
+            needs_sync: true,
+        };
+
+        assert_eq!(pull(&mut entry, &remote).unwrap(), Pull::KeptLocalChanges);
+        assert_eq!(entry.entry.watched_eps, 6);
+
+        push(&mut entry, &remote).unwrap();
+        assert!(!entry.needs_sync);
+
+        entry.entry.watched_eps = 0;
+        assert_eq!(pull(&mut entry, &remote).unwrap(), Pull::Replaced);
+        assert_eq!(entry.entry.watched_eps, 6);
+
+        let offline = Remote::offline();
+        assert_eq!(force_pull(&mut entry, &offline).unwrap(), Pull::Offline);
+    }
+
+    #[test]
+    fn write_queue_coalesces() {
+        let delay = Duration::from_secs(60);
+        let mut queue = WriteQueue::new(delay);
+
+        queue.push(1);
+        queue.push(2);
+        queue.push(1);
+
+        let now = Instant::now();
+        assert!(!queue.has_ready(now));
+        assert!(queue.take_ready(now).is_empty());
+
+        let later = now + delay * 2;
+        assert!(queue.has_ready(later));
+
+        let mut ready = queue.take_ready(later);
+        ready.sort_unstable();
+        assert_eq!(ready.as_slice(), [1, 2]);
+
+        assert!(queue.take_ready(later).is_empty());
+
+        queue.push(3);
+        assert_eq!(queue.take_all().as_slice(), [3]);
+    }
+}
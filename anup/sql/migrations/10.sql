@@ -0,0 +1,10 @@
+-- Entries that keep failing to sync are tracked so they can be skipped when syncing everything at once
+CREATE TABLE IF NOT EXISTS sync_failures (
+    id INTEGER NOT NULL,
+    account_id TEXT NOT NULL DEFAULT '',
+    failures INTEGER NOT NULL,
+    last_error TEXT NOT NULL,
+    PRIMARY KEY(id, account_id)
+);
+
+PRAGMA user_version = 10;
\ No newline at end of file
@@ -0,0 +1,5 @@
+-- Completed series can be marked as planned to be rewatched without changing their status
+ALTER TABLE series_configs ADD COLUMN planned_rewatch BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE deleted_series ADD COLUMN planned_rewatch BOOLEAN NOT NULL DEFAULT 0;
+
+PRAGMA user_version = 11;
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- Episodes can be rated locally once they've been watched
+ALTER TABLE watch_history ADD COLUMN rating SMALLINT;
+
+PRAGMA user_version = 12;
\ No newline at end of file
@@ -0,0 +1,9 @@
+-- Episodes being played are recorded here until their player exits, so they can be recovered if the program closes first
+CREATE TABLE IF NOT EXISTS active_playbacks (
+    series_id INTEGER NOT NULL PRIMARY KEY,
+    episode SMALLINT NOT NULL,
+    started_at BIGINT NOT NULL,
+    pid INTEGER NOT NULL
+);
+
+PRAGMA user_version = 13;
\ No newline at end of file
@@ -0,0 +1,10 @@
+-- English and native titles are stored so a series can be shown with one of them instead of its preferred title
+ALTER TABLE series_info ADD COLUMN title_english TEXT;
+ALTER TABLE series_info ADD COLUMN title_native TEXT;
+ALTER TABLE series_configs ADD COLUMN title_override TEXT;
+
+ALTER TABLE deleted_series ADD COLUMN title_english TEXT;
+ALTER TABLE deleted_series ADD COLUMN title_native TEXT;
+ALTER TABLE deleted_series ADD COLUMN title_override TEXT;
+
+PRAGMA user_version = 14;
\ No newline at end of file
@@ -0,0 +1,9 @@
+-- Aliases are compared case-insensitively, and are kept while their series is deleted so it can be restored with them
+CREATE TABLE IF NOT EXISTS series_aliases (
+    alias TEXT NOT NULL PRIMARY KEY COLLATE NOCASE,
+    series_id INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS series_aliases_series_id ON series_aliases(series_id);
+
+PRAGMA user_version = 15;
\ No newline at end of file
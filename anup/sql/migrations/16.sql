@@ -0,0 +1,10 @@
+-- Collection names are compared case-insensitively, and series are kept in them while deleted so they can be restored
+CREATE TABLE IF NOT EXISTS series_collections (
+    name TEXT NOT NULL COLLATE NOCASE,
+    series_id INTEGER NOT NULL,
+    PRIMARY KEY(name, series_id)
+);
+
+CREATE INDEX IF NOT EXISTS series_collections_series_id ON series_collections(series_id);
+
+PRAGMA user_version = 16;
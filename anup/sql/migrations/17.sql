@@ -0,0 +1,3 @@
+ALTER TABLE series_entries ADD COLUMN priority SMALLINT NOT NULL DEFAULT 0;
+
+PRAGMA user_version = 17;
@@ -0,0 +1,8 @@
+-- Filler episodes are kept while a series is deleted so they can be restored
+CREATE TABLE IF NOT EXISTS filler_episodes (
+    series_id INTEGER NOT NULL,
+    episode SMALLINT NOT NULL,
+    PRIMARY KEY(series_id, episode)
+);
+
+PRAGMA user_version = 18;
@@ -0,0 +1,10 @@
+-- Errors and notifications are kept so ones that scrolled out of the log can still be read later
+CREATE TABLE IF NOT EXISTS saved_events (
+    id INTEGER NOT NULL PRIMARY KEY,
+    kind SMALLINT NOT NULL,
+    message TEXT NOT NULL,
+    logged_at BIGINT NOT NULL,
+    read BOOLEAN NOT NULL DEFAULT 0
+);
+
+PRAGMA user_version = 19;
@@ -0,0 +1,3 @@
+ALTER TABLE series_info ADD COLUMN genres TEXT;
+
+PRAGMA user_version = 2;
@@ -0,0 +1,9 @@
+-- Episode titles come from the remote service and are refreshed along with the series info
+CREATE TABLE IF NOT EXISTS episode_titles (
+    series_id INTEGER NOT NULL,
+    episode SMALLINT NOT NULL,
+    title TEXT NOT NULL,
+    PRIMARY KEY(series_id, episode)
+);
+
+PRAGMA user_version = 20;
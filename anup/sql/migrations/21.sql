@@ -0,0 +1,5 @@
+-- Series can be muted so new episodes of them don't send notifications
+ALTER TABLE series_configs ADD COLUMN muted BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE deleted_series ADD COLUMN muted BOOLEAN NOT NULL DEFAULT 0;
+
+PRAGMA user_version = 21;
@@ -0,0 +1,5 @@
+-- The average color of the cover image of a series, used to accent its info panel
+ALTER TABLE series_info ADD COLUMN cover_color TEXT;
+ALTER TABLE deleted_series ADD COLUMN cover_color TEXT;
+
+PRAGMA user_version = 22;
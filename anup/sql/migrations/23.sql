@@ -0,0 +1,5 @@
+-- The IDs of the sequels, side stories, and other related series of a series, used to group franchises together
+ALTER TABLE series_info ADD COLUMN sequel_ids TEXT;
+ALTER TABLE deleted_series ADD COLUMN sequel_ids TEXT;
+
+PRAGMA user_version = 23;
@@ -0,0 +1,17 @@
+-- List entries are recorded before they're sent to the remote service and removed once they're saved locally, so a
+-- crash in between can be reconciled the next time the program starts
+CREATE TABLE IF NOT EXISTS sync_journal (
+    id INTEGER NOT NULL,
+    account_id TEXT NOT NULL DEFAULT '',
+    watched_episodes SMALLINT NOT NULL,
+    score SMALLINT,
+    status SMALLINT NOT NULL,
+    times_rewatched SMALLINT NOT NULL,
+    priority SMALLINT NOT NULL,
+    start_date DATE,
+    end_date DATE,
+    acknowledged BOOLEAN NOT NULL DEFAULT 0,
+    PRIMARY KEY(id, account_id)
+);
+
+PRAGMA user_version = 24;
@@ -0,0 +1,9 @@
+-- Files that the episode parser got wrong can be given the episode they are by hand
+CREATE TABLE IF NOT EXISTS episode_overrides (
+    series_id INTEGER NOT NULL,
+    filename TEXT NOT NULL,
+    episode SMALLINT NOT NULL,
+    PRIMARY KEY(series_id, filename)
+);
+
+PRAGMA user_version = 25;
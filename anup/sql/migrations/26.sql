@@ -0,0 +1,21 @@
+-- Every list entry sent to or removed from a remote service is recorded with the values that were sent
+-- Rows are kept after their series is removed, since they describe what the remote list should look like
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER NOT NULL PRIMARY KEY,
+    series_id INTEGER NOT NULL,
+    account_id TEXT NOT NULL DEFAULT '',
+    service TEXT NOT NULL,
+    sent_at BIGINT NOT NULL,
+    removed BOOLEAN NOT NULL DEFAULT 0,
+    watched_episodes SMALLINT,
+    score SMALLINT,
+    status SMALLINT,
+    times_rewatched SMALLINT,
+    priority SMALLINT,
+    start_date DATE,
+    end_date DATE
+);
+
+CREATE INDEX IF NOT EXISTS audit_log_account_id ON audit_log(account_id, id);
+
+PRAGMA user_version = 26;
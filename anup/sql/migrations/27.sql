@@ -0,0 +1,9 @@
+-- Resume positions imported from mpv, used when mpv doesn't have one of its own for an episode
+CREATE TABLE IF NOT EXISTS resume_positions (
+    series_id INTEGER NOT NULL,
+    episode SMALLINT NOT NULL,
+    seconds INTEGER NOT NULL,
+    PRIMARY KEY(series_id, episode)
+);
+
+PRAGMA user_version = 27;
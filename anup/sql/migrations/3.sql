@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS watch_history (
+    id INTEGER NOT NULL PRIMARY KEY,
+    series_id INTEGER NOT NULL,
+    episode SMALLINT NOT NULL,
+    minutes SMALLINT NOT NULL,
+    watched_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS watch_history_series_id ON watch_history(series_id);
+
+PRAGMA user_version = 3;
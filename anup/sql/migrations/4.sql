@@ -0,0 +1,3 @@
+ALTER TABLE series_configs ADD COLUMN episode_offset INTEGER NOT NULL DEFAULT 0;
+
+PRAGMA user_version = 4;
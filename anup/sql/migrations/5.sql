@@ -0,0 +1,24 @@
+-- SQLite can't change the primary key of an existing table, so the entries are moved to a new one.
+-- Existing entries are left unassigned until an account is used.
+-- The foreign key is dropped as well, since series configs are saved with REPLACE and would wipe every account's entry.
+CREATE TABLE series_entries_new (
+    id INTEGER NOT NULL,
+    account_id TEXT NOT NULL DEFAULT '',
+    watched_episodes SMALLINT NOT NULL,
+    score SMALLINT,
+    status SMALLINT NOT NULL,
+    times_rewatched SMALLINT NOT NULL,
+    start_date DATE,
+    end_date DATE,
+    needs_sync BIT NOT NULL,
+    PRIMARY KEY(id, account_id)
+);
+
+INSERT INTO series_entries_new
+    SELECT id, '', watched_episodes, score, status, times_rewatched, start_date, end_date, needs_sync
+    FROM series_entries;
+
+DROP TABLE series_entries;
+ALTER TABLE series_entries_new RENAME TO series_entries;
+
+PRAGMA user_version = 5;
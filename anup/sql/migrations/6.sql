@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS playback_sessions (
+    id INTEGER NOT NULL PRIMARY KEY,
+    series_id INTEGER NOT NULL,
+    episode SMALLINT NOT NULL,
+    started_at BIGINT NOT NULL,
+    seconds INTEGER NOT NULL,
+    exit_code INTEGER,
+    counted BOOLEAN NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS playback_sessions_series_id ON playback_sessions(series_id);
+
+PRAGMA user_version = 6;
\ No newline at end of file
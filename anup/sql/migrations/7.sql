@@ -0,0 +1,4 @@
+-- Series that are only tracked locally are never synced with the remote service
+ALTER TABLE series_configs ADD COLUMN local_only BOOLEAN NOT NULL DEFAULT 0;
+
+PRAGMA user_version = 7;
\ No newline at end of file
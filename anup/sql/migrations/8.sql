@@ -0,0 +1,20 @@
+-- Deleted series are kept here until they are restored or expire.
+-- Their list entries, watch history, and playback sessions are left in place until then.
+CREATE TABLE IF NOT EXISTS deleted_series (
+    id INTEGER NOT NULL PRIMARY KEY,
+    nickname TEXT NOT NULL,
+    path TEXT NOT NULL,
+    episode_parser TEXT,
+    player_args TEXT,
+    episode_offset INTEGER NOT NULL,
+    local_only BOOLEAN NOT NULL,
+    title_preferred TEXT,
+    title_romaji TEXT,
+    episodes SMALLINT,
+    episode_length_mins SMALLINT,
+    genres TEXT,
+    trashed_path TEXT,
+    deleted_at BIGINT NOT NULL
+);
+
+PRAGMA user_version = 8;
\ No newline at end of file
@@ -0,0 +1,5 @@
+-- Series can have their episodes removed automatically once they have been watched
+ALTER TABLE series_configs ADD COLUMN remove_watched BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE deleted_series ADD COLUMN remove_watched BOOLEAN NOT NULL DEFAULT 0;
+
+PRAGMA user_version = 9;
\ No newline at end of file
@@ -1,4 +1,5 @@
-PRAGMA locking_mode = EXCLUSIVE;
+PRAGMA locking_mode = NORMAL;
 PRAGMA journal_mode = WAL;
 PRAGMA synchronous = NORMAL;
+PRAGMA busy_timeout = 5000;
 PRAGMA foreign_keys = ON;
\ No newline at end of file
@@ -1,11 +1,19 @@
-PRAGMA user_version = 1;
+PRAGMA user_version = 27;
 
+-- Series configs are saved with REPLACE, so tables that keep per-series data can't cascade from them
 CREATE TABLE IF NOT EXISTS series_configs (
     id INTEGER NOT NULL PRIMARY KEY,
     nickname TEXT NOT NULL UNIQUE,
     path TEXT NOT NULL,
     episode_parser TEXT,
-    player_args TEXT
+    player_args TEXT,
+    episode_offset INTEGER NOT NULL DEFAULT 0,
+    local_only BOOLEAN NOT NULL DEFAULT 0,
+    remove_watched BOOLEAN NOT NULL DEFAULT 0,
+    planned_rewatch BOOLEAN NOT NULL DEFAULT 0,
+    -- The title to show instead of the preferred one, such as the English title or one the user chose
+    title_override TEXT,
+    muted BOOLEAN NOT NULL DEFAULT 0
 );
 
 CREATE TABLE IF NOT EXISTS series_info (
@@ -14,11 +22,18 @@ CREATE TABLE IF NOT EXISTS series_info (
     title_romaji TEXT NOT NULL,
     episodes SMALLINT NOT NULL,
     episode_length_mins SMALLINT NOT NULL,
+    genres TEXT,
+    title_english TEXT,
+    title_native TEXT,
+    cover_color TEXT,
+    sequel_ids TEXT,
     FOREIGN KEY(id) REFERENCES series_configs(id) ON DELETE CASCADE
 );
 
+-- Each account has its own entry for a series, so this table doesn't cascade from series configs either
 CREATE TABLE IF NOT EXISTS series_entries (
-    id INTEGER NOT NULL PRIMARY KEY,
+    id INTEGER NOT NULL,
+    account_id TEXT NOT NULL DEFAULT '',
     watched_episodes SMALLINT NOT NULL,
     score SMALLINT,
     status SMALLINT NOT NULL,
@@ -26,5 +41,166 @@ CREATE TABLE IF NOT EXISTS series_entries (
     start_date DATE,
     end_date DATE,
     needs_sync BIT NOT NULL,
-    FOREIGN KEY(id) REFERENCES series_configs(id) ON DELETE CASCADE
-);
\ No newline at end of file
+    priority SMALLINT NOT NULL DEFAULT 0,
+    PRIMARY KEY(id, account_id)
+);
+
+-- Entries that keep failing to sync are tracked so they can be skipped when syncing everything at once
+CREATE TABLE IF NOT EXISTS sync_failures (
+    id INTEGER NOT NULL,
+    account_id TEXT NOT NULL DEFAULT '',
+    failures INTEGER NOT NULL,
+    last_error TEXT NOT NULL,
+    PRIMARY KEY(id, account_id)
+);
+
+-- List entries are recorded before they're sent to the remote service and removed once they're saved locally, so a
+-- crash in between can be reconciled the next time the program starts
+CREATE TABLE IF NOT EXISTS sync_journal (
+    id INTEGER NOT NULL,
+    account_id TEXT NOT NULL DEFAULT '',
+    watched_episodes SMALLINT NOT NULL,
+    score SMALLINT,
+    status SMALLINT NOT NULL,
+    times_rewatched SMALLINT NOT NULL,
+    priority SMALLINT NOT NULL,
+    start_date DATE,
+    end_date DATE,
+    acknowledged BOOLEAN NOT NULL DEFAULT 0,
+    PRIMARY KEY(id, account_id)
+);
+
+-- Every list entry sent to or removed from a remote service is recorded with the values that were sent
+-- Rows are kept after their series is removed, since they describe what the remote list should look like
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER NOT NULL PRIMARY KEY,
+    series_id INTEGER NOT NULL,
+    account_id TEXT NOT NULL DEFAULT '',
+    service TEXT NOT NULL,
+    sent_at BIGINT NOT NULL,
+    removed BOOLEAN NOT NULL DEFAULT 0,
+    watched_episodes SMALLINT,
+    score SMALLINT,
+    status SMALLINT,
+    times_rewatched SMALLINT,
+    priority SMALLINT,
+    start_date DATE,
+    end_date DATE
+);
+
+CREATE INDEX IF NOT EXISTS audit_log_account_id ON audit_log(account_id, id);
+
+-- Aliases are compared case-insensitively, and are kept while their series is deleted so it can be restored with them
+CREATE TABLE IF NOT EXISTS series_aliases (
+    alias TEXT NOT NULL PRIMARY KEY COLLATE NOCASE,
+    series_id INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS series_aliases_series_id ON series_aliases(series_id);
+
+-- Collection names are compared case-insensitively, and series are kept in them while deleted so they can be restored
+CREATE TABLE IF NOT EXISTS series_collections (
+    name TEXT NOT NULL COLLATE NOCASE,
+    series_id INTEGER NOT NULL,
+    PRIMARY KEY(name, series_id)
+);
+
+CREATE INDEX IF NOT EXISTS series_collections_series_id ON series_collections(series_id);
+
+-- Filler episodes are kept while a series is deleted so they can be restored
+CREATE TABLE IF NOT EXISTS filler_episodes (
+    series_id INTEGER NOT NULL,
+    episode SMALLINT NOT NULL,
+    PRIMARY KEY(series_id, episode)
+);
+
+-- Files that the episode parser got wrong can be given the episode they are by hand
+CREATE TABLE IF NOT EXISTS episode_overrides (
+    series_id INTEGER NOT NULL,
+    filename TEXT NOT NULL,
+    episode SMALLINT NOT NULL,
+    PRIMARY KEY(series_id, filename)
+);
+
+CREATE TABLE IF NOT EXISTS watch_history (
+    id INTEGER NOT NULL PRIMARY KEY,
+    series_id INTEGER NOT NULL,
+    episode SMALLINT NOT NULL,
+    minutes SMALLINT NOT NULL,
+    watched_at BIGINT NOT NULL,
+    rating SMALLINT
+);
+
+CREATE INDEX IF NOT EXISTS watch_history_series_id ON watch_history(series_id);
+
+CREATE TABLE IF NOT EXISTS playback_sessions (
+    id INTEGER NOT NULL PRIMARY KEY,
+    series_id INTEGER NOT NULL,
+    episode SMALLINT NOT NULL,
+    started_at BIGINT NOT NULL,
+    seconds INTEGER NOT NULL,
+    exit_code INTEGER,
+    counted BOOLEAN NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS playback_sessions_series_id ON playback_sessions(series_id);
+
+-- Resume positions imported from mpv, used when mpv doesn't have one of its own for an episode
+CREATE TABLE IF NOT EXISTS resume_positions (
+    series_id INTEGER NOT NULL,
+    episode SMALLINT NOT NULL,
+    seconds INTEGER NOT NULL,
+    PRIMARY KEY(series_id, episode)
+);
+
+-- Episodes being played are recorded here until their player exits, so they can be recovered if the program closes first
+CREATE TABLE IF NOT EXISTS active_playbacks (
+    series_id INTEGER NOT NULL PRIMARY KEY,
+    episode SMALLINT NOT NULL,
+    started_at BIGINT NOT NULL,
+    pid INTEGER NOT NULL
+);
+
+-- Deleted series are kept here until they are restored or expire.
+-- Their list entries, watch history, and playback sessions are left in place until then.
+CREATE TABLE IF NOT EXISTS deleted_series (
+    id INTEGER NOT NULL PRIMARY KEY,
+    nickname TEXT NOT NULL,
+    path TEXT NOT NULL,
+    episode_parser TEXT,
+    player_args TEXT,
+    episode_offset INTEGER NOT NULL,
+    local_only BOOLEAN NOT NULL,
+    title_preferred TEXT,
+    title_romaji TEXT,
+    episodes SMALLINT,
+    episode_length_mins SMALLINT,
+    genres TEXT,
+    trashed_path TEXT,
+    deleted_at BIGINT NOT NULL,
+    remove_watched BOOLEAN NOT NULL DEFAULT 0,
+    planned_rewatch BOOLEAN NOT NULL DEFAULT 0,
+    title_english TEXT,
+    title_native TEXT,
+    title_override TEXT,
+    muted BOOLEAN NOT NULL DEFAULT 0,
+    cover_color TEXT,
+    sequel_ids TEXT
+);
+
+-- Errors and notifications are kept so ones that scrolled out of the log can still be read later
+CREATE TABLE IF NOT EXISTS saved_events (
+    id INTEGER NOT NULL PRIMARY KEY,
+    kind SMALLINT NOT NULL,
+    message TEXT NOT NULL,
+    logged_at BIGINT NOT NULL,
+    read BOOLEAN NOT NULL DEFAULT 0
+);
+
+-- Episode titles come from the remote service and are refreshed along with the series info
+CREATE TABLE IF NOT EXISTS episode_titles (
+    series_id INTEGER NOT NULL,
+    episode SMALLINT NOT NULL,
+    title TEXT NOT NULL,
+    PRIMARY KEY(series_id, episode)
+);
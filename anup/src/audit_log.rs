@@ -0,0 +1,342 @@
+use crate::database::Database;
+use crate::user::RemoteType;
+use anime::remote::{Remote, ScoreParser, SeriesDate, Status};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use diesel::prelude::*;
+
+/// A field of a list entry that can be sent to a remote service.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EntryField {
+    Progress,
+    Score,
+    Status,
+    Rewatched,
+    Priority,
+    StartDate,
+    FinishDate,
+}
+
+impl EntryField {
+    const ALL: [Self; 7] = [
+        Self::Progress,
+        Self::Score,
+        Self::Status,
+        Self::Rewatched,
+        Self::Priority,
+        Self::StartDate,
+        Self::FinishDate,
+    ];
+}
+
+/// The values of a list entry that were sent to a remote service.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SentEntry {
+    pub watched_episodes: i16,
+    pub score: Option<i16>,
+    pub status: Status,
+    pub times_rewatched: i16,
+    pub priority: i16,
+    pub start_date: Option<SeriesDate>,
+    pub end_date: Option<SeriesDate>,
+}
+
+impl SentEntry {
+    /// Returns the values `remote` receives when `entry` is sent to it.
+    ///
+    /// Scores are normalized the same way `anime::sync::force_push` does before sending them.
+    pub fn new(entry: &anime::remote::SeriesEntry, remote: &Remote) -> Self {
+        Self {
+            watched_episodes: entry.watched_eps as i16,
+            score: entry
+                .score
+                .map(|score| remote.normalize_score(score))
+                .filter(|&score| score > 0)
+                .map(i16::from),
+            status: entry.status,
+            times_rewatched: entry.times_rewatched as i16,
+            priority: entry.priority as i16,
+            start_date: entry.start_date,
+            end_date: entry.end_date,
+        }
+    }
+
+    /// Returns the fields that were sent with a different value than in `previous`.
+    ///
+    /// If nothing was sent before, every field that has a value is returned instead.
+    pub fn changed_fields(&self, previous: Option<&Self>) -> Vec<EntryField> {
+        EntryField::ALL
+            .iter()
+            .copied()
+            .filter(|&field| match previous {
+                Some(previous) => !self.same_value(previous, field),
+                None => self.has_value(field),
+            })
+            .collect()
+    }
+
+    fn same_value(&self, other: &Self, field: EntryField) -> bool {
+        match field {
+            EntryField::Progress => self.watched_episodes == other.watched_episodes,
+            EntryField::Score => self.score == other.score,
+            EntryField::Status => self.status == other.status,
+            EntryField::Rewatched => self.times_rewatched == other.times_rewatched,
+            EntryField::Priority => self.priority == other.priority,
+            EntryField::StartDate => self.start_date == other.start_date,
+            EntryField::FinishDate => self.end_date == other.end_date,
+        }
+    }
+
+    fn has_value(&self, field: EntryField) -> bool {
+        match field {
+            EntryField::Progress | EntryField::Status => true,
+            EntryField::Score => self.score.is_some(),
+            EntryField::Rewatched => self.times_rewatched > 0,
+            EntryField::Priority => self.priority > 0,
+            EntryField::StartDate => self.start_date.is_some(),
+            EntryField::FinishDate => self.end_date.is_some(),
+        }
+    }
+}
+
+/// A list entry that was sent to or removed from a remote service, kept so what the remote list should look like can
+/// be traced back to what was sent.
+pub struct AuditRecord {
+    pub series_id: i32,
+    pub service: String,
+    /// When the entry was sent, as a UNIX timestamp.
+    pub sent_at: i64,
+    /// The values that were sent, or None if the entry was removed from the list.
+    pub sent: Option<SentEntry>,
+}
+
+impl AuditRecord {
+    /// The number of records that are kept. Older ones are removed as new ones are saved.
+    pub const MAX_SAVED: i64 = 5000;
+
+    /// Record that `sent` was sent to `remote` for the series with the specified `id` as the specified `account`.
+    pub fn record_sent(
+        db: &Database,
+        account: &str,
+        remote: &Remote,
+        id: i32,
+        sent: SentEntry,
+    ) -> Result<()> {
+        Self::record(db, account, remote, id, Some(sent))
+    }
+
+    /// Record that the series with the specified `id` was removed from the list of the current account on `remote`.
+    pub fn record_removed(db: &Database, remote: &Remote, id: i32) -> Result<()> {
+        Self::record(db, db.account(), remote, id, None)
+    }
+
+    fn record(
+        db: &Database,
+        account: &str,
+        remote: &Remote,
+        id: i32,
+        sent: Option<SentEntry>,
+    ) -> Result<()> {
+        use crate::database::schema::audit_log::dsl;
+
+        db.write_transaction(|| {
+            diesel::insert_into(dsl::audit_log)
+                .values((
+                    dsl::series_id.eq(id),
+                    dsl::account_id.eq(account),
+                    dsl::service.eq(service_name(remote)),
+                    dsl::sent_at.eq(Utc::now().timestamp()),
+                    dsl::removed.eq(sent.is_none()),
+                    dsl::watched_episodes.eq(sent.map(|sent| sent.watched_episodes)),
+                    dsl::score.eq(sent.and_then(|sent| sent.score)),
+                    dsl::status.eq(sent.map(|sent| sent.status)),
+                    dsl::times_rewatched.eq(sent.map(|sent| sent.times_rewatched)),
+                    dsl::priority.eq(sent.map(|sent| sent.priority)),
+                    dsl::start_date.eq(sent.and_then(|sent| sent.start_date)),
+                    dsl::end_date.eq(sent.and_then(|sent| sent.end_date)),
+                ))
+                .execute(db.conn())?;
+
+            let oldest_kept = dsl::audit_log
+                .select(dsl::id)
+                .order(dsl::id.desc())
+                .offset(Self::MAX_SAVED - 1)
+                .first::<i32>(db.conn())
+                .optional()?;
+
+            if let Some(oldest_kept) = oldest_kept {
+                diesel::delete(dsl::audit_log.filter(dsl::id.lt(oldest_kept)))
+                    .execute(db.conn())?;
+            }
+
+            Ok(())
+        })
+        .context("recording list entry in the audit log")
+    }
+
+    /// Load every record of the current account, newest first.
+    pub fn load_all(db: &Database) -> diesel::QueryResult<Vec<Self>> {
+        use crate::database::schema::audit_log::dsl::{
+            account_id, audit_log, end_date, id, priority, removed, score, sent_at, series_id,
+            service, start_date, status, times_rewatched, watched_episodes,
+        };
+
+        type Row = (
+            i32,
+            String,
+            i64,
+            bool,
+            Option<i16>,
+            Option<i16>,
+            Option<Status>,
+            Option<i16>,
+            Option<i16>,
+            Option<SeriesDate>,
+            Option<SeriesDate>,
+        );
+
+        let rows = audit_log
+            .select((
+                series_id,
+                service,
+                sent_at,
+                removed,
+                watched_episodes,
+                score,
+                status,
+                times_rewatched,
+                priority,
+                start_date,
+                end_date,
+            ))
+            .filter(account_id.eq(db.account()))
+            .order(id.desc())
+            .load::<Row>(db.conn())?;
+
+        let records = rows
+            .into_iter()
+            .map(|row| {
+                let (
+                    series,
+                    name,
+                    at,
+                    was_removed,
+                    watched,
+                    points,
+                    sent_status,
+                    rewatched,
+                    prio,
+                    started,
+                    finished,
+                ) = row;
+
+                let sent = match (was_removed, watched, sent_status) {
+                    (false, Some(watched), Some(sent_status)) => Some(SentEntry {
+                        watched_episodes: watched,
+                        score: points,
+                        status: sent_status,
+                        times_rewatched: rewatched.unwrap_or(0),
+                        priority: prio.unwrap_or(0),
+                        start_date: started,
+                        end_date: finished,
+                    }),
+                    _ => None,
+                };
+
+                Self {
+                    series_id: series,
+                    service: name,
+                    sent_at: at,
+                    sent,
+                }
+            })
+            .collect();
+
+        Ok(records)
+    }
+
+    /// Returns the values that were sent for the same series before the record at `index` of `records`, which must be
+    /// ordered newest first.
+    ///
+    /// Returns None if nothing was sent before, or the entry was removed from the list since.
+    pub fn previously_sent(records: &[Self], index: usize) -> Option<&SentEntry> {
+        let series_id = records.get(index)?.series_id;
+
+        records
+            .iter()
+            .skip(index + 1)
+            .find(|record| record.series_id == series_id)
+            .and_then(|record| record.sent.as_ref())
+    }
+}
+
+/// Returns the name of the service `remote` sends list entries to.
+fn service_name(remote: &Remote) -> &'static str {
+    match remote {
+        Remote::AniList(_) => RemoteType::AniList.as_str(),
+        Remote::Mock(_) => "Mock",
+        Remote::Offline(_) => "Offline",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sent(watched_episodes: i16, status: Status) -> SentEntry {
+        SentEntry {
+            watched_episodes,
+            score: None,
+            status,
+            times_rewatched: 0,
+            priority: 0,
+            start_date: None,
+            end_date: None,
+        }
+    }
+
+    #[test]
+    fn changed_fields_against_previous() {
+        let first = sent(1, Status::Watching);
+        assert_eq!(
+            first.changed_fields(None),
+            [EntryField::Progress, EntryField::Status]
+        );
+
+        let mut completed = sent(12, Status::Completed);
+        completed.score = Some(80);
+
+        assert_eq!(
+            completed.changed_fields(Some(&first)),
+            [EntryField::Progress, EntryField::Score, EntryField::Status]
+        );
+
+        assert!(completed.changed_fields(Some(&completed)).is_empty());
+    }
+
+    #[test]
+    fn previously_sent_skips_other_series() {
+        let record = |series_id, sent| AuditRecord {
+            series_id,
+            service: "AniList".into(),
+            sent_at: 0,
+            sent,
+        };
+
+        let records = [
+            record(1, Some(sent(3, Status::Watching))),
+            record(2, Some(sent(5, Status::Watching))),
+            record(1, Some(sent(2, Status::Watching))),
+            record(2, None),
+            record(2, Some(sent(4, Status::Watching))),
+        ];
+
+        assert_eq!(
+            AuditRecord::previously_sent(&records, 0),
+            Some(&sent(2, Status::Watching))
+        );
+        assert_eq!(AuditRecord::previously_sent(&records, 1), None);
+        assert_eq!(AuditRecord::previously_sent(&records, 2), None);
+        assert_eq!(AuditRecord::previously_sent(&records, 5), None);
+    }
+}
@@ -0,0 +1,111 @@
+use crate::config::{BackupConfig, Config};
+use crate::database::Database;
+use crate::file::{SaveDir, SerializedFile};
+use crate::user::Users;
+use anyhow::{Context, Result};
+use chrono::{Duration, Local, NaiveDateTime};
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The format used to name each backup directory.
+///
+/// Backups are ordered by their name, so this must sort chronologically.
+const BACKUP_NAME_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+/// Take a snapshot of the database, accounts, and config if the most recent backup is older than the configured interval,
+/// and remove the oldest backups that exceed the configured limit.
+///
+/// Returns true if a backup was made.
+pub fn create_if_due(config: &BackupConfig) -> Result<bool> {
+    if !config.enabled || config.keep == 0 {
+        return Ok(false);
+    }
+
+    let dir = backups_dir()?;
+    let backups = existing_backups(&dir).context("reading backups")?;
+    let now = Local::now().naive_local();
+
+    let due = match backups.last() {
+        Some((time, _)) => now - *time >= Duration::hours(config.interval_hours.into()),
+        None => true,
+    };
+
+    if !due {
+        return Ok(false);
+    }
+
+    let path = dir.join(now.format(BACKUP_NAME_FORMAT).to_string());
+    create(&path).with_context(|| format!("creating backup at {}", path.display()))?;
+
+    // The new backup is always the newest, so only the existing backups need to be considered for removal
+    let num_to_remove = (backups.len() + 1).saturating_sub(config.keep);
+
+    for (_, old_path) in backups.iter().take(num_to_remove) {
+        fs::remove_dir_all(old_path)
+            .with_context(|| format!("removing old backup at {}", old_path.display()))?;
+    }
+
+    Ok(true)
+}
+
+fn create(path: &Path) -> Result<()> {
+    fs::create_dir_all(path).context("creating directory")?;
+
+    let db_path = Database::validated_path()?;
+
+    // The database can't simply be copied, as recent changes may still be in its write-ahead log
+    if db_path.exists() {
+        let conn = SqliteConnection::establish(&db_path.to_string_lossy())?;
+        let backup_path = path.join("data.sqlite");
+
+        conn.batch_execute(&format!(
+            "VACUUM INTO '{}'",
+            backup_path.to_string_lossy().replace('\'', "''")
+        ))
+        .context("copying database")?;
+    }
+
+    for file in &[
+        Users::validated_save_path()?,
+        Config::validated_save_path()?,
+    ] {
+        if !file.exists() {
+            continue;
+        }
+
+        if let Some(name) = file.file_name() {
+            fs::copy(file, path.join(name))
+                .with_context(|| format!("copying {}", file.display()))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns every backup in `dir` along with the time it was made, sorted from oldest to newest.
+fn existing_backups(dir: &Path) -> Result<Vec<(NaiveDateTime, PathBuf)>> {
+    let mut backups = fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_str()?;
+            let time = NaiveDateTime::parse_from_str(name, BACKUP_NAME_FORMAT).ok()?;
+            Some((time, path))
+        })
+        .collect::<Vec<_>>();
+
+    backups.sort_unstable_by_key(|(time, _)| *time);
+    Ok(backups)
+}
+
+fn backups_dir() -> Result<PathBuf> {
+    let mut dir = SaveDir::LocalData.validated_dir_path()?.to_path_buf();
+    dir.push("backups");
+
+    if !dir.exists() {
+        fs::create_dir_all(&dir).context("creating backups directory")?;
+    }
+
+    Ok(dir)
+}
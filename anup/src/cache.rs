@@ -0,0 +1,228 @@
+use crate::file::SaveDir;
+use crate::util;
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+#[derive(FromArgs)]
+/// Manage data cached on disk.
+#[argh(subcommand, name = "cache")]
+pub struct CacheArgs {
+    #[argh(subcommand)]
+    command: CacheCommand,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum CacheCommand {
+    Stats(StatsArgs),
+    Clear(ClearArgs),
+    Prune(PruneArgs),
+}
+
+#[derive(FromArgs)]
+/// Show the number of files and space used by each cache.
+#[argh(subcommand, name = "stats")]
+struct StatsArgs {}
+
+#[derive(FromArgs)]
+/// Remove every cached file.
+#[argh(subcommand, name = "clear")]
+struct ClearArgs {}
+
+#[derive(FromArgs)]
+/// Remove cached files that haven't been modified in the specified amount of time.
+#[argh(subcommand, name = "prune")]
+struct PruneArgs {
+    /// the minimum age of files to remove, such as 30d, 12h, or 45m
+    #[argh(option, from_str_fn(parse_age))]
+    older_than: Duration,
+}
+
+pub fn run(args: &CacheArgs) -> Result<()> {
+    match &args.command {
+        CacheCommand::Stats(_) => print_stats(),
+        CacheCommand::Clear(_) => prune(None),
+        CacheCommand::Prune(prune_args) => prune(Some(prune_args.older_than)),
+    }
+}
+
+fn print_stats() -> Result<()> {
+    let mut total = CacheUsage::default();
+
+    for &kind in CacheKind::ALL {
+        let usage = CacheUsage::of(&kind.path()?)
+            .with_context(|| format!("getting size of {} cache", kind.name()))?;
+
+        println!(
+            "{}: {} file(s), {}",
+            kind.name(),
+            usage.files,
+            util::human_size(usage.bytes)
+        );
+
+        total.files += usage.files;
+        total.bytes += usage.bytes;
+    }
+
+    println!(
+        "total: {} file(s), {}",
+        total.files,
+        util::human_size(total.bytes)
+    );
+
+    Ok(())
+}
+
+/// Remove every cached file older than `max_age`, or all of them if `max_age` is `None`.
+fn prune(max_age: Option<Duration>) -> Result<()> {
+    let cutoff = max_age.and_then(|age| SystemTime::now().checked_sub(age));
+    let mut removed = CacheUsage::default();
+
+    for &kind in CacheKind::ALL {
+        let usage = remove_files(&kind.path()?, cutoff)
+            .with_context(|| format!("pruning {} cache", kind.name()))?;
+
+        removed.files += usage.files;
+        removed.bytes += usage.bytes;
+    }
+
+    println!(
+        "removed {} file(s), freeing {}",
+        removed.files,
+        util::human_size(removed.bytes)
+    );
+
+    Ok(())
+}
+
+/// Categories of data cached on disk.
+#[derive(Copy, Clone)]
+pub enum CacheKind {
+    SearchResults,
+    /// Episodes downloaded from SFTP servers before they're played.
+    Downloads,
+}
+
+impl CacheKind {
+    const ALL: &'static [Self] = &[Self::SearchResults, Self::Downloads];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::SearchResults => "search results",
+            Self::Downloads => "downloaded episodes",
+        }
+    }
+
+    fn dir_name(self) -> &'static str {
+        match self {
+            Self::SearchResults => "search",
+            Self::Downloads => "downloads",
+        }
+    }
+
+    /// Returns the path to the directory for the cache.
+    ///
+    /// The directory will not be created if it doesn't exist.
+    pub fn path(self) -> Result<PathBuf> {
+        let mut path = SaveDir::Cache.validated_dir_path()?.to_path_buf();
+        path.push(self.dir_name());
+        Ok(path)
+    }
+}
+
+#[derive(Default)]
+struct CacheUsage {
+    files: u64,
+    bytes: u64,
+}
+
+impl CacheUsage {
+    fn of(dir: &Path) -> io::Result<Self> {
+        let mut usage = Self::default();
+
+        visit_files(dir, &mut |_, metadata| {
+            usage.files += 1;
+            usage.bytes += metadata.len();
+            Ok(())
+        })?;
+
+        Ok(usage)
+    }
+}
+
+fn remove_files(dir: &Path, cutoff: Option<SystemTime>) -> io::Result<CacheUsage> {
+    let mut removed = CacheUsage::default();
+
+    visit_files(dir, &mut |path, metadata| {
+        if let Some(cutoff) = cutoff {
+            if metadata.modified()? > cutoff {
+                return Ok(());
+            }
+        }
+
+        fs::remove_file(path)?;
+
+        removed.files += 1;
+        removed.bytes += metadata.len();
+        Ok(())
+    })?;
+
+    Ok(removed)
+}
+
+/// Call `func` on every file in `dir` and its subdirectories.
+///
+/// Nothing will happen if `dir` doesn't exist.
+fn visit_files<F>(dir: &Path, func: &mut F) -> io::Result<()>
+where
+    F: FnMut(&Path, &fs::Metadata) -> io::Result<()>,
+{
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        let metadata = entry.metadata()?;
+
+        if metadata.is_dir() {
+            visit_files(&path, func)?;
+        } else {
+            func(&path, &metadata)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse an age like `30d`, `12h`, `45m`, or `90s`.
+fn parse_age(value: &str) -> Result<Duration, String> {
+    let parse = || {
+        let unit_pos = value
+            .find(|ch: char| !ch.is_ascii_digit())
+            .ok_or_else(|| anyhow!("missing unit"))?;
+
+        let (amount, unit) = value.split_at(unit_pos);
+        let amount = amount.parse::<u64>().context("invalid amount")?;
+
+        let secs_per_unit = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 60 * 60 * 24,
+            "w" => 60 * 60 * 24 * 7,
+            _ => return Err(anyhow!("unknown unit: {}", unit)),
+        };
+
+        Ok(Duration::from_secs(amount * secs_per_unit))
+    };
+
+    parse().map_err(|err: anyhow::Error| format!("invalid age \"{}\": {}", value, err))
+}
@@ -0,0 +1,212 @@
+use crate::config::Config;
+use crate::database::Database;
+use crate::series::config::SeriesConfig;
+use crate::series::history::WatchEvent;
+use crate::series::SeriesData;
+use crate::util;
+use crate::{init_remote, open_database, Args};
+use anime::remote::Status;
+use anyhow::{Context, Result};
+use argh::FromArgs;
+use chrono::{Duration, Utc};
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+#[derive(FromArgs)]
+/// List series that are being watched, but haven't had an episode watched in a while, and put them on hold or drop them.
+#[argh(subcommand, name = "cleanup")]
+pub struct CleanupArgs {
+    /// the number of weeks without progress before a series is listed (default: 8)
+    #[argh(option, default = "8")]
+    weeks: u32,
+}
+
+/// A series that is being watched, but hasn't made progress recently.
+struct StaleSeries {
+    data: SeriesData,
+    /// How long ago progress was last made.
+    idle: Duration,
+}
+
+impl StaleSeries {
+    fn describe(&self) -> String {
+        format!(
+            "{}: {} of {} episodes watched, last watched {} weeks ago",
+            self.data.config.nickname,
+            self.data.entry.watched_episodes(),
+            self.data.info.episodes_text(),
+            self.idle.num_weeks()
+        )
+    }
+}
+
+/// What to do with the stale series.
+#[derive(Copy, Clone)]
+enum Choice {
+    Set(Status),
+    Skip,
+    Quit,
+}
+
+pub fn run(args: &Args, cleanup_args: &CleanupArgs) -> Result<()> {
+    let config = Config::load_and_report()?;
+    let db = open_database(args)?;
+
+    let mut stale = find_stale(&db, Duration::weeks(i64::from(cleanup_args.weeks)))?;
+
+    if stale.is_empty() {
+        println!(
+            "every series being watched has progressed in the last {} weeks",
+            cleanup_args.weeks
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{} series being watched haven't progressed in {} weeks:",
+        stale.len(),
+        cleanup_args.weeks
+    );
+
+    for (i, series) in stale.iter().enumerate() {
+        println!("{}. {}", i + 1, series.describe());
+    }
+
+    let choices = match ask(
+        "put all of them on (h)old, (d)rop all of them, choose for (e)ach one, or (q)uit?",
+    )? {
+        'h' => vec![Choice::Set(Status::OnHold); stale.len()],
+        'd' => vec![Choice::Set(Status::Dropped); stale.len()],
+        'e' => choose_each(&stale)?,
+        _ => return Ok(()),
+    };
+
+    // Changes are saved even when offline, so they can be synced later with --sync
+    let remote = if args.offline {
+        None
+    } else {
+        init_remote(args, &config)?
+    };
+
+    let mut changed = 0;
+    let mut unsynced = 0;
+
+    for (series, choice) in stale.iter_mut().zip(choices) {
+        let status = match choice {
+            Choice::Set(status) => status,
+            Choice::Skip => continue,
+            Choice::Quit => break,
+        };
+
+        let data = &mut series.data;
+
+        data.entry.set_status(status, &config);
+        data.save(&db)?;
+        changed += 1;
+
+        let remote = match &remote {
+            Some(remote) => remote,
+            None => continue,
+        };
+
+        if let Err(err) = data
+            .sync_to_remote(remote, &db)
+            .and_then(|()| data.save(&db))
+        {
+            eprintln!("failed to sync {}: {:#}", data.config.nickname, err);
+            unsynced += 1;
+        }
+    }
+
+    println!("changed {} series", changed);
+
+    if changed > 0 && (remote.is_none() || unsynced > 0) {
+        println!("run anup --sync to send any changes that haven't been synced");
+    }
+
+    Ok(())
+}
+
+/// Returns every series being watched that hasn't progressed for at least `min_idle`, with the longest idle series first.
+///
+/// Progress is taken from the watch history, or the start date of the list entry for series that don't have any.
+/// Series without either are left out, since there's no telling when they were last watched.
+fn find_stale(db: &Database, min_idle: Duration) -> Result<Vec<StaleSeries>> {
+    let now = Utc::now();
+    let mut stale = Vec::new();
+
+    let configs = SeriesConfig::load_all(db).context("loading series configs")?;
+
+    for series_config in configs {
+        let data = SeriesData::load_from_config(db, Cow::Owned(series_config))
+            .context("loading series")?;
+
+        if data.entry.status() != Status::Watching {
+            continue;
+        }
+
+        let last_progress = WatchEvent::latest_watched_at(db, data.info.id)
+            .context("loading watch history")?
+            .or_else(|| {
+                data.entry
+                    .start_date()
+                    .and_then(util::local_midnight)
+                    .map(|midnight| midnight.timestamp())
+            });
+
+        let last_progress = match last_progress {
+            Some(last_progress) => last_progress,
+            None => continue,
+        };
+
+        let idle = Duration::seconds(now.timestamp() - last_progress);
+
+        if idle >= min_idle {
+            stale.push(StaleSeries { data, idle });
+        }
+    }
+
+    stale.sort_unstable_by_key(|series| std::cmp::Reverse(series.idle));
+    Ok(stale)
+}
+
+fn choose_each(stale: &[StaleSeries]) -> Result<Vec<Choice>> {
+    let mut choices = Vec::with_capacity(stale.len());
+
+    for series in stale {
+        println!("{}", series.describe());
+
+        let choice = match ask("(h)old, (d)rop, (s)kip, or (q)uit?")? {
+            'h' => Choice::Set(Status::OnHold),
+            'd' => Choice::Set(Status::Dropped),
+            'q' => Choice::Quit,
+            _ => Choice::Skip,
+        };
+
+        choices.push(choice);
+
+        if let Choice::Quit = choice {
+            break;
+        }
+    }
+
+    Ok(choices)
+}
+
+/// Ask `question` and return the first letter of the answer in lowercase.
+fn ask(question: &str) -> Result<char> {
+    print!("{} ", question);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+
+    io::stdin()
+        .read_line(&mut answer)
+        .context("reading answer")?;
+
+    Ok(answer
+        .trim()
+        .chars()
+        .next()
+        .map_or('q', |ch| ch.to_ascii_lowercase()))
+}
@@ -0,0 +1,512 @@
+use crate::config::Config;
+use crate::database::Database;
+use crate::err::Failure;
+use crate::private_session::PrivateSession;
+use crate::remote::ensure_scores_supported;
+use crate::series::alias::SeriesAlias;
+use crate::series::collection::SeriesCollection;
+use crate::series::config::SeriesConfig;
+use crate::series::deleted::DeletedSeries;
+use crate::series::filler::FillerEpisodes;
+use crate::series::history::{WatchEvent, WatchTotals};
+use crate::series::sync_failure::SyncFailure;
+use crate::series::{LastWatched, LoadedSeries, Series, SeriesData};
+use crate::tui::command::{
+    AliasAction, CollectionAction, Command, FillerAction, FixEpisodeAction, ProgressDirection,
+};
+use crate::tui::locale::Text;
+use crate::{init_remote, open_database, Args};
+use anime::remote::{Remote, RemoteService, ScoreParser, Status};
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+
+#[derive(FromArgs)]
+/// Run a command from the TUI's command prompt without opening the TUI, for use in scripts.
+#[argh(subcommand, name = "cmd")]
+pub struct CmdArgs {
+    /// the command to run, written the same way as in the TUI, such as "status watching" or "rate 80"
+    #[argh(positional)]
+    command: String,
+
+    /// the nickname or alias of the series to run the command on, instead of the last watched series
+    #[argh(option)]
+    series: Option<String>,
+}
+
+pub fn run(args: &Args, cmd_args: &CmdArgs) -> Result<()> {
+    let config = Config::load_and_report()?;
+    let command = Command::from_str(&cmd_args.command, &config)?;
+
+    match command {
+        // Syncing every series is what --sync already does
+        Command::SyncAll => return crate::sync(args),
+        Command::Private(true) => {
+            PrivateSession::start()?;
+            println!(
+                "private session started; changes will only be saved locally until it's turned off"
+            );
+            return Ok(());
+        }
+        // The changes made during the session are synced the same way as with --sync
+        Command::Private(false) => {
+            PrivateSession::end()?;
+            println!("private session ended");
+            return crate::sync(args);
+        }
+        Command::RefreshAll | Command::Suggest | Command::Play(_, _) | Command::Sort(_) => {
+            return Err(anyhow!(
+                "{} can only be used in the TUI",
+                cmd_args.command.trim()
+            ))
+        }
+        _ => (),
+    }
+
+    let db = open_database(args)?;
+
+    // Changes are still saved without an account, so they can be synced later with --sync
+    let remote = init_remote(args, &config)?.unwrap_or_else(Remote::offline);
+
+    if let Command::Undelete(name) = &command {
+        let deleted = DeletedSeries::load_by_name(&db, name)
+            .with_context(|| format!("no deleted series named {}", name))?;
+
+        let restored = deleted.restore(&db, &config)?;
+        println!("restored {}", restored.nickname);
+        return Ok(());
+    }
+
+    let last_watched = LastWatched::load().context("loading last watched series")?;
+
+    let nickname = cmd_args
+        .series
+        .as_ref()
+        .or(args.series.as_ref())
+        .or_else(|| last_watched.get())
+        .ok_or_else(|| anyhow!("no series has been watched yet\nspecify one with --series"))?;
+
+    let series_config = SeriesConfig::load_by_name_or_alias(&db, nickname)
+        .with_context(|| format!("{} has not been added", nickname))?;
+
+    let mut series = Series::load_from_config(series_config, &config, &db);
+    let message = run_on_series(command, &mut series, &config, &db, &remote)?;
+
+    println!("{}", message);
+
+    if remote.is_offline() && series.data().map_or(false, |data| data.entry.needs_sync()) {
+        println!("run anup --sync to send the changes to AniList");
+    }
+
+    Ok(())
+}
+
+/// Run `command` on `series`, and return a description of what it did.
+#[allow(clippy::too_many_lines)]
+fn run_on_series(
+    command: Command,
+    series: &mut LoadedSeries,
+    config: &Config,
+    db: &Database,
+    remote: &Remote,
+) -> Result<String> {
+    match command {
+        Command::PlayerArgs(args) => {
+            let data = loaded(series)?;
+
+            data.config.player_args = args.into();
+            data.save(db)?;
+
+            Ok(format!("player arguments of {} set", data.title()))
+        }
+        Command::Progress(direction) => {
+            let series = complete(series)?;
+
+            match direction {
+                ProgressDirection::Forwards => series.episode_completed(config, db)?,
+                ProgressDirection::Backwards => series.episode_regressed(config, db)?,
+            }
+
+            series.data.sync_to_remote(remote, db)?;
+            series.save(db)?;
+
+            Ok(format!(
+                "{} of {} episodes of {} watched",
+                series.data.entry.watched_episodes(),
+                series.data.info.episodes_text(),
+                series.data.title()
+            ))
+        }
+        Command::SyncFromRemote => {
+            let data = loaded(series)?;
+            ensure_online(remote)?;
+
+            data.force_sync_from_remote(remote)?;
+            data.refresh_episode_titles(db, remote)?;
+            data.save(db)?;
+
+            Ok(format!("retrieved the list entry of {}", data.title()))
+        }
+        Command::SyncToRemote => {
+            let data = loaded(series)?;
+            ensure_online(remote)?;
+
+            data.force_sync_to_remote(remote, db)?;
+            SyncFailure::clear(db, data.info.id)?;
+            data.save(db)?;
+
+            Ok(format!("synced {}", data.title()))
+        }
+        Command::Score(raw_score) => {
+            let data = loaded(series)?;
+            ensure_scores_supported(remote)?;
+
+            let current = data.entry.score().map(|score| score as u8);
+
+            let score = match remote.parse_relative_score(&raw_score, current) {
+                Some(0) => None,
+                Some(score) => Some(score),
+                None => return Err(anyhow!("invalid score")),
+            };
+
+            data.entry.set_score(score.map(i16::from));
+            data.sync_to_remote(remote, db)?;
+            data.save(db)?;
+
+            Ok(match score {
+                Some(score) => format!(
+                    "score of {} set to {}",
+                    data.title(),
+                    remote.score_to_str(score)
+                ),
+                None => format!("score of {} removed", data.title()),
+            })
+        }
+        Command::Status(status) => {
+            let data = loaded(series)?;
+
+            data.entry.set_status(status, config);
+            data.sync_to_remote(remote, db)?;
+            data.save(db)?;
+
+            Ok(format!("status of {} set to {}", data.title(), status))
+        }
+        Command::Offset(offset) => {
+            let series = complete(series)?;
+
+            series.set_episode_offset(offset, config)?;
+            series.save(db)?;
+
+            Ok(format!(
+                "episode offset of {} set to {}",
+                series.data.title(),
+                offset
+            ))
+        }
+        Command::Title(title) => {
+            let data = loaded(series)?;
+
+            data.set_title_override(title)?;
+            data.save(db)?;
+
+            Ok(format!("title set to {}", data.title()))
+        }
+        Command::RemoteSync(enabled) => {
+            let data = loaded(series)?;
+
+            data.config.local_only = !enabled;
+
+            // Push any changes that were made while the series wasn't synced
+            if enabled {
+                data.sync_to_remote(remote, db)?;
+            }
+
+            data.save(db)?;
+
+            let state = if enabled { "on" } else { "off" };
+            Ok(format!("syncing of {} turned {}", data.title(), state))
+        }
+        Command::RemoveWatched(enabled) => {
+            let series = complete(series)?;
+
+            series.data.config.remove_watched = enabled;
+            series.save(db)?;
+
+            if !enabled {
+                return Ok(format!(
+                    "watched episodes of {} will be kept",
+                    series.data.title()
+                ));
+            }
+
+            let removed = series.remove_watched_episodes(config, db)?;
+
+            Ok(format!(
+                "removed {} watched episodes of {}",
+                removed,
+                series.data.title()
+            ))
+        }
+        Command::ResetSync => {
+            let data = loaded(series)?;
+
+            SyncFailure::clear(db, data.info.id)?;
+            data.sync_to_remote(remote, db)?;
+            data.save(db)?;
+
+            Ok(format!("{} will be synced again", data.title()))
+        }
+        Command::Relink(id) => {
+            let data = loaded(series)?;
+            ensure_online(remote)?;
+
+            data.relink(id, db, remote)?;
+            data.sync_to_remote(remote, db)?;
+            data.save(db)?;
+
+            Ok(format!(
+                "{} relinked to {}",
+                data.config.nickname,
+                data.title()
+            ))
+        }
+        Command::Mute(muted) => {
+            let data = loaded(series)?;
+
+            data.config.muted = muted;
+            data.save(db)?;
+
+            let state = if muted { "muted" } else { "unmuted" };
+            Ok(format!("notifications of {} {}", data.title(), state))
+        }
+        Command::RewatchPlan(planned) => {
+            let data = loaded(series)?;
+
+            if planned && data.entry.status() != Status::Completed {
+                return Err(anyhow!(
+                    "only completed series can be planned to be rewatched"
+                ));
+            }
+
+            data.config.planned_rewatch = planned;
+            data.save(db)?;
+
+            let state = if planned { "on" } else { "off" };
+            Ok(format!("rewatch plan of {} turned {}", data.title(), state))
+        }
+        Command::RateEpisode(rating, episode) => {
+            let data = loaded(series)?;
+            let episode = episode.unwrap_or_else(|| data.entry.watched_episodes());
+
+            if episode < 1 {
+                return Err(anyhow!("no episodes have been watched"));
+            }
+
+            let id = data.info.id;
+
+            if WatchEvent::rate(db, id, episode, rating)? == 0 {
+                return Err(anyhow!(
+                    "episode {} hasn't been watched to completion",
+                    episode
+                ));
+            }
+
+            data.history = WatchTotals::load(db, id)?;
+
+            Ok(format!(
+                "episode {} of {} rated {}",
+                episode,
+                data.title(),
+                rating
+            ))
+        }
+        Command::PullProgress => {
+            let data = loaded(series)?;
+            ensure_online(remote)?;
+
+            if !data.entry.pull_progress(remote)? {
+                return Err(anyhow!("{} isn't on your list", data.title()));
+            }
+
+            data.save(db)?;
+
+            Ok(Text::ProgressPulled.format(&[
+                data.title(),
+                &data.entry.watched_episodes(),
+                &data.entry.status(),
+            ]))
+        }
+        Command::Priority(priority) => {
+            let data = loaded(series)?;
+
+            data.entry.set_priority(priority);
+            data.sync_to_remote(remote, db)?;
+            data.save(db)?;
+
+            Ok(format!("priority of {} set to {}", data.title(), priority))
+        }
+        Command::ResetDates => {
+            let data = loaded(series)?;
+
+            data.entry.reset_dates();
+            data.sync_to_remote(remote, db)?;
+            data.save(db)?;
+
+            Ok(format!("dates of {} reset", data.title()))
+        }
+        Command::Alias(action) => {
+            let id = series.config().id;
+            let nickname = series.nickname();
+
+            match action {
+                AliasAction::Add(alias) => {
+                    SeriesAlias::add(db, id, &alias)?;
+                    Ok(Text::AliasAdded.format(&[&alias, &nickname]))
+                }
+                AliasAction::Remove(alias) => {
+                    if SeriesAlias::remove(db, id, &alias)? == 0 {
+                        return Err(anyhow!("{} isn't an alias of {}", alias, nickname));
+                    }
+
+                    Ok(Text::AliasRemoved.format(&[&alias, &nickname]))
+                }
+                AliasAction::List => {
+                    let aliases = SeriesAlias::for_series(db, id)?;
+
+                    if aliases.is_empty() {
+                        Ok(Text::NoAliases.format(&[&nickname]))
+                    } else {
+                        Ok(Text::Aliases.format(&[&nickname, &aliases.join(", ")]))
+                    }
+                }
+            }
+        }
+        Command::Filler(action) => {
+            let data = loaded(series)?;
+            let id = data.config.id;
+
+            match action {
+                FillerAction::Set(filler) => {
+                    filler.save(db, id)?;
+                    data.filler = filler;
+                    Ok(Text::FillerSet.format(&[&data.config.nickname, &data.filler]))
+                }
+                FillerAction::Clear => {
+                    FillerEpisodes::delete_for_series(db, id)?;
+                    Ok(Text::FillerCleared.format(&[&data.config.nickname]))
+                }
+                FillerAction::List if data.filler.is_empty() => {
+                    Ok(Text::NoFiller.format(&[&data.config.nickname]))
+                }
+                FillerAction::List => {
+                    Ok(Text::FillerEpisodes.format(&[&data.config.nickname, &data.filler]))
+                }
+            }
+        }
+        Command::FixEpisode(action) => {
+            let series = complete(series)?;
+            let nickname = series.data.config.nickname.clone();
+
+            match action {
+                FixEpisodeAction::Set(episode, name) => {
+                    let filename = series.override_episode(episode, &name, db, config)?;
+                    Ok(Text::EpisodeOverrideSet.format(&[&filename, &episode, &nickname]))
+                }
+                FixEpisodeAction::Clear => {
+                    series.clear_episode_overrides(db, config)?;
+                    Ok(Text::EpisodeOverridesCleared.format(&[&nickname]))
+                }
+                FixEpisodeAction::List if series.data.episode_overrides.is_empty() => {
+                    Ok(Text::NoEpisodeOverrides.format(&[&nickname]))
+                }
+                FixEpisodeAction::List => {
+                    Ok(Text::EpisodeOverrides.format(&[&nickname, &series.data.episode_overrides]))
+                }
+            }
+        }
+        Command::Collection(action) => {
+            let id = series.config().id;
+            let nickname = series.nickname();
+
+            match action {
+                CollectionAction::Add(name) => {
+                    if !SeriesCollection::add(db, id, &name)? {
+                        return Err(anyhow!("{} is already in {}", nickname, name));
+                    }
+
+                    Ok(Text::CollectionAdded.format(&[&nickname, &name]))
+                }
+                CollectionAction::Remove(name) => {
+                    if SeriesCollection::remove(db, id, &name)? == 0 {
+                        return Err(anyhow!("{} isn't in {}", nickname, name));
+                    }
+
+                    Ok(Text::CollectionRemoved.format(&[&nickname, &name]))
+                }
+                CollectionAction::List => {
+                    let names = SeriesCollection::for_series(db, id)?;
+
+                    if names.is_empty() {
+                        Ok(Text::NoCollections.format(&[&nickname]))
+                    } else {
+                        Ok(Text::Collections.format(&[&nickname, &names.join(", ")]))
+                    }
+                }
+            }
+        }
+        Command::RemoveEntry => {
+            let data = loaded(series)?;
+            ensure_online(remote)?;
+
+            let removed = data.remove_from_remote(remote, db)?;
+            data.save(db)?;
+
+            let text = if removed {
+                Text::EntryRemoved
+            } else {
+                Text::EntryNotListed
+            };
+
+            Ok(text.format(&[data.title()]))
+        }
+        // Scripts can pipe the message wherever it's needed, so it isn't copied to the clipboard
+        Command::Share(with_url) => {
+            let data = loaded(series)?;
+            Ok(data.share_text(data.shared_episode(), with_url))
+        }
+        Command::Undelete(_)
+        | Command::SyncAll
+        | Command::Private(_)
+        | Command::RefreshAll
+        | Command::Suggest
+        | Command::Play(_, _)
+        | Command::Sort(_) => {
+            unreachable!("commands that aren't for a single series are run before one is loaded")
+        }
+    }
+}
+
+fn ensure_online(remote: &Remote) -> Result<()> {
+    if remote.is_offline() {
+        Err(Failure::Offline.error("must be online to run this command"))
+    } else {
+        Ok(())
+    }
+}
+
+fn complete(series: &mut LoadedSeries) -> Result<&mut Series> {
+    match series {
+        LoadedSeries::Complete(series) => Ok(series),
+        LoadedSeries::Partial(data, err) => Err(anyhow!("{} can't be used: {}", data.title(), err)),
+        LoadedSeries::None(config, err) => {
+            Err(anyhow!("{} can't be used: {:#}", config.nickname, err))
+        }
+    }
+}
+
+fn loaded(series: &mut LoadedSeries) -> Result<&mut SeriesData> {
+    let nickname = series.config().nickname.clone();
+
+    series
+        .data_mut()
+        .ok_or_else(|| anyhow!("{} isn't loaded", nickname))
+}
@@ -0,0 +1,206 @@
+use super::Config;
+use crate::file::SerializedFile;
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+use serde_json::Value;
+use std::fs;
+
+/// The default config with a comment explaining each option.
+///
+/// `{series_dir}` is replaced with the default series directory, since it depends on the home directory.
+const COMMENTED_DEFAULT: &str = include_str!("default.ron");
+
+#[derive(FromArgs)]
+/// View or change options in the config file.
+#[argh(subcommand, name = "config")]
+pub struct ConfigArgs {
+    #[argh(subcommand)]
+    command: ConfigCommand,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum ConfigCommand {
+    Init(InitArgs),
+    Get(GetArgs),
+    Set(SetArgs),
+}
+
+#[derive(FromArgs)]
+/// Write a config file with every option set to its default value, along with a comment explaining each one.
+#[argh(subcommand, name = "init")]
+struct InitArgs {
+    /// replace the existing config file
+    #[argh(switch)]
+    force: bool,
+}
+
+#[derive(FromArgs)]
+/// Print the value of an option.
+#[argh(subcommand, name = "get")]
+struct GetArgs {
+    /// the name of the option, with sections separated by dots, such as episode.player
+    #[argh(positional)]
+    name: String,
+}
+
+#[derive(FromArgs)]
+/// Change the value of an option.
+#[argh(subcommand, name = "set")]
+struct SetArgs {
+    /// the name of the option, with sections separated by dots, such as episode.player
+    #[argh(positional)]
+    name: String,
+
+    /// the new value of the option
+    #[argh(positional)]
+    value: String,
+}
+
+pub fn run(args: &ConfigArgs) -> Result<()> {
+    match &args.command {
+        ConfigCommand::Init(init_args) => init(init_args.force),
+        ConfigCommand::Get(get_args) => get(&get_args.name),
+        ConfigCommand::Set(set_args) => set(&set_args.name, &set_args.value),
+    }
+}
+
+fn init(force: bool) -> Result<()> {
+    let path = Config::validated_save_path()?;
+
+    if path.exists() && !force {
+        return Err(anyhow!(
+            "{} already exists\nuse --force to replace it",
+            path.display()
+        ));
+    }
+
+    let contents = commented_default()?;
+    fs::write(&path, contents).with_context(|| format!("writing {}", path.display()))?;
+
+    println!("wrote the default config to {}", path.display());
+    Ok(())
+}
+
+fn commented_default() -> Result<String> {
+    let series_dir =
+        ron::to_string(&Config::default().series_dir).context("encoding series directory")?;
+
+    Ok(COMMENTED_DEFAULT.replace("{series_dir}", &series_dir))
+}
+
+fn get(name: &str) -> Result<()> {
+    let config = Config::load_or_create()?;
+    let options = serde_json::to_value(&config).context("encoding config")?;
+
+    let option = name
+        .split('.')
+        .try_fold(&options, |option, part| option.get(part))
+        .ok_or_else(|| anyhow!("unknown option: {}", name))?;
+
+    match option {
+        Value::String(value) => println!("{}", value),
+        Value::Null => println!("none"),
+        option => println!("{}", serde_json::to_string_pretty(option)?),
+    }
+
+    Ok(())
+}
+
+fn set(name: &str, value: &str) -> Result<()> {
+    let config = Config::load_or_create()?;
+    let mut options = serde_json::to_value(&config).context("encoding config")?;
+
+    let option = name
+        .split('.')
+        .try_fold(&mut options, |option, part| option.get_mut(part))
+        .ok_or_else(|| anyhow!("unknown option: {}", name))?;
+
+    *option = parse_value(option, value);
+
+    let new_config: Config =
+        serde_json::from_value(options).with_context(|| format!("invalid value for {}", name))?;
+
+    // Problems that were already in the config shouldn't stop other options from being changed
+    let existing = config.problems();
+
+    let new_problems = new_config
+        .problems()
+        .into_iter()
+        .filter(|problem| !existing.contains(problem))
+        .collect::<Vec<_>>();
+
+    if !new_problems.is_empty() {
+        return Err(anyhow!(
+            "{} was not changed:\n{}",
+            name,
+            new_problems.join("\n")
+        ));
+    }
+
+    new_config.save().context("saving config")?;
+    println!("{} set to {}", name, value);
+
+    Ok(())
+}
+
+/// Parse `value` as the new value of the option that is currently set to `current`.
+///
+/// Values are parsed as JSON, except for options that hold text so they don't need to be quoted.
+/// Anything that isn't valid JSON is also treated as text, so paths can be given to options that aren't set yet.
+/// A value of `none` unsets optional options.
+fn parse_value(current: &Value, value: &str) -> Value {
+    match current {
+        _ if value.eq_ignore_ascii_case("none") => Value::Null,
+        Value::String(_) => Value::String(value.into()),
+        _ => serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.into())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the name of every option in `value`, with sections separated by dots.
+    fn option_names(value: &Value, prefix: &str, names: &mut Vec<String>) {
+        let options = match value {
+            Value::Object(options) => options,
+            _ => return names.push(prefix.into()),
+        };
+
+        for (name, value) in options {
+            let name = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{}.{}", prefix, name)
+            };
+
+            option_names(value, &name, names);
+        }
+    }
+
+    #[test]
+    fn commented_default_matches_default() {
+        let contents = commented_default().unwrap();
+        let parsed: Config = ron::from_str(&contents).unwrap();
+
+        let expected = serde_json::to_value(Config::default()).unwrap();
+        let parsed = serde_json::to_value(&parsed).unwrap();
+
+        assert_eq!(parsed, expected);
+
+        // Options left out of the file would still be set to their default, so each one has to be looked for by name
+        let mut names = Vec::new();
+        option_names(&expected, "", &mut names);
+
+        for name in names {
+            let field = name.rsplit('.').next().unwrap();
+
+            assert!(
+                contents.contains(&format!("{}: ", field)),
+                "{} is missing",
+                name
+            );
+        }
+    }
+}
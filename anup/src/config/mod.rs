@@ -0,0 +1,924 @@
+pub mod command;
+
+use crate::{
+    file::{FileFormat, SaveDir, SerializedFile},
+    key::{Key, KeySequence},
+    tui::{locale::Language, theme::Palette},
+};
+use anime::remote::{ScoreRounding, SeriesDate};
+use anyhow::Result;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{Local, NaiveDate, TimeZone};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::ser::Serializer;
+use serde::{
+    de::{self, Deserializer, Visitor},
+    Deserialize, Serialize,
+};
+use std::borrow::Cow;
+use std::convert::TryInto;
+use std::env;
+use std::ops::Mul;
+use std::path::{Path, PathBuf};
+use std::result;
+
+/// Keys the TUI uses for actions that can't be rebound.
+const RESERVED_KEYS: [KeyCode; 24] = [
+    KeyCode::Char('q'),
+    KeyCode::Char('a'),
+    KeyCode::Char('e'),
+    KeyCode::Char('D'),
+    KeyCode::Char('u'),
+    KeyCode::Char('U'),
+    KeyCode::Char('s'),
+    KeyCode::Char('t'),
+    KeyCode::Char('h'),
+    KeyCode::Char('x'),
+    KeyCode::Char('o'),
+    KeyCode::Char('b'),
+    KeyCode::Char('n'),
+    KeyCode::Char('L'),
+    KeyCode::Char('S'),
+    KeyCode::Char('p'),
+    KeyCode::Char('R'),
+    KeyCode::Char('A'),
+    KeyCode::Char('C'),
+    KeyCode::Char('G'),
+    KeyCode::Char('V'),
+    KeyCode::Char(':'),
+    KeyCode::Up,
+    KeyCode::Down,
+];
+
+#[derive(Deserialize, Serialize)]
+pub struct Config {
+    pub series_dir: PathBuf,
+    pub reset_dates_on_rewatch: bool,
+    /// Only use the network to read and write list entries, unless something else is asked for directly.
+    #[serde(default)]
+    pub low_data: bool,
+    #[serde(default)]
+    pub score_rounding: ScoreRounding,
+    #[serde(default)]
+    pub dates: DatesConfig,
+    pub episode: EpisodeConfig,
+    pub tui: TuiConfig,
+    #[serde(default)]
+    pub backup: BackupConfig,
+    #[serde(default)]
+    pub matching: MatchingConfig,
+    #[serde(default)]
+    pub trash: TrashConfig,
+    #[serde(default)]
+    pub torrent: TorrentConfig,
+    #[serde(default)]
+    pub serve: ServeConfig,
+    #[serde(default)]
+    pub sync: SyncConfig,
+    #[serde(default)]
+    pub goal: GoalConfig,
+    #[serde(default)]
+    pub updates: UpdateConfig,
+    #[serde(default)]
+    pub digest: DigestConfig,
+}
+
+impl Config {
+    pub fn new<P>(series_dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self {
+            series_dir: series_dir.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Load the config and print every problem found with it.
+    pub fn load_and_report() -> Result<Self> {
+        let config = Self::load_or_create()?;
+
+        for problem in config.problems() {
+            eprintln!("config problem: {}", problem);
+        }
+
+        Ok(config)
+    }
+
+    /// Returns the logins and tokens in the config, which shouldn't leave the machine unless asked to.
+    ///
+    /// These are the login of the torrent client, the token of `anup serve`, and the logins used to send digests.
+    pub fn secrets_mut(&mut self) -> [&mut Option<String>; 6] {
+        [
+            &mut self.torrent.username,
+            &mut self.torrent.password,
+            &mut self.serve.token,
+            &mut self.digest.ntfy_token,
+            &mut self.digest.smtp.username,
+            &mut self.digest.smtp.password,
+        ]
+    }
+
+    /// Returns every problem with the config that would otherwise only cause an error once the option is used.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if !self.series_dir.is_dir() {
+            problems.push(format!(
+                "series_dir is not a directory: {}",
+                self.series_dir.display()
+            ));
+        }
+
+        if self.episode.player.is_empty() {
+            problems.push("episode.player is empty".into());
+        } else if !program_exists(&self.episode.player) {
+            problems.push(format!(
+                "episode.player was not found in PATH: {}",
+                self.episode.player
+            ));
+        }
+
+        let percentages = [
+            (
+                "episode.percent_watched_to_progress",
+                self.episode.pcnt_must_watch,
+            ),
+            ("matching.title_confidence", self.matching.title_confidence),
+            ("matching.path_confidence", self.matching.path_confidence),
+        ];
+
+        for (name, pcnt) in &percentages {
+            if !pcnt.is_valid() {
+                problems.push(format!(
+                    "{} must be between 0 and 100: {}",
+                    name,
+                    pcnt.as_multiplier() * 100.0
+                ));
+            }
+        }
+
+        let smtp = &self.digest.smtp;
+
+        if smtp.url.is_some() && (smtp.from.is_none() || smtp.to.is_empty()) {
+            problems.push("digest.smtp needs both from and to to send email".into());
+        }
+
+        if smtp.url.is_some() && !program_exists("curl") {
+            problems.push("digest.smtp needs curl, which was not found in PATH".into());
+        }
+
+        let formats = [
+            ("dates.date_format", &self.dates.date_format),
+            ("dates.time_format", &self.dates.time_format),
+        ];
+
+        for (name, format) in &formats {
+            if !is_valid_format(format) {
+                problems.push(format!("{} is not a valid format: {}", name, format));
+            }
+        }
+
+        let keys = self.tui.keys.named();
+        let is_reserved = |key: &Key| {
+            RESERVED_KEYS
+                .iter()
+                .any(|&code| *key == Key::from_code(code))
+        };
+
+        for (i, (name, key)) in keys.iter().enumerate() {
+            if is_reserved(key) {
+                problems.push(format!(
+                    "tui.keys.{} uses a key that is already used by the program",
+                    name
+                ));
+            }
+
+            let duplicate = keys[i + 1..].iter().find(|(_, other)| other == key);
+
+            if let Some((other_name, _)) = duplicate {
+                problems.push(format!(
+                    "tui.keys.{} and tui.keys.{} use the same key",
+                    name, other_name
+                ));
+            }
+        }
+
+        let chords = &self.tui.chords.bindings;
+
+        for (i, chord) in chords.iter().enumerate() {
+            let first = chord.keys.keys()[0];
+
+            if is_reserved(&first) || keys.iter().any(|(_, key)| *key == first) {
+                problems.push(format!(
+                    "the tui.chords binding for {} starts with a key that is already used",
+                    chord.keys
+                ));
+            }
+
+            // A chord that starts another one would always run before the longer one could be finished
+            for other in &chords[i + 1..] {
+                if chord.keys.starts_with(other.keys.keys())
+                    || other.keys.starts_with(chord.keys.keys())
+                {
+                    problems.push(format!(
+                        "the tui.chords bindings for {} and {} conflict",
+                        chord.keys, other.keys
+                    ));
+                }
+            }
+        }
+
+        problems
+    }
+}
+
+/// Returns true if `program` is the path to a file, or the name of a file in one of the directories in `PATH`.
+fn program_exists(program: &str) -> bool {
+    let path = Path::new(program);
+
+    if path.components().count() > 1 {
+        return path.is_file();
+    }
+
+    env::var_os("PATH").map_or(false, |paths| {
+        env::split_paths(&paths).any(|dir| dir.join(path).is_file())
+    })
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        // Default series dir is ~/anime/
+        let mut series_dir = dirs_next::home_dir().unwrap_or_else(|| PathBuf::from("~/"));
+        series_dir.push("anime");
+
+        Self {
+            series_dir,
+            reset_dates_on_rewatch: false,
+            low_data: false,
+            score_rounding: ScoreRounding::default(),
+            dates: DatesConfig::default(),
+            episode: EpisodeConfig::default(),
+            tui: TuiConfig::default(),
+            backup: BackupConfig::default(),
+            matching: MatchingConfig::default(),
+            trash: TrashConfig::default(),
+            torrent: TorrentConfig::default(),
+            serve: ServeConfig::default(),
+            sync: SyncConfig::default(),
+            goal: GoalConfig::default(),
+            updates: UpdateConfig::default(),
+            digest: DigestConfig::default(),
+        }
+    }
+}
+
+impl SerializedFile for Config {
+    fn filename() -> &'static str {
+        "config"
+    }
+
+    fn save_dir() -> SaveDir {
+        SaveDir::Config
+    }
+
+    fn format() -> FileFormat {
+        FileFormat::Config
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct DatesConfig {
+    /// Set the start and end dates of a series when its status changes.
+    pub auto_set: bool,
+    /// Set the end date of a series when it is dropped.
+    pub set_end_date_on_drop: bool,
+    /// Remove the end date of a series when it is rewatched, until the rewatch is completed.
+    pub clear_end_date_on_rewatch: bool,
+    /// How dates are shown, in the strftime format used by chrono.
+    pub date_format: String,
+    /// How the time of day is shown after a date, in the strftime format used by chrono.
+    pub time_format: String,
+}
+
+impl DatesConfig {
+    const DEFAULT_DATE_FORMAT: &'static str = "%Y-%m-%d";
+    const DEFAULT_TIME_FORMAT: &'static str = "%H:%M";
+
+    /// Format `date` with `date_format`, or return `??` if there isn't one.
+    pub fn format_date(&self, date: Option<SeriesDate>) -> Cow<'static, str> {
+        let date = date.and_then(|date| {
+            NaiveDate::from_ymd_opt(date.year.into(), date.month.into(), date.day.into())
+        });
+
+        match date {
+            Some(date) => date.format(self.date_format()).to_string().into(),
+            None => "??".into(),
+        }
+    }
+
+    /// Format the UNIX `timestamp` in the local time zone with `date_format` followed by `time_format`.
+    pub fn format_timestamp(&self, timestamp: i64) -> String {
+        let time = Local.timestamp(timestamp, 0);
+
+        format!(
+            "{} {}",
+            time.format(self.date_format()),
+            time.format(self.time_format())
+        )
+    }
+
+    /// Format the date of the UNIX `timestamp` in the local time zone with `date_format`.
+    pub fn format_timestamp_date(&self, timestamp: i64) -> String {
+        Local
+            .timestamp(timestamp, 0)
+            .format(self.date_format())
+            .to_string()
+    }
+
+    fn date_format(&self) -> &str {
+        valid_format_or(&self.date_format, Self::DEFAULT_DATE_FORMAT)
+    }
+
+    fn time_format(&self) -> &str {
+        valid_format_or(&self.time_format, Self::DEFAULT_TIME_FORMAT)
+    }
+}
+
+impl Default for DatesConfig {
+    fn default() -> Self {
+        Self {
+            auto_set: true,
+            set_end_date_on_drop: true,
+            clear_end_date_on_rewatch: false,
+            date_format: Self::DEFAULT_DATE_FORMAT.into(),
+            time_format: Self::DEFAULT_TIME_FORMAT.into(),
+        }
+    }
+}
+
+/// Returns true if `format` only uses specifiers chrono knows of.
+fn is_valid_format(format: &str) -> bool {
+    !StrftimeItems::new(format).any(|item| matches!(item, Item::Error))
+}
+
+/// Returns `format` if it's valid, or `default` otherwise.
+///
+/// Invalid formats are reported as a problem with the config, but still need to be replaced since chrono panics when
+/// one is displayed.
+fn valid_format_or<'a>(format: &'a str, default: &'a str) -> &'a str {
+    if is_valid_format(format) {
+        format
+    } else {
+        default
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct EpisodeConfig {
+    #[serde(rename = "percent_watched_to_progress")]
+    pub pcnt_must_watch: Percentage,
+    pub player: String,
+    pub player_args: Vec<String>,
+    /// Set the progress of newly added series from episodes found in a `watched` subdirectory.
+    pub import_progress_on_add: bool,
+    /// The number of hours to wait after an episode is watched before removing it from series that have `removewatched` turned on.
+    pub remove_watched_after_hours: u32,
+    /// Where to move watched episodes to instead of deleting them.
+    pub archive_dir: Option<PathBuf>,
+    /// Count filler episodes as watched instead of playing them when playing the next episode.
+    pub skip_filler: bool,
+    /// Download episodes from series on SFTP servers to the cache before playing them, instead of streaming them.
+    pub download_sftp_episodes: bool,
+    /// What to do with episodes on disk that are numbered past the official episode count of their series.
+    pub extra_episodes: ExtraEpisodes,
+}
+
+impl Default for EpisodeConfig {
+    fn default() -> Self {
+        Self {
+            pcnt_must_watch: Percentage::new(50.0),
+            player: String::from("mpv"),
+            player_args: Vec::new(),
+            import_progress_on_add: true,
+            remove_watched_after_hours: 24,
+            archive_dir: None,
+            skip_filler: false,
+            download_sftp_episodes: false,
+            extra_episodes: ExtraEpisodes::default(),
+        }
+    }
+}
+
+/// What to do with episodes on disk that are numbered past the official episode count of their series, such as the
+/// 13th file of a batch for a series with 12 episodes.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub enum ExtraEpisodes {
+    /// Extra episodes are never played, and finishing the series mentions that they exist.
+    #[default]
+    Warn,
+    /// Extra episodes are played after the last one like specials, without being added to the watch history.
+    Special,
+    /// Extra episodes are played after the last one and count as progress, which is only kept locally since remote
+    /// services don't accept progress past the official count.
+    Local,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct BackupConfig {
+    /// Back up the database, accounts, and config when the TUI is started.
+    pub enabled: bool,
+    /// The minimum number of hours between each backup.
+    pub interval_hours: u32,
+    /// The number of backups to keep before the oldest ones are removed.
+    pub keep: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: 24,
+            keep: 7,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct MatchingConfig {
+    /// How similar a title on the remote service must be to the name of a new series to be picked automatically.
+    /// When no title is similar enough, every search result is listed to pick from instead.
+    pub title_confidence: Percentage,
+    /// How similar a folder in the series directory must be to the name of a new series to be used as its path.
+    pub path_confidence: Percentage,
+}
+
+impl Default for MatchingConfig {
+    fn default() -> Self {
+        Self {
+            title_confidence: Percentage::new(85.0),
+            path_confidence: Percentage::new(60.0),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct TrashConfig {
+    /// The number of days a deleted series can be restored with the `undelete` command before it is removed for good.
+    pub retention_days: u32,
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self { retention_days: 30 }
+    }
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TorrentConfig {
+    /// The address of a qBittorrent Web UI to check for torrents that are still seeding before episode files are removed.
+    pub qbittorrent_url: Option<String>,
+    /// The username to log in to the Web UI with, if it needs one.
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct ServeConfig {
+    /// The address and port that `anup serve` listens on.
+    pub address: String,
+    /// The token clients of `anup serve` have to send, which is generated the first time it runs.
+    pub token: Option<String>,
+}
+
+impl Default for ServeConfig {
+    fn default() -> Self {
+        Self {
+            address: String::from("127.0.0.1:7474"),
+            token: None,
+        }
+    }
+}
+
+/// Which version of each list entry field is kept when an entry without unsynced changes is replaced with the one on
+/// the remote service.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SyncConfig {
+    pub progress: SyncWinner,
+    pub score: SyncWinner,
+    pub status: SyncWinner,
+    /// The start and end dates.
+    pub dates: SyncWinner,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GoalConfig {
+    /// The number of episodes to watch each week, starting on Monday. Set to 0 to not have a goal.
+    pub weekly_episodes: u32,
+    /// Have the daemon send a notification with how the previous week went once a new one starts.
+    pub weekly_summary: bool,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct UpdateConfig {
+    /// Look up the latest release on GitHub when the TUI is started, and show a notice if it's newer.
+    pub check_on_startup: bool,
+}
+
+impl Default for UpdateConfig {
+    fn default() -> Self {
+        Self {
+            check_on_startup: true,
+        }
+    }
+}
+
+/// How `anup digest` and the daemon send a summary of the week. Every method that is set is used.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DigestConfig {
+    /// Have the daemon send a digest of the previous week once a new one starts.
+    pub weekly: bool,
+    /// A command to run with the digest as its standard input, like `["mail", "-s", "anup digest", "me@example.com"]`.
+    pub command: Vec<String>,
+    /// The URL of an ntfy topic to publish the digest to, like `https://ntfy.sh/my-anup-digest`.
+    pub ntfy_url: Option<String>,
+    /// The access token to publish to the ntfy topic with, if it needs one.
+    pub ntfy_token: Option<String>,
+    pub smtp: SmtpConfig,
+}
+
+impl DigestConfig {
+    /// Returns true if at least one way to send the digest is set.
+    pub fn can_send(&self) -> bool {
+        !self.command.is_empty() || self.ntfy_url.is_some() || self.smtp.url.is_some()
+    }
+}
+
+/// The SMTP server to email the digest with, which is sent through curl.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SmtpConfig {
+    /// The address of the server, like `smtps://smtp.example.com:465`. The connection always has to be encrypted.
+    pub url: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// The address the digest is sent from.
+    pub from: Option<String>,
+    /// The addresses the digest is sent to.
+    pub to: Vec<String>,
+}
+
+/// Whether the local or remote value of a list entry field wins when the entry is synced from the remote service.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub enum SyncWinner {
+    #[default]
+    Remote,
+    /// The local value is kept, and is sent to the remote service with the next sync if it differs.
+    Local,
+}
+
+#[derive(Copy, Clone, Deserialize, Serialize)]
+pub struct Percentage(#[serde(with = "Percentage")] f32);
+
+impl Percentage {
+    pub fn new(value: f32) -> Self {
+        Self(value / 100.0)
+    }
+
+    fn deserialize<'de, D>(de: D) -> result::Result<f32, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use std::fmt;
+
+        struct PercentageVisitor;
+
+        impl<'de> Visitor<'de> for PercentageVisitor {
+            type Value = f32;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a positive percentage number")
+            }
+
+            fn visit_f32<E>(self, value: f32) -> result::Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                if value.is_sign_negative() {
+                    return Err(E::custom(format!(
+                        "percentage must be greater than 0: {}",
+                        value
+                    )));
+                }
+
+                Ok(value / 100.0)
+            }
+
+            fn visit_f64<E>(self, value: f64) -> result::Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                if value.is_sign_negative() {
+                    return Err(E::custom(format!(
+                        "percentage must be greater than 0: {}",
+                        value
+                    )));
+                }
+
+                Ok(value as f32 / 100.0)
+            }
+
+            fn visit_i64<E>(self, value: i64) -> result::Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                self.visit_f64(value as f64)
+            }
+
+            fn visit_u64<E>(self, value: u64) -> result::Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                self.visit_f64(value as f64)
+            }
+        }
+
+        de.deserialize_f32(PercentageVisitor)
+    }
+
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    fn serialize<S>(value: &f32, ser: S) -> result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Rounded to avoid writing values like 60.000004 from the precision lost by storing them as a multiplier
+        ser.serialize_f32((value * 10_000.0).round() / 100.0)
+    }
+
+    #[inline(always)]
+    pub fn as_multiplier(self) -> f32 {
+        self.0
+    }
+
+    /// Returns true if the percentage is between 0 and 100.
+    pub fn is_valid(self) -> bool {
+        (0.0..=1.0).contains(&self.0)
+    }
+}
+
+impl Mul<Percentage> for f32 {
+    type Output = f32;
+
+    fn mul(self, other: Percentage) -> Self::Output {
+        self * other.as_multiplier()
+    }
+}
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct TuiConfig {
+    pub keys: TuiKeys,
+    /// Ask for a score as soon as a series is completed.
+    #[serde(default)]
+    pub score_on_complete: bool,
+    /// The language the TUI is shown in. When unset, it is detected from the `LANG` environment variable.
+    #[serde(default)]
+    pub language: Option<Language>,
+    /// The colors the TUI is drawn with.
+    #[serde(default)]
+    pub palette: Palette,
+    /// Draw borders and symbols with unicode characters. When unset, it is detected from the locale and `TERM`.
+    #[serde(default)]
+    pub unicode: Option<bool>,
+    /// How the progress of each series is shown next to its name in the series list.
+    #[serde(default)]
+    pub progress_column: ProgressColumn,
+    /// Group the series list by franchise when the TUI starts.
+    #[serde(default)]
+    pub group_franchises: bool,
+    /// How many items the TUI keeps at once.
+    #[serde(default)]
+    pub limits: TuiLimits,
+    /// Sequences of keys that run an action once every key has been pressed.
+    #[serde(default)]
+    pub chords: ChordConfig,
+}
+
+/// How many items the TUI keeps at once, so it doesn't use more memory the longer it's left open.
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct TuiLimits {
+    /// The number of messages kept in the log. The oldest ones are removed as new ones are added.
+    pub log_messages: u8,
+    /// The number of playback sessions shown in the history panel.
+    pub history_sessions: u16,
+    /// The number of notifications fetched when the notifications panel is opened.
+    pub notifications: u8,
+    /// The number of finished background tasks kept, so their results can still be viewed.
+    pub finished_tasks: u8,
+}
+
+impl Default for TuiLimits {
+    fn default() -> Self {
+        Self {
+            log_messages: 15,
+            history_sessions: 100,
+            notifications: 50,
+            finished_tasks: 20,
+        }
+    }
+}
+
+/// How the progress of each series is shown next to its name in the series list.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub enum ProgressColumn {
+    /// Progress isn't shown.
+    #[default]
+    #[serde(alias = "none")]
+    None,
+    /// The number of watched episodes out of the total, like 8/12.
+    #[serde(alias = "count")]
+    Count,
+    /// A small bar that fills up as episodes are watched.
+    #[serde(alias = "bar")]
+    Bar,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct TuiKeys {
+    pub play_next_episode: Key,
+    /// Mark the episode being played (or the next one) as watched, regardless of how long it has been played for.
+    pub force_complete_episode: Key,
+    /// Close the player of the selected series without recording the episode being played.
+    pub stop_player: Key,
+    /// Hide scores and statuses and stop the list from changing, such as while sharing the screen. Pressing it again
+    /// lifts the lock.
+    pub presentation_lock: Key,
+    /// Open a search to jump to any series by part of its nickname, title, or one of its aliases.
+    pub quick_switch: Key,
+}
+
+impl TuiKeys {
+    /// Returns every key along with the name of its field.
+    fn named(&self) -> [(&'static str, Key); 5] {
+        [
+            ("play_next_episode", self.play_next_episode),
+            ("force_complete_episode", self.force_complete_episode),
+            ("stop_player", self.stop_player),
+            ("presentation_lock", self.presentation_lock),
+            ("quick_switch", self.quick_switch),
+        ]
+    }
+}
+
+impl Default for TuiKeys {
+    fn default() -> TuiKeys {
+        TuiKeys {
+            play_next_episode: Key::from_code(KeyCode::Enter),
+            force_complete_episode: Key::from_code(KeyCode::Char('w')),
+            stop_player: Key::from_code(KeyCode::Char('k')),
+            presentation_lock: Key::new(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL)),
+            quick_switch: Key::new(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL)),
+        }
+    }
+}
+
+/// Sequences of keys that run an action, like pressing `g` and then `s` to open the statistics panel.
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct ChordConfig {
+    /// The number of milliseconds to wait for the next key of a chord before it's cancelled.
+    pub timeout_ms: u32,
+    pub bindings: Vec<KeyChord>,
+}
+
+impl ChordConfig {
+    /// Returns every binding whose keys start with `keys`.
+    pub fn starting_with<'a>(&'a self, keys: &'a [Key]) -> impl Iterator<Item = &'a KeyChord> {
+        self.bindings
+            .iter()
+            .filter(move |binding| binding.keys.starts_with(keys))
+    }
+
+    /// Returns the binding with exactly the specified `keys`.
+    pub fn find(&self, keys: &[Key]) -> Option<&KeyChord> {
+        self.bindings
+            .iter()
+            .find(|binding| binding.keys.keys() == keys)
+    }
+}
+
+impl Default for ChordConfig {
+    fn default() -> Self {
+        let binding = |keys: &str, action| KeyChord {
+            keys: keys.try_into().unwrap(),
+            action,
+        };
+
+        Self {
+            timeout_ms: 1000,
+            bindings: vec![
+                binding("g s", ChordAction::Stats),
+                binding("g h", ChordAction::History),
+                binding("g t", ChordAction::Tasks),
+                binding("g n", ChordAction::Notifications),
+                binding("g l", ChordAction::Events),
+                binding("g a", ChordAction::AuditLog),
+                binding("g p", ChordAction::Profile),
+                binding("g u", ChordAction::Users),
+                binding("g r", ChordAction::Command("pullprogress".into())),
+            ],
+        }
+    }
+}
+
+/// A sequence of keys and the action it runs.
+#[derive(Deserialize, Serialize)]
+pub struct KeyChord {
+    pub keys: KeySequence,
+    pub action: ChordAction,
+}
+
+/// Something a key chord can do.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum ChordAction {
+    Stats,
+    History,
+    Tasks,
+    Notifications,
+    Events,
+    AuditLog,
+    Profile,
+    Users,
+    AddSeries,
+    EditSeries,
+    DeleteSeries,
+    SplitSeries,
+    RewatchFilter,
+    AiringFilter,
+    NextCollection,
+    /// Run a command the same way as entering it in the command prompt, such as `Command("rate +5")`.
+    Command(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_problems() {
+        let mut config = Config::new(env::temp_dir());
+        config.episode.pcnt_must_watch = Percentage::new(150.0);
+        config.tui.keys.force_complete_episode = Key::from_code(KeyCode::Char('q'));
+        config.tui.keys.stop_player = config.tui.keys.play_next_episode;
+        config.dates.date_format = "%Y-%!".into();
+        assert!(!config
+            .problems()
+            .iter()
+            .any(|problem| problem.contains("chords")));
+
+        let chord = |keys: &str| KeyChord {
+            keys: keys.try_into().unwrap(),
+            action: ChordAction::Stats,
+        };
+
+        config.tui.chords.bindings.push(chord("q x"));
+        config.tui.chords.bindings.push(chord("g"));
+
+        let problems = config.problems();
+        let has_problem = |text: &str| problems.iter().any(|problem| problem.contains(text));
+
+        assert!(!has_problem("series_dir"));
+        assert!(has_problem(
+            "percent_watched_to_progress must be between 0 and 100"
+        ));
+        assert!(!has_problem("title_confidence"));
+        assert!(has_problem(
+            "force_complete_episode uses a key that is already used"
+        ));
+        assert!(has_problem(
+            "play_next_episode and tui.keys.stop_player use the same key"
+        ));
+        assert!(has_problem(
+            "binding for q x starts with a key that is already used"
+        ));
+        assert!(has_problem("bindings for g s and g conflict"));
+        assert!(has_problem("dates.date_format is not a valid format"));
+        assert!(!has_problem("dates.time_format"));
+
+        // The default format is used in place of an invalid one
+        let date = SeriesDate::from_ymd(2021, 3, 15);
+        assert_eq!(config.dates.format_date(Some(date)), "2021-03-15");
+    }
+}
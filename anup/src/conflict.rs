@@ -0,0 +1,501 @@
+use crate::database::Database;
+use crate::err::Failure;
+use crate::file::SaveDir;
+use crate::{confirm, open_database, Args};
+use anime::remote::Status;
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Integer, Nullable, SmallInt, Text};
+use std::env;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::SystemTime;
+
+/// The name a conflicting copy of the database is attached to the database as.
+const CONFLICT: &str = "conflict";
+
+/// Series that are only in the conflicting copy, and can be added without replacing one that already has been.
+const NEW_SERIES: &str = "SELECT c.id FROM conflict.series_configs c
+    WHERE NOT EXISTS (SELECT 1 FROM main.series_configs m WHERE m.id = c.id OR m.nickname = c.nickname)
+    AND c.id NOT IN (SELECT id FROM main.deleted_series)";
+
+/// Episodes in the watch history of the conflicting copy that aren't in the database, for series that are or will be added.
+const NEW_HISTORY: &str = "FROM conflict.watch_history c
+    WHERE NOT EXISTS (
+        SELECT 1 FROM main.watch_history m
+        WHERE m.series_id = c.series_id AND m.episode = c.episode AND m.watched_at = c.watched_at
+    )
+    AND (c.series_id IN (SELECT id FROM main.series_configs) OR c.series_id IN temp.new_series)";
+
+#[derive(FromArgs)]
+/// Review and merge conflicting copies of the database made by sync programs like Syncthing.
+#[argh(subcommand, name = "merge-conflicts")]
+pub struct MergeConflictsArgs {
+    /// show what would be merged without changing anything
+    #[argh(switch)]
+    dry_run: bool,
+
+    /// don't ask for confirmation before merging each copy
+    #[argh(switch, short = 'y')]
+    yes: bool,
+}
+
+pub fn run(args: &Args, merge_args: &MergeConflictsArgs) -> Result<()> {
+    let paths = SyncConflict::find_all().context("looking for conflicting copies")?;
+
+    if paths.is_empty() {
+        return Err(Failure::NothingToDo.error("no conflicting copies of the database were found"));
+    }
+
+    let db = open_database(args)?;
+    let mut merged = 0;
+
+    for path in paths {
+        let conflict = SyncConflict::load(&db, path)?;
+
+        println!("{}", conflict.path.display());
+
+        for change in &conflict.changes {
+            println!("  {}", change);
+        }
+
+        println!("  {}", conflict);
+
+        if merge_args.dry_run || (!merge_args.yes && !confirm("merge this copy?")?) {
+            continue;
+        }
+
+        conflict.merge(&db)?;
+        merged += 1;
+    }
+
+    if !merge_args.dry_run {
+        println!("merged {} conflicting copies", merged);
+    }
+
+    Ok(())
+}
+
+/// A copy of the database that a sync program like Syncthing made because it was changed on two machines at once.
+///
+/// Merging it keeps whichever version of each list entry was watched more recently, adds series that were only added
+/// on the other machine, and adds the episodes that are missing from the watch history.
+pub struct SyncConflict {
+    pub path: PathBuf,
+    /// Every list entry that differs between the database and the copy.
+    pub changes: Vec<EntryChange>,
+    /// The number of series that are only in the copy.
+    pub new_series: i64,
+    /// The number of watched episodes that are only in the copy.
+    pub new_history: i64,
+}
+
+impl SyncConflict {
+    /// Returns the path of every conflicting copy of the database, from oldest to newest.
+    ///
+    /// Syncthing names them like `data.sync-conflict-20210401-120000-ABCDEFG.sqlite`.
+    pub fn find_all() -> Result<Vec<PathBuf>> {
+        let dir = SaveDir::LocalData.validated_dir_path()?;
+
+        let mut paths = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .map_or(false, is_conflict)
+            })
+            .collect::<Vec<_>>();
+
+        paths.sort_unstable();
+        Ok(paths)
+    }
+
+    /// Compare the copy at `path` with `db`.
+    pub fn load(db: &Database, path: PathBuf) -> Result<Self> {
+        let migrated = prepare(db, &path)?;
+        let result = Self::compare(db, path);
+        detach(db, migrated.as_deref())?;
+        result
+    }
+
+    fn compare(db: &Database, path: PathBuf) -> Result<Self> {
+        let conn = db.conn();
+
+        // When timestamps can't tell the versions apart, the copy that was written to last is assumed to be newer
+        let copy_is_newer = modified(&path) > modified(&Database::validated_path()?);
+
+        let changes = diesel::sql_query(
+            "SELECT c.id, c.account_id,
+                COALESCE(mi.title_preferred, ci.title_preferred, 'ID ' || c.id) AS title,
+                COALESCE(mi.episodes, ci.episodes, 0) AS episodes,
+                m.watched_episodes AS local_watched, m.status AS local_status,
+                (SELECT MAX(watched_at) FROM main.watch_history h WHERE h.series_id = c.id) AS local_watched_at,
+                c.watched_episodes AS other_watched, c.status AS other_status,
+                (SELECT MAX(watched_at) FROM conflict.watch_history h WHERE h.series_id = c.id) AS other_watched_at
+            FROM conflict.series_entries c
+            LEFT JOIN main.series_entries m ON m.id = c.id AND m.account_id = c.account_id
+            LEFT JOIN main.series_info mi ON mi.id = c.id
+            LEFT JOIN conflict.series_info ci ON ci.id = c.id
+            WHERE (c.id IN (SELECT id FROM main.series_configs) OR c.id IN temp.new_series)
+            AND (m.id IS NULL
+                OR m.watched_episodes != c.watched_episodes OR m.status != c.status OR m.score IS NOT c.score
+                OR m.times_rewatched != c.times_rewatched OR m.priority != c.priority
+                OR m.start_date IS NOT c.start_date OR m.end_date IS NOT c.end_date)
+            ORDER BY title",
+        )
+        .load::<ChangeRow>(conn)
+        .context("comparing list entries")?
+        .into_iter()
+        .map(|row| EntryChange::new(row, copy_is_newer))
+        .collect();
+
+        let new_series = count(conn, "SELECT COUNT(*) AS count FROM temp.new_series")?;
+        let new_history = count(conn, &format!("SELECT COUNT(*) AS count {}", NEW_HISTORY))?;
+
+        Ok(Self {
+            path,
+            changes,
+            new_series,
+            new_history,
+        })
+    }
+
+    /// Merge the copy into `db`, and rename it so it isn't found again.
+    ///
+    /// List entries taken from the copy are marked as needing a sync, so the remote service ends up with them as well.
+    pub fn merge(&self, db: &Database) -> Result<()> {
+        let migrated = prepare(db, &self.path)?;
+        let result = self.merge_attached(db);
+        detach(db, migrated.as_deref())?;
+        result?;
+
+        let mut merged = self.path.clone().into_os_string();
+        merged.push(".merged");
+
+        fs::rename(&self.path, &merged).with_context(|| format!("renaming {}", self.path.display()))
+    }
+
+    fn merge_attached(&self, db: &Database) -> Result<()> {
+        let conn = db.conn();
+
+        db.write_transaction(|| {
+            conn.batch_execute(&format!(
+                "INSERT INTO main.series_configs SELECT * FROM conflict.series_configs WHERE id IN temp.new_series;
+                INSERT OR IGNORE INTO main.series_info SELECT * FROM conflict.series_info WHERE id IN temp.new_series;
+                INSERT OR IGNORE INTO main.series_aliases
+                    SELECT * FROM conflict.series_aliases WHERE series_id IN temp.new_series;
+                INSERT OR IGNORE INTO main.series_collections
+                    SELECT * FROM conflict.series_collections WHERE series_id IN temp.new_series;
+                INSERT OR IGNORE INTO main.filler_episodes
+                    SELECT * FROM conflict.filler_episodes WHERE series_id IN temp.new_series;
+                INSERT OR IGNORE INTO main.episode_overrides
+                    SELECT * FROM conflict.episode_overrides WHERE series_id IN temp.new_series;
+                INSERT OR IGNORE INTO main.episode_titles
+                    SELECT * FROM conflict.episode_titles WHERE series_id IN temp.new_series;
+                INSERT INTO main.watch_history (series_id, episode, minutes, watched_at, rating)
+                    SELECT c.series_id, c.episode, c.minutes, c.watched_at, c.rating {};",
+                NEW_HISTORY
+            ))
+            .context("copying series and watch history")?;
+
+            for change in self.changes.iter().filter(|change| change.take_other) {
+                diesel::sql_query(
+                    "INSERT OR REPLACE INTO main.series_entries
+                        (id, account_id, watched_episodes, score, status, times_rewatched, start_date, end_date,
+                        needs_sync, priority)
+                    SELECT id, account_id, watched_episodes, score, status, times_rewatched, start_date, end_date,
+                        1, priority
+                    FROM conflict.series_entries WHERE id = ? AND account_id = ?",
+                )
+                .bind::<Integer, _>(change.id)
+                .bind::<Text, _>(&change.account_id)
+                .execute(conn)
+                .with_context(|| format!("merging the list entry of {}", change.title))?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl fmt::Display for SyncConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let taken = self
+            .changes
+            .iter()
+            .filter(|change| change.take_other)
+            .count();
+
+        write!(
+            f,
+            "{} of {} differing list entries would be taken from this copy, along with {} series and {} watched episodes",
+            taken,
+            self.changes.len(),
+            self.new_series,
+            self.new_history
+        )
+    }
+}
+
+#[derive(QueryableByName)]
+struct ChangeRow {
+    #[sql_type = "Integer"]
+    id: i32,
+    #[sql_type = "Text"]
+    account_id: String,
+    #[sql_type = "Text"]
+    title: String,
+    #[sql_type = "SmallInt"]
+    episodes: i16,
+    #[sql_type = "Nullable<SmallInt>"]
+    local_watched: Option<i16>,
+    #[sql_type = "Nullable<SmallInt>"]
+    local_status: Option<Status>,
+    #[sql_type = "Nullable<BigInt>"]
+    local_watched_at: Option<i64>,
+    #[sql_type = "SmallInt"]
+    other_watched: i16,
+    #[sql_type = "SmallInt"]
+    other_status: Status,
+    #[sql_type = "Nullable<BigInt>"]
+    other_watched_at: Option<i64>,
+}
+
+/// A list entry that differs between the database and a conflicting copy of it.
+pub struct EntryChange {
+    pub id: i32,
+    pub account_id: String,
+    pub title: String,
+    /// The number of episodes the series has, which is 0 when it isn't known.
+    pub episodes: i16,
+    /// The watched episodes and status of the entry in the database, or `None` if it only exists in the copy.
+    pub local: Option<(i16, Status)>,
+    /// The watched episodes and status of the entry in the copy.
+    pub other: (i16, Status),
+    /// Whether the version in the copy is newer and will replace the one in the database.
+    pub take_other: bool,
+}
+
+impl EntryChange {
+    fn new(row: ChangeRow, copy_is_newer: bool) -> Self {
+        let local = row.local_watched.zip(row.local_status);
+
+        let take_other =
+            local.is_none() || is_newer(row.other_watched_at, row.local_watched_at, copy_is_newer);
+
+        Self {
+            id: row.id,
+            account_id: row.account_id,
+            title: row.title,
+            episodes: row.episodes,
+            local,
+            other: (row.other_watched, row.other_status),
+            take_other,
+        }
+    }
+}
+
+impl fmt::Display for EntryChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let progress = |(watched, status): (i16, Status)| match self.episodes {
+            0 => format!("{}/? {}", watched, status),
+            episodes => format!("{}/{} {}", watched, episodes, status),
+        };
+
+        let local = self.local.map_or_else(|| "missing".into(), progress);
+        let other = progress(self.other);
+
+        if self.take_other {
+            write!(f, "{}: {} -> {} (from the copy)", self.title, local, other)
+        } else {
+            write!(
+                f,
+                "{}: keeping {} over {} (from the copy)",
+                self.title, local, other
+            )
+        }
+    }
+}
+
+/// Returns true if a version of a list entry whose series was last watched at `other` should replace one whose
+/// series was last watched at `local`.
+///
+/// `copy_is_newer` decides when both were last watched at the same time, or neither has been watched.
+fn is_newer(other: Option<i64>, local: Option<i64>, copy_is_newer: bool) -> bool {
+    match other.cmp(&local) {
+        std::cmp::Ordering::Greater => true,
+        std::cmp::Ordering::Less => false,
+        std::cmp::Ordering::Equal => copy_is_newer,
+    }
+}
+
+/// Returns true if `name` is the file name of a conflicting copy of the database made by Syncthing, which can either
+/// be placed before the extension or after it.
+fn is_conflict(name: &str) -> bool {
+    let is_copy = name.starts_with("data.sync-conflict-") && name.ends_with(".sqlite")
+        || name.starts_with("data.sqlite.sync-conflict-");
+
+    // SQLite's own files can be synced as well, but can't be merged on their own
+    let is_sqlite_file = ["-wal", "-shm", "-journal"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix));
+
+    is_copy && !is_sqlite_file && !name.ends_with(".merged")
+}
+
+/// Attach the copy at `path` to `db` and list the series that are only in it.
+///
+/// A copy made by an older version is left as it is, since it may still be synced to the other machine, and a
+/// migrated copy of it is attached instead. Its path is returned so it can be removed with `detach`.
+fn prepare(db: &Database, path: &Path) -> Result<Option<PathBuf>> {
+    let conn = db.conn();
+    attach(conn, path)?;
+
+    let version = Database::user_version(conn, "main").context("getting schema version")?;
+    let copy_version = Database::user_version(conn, CONFLICT).context("getting copy version")?;
+    let mut migrated = None;
+
+    if copy_version != version {
+        if copy_version > version {
+            detach(db, None)?;
+
+            return Err(anyhow!(
+                "{} was made by a newer version of anup\nupdate this machine to merge it",
+                path.display()
+            ));
+        }
+
+        let migrated_path = copy_to_migrate(db, path);
+        detach(db, None)?;
+        let migrated_path = migrated_path?;
+
+        if let Err(err) = Database::open_file(&migrated_path) {
+            fs::remove_file(&migrated_path).ok();
+            return Err(err.context(format!("migrating {}", path.display())));
+        }
+
+        attach(conn, &migrated_path)?;
+        migrated = Some(migrated_path);
+    }
+
+    let found = conn
+        .batch_execute(&format!("CREATE TEMP TABLE new_series AS {}", NEW_SERIES))
+        .context("finding new series");
+
+    if let Err(err) = found {
+        detach(db, migrated.as_deref())?;
+        return Err(err);
+    }
+
+    Ok(migrated)
+}
+
+/// Copy the attached copy at `path` to a new file in the temporary directory that only the current user can read, so
+/// it can be migrated without changing the original.
+fn copy_to_migrate(db: &Database, path: &Path) -> Result<PathBuf> {
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |time| time.subsec_nanos());
+
+    let name = format!("anup-conflict-{}-{}.sqlite", process::id(), nanos);
+    let temp_path = env::temp_dir().join(name);
+
+    // SQLite can copy into an empty file, so it's created here to make sure nothing else was already at the path
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&temp_path)
+        .context("creating copy to migrate")?;
+
+    let copied = db.conn().batch_execute(&format!(
+        "VACUUM {} INTO '{}'",
+        CONFLICT,
+        temp_path.to_string_lossy().replace('\'', "''")
+    ));
+
+    if let Err(err) = copied {
+        fs::remove_file(&temp_path).ok();
+        return Err(err).with_context(|| format!("copying {}", path.display()));
+    }
+
+    Ok(temp_path)
+}
+
+fn attach(conn: &SqliteConnection, path: &Path) -> Result<()> {
+    conn.batch_execute(&format!(
+        "ATTACH DATABASE '{}' AS {}",
+        path.to_string_lossy().replace('\'', "''"),
+        CONFLICT
+    ))
+    .with_context(|| format!("opening {}", path.display()))
+}
+
+/// Detach the copy from `db`, and remove the `migrated` copy that was attached in its place, if any.
+fn detach(db: &Database, migrated: Option<&Path>) -> Result<()> {
+    let detached = db
+        .conn()
+        .batch_execute(&format!(
+            "DROP TABLE IF EXISTS temp.new_series; DETACH DATABASE {}",
+            CONFLICT
+        ))
+        .context("closing conflicting copy");
+
+    if let Some(migrated) = migrated {
+        fs::remove_file(migrated).ok();
+    }
+
+    detached
+}
+
+fn count(conn: &SqliteConnection, query: &str) -> Result<i64> {
+    #[derive(QueryableByName)]
+    struct Count {
+        #[sql_type = "BigInt"]
+        count: i64,
+    }
+
+    let count = diesel::sql_query(query).get_result::<Count>(conn)?.count;
+    Ok(count)
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_conflict_names() {
+        assert!(is_conflict(
+            "data.sync-conflict-20210401-120000-ABCDEFG.sqlite"
+        ));
+        assert!(is_conflict(
+            "data.sqlite.sync-conflict-20210401-120000-ABCDEFG"
+        ));
+        assert!(!is_conflict("data.sqlite"));
+        assert!(!is_conflict("data.sqlite-wal"));
+        assert!(!is_conflict(
+            "data.sync-conflict-20210401-120000-ABCDEFG.sqlite-wal"
+        ));
+        assert!(!is_conflict(
+            "data.sync-conflict-20210401-120000-ABCDEFG.sqlite.merged"
+        ));
+    }
+
+    #[test]
+    fn newest_watch_wins() {
+        assert!(is_newer(Some(200), Some(100), false));
+        assert!(!is_newer(Some(100), Some(200), true));
+        assert!(is_newer(Some(100), None, false));
+        assert!(is_newer(None, None, true));
+        assert!(!is_newer(Some(100), Some(100), false));
+    }
+}
@@ -0,0 +1,209 @@
+use crate::file::SerializedFile;
+use crate::user::Users;
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, NewAead};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use crossterm::terminal;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::env;
+use std::io::{self, Write};
+
+/// The environment variable that can hold the passphrase when a terminal isn't available to prompt for it, such as when using `--play-one` from a hotkey.
+pub const PASSPHRASE_VAR: &str = "ANUP_PASSPHRASE";
+
+/// The bytes every encrypted file starts with.
+const MAGIC: &[u8] = b"ANUPENC1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// The passphrase entered for this session, if the accounts file is encrypted.
+static SESSION_PASSPHRASE: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(FromArgs)]
+/// Encrypt the accounts file with a passphrase.
+#[argh(subcommand, name = "encrypt")]
+pub struct EncryptArgs {}
+
+#[derive(FromArgs)]
+/// Remove the passphrase from the accounts file.
+#[argh(subcommand, name = "decrypt")]
+pub struct DecryptArgs {}
+
+pub fn run_encrypt(_: &EncryptArgs) -> Result<()> {
+    let users = Users::load_or_create().context("loading accounts")?;
+
+    if session_passphrase().is_some() {
+        return Err(anyhow!("the accounts file is already encrypted"));
+    }
+
+    let passphrase = prompt_hidden("New passphrase: ")?;
+
+    if passphrase.is_empty() {
+        return Err(anyhow!("the passphrase cannot be empty"));
+    }
+
+    if prompt_hidden("Confirm passphrase: ")? != passphrase {
+        return Err(anyhow!("the passphrases do not match"));
+    }
+
+    *SESSION_PASSPHRASE.lock() = Some(passphrase);
+    users.save().context("saving encrypted accounts")?;
+
+    println!("the accounts file is now encrypted");
+    Ok(())
+}
+
+pub fn run_decrypt(_: &DecryptArgs) -> Result<()> {
+    let users = Users::load_or_create().context("loading accounts")?;
+
+    if SESSION_PASSPHRASE.lock().take().is_none() {
+        return Err(anyhow!("the accounts file is not encrypted"));
+    }
+
+    users.save().context("saving decrypted accounts")?;
+
+    println!("the accounts file is no longer encrypted");
+    Ok(())
+}
+
+/// Returns the passphrase that files should be encrypted with, if one was entered this session.
+pub fn session_passphrase() -> Option<String> {
+    SESSION_PASSPHRASE.lock().clone()
+}
+
+/// Returns true if `data` was encrypted with `encrypt`.
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Decrypt `data` with the passphrase of the current session.
+///
+/// If a passphrase hasn't been entered yet, it will be read from the `ANUP_PASSPHRASE` environment variable or prompted for.
+/// It will then be remembered for the rest of the session if it was correct.
+pub fn decrypt_for_session(data: &[u8]) -> Result<Vec<u8>> {
+    let mut session = SESSION_PASSPHRASE.lock();
+
+    if let Some(passphrase) = session.as_ref() {
+        return decrypt(data, passphrase);
+    }
+
+    let passphrase = match env::var(PASSPHRASE_VAR) {
+        Ok(passphrase) => passphrase,
+        Err(_) => prompt_hidden("Accounts passphrase: ").with_context(|| {
+            format!(
+                "reading passphrase\nset {} if a terminal isn't available",
+                PASSPHRASE_VAR
+            )
+        })?,
+    };
+
+    let decrypted = decrypt(data, &passphrase)?;
+    *session = Some(passphrase);
+
+    Ok(decrypted)
+}
+
+pub fn encrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let mut salt = [0; SALT_LEN];
+    let mut nonce = [0; NONCE_LEN];
+
+    getrandom::getrandom(&mut salt).context("generating salt")?;
+    getrandom::getrandom(&mut nonce).context("generating nonce")?;
+
+    let cipher = cipher(passphrase, &salt)?;
+
+    let encrypted = cipher
+        .encrypt(Nonce::from_slice(&nonce), data)
+        .map_err(|_| anyhow!("failed to encrypt data"))?;
+
+    let mut output = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + encrypted.len());
+    output.extend_from_slice(MAGIC);
+    output.extend_from_slice(&salt);
+    output.extend_from_slice(&nonce);
+    output.extend_from_slice(&encrypted);
+
+    Ok(output)
+}
+
+pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let header_len = MAGIC.len() + SALT_LEN + NONCE_LEN;
+
+    if !is_encrypted(data) || data.len() < header_len {
+        return Err(anyhow!("data is not encrypted"));
+    }
+
+    let salt = &data[MAGIC.len()..MAGIC.len() + SALT_LEN];
+    let nonce = &data[MAGIC.len() + SALT_LEN..header_len];
+
+    cipher(passphrase, salt)?
+        .decrypt(Nonce::from_slice(nonce), &data[header_len..])
+        .map_err(|_| anyhow!("incorrect passphrase"))
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> Result<ChaCha20Poly1305> {
+    let mut key = [0; 32];
+
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|err| anyhow!("failed to derive key: {}", err))?;
+
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+}
+
+/// Read a line, such as a passphrase or token, from the terminal without echoing it.
+pub fn prompt_hidden(prompt: &str) -> Result<String> {
+    let mut stderr = io::stderr();
+    write!(stderr, "{}", prompt)?;
+    stderr.flush()?;
+
+    terminal::enable_raw_mode().context("failed to enable raw mode")?;
+    let result = read_hidden_line();
+    terminal::disable_raw_mode().context("failed to disable raw mode")?;
+
+    writeln!(stderr)?;
+    result
+}
+
+fn read_hidden_line() -> Result<String> {
+    let mut line = String::new();
+
+    loop {
+        let key = match event::read()? {
+            Event::Key(key) => key,
+            _ => continue,
+        };
+
+        match key.code {
+            KeyCode::Enter => return Ok(line),
+            KeyCode::Esc => return Err(anyhow!("entry cancelled")),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Err(anyhow!("entry cancelled"))
+            }
+            KeyCode::Char(ch) => line.push(ch),
+            KeyCode::Backspace => {
+                line.pop();
+            }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_roundtrip() {
+        let data = b"access token";
+        let encrypted = encrypt(data, "passphrase").unwrap();
+
+        assert!(is_encrypted(&encrypted));
+        assert_eq!(decrypt(&encrypted, "passphrase").unwrap(), data);
+        assert!(decrypt(&encrypted, "wrong").is_err());
+        assert!(decrypt(data, "passphrase").is_err());
+    }
+}
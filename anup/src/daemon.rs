@@ -0,0 +1,647 @@
+use crate::config::Config;
+use crate::crypt;
+use crate::database::Database;
+use crate::digest;
+use crate::err;
+use crate::file::{FileFormat, SaveDir, SerializedFile};
+use crate::goal::{self, GoalSummary, WeeklyGoal};
+use crate::ipc::{self, AdoptedPlayback, Endpoint, Request};
+use crate::private_session::PrivateSession;
+use crate::saved_event::{EventKind, SavedEvent};
+use crate::series::config::SeriesConfig;
+use crate::series::entry::SeriesEntry;
+use crate::series::history::PlaybackTimer;
+use crate::series::info::SeriesInfo;
+use crate::series::sync_failure::SyncFailure;
+use crate::series::{LoadedSeries, Series, SeriesData};
+use crate::util;
+use crate::{init_remote, open_database, Args};
+use anime::remote::anilist::AniList;
+use anime::remote::{AiringEpisode, Remote, RemoteService, Status};
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+use chrono::{Duration as ChronoDuration, Local, Utc};
+use futures::{select, FutureExt};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Write;
+use std::mem;
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::time;
+
+/// How often to check whether the players of adopted episodes have exited.
+const ADOPTED_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often to check whether a daemon that was just started is listening for requests.
+const STARTUP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How many times to check whether a daemon that was just started is listening before giving up on it.
+const STARTUP_ATTEMPTS: u32 = 50;
+
+/// How long a daemon started with `--until-idle` keeps waiting for requests once it has nothing left to do.
+const IDLE_EXIT_DELAY: Duration = Duration::from_secs(30);
+
+#[derive(FromArgs)]
+/// Run in the background to sync changes and send notifications about new episodes.
+#[argh(subcommand, name = "daemon")]
+pub struct DaemonArgs {
+    /// the number of minutes to wait between each check
+    #[argh(option, default = "15")]
+    interval: u64,
+
+    /// only check once and exit
+    #[argh(switch)]
+    once: bool,
+
+    /// exit once every episode handed over by a closed TUI has been played, instead of running until stopped
+    #[argh(switch)]
+    until_idle: bool,
+}
+
+pub async fn run(args: &Args, daemon_args: &DaemonArgs) -> Result<()> {
+    let config = Config::load_and_report().context("loading config")?;
+    let db = open_database(args)?;
+    let state = DaemonState::load_or_create().context("loading daemon state")?;
+
+    let (remote, authenticated) = match init_remote(args, &config)? {
+        Some(remote) => (remote, true),
+        None => (AniList::Unauthenticated.into(), false),
+    };
+
+    let mut daemon = Daemon {
+        config,
+        db,
+        remote,
+        authenticated,
+        state,
+        adopted: Vec::new(),
+        sync_requested: false,
+    };
+
+    if daemon_args.once {
+        return daemon.check();
+    }
+
+    let server = ipc::Server::bind(Endpoint::Daemon)
+        .context("listening for requests")?
+        .ok_or_else(|| anyhow!("another daemon is already running"))?;
+
+    let interval = Duration::from_secs(daemon_args.interval.max(1) * 60);
+    let mut next_check = Instant::now();
+    let mut last_request = Instant::now();
+
+    loop {
+        // A daemon started by the TUI only needs to handle what it was handed, and has to be ready for it right away
+        if !daemon_args.until_idle && Instant::now() >= next_check {
+            daemon.check()?;
+            next_check = Instant::now() + interval;
+        }
+
+        daemon.finish_exited_players();
+
+        if mem::take(&mut daemon.sync_requested) {
+            if let Err(err) = sync_pending_entries(&daemon.config, &daemon.db, &daemon.remote) {
+                eprintln!("failed to sync changes: {:#}", err);
+            }
+        }
+
+        let idle_at = last_request + IDLE_EXIT_DELAY;
+
+        if daemon_args.until_idle && daemon.adopted.is_empty() && Instant::now() >= idle_at {
+            break Ok(());
+        }
+
+        let mut wake_at = if daemon_args.until_idle {
+            idle_at
+        } else {
+            next_check
+        };
+
+        if !daemon.adopted.is_empty() {
+            wake_at = wake_at.min(Instant::now() + ADOPTED_CHECK_INTERVAL);
+        }
+
+        tokio::pin! {
+            let wake = time::sleep_until(wake_at.into()).fuse();
+            let request = server.accept().fuse();
+        }
+
+        select! {
+            () = wake => (),
+            request = request => {
+                last_request = Instant::now();
+
+                // Requests that can't be read are ignored, since there's nobody to report them to
+                if let Ok((request, conn)) = request {
+                    let result = daemon.handle_request(request);
+
+                    if let Err(err) = &result {
+                        eprintln!("failed to handle request: {:#}", err);
+                    }
+
+                    conn.respond(&result).await.ok();
+                }
+            },
+        }
+    }
+}
+
+/// Start a daemon that exits once it has nothing left to do, and wait for it to accept requests.
+///
+/// The daemon gets its own process group, so it keeps running if the terminal is closed.
+pub fn start_until_idle(args: &Args) -> Result<()> {
+    let exe = env::current_exe().context("getting path of the program")?;
+
+    let mut cmd = Command::new(exe);
+    cmd.arg("--profile").arg(&args.profile);
+
+    if args.offline {
+        cmd.arg("--offline");
+    }
+
+    cmd.arg("daemon").arg("--until-idle");
+    cmd.stdin(Stdio::null());
+    cmd.stdout(Stdio::null());
+    cmd.stderr(Stdio::null());
+    cmd.process_group(0);
+
+    // The accounts can't be decrypted without the passphrase, and there's nobody to prompt for it
+    if let Some(passphrase) = crypt::session_passphrase() {
+        cmd.env(crypt::PASSPHRASE_VAR, passphrase);
+    }
+
+    cmd.spawn().context("running daemon")?;
+
+    for _ in 0..STARTUP_ATTEMPTS {
+        thread::sleep(STARTUP_POLL_INTERVAL);
+
+        if ipc::is_listening(Endpoint::Daemon) {
+            return Ok(());
+        }
+    }
+
+    Err(anyhow!("the daemon didn't start listening for requests"))
+}
+
+/// An episode whose player is tracked by the daemon.
+struct Adopted {
+    playback: AdoptedPlayback,
+    timer: PlaybackTimer,
+}
+
+struct Daemon {
+    config: Config,
+    db: Database,
+    remote: Remote,
+    authenticated: bool,
+    state: DaemonState,
+    adopted: Vec<Adopted>,
+    /// Set when a client asked for changes to be synced.
+    sync_requested: bool,
+}
+
+impl Daemon {
+    /// Sync changes, look for new episodes, and save what was seen.
+    fn check(&mut self) -> Result<()> {
+        if self.authenticated {
+            if let Err(err) = sync_pending_entries(&self.config, &self.db, &self.remote) {
+                let message = format!("failed to sync changes: {:#}", err);
+                log_event(&self.db, EventKind::Error, &message);
+            }
+        }
+
+        if let Err(err) = check_series(&self.config, &self.db, &self.remote, &mut self.state) {
+            let message = format!("failed to check series: {:#}", err);
+            log_event(&self.db, EventKind::Error, &message);
+        }
+
+        if self.config.goal.weekly_summary {
+            if let Err(err) = summarize_goal(&self.config, &self.db) {
+                let message = format!("failed to summarize weekly goal: {:#}", err);
+                log_event(&self.db, EventKind::Error, &message);
+            }
+        }
+
+        if self.config.digest.weekly {
+            if let Err(err) = digest::send_weekly(&self.config, &self.db, &self.remote) {
+                let message = format!("failed to send weekly digest: {:#}", err);
+                log_event(&self.db, EventKind::Error, &message);
+            }
+        }
+
+        self.state.save().context("saving daemon state")
+    }
+
+    fn handle_request(&mut self, request: Request) -> Result<()> {
+        match request {
+            Request::AdoptPlayback(playback) => self.adopt(playback),
+            // Syncing can take a while, so it's done after responding
+            Request::Sync if self.authenticated => {
+                self.sync_requested = true;
+                Ok(())
+            }
+            Request::Sync => Err(anyhow!("the daemon isn't logged in")),
+            Request::PlayNext | Request::CompleteEpisode(_, _) | Request::ReloadSeries(_) => {
+                Err(anyhow!("only the TUI can handle {} requests", request))
+            }
+        }
+    }
+
+    fn adopt(&mut self, playback: AdoptedPlayback) -> Result<()> {
+        // The player isn't a child of the daemon, so the only way to tell when it exits is to look it up
+        if !cfg!(target_os = "linux") {
+            return Err(anyhow!("players can only be tracked on Linux"));
+        }
+
+        let timer = PlaybackTimer::adopt(
+            &self.db,
+            playback.series_id,
+            playback.episode,
+            playback.started_at,
+        )
+        .context("taking over active playback")?
+        .ok_or_else(|| anyhow!("episode {} isn't being played", playback.episode))?;
+
+        println!(
+            "tracking episode {} of {}",
+            playback.episode,
+            self.series_title(playback.series_id)
+        );
+
+        self.adopted.push(Adopted { playback, timer });
+        Ok(())
+    }
+
+    fn finish_exited_players(&mut self) {
+        let (exited, running) = mem::take(&mut self.adopted)
+            .into_iter()
+            .partition::<Vec<_>, _>(|adopted| !util::is_process_running(adopted.playback.pid));
+
+        self.adopted = running;
+
+        for adopted in exited {
+            let episode = adopted.playback.episode;
+            let id = adopted.playback.series_id;
+
+            if let Err(err) = self.finish_playback(adopted) {
+                eprintln!(
+                    "failed to finish episode {} of {}: {:#}",
+                    episode,
+                    self.series_title(id),
+                    err
+                );
+            }
+        }
+    }
+
+    /// Record the session of an episode whose player has exited, and count it as watched if it was played for long enough.
+    ///
+    /// Since the player wasn't started by the daemon, its exit code can't be checked.
+    fn finish_playback(&mut self, adopted: Adopted) -> Result<()> {
+        let Adopted { playback, timer } = adopted;
+        let counted = Utc::now().timestamp() >= playback.progress_at;
+
+        timer
+            .finish(&self.db, None, counted)
+            .and_then(|session| session.save(&self.db))
+            .context("saving playback session")?;
+
+        let title = self.series_title(playback.series_id);
+
+        if !counted {
+            println!(
+                "episode {} of {} wasn't played for long enough to count",
+                playback.episode, title
+            );
+
+            return Ok(());
+        }
+
+        // An open TUI has its own copy of the series, so it has to be the one to change it
+        let request = Request::CompleteEpisode(playback.series_id, playback.episode);
+
+        if ipc::send(Endpoint::Tui, request).context("sending episode to the TUI")? {
+            return Ok(());
+        }
+
+        let series_config =
+            SeriesConfig::load_by_id(&self.db, playback.series_id).context("loading series")?;
+
+        let mut series = match Series::load_from_config(series_config, &self.config, &self.db) {
+            LoadedSeries::Complete(series) => series,
+            LoadedSeries::Partial(_, err) => return Err(err.into()),
+            LoadedSeries::None(_, err) => return Err(err),
+        };
+
+        // The episode may have been counted some other way while it was playing
+        if series.data.entry.watched_episodes() + 1 != playback.episode {
+            return Ok(());
+        }
+
+        series
+            .episode_completed(&self.config, &self.db)
+            .context("marking episode as completed")?;
+
+        println!(
+            "counted episode {} of {} as watched",
+            playback.episode, title
+        );
+
+        if self.authenticated {
+            sync_pending_entries(&self.config, &self.db, &self.remote)?;
+        }
+
+        Ok(())
+    }
+
+    fn series_title(&self, id: i32) -> String {
+        SeriesInfo::load(&self.db, id).map_or_else(
+            |_| format!("the series with ID {}", id),
+            |info| info.title_preferred.to_string(),
+        )
+    }
+}
+
+/// Sync every list entry that was changed while offline, except for ones that keep failing to sync.
+fn sync_pending_entries(config: &Config, db: &Database, remote: &Remote) -> Result<()> {
+    // The changes are synced once the private session ends
+    if PrivateSession::is_active() {
+        return Ok(());
+    }
+
+    let entries = SeriesEntry::entries_that_need_sync(db)?;
+    let mut synced = 0;
+
+    for mut entry in entries {
+        let id = entry.id();
+
+        if SyncFailure::load(db, id)?.map_or(false, |failure| failure.should_skip()) {
+            continue;
+        }
+
+        let episodes = SeriesInfo::load(db, id)
+            .ok()
+            .and_then(|info| info.total_episodes());
+
+        let result = entry
+            .sync_to_remote(remote, db, episodes)
+            .and_then(|()| entry.save(db).map(|_| ()).map_err(Into::into));
+
+        match result {
+            Ok(()) => {
+                SyncFailure::clear(db, id)?;
+                synced += 1;
+            }
+            Err(err) => {
+                if err::is_series_missing(&err) && !config.low_data {
+                    suggest_relink(config, db, remote, id);
+                }
+
+                let err = format!("{:#}", err);
+                SyncFailure::record(db, id, &err)?;
+
+                let message = format!("failed to sync list entry with ID {}: {}", id, err);
+                log_event(db, EventKind::Error, &message);
+            }
+        }
+    }
+
+    if synced > 0 {
+        println!("synced {} list entries", synced);
+    }
+
+    Ok(())
+}
+
+/// Print the series that likely replaced the series with the specified `id` on `remote`, if one can be found.
+fn suggest_relink(config: &Config, db: &Database, remote: &Remote, id: i32) {
+    let info = match SeriesInfo::load(db, id) {
+        Ok(info) => info,
+        Err(_) => return,
+    };
+
+    match info.find_replacement(config, remote) {
+        Ok(Some(replacement)) => {
+            let message = format!(
+                "{} no longer exists on AniList, but was likely merged into {}\nrelink it with `relink {}` in the TUI",
+                info.title_preferred, replacement.title_preferred, replacement.id
+            );
+
+            log_event(db, EventKind::Warning, &message);
+        }
+        Ok(None) => (),
+        Err(err) => eprintln!(
+            "failed to search for a replacement for {}: {:#}",
+            info.title_preferred, err
+        ),
+    }
+}
+
+fn check_series(
+    config: &Config,
+    db: &Database,
+    remote: &Remote,
+    state: &mut DaemonState,
+) -> Result<()> {
+    let configs = SeriesConfig::load_all(db).context("loading series configs")?;
+
+    for series_config in configs {
+        let id = series_config.id;
+
+        let mut series = match Series::load_from_config(series_config, config, db) {
+            LoadedSeries::Complete(series) => series,
+            LoadedSeries::Partial(_, _) | LoadedSeries::None(_, _) => continue,
+        };
+
+        state.check_new_files(&series, db);
+
+        if series.data.config.remove_watched {
+            if let Err(err) = series.remove_watched_episodes(config, db) {
+                eprintln!(
+                    "failed to remove watched episodes of {}: {:#}",
+                    series.data.title(),
+                    err
+                );
+            }
+        }
+
+        // Only new files are noticed when saving data, since checking the airing schedule needs a request
+        if series.data.entry.status() != Status::Watching || config.low_data {
+            continue;
+        }
+
+        match remote.next_airing_episode(id as u32) {
+            Ok(next) => state.check_aired(&mut series.data, next, db, remote),
+            Err(err) => eprintln!(
+                "failed to get airing schedule for {}: {}",
+                series.data.title(),
+                err
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+/// What the daemon has already seen, so notifications are only sent once.
+#[derive(Default, Deserialize, Serialize)]
+struct DaemonState {
+    /// Series ID -> highest episode number found on disk.
+    episodes_on_disk: HashMap<i32, u32>,
+    /// Series ID -> the next episode to air when the series was last checked.
+    next_airing: HashMap<i32, AiringEpisode>,
+}
+
+impl DaemonState {
+    fn check_new_files(&mut self, series: &Series, db: &Database) {
+        let highest = series.episodes.highest_episode_number();
+        let previous = self.episodes_on_disk.insert(series.data.info.id, highest);
+
+        // We don't want to notify about every existing episode the first time a series is seen
+        let previous = match previous {
+            Some(previous) => previous,
+            None => return,
+        };
+
+        let watched = series.data.entry.watched_episodes().max(0) as u32;
+
+        if highest > previous && highest > watched && !series.data.config.muted {
+            let mut message = format!(
+                "Episode {} of {} is now available",
+                highest,
+                series.data.title()
+            );
+
+            if let Some(title) = series.data.episode_titles.get(highest) {
+                write!(message, ": {}", title).ok();
+            }
+
+            notify(db, &message);
+        }
+    }
+
+    fn check_aired(
+        &mut self,
+        data: &mut SeriesData,
+        next: Option<AiringEpisode>,
+        db: &Database,
+        remote: &Remote,
+    ) {
+        let id = data.info.id;
+
+        let previous = match next {
+            Some(next) => self.next_airing.insert(id, next),
+            None => self.next_airing.remove(&id),
+        };
+
+        let previous = match previous {
+            Some(previous) => previous,
+            None => return,
+        };
+
+        let aired = previous.airs_at <= Utc::now().timestamp();
+
+        if aired && !data.config.muted && next.map_or(true, |next| next.episode != previous.episode)
+        {
+            // The title of the episode that aired is usually only known once it has aired
+            if let Err(err) = data.refresh_episode_titles(db, remote) {
+                eprintln!(
+                    "failed to get episode titles of {}: {:#}",
+                    data.title(),
+                    err
+                );
+            }
+
+            let mut message = format!("Episode {} of {} has aired", previous.episode, data.title());
+
+            if let Some(title) = data.episode_titles.get(previous.episode) {
+                write!(message, ": {}", title).ok();
+            }
+
+            notify(db, &message);
+        }
+    }
+}
+
+/// Send a notification with how the previous week went toward the weekly goal, once per week.
+///
+/// The first week the daemon sees is only recorded, since it can't tell whether the previous one was already summarized.
+fn summarize_goal(config: &Config, db: &Database) -> Result<()> {
+    let this_week = goal::week_start(Local::now());
+    let mut summary = GoalSummary::load_or_create().context("loading goal summary")?;
+
+    if summary.week_start >= this_week.timestamp() {
+        return Ok(());
+    }
+
+    if summary.week_start > 0 {
+        let last_week = goal::week_start(this_week - ChronoDuration::days(1));
+
+        let goal = WeeklyGoal::load_for_week(config, db, last_week)
+            .context("loading last week's progress")?;
+
+        if let Some(goal) = goal {
+            let message = if goal.is_met() {
+                format!("weekly goal met: watched {} episodes last week", goal)
+            } else {
+                format!("weekly goal missed: watched {} episodes last week", goal)
+            };
+
+            notify(db, &message);
+        }
+    }
+
+    summary.week_start = this_week.timestamp();
+    summary.save().context("saving goal summary")
+}
+
+impl SerializedFile for DaemonState {
+    fn filename() -> &'static str {
+        "daemon"
+    }
+
+    fn save_dir() -> SaveDir {
+        SaveDir::LocalData
+    }
+
+    fn format() -> FileFormat {
+        FileFormat::Binary
+    }
+}
+
+/// Print `message` and save it, so it can still be read in the TUI later.
+fn log_event(db: &Database, kind: EventKind, message: &str) {
+    match kind {
+        EventKind::Error | EventKind::Warning => eprintln!("{}", message),
+        EventKind::Notification => println!("{}", message),
+    }
+
+    if let Err(err) = SavedEvent::record(db, kind, message) {
+        eprintln!("failed to save event: {:#}", err);
+    }
+}
+
+/// Send a desktop notification with `message`, or print it if that isn't possible.
+///
+/// The notification is also saved, so it can still be read in the TUI later.
+fn notify(db: &Database, message: &str) {
+    if let Err(err) = SavedEvent::record(db, EventKind::Notification, message) {
+        eprintln!("failed to save notification: {:#}", err);
+    }
+
+    let sent = Command::new("notify-send")
+        .arg(env!("CARGO_PKG_NAME"))
+        .arg(message)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_or(false, |status| status.success());
+
+    if !sent {
+        println!("{}", message);
+    }
+}
@@ -1,14 +1,21 @@
+use crate::err;
 use crate::file::SaveDir;
 use anyhow::{Context, Result};
 use diesel::connection::SimpleConnection;
 use diesel::deserialize::{self, FromSql};
 use diesel::prelude::*;
 use diesel::serialize::{self, Output, ToSql};
-use diesel::sql_types::{Nullable, Text};
+use diesel::sql_types::{Integer, Nullable, Text};
+use serde::{Serialize, Serializer};
 use smallvec::SmallVec;
+use std::borrow::{Borrow, Cow};
+use std::fmt;
 use std::io::Write;
 use std::ops::Deref;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
 pub mod schema {
     table! {
@@ -18,6 +25,12 @@ pub mod schema {
             path -> Text,
             episode_parser -> Nullable<Text>,
             player_args -> Nullable<Text>,
+            episode_offset -> Integer,
+            local_only -> Bool,
+            remove_watched -> Bool,
+            planned_rewatch -> Bool,
+            title_override -> Nullable<Text>,
+            muted -> Bool,
         }
     }
 
@@ -28,12 +41,18 @@ pub mod schema {
             title_romaji -> Text,
             episodes -> SmallInt,
             episode_length_mins -> SmallInt,
+            genres -> Nullable<Text>,
+            title_english -> Nullable<Text>,
+            title_native -> Nullable<Text>,
+            cover_color -> Nullable<Text>,
+            sequel_ids -> Nullable<Text>,
         }
     }
 
     table! {
-        series_entries {
+        series_entries (id, account_id) {
             id -> Integer,
+            account_id -> Text,
             watched_episodes -> SmallInt,
             score -> Nullable<SmallInt>,
             status -> SmallInt,
@@ -41,24 +60,265 @@ pub mod schema {
             start_date -> Nullable<Date>,
             end_date -> Nullable<Date>,
             needs_sync -> Bool,
+            priority -> SmallInt,
         }
     }
+
+    table! {
+        sync_failures (id, account_id) {
+            id -> Integer,
+            account_id -> Text,
+            failures -> Integer,
+            last_error -> Text,
+        }
+    }
+
+    table! {
+        series_aliases (alias) {
+            alias -> Text,
+            series_id -> Integer,
+        }
+    }
+
+    table! {
+        series_collections (name, series_id) {
+            name -> Text,
+            series_id -> Integer,
+        }
+    }
+
+    table! {
+        filler_episodes (series_id, episode) {
+            series_id -> Integer,
+            episode -> SmallInt,
+        }
+    }
+
+    table! {
+        episode_overrides (series_id, filename) {
+            series_id -> Integer,
+            filename -> Text,
+            episode -> SmallInt,
+        }
+    }
+
+    table! {
+        watch_history {
+            id -> Integer,
+            series_id -> Integer,
+            episode -> SmallInt,
+            minutes -> SmallInt,
+            watched_at -> BigInt,
+            rating -> Nullable<SmallInt>,
+        }
+    }
+
+    table! {
+        playback_sessions {
+            id -> Integer,
+            series_id -> Integer,
+            episode -> SmallInt,
+            started_at -> BigInt,
+            seconds -> Integer,
+            exit_code -> Nullable<Integer>,
+            counted -> Bool,
+        }
+    }
+
+    table! {
+        resume_positions (series_id, episode) {
+            series_id -> Integer,
+            episode -> SmallInt,
+            seconds -> Integer,
+        }
+    }
+
+    table! {
+        active_playbacks (series_id) {
+            series_id -> Integer,
+            episode -> SmallInt,
+            started_at -> BigInt,
+            pid -> Integer,
+        }
+    }
+
+    table! {
+        deleted_series {
+            id -> Integer,
+            nickname -> Text,
+            path -> Text,
+            episode_parser -> Nullable<Text>,
+            player_args -> Nullable<Text>,
+            episode_offset -> Integer,
+            local_only -> Bool,
+            title_preferred -> Nullable<Text>,
+            title_romaji -> Nullable<Text>,
+            episodes -> Nullable<SmallInt>,
+            episode_length_mins -> Nullable<SmallInt>,
+            genres -> Nullable<Text>,
+            trashed_path -> Nullable<Text>,
+            deleted_at -> BigInt,
+            remove_watched -> Bool,
+            planned_rewatch -> Bool,
+            // title_english, title_native, title_override, muted, cover_color, and sequel_ids are left out since tables
+            // are limited to 16 columns, and they're only ever copied with raw queries
+        }
+    }
+
+    table! {
+        saved_events {
+            id -> Integer,
+            kind -> SmallInt,
+            message -> Text,
+            logged_at -> BigInt,
+            read -> Bool,
+        }
+    }
+
+    table! {
+        episode_titles (series_id, episode) {
+            series_id -> Integer,
+            episode -> SmallInt,
+            title -> Text,
+        }
+    }
+
+    table! {
+        sync_journal (id, account_id) {
+            id -> Integer,
+            account_id -> Text,
+            watched_episodes -> SmallInt,
+            score -> Nullable<SmallInt>,
+            status -> SmallInt,
+            times_rewatched -> SmallInt,
+            priority -> SmallInt,
+            start_date -> Nullable<Date>,
+            end_date -> Nullable<Date>,
+            acknowledged -> Bool,
+        }
+    }
+
+    table! {
+        audit_log {
+            id -> Integer,
+            series_id -> Integer,
+            account_id -> Text,
+            service -> Text,
+            sent_at -> BigInt,
+            removed -> Bool,
+            watched_episodes -> Nullable<SmallInt>,
+            score -> Nullable<SmallInt>,
+            status -> Nullable<SmallInt>,
+            times_rewatched -> Nullable<SmallInt>,
+            priority -> Nullable<SmallInt>,
+            start_date -> Nullable<Date>,
+            end_date -> Nullable<Date>,
+        }
+    }
+
+    allow_tables_to_appear_in_same_query!(series_configs, series_entries);
 }
 
-pub struct Database(SqliteConnection);
+/// Migrations to apply to databases created with an older schema.
+///
+/// The migration at index `i` upgrades a database from version `i + 1` to version `i + 2`.
+const MIGRATIONS: [&str; 26] = [
+    include_str!("../sql/migrations/2.sql"),
+    include_str!("../sql/migrations/3.sql"),
+    include_str!("../sql/migrations/4.sql"),
+    include_str!("../sql/migrations/5.sql"),
+    include_str!("../sql/migrations/6.sql"),
+    include_str!("../sql/migrations/7.sql"),
+    include_str!("../sql/migrations/8.sql"),
+    include_str!("../sql/migrations/9.sql"),
+    include_str!("../sql/migrations/10.sql"),
+    include_str!("../sql/migrations/11.sql"),
+    include_str!("../sql/migrations/12.sql"),
+    include_str!("../sql/migrations/13.sql"),
+    include_str!("../sql/migrations/14.sql"),
+    include_str!("../sql/migrations/15.sql"),
+    include_str!("../sql/migrations/16.sql"),
+    include_str!("../sql/migrations/17.sql"),
+    include_str!("../sql/migrations/18.sql"),
+    include_str!("../sql/migrations/19.sql"),
+    include_str!("../sql/migrations/20.sql"),
+    include_str!("../sql/migrations/21.sql"),
+    include_str!("../sql/migrations/22.sql"),
+    include_str!("../sql/migrations/23.sql"),
+    include_str!("../sql/migrations/24.sql"),
+    include_str!("../sql/migrations/25.sql"),
+    include_str!("../sql/migrations/26.sql"),
+    include_str!("../sql/migrations/27.sql"),
+];
+
+/// The number of times a write transaction will be attempted while the database is locked by another process.
+const WRITE_ATTEMPTS: u32 = 4;
+
+pub struct Database {
+    conn: SqliteConnection,
+    /// The account that list entries are loaded from and saved to.
+    account: String,
+}
 
 impl Database {
     pub fn open() -> Result<Self> {
         let path = Self::validated_path().context("getting path")?;
-        let conn = SqliteConnection::establish(&path.to_string_lossy())?;
+        Self::open_url(&path.to_string_lossy())
+    }
+
+    /// Open the database at `path` instead of the usual one, such as a copy of it. It's migrated to the current schema.
+    pub fn open_file(path: &Path) -> Result<Self> {
+        Self::open_url(&path.to_string_lossy())
+    }
+
+    /// Open a new database that only exists in memory, and is gone once it's dropped.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::open_url(":memory:")
+    }
+
+    fn open_url(url: &str) -> Result<Self> {
+        let conn = SqliteConnection::establish(url)?;
 
         conn.batch_execute(include_str!("../sql/pragmas.sql"))
             .context("executing pragmas")?;
 
-        conn.batch_execute(include_str!("../sql/schema.sql"))
-            .context("executing schema")?;
+        Self::migrate(&conn).context("migrating schema")?;
+
+        Ok(Self {
+            conn,
+            account: String::new(),
+        })
+    }
+
+    fn migrate(conn: &SqliteConnection) -> Result<()> {
+        let version = Self::user_version(conn, "main").context("getting schema version")?;
+
+        // A version of 0 means that the database was just created
+        if version == 0 {
+            return conn
+                .batch_execute(include_str!("../sql/schema.sql"))
+                .context("executing schema");
+        }
+
+        for (i, migration) in MIGRATIONS.iter().enumerate().skip(version as usize - 1) {
+            conn.transaction(|| conn.batch_execute(migration))
+                .with_context(|| format!("applying migration to version {}", i + 2))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the schema version of the database attached to `conn` with the name `schema`.
+    pub fn user_version(conn: &SqliteConnection, schema: &str) -> diesel::QueryResult<i32> {
+        #[derive(QueryableByName)]
+        struct UserVersion {
+            #[sql_type = "Integer"]
+            user_version: i32,
+        }
 
-        Ok(Self(conn))
+        diesel::sql_query(format!("PRAGMA {}.user_version", schema))
+            .get_result::<UserVersion>(conn)
+            .map(|version| version.user_version)
     }
 
     pub fn validated_path() -> Result<PathBuf> {
@@ -69,7 +329,54 @@ impl Database {
 
     #[inline(always)]
     pub fn conn(&self) -> &SqliteConnection {
-        &self.0
+        &self.conn
+    }
+
+    /// Run `func` within a write transaction that will be rolled back if it returns an error.
+    ///
+    /// Since another instance of the program can be using the database at the same time (such as one started with `--play-one`),
+    /// the transaction will be retried a few times if the database stays locked for longer than the busy timeout.
+    pub fn write_transaction<T, F>(&self, mut func: F) -> Result<T>
+    where
+        F: FnMut() -> Result<T>,
+    {
+        let mut attempt = 1;
+
+        loop {
+            match self.conn.immediate_transaction(&mut func) {
+                Err(err) if attempt < WRITE_ATTEMPTS && err::is_database_locked(&err) => {
+                    thread::sleep(Duration::from_millis(250 * u64::from(attempt)));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Returns the ID of the account that list entries are loaded from and saved to.
+    #[inline(always)]
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    /// Set the account that list entries are loaded from and saved to.
+    ///
+    /// Entries that were saved before they were tracked per account will be given to `account`,
+    /// unless it already has its own entry for the same series.
+    pub fn set_account<S>(&mut self, account: S) -> diesel::QueryResult<()>
+    where
+        S: Into<String>,
+    {
+        let account = account.into();
+
+        diesel::sql_query(
+            "UPDATE OR IGNORE series_entries SET account_id = ? WHERE account_id = ''",
+        )
+        .bind::<Text, _>(&account)
+        .execute(self.conn())?;
+
+        self.account = account;
+        Ok(())
     }
 }
 
@@ -140,3 +447,220 @@ impl Deref for PlayerArgs {
         &self.0
     }
 }
+
+/// The genres of a series, stored as a single delimited string.
+#[derive(Clone, Debug, Default, AsExpression, FromSqlRow)]
+#[sql_type = "Text"]
+pub struct Genres(Vec<String>);
+
+impl<DB> FromSql<Nullable<Text>, DB> for Genres
+where
+    DB: diesel::backend::Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: Option<&DB::RawValue>) -> deserialize::Result<Self> {
+        match bytes {
+            Some(_) => {
+                let genres = String::from_sql(bytes)?
+                    .split(";;")
+                    .filter(|genre| !genre.is_empty())
+                    .map(Into::into)
+                    .collect();
+
+                Ok(Self(genres))
+            }
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+impl<DB> ToSql<Text, DB> for Genres
+where
+    DB: diesel::backend::Backend,
+    String: ToSql<Text, DB>,
+{
+    fn to_sql<W: Write>(&self, out: &mut Output<W, DB>) -> serialize::Result {
+        let value = self.0.join(";;");
+        value.to_sql(out)
+    }
+}
+
+impl From<Vec<String>> for Genres {
+    fn from(value: Vec<String>) -> Self {
+        Self(value)
+    }
+}
+
+impl Deref for Genres {
+    type Target = Vec<String>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// The IDs of the sequels of a series, stored as a single delimited string.
+#[derive(Clone, Debug, Default, AsExpression, FromSqlRow)]
+#[sql_type = "Text"]
+pub struct SequelIds(Vec<i32>);
+
+impl<DB> FromSql<Nullable<Text>, DB> for SequelIds
+where
+    DB: diesel::backend::Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: Option<&DB::RawValue>) -> deserialize::Result<Self> {
+        match bytes {
+            Some(_) => {
+                let ids = String::from_sql(bytes)?
+                    .split(',')
+                    .filter_map(|id| id.parse().ok())
+                    .collect();
+
+                Ok(Self(ids))
+            }
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+impl<DB> ToSql<Text, DB> for SequelIds
+where
+    DB: diesel::backend::Backend,
+    String: ToSql<Text, DB>,
+{
+    fn to_sql<W: Write>(&self, out: &mut Output<W, DB>) -> serialize::Result {
+        let value = self
+            .0
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+
+        value.to_sql(out)
+    }
+}
+
+impl From<Vec<i32>> for SequelIds {
+    fn from(value: Vec<i32>) -> Self {
+        Self(value)
+    }
+}
+
+impl Deref for SequelIds {
+    type Target = Vec<i32>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// An immutable string that is cheap to clone, for text such as series titles and nicknames
+/// that is loaded once and then shared between the series and everything that shows it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, AsExpression, FromSqlRow)]
+#[sql_type = "Text"]
+pub struct SharedStr(Arc<str>);
+
+impl SharedStr {
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<DB> FromSql<Text, DB> for SharedStr
+where
+    DB: diesel::backend::Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: Option<&DB::RawValue>) -> deserialize::Result<Self> {
+        String::from_sql(bytes).map(Into::into)
+    }
+}
+
+impl<DB> ToSql<Text, DB> for SharedStr
+where
+    DB: diesel::backend::Backend,
+    str: ToSql<Text, DB>,
+{
+    fn to_sql<W: Write>(&self, out: &mut Output<W, DB>) -> serialize::Result {
+        self.as_str().to_sql(out)
+    }
+}
+
+impl From<String> for SharedStr {
+    fn from(value: String) -> Self {
+        Self(value.into())
+    }
+}
+
+impl From<&str> for SharedStr {
+    fn from(value: &str) -> Self {
+        Self(value.into())
+    }
+}
+
+impl Deref for SharedStr {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<str> for SharedStr {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for SharedStr {
+    fn as_ref(&self) -> &Path {
+        self.as_str().as_ref()
+    }
+}
+
+impl<'a> From<&'a SharedStr> for Cow<'a, str> {
+    fn from(value: &'a SharedStr) -> Self {
+        Cow::Borrowed(value.as_str())
+    }
+}
+
+impl Borrow<str> for SharedStr {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for SharedStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<String> for SharedStr {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SharedStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Serialize for SharedStr {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl fmt::Display for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
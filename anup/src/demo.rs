@@ -0,0 +1,98 @@
+use crate::config::Config;
+use crate::database::Database;
+use crate::series::config::SeriesConfig;
+use crate::series::{self, Series, SeriesData, SeriesParams, SeriesPath};
+use crate::Args;
+use anime::local::EpisodeParser;
+use anime::remote::mock::{Conditions, Mock};
+use anime::remote::{Remote, SeriesInfo};
+use anyhow::{anyhow, Context, Result};
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::Duration;
+
+/// The account that the list entries of demo series are saved to.
+const ACCOUNT: &str = "demo";
+
+/// Returns the remote service and database to use when running with `--demo`.
+///
+/// The database only exists in memory, and has every series the demo remote knows about added to it.
+/// Their episodes are empty files in a temporary directory.
+///
+/// The returned remote is slowed down or made to fail as requested by `args`.
+pub fn init(config: &Config, args: &Args) -> Result<(Remote, Database)> {
+    let mock = Mock::demo();
+    let infos = mock.series().to_vec();
+
+    // Series are added with a remote that isn't slowed down or failing, so the demo can always start
+    let setup_remote = Remote::from(mock);
+
+    let mut db = Database::open_in_memory().context("opening demo database")?;
+    db.set_account(ACCOUNT)
+        .context("setting demo database account")?;
+
+    let episodes_dir = std::env::temp_dir().join("anup-demo");
+
+    for info in infos {
+        let nickname = unused_nickname(&db, &info)?;
+
+        let path = episodes_dir.join(&nickname);
+
+        create_episode_files(&path, &info)
+            .with_context(|| format!("creating episodes for {}", nickname))?;
+
+        let params = SeriesParams::new(
+            nickname,
+            SeriesPath::with_base(&config.series_dir, path),
+            EpisodeParser::default(),
+        );
+
+        #[allow(clippy::cast_possible_wrap)]
+        let series_config = SeriesConfig::new(info.id as i32, params, &db)?;
+        let data = SeriesData::from_remote(series_config, info.into(), &setup_remote)?;
+
+        Series::init(data, config).save(&db)?;
+    }
+
+    let conditions = Conditions {
+        latency: Duration::from_millis(args.demo_latency),
+        failure_rate: f32::from(args.demo_failure_rate.min(100)) / 100.0,
+    };
+
+    let remote = Mock::demo().with_conditions(conditions).into();
+
+    Ok((remote, db))
+}
+
+/// Returns a nickname for the series that no other series uses, since sequels generate the same nickname as their first season.
+fn unused_nickname(db: &Database, info: &SeriesInfo) -> Result<String> {
+    let generated = series::generate_nickname(info.title.romaji.as_str())
+        .ok_or_else(|| anyhow!("failed to generate nickname for {}", info.title.romaji))?;
+
+    let mut nickname = generated.clone();
+    let mut num = 1;
+
+    while SeriesConfig::nickname_exists(db, &nickname).is_some() {
+        num += 1;
+        nickname = format!("{}_{}", generated, num);
+    }
+
+    Ok(nickname)
+}
+
+fn create_episode_files(dir: &Path, info: &SeriesInfo) -> Result<()> {
+    fs::create_dir_all(dir)?;
+
+    // Titles can have characters that aren't allowed in filenames on some platforms
+    let title = info.title.romaji.replace(':', "");
+
+    for episode in 1..=info.episodes {
+        let path = dir.join(format!("[Demo] {} - {:02}.mkv", title, episode));
+
+        if !path.exists() {
+            File::create(path)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,554 @@
+use crate::config::{Config, DigestConfig};
+use crate::database::Database;
+use crate::file::{FileFormat, SaveDir, SerializedFile};
+use crate::goal;
+use crate::series::config::SeriesConfig;
+use crate::series::SeriesData;
+use crate::util;
+use crate::{init_remote, open_database, Args};
+use anime::remote::anilist::AniList;
+use anime::remote::{Remote, RemoteService, SeriesDate, SeriesID, Status};
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+use chrono::{DateTime, Duration as ChronoDuration, Local, Utc};
+use diesel::prelude::*;
+use serde_derive::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::process::{self, Command, Stdio};
+use std::time::Duration;
+
+/// How long to wait for an ntfy server before giving up.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How far ahead to look for episodes that are about to air.
+const UPCOMING_DAYS: i64 = 7;
+
+#[derive(FromArgs)]
+/// Send a summary of recently watched episodes, completed series, and episodes airing soon.
+#[argh(subcommand, name = "digest")]
+pub struct DigestArgs {
+    /// the number of days to summarize, counting back from now
+    #[argh(option, default = "7")]
+    days: u16,
+
+    /// print the digest instead of sending it
+    #[argh(switch)]
+    print: bool,
+}
+
+pub fn run(args: &Args, digest_args: &DigestArgs) -> Result<()> {
+    let config = Config::load_and_report()?;
+    let db = open_database(args)?;
+
+    let end = Local::now();
+    let start = end - ChronoDuration::days(digest_args.days.into());
+
+    // Airing schedules can be looked up without an account
+    let remote = if args.offline {
+        None
+    } else {
+        Some(init_remote(args, &config)?.unwrap_or_else(|| AniList::Unauthenticated.into()))
+    };
+
+    let digest = Digest::build(&config, &db, remote.as_ref(), start, end)?;
+
+    if digest_args.print || !config.digest.can_send() {
+        print!("{}", digest);
+        return Ok(());
+    }
+
+    digest.send(&config.digest)
+}
+
+/// Send a digest of the previous week once a new one starts.
+///
+/// The first week the daemon sees is only recorded, since it can't tell whether the previous one was already sent.
+pub fn send_weekly(config: &Config, db: &Database, remote: &Remote) -> Result<()> {
+    let this_week = goal::week_start(Local::now());
+    let mut last = LastDigest::load_or_create().context("loading last digest")?;
+
+    if last.week_start >= this_week.timestamp() {
+        return Ok(());
+    }
+
+    if last.week_start > 0 {
+        let last_week = goal::week_start(this_week - ChronoDuration::days(1));
+        let digest = Digest::build(config, db, Some(remote), last_week, this_week)?;
+        digest.send(&config.digest)?;
+    }
+
+    last.week_start = this_week.timestamp();
+    last.save().context("saving last digest")
+}
+
+/// What happened between two points in time, and which episodes air soon after.
+pub struct Digest {
+    start: String,
+    end: String,
+    /// Title -> the episodes of the series that were watched.
+    watched: BTreeMap<String, Vec<i16>>,
+    watched_mins: u32,
+    /// The titles of series that were completed.
+    completed: Vec<String>,
+    /// Episodes that air within `UPCOMING_DAYS` of the digest being made, or `None` if they couldn't be looked up.
+    upcoming: Option<Vec<UpcomingEpisode>>,
+}
+
+struct UpcomingEpisode {
+    title: String,
+    episode: u32,
+    airs_at: i64,
+    /// The time the episode airs, formatted with the config's date and time formats.
+    airs_at_text: String,
+}
+
+impl Digest {
+    /// Summarize the episodes watched and series completed between `start` and `end`.
+    ///
+    /// Episodes airing soon are only looked up when `remote` is given. Episodes of series that are no longer added are
+    /// left out.
+    pub fn build(
+        config: &Config,
+        db: &Database,
+        remote: Option<&Remote>,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+    ) -> Result<Self> {
+        use crate::database::schema::watch_history::dsl::{
+            episode, minutes, series_id, watch_history, watched_at,
+        };
+
+        let series = SeriesConfig::load_all(db)
+            .context("loading series configs")?
+            .into_iter()
+            .map(|config| SeriesData::load_from_config(db, Cow::Owned(config)))
+            .collect::<Result<Vec<_>, _>>()
+            .context("loading series")?;
+
+        let titles = series
+            .iter()
+            .map(|data| (data.info.id, data.title()))
+            .collect::<HashMap<_, _>>();
+
+        let history = watch_history
+            .select((series_id, episode, minutes))
+            .filter(watched_at.ge(start.timestamp()))
+            .filter(watched_at.lt(end.timestamp()))
+            .order(watched_at.asc())
+            .load::<(i32, i16, i16)>(db.conn())
+            .context("loading watch history")?;
+
+        let mut watched = BTreeMap::<_, Vec<_>>::new();
+        let mut watched_mins = 0;
+
+        for (id, ep, mins) in history {
+            let title = match titles.get(&id) {
+                Some(title) => title,
+                None => continue,
+            };
+
+            watched.entry(title.to_string()).or_default().push(ep);
+            watched_mins += mins.max(0) as u32;
+        }
+
+        // The end is exclusive, so the last day included is the one before it
+        let last_shown = end - ChronoDuration::seconds(1);
+
+        let (first_day, last_day) = (
+            util::local_date(start.with_timezone(&Utc)),
+            util::local_date(last_shown.with_timezone(&Utc)),
+        );
+
+        let mut completed = series
+            .iter()
+            .filter(|data| data.entry.status() == Status::Completed)
+            .filter(|data| {
+                data.entry
+                    .end_date()
+                    .map_or(false, |date| is_between(date, first_day, last_day))
+            })
+            .map(|data| data.title().to_string())
+            .collect::<Vec<_>>();
+
+        completed.sort_unstable();
+
+        let upcoming = match remote {
+            Some(remote) => match Self::load_upcoming(config, remote, &series) {
+                Ok(upcoming) => Some(upcoming),
+                Err(err) => {
+                    eprintln!("failed to look up airing episodes: {:#}", err);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        Ok(Self {
+            start: config.dates.format_timestamp_date(start.timestamp()),
+            end: config.dates.format_timestamp_date(last_shown.timestamp()),
+            watched,
+            watched_mins,
+            completed,
+            upcoming,
+        })
+    }
+
+    fn load_upcoming(
+        config: &Config,
+        remote: &Remote,
+        series: &[SeriesData],
+    ) -> Result<Vec<UpcomingEpisode>> {
+        let until = (Utc::now() + ChronoDuration::days(UPCOMING_DAYS)).timestamp();
+        let mut upcoming = Vec::new();
+
+        let airing = series
+            .iter()
+            .filter(|data| matches!(data.entry.status(), Status::Watching | Status::PlanToWatch));
+
+        for data in airing {
+            let next = remote
+                .next_airing_episode(data.info.id as SeriesID)
+                .with_context(|| format!("looking up when {} airs", data.config.nickname))?;
+
+            let next = match next {
+                Some(next) if next.airs_at < until => next,
+                _ => continue,
+            };
+
+            upcoming.push(UpcomingEpisode {
+                title: data.title().to_string(),
+                episode: next.episode,
+                airs_at: next.airs_at,
+                airs_at_text: config.dates.format_timestamp(next.airs_at),
+            });
+        }
+
+        upcoming.sort_unstable_by_key(|upcoming| upcoming.airs_at);
+        Ok(upcoming)
+    }
+
+    /// The subject of emails and title of notifications the digest is sent with.
+    pub fn subject(&self) -> String {
+        format!("anup digest for {} to {}", self.start, self.end)
+    }
+
+    /// Send the digest with every method set in `config`.
+    ///
+    /// Every method is tried even if one fails, and the first error is returned.
+    pub fn send(&self, config: &DigestConfig) -> Result<()> {
+        if !config.can_send() {
+            return Err(anyhow!(
+                "no way to send the digest is set in the digest section of the config"
+            ));
+        }
+
+        let body = self.to_string();
+        let mut results = Vec::new();
+
+        if !config.command.is_empty() {
+            results.push(send_with_command(&config.command, &body).context("sending with command"));
+        }
+
+        if let Some(url) = &config.ntfy_url {
+            let token = config.ntfy_token.as_deref();
+            results
+                .push(send_to_ntfy(url, token, &self.subject(), &body).context("sending to ntfy"));
+        }
+
+        if config.smtp.url.is_some() {
+            results.push(send_email(config, &self.subject(), &body).context("sending email"));
+        }
+
+        results.into_iter().collect()
+    }
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let episodes = self.watched.values().map(Vec::len).sum::<usize>();
+
+        if episodes == 0 {
+            writeln!(
+                f,
+                "Nothing was watched from {} to {}.",
+                self.start, self.end
+            )?;
+        } else {
+            writeln!(
+                f,
+                "Watched {} episodes ({}) from {} to {}:",
+                episodes,
+                util::hm_from_mins(self.watched_mins as f32),
+                self.start,
+                self.end
+            )?;
+
+            for (title, watched) in &self.watched {
+                writeln!(f, "  {}: {}", title, episode_ranges(watched))?;
+            }
+        }
+
+        if !self.completed.is_empty() {
+            writeln!(f, "\nCompleted:")?;
+
+            for title in &self.completed {
+                writeln!(f, "  {}", title)?;
+            }
+        }
+
+        match &self.upcoming {
+            Some(upcoming) if upcoming.is_empty() => {
+                writeln!(f, "\nNothing airs in the next {} days.", UPCOMING_DAYS)
+            }
+            Some(upcoming) => {
+                writeln!(f, "\nAiring in the next {} days:", UPCOMING_DAYS)?;
+
+                for upcoming in upcoming {
+                    writeln!(
+                        f,
+                        "  {}: episode {} at {}",
+                        upcoming.title, upcoming.episode, upcoming.airs_at_text
+                    )?;
+                }
+
+                Ok(())
+            }
+            None => writeln!(f, "\nEpisodes airing soon couldn't be looked up."),
+        }
+    }
+}
+
+/// Returns true if `date` falls on or between `first` and `last`.
+fn is_between(date: SeriesDate, first: SeriesDate, last: SeriesDate) -> bool {
+    let key = |date: SeriesDate| (date.year, date.month, date.day);
+    key(first) <= key(date) && key(date) <= key(last)
+}
+
+/// Describe `episodes` with consecutive ones grouped together, like `episodes 1-3, 5`.
+fn episode_ranges(episodes: &[i16]) -> String {
+    let mut episodes = episodes.to_vec();
+    episodes.sort_unstable();
+    episodes.dedup();
+
+    let mut ranges: Vec<(i16, i16)> = Vec::new();
+
+    for episode in episodes {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == episode => *end = episode,
+            _ => ranges.push((episode, episode)),
+        }
+    }
+
+    let ranges = ranges
+        .iter()
+        .map(|&(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}-{}", start, end)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    match ranges.as_slice() {
+        [single] if !single.contains('-') => format!("episode {}", single),
+        _ => format!("episodes {}", ranges.join(", ")),
+    }
+}
+
+/// Run `command` with `body` as its standard input.
+fn send_with_command(command: &[String], body: &str) -> Result<()> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| anyhow!("no command set"))?;
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("running {}", program))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(body.as_bytes())
+            .context("writing digest to command")?;
+    }
+
+    let status = child.wait().context("waiting for command")?;
+
+    if !status.success() {
+        return Err(anyhow!("{} exited with {}", program, status));
+    }
+
+    Ok(())
+}
+
+/// Publish `body` to the ntfy topic at `url`.
+fn send_to_ntfy(url: &str, token: Option<&str>, title: &str, body: &str) -> Result<()> {
+    let mut request = attohttpc::post(url)
+        .header("Title", title)
+        .timeout(REQUEST_TIMEOUT);
+
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+
+    let response = request.text(body).send().context("publishing digest")?;
+
+    if !response.is_success() {
+        return Err(anyhow!("ntfy returned {}", response.status()));
+    }
+
+    Ok(())
+}
+
+/// Email `body` with the SMTP server in `config`, using curl.
+///
+/// The message is given to curl through its standard input, and the login through a config file only the current user
+/// can read, so neither shows up in the list of running processes or is left readable in the temporary directory.
+fn send_email(config: &DigestConfig, subject: &str, body: &str) -> Result<()> {
+    let smtp = &config.smtp;
+
+    let url = smtp
+        .url
+        .as_ref()
+        .ok_or_else(|| anyhow!("no SMTP server set"))?;
+
+    let from = smtp
+        .from
+        .as_ref()
+        .ok_or_else(|| anyhow!("no address to send from set"))?;
+
+    if smtp.to.is_empty() {
+        return Err(anyhow!("no addresses to send to set"));
+    }
+
+    let message = format!(
+        "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}",
+        from,
+        smtp.to.join(", "),
+        subject,
+        Local::now().to_rfc2822(),
+        body.replace('\n', "\r\n")
+    );
+
+    let login_path = match (&smtp.username, &smtp.password) {
+        (Some(username), Some(password)) => Some(write_curl_login(username, password)?),
+        _ => None,
+    };
+
+    let mut cmd = Command::new("curl");
+    cmd.args([
+        "--silent",
+        "--show-error",
+        "--ssl-reqd",
+        "--upload-file",
+        "-",
+    ])
+    .arg("--url")
+    .arg(url)
+    .arg("--mail-from")
+    .arg(from)
+    .stdin(Stdio::piped());
+
+    if let Some(login_path) = &login_path {
+        cmd.arg("--config").arg(login_path);
+    }
+
+    for to in &smtp.to {
+        cmd.arg("--mail-rcpt").arg(to);
+    }
+
+    let result = (|| {
+        let mut child = cmd.spawn().context("running curl")?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(message.as_bytes())
+                .context("writing email to curl")?;
+        }
+
+        let status = child.wait().context("waiting for curl")?;
+
+        if !status.success() {
+            return Err(anyhow!("curl exited with {}", status));
+        }
+
+        Ok(())
+    })();
+
+    if let Some(login_path) = login_path {
+        fs::remove_file(login_path).ok();
+    }
+
+    result
+}
+
+/// Write a curl config file with the SMTP login to the temporary directory, and return its path.
+///
+/// The file is always newly created and only readable by the current user, so an existing file or link at the same
+/// path can't be written through.
+fn write_curl_login(username: &str, password: &str) -> Result<PathBuf> {
+    let nanos = Utc::now().timestamp_subsec_nanos();
+    let path = env::temp_dir().join(format!("anup-digest-{}-{}.curlrc", process::id(), nanos));
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&path)
+        .context("creating curl login file")?;
+
+    let login = format!("{}:{}", username, password)
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"");
+
+    let written = writeln!(file, "user = \"{}\"", login).context("writing curl login file");
+
+    if written.is_err() {
+        fs::remove_file(&path).ok();
+    }
+
+    written.map(|()| path)
+}
+
+/// The last week the daemon sent a digest for, so each week is only sent once.
+#[derive(Default, Deserialize, Serialize)]
+struct LastDigest {
+    /// When the last week sent started, as a UNIX timestamp. This is 0 until the first week is recorded.
+    week_start: i64,
+}
+
+impl SerializedFile for LastDigest {
+    fn filename() -> &'static str {
+        "last_digest"
+    }
+
+    fn save_dir() -> SaveDir {
+        SaveDir::LocalData
+    }
+
+    fn format() -> FileFormat {
+        FileFormat::Binary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_episode_ranges() {
+        assert_eq!(episode_ranges(&[4]), "episode 4");
+        assert_eq!(episode_ranges(&[3, 1, 2]), "episodes 1-3");
+        assert_eq!(episode_ranges(&[1, 2, 3, 5, 7, 8]), "episodes 1-3, 5, 7-8");
+        assert_eq!(episode_ranges(&[2, 2, 6]), "episodes 2, 6");
+    }
+}
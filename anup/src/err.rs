@@ -1,6 +1,121 @@
+use anime::ErrorKind;
 use anyhow::Error;
-use std::io;
+use diesel::result::Error as DieselError;
+use std::{fmt, io};
+
+/// A reason for a command to fail that scripts may want to tell apart from other errors, since each one exits the
+/// program with its own code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Failure {
+    /// There was nothing for the command to do, such as when no list entries need to be synced.
+    NothingToDo,
+    /// Some of what the command was meant to do succeeded, but the rest failed.
+    Partial,
+    /// No account was found, or the remote service rejected it.
+    Auth,
+    /// The command needs to reach the remote service, but it's running offline or the service couldn't be reached.
+    Offline,
+}
+
+impl Failure {
+    /// Returns the exit code of the program when a command fails with `failure`, or 1 for any other error.
+    pub fn exit_code(failure: Option<Self>) -> i32 {
+        match failure {
+            None => 1,
+            Some(Self::NothingToDo) => 2,
+            Some(Self::Partial) => 3,
+            Some(Self::Auth) => 4,
+            Some(Self::Offline) => 5,
+        }
+    }
+
+    /// Returns an error with `message` that fails with this reason.
+    pub fn error<S>(self, message: S) -> Error
+    where
+        S: Into<String>,
+    {
+        Error::new(FailureError {
+            failure: self,
+            message: message.into(),
+        })
+    }
+
+    /// Returns the reason `err` failed, from either an error made with `Failure::error` or an error from the remote
+    /// service anywhere in its chain.
+    pub fn of(err: &Error) -> Option<Self> {
+        err.chain().find_map(|cause| {
+            if let Some(err) = cause.downcast_ref::<FailureError>() {
+                return Some(err.failure);
+            }
+
+            match cause.downcast_ref::<anime::Error>()?.kind() {
+                ErrorKind::Network => Some(Self::Offline),
+                ErrorKind::Auth => Some(Self::Auth),
+                _ => None,
+            }
+        })
+    }
+}
+
+#[derive(Debug)]
+struct FailureError {
+    failure: Failure,
+    message: String,
+}
+
+impl fmt::Display for FailureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for FailureError {}
 
 pub fn is_file_nonexistant(err: &Error) -> bool {
     matches!(err.downcast_ref::<io::Error>(), Some(err) if err.kind() == io::ErrorKind::NotFound)
 }
+
+/// Returns true if any error in the chain indicates that the remote service is down.
+pub fn is_service_unavailable(err: &Error) -> bool {
+    err.chain().any(|cause| {
+        matches!(cause.downcast_ref::<anime::Error>(), Some(err) if err.is_service_unavailable())
+    })
+}
+
+/// Returns true if any error in the chain indicates that a series no longer exists on the remote service.
+pub fn is_series_missing(err: &Error) -> bool {
+    err.chain().any(
+        |cause| matches!(cause.downcast_ref::<anime::Error>(), Some(err) if err.is_not_found()),
+    )
+}
+
+/// Returns true if any error in the chain was caused by the database being locked by another process.
+pub fn is_database_locked(err: &Error) -> bool {
+    err.chain()
+        .any(|cause| match cause.downcast_ref::<DieselError>() {
+            Some(DieselError::DatabaseError(_, info)) => {
+                let msg = info.message();
+                msg.contains("database is locked") || msg.contains("database is busy")
+            }
+            _ => false,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn failure_is_found_in_chain() {
+        let err = Failure::Offline.error("must be online").context("syncing");
+        assert_eq!(Failure::of(&err), Some(Failure::Offline));
+        assert_eq!(err.to_string(), "syncing");
+
+        let err = Error::new(anime::Error::NeedAuthentication).context("getting list entry");
+        assert_eq!(Failure::of(&err), Some(Failure::Auth));
+
+        assert_eq!(Failure::of(&anyhow!("something else")), None);
+        assert_eq!(Failure::exit_code(None), 1);
+    }
+}
@@ -0,0 +1,257 @@
+#![allow(clippy::doc_markdown)]
+
+use crate::series::config::SeriesConfig;
+use crate::series::SeriesData;
+use crate::{open_database, Args};
+use anime::remote::{SeriesDate, Status};
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+use chrono::Utc;
+use serde_derive::Serialize;
+use std::borrow::Cow;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+#[derive(FromArgs)]
+/// Export every added series in a format that other anime trackers can import.
+#[argh(subcommand, name = "export")]
+pub struct ExportArgs {
+    /// the format to export to, either anime-planet or notify-moe
+    #[argh(option, short = 'f')]
+    format: Format,
+
+    /// the file to write the export to, instead of printing it
+    #[argh(option, short = 'o')]
+    output: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Format {
+    AnimePlanet,
+    NotifyMoe,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "anime-planet" | "animeplanet" | "ap" => Ok(Self::AnimePlanet),
+            "notify-moe" | "notify.moe" | "notifymoe" | "notify" => Ok(Self::NotifyMoe),
+            _ => Err(anyhow!("unknown format: {}", value)),
+        }
+    }
+}
+
+pub fn run(args: &Args, export_args: &ExportArgs) -> Result<()> {
+    let db = open_database(args)?;
+
+    let mut series = SeriesConfig::load_all(&db)
+        .context("loading series configs")?
+        .into_iter()
+        .map(|series_config| SeriesData::load_from_config(&db, Cow::Owned(series_config)))
+        .collect::<Result<Vec<_>, _>>()
+        .context("loading series")?;
+
+    series.sort_unstable_by(|x, y| x.info.title_preferred.cmp(&y.info.title_preferred));
+
+    let exported = match export_args.format {
+        Format::AnimePlanet => serde_json::to_string_pretty(&AnimePlanetExport::new(&series)),
+        Format::NotifyMoe => serde_json::to_string_pretty(&NotifyMoeExport::new(&series)),
+    }
+    .context("encoding export")?;
+
+    match &export_args.output {
+        Some(path) => {
+            fs::write(path, exported)
+                .with_context(|| format!("writing export to {}", path.display()))?;
+
+            eprintln!("exported {} series to {}", series.len(), path.display());
+        }
+        None => println!("{}", exported),
+    }
+
+    Ok(())
+}
+
+/// An export in the JSON format Anime-Planet uses for its own list exports.
+///
+/// Anime-Planet matches entries by their name when importing them.
+#[derive(Serialize)]
+struct AnimePlanetExport<'a> {
+    export: AnimePlanetHeader,
+    entries: Vec<AnimePlanetEntry<'a>>,
+}
+
+impl<'a> AnimePlanetExport<'a> {
+    fn new(series: &'a [SeriesData]) -> Self {
+        Self {
+            export: AnimePlanetHeader {
+                version: "1.0",
+                kind: "anime",
+                date: Utc::now().format("%Y-%m-%d").to_string(),
+            },
+            entries: series.iter().map(AnimePlanetEntry::new).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct AnimePlanetHeader {
+    version: &'static str,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    date: String,
+}
+
+#[derive(Serialize)]
+struct AnimePlanetEntry<'a> {
+    name: &'a str,
+    status: &'static str,
+    started: Option<String>,
+    completed: Option<String>,
+    /// The rating out of 5 stars, in half star steps.
+    rating: Option<f32>,
+    /// The number of times the series was watched to completion.
+    times: i16,
+    eps: i16,
+    #[serde(rename = "type")]
+    kind: &'static str,
+}
+
+impl<'a> AnimePlanetEntry<'a> {
+    fn new(data: &'a SeriesData) -> Self {
+        let entry = &data.entry;
+
+        let status = match entry.status() {
+            Status::Watching | Status::Rewatching => "watching",
+            Status::Completed => "watched",
+            Status::OnHold => "stalled",
+            Status::Dropped => "dropped",
+            Status::PlanToWatch => "want to watch",
+        };
+
+        let times = match entry.status() {
+            Status::Completed | Status::Rewatching => entry.times_rewatched() + 1,
+            _ => 0,
+        };
+
+        let format_date =
+            |date: SeriesDate| format!("{}-{:02}-{:02} 00:00:00", date.year, date.month, date.day);
+
+        Self {
+            name: &data.info.title_romaji,
+            status,
+            started: entry.start_date().map(format_date),
+            completed: entry.end_date().map(format_date),
+            rating: entry
+                .score()
+                .map(|score| f32::from(anime_planet_half_stars(score)) / 2.0),
+            times,
+            eps: entry.watched_episodes(),
+            kind: "anime",
+        }
+    }
+}
+
+/// Converts a score from 0 to 100 to the number of half stars it's worth on Anime-Planet, from 1 to 10.
+fn anime_planet_half_stars(score: i16) -> i16 {
+    ((score.clamp(0, 100) + 5) / 10).max(1)
+}
+
+/// An export compatible with the anime list format of Notify.moe.
+///
+/// Notify.moe has its own IDs for each anime, so entries are identified by their AniList ID in the same way
+/// Notify.moe maps its anime to other services, along with their title.
+#[derive(Serialize)]
+struct NotifyMoeExport<'a> {
+    items: Vec<NotifyMoeItem<'a>>,
+}
+
+impl<'a> NotifyMoeExport<'a> {
+    fn new(series: &'a [SeriesData]) -> Self {
+        Self {
+            items: series.iter().map(NotifyMoeItem::new).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NotifyMoeItem<'a> {
+    title: &'a str,
+    mappings: [NotifyMoeMapping; 1],
+    status: &'static str,
+    episodes: i16,
+    rating: NotifyMoeRating,
+    notes: &'static str,
+    rewatch_count: i16,
+    private: bool,
+}
+
+impl<'a> NotifyMoeItem<'a> {
+    fn new(data: &'a SeriesData) -> Self {
+        let entry = &data.entry;
+
+        let status = match entry.status() {
+            Status::Watching | Status::Rewatching => "watching",
+            Status::Completed => "completed",
+            Status::OnHold => "hold",
+            Status::Dropped => "dropped",
+            Status::PlanToWatch => "planned",
+        };
+
+        Self {
+            title: &data.info.title_romaji,
+            mappings: [NotifyMoeMapping {
+                service: "anilist/anime",
+                service_id: data.info.id.to_string(),
+            }],
+            status,
+            episodes: entry.watched_episodes(),
+            rating: NotifyMoeRating {
+                overall: entry.score().map_or(0.0, |score| f32::from(score) / 10.0),
+            },
+            notes: "",
+            rewatch_count: entry.times_rewatched(),
+            private: false,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NotifyMoeMapping {
+    service: &'static str,
+    service_id: String,
+}
+
+#[derive(Serialize)]
+struct NotifyMoeRating {
+    /// The rating from 0 to 10, where 0 means it hasn't been rated.
+    overall: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_format() {
+        assert_eq!(
+            "anime-planet".parse::<Format>().unwrap(),
+            Format::AnimePlanet
+        );
+        assert_eq!("Notify.moe".parse::<Format>().unwrap(), Format::NotifyMoe);
+        assert!("mal".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn anime_planet_ratings() {
+        assert_eq!(anime_planet_half_stars(100), 10);
+        assert_eq!(anime_planet_half_stars(75), 8);
+        assert_eq!(anime_planet_half_stars(70), 7);
+        assert_eq!(anime_planet_half_stars(1), 1);
+    }
+}
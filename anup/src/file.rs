@@ -106,6 +106,7 @@ impl FileFormat {
 pub enum SaveDir {
     Config,
     LocalData,
+    Cache,
 }
 
 impl SaveDir {
@@ -123,9 +124,16 @@ impl SaveDir {
             dir
         });
 
+        static CACHE_PATH: Lazy<PathBuf> = Lazy::new(|| {
+            let mut dir = dirs_next::cache_dir().unwrap_or_else(|| PathBuf::from("~/.cache/"));
+            dir.push(env!("CARGO_PKG_NAME"));
+            dir
+        });
+
         match self {
             SaveDir::Config => CONFIG_PATH.as_ref(),
             SaveDir::LocalData => LOCAL_DATA_PATH.as_ref(),
+            SaveDir::Cache => CACHE_PATH.as_ref(),
         }
     }
 
@@ -0,0 +1,115 @@
+use crate::config::Config;
+use crate::database::Database;
+use crate::file::{FileFormat, SaveDir, SerializedFile};
+use crate::series::history::WatchEvent;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone};
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+/// The number of episodes watched during a week, and how many were meant to be.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WeeklyGoal {
+    pub watched: u32,
+    pub goal: u32,
+}
+
+impl WeeklyGoal {
+    /// Load the progress made toward the goal in the config during the current week.
+    ///
+    /// Returns `Ok(None)` if no goal is set.
+    pub fn load(config: &Config, db: &Database) -> diesel::QueryResult<Option<Self>> {
+        Self::load_for_week(config, db, week_start(Local::now()))
+    }
+
+    /// Load the progress made toward the goal in the config during the week starting at `start`.
+    ///
+    /// Returns `Ok(None)` if no goal is set.
+    pub fn load_for_week(
+        config: &Config,
+        db: &Database,
+        start: DateTime<Local>,
+    ) -> diesel::QueryResult<Option<Self>> {
+        let goal = config.goal.weekly_episodes;
+
+        if goal == 0 {
+            return Ok(None);
+        }
+
+        let end = week_start(start + Duration::days(7));
+        let watched = WatchEvent::count_between(db, start.timestamp(), end.timestamp())?;
+
+        Ok(Some(Self {
+            watched: watched as u32,
+            goal,
+        }))
+    }
+
+    #[inline(always)]
+    pub fn is_met(self) -> bool {
+        self.watched >= self.goal
+    }
+}
+
+impl fmt::Display for WeeklyGoal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.watched, self.goal)
+    }
+}
+
+/// Returns the first moment of the week `instant` falls in, which starts on Monday in the local time zone.
+pub fn week_start(instant: DateTime<Local>) -> DateTime<Local> {
+    let monday = monday_of(instant.date().naive_local());
+
+    // Midnight can be skipped by a time zone change, so the week starts at the earliest time that does exist
+    (0..24)
+        .find_map(|hour| {
+            Local
+                .from_local_datetime(&monday.and_hms(hour, 0, 0))
+                .earliest()
+        })
+        .unwrap_or(instant)
+}
+
+fn monday_of(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday().into())
+}
+
+/// The last week the daemon sent a summary of the weekly goal for, so each week is only summarized once.
+#[derive(Default, Deserialize, Serialize)]
+pub struct GoalSummary {
+    /// When the last summarized week started, as a UNIX timestamp. This is 0 until the first week is recorded.
+    pub week_start: i64,
+}
+
+impl SerializedFile for GoalSummary {
+    fn filename() -> &'static str {
+        "goal_summary"
+    }
+
+    fn save_dir() -> SaveDir {
+        SaveDir::LocalData
+    }
+
+    fn format() -> FileFormat {
+        FileFormat::Binary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weeks_start_on_monday() {
+        let monday = NaiveDate::from_ymd(2021, 3, 15);
+
+        for offset in 0..7 {
+            assert_eq!(monday_of(monday + Duration::days(offset)), monday);
+        }
+
+        assert_eq!(
+            monday_of(monday - Duration::days(1)),
+            NaiveDate::from_ymd(2021, 3, 8)
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use crate::database::Database;
+use anyhow::{Context, Result};
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use std::fmt;
+
+/// A table with rows that belong to a series.
+struct SeriesTable {
+    name: &'static str,
+    /// The column that holds the ID of the series a row belongs to.
+    column: &'static str,
+    /// Whether rows are kept while their series is in the trash, so they're still there if it's restored.
+    kept_in_trash: bool,
+}
+
+impl SeriesTable {
+    const fn new(name: &'static str, column: &'static str, kept_in_trash: bool) -> Self {
+        Self {
+            name,
+            column,
+            kept_in_trash,
+        }
+    }
+
+    /// Returns the condition that matches rows whose series no longer exists.
+    fn orphaned(&self) -> String {
+        let condition = format!("{} NOT IN (SELECT id FROM series_configs)", self.column);
+
+        if self.kept_in_trash {
+            format!(
+                "{} AND {} NOT IN (SELECT id FROM deleted_series)",
+                condition, self.column
+            )
+        } else {
+            condition
+        }
+    }
+}
+
+/// The info of a series is copied to the trash along with its config, so it's the only table that isn't kept there.
+const SERIES_TABLES: [SeriesTable; 12] = [
+    SeriesTable::new("series_info", "id", false),
+    SeriesTable::new("series_entries", "id", true),
+    SeriesTable::new("sync_failures", "id", true),
+    SeriesTable::new("series_aliases", "series_id", true),
+    SeriesTable::new("series_collections", "series_id", true),
+    SeriesTable::new("filler_episodes", "series_id", true),
+    SeriesTable::new("episode_overrides", "series_id", true),
+    SeriesTable::new("episode_titles", "series_id", true),
+    SeriesTable::new("watch_history", "series_id", true),
+    SeriesTable::new("playback_sessions", "series_id", true),
+    SeriesTable::new("resume_positions", "series_id", true),
+    SeriesTable::new("active_playbacks", "series_id", true),
+];
+
+/// Rows that were left behind by a series that no longer exists, such as when the program was closed partway through
+/// removing one or the database was edited by hand.
+///
+/// Series in the trash still have their rows, so they aren't counted.
+pub struct Orphans(Vec<(&'static str, i64)>);
+
+impl Orphans {
+    /// Count the orphaned rows of every table.
+    pub fn find(db: &Database) -> diesel::QueryResult<Self> {
+        #[derive(QueryableByName)]
+        struct Count {
+            #[sql_type = "BigInt"]
+            count: i64,
+        }
+
+        let mut counts = Vec::new();
+
+        for table in &SERIES_TABLES {
+            let query = format!(
+                "SELECT COUNT(*) AS count FROM {} WHERE {}",
+                table.name,
+                table.orphaned()
+            );
+
+            let count = diesel::sql_query(query)
+                .get_result::<Count>(db.conn())?
+                .count;
+
+            if count > 0 {
+                counts.push((table.name, count));
+            }
+        }
+
+        Ok(Self(counts))
+    }
+
+    /// Remove every orphaned row, and return the number of rows that were removed.
+    pub fn remove(db: &Database) -> Result<usize> {
+        db.write_transaction(|| {
+            let mut removed = 0;
+
+            for table in &SERIES_TABLES {
+                let query = format!("DELETE FROM {} WHERE {}", table.name, table.orphaned());
+                removed += diesel::sql_query(query).execute(db.conn())?;
+            }
+
+            Ok(removed)
+        })
+        .context("removing orphaned rows")
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of orphaned rows across every table.
+    pub fn total(&self) -> i64 {
+        self.0.iter().map(|(_, count)| count).sum()
+    }
+}
+
+impl fmt::Display for Orphans {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (table, count)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "{} in {}", count, table)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+use crate::file::SaveDir;
+use anyhow::{anyhow, Context, Result};
+use std::fmt;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream as StdUnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader as AsyncBufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::time;
+
+/// How long to wait for a request to be handled before giving up on it.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long to wait for a connected client to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// A program that listens for requests from other instances of the program.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Endpoint {
+    Tui,
+    Daemon,
+}
+
+impl Endpoint {
+    fn socket_path(self) -> PathBuf {
+        let filename = match self {
+            Self::Tui => "tui.sock",
+            Self::Daemon => "daemon.sock",
+        };
+
+        SaveDir::LocalData.dir_path().join(filename)
+    }
+}
+
+/// A command that can be sent to a running TUI or daemon.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Request {
+    /// Play the next episode of the last watched series.
+    PlayNext,
+    /// Take over the timer of an episode that's being played by a TUI that is closing.
+    AdoptPlayback(AdoptedPlayback),
+    /// Count the specified episode of the series with the specified ID as watched, if it's still the next one.
+    CompleteEpisode(i32, i16),
+    /// Load the series with the specified ID from the database again, since another instance of the program changed it.
+    ReloadSeries(i32),
+    /// Sync every list entry that has changes right away.
+    Sync,
+}
+
+impl Request {
+    fn parse(value: &str) -> Option<Self> {
+        let mut args = value.split_whitespace();
+
+        let request = match args.next()? {
+            "play-next" => Self::PlayNext,
+            "adopt-playback" => Self::AdoptPlayback(AdoptedPlayback {
+                series_id: args.next()?.parse().ok()?,
+                episode: args.next()?.parse().ok()?,
+                started_at: args.next()?.parse().ok()?,
+                progress_at: args.next()?.parse().ok()?,
+                pid: args.next()?.parse().ok()?,
+            }),
+            "complete-episode" => {
+                Self::CompleteEpisode(args.next()?.parse().ok()?, args.next()?.parse().ok()?)
+            }
+            "reload-series" => Self::ReloadSeries(args.next()?.parse().ok()?),
+            "sync" => Self::Sync,
+            _ => return None,
+        };
+
+        // Extra arguments mean the request came from a version of the program that does something else with it
+        if args.next().is_some() {
+            return None;
+        }
+
+        Some(request)
+    }
+}
+
+impl fmt::Display for Request {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::PlayNext => write!(f, "play-next"),
+            Self::AdoptPlayback(playback) => write!(
+                f,
+                "adopt-playback {} {} {} {} {}",
+                playback.series_id,
+                playback.episode,
+                playback.started_at,
+                playback.progress_at,
+                playback.pid
+            ),
+            Self::CompleteEpisode(id, episode) => write!(f, "complete-episode {} {}", id, episode),
+            Self::ReloadSeries(id) => write!(f, "reload-series {}", id),
+            Self::Sync => write!(f, "sync"),
+        }
+    }
+}
+
+/// An episode whose player is still open, handed over to the daemon when the TUI that started it closes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AdoptedPlayback {
+    pub series_id: i32,
+    pub episode: i16,
+    /// When the player was started, as a UNIX timestamp.
+    pub started_at: i64,
+    /// When enough of the episode will have been played to count it as watched, as a UNIX timestamp.
+    pub progress_at: i64,
+    /// The ID of the player's process.
+    pub pid: u32,
+}
+
+/// Returns true if a program is listening for requests at `endpoint`.
+pub fn is_listening(endpoint: Endpoint) -> bool {
+    StdUnixStream::connect(endpoint.socket_path()).is_ok()
+}
+
+/// Send `request` to the running program at `endpoint` and wait for it to be handled.
+///
+/// Returns `Ok(false)` if nothing is listening at `endpoint`.
+pub fn send(endpoint: Endpoint, request: Request) -> Result<bool> {
+    let mut stream = match StdUnixStream::connect(endpoint.socket_path()) {
+        Ok(stream) => stream,
+        Err(_) => return Ok(false),
+    };
+
+    stream
+        .set_read_timeout(Some(RESPONSE_TIMEOUT))
+        .context("setting response timeout")?;
+
+    writeln!(stream, "{}", request).context("sending request")?;
+
+    let mut response = String::new();
+
+    BufReader::new(stream)
+        .read_line(&mut response)
+        .context("reading response")?;
+
+    match response.trim_end() {
+        "ok" => Ok(true),
+        "" => Err(anyhow!(
+            "the connection was closed before a response was sent"
+        )),
+        err => Err(anyhow!("{}", err.strip_prefix("error: ").unwrap_or(err))),
+    }
+}
+
+/// Listens for requests sent by other instances of the program with `send`.
+///
+/// The socket is removed once dropped.
+pub struct Server {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+impl Server {
+    /// Start listening for requests sent to `endpoint`.
+    ///
+    /// Returns `Ok(None)` if another instance of the program is already listening at `endpoint`.
+    pub fn bind(endpoint: Endpoint) -> Result<Option<Self>> {
+        let path = endpoint.socket_path();
+
+        if path.exists() {
+            if StdUnixStream::connect(&path).is_ok() {
+                return Ok(None);
+            }
+
+            // The socket was left behind by a program that didn't exit cleanly
+            fs::remove_file(&path).context("removing stale socket")?;
+        }
+
+        SaveDir::LocalData.validated_dir_path()?;
+
+        let listener = UnixListener::bind(&path).context("binding socket")?;
+
+        Ok(Some(Self { listener, path }))
+    }
+
+    /// Wait for the next request.
+    pub async fn accept(&self) -> Result<(Request, Connection)> {
+        let (stream, _) = self
+            .listener
+            .accept()
+            .await
+            .context("accepting connection")?;
+
+        let mut stream = AsyncBufReader::new(stream);
+        let mut line = String::new();
+
+        time::timeout(REQUEST_TIMEOUT, stream.read_line(&mut line))
+            .await
+            .context("timed out waiting for request")?
+            .context("reading request")?;
+
+        let request =
+            Request::parse(&line).ok_or_else(|| anyhow!("unknown request: {}", line.trim()))?;
+
+        let conn = Connection {
+            stream: stream.into_inner(),
+        };
+
+        Ok((request, conn))
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        fs::remove_file(&self.path).ok();
+    }
+}
+
+/// A connection to a client that is waiting for its request to be handled.
+#[derive(Debug)]
+pub struct Connection {
+    stream: UnixStream,
+}
+
+impl Connection {
+    /// Tell the client whether its request was handled successfully.
+    pub async fn respond(mut self, result: &Result<()>) -> Result<()> {
+        let response = match result {
+            Ok(()) => "ok\n".into(),
+            // Responses are a single line
+            Err(err) => format!("error: {}\n", format!("{:#}", err).replace('\n', " ")),
+        };
+
+        self.stream
+            .write_all(response.as_bytes())
+            .await
+            .context("sending response")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_round_trip() {
+        let playback = AdoptedPlayback {
+            series_id: 21,
+            episode: 4,
+            started_at: 1_600_000_000,
+            progress_at: 1_600_000_720,
+            pid: 1234,
+        };
+
+        let requests = [
+            Request::PlayNext,
+            Request::AdoptPlayback(playback),
+            Request::CompleteEpisode(21, 4),
+            Request::ReloadSeries(21),
+            Request::Sync,
+        ];
+
+        for request in requests {
+            assert_eq!(Request::parse(&request.to_string()), Some(request));
+        }
+
+        assert_eq!(Request::parse("play-next\n"), Some(Request::PlayNext));
+        assert_eq!(Request::parse("quit"), None);
+        assert_eq!(Request::parse("complete-episode 21"), None);
+        assert_eq!(Request::parse("sync now"), None);
+    }
+}
@@ -6,7 +6,9 @@ use serde::{
 };
 use smallvec::SmallVec;
 use std::{
+    borrow::Cow,
     convert::{TryFrom, TryInto},
+    fmt,
     ops::Deref,
     result,
 };
@@ -26,6 +28,10 @@ impl Key {
     pub fn ctrl_pressed(&self) -> bool {
         self.0.modifiers.contains(KeyModifiers::CONTROL)
     }
+
+    pub fn alt_pressed(&self) -> bool {
+        self.0.modifiers.contains(KeyModifiers::ALT)
+    }
 }
 
 impl Deref for Key {
@@ -72,6 +78,7 @@ impl TryFrom<&str> for Key {
             "insert" => KeyCode::Insert,
             "unknown" => KeyCode::Null,
             "escape" => KeyCode::Esc,
+            "space" => KeyCode::Char(' '),
             key if key.len() == 1 && key.is_ascii() => {
                 let bytes = key.as_bytes();
                 KeyCode::Char(bytes[0] as char)
@@ -99,8 +106,6 @@ impl<'de> Deserialize<'de> for Key {
     where
         D: Deserializer<'de>,
     {
-        use std::fmt;
-
         struct KeyVisitor;
 
         impl<'de> Visitor<'de> for KeyVisitor {
@@ -116,9 +121,55 @@ impl<'de> Deserialize<'de> for Key {
             {
                 value.try_into().map_err(E::custom)
             }
+
+            // Older versions wrote character keys as a char
+            fn visit_char<E>(self, value: char) -> result::Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                self.visit_str(value.encode_utf8(&mut [0; 4]))
+            }
         }
 
-        de.deserialize_str(KeyVisitor)
+        de.deserialize_any(KeyVisitor)
+    }
+}
+
+impl fmt::Display for Key {
+    /// Write the key in the same form it's parsed from, such as `ctrl+b`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let modifier = match self.0.modifiers {
+            KeyModifiers::CONTROL => "ctrl+",
+            KeyModifiers::SHIFT => "shift+",
+            KeyModifiers::ALT => "alt+",
+            _ => "",
+        };
+
+        let key: Cow<str> = match self.0.code {
+            KeyCode::Backspace => "backspace".into(),
+            KeyCode::Enter => "enter".into(),
+            KeyCode::Left => "left".into(),
+            KeyCode::Right => "right".into(),
+            KeyCode::Up => "up".into(),
+            KeyCode::Down => "down".into(),
+            KeyCode::Home => "home".into(),
+            KeyCode::End => "end".into(),
+            KeyCode::PageUp => "pageup".into(),
+            KeyCode::PageDown => "pagedown".into(),
+            KeyCode::Tab => "tab".into(),
+            KeyCode::BackTab => "backtab".into(),
+            KeyCode::Delete => "delete".into(),
+            KeyCode::Insert => "insert".into(),
+            KeyCode::F(key) => format!("f{}", key).into(),
+            // Spaces are trimmed when keys are parsed
+            KeyCode::Char(' ') => "space".into(),
+            // Keys are always deserialized from strings, so characters can't be serialized as a char
+            KeyCode::Char(key) => key.to_string().into(),
+            KeyCode::Null => "unknown".into(),
+            KeyCode::Esc => "escape".into(),
+        };
+
+        write!(f, "{}{}", modifier, key)
     }
 }
 
@@ -127,32 +178,74 @@ impl Serialize for Key {
     where
         S: Serializer,
     {
-        match self.0.code {
-            KeyCode::Backspace => se.serialize_str("backspace"),
-            KeyCode::Enter => se.serialize_str("enter"),
-            KeyCode::Left => se.serialize_str("left"),
-            KeyCode::Right => se.serialize_str("right"),
-            KeyCode::Up => se.serialize_str("up"),
-            KeyCode::Down => se.serialize_str("down"),
-            KeyCode::Home => se.serialize_str("home"),
-            KeyCode::End => se.serialize_str("end"),
-            KeyCode::PageUp => se.serialize_str("pageup"),
-            KeyCode::PageDown => se.serialize_str("pagedown"),
-            KeyCode::Tab => se.serialize_str("tab"),
-            KeyCode::BackTab => se.serialize_str("backtab"),
-            KeyCode::Delete => se.serialize_str("delete"),
-            KeyCode::Insert => se.serialize_str("insert"),
-            KeyCode::F(key) => se.serialize_str(&format!("f{}", key)),
-            KeyCode::Char(key) => se.serialize_char(key),
-            KeyCode::Null => se.serialize_str("unknown"),
-            KeyCode::Esc => se.serialize_str("escape"),
+        se.serialize_str(&self.to_string())
+    }
+}
+
+/// Keys that have to be pressed one after another, such as `g` and then `s`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeySequence(SmallVec<[Key; 3]>);
+
+impl KeySequence {
+    #[inline(always)]
+    pub fn keys(&self) -> &[Key] {
+        &self.0
+    }
+
+    /// Returns true if the sequence begins with every key in `keys`.
+    #[inline(always)]
+    pub fn starts_with(&self, keys: &[Key]) -> bool {
+        self.0.starts_with(keys)
+    }
+}
+
+impl TryFrom<&str> for KeySequence {
+    type Error = anyhow::Error;
+
+    /// Parse keys separated by spaces, such as `g s` or `ctrl+x f1`.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let keys = value
+            .split_whitespace()
+            .map(Key::try_from)
+            .collect::<Result<SmallVec<_>>>()?;
+
+        if keys.is_empty() {
+            return Err(anyhow!("no keys specified"));
         }
+
+        Ok(Self(keys))
+    }
+}
+
+impl<'de> Deserialize<'de> for KeySequence {
+    fn deserialize<D>(de: D) -> result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(de)?;
+        value.as_str().try_into().map_err(de::Error::custom)
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let keys = self.0.iter().map(Key::to_string).collect::<Vec<_>>();
+        write!(f, "{}", keys.join(" "))
+    }
+}
+
+impl Serialize for KeySequence {
+    fn serialize<S>(&self, se: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        se.serialize_str(&self.to_string())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Key;
+    use super::{Key, KeySequence};
     use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
     use std::convert::TryInto;
 
@@ -178,6 +271,34 @@ mod tests {
         test_key!("f1", KeyCode::F(1) => KeyModifiers::NONE);
     }
 
+    #[test]
+    fn serialize_keys() {
+        for &key in &["j", "enter", "ctrl+b", "shift+f12", ":", "space"] {
+            let parsed: Key = key.try_into().unwrap();
+            let serialized = serde_json::to_string(&parsed).unwrap();
+            assert_eq!(serialized, format!("\"{}\"", key));
+
+            let deserialized: Key = serde_json::from_str(&serialized).unwrap();
+            assert_eq!(deserialized, parsed);
+        }
+    }
+
+    #[test]
+    fn key_sequences() {
+        let sequence: KeySequence = "g  ctrl+s".try_into().unwrap();
+        let g = Key::from_code(KeyCode::Char('g'));
+
+        assert_eq!(sequence.keys().len(), 2);
+        assert!(sequence.starts_with(&[g]));
+        assert!(!sequence.starts_with(&[Key::from_code(KeyCode::Char('s'))]));
+
+        let serialized = serde_json::to_string(&sequence).unwrap();
+        assert_eq!(serialized, "\"g ctrl+s\"");
+
+        let empty: Result<KeySequence, _> = " ".try_into();
+        assert!(empty.is_err());
+    }
+
     #[test]
     #[should_panic]
     fn invalid_keys() {
@@ -0,0 +1,261 @@
+use crate::config::Config;
+use crate::database::SharedStr;
+use crate::series::config::SeriesConfig;
+use crate::series::{LoadedSeries, Series};
+use crate::{open_database, Args};
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+use std::cmp::Ordering;
+use std::str::FromStr;
+use unicode_width::UnicodeWidthStr;
+
+#[derive(FromArgs)]
+/// Print every added series as a table.
+#[argh(subcommand, name = "list")]
+pub struct ListArgs {
+    /// comma separated columns to show, from nickname, title, progress, score, status, and disk
+    #[argh(option, short = 'c', default = "Columns::default()")]
+    columns: Columns,
+
+    /// the column to sort by
+    #[argh(option, default = "Column::Title")]
+    sort: Column,
+
+    /// reverse the sort order
+    #[argh(switch, short = 'r')]
+    reverse: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Column {
+    Nickname,
+    Title,
+    Progress,
+    Score,
+    Status,
+    Disk,
+}
+
+impl Column {
+    fn header(self) -> &'static str {
+        match self {
+            Self::Nickname => "Nickname",
+            Self::Title => "Title",
+            Self::Progress => "Progress",
+            Self::Score => "Score",
+            Self::Status => "Status",
+            Self::Disk => "On Disk",
+        }
+    }
+}
+
+impl FromStr for Column {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "nickname" | "name" => Ok(Self::Nickname),
+            "title" => Ok(Self::Title),
+            "progress" => Ok(Self::Progress),
+            "score" => Ok(Self::Score),
+            "status" => Ok(Self::Status),
+            "disk" => Ok(Self::Disk),
+            _ => Err(anyhow!("unknown column: {}", value)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Columns(Vec<Column>);
+
+impl Default for Columns {
+    fn default() -> Self {
+        Self(vec![
+            Column::Title,
+            Column::Progress,
+            Column::Score,
+            Column::Status,
+            Column::Disk,
+        ])
+    }
+}
+
+impl FromStr for Columns {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let columns = value
+            .split(',')
+            .filter(|column| !column.trim().is_empty())
+            .map(Column::from_str)
+            .collect::<Result<Vec<_>>>()?;
+
+        if columns.is_empty() {
+            return Err(anyhow!("at least one column must be specified"));
+        }
+
+        Ok(Self(columns))
+    }
+}
+
+/// The values of a single series in the table.
+struct Row {
+    nickname: SharedStr,
+    title: Option<SharedStr>,
+    watched: i16,
+    episodes: i16,
+    score: Option<i16>,
+    status: Option<&'static str>,
+    status_order: Option<u8>,
+    on_disk: Option<usize>,
+}
+
+impl Row {
+    fn new(series: &LoadedSeries) -> Self {
+        let data = series.data();
+
+        let on_disk = match series {
+            LoadedSeries::Complete(Series { episodes, .. }) => Some(episodes.len()),
+            LoadedSeries::Partial(_, _) | LoadedSeries::None(_, _) => None,
+        };
+
+        Self {
+            nickname: series.config().nickname.clone(),
+            title: data.map(|data| data.title().clone()),
+            watched: data.map_or(0, |data| data.entry.watched_episodes()),
+            episodes: data.map_or(0, |data| data.info.episodes),
+            score: data.and_then(|data| data.entry.score()),
+            status: data.map(|data| data.entry.status().into()),
+            status_order: data.map(|data| data.entry.status() as u8),
+            on_disk,
+        }
+    }
+
+    fn value(&self, column: Column) -> String {
+        match column {
+            Column::Nickname => self.nickname.to_string(),
+            Column::Title => self.title.as_ref().unwrap_or(&self.nickname).to_string(),
+            // Series with an unknown number of episodes report 0
+            Column::Progress if self.episodes > 0 => format!("{}/{}", self.watched, self.episodes),
+            Column::Progress => format!("{}/?", self.watched),
+            Column::Score => self
+                .score
+                .map_or_else(|| "-".into(), |score| score.to_string()),
+            Column::Status => self.status.unwrap_or("-").into(),
+            Column::Disk => self
+                .on_disk
+                .map_or_else(|| "-".into(), |on_disk| on_disk.to_string()),
+        }
+    }
+
+    fn compare(&self, other: &Self, column: Column) -> Ordering {
+        let ordering = match column {
+            Column::Nickname => self.nickname.cmp(&other.nickname),
+            Column::Title => {
+                let title = |row: &Self| row.value(Column::Title).to_lowercase();
+                title(self).cmp(&title(other))
+            }
+            Column::Progress => self.watched.cmp(&other.watched),
+            // Series with the highest score should come first
+            Column::Score => other.score.cmp(&self.score),
+            Column::Status => self.status_order.cmp(&other.status_order),
+            Column::Disk => other.on_disk.cmp(&self.on_disk),
+        };
+
+        ordering.then_with(|| self.nickname.cmp(&other.nickname))
+    }
+}
+
+pub fn run(args: &Args, list_args: &ListArgs) -> Result<()> {
+    let config = Config::load_and_report()?;
+    let db = open_database(args)?;
+
+    let mut rows = SeriesConfig::load_all(&db)
+        .context("loading series configs")?
+        .into_iter()
+        .map(|series_config| Row::new(&Series::load_from_config(series_config, &config, &db)))
+        .collect::<Vec<_>>();
+
+    if rows.is_empty() {
+        println!("no series have been added");
+        return Ok(());
+    }
+
+    rows.sort_unstable_by(|x, y| x.compare(y, list_args.sort));
+
+    if list_args.reverse {
+        rows.reverse();
+    }
+
+    let columns = &list_args.columns.0;
+
+    let table = rows
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|&column| row.value(column))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let widths = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            table
+                .iter()
+                .map(|values| values[i].width())
+                .chain(std::iter::once(column.header().len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+
+    let headers = columns.iter().map(|column| column.header().to_string());
+
+    print_row(headers, &widths);
+
+    let separator = widths.iter().map(|&width| "-".repeat(width));
+    print_row(separator, &widths);
+
+    for values in table {
+        print_row(values.into_iter(), &widths);
+    }
+
+    Ok(())
+}
+
+/// Print `values` padded to their column's width, without trailing whitespace.
+fn print_row<I>(values: I, widths: &[usize])
+where
+    I: Iterator<Item = String>,
+{
+    let line = values
+        .zip(widths)
+        .map(|(value, &width)| {
+            // The width of the value is calculated manually to account for wide characters
+            let padding = width.saturating_sub(value.width());
+            format!("{}{}", value, " ".repeat(padding))
+        })
+        .collect::<Vec<_>>()
+        .join("  ");
+
+    println!("{}", line.trim_end());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_columns() {
+        assert_eq!(
+            "title, progress,DISK".parse::<Columns>().unwrap(),
+            Columns(vec![Column::Title, Column::Progress, Column::Disk])
+        );
+
+        assert!("title,eps".parse::<Columns>().is_err());
+        assert!(",".parse::<Columns>().is_err());
+    }
+}
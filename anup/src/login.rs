@@ -0,0 +1,112 @@
+use crate::file::SerializedFile;
+use crate::user::{RemoteType, UserInfo, Users};
+use crate::{crypt, Args};
+use anime::remote::anilist::{self, Auth};
+use anime::remote::AccessToken;
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+use crossterm::tty::IsTty;
+use std::io;
+
+#[derive(FromArgs)]
+/// Add an account with a token that can be obtained from any device, such as when a browser isn't available.
+#[argh(subcommand, name = "login")]
+pub struct LoginArgs {
+    /// the service to log in to
+    #[argh(positional, default = "RemoteType::AniList")]
+    service: RemoteType,
+}
+
+#[derive(FromArgs)]
+/// Remove an account.
+#[argh(subcommand, name = "logout")]
+pub struct LogoutArgs {
+    /// the name of the account to remove, instead of the most recently used one of the selected profile
+    #[argh(positional)]
+    username: Option<String>,
+}
+
+pub fn run_login(args: &Args, login_args: &LoginArgs) -> Result<()> {
+    let mut users = Users::load_or_create().context("loading accounts")?;
+
+    let url = match login_args.service {
+        RemoteType::AniList => anilist::auth_url(crate::ANILIST_CLIENT_ID),
+    };
+
+    // AniList doesn't support device codes, so the token has to be brought back from wherever the URL was opened
+    println!(
+        "open this URL on any device and log in to get a token:\n{}\n",
+        url
+    );
+
+    let token = read_token()?;
+
+    if token.is_empty() {
+        return Err(anyhow!("no token was entered"));
+    }
+
+    let token = AccessToken::encode(token);
+
+    let username = match login_args.service {
+        RemoteType::AniList => {
+            let auth = Auth::retrieve(token.clone()).context("failed to log in with token")?;
+            auth.user.name
+        }
+    };
+
+    let info = UserInfo::new(login_args.service, &username);
+
+    users.add(info, token, args.profile.as_str());
+    users.save().context("saving accounts")?;
+
+    println!(
+        "logged in to {} as {} in the {} profile",
+        login_args.service.as_str(),
+        username,
+        args.profile
+    );
+
+    Ok(())
+}
+
+pub fn run_logout(args: &Args, logout_args: &LogoutArgs) -> Result<()> {
+    let mut users = Users::load_or_create().context("loading accounts")?;
+
+    let user = match &logout_args.username {
+        Some(username) => users
+            .sorted()
+            .into_iter()
+            .find(|(user, _)| user.username.eq_ignore_ascii_case(username))
+            .map(|(user, _)| user.clone())
+            .ok_or_else(|| anyhow!("no account named {} was found", username))?,
+        None => users
+            .last_used(&args.profile)
+            .map(|(user, _)| user.clone())
+            .ok_or_else(|| anyhow!("no accounts found in the {} profile", args.profile))?,
+    };
+
+    users.remove(&user);
+    users.save().context("saving accounts")?;
+
+    println!(
+        "removed the {} account {}",
+        user.service.as_str(),
+        user.username
+    );
+
+    Ok(())
+}
+
+/// Read a token from the terminal without echoing it, or from standard input when it's piped in.
+fn read_token() -> Result<String> {
+    let stdin = io::stdin();
+
+    if stdin.is_tty() {
+        return crypt::prompt_hidden("Token: ").map(|token| token.trim().to_string());
+    }
+
+    let mut token = String::new();
+    stdin.read_line(&mut token).context("reading token")?;
+
+    Ok(token.trim().to_string())
+}
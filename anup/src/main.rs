@@ -13,36 +13,80 @@
 #[macro_use]
 extern crate diesel;
 
+mod audit_log;
+mod backup;
+mod cache;
+mod cleanup;
+mod cmd;
 mod config;
+mod conflict;
+mod crypt;
+mod daemon;
 mod database;
+mod demo;
+mod digest;
 mod err;
+mod export;
 mod file;
+mod goal;
+mod integrity;
+mod ipc;
 mod key;
+mod list;
+mod login;
+mod mpv;
+mod next;
+mod plain;
+mod plan;
+mod play_next;
+mod preflight;
+mod private_session;
+mod query;
 mod remote;
+mod report;
+mod saved_event;
+mod seeding;
 mod series;
+mod serve;
+mod set;
+mod settings;
+mod stats;
+mod trakt;
+mod trash;
 mod tui;
+mod update;
 mod user;
 mod util;
+mod watch_party;
 
-use crate::config::Config;
+use crate::config::{Config, ExtraEpisodes};
 use crate::database::Database;
+use crate::err::Failure;
 use crate::file::SerializedFile;
+use crate::ipc::{Endpoint, Request};
+use crate::private_session::PrivateSession;
 use crate::series::config::SeriesConfig;
 use crate::series::entry::SeriesEntry;
+use crate::series::history::{ActivePlayback, PlaybackTimer};
 use crate::series::info::SeriesInfo;
-use crate::series::{LastWatched, LoadedSeries, Series};
+use crate::series::sync_failure::SyncFailure;
+use crate::series::{LastWatched, LoadedSeries, Series, SeriesData};
 use crate::user::Users;
-use anime::remote::Remote;
+use crate::util::SleepDetector;
+use crate::watch_party::WatchParty;
+use anime::remote::{Remote, RemoteService, SeriesID};
 use anyhow::{anyhow, Context, Result};
 use argh::FromArgs;
 use chrono::Utc;
+use std::borrow::Cow;
 
 const ANILIST_CLIENT_ID: u32 = 427;
 
 #[derive(FromArgs)]
+#[allow(clippy::struct_excessive_bools)]
 /// Play, manage, and sync anime from the terminal.
 pub struct Args {
-    /// the nickname of the series to watch
+    /// the nickname or alias of the series to watch
     #[argh(positional)]
     pub series: Option<String>,
 
@@ -57,25 +101,124 @@ pub struct Args {
     /// syncronize changes made while offline
     #[argh(switch)]
     pub sync: bool,
+
+    /// ask for commands one at a time and print plain text instead of running the TUI, which works better with screen readers
+    #[argh(switch)]
+    pub no_tui: bool,
+
+    /// try out the TUI with made up series and a pretend remote service, without using your own series or accounts
+    #[argh(switch)]
+    pub demo: bool,
+
+    /// with --demo, the number of milliseconds each request to the pretend remote service takes
+    #[argh(option, default = "0")]
+    pub demo_latency: u64,
+
+    /// with --demo, the percentage of requests to the pretend remote service that fail as if it were unavailable
+    #[argh(option, default = "0")]
+    pub demo_failure_rate: u8,
+
+    /// the profile to select accounts from
+    #[argh(option, default = "user::DEFAULT_PROFILE.into()")]
+    pub profile: String,
+
+    #[argh(subcommand)]
+    pub command: Option<Command>,
 }
 
-fn main() -> Result<()> {
-    let rt = tokio::runtime::Builder::new_current_thread()
+#[derive(FromArgs)]
+#[argh(subcommand)]
+pub enum Command {
+    Cache(cache::CacheArgs),
+    Cleanup(cleanup::CleanupArgs),
+    Cmd(cmd::CmdArgs),
+    Config(config::command::ConfigArgs),
+    Daemon(daemon::DaemonArgs),
+    Encrypt(crypt::EncryptArgs),
+    Decrypt(crypt::DecryptArgs),
+    Digest(digest::DigestArgs),
+    Export(export::ExportArgs),
+    ExportSettings(settings::ExportSettingsArgs),
+    ImportMpv(mpv::ImportMpvArgs),
+    ImportSettings(settings::ImportSettingsArgs),
+    ImportTrakt(trakt::ImportTraktArgs),
+    List(list::ListArgs),
+    MergeConflicts(conflict::MergeConflictsArgs),
+    Login(login::LoginArgs),
+    Logout(login::LogoutArgs),
+    Next(next::NextArgs),
+    Plan(plan::PlanArgs),
+    PlayNext(play_next::PlayNextArgs),
+    Preflight(preflight::PreflightArgs),
+    Query(query::QueryArgs),
+    Report(report::ReportArgs),
+    Serve(serve::ServeArgs),
+    Set(set::SetArgs),
+    Stats(stats::StatsArgs),
+}
+
+fn main() {
+    let result = tokio::runtime::Builder::new_current_thread()
         .enable_io()
         .enable_time()
         .build()
-        .context("failed to build async runtime")?;
+        .context("failed to build async runtime")
+        .and_then(|rt| rt.block_on(async { run().await }));
+
+    if let Err(err) = result {
+        let failure = Failure::of(&err);
 
-    rt.block_on(async { run().await })
+        // Having nothing to do isn't a problem, so it's only reported as one through the exit code
+        if failure == Some(Failure::NothingToDo) {
+            println!("{}", err);
+        } else {
+            eprintln!("Error: {:?}", err);
+        }
+
+        std::process::exit(Failure::exit_code(failure));
+    }
 }
 
 async fn run() -> Result<()> {
     let args: Args = argh::from_env();
 
+    if let Some(command) = &args.command {
+        return match command {
+            Command::Cache(cache_args) => cache::run(cache_args),
+            Command::Cleanup(cleanup_args) => cleanup::run(&args, cleanup_args),
+            Command::Cmd(cmd_args) => cmd::run(&args, cmd_args),
+            Command::Config(config_args) => config::command::run(config_args),
+            Command::Daemon(daemon_args) => daemon::run(&args, daemon_args).await,
+            Command::Encrypt(encrypt_args) => crypt::run_encrypt(encrypt_args),
+            Command::Decrypt(decrypt_args) => crypt::run_decrypt(decrypt_args),
+            Command::Digest(digest_args) => digest::run(&args, digest_args),
+            Command::Export(export_args) => export::run(&args, export_args),
+            Command::ExportSettings(export_args) => settings::run_export(&args, export_args),
+            Command::ImportMpv(import_args) => mpv::run(&args, import_args),
+            Command::ImportSettings(import_args) => settings::run_import(&args, import_args),
+            Command::ImportTrakt(import_args) => trakt::run(&args, import_args),
+            Command::List(list_args) => list::run(&args, list_args),
+            Command::MergeConflicts(merge_args) => conflict::run(&args, merge_args),
+            Command::Login(login_args) => login::run_login(&args, login_args),
+            Command::Logout(logout_args) => login::run_logout(&args, logout_args),
+            Command::Next(next_args) => next::run(&args, next_args),
+            Command::Plan(plan_args) => plan::run(&args, plan_args),
+            Command::PlayNext(play_args) => play_next::run(&args, play_args).await,
+            Command::Preflight(preflight_args) => preflight::run(&args, preflight_args),
+            Command::Query(query_args) => query::run(&args, query_args),
+            Command::Report(report_args) => report::run(&args, report_args),
+            Command::Serve(serve_args) => serve::run(&args, serve_args).await,
+            Command::Set(set_args) => set::run(&args, set_args),
+            Command::Stats(stats_args) => stats::run(&args, stats_args),
+        };
+    }
+
     if args.play_one {
         play_episode(&args).await
     } else if args.sync {
         sync(&args)
+    } else if args.no_tui {
+        plain::run(&args).await
     } else {
         tui::run(&args).await
     }
@@ -84,63 +227,224 @@ async fn run() -> Result<()> {
 /// Initialize a new remote service specified by `args`.
 ///
 /// If there are no users, returns Ok(None).
-fn init_remote(args: &Args) -> Result<Option<Remote>> {
+fn init_remote(args: &Args, config: &Config) -> Result<Option<Remote>> {
     use anime::remote::anilist::{AniList, Auth};
 
     if args.offline {
         Ok(Some(Remote::offline()))
     } else {
-        let token = match Users::load_or_create()?.take_last_used_token() {
+        let token = match Users::load_or_create()?.take_last_used_token(&args.profile) {
             Some(token) => token,
             None => return Ok(None),
         };
 
-        let auth = Auth::retrieve(token)?;
+        let auth = Auth::retrieve(token)?.with_score_rounding(config.score_rounding);
         Ok(Some(AniList::Authenticated(auth).into()))
     }
 }
 
+/// Open the database with the most recently used account of the selected profile.
+fn open_database(args: &Args) -> Result<Database> {
+    let mut db = Database::open().context("failed to open database")?;
+    let users = Users::load_or_create()?;
+
+    if let Some((user, _)) = users.last_used(&args.profile) {
+        db.set_account(user.account_id())
+            .context("setting database account")?;
+    }
+
+    SeriesEntry::recover_journal(&db).context("recovering list entries from the sync journal")?;
+
+    Ok(db)
+}
+
 fn sync(args: &Args) -> Result<()> {
+    const PROGRESS_WIDTH: usize = 20;
+
     if args.offline {
-        return Err(anyhow!("must be online to run this command"));
+        return Err(Failure::Offline.error("must be online to run this command"));
+    }
+
+    if PrivateSession::is_active() {
+        return Err(anyhow!(
+            "a private session is active\nend it with anup cmd \"private off\" to sync"
+        ));
     }
 
-    let db = Database::open().context("failed to open database")?;
+    let config = Config::load_and_report()?;
+    let db = open_database(args)?;
     let mut list_entries = SeriesEntry::entries_that_need_sync(&db)?;
 
     if list_entries.is_empty() {
-        return Ok(());
+        return Err(Failure::NothingToDo.error("no list entries need to be synced"));
     }
 
-    let remote =
-        init_remote(&args)?.ok_or_else(|| anyhow!("no users found\nadd one in the TUI"))?;
+    let remote = init_remote(args, &config)?
+        .ok_or_else(|| Failure::Auth.error("no users found\nadd one in the TUI"))?;
+
+    let total = list_entries.len();
+    let mut failed = Vec::new();
+    let mut skipped = Vec::new();
+    // Why each entry that wasn't synced failed, which is unknown for skipped entries
+    let mut reasons = Vec::new();
+
+    for (i, entry) in list_entries.iter_mut().enumerate() {
+        let info = SeriesInfo::load(&db, entry.id()).ok();
+
+        let title = info.as_ref().map_or_else(
+            || format!("anime with ID {}", entry.id()),
+            |info| info.title_preferred.to_string(),
+        );
+
+        let progress = format!(
+            "{} {}/{}",
+            util::progress_bar(i + 1, total, PROGRESS_WIDTH),
+            i + 1,
+            total
+        );
+
+        // Entries that keep failing (such as ones removed from the remote service) would otherwise be retried forever
+        if let Some(failure) = SyncFailure::load(&db, entry.id())? {
+            if failure.should_skip() {
+                eprintln!(
+                    "{} skipped {}: failed to sync {} times in a row",
+                    progress, title, failure.failures
+                );
+
+                skipped.push((title, failure.last_error));
+                reasons.push(None);
+                continue;
+            }
+        }
 
-    for entry in &mut list_entries {
-        match SeriesInfo::load(&db, entry.id()) {
-            Ok(info) => println!("{} is being synced..", info.title_preferred),
-            Err(err) => eprintln!(
-                "warning: failed to get info for anime with ID {}: {}",
-                entry.id(),
-                err
-            ),
+        // Entries that fail to sync stay marked as needing a sync, so they will be retried next time
+        let episodes = info.and_then(|info| info.total_episodes());
+
+        let result = entry
+            .sync_to_remote(&remote, &db, episodes)
+            .and_then(|()| entry.save(&db).map(|_| ()).map_err(Into::into));
+
+        match result {
+            Ok(()) => {
+                SyncFailure::clear(&db, entry.id())?;
+                println!("{} synced {}", progress, title);
+            }
+            Err(err) => {
+                if err::is_series_missing(&err) {
+                    match offer_relink(&db, &config, &remote, entry.id()) {
+                        Ok(true) => {
+                            println!("{} relinked and synced {}", progress, title);
+                            continue;
+                        }
+                        Ok(false) => (),
+                        Err(err) => eprintln!("failed to relink {}: {:#}", title, err),
+                    }
+                }
+
+                reasons.push(Failure::of(&err));
+                let err = format!("{:#}", err);
+
+                SyncFailure::record(&db, entry.id(), &err)?;
+                eprintln!("{} failed to sync {}: {}", progress, title, err);
+                failed.push(title);
+            }
         }
+    }
 
-        entry.sync_to_remote(&remote)?;
-        entry.save(&db)?;
+    let synced = total - failed.len() - skipped.len();
+    println!("synced {} of {} list entries", synced, total);
+
+    if !failed.is_empty() {
+        eprintln!("the following will be retried the next time changes are synced:");
+
+        for title in &failed {
+            eprintln!("  {}", title);
+        }
     }
 
-    Ok(())
+    if !skipped.is_empty() {
+        eprintln!(
+            "the following were skipped after failing to sync {} times in a row:",
+            SyncFailure::MAX_FAILURES
+        );
+
+        for (title, last_error) in &skipped {
+            eprintln!("  {} (last error: {})", title, last_error);
+        }
+
+        eprintln!("use the resetsync command in the TUI to retry them, or relink to link them to another series");
+    }
+
+    if reasons.is_empty() {
+        Ok(())
+    } else {
+        Err(unsynced_error(synced, &reasons))
+    }
+}
+
+/// Returns the error `--sync` fails with when some list entries weren't synced, given why each of them failed.
+fn unsynced_error(synced: usize, reasons: &[Option<Failure>]) -> anyhow::Error {
+    let message = format!("{} list entries were not synced", reasons.len());
+
+    if synced > 0 {
+        return Failure::Partial.error(message);
+    }
+
+    // When nothing was synced for the same reason, such as AniList being unreachable, that's what the sync failed with
+    let first = reasons.first().copied().flatten();
+
+    match first {
+        Some(reason) if reasons.iter().all(|&r| r == first) => reason.error(message),
+        _ => anyhow!(message),
+    }
+}
+
+/// Offer to relink the series with the specified `id` to the series that likely replaced it on `remote`.
+///
+/// Returns true if the series was relinked and its list entry was synced to the new series.
+fn offer_relink(db: &Database, config: &Config, remote: &Remote, id: i32) -> Result<bool> {
+    let info = SeriesInfo::load(db, id).context("loading series info")?;
+
+    let replacement = match info.find_replacement(config, remote)? {
+        Some(replacement) => replacement,
+        None => return Ok(false),
+    };
+
+    println!(
+        "{} no longer exists on AniList, but was likely merged into {} (ID {})",
+        info.title_preferred, replacement.title_preferred, replacement.id
+    );
+
+    if !confirm("relink it?")? {
+        return Ok(false);
+    }
+
+    let nickname = SeriesConfig::id_exists(db, id)
+        .ok_or_else(|| anyhow!("{} is no longer added", info.title_preferred))?;
+
+    let series_config = SeriesConfig::load_by_name(db, &nickname)?;
+    let mut data = SeriesData::load_from_config(db, Cow::Owned(series_config))?;
+
+    data.relink(replacement.id as SeriesID, db, remote)?;
+    data.sync_to_remote(remote, db)?;
+    data.save(db)?;
+
+    Ok(true)
 }
 
 async fn play_episode(args: &Args) -> Result<()> {
-    use anime::remote::Status;
+    let config = Config::load_and_report()?;
 
-    let config = Config::load_or_create()?;
-    let db = Database::open().context("failed to open database")?;
-    let mut last_watched = LastWatched::load()?;
+    let remote = init_remote(args, &config)?
+        .ok_or_else(|| Failure::Auth.error("no users found\nadd one in the TUI"))?;
+
+    play_last_watched_episode(args, &config, &remote).await
+}
 
-    let remote =
-        init_remote(&args)?.ok_or_else(|| anyhow!("no users found\nadd one in the TUI"))?;
+/// Play the next episode of the series specified by `args`, or the last watched series if none was specified.
+async fn play_last_watched_episode(args: &Args, config: &Config, remote: &Remote) -> Result<()> {
+    let db = open_database(args)?;
+    let mut last_watched = LastWatched::load()?;
 
     let desired_series = args
         .series
@@ -149,14 +453,14 @@ async fn play_episode(args: &Args) -> Result<()> {
         .ok_or_else(|| anyhow!("series name must be specified"))?;
 
     let mut series = {
-        let cfg = SeriesConfig::load_by_name(&db, desired_series).with_context(|| {
+        let cfg = SeriesConfig::load_by_name_or_alias(&db, desired_series).with_context(|| {
             format!(
                 "{} must be added to the program in the TUI first",
                 desired_series
             )
         })?;
 
-        match Series::load_from_config(cfg, &config, &db) {
+        match Series::load_from_config(cfg, config, &db) {
             LoadedSeries::Complete(series) => series,
             LoadedSeries::Partial(_, err) => return Err(err.into()),
             LoadedSeries::None(_, err) => return Err(err),
@@ -167,33 +471,176 @@ async fn play_episode(args: &Args) -> Result<()> {
         last_watched.save()?;
     }
 
-    series.begin_watching(&remote, &config, &db)?;
+    watch_next_episode(&mut series, config, &db, remote).await
+}
+
+/// Give the rest of the watch party the same progress as `series`, and print the accounts that couldn't be updated.
+fn share_with_watch_party(series: &Series, config: &Config, db: &Database) -> Result<()> {
+    let mut party = WatchParty::load_or_create().context("loading watch party")?;
+
+    if party.is_empty() {
+        return Ok(());
+    }
+
+    let users = Users::load_or_create()?;
+
+    for err in party.share_progress(&series.data, &users, config, db) {
+        eprintln!("{:#}", err);
+    }
+
+    Ok(())
+}
+
+/// Play the next episode of `series` and wait for the player to exit.
+///
+/// The episode is counted as watched if it was played for long enough, or if the player exited abnormally and the user
+/// chose to count it anyway.
+async fn watch_next_episode(
+    series: &mut Series,
+    config: &Config,
+    db: &Database,
+    remote: &Remote,
+) -> Result<()> {
+    use anime::remote::Status;
+
+    let id = series.data.info.id;
+
+    if let Some(playback) = ActivePlayback::load_elsewhere(db, id)? {
+        return Err(anyhow!(
+            "episode {} of {} is already being played by another instance of the program",
+            playback.episode,
+            series.data.title()
+        ));
+    }
+
+    series.begin_watching(remote, config, db)?;
+
+    if let Some((first, last)) = series.skip_filler(config, db)? {
+        println!("skipped filler episodes {} to {}", first, last);
+    }
+
+    reload_in_tui(id);
 
-    let progress_time = series.data.next_watch_progress_time(&config);
     let next_episode_num = series.data.entry.watched_episodes() + 1;
 
-    series
-        .play_episode(next_episode_num as u32, &config)?
+    let timer =
+        PlaybackTimer::start(db, id, next_episode_num).context("recording active playback")?;
+    let progress_time = series
+        .data
+        .next_watch_progress_time(timer.started_at(), config);
+
+    let mut sleep_detector = SleepDetector::new();
+
+    let status = series
+        .play_episode(next_episode_num as u32, &[], config, db)?
         .wait()
         .await
         .context("waiting for episode to finish failed")?;
 
-    if Utc::now() >= progress_time {
-        series.episode_completed(&remote, &config, &db)?;
+    // Time spent asleep doesn't count towards watching the episode, unless it was already watched for long enough
+    let progress_time = match sleep_detector.take_slept() {
+        Some(slept) if progress_time > Utc::now() - slept => progress_time + slept,
+        Some(_) | None => progress_time,
+    };
+
+    let counted = if status.success() {
+        Utc::now() >= progress_time
+    } else {
+        confirm_abnormal_exit(status.code())?
+    };
+
+    timer
+        .finish(db, status.code(), counted)
+        .context("removing active playback")?
+        .save(db)
+        .context("saving playback session")?;
+
+    if counted {
+        // An open TUI would overwrite the change with its own copy of the series, so it has to count the episode itself
+        let request = Request::CompleteEpisode(id, next_episode_num);
+
+        if ipc::send(Endpoint::Tui, request).context("sending episode to the TUI")? {
+            println!(
+                "episode {} of {} was counted by the open TUI",
+                next_episode_num,
+                series.data.title()
+            );
+
+            return Ok(());
+        }
+
+        let is_extra = series.next_is_extra(config);
+
+        series.episode_completed(config, db)?;
+        series.data.sync_to_remote(remote, db)?;
+        series.save(db)?;
+
+        if !remote.is_offline() {
+            share_with_watch_party(series, config, db)?;
+        }
+
+        if is_extra {
+            println!(
+                "extra episode {} of {} completed",
+                series.data.entry.watched_episodes(),
+                series.data.title()
+            );
+        } else if series.data.entry.status() == Status::Completed {
+            println!("{} completed!", series.data.title());
 
-        if series.data.entry.status() == Status::Completed {
-            println!("{} completed!", series.data.info.title_preferred);
+            let extra = series.extra_episodes();
+
+            if extra > 0 && config.episode.extra_episodes == ExtraEpisodes::Warn {
+                println!(
+                    "{} episode(s) past the official count were found, which can be played by setting episode.extra_episodes",
+                    extra
+                );
+            }
         } else {
             println!(
                 "{}/{} of {} completed",
                 series.data.entry.watched_episodes(),
-                series.data.info.episodes,
-                series.data.info.title_preferred
+                series.data.info.episodes_text(),
+                series.data.title()
             );
         }
     } else {
-        println!("did not watch long enough to count episode as completed");
+        return Err(
+            Failure::NothingToDo.error("did not watch long enough to count episode as completed")
+        );
     }
 
     Ok(())
 }
+
+/// Have an open TUI load the series with the specified `id` again, so it sees the changes made to it here.
+fn reload_in_tui(id: i32) {
+    if let Err(err) = ipc::send(Endpoint::Tui, Request::ReloadSeries(id)) {
+        eprintln!("failed to update the open TUI: {:#}", err);
+    }
+}
+
+/// Ask if an episode should be counted as watched after the player exited with a non-zero status.
+fn confirm_abnormal_exit(code: Option<i32>) -> Result<bool> {
+    match code {
+        Some(code) => print!("the player exited with status {}; ", code),
+        None => print!("the player was killed; "),
+    }
+
+    confirm("count episode anyway?")
+}
+
+/// Ask `question` and return true if it was answered with yes.
+fn confirm(question: &str) -> Result<bool> {
+    use std::io::{self, Write};
+
+    print!("{} (y/N) ", question);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .context("reading answer")?;
+
+    Ok(answer.trim().eq_ignore_ascii_case("y"))
+}
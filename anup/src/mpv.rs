@@ -0,0 +1,209 @@
+use crate::config::Config;
+use crate::database::SharedStr;
+use crate::series::config::SeriesConfig;
+use crate::series::history::{PlaybackSession, ResumePosition};
+use crate::series::{LastWatched, LoadedSeries, Series};
+use crate::{open_database, Args};
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+#[derive(FromArgs)]
+/// Import the resume positions mpv saved for episodes of series that have been added, along with when they were watched.
+#[argh(subcommand, name = "import-mpv")]
+pub struct ImportMpvArgs {
+    /// the directory mpv saves resume positions to, if it isn't in the default location
+    #[argh(option)]
+    dir: Option<PathBuf>,
+}
+
+pub fn run(args: &Args, import_args: &ImportMpvArgs) -> Result<()> {
+    let dir = match &import_args.dir {
+        Some(dir) => dir.clone(),
+        None => default_watch_later_dir()?,
+    };
+
+    let saved = WatchLater::load_all(&dir)
+        .with_context(|| format!("reading watch later files from {}", dir.display()))?;
+
+    if saved.is_empty() {
+        println!("no resume positions found in {}", dir.display());
+        return Ok(());
+    }
+
+    let config = Config::load_and_report()?;
+    let db = open_database(args)?;
+    let configs = SeriesConfig::load_all(&db).context("loading series configs")?;
+
+    // The series and time of the most recently watched episode that was imported
+    let mut latest: Option<(SharedStr, i64)> = None;
+
+    for series_config in configs {
+        let series = match Series::load_from_config(series_config, &config, &db) {
+            LoadedSeries::Complete(series) => series,
+            LoadedSeries::Partial(_, _) | LoadedSeries::None(_, _) => continue,
+        };
+
+        let mut imported = 0;
+
+        for episode in series.episodes.iter() {
+            let path = match series.episode_path(episode.number, &config) {
+                Ok(path) => path,
+                Err(_) => continue,
+            };
+
+            let watch_later = match saved.get(&hash_path(path.as_ref())) {
+                Some(watch_later) => watch_later,
+                None => continue,
+            };
+
+            let session = PlaybackSession {
+                series_id: series.data.info.id,
+                episode: episode.number as i16,
+                started_at: watch_later.saved_at,
+                seconds: watch_later.position,
+                exit_code: None,
+                counted: false,
+            };
+
+            if session.exists(&db)? {
+                continue;
+            }
+
+            session.save(&db)?;
+            ResumePosition::save(&db, session.series_id, session.episode, session.seconds)?;
+            imported += 1;
+
+            let is_newer = latest
+                .as_ref()
+                .map_or(true, |(_, saved_at)| watch_later.saved_at > *saved_at);
+
+            if is_newer {
+                latest = Some((series.data.config.nickname.clone(), watch_later.saved_at));
+            }
+        }
+
+        if imported > 0 {
+            println!(
+                "{}: imported {} episode(s)",
+                series.data.config.nickname, imported
+            );
+        }
+    }
+
+    let nickname = if let Some((nickname, _)) = latest {
+        nickname
+    } else {
+        println!("no new resume positions were found for episodes of added series");
+        return Ok(());
+    };
+
+    // Only fill in the last watched series, since it may already point to something more recent
+    let mut last_watched = LastWatched::load().context("loading last watched series")?;
+
+    if last_watched.get().is_none() {
+        last_watched.set(&nickname);
+        last_watched.save().context("setting last watched series")?;
+    }
+
+    Ok(())
+}
+
+/// A resume position saved by mpv.
+struct WatchLater {
+    /// The position in the file in seconds.
+    position: i32,
+    /// When the position was saved, as a Unix timestamp.
+    saved_at: i64,
+}
+
+impl WatchLater {
+    /// Load every watch later file in `dir`, keyed by their filename.
+    #[allow(clippy::cast_possible_wrap)]
+    fn load_all(dir: &Path) -> Result<HashMap<String, Self>> {
+        let mut saved = HashMap::new();
+
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+
+            let name = match entry.file_name().into_string() {
+                Ok(name) => name,
+                Err(_) => continue,
+            };
+
+            let contents = fs::read_to_string(entry.path())
+                .with_context(|| format!("reading {}", entry.path().display()))?;
+
+            let position = match Self::parse_position(&contents) {
+                Some(position) => position,
+                None => continue,
+            };
+
+            let saved_at = entry
+                .metadata()?
+                .modified()?
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_secs() as i64);
+
+            saved.insert(name, Self { position, saved_at });
+        }
+
+        Ok(saved)
+    }
+
+    fn parse_position(contents: &str) -> Option<i32> {
+        contents
+            .lines()
+            .find_map(|line| line.strip_prefix("start="))
+            .and_then(|start| start.trim().parse::<f32>().ok())
+            .map(|start| start as i32)
+    }
+}
+
+/// Returns true if `player` is mpv.
+pub fn is_player(player: &str) -> bool {
+    Path::new(player).file_name() == Some(OsStr::new("mpv"))
+}
+
+/// Returns true if mpv saved a resume position of its own for the file or URL at `path`.
+pub fn has_position(path: &OsStr) -> bool {
+    default_watch_later_dir().map_or(false, |dir| dir.join(hash_path(path)).exists())
+}
+
+/// Returns the name mpv uses for the watch later file of the file or URL at `path`.
+fn hash_path(path: &OsStr) -> String {
+    format!("{:X}", md5::compute(path.as_bytes()))
+}
+
+fn default_watch_later_dir() -> Result<PathBuf> {
+    // Newer versions of mpv save to the state directory, while older ones save to the config directory
+    let candidates = [
+        dirs_next::home_dir().map(|dir| dir.join(".local/state/mpv/watch_later")),
+        dirs_next::config_dir().map(|dir| dir.join("mpv/watch_later")),
+    ];
+
+    candidates
+        .iter()
+        .flatten()
+        .find(|dir| dir.is_dir())
+        .cloned()
+        .ok_or_else(|| anyhow!("unable to find mpv's watch later directory\nspecify it with --dir"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watch_later_position() {
+        let contents = "# /anime/series/01.mkv\nstart=1234.567000\nvolume=80.000000\n";
+        assert_eq!(WatchLater::parse_position(contents), Some(1234));
+
+        assert_eq!(WatchLater::parse_position("volume=80.000000\n"), None);
+    }
+}
@@ -0,0 +1,171 @@
+use crate::config::Config;
+use crate::database::Database;
+use crate::goal::WeeklyGoal;
+use crate::series::config::SeriesConfig;
+use crate::series::{LastWatched, LoadedSeries, Series};
+use crate::stats::Backlog;
+use crate::util;
+use crate::{open_database, Args};
+use anime::remote::anilist::AniList;
+use anime::remote::{AiringEpisode, Remote, RemoteService};
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+use chrono::Utc;
+use serde_json::json;
+use std::fmt::Write;
+
+#[derive(FromArgs)]
+/// Print the next episode of the last watched series, for use in scripts and status bars.
+#[argh(subcommand, name = "next")]
+pub struct NextArgs {
+    /// print the result as JSON
+    #[argh(switch)]
+    json: bool,
+
+    /// also count the unwatched episodes on disk of every added series, which has to look through each series folder
+    #[argh(switch, short = 'b')]
+    backlog: bool,
+}
+
+pub fn run(args: &Args, next_args: &NextArgs) -> Result<()> {
+    let config = Config::load_and_report()?;
+    let db = open_database(args)?;
+    let last_watched = LastWatched::load().context("loading last watched series")?;
+
+    let nickname = args
+        .series
+        .as_ref()
+        .or_else(|| last_watched.get())
+        .ok_or_else(|| anyhow!("no series has been watched yet"))?;
+
+    let series_config = SeriesConfig::load_by_name_or_alias(&db, nickname)
+        .with_context(|| format!("{} has not been added", nickname))?;
+
+    let series = Series::load_from_config(series_config, &config, &db);
+
+    let data = series
+        .data()
+        .ok_or_else(|| anyhow!("failed to load {}", nickname))?;
+
+    let watched = data.entry.watched_episodes().max(0) as u32;
+    let total = data.info.episodes.max(0) as u32;
+
+    // Series with an unknown number of episodes report 0
+    let next_episode = if total == 0 || watched < total {
+        Some(watched + 1)
+    } else {
+        None
+    };
+
+    let on_disk = match (&series, next_episode) {
+        (LoadedSeries::Complete(series), Some(next)) => series.episode_path(next, &config).is_ok(),
+        _ => false,
+    };
+
+    let unwatched_on_disk = series.complete().map_or(0, Series::unwatched_on_disk);
+
+    let backlog = if next_args.backlog {
+        Some(load_backlog(&config, &db)?)
+    } else {
+        None
+    };
+
+    let goal = WeeklyGoal::load(&config, &db).context("loading weekly goal")?;
+
+    // Checking the airing schedule needs a request, so it's skipped when offline or saving data
+    let airing = if args.offline || config.low_data {
+        None
+    } else {
+        let remote: Remote = AniList::Unauthenticated.into();
+        remote
+            .next_airing_episode(data.info.id as u32)
+            .ok()
+            .flatten()
+    };
+
+    if next_args.json {
+        let output = json!({
+            "nickname": data.config.nickname,
+            "title": data.title(),
+            "watched_episodes": watched,
+            "total_episodes": total,
+            "next_episode": next_episode,
+            "next_episode_on_disk": on_disk,
+            "unwatched_on_disk": unwatched_on_disk,
+            "backlog_episodes": backlog.as_ref().map(|backlog| backlog.episodes),
+            "backlog_mins": backlog.as_ref().map(|backlog| backlog.mins),
+            "next_airing_episode": airing.map(|airing| airing.episode),
+            "next_airs_at": airing.map(|airing| airing.airs_at),
+            "goal_watched": goal.map(|goal| goal.watched),
+            "goal_episodes": goal.map(|goal| goal.goal),
+        });
+
+        println!("{}", output);
+        return Ok(());
+    }
+
+    let mut output = match next_episode {
+        Some(next) if on_disk => format!("{} - episode {} ready", data.title(), next),
+        Some(next) => format!("{} - episode {} not downloaded", data.title(), next),
+        None => format!("{} - completed", data.title()),
+    };
+
+    // The next episode being ready already implies one is on disk
+    if unwatched_on_disk > 1 {
+        write!(output, " ({} on disk)", unwatched_on_disk).ok();
+    }
+
+    if let Some(backlog) = &backlog {
+        write!(
+            output,
+            " | {} unwatched on disk ({})",
+            backlog.episodes,
+            util::hm_from_mins(backlog.mins as f32)
+        )
+        .ok();
+    }
+
+    if let Some(goal) = goal {
+        write!(output, " | goal {} this week", goal).ok();
+    }
+
+    match airing {
+        Some(airing) => println!(
+            "{} | episode {} airs in {}",
+            output,
+            airing.episode,
+            time_until(&airing)
+        ),
+        None => println!("{}", output),
+    }
+
+    Ok(())
+}
+
+/// Returns the unwatched episodes on disk of every added series.
+fn load_backlog(config: &Config, db: &Database) -> Result<Backlog> {
+    let series = SeriesConfig::load_all(db)
+        .context("loading series configs")?
+        .into_iter()
+        .map(|series_config| Series::load_from_config(series_config, config, db))
+        .collect::<Vec<_>>();
+
+    Ok(Backlog::from_series(
+        series.iter().filter_map(LoadedSeries::complete),
+    ))
+}
+
+/// Returns a short description of how long it is until `airing` airs, like `2d 4h` or `35m`.
+fn time_until(airing: &AiringEpisode) -> String {
+    let secs = (airing.airs_at - Utc::now().timestamp()).max(0);
+
+    let days = secs / (60 * 60 * 24);
+    let hours = (secs / (60 * 60)) % 24;
+    let mins = (secs / 60) % 60;
+
+    match (days, hours) {
+        (0, 0) => format!("{}m", mins),
+        (0, _) => format!("{}h {}m", hours, mins),
+        _ => format!("{}d {}h", days, hours),
+    }
+}
@@ -0,0 +1,377 @@
+use crate::config::Config;
+use crate::database::Database;
+use crate::remote::ensure_scores_supported;
+use crate::series::alias::SeriesAlias;
+use crate::series::config::SeriesConfig;
+use crate::series::sync_failure::SyncFailure;
+use crate::series::{LastWatched, LoadedSeries, Series, SeriesData};
+use crate::{init_remote, open_database, util, watch_next_episode, Args};
+use anime::remote::{Remote, RemoteService, ScoreParser, Status};
+use anyhow::{anyhow, Context, Result};
+use std::io::{self, Write};
+
+const HELP: &str = "\
+commands:
+  list              list every series with a number to select it by
+  select <series>   select a series by its number, nickname, or alias
+  info              describe the selected series
+  play              play the next episode of the selected series
+  progress <+/->    mark the next episode as watched, or the last one as unwatched
+  score <score>     rate the selected series (use 0 to remove the score)
+  status <status>   set the status of the selected series, such as watching or on_hold
+  sync              send the list entry of the selected series to AniList
+  help              show this list
+  quit              exit";
+
+/// The state of the plain interactive mode.
+struct Session {
+    config: Config,
+    db: Database,
+    remote: Remote,
+    series: Vec<LoadedSeries>,
+    selected: Option<usize>,
+    last_watched: LastWatched,
+}
+
+/// Run an interactive session that asks for one command at a time and only prints plain text.
+///
+/// This works better with screen readers than the TUI, which redraws the whole screen.
+pub async fn run(args: &Args) -> Result<()> {
+    let config = Config::load_and_report()?;
+    let db = open_database(args)?;
+
+    let remote = if let Some(remote) = init_remote(args, &config)? {
+        remote
+    } else {
+        println!("no accounts have been added, so changes will only be saved locally");
+        println!("add an account with the login command to sync them to AniList");
+        Remote::offline()
+    };
+
+    let mut series = SeriesConfig::load_all(&db)
+        .context("loading series configs")?
+        .into_iter()
+        .map(|series_config| Series::load_from_config(series_config, &config, &db))
+        .collect::<Vec<_>>();
+
+    series.sort_unstable();
+
+    let last_watched = LastWatched::load().context("loading last watched series")?;
+
+    let selected = match args.series.as_ref().or_else(|| last_watched.get()) {
+        Some(name) => {
+            let nickname = SeriesAlias::resolve(&db, name)
+                .context("resolving series name")?
+                .unwrap_or_else(|| name.clone());
+
+            series.iter().position(|s| s.nickname() == nickname)
+        }
+        None => None,
+    };
+
+    let mut session = Session {
+        config,
+        db,
+        remote,
+        series,
+        selected,
+        last_watched,
+    };
+
+    println!("{} series added", session.series.len());
+    session.print_selected();
+    println!("type help for a list of commands");
+
+    while let Some(line) = prompt()? {
+        let mut parts = line.splitn(2, ' ');
+        let command = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let value = parts.next().unwrap_or_default().trim();
+
+        let result = match command.as_str() {
+            "" => continue,
+            "list" | "ls" | "l" => {
+                session.list();
+                Ok(())
+            }
+            "select" | "s" => session.select(value),
+            "info" | "i" => session.info(),
+            "play" | "p" => session.play().await,
+            "progress" => session.progress(value),
+            "score" => session.score(value),
+            "status" => session.status(value),
+            "sync" => session.sync(),
+            "help" | "h" | "?" => {
+                println!("{}", HELP);
+                Ok(())
+            }
+            "quit" | "exit" | "q" => break,
+            _ => Err(anyhow!(
+                "unknown command: {}\ntype help for a list of commands",
+                command
+            )),
+        };
+
+        if let Err(err) = result {
+            println!("error: {:#}", err);
+        }
+    }
+
+    Ok(())
+}
+
+/// Print a prompt and read a line from stdin.
+///
+/// Returns None when stdin is closed.
+fn prompt() -> Result<Option<String>> {
+    print!("> ");
+    io::stdout().flush()?;
+
+    let mut line = String::new();
+
+    let read = io::stdin()
+        .read_line(&mut line)
+        .context("reading command")?;
+
+    if read == 0 {
+        println!();
+        return Ok(None);
+    }
+
+    Ok(Some(line.trim().to_string()))
+}
+
+impl Session {
+    fn selected_index(&self) -> Result<usize> {
+        self.selected
+            .ok_or_else(|| anyhow!("no series selected\nuse select to pick one"))
+    }
+
+    fn complete(series: &mut LoadedSeries) -> Result<&mut Series> {
+        match series {
+            LoadedSeries::Complete(series) => Ok(series),
+            LoadedSeries::Partial(data, err) => {
+                Err(anyhow!("{} can't be used: {}", data.title(), err))
+            }
+            LoadedSeries::None(config, err) => {
+                Err(anyhow!("{} can't be used: {:#}", config.nickname, err))
+            }
+        }
+    }
+
+    fn loaded(series: &mut LoadedSeries) -> Result<&mut SeriesData> {
+        let nickname = series.config().nickname.clone();
+
+        series
+            .data_mut()
+            .ok_or_else(|| anyhow!("{} isn't loaded", nickname))
+    }
+
+    fn print_selected(&self) {
+        match self.selected {
+            Some(index) => println!("selected {}", Self::title(&self.series[index])),
+            None => println!("no series selected"),
+        }
+    }
+
+    fn title(series: &LoadedSeries) -> &str {
+        series
+            .info()
+            .map_or_else(|| series.nickname(), |info| info.title_preferred.as_str())
+    }
+
+    fn list(&self) {
+        if self.series.is_empty() {
+            println!("no series have been added");
+            return;
+        }
+
+        for (i, series) in self.series.iter().enumerate() {
+            let marker = if self.selected == Some(i) {
+                ", selected"
+            } else {
+                ""
+            };
+
+            match series.data() {
+                Some(data) => println!(
+                    "{}. {}, {} of {} episodes watched, {}{}",
+                    i + 1,
+                    data.title(),
+                    data.entry.watched_episodes(),
+                    data.info.episodes_text(),
+                    data.entry.status(),
+                    marker
+                ),
+                None => println!("{}. {}, not loaded{}", i + 1, series.nickname(), marker),
+            }
+        }
+    }
+
+    fn select(&mut self, value: &str) -> Result<()> {
+        if value.is_empty() {
+            return Err(anyhow!(
+                "specify the number, nickname, or alias of a series"
+            ));
+        }
+
+        let index = match value.parse::<usize>() {
+            Ok(num) if num >= 1 && num <= self.series.len() => num - 1,
+            Ok(_) => return Err(anyhow!("there is no series numbered {}", value)),
+            Err(_) => {
+                let nickname = SeriesAlias::resolve(&self.db, value)?
+                    .ok_or_else(|| anyhow!("no series has the nickname or alias {}", value))?;
+
+                self.series
+                    .iter()
+                    .position(|series| series.nickname() == nickname)
+                    .ok_or_else(|| anyhow!("{} isn't loaded", nickname))?
+            }
+        };
+
+        self.selected = Some(index);
+        self.print_selected();
+
+        Ok(())
+    }
+
+    fn info(&mut self) -> Result<()> {
+        let index = self.selected_index()?;
+        let series = Self::complete(&mut self.series[index])?;
+        let info = &series.data.info;
+        let entry = &series.data.entry;
+
+        let score = entry
+            .score()
+            .map_or_else(|| "none".into(), |score| score.to_string());
+
+        let time_left = info.total_episodes().map_or_else(
+            || "unknown".into(),
+            |total| {
+                let eps_left = total - entry.watched_episodes().min(total);
+                util::hm_from_mins(f32::from(eps_left * info.episode_length_mins))
+            },
+        );
+
+        println!("{}", info.title_preferred);
+        println!("status: {}", entry.status());
+        println!(
+            "progress: {} of {} episodes",
+            entry.watched_episodes(),
+            info.episodes_text()
+        );
+        println!("score: {}", score);
+        println!("episodes on disk: {}", series.episodes.len());
+        println!("episode length: {} minutes", info.episode_length_mins);
+        println!("time left: {}", time_left);
+
+        if entry.needs_sync() {
+            println!("has changes that haven't been synced to AniList");
+        }
+
+        Ok(())
+    }
+
+    async fn play(&mut self) -> Result<()> {
+        let index = self.selected_index()?;
+        let series = Self::complete(&mut self.series[index])?;
+
+        if self.last_watched.set(&series.data.config.nickname) {
+            self.last_watched.save()?;
+        }
+
+        println!(
+            "playing episode {} of {}",
+            series.data.entry.watched_episodes() + 1,
+            series.data.title()
+        );
+
+        watch_next_episode(series, &self.config, &self.db, &self.remote).await
+    }
+
+    fn progress(&mut self, value: &str) -> Result<()> {
+        let forwards = match value {
+            "+" | "f" | "forward" => true,
+            "-" | "b" | "backward" => false,
+            _ => return Err(anyhow!("specify + to add an episode, or - to remove one")),
+        };
+
+        let index = self.selected_index()?;
+        let series = Self::complete(&mut self.series[index])?;
+        let (config, db, remote) = (&self.config, &self.db, &self.remote);
+
+        if forwards {
+            series.episode_completed(config, db)?;
+        } else {
+            series.episode_regressed(config, db)?;
+        }
+
+        series.data.sync_to_remote(remote, db)?;
+        series.save(db)?;
+
+        println!(
+            "{} of {} episodes watched",
+            series.data.entry.watched_episodes(),
+            series.data.info.episodes_text()
+        );
+
+        Ok(())
+    }
+
+    fn score(&mut self, value: &str) -> Result<()> {
+        let index = self.selected_index()?;
+        let data = Self::loaded(&mut self.series[index])?;
+        let (db, remote) = (&self.db, &self.remote);
+        ensure_scores_supported(remote)?;
+
+        let current = data.entry.score().map(|score| score as u8);
+
+        let score = match remote.parse_relative_score(value, current) {
+            Some(0) => None,
+            Some(score) => Some(score),
+            None => return Err(anyhow!("invalid score")),
+        };
+
+        data.entry.set_score(score.map(i16::from));
+        data.sync_to_remote(remote, db)?;
+        data.save(db)?;
+
+        match score {
+            Some(score) => println!("score set to {}", remote.score_to_str(score)),
+            None => println!("score removed"),
+        }
+
+        Ok(())
+    }
+
+    fn status(&mut self, value: &str) -> Result<()> {
+        let status = value.parse::<Status>()?;
+
+        let index = self.selected_index()?;
+        let data = Self::loaded(&mut self.series[index])?;
+        let (config, db, remote) = (&self.config, &self.db, &self.remote);
+
+        data.entry.set_status(status, config);
+        data.sync_to_remote(remote, db)?;
+        data.save(db)?;
+
+        println!("status set to {}", status);
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<()> {
+        let index = self.selected_index()?;
+        let data = Self::loaded(&mut self.series[index])?;
+        let (db, remote) = (&self.db, &self.remote);
+
+        if remote.is_offline() {
+            return Err(anyhow!("must be online to sync"));
+        }
+
+        data.force_sync_to_remote(remote, db)?;
+        SyncFailure::clear(db, data.info.id)?;
+        data.save(db)?;
+
+        println!("synced {}", data.title());
+        Ok(())
+    }
+}
@@ -0,0 +1,437 @@
+use crate::err::Failure;
+use crate::series::config::SeriesConfig;
+use crate::series::SeriesData;
+use crate::{open_database, Args};
+use anime::remote::Status;
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+use chrono::{DateTime, Duration, Local, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc};
+use serde_json::json;
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// The length episodes are assumed to be when the remote service doesn't know it.
+const DEFAULT_EPISODE_MINS: i64 = 24;
+
+/// The most episodes a session can have, which keeps sessions of series with an unknown episode count from ending
+/// past the dates that can be represented.
+const MAX_SESSION_EPISODES: u32 = 1000;
+
+#[derive(FromArgs)]
+/// Export sessions to watch the next episodes of series, as a calendar file or tasks for Taskwarrior.
+#[argh(subcommand, name = "plan")]
+pub struct PlanArgs {
+    /// the format to export to, either ics or taskwarrior
+    #[argh(option, short = 'f')]
+    format: Format,
+
+    /// a series to plan a session for, by nickname or alias; can be given more than once, and defaults to every
+    /// series being watched
+    #[argh(option, short = 's')]
+    series: Vec<String>,
+
+    /// the number of episodes to watch in each session
+    #[argh(option, short = 'e', default = "1")]
+    episodes: u32,
+
+    /// when the first session starts, like 20:00 for today or "2021-04-02 20:00", which defaults to the next full hour
+    #[argh(option)]
+    start: Option<String>,
+
+    /// the number of minutes to leave between sessions
+    #[argh(option, default = "0")]
+    gap: u32,
+
+    /// the file to write the sessions to, instead of printing them
+    #[argh(option, short = 'o')]
+    output: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Format {
+    Ics,
+    Taskwarrior,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "ics" | "ical" | "icalendar" | "calendar" => Ok(Self::Ics),
+            "taskwarrior" | "task" | "tw" => Ok(Self::Taskwarrior),
+            _ => Err(anyhow!("unknown format: {}", value)),
+        }
+    }
+}
+
+pub fn run(args: &Args, plan_args: &PlanArgs) -> Result<()> {
+    if plan_args.episodes == 0 {
+        return Err(anyhow!("sessions must have at least one episode"));
+    }
+
+    if plan_args.episodes > MAX_SESSION_EPISODES {
+        return Err(anyhow!(
+            "sessions can't have more than {} episodes",
+            MAX_SESSION_EPISODES
+        ));
+    }
+
+    let db = open_database(args)?;
+
+    let configs = if plan_args.series.is_empty() {
+        SeriesConfig::load_all(&db).context("loading series configs")?
+    } else {
+        plan_args
+            .series
+            .iter()
+            .map(|name| {
+                SeriesConfig::load_by_name_or_alias(&db, name)
+                    .with_context(|| format!("{} has not been added", name))
+            })
+            .collect::<Result<_>>()?
+    };
+
+    let mut series = configs
+        .into_iter()
+        .map(|series_config| SeriesData::load_from_config(&db, Cow::Owned(series_config)))
+        .collect::<Result<Vec<_>, _>>()
+        .context("loading series")?;
+
+    // Series that were asked for are planned in the order they were given
+    if plan_args.series.is_empty() {
+        series.retain(|data| matches!(data.entry.status(), Status::Watching | Status::Rewatching));
+        series.sort_unstable_by(|x, y| x.info.title_preferred.cmp(&y.info.title_preferred));
+    }
+
+    let start = match &plan_args.start {
+        Some(start) => parse_start(start, Local::now())?,
+        None => next_full_hour(Local::now()),
+    };
+
+    let gap = Duration::minutes(i64::from(plan_args.gap));
+    let sessions = Session::plan(&series, plan_args.episodes, start.with_timezone(&Utc), gap);
+
+    if sessions.is_empty() {
+        return Err(Failure::NothingToDo.error("no series have episodes left to watch"));
+    }
+
+    let exported = match plan_args.format {
+        Format::Ics => ics_calendar(&sessions, Utc::now()),
+        Format::Taskwarrior => taskwarrior_tasks(&sessions, Utc::now()),
+    };
+
+    match &plan_args.output {
+        Some(path) => {
+            fs::write(path, exported)
+                .with_context(|| format!("writing sessions to {}", path.display()))?;
+
+            eprintln!("planned {} sessions in {}", sessions.len(), path.display());
+        }
+        None => print!("{}", exported),
+    }
+
+    Ok(())
+}
+
+/// Parse when the first session starts, which is either a time today or a full date and time, in local time.
+fn parse_start(value: &str, now: DateTime<Local>) -> Result<DateTime<Local>> {
+    let value = value.trim();
+
+    let naive = match NaiveTime::parse_from_str(value, "%H:%M") {
+        Ok(time) => now.date().naive_local().and_time(time),
+        Err(_) => NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+            .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+            .map_err(|_| {
+                anyhow!(
+                    "{} isn't a valid start; use a time like 20:00 or a date and time like \"2021-04-02 20:00\"",
+                    value
+                )
+            })?,
+    };
+
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(|| anyhow!("{} doesn't exist in the local time zone", value))
+}
+
+fn next_full_hour(now: DateTime<Local>) -> DateTime<Local> {
+    let hour = now
+        .with_minute(0)
+        .and_then(|time| time.with_second(0))
+        .and_then(|time| time.with_nanosecond(0))
+        .unwrap_or(now);
+
+    hour + Duration::hours(1)
+}
+
+/// A block of time to watch one or more episodes of a series in a row.
+#[cfg_attr(test, derive(Debug, PartialEq))]
+struct Session {
+    series_id: i32,
+    title: String,
+    first_episode: u32,
+    last_episode: u32,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+impl Session {
+    /// Plan a session of up to `episodes` of the next episodes of each series, one after the other starting from
+    /// `start` with `gap` between them.
+    ///
+    /// Series that have been watched to their last episode are skipped.
+    fn plan(
+        series: &[SeriesData],
+        episodes: u32,
+        start: DateTime<Utc>,
+        gap: Duration,
+    ) -> Vec<Self> {
+        let mut sessions = Vec::with_capacity(series.len());
+        let mut start = start;
+
+        for data in series {
+            let first_episode = data.entry.watched_episodes().max(0) as u32 + 1;
+            let mut last_episode = first_episode.saturating_add(episodes - 1);
+
+            // Series with an unknown number of episodes report 0
+            if data.info.episodes > 0 {
+                let total = data.info.episodes as u32;
+
+                if first_episode > total {
+                    continue;
+                }
+
+                last_episode = last_episode.min(total);
+            }
+
+            let episode_mins = match data.info.episode_length_mins {
+                mins if mins > 0 => i64::from(mins),
+                _ => DEFAULT_EPISODE_MINS,
+            };
+
+            let count = i64::from(last_episode - first_episode + 1);
+            let end = start + Duration::minutes(episode_mins * count);
+
+            sessions.push(Self {
+                series_id: data.info.id,
+                title: data.title().to_string(),
+                first_episode,
+                last_episode,
+                start,
+                end,
+            });
+
+            start = end + gap;
+        }
+
+        sessions
+    }
+
+    fn summary(&self) -> String {
+        if self.first_episode == self.last_episode {
+            format!("Watch {} - episode {}", self.title, self.first_episode)
+        } else {
+            format!(
+                "Watch {} - episodes {}-{}",
+                self.title, self.first_episode, self.last_episode
+            )
+        }
+    }
+
+    /// Returns an ID that stays the same for the same episodes of the same series, so exporting them again updates
+    /// the session instead of adding another one.
+    fn uid(&self) -> String {
+        format!(
+            "anup-{}-{}-{}",
+            self.series_id, self.first_episode, self.last_episode
+        )
+    }
+
+    /// Returns the ID of the session in the format of a name-based UUID, which Taskwarrior requires.
+    fn uuid(&self) -> String {
+        let mut bytes = md5::compute(self.uid()).0;
+
+        // Mark it as a version 3 (MD5) UUID of the RFC 4122 variant
+        bytes[6] = (bytes[6] & 0x0f) | 0x30;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+        let hex = bytes
+            .iter()
+            .fold(String::with_capacity(32), |mut hex, byte| {
+                write!(hex, "{:02x}", byte).ok();
+                hex
+            });
+
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..]
+        )
+    }
+}
+
+/// The format dates are written in by both iCalendar and Taskwarrior.
+fn utc_timestamp(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Returns `sessions` as an iCalendar file, which most calendar programs can import.
+fn ics_calendar(sessions: &[Session], now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//anup//Planned Watching//EN".to_string(),
+    ];
+
+    for session in sessions {
+        lines.push("BEGIN:VEVENT".into());
+        lines.push(format!("UID:{}", session.uid()));
+        lines.push(format!("DTSTAMP:{}", utc_timestamp(now)));
+        lines.push(format!("DTSTART:{}", utc_timestamp(session.start)));
+        lines.push(format!("DTEND:{}", utc_timestamp(session.end)));
+        lines.push(format!("SUMMARY:{}", ics_escape(&session.summary())));
+        lines.push("CATEGORIES:anime".into());
+        lines.push("END:VEVENT".into());
+    }
+
+    lines.push("END:VCALENDAR".into());
+
+    lines.iter().fold(String::new(), |mut calendar, line| {
+        calendar.push_str(&ics_fold(line));
+        calendar.push_str("\r\n");
+        calendar
+    })
+}
+
+/// Escape the characters that have a special meaning in iCalendar text values.
+fn ics_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        match ch {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => (),
+            _ => escaped.push(ch),
+        }
+    }
+
+    escaped
+}
+
+/// Split `line` into lines of at most 75 bytes, as iCalendar requires, without splitting any characters.
+///
+/// Every line after the first starts with a space to mark it as continuing the one before it.
+fn ics_fold(line: &str) -> Cow<str> {
+    const MAX_LEN: usize = 75;
+
+    if line.len() <= MAX_LEN {
+        return Cow::Borrowed(line);
+    }
+
+    let mut folded = String::with_capacity(line.len() + line.len() / MAX_LEN * 3);
+    let mut len = 0;
+
+    for ch in line.chars() {
+        if len + ch.len_utf8() > MAX_LEN {
+            folded.push_str("\r\n ");
+            // The space counts toward the length of the line
+            len = 1;
+        }
+
+        folded.push(ch);
+        len += ch.len_utf8();
+    }
+
+    Cow::Owned(folded)
+}
+
+/// Returns `sessions` as tasks that can be imported into Taskwarrior with `task import`.
+fn taskwarrior_tasks(sessions: &[Session], now: DateTime<Utc>) -> String {
+    let tasks = sessions
+        .iter()
+        .map(|session| {
+            json!({
+                "uuid": session.uuid(),
+                "status": "pending",
+                "entry": utc_timestamp(now),
+                "description": session.summary(),
+                "scheduled": utc_timestamp(session.start),
+                "due": utc_timestamp(session.end),
+                "project": "anime",
+                "tags": ["anup"],
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut exported = serde_json::Value::Array(tasks).to_string();
+    exported.push('\n');
+    exported
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(first_episode: u32, last_episode: u32) -> Session {
+        let start = Utc.ymd(2021, 4, 2).and_hms(11, 0, 0);
+
+        Session {
+            series_id: 1234,
+            title: "Series, Part 2; Finale".into(),
+            first_episode,
+            last_episode,
+            start,
+            end: start + Duration::minutes(48),
+        }
+    }
+
+    #[test]
+    fn ics_export() {
+        let now = Utc.ymd(2021, 4, 1).and_hms(12, 0, 0);
+        let calendar = ics_calendar(&[session(5, 6)], now);
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(calendar.contains("\r\nUID:anup-1234-5-6\r\n"));
+        assert!(calendar.contains("\r\nDTSTART:20210402T110000Z\r\nDTEND:20210402T114800Z\r\n"));
+        assert!(
+            calendar.contains("\r\nSUMMARY:Watch Series\\, Part 2\\; Finale - episodes 5-6\r\n")
+        );
+        assert!(calendar.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+
+        let line = format!("SUMMARY:{}", "あ".repeat(40));
+        let folded = ics_fold(&line);
+        let lines = folded.split("\r\n").collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.len() <= 75));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+
+    #[test]
+    fn parse_start_times() {
+        let now = Local.ymd(2021, 4, 2).and_hms(18, 30, 0);
+
+        assert_eq!(
+            parse_start("20:00", now).unwrap(),
+            Local.ymd(2021, 4, 2).and_hms(20, 0, 0)
+        );
+        assert_eq!(
+            parse_start("2021-04-03 09:15", now).unwrap(),
+            Local.ymd(2021, 4, 3).and_hms(9, 15, 0)
+        );
+        assert!(parse_start("tonight", now).is_err());
+        assert_eq!(next_full_hour(now), Local.ymd(2021, 4, 2).and_hms(19, 0, 0));
+    }
+}
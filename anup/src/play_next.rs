@@ -0,0 +1,22 @@
+use crate::config::Config;
+use crate::ipc::{self, Endpoint, Request};
+use crate::{play_last_watched_episode, Args};
+use anime::remote::Remote;
+use anyhow::Result;
+use argh::FromArgs;
+
+#[derive(FromArgs)]
+/// Play the next episode of the last watched series as quickly as possible, for use with a desktop hotkey.
+#[argh(subcommand, name = "play-next")]
+pub struct PlayNextArgs {}
+
+pub async fn run(args: &Args, _: &PlayNextArgs) -> Result<()> {
+    // The TUI has to play the episode itself when it's open, so it can keep track of it
+    if ipc::send(Endpoint::Tui, Request::PlayNext)? {
+        return Ok(());
+    }
+
+    // Logging in can take a while, so any changes are synced the next time the program is online instead
+    let config = Config::load_and_report()?;
+    play_last_watched_episode(args, &config, &Remote::offline()).await
+}
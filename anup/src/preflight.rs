@@ -0,0 +1,139 @@
+use crate::config::Config;
+use crate::err::Failure;
+use crate::series::config::SeriesConfig;
+use crate::series::sync_failure::SyncFailure;
+use crate::series::{EpisodeScanError, LoadedSeries, Series};
+use crate::{init_remote, open_database, Args};
+use anime::remote::anilist::AniList;
+use anime::remote::{Remote, RemoteService, SeriesID, Status};
+use anyhow::{Context, Result};
+use argh::FromArgs;
+use smallvec::SmallVec;
+
+#[derive(FromArgs)]
+/// Check that every airing series being watched or planned to be watched is ready for its next episode.
+#[argh(subcommand, name = "preflight")]
+pub struct PreflightArgs {}
+
+pub fn run(args: &Args, _: &PreflightArgs) -> Result<()> {
+    if args.offline {
+        return Err(Failure::Offline.error("must be online to check which series are airing"));
+    }
+
+    let config = Config::load_and_report()?;
+    let db = open_database(args)?;
+
+    // List entries can only be checked with an account, but airing schedules can be looked up without one
+    let account = init_remote(args, &config)?;
+    let public: Remote = AniList::Unauthenticated.into();
+    let remote = account.as_ref().unwrap_or(&public);
+
+    let configs = SeriesConfig::load_all(&db).context("loading series configs")?;
+
+    let mut airing = 0;
+    let mut with_problems = 0;
+
+    for series_config in configs {
+        let series = Series::load_from_config(series_config, &config, &db);
+
+        if let LoadedSeries::None(series_config, err) = &series {
+            eprintln!("failed to load {}: {:#}", series_config.nickname, err);
+            continue;
+        }
+
+        let data = match series.data() {
+            Some(data) => data,
+            None => continue,
+        };
+
+        if !matches!(data.entry.status(), Status::Watching | Status::PlanToWatch) {
+            continue;
+        }
+
+        let id = data.info.id as SeriesID;
+
+        let next_airing = remote
+            .next_airing_episode(id)
+            .with_context(|| format!("looking up when {} airs", data.config.nickname))?;
+
+        let next_airing = match next_airing {
+            Some(next_airing) => next_airing,
+            None => continue,
+        };
+
+        airing += 1;
+
+        let mut problems = SmallVec::<[String; 4]>::new();
+
+        if let LoadedSeries::Partial(_, err) = &series {
+            match err {
+                // Nothing has to be downloaded until the first episode airs
+                EpisodeScanError::NoEpisodes => (),
+                EpisodeScanError::PathNotFound { .. }
+                | EpisodeScanError::BrokenLink { .. }
+                | EpisodeScanError::Unreachable { .. }
+                | EpisodeScanError::Unlisted { .. } => problems.push(format!("folder: {}", err)),
+                EpisodeScanError::EpisodeParseFailed { .. }
+                | EpisodeScanError::SeriesNeedsSplitting => {
+                    problems.push(format!("episode pattern: {}", err));
+                }
+            }
+        }
+
+        if data.config.local_only {
+            problems.push(
+                "only tracked locally, so episodes won't be synced (turn on remotesync)".into(),
+            );
+        }
+
+        if let Some(failure) = SyncFailure::load(&db, data.info.id)? {
+            problems.push(format!(
+                "failed to sync {} times in a row: {}",
+                failure.failures, failure.last_error
+            ));
+        }
+
+        if let Some(account) = &account {
+            let listed = account
+                .get_list_entry(id)
+                .with_context(|| format!("getting the list entry of {}", data.config.nickname))?
+                .is_some();
+
+            if !listed && !data.config.local_only {
+                problems.push("not on your AniList list yet".into());
+            }
+        }
+
+        if problems.is_empty() {
+            continue;
+        }
+
+        with_problems += 1;
+
+        println!(
+            "{} (episode {} airs next):",
+            data.config.nickname, next_airing.episode
+        );
+
+        for problem in problems {
+            println!("  - {}", problem);
+        }
+    }
+
+    if airing == 0 {
+        println!("none of the series being watched or planned to be watched are airing");
+    } else if with_problems == 0 {
+        println!("all {} airing series are ready", airing);
+    } else {
+        println!(
+            "{} of {} airing series have problems",
+            with_problems, airing
+        );
+    }
+
+    if account.is_none() {
+        println!("list entries weren't checked since no account has been added");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use crate::file::{FileFormat, SaveDir, SerializedFile};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde_derive::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+
+/// A stretch of time where changes to list entries are only saved locally, so they don't show up on the user's
+/// profile until the session ends.
+///
+/// The session is kept in a file rather than the config, so the TUI, daemon, and every command see it right away.
+#[derive(Default, Deserialize, Serialize)]
+pub struct PrivateSession {
+    /// When the session was started, as a UNIX timestamp.
+    pub started_at: i64,
+}
+
+impl PrivateSession {
+    /// Returns true if changes to list entries shouldn't be sent to the remote service.
+    pub fn is_active() -> bool {
+        Self::validated_save_path().map_or(false, |path| path.exists())
+    }
+
+    pub fn start() -> Result<()> {
+        let session = Self {
+            started_at: Utc::now().timestamp(),
+        };
+
+        session.save().context("starting private session")
+    }
+
+    /// End the session. Entries that were changed during it still need to be synced afterwards.
+    pub fn end() -> Result<()> {
+        let path = Self::validated_save_path()?;
+
+        match fs::remove_file(path) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err).context("ending private session"),
+        }
+    }
+}
+
+impl SerializedFile for PrivateSession {
+    fn filename() -> &'static str {
+        "private_session"
+    }
+
+    fn save_dir() -> SaveDir {
+        SaveDir::LocalData
+    }
+
+    fn format() -> FileFormat {
+        FileFormat::Binary
+    }
+}
@@ -0,0 +1,290 @@
+//! A read-only view of the database for third-party tools.
+//!
+//! The tables in the database change shape whenever a migration needs them to, so tools shouldn't read them directly.
+//! The records here are kept stable instead: new fields can be added, but existing ones keep their names and meanings
+//! until `QUERY_VERSION` changes.
+
+use crate::database::Database;
+use crate::series::config::SeriesConfig;
+use crate::series::SeriesData;
+use crate::{open_database, Args};
+use anime::remote::{SeriesDate, Status};
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+use chrono::{Local, NaiveDate, TimeZone};
+use diesel::prelude::*;
+use serde_derive::Serialize;
+use serde_json::json;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// The version of the records returned by queries, which only changes when a field is renamed, removed, or changes
+/// meaning.
+pub const QUERY_VERSION: u32 = 1;
+
+#[derive(FromArgs)]
+/// Print added series or watch history as JSON, for use by other programs.
+#[argh(subcommand, name = "query")]
+pub struct QueryArgs {
+    /// what to query, either series or history
+    #[argh(positional)]
+    target: Target,
+
+    /// only include series with this watch status
+    #[argh(option)]
+    status: Option<Status>,
+
+    /// only include the series with this nickname
+    #[argh(option)]
+    series: Option<String>,
+
+    /// with history, only include episodes watched on or after this date, like 2021-04-01
+    #[argh(option)]
+    since: Option<NaiveDate>,
+
+    /// with history, the maximum number of episodes to include, starting from the most recent
+    #[argh(option)]
+    limit: Option<u32>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Target {
+    Series,
+    History,
+}
+
+impl FromStr for Target {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "series" => Ok(Self::Series),
+            "history" => Ok(Self::History),
+            _ => Err(anyhow!("unknown query: {}", value)),
+        }
+    }
+}
+
+pub fn run(args: &Args, query_args: &QueryArgs) -> Result<()> {
+    let db = open_database(args)?;
+
+    let since = match query_args.since {
+        Some(date) => Some(
+            Local
+                .from_local_datetime(&date.and_hms(0, 0, 0))
+                .earliest()
+                .ok_or_else(|| anyhow!("{} doesn't exist in the local time zone", date))?
+                .timestamp(),
+        ),
+        None => None,
+    };
+
+    let filter = Filter {
+        status: query_args.status,
+        nickname: query_args.series.clone(),
+        since,
+        limit: query_args.limit,
+    };
+
+    let output = match query_args.target {
+        Target::Series => json!({
+            "version": QUERY_VERSION,
+            "series": series(&db, &filter)?,
+        }),
+        Target::History => json!({
+            "version": QUERY_VERSION,
+            "history": history(&db, &filter)?,
+        }),
+    };
+
+    println!("{}", output);
+    Ok(())
+}
+
+/// Limits what a query returns. Every field that is set has to match.
+#[derive(Default)]
+pub struct Filter {
+    /// Only include series with this watch status.
+    pub status: Option<Status>,
+    /// Only include the series with this nickname.
+    pub nickname: Option<String>,
+    /// Only include episodes watched at or after this UNIX timestamp.
+    pub since: Option<i64>,
+    /// The maximum number of episodes to return, starting from the most recent.
+    pub limit: Option<u32>,
+}
+
+/// An added series and its list entry for the current account.
+#[derive(Debug, Serialize)]
+pub struct SeriesRecord {
+    /// The ID of the series on the remote service.
+    pub id: i32,
+    pub nickname: String,
+    pub title: String,
+    /// One of `watching`, `completed`, `on_hold`, `dropped`, `plan_to_watch`, or `rewatching`.
+    pub status: &'static str,
+    pub watched_episodes: i16,
+    /// `None` when the number of episodes isn't known yet.
+    pub total_episodes: Option<i16>,
+    /// The score out of 100, or `None` if the series hasn't been rated.
+    pub score: Option<i16>,
+    pub times_rewatched: i16,
+    /// Formatted like `2021-04-01`.
+    pub start_date: Option<String>,
+    /// Formatted like `2021-04-01`.
+    pub end_date: Option<String>,
+    /// Whether the list entry is only tracked locally and never synced.
+    pub local_only: bool,
+}
+
+impl SeriesRecord {
+    fn new(data: &SeriesData) -> Self {
+        let entry = &data.entry;
+
+        Self {
+            id: data.info.id,
+            nickname: data.config.nickname.to_string(),
+            title: data.title().to_string(),
+            status: status_name(entry.status()),
+            watched_episodes: entry.watched_episodes(),
+            total_episodes: data.info.total_episodes(),
+            score: entry.score(),
+            times_rewatched: entry.times_rewatched(),
+            start_date: entry.start_date().map(format_date),
+            end_date: entry.end_date().map(format_date),
+            local_only: data.config.local_only,
+        }
+    }
+}
+
+/// A single episode that was watched to completion.
+#[derive(Debug, Serialize)]
+pub struct WatchRecord {
+    /// The ID of the series on the remote service.
+    pub series_id: i32,
+    pub episode: i16,
+    /// When the episode was finished, as a UNIX timestamp.
+    pub watched_at: i64,
+    /// The rating given to the episode out of 10, if it was rated.
+    pub rating: Option<i16>,
+    /// The nickname of the series, or `None` if it's no longer added.
+    pub nickname: Option<String>,
+}
+
+/// Returns every added series that matches `filter`, sorted by title.
+pub fn series(db: &Database, filter: &Filter) -> Result<Vec<SeriesRecord>> {
+    let mut records = SeriesConfig::load_all(db)
+        .context("loading series configs")?
+        .into_iter()
+        .filter(|config| {
+            filter.nickname.as_ref().map_or(true, |nickname| {
+                config.nickname.as_str() == nickname.as_str()
+            })
+        })
+        .map(|config| SeriesData::load_from_config(db, Cow::Owned(config)))
+        .collect::<Result<Vec<_>, _>>()
+        .context("loading series")?
+        .iter()
+        .filter(|data| {
+            filter
+                .status
+                .map_or(true, |status| data.entry.status() == status)
+        })
+        .map(SeriesRecord::new)
+        .collect::<Vec<_>>();
+
+    records.sort_unstable_by(|x, y| x.title.cmp(&y.title));
+    Ok(records)
+}
+
+/// Returns every watched episode that matches `filter`, from the most recent to the oldest.
+///
+/// Episodes of series that are no longer added are only included when `filter` doesn't narrow down the series.
+pub fn history(db: &Database, filter: &Filter) -> Result<Vec<WatchRecord>> {
+    use crate::database::schema::watch_history::dsl::{
+        episode, rating, series_id, watch_history, watched_at,
+    };
+
+    let added = series(
+        db,
+        &Filter {
+            status: filter.status,
+            nickname: filter.nickname.clone(),
+            ..Filter::default()
+        },
+    )?;
+
+    let nicknames = added
+        .into_iter()
+        .map(|record| (record.id, record.nickname))
+        .collect::<HashMap<_, _>>();
+
+    let mut query = watch_history
+        .select((series_id, episode, watched_at, rating))
+        .order((watched_at.desc(), episode.desc()))
+        .into_boxed();
+
+    if filter.status.is_some() || filter.nickname.is_some() {
+        query = query.filter(series_id.eq_any(nicknames.keys().copied().collect::<Vec<_>>()));
+    }
+
+    if let Some(since) = filter.since {
+        query = query.filter(watched_at.ge(since));
+    }
+
+    if let Some(limit) = filter.limit {
+        query = query.limit(i64::from(limit));
+    }
+
+    let records = query
+        .load::<(i32, i16, i64, Option<i16>)>(db.conn())
+        .context("loading watch history")?
+        .into_iter()
+        .map(|(id, ep, at, ep_rating)| WatchRecord {
+            series_id: id,
+            episode: ep,
+            watched_at: at,
+            rating: ep_rating,
+            nickname: nicknames.get(&id).cloned(),
+        })
+        .collect();
+
+    Ok(records)
+}
+
+fn status_name(status: Status) -> &'static str {
+    match status {
+        Status::Watching => "watching",
+        Status::Completed => "completed",
+        Status::OnHold => "on_hold",
+        Status::Dropped => "dropped",
+        Status::PlanToWatch => "plan_to_watch",
+        Status::Rewatching => "rewatching",
+    }
+}
+
+fn format_date(date: SeriesDate) -> String {
+    format!("{}-{:02}-{:02}", date.year, date.month, date.day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_names_parse_back() {
+        let statuses = [
+            Status::Watching,
+            Status::Completed,
+            Status::OnHold,
+            Status::Dropped,
+            Status::PlanToWatch,
+            Status::Rewatching,
+        ];
+
+        for &status in &statuses {
+            assert_eq!(Status::from_str(status_name(status)).unwrap(), status);
+        }
+    }
+}
@@ -1,5 +1,6 @@
-use anime::remote::{AccessToken, Remote};
+use anime::remote::{AccessToken, Remote, RemoteService};
 use anyhow::{anyhow, Result};
+use std::sync::Arc;
 
 pub type Username = String;
 
@@ -9,7 +10,7 @@ pub enum RemoteLogin {
 
 pub enum RemoteStatus {
     LoggingIn(Username),
-    LoggedIn(Remote),
+    LoggedIn(Arc<Remote>),
 }
 
 impl RemoteStatus {
@@ -19,4 +20,21 @@ impl RemoteStatus {
             Self::LoggedIn(remote) => Ok(remote),
         }
     }
+
+    /// Returns a handle to the remote that can be used without holding onto the state, such as from a background task.
+    pub fn get_logged_in_shared(&self) -> Result<Arc<Remote>> {
+        match self {
+            Self::LoggingIn(name) => Err(anyhow!("currently logging in as {}", name)),
+            Self::LoggedIn(remote) => Ok(Arc::clone(remote)),
+        }
+    }
+}
+
+/// Returns an error if `remote` can't store scores, since any score sent to it would be dropped.
+pub fn ensure_scores_supported(remote: &Remote) -> Result<()> {
+    if remote.capabilities().supports_scores() {
+        Ok(())
+    } else {
+        Err(anyhow!("scores can't be stored by the current service"))
+    }
 }
@@ -0,0 +1,123 @@
+use crate::database::Database;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use diesel::prelude::*;
+
+/// What a saved event is about.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EventKind {
+    Error,
+    Warning,
+    Notification,
+}
+
+impl EventKind {
+    fn from_i16(value: i16) -> Self {
+        match value {
+            0 => Self::Error,
+            1 => Self::Warning,
+            _ => Self::Notification,
+        }
+    }
+
+    fn as_i16(self) -> i16 {
+        match self {
+            Self::Error => 0,
+            Self::Warning => 1,
+            Self::Notification => 2,
+        }
+    }
+}
+
+/// An error or notification that was logged, kept so it can still be read after it scrolls out of the log or happens
+/// while nobody is looking.
+pub struct SavedEvent {
+    pub kind: EventKind,
+    pub message: String,
+    /// When the event happened, as a UNIX timestamp.
+    pub logged_at: i64,
+    pub read: bool,
+}
+
+impl SavedEvent {
+    /// The number of events that are kept. Older ones are removed as new ones are saved.
+    pub const MAX_SAVED: i64 = 500;
+
+    /// Save a new unread event with `message`.
+    pub fn record(db: &Database, kind: EventKind, message: &str) -> Result<()> {
+        use crate::database::schema::saved_events::dsl;
+
+        db.write_transaction(|| {
+            diesel::insert_into(dsl::saved_events)
+                .values((
+                    dsl::kind.eq(kind.as_i16()),
+                    dsl::message.eq(message),
+                    dsl::logged_at.eq(Utc::now().timestamp()),
+                ))
+                .execute(db.conn())?;
+
+            let oldest_kept = dsl::saved_events
+                .select(dsl::id)
+                .order(dsl::id.desc())
+                .offset(Self::MAX_SAVED - 1)
+                .first::<i32>(db.conn())
+                .optional()?;
+
+            if let Some(oldest_kept) = oldest_kept {
+                diesel::delete(dsl::saved_events.filter(dsl::id.lt(oldest_kept)))
+                    .execute(db.conn())?;
+            }
+
+            Ok(())
+        })
+        .context("saving event")
+    }
+
+    /// Load every saved event, newest first.
+    pub fn load_all(db: &Database) -> diesel::QueryResult<Vec<Self>> {
+        use crate::database::schema::saved_events::dsl::{
+            id, kind, logged_at, message, read, saved_events,
+        };
+
+        let rows = saved_events
+            .select((kind, message, logged_at, read))
+            .order(id.desc())
+            .load::<(i16, String, i64, bool)>(db.conn())?;
+
+        let events = rows
+            .into_iter()
+            .map(|(event_kind, text, at, was_read)| Self {
+                kind: EventKind::from_i16(event_kind),
+                message: text,
+                logged_at: at,
+                read: was_read,
+            })
+            .collect();
+
+        Ok(events)
+    }
+
+    /// Returns the number of events that haven't been read yet.
+    pub fn unread_count(db: &Database) -> diesel::QueryResult<i64> {
+        use crate::database::schema::saved_events::dsl::{read, saved_events};
+
+        saved_events
+            .filter(read.eq(false))
+            .count()
+            .get_result(db.conn())
+    }
+
+    pub fn mark_all_read(db: &Database) -> diesel::QueryResult<usize> {
+        use crate::database::schema::saved_events::dsl::{read, saved_events};
+
+        diesel::update(saved_events.filter(read.eq(false)))
+            .set(read.eq(true))
+            .execute(db.conn())
+    }
+
+    pub fn delete_all(db: &Database) -> diesel::QueryResult<usize> {
+        use crate::database::schema::saved_events::dsl::saved_events;
+
+        diesel::delete(saved_events).execute(db.conn())
+    }
+}
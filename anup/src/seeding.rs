@@ -0,0 +1,132 @@
+use crate::config::TorrentConfig;
+use anyhow::{anyhow, Context, Result};
+use serde_derive::Deserialize;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// The torrents a torrent client is seeding, used to avoid removing files that are still being uploaded.
+pub struct SeedingTorrents {
+    /// The path of the files of each torrent being seeded.
+    paths: Vec<PathBuf>,
+}
+
+impl SeedingTorrents {
+    /// Get the torrents being seeded by the torrent client in `config`.
+    ///
+    /// Returns `None` if no torrent client is set.
+    pub fn query(config: &TorrentConfig) -> Result<Option<Self>> {
+        let url = match &config.qbittorrent_url {
+            Some(url) => url.trim_end_matches('/'),
+            None => return Ok(None),
+        };
+
+        let cookie = match &config.username {
+            Some(username) => Some(
+                Self::login(url, username, config.password.as_deref().unwrap_or(""))
+                    .context("logging in to qBittorrent")?,
+            ),
+            None => None,
+        };
+
+        let mut request =
+            attohttpc::get(format!("{}/api/v2/torrents/info", url)).param("filter", "seeding");
+
+        if let Some(cookie) = &cookie {
+            request = request.header("Cookie", cookie.as_str());
+        }
+
+        let response = request
+            .send()
+            .context("getting torrents from qBittorrent")?;
+
+        if !response.is_success() {
+            return Err(anyhow!(
+                "qBittorrent returned {} when getting torrents",
+                response.status()
+            ));
+        }
+
+        let torrents = response
+            .json::<Vec<Torrent>>()
+            .context("decoding torrents from qBittorrent")?;
+
+        let paths = torrents
+            .into_iter()
+            .map(|torrent| {
+                let path = PathBuf::from(torrent.content_path);
+                path.canonicalize().unwrap_or(path)
+            })
+            .collect();
+
+        Ok(Some(Self { paths }))
+    }
+
+    /// Log in to the qBittorrent Web UI at `url`, and return the cookie that authenticates later requests.
+    fn login(url: &str, username: &str, password: &str) -> Result<String> {
+        let body = format!(
+            "username={}&password={}",
+            form_encode(username),
+            form_encode(password)
+        );
+
+        let response = attohttpc::post(format!("{}/api/v2/auth/login", url))
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .text(body)
+            .send()?;
+
+        // Failed logins still return a success code, but without a session cookie
+        let cookie = response
+            .headers()
+            .get("set-cookie")
+            .and_then(|cookie| cookie.to_str().ok())
+            .and_then(|cookie| cookie.split(';').next())
+            .ok_or_else(|| anyhow!("incorrect username or password"))?;
+
+        Ok(cookie.into())
+    }
+
+    /// Returns true if `path`, or any file within it, is part of a torrent being seeded.
+    pub fn contains(&self, path: &Path) -> bool {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+        self.paths
+            .iter()
+            .any(|seeding| path.starts_with(seeding) || seeding.starts_with(&path))
+    }
+}
+
+#[derive(Deserialize)]
+struct Torrent {
+    /// The path of the torrent's file, or the folder containing its files if it has more than one.
+    content_path: String,
+}
+
+/// Encode `value` to be sent as a field of a form.
+fn form_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char);
+            }
+            _ => {
+                write!(encoded, "%{:02X}", byte).ok();
+            }
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn form_encoding() {
+        assert_eq!(form_encode("admin"), "admin");
+        assert_eq!(form_encode("a b&c=d"), "a%20b%26c%3Dd");
+        assert_eq!(form_encode("パス"), "%E3%83%91%E3%82%B9");
+    }
+}
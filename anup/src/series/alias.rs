@@ -0,0 +1,158 @@
+use super::config::SeriesConfig;
+use crate::database::Database;
+use anyhow::{anyhow, Result};
+use diesel::prelude::*;
+
+/// Alternate names that a series can be referred to by in place of its nickname.
+///
+/// Aliases are compared case-insensitively.
+pub struct SeriesAlias;
+
+impl SeriesAlias {
+    /// The minimum similarity a name must have to a nickname or alias to refer to its series without matching it exactly.
+    const MIN_CONFIDENCE: f32 = 0.85;
+
+    /// Add `alias` to the series with the specified `id`.
+    ///
+    /// Aliases can't be the nickname of a series, or be used by another series.
+    pub fn add(db: &Database, id: i32, alias: &str) -> Result<()> {
+        use crate::database::schema::series_aliases::dsl::{
+            alias as alias_col, series_aliases, series_id,
+        };
+
+        if alias.trim().is_empty() {
+            return Err(anyhow!("aliases can't be empty"));
+        }
+
+        if let Some(existing) = SeriesConfig::nickname_exists(db, alias) {
+            return Err(anyhow!("{} is already a nickname", existing));
+        }
+
+        if let Some(owner) = Self::series_id_of(db, alias)? {
+            let owner =
+                SeriesConfig::id_exists(db, owner).unwrap_or_else(|| "a deleted series".into());
+
+            return Err(anyhow!("{} is already an alias of {}", alias, owner));
+        }
+
+        diesel::insert_into(series_aliases)
+            .values((alias_col.eq(alias), series_id.eq(id)))
+            .execute(db.conn())?;
+
+        Ok(())
+    }
+
+    /// Remove `alias` from the series with the specified `id`.
+    ///
+    /// Returns the number of aliases that were removed, which will be 0 if the series doesn't have `alias`.
+    pub fn remove(db: &Database, id: i32, alias: &str) -> diesel::QueryResult<usize> {
+        use crate::database::schema::series_aliases::dsl::{
+            alias as alias_col, series_aliases, series_id,
+        };
+
+        diesel::delete(
+            series_aliases
+                .filter(alias_col.eq(alias))
+                .filter(series_id.eq(id)),
+        )
+        .execute(db.conn())
+    }
+
+    /// Returns every alias of the series with the specified `id`, in alphabetical order.
+    pub fn for_series(db: &Database, id: i32) -> diesel::QueryResult<Vec<String>> {
+        use crate::database::schema::series_aliases::dsl::{alias, series_aliases, series_id};
+
+        series_aliases
+            .select(alias)
+            .filter(series_id.eq(id))
+            .order(alias.asc())
+            .load(db.conn())
+    }
+
+    /// Returns every alias along with the ID of its series, including those of series that have been deleted.
+    pub fn all(db: &Database) -> diesel::QueryResult<Vec<(i32, String)>> {
+        use crate::database::schema::series_aliases::dsl::{alias, series_aliases, series_id};
+
+        series_aliases.select((series_id, alias)).load(db.conn())
+    }
+
+    /// Returns the ID of the series that has `alias`, including series that have been deleted.
+    pub fn series_id_of(db: &Database, alias: &str) -> diesel::QueryResult<Option<i32>> {
+        use crate::database::schema::series_aliases::dsl::{
+            alias as alias_col, series_aliases, series_id,
+        };
+
+        series_aliases
+            .select(series_id)
+            .filter(alias_col.eq(alias))
+            .get_result(db.conn())
+            .optional()
+    }
+
+    pub fn delete_for_series(db: &Database, id: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::series_aliases::dsl::{series_aliases, series_id};
+
+        diesel::delete(series_aliases.filter(series_id.eq(id))).execute(db.conn())
+    }
+
+    /// Move every alias of the series with the ID `from` to the series with the ID `to`.
+    pub fn move_to_series(db: &Database, from: i32, to: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::series_aliases::dsl::{series_aliases, series_id};
+
+        diesel::update(series_aliases.filter(series_id.eq(from)))
+            .set(series_id.eq(to))
+            .execute(db.conn())
+    }
+
+    /// Returns the nickname of the series that `name` refers to.
+    ///
+    /// `name` can be the nickname of a series or one of its aliases. When neither match exactly, the series with the
+    /// nickname or alias most similar to `name` is used instead, so small typos still find the intended series.
+    pub fn resolve(db: &Database, name: &str) -> diesel::QueryResult<Option<String>> {
+        use crate::database::schema::series_aliases::dsl::{alias, series_aliases, series_id};
+        use crate::database::schema::series_configs::dsl::{id, nickname, series_configs};
+
+        if let Some(existing) = SeriesConfig::nickname_exists(db, name) {
+            return Ok(Some(existing));
+        }
+
+        if let Some(aliased) = Self::series_id_of(db, name)? {
+            if let Some(existing) = SeriesConfig::id_exists(db, aliased) {
+                return Ok(Some(existing));
+            }
+        }
+
+        let configs = series_configs
+            .select((id, nickname))
+            .load::<(i32, String)>(db.conn())?;
+
+        let aliases = series_aliases
+            .select((alias, series_id))
+            .load::<(String, i32)>(db.conn())?;
+
+        let names = configs
+            .iter()
+            .map(|(config_id, config_nickname)| (config_nickname.as_str(), *config_id))
+            .chain(
+                aliases
+                    .iter()
+                    .map(|(aliased, aliased_id)| (aliased.as_str(), *aliased_id)),
+            );
+
+        let name = name.to_lowercase();
+
+        let closest = anime::closest_match(names, Self::MIN_CONFIDENCE, |(candidate, _)| {
+            Some(strsim::jaro_winkler(&candidate.to_lowercase(), &name) as f32)
+        });
+
+        let nickname_of = |target: i32| {
+            configs
+                .iter()
+                .find(|(config_id, _)| *config_id == target)
+                .map(|(_, config_nickname)| config_nickname.clone())
+        };
+
+        // Aliases of deleted series won't have a config to take a nickname from
+        Ok(closest.and_then(|(_, (_, target))| nickname_of(target)))
+    }
+}
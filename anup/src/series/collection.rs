@@ -0,0 +1,96 @@
+use crate::database::Database;
+use anyhow::{anyhow, Result};
+use diesel::prelude::*;
+
+/// User-defined groups of series, such as the ones being watched with friends.
+///
+/// Collections are only kept locally, and their names are compared case-insensitively.
+pub struct SeriesCollection;
+
+impl SeriesCollection {
+    /// Add the series with the specified `id` to the collection called `name`, creating the collection if needed.
+    ///
+    /// Returns false if the series was already in the collection.
+    pub fn add(db: &Database, id: i32, name: &str) -> Result<bool> {
+        use crate::database::schema::series_collections::dsl::{
+            name as name_col, series_collections, series_id,
+        };
+
+        if name.trim().is_empty() {
+            return Err(anyhow!("collection names can't be empty"));
+        }
+
+        let added = diesel::insert_or_ignore_into(series_collections)
+            .values((name_col.eq(name), series_id.eq(id)))
+            .execute(db.conn())?;
+
+        Ok(added > 0)
+    }
+
+    /// Remove the series with the specified `id` from the collection called `name`.
+    ///
+    /// Returns the number of series that were removed, which will be 0 if the series isn't in the collection.
+    pub fn remove(db: &Database, id: i32, name: &str) -> diesel::QueryResult<usize> {
+        use crate::database::schema::series_collections::dsl::{
+            name as name_col, series_collections, series_id,
+        };
+
+        diesel::delete(
+            series_collections
+                .filter(name_col.eq(name))
+                .filter(series_id.eq(id)),
+        )
+        .execute(db.conn())
+    }
+
+    /// Returns the name of every collection the series with the specified `id` is in, in alphabetical order.
+    pub fn for_series(db: &Database, id: i32) -> diesel::QueryResult<Vec<String>> {
+        use crate::database::schema::series_collections::dsl::{
+            name, series_collections, series_id,
+        };
+
+        series_collections
+            .select(name)
+            .filter(series_id.eq(id))
+            .order(name.asc())
+            .load(db.conn())
+    }
+
+    /// Returns the name of every collection, in alphabetical order.
+    pub fn names(db: &Database) -> diesel::QueryResult<Vec<String>> {
+        use crate::database::schema::series_collections::dsl::{name, series_collections};
+
+        series_collections
+            .select(name)
+            .distinct()
+            .order(name.asc())
+            .load(db.conn())
+    }
+
+    /// Returns the ID of every series in the collection called `name`, including series that have been deleted.
+    pub fn members(db: &Database, name: &str) -> diesel::QueryResult<Vec<i32>> {
+        use crate::database::schema::series_collections::dsl::{
+            name as name_col, series_collections, series_id,
+        };
+
+        series_collections
+            .select(series_id)
+            .filter(name_col.eq(name))
+            .load(db.conn())
+    }
+
+    pub fn delete_for_series(db: &Database, id: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::series_collections::dsl::{series_collections, series_id};
+
+        diesel::delete(series_collections.filter(series_id.eq(id))).execute(db.conn())
+    }
+
+    /// Move the series with the ID `from` to the series with the ID `to` in every collection it's in.
+    pub fn move_to_series(db: &Database, from: i32, to: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::series_collections::dsl::{series_collections, series_id};
+
+        diesel::update(series_collections.filter(series_id.eq(from)))
+            .set(series_id.eq(to))
+            .execute(db.conn())
+    }
+}
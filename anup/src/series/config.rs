@@ -1,19 +1,37 @@
+use super::alias::SeriesAlias;
 use super::{SeriesParams, SeriesPath, UpdateParams};
 use crate::database::schema::series_configs;
-use crate::database::{self, Database};
+use crate::database::{self, Database, SharedStr};
 use anime::local::EpisodeParser;
 use anime::remote::{Remote, RemoteService};
 use anyhow::{anyhow, Result};
+use diesel::deserialize::{self, FromSql};
 use diesel::prelude::*;
+use diesel::serialize::{self, Output, ToSql};
+use diesel::sql_types::Text;
 use std::borrow::Cow;
+use std::io::Write;
 
 #[derive(Clone, Queryable, Insertable)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct SeriesConfig {
     pub id: i32,
-    pub nickname: String,
+    pub nickname: SharedStr,
     pub path: SeriesPath,
     pub episode_parser: EpisodeParser,
     pub player_args: database::PlayerArgs,
+    /// The amount to subtract from each episode number found on disk.
+    pub episode_offset: i32,
+    /// Indicates that the list entry should never be synced to or from the remote service.
+    pub local_only: bool,
+    /// Indicates that episodes should be removed from disk once they have been watched.
+    pub remove_watched: bool,
+    /// Indicates that the series was completed and is planned to be rewatched, without its status being changed yet.
+    pub planned_rewatch: bool,
+    /// The title to show instead of the title in the user's preferred format.
+    pub title_override: Option<TitleOverride>,
+    /// Indicates that new episodes of the series shouldn't send notifications.
+    pub muted: bool,
 }
 
 impl SeriesConfig {
@@ -22,13 +40,34 @@ impl SeriesConfig {
             return Err(anyhow!("series already exists as {}", existing));
         }
 
-        Ok(Self {
+        Ok(Self::with_params(id, params))
+    }
+
+    /// Create a new `SeriesConfig` that will take the place of the existing series with the same `id` when saved.
+    ///
+    /// The nickname in `params` must not be used by any series, including the one being replaced.
+    pub fn new_replacing(id: i32, params: SeriesParams, db: &Database) -> Result<Self> {
+        if let Some(existing) = Self::nickname_exists(db, &params.name) {
+            return Err(anyhow!("nickname is already used by {}", existing));
+        }
+
+        Ok(Self::with_params(id, params))
+    }
+
+    fn with_params(id: i32, params: SeriesParams) -> Self {
+        Self {
             id,
-            nickname: params.name,
+            nickname: params.name.into(),
             path: params.path,
             episode_parser: params.parser,
             player_args: database::PlayerArgs::new(),
-        })
+            episode_offset: 0,
+            local_only: false,
+            remove_watched: false,
+            planned_rewatch: false,
+            title_override: None,
+            muted: false,
+        }
     }
 
     /// Update the `SeriesConfig` fields with the specified `params`.
@@ -77,6 +116,14 @@ impl SeriesConfig {
         series_configs.load(db.conn())
     }
 
+    pub fn load_by_id(db: &Database, config_id: i32) -> diesel::QueryResult<Self> {
+        use crate::database::schema::series_configs::dsl::{id, series_configs};
+
+        series_configs
+            .filter(id.eq(config_id))
+            .get_result(db.conn())
+    }
+
     pub fn load_by_name(db: &Database, name: &str) -> diesel::QueryResult<Self> {
         use crate::database::schema::series_configs::dsl::{nickname, series_configs};
 
@@ -85,13 +132,10 @@ impl SeriesConfig {
             .get_result(db.conn())
     }
 
-    /// Delete the series configuration from the database.
-    ///
-    /// This will also remove the series info and entry, if it exists.
-    pub fn delete(&self, db: &Database) -> diesel::QueryResult<usize> {
-        use crate::database::schema::series_configs::dsl::{id, series_configs};
-
-        diesel::delete(series_configs.filter(id.eq(self.id))).execute(db.conn())
+    /// Load the series that `name` refers to, which can be its nickname, one of its aliases, or something similar to either.
+    pub fn load_by_name_or_alias(db: &Database, name: &str) -> diesel::QueryResult<Self> {
+        let nickname = SeriesAlias::resolve(db, name)?.ok_or(diesel::NotFound)?;
+        Self::load_by_name(db, &nickname)
     }
 
     pub fn exists(db: &Database, config_id: i32, params: &SeriesParams) -> Option<String> {
@@ -104,7 +148,8 @@ impl SeriesConfig {
             .ok()
     }
 
-    fn id_exists(db: &Database, config_id: i32) -> Option<String> {
+    /// Returns the nickname of the series with the specified `config_id`, if it exists.
+    pub fn id_exists(db: &Database, config_id: i32) -> Option<String> {
         use crate::database::schema::series_configs::dsl::{id, nickname, series_configs};
 
         series_configs
@@ -113,6 +158,16 @@ impl SeriesConfig {
             .get_result(db.conn())
             .ok()
     }
+
+    pub fn nickname_exists(db: &Database, name: &str) -> Option<String> {
+        use crate::database::schema::series_configs::dsl::{nickname, series_configs};
+
+        series_configs
+            .filter(nickname.eq(name))
+            .select(nickname)
+            .get_result(db.conn())
+            .ok()
+    }
 }
 
 impl PartialEq<String> for SeriesConfig {
@@ -132,3 +187,68 @@ impl<'a> Into<Cow<'a, SeriesConfig>> for &'a SeriesConfig {
         Cow::Borrowed(self)
     }
 }
+
+/// A title to show for a series instead of the title in the user's preferred format.
+#[derive(Clone, Debug, PartialEq, AsExpression, FromSqlRow)]
+#[sql_type = "Text"]
+pub enum TitleOverride {
+    Romaji,
+    English,
+    Native,
+    /// A title chosen by the user.
+    Custom(SharedStr),
+}
+
+impl TitleOverride {
+    const CUSTOM_PREFIX: &'static str = "custom:";
+
+    /// Parse the title variant named by `value`, or return None if it should be shown as the preferred title.
+    ///
+    /// Anything that isn't the name of a variant is used as a custom title.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "preferred" => None,
+            "romaji" => Some(Self::Romaji),
+            "english" => Some(Self::English),
+            "native" => Some(Self::Native),
+            _ => Some(Self::Custom(value.into())),
+        }
+    }
+}
+
+impl<DB> FromSql<Text, DB> for TitleOverride
+where
+    DB: diesel::backend::Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: Option<&DB::RawValue>) -> deserialize::Result<Self> {
+        let value = String::from_sql(bytes)?;
+
+        match value.as_str() {
+            "romaji" => Ok(Self::Romaji),
+            "english" => Ok(Self::English),
+            "native" => Ok(Self::Native),
+            other => match other.strip_prefix(Self::CUSTOM_PREFIX) {
+                Some(title) => Ok(Self::Custom(title.into())),
+                None => Err(format!("unknown title override: {}", other).into()),
+            },
+        }
+    }
+}
+
+impl<DB> ToSql<Text, DB> for TitleOverride
+where
+    DB: diesel::backend::Backend,
+    str: ToSql<Text, DB>,
+{
+    fn to_sql<W: Write>(&self, out: &mut Output<W, DB>) -> serialize::Result {
+        let value = match self {
+            Self::Romaji => Cow::Borrowed("romaji"),
+            Self::English => Cow::Borrowed("english"),
+            Self::Native => Cow::Borrowed("native"),
+            Self::Custom(title) => Cow::Owned(format!("{}{}", Self::CUSTOM_PREFIX, title)),
+        };
+
+        value.to_sql(out)
+    }
+}
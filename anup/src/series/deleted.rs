@@ -0,0 +1,178 @@
+use super::alias::SeriesAlias;
+use super::collection::SeriesCollection;
+use super::config::SeriesConfig;
+use super::entry::SeriesEntry;
+use super::episode_override::EpisodeOverrides;
+use super::episode_title::EpisodeTitles;
+use super::filler::FillerEpisodes;
+use super::history::{PlaybackSession, ResumePosition, WatchEvent};
+use super::sync_failure::SyncFailure;
+use super::SeriesPath;
+use crate::config::Config;
+use crate::database::Database;
+use crate::trash;
+use anyhow::{anyhow, Context, Result};
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Integer, Nullable, Text};
+use std::path::{Path, PathBuf};
+
+/// A series that was deleted, but can still be restored.
+#[derive(Queryable)]
+pub struct DeletedSeries {
+    pub id: i32,
+    pub nickname: String,
+    pub path: SeriesPath,
+    /// Where the files of the series were moved to in the trash, if they were removed.
+    pub trashed_path: Option<String>,
+}
+
+impl DeletedSeries {
+    /// Remove the series with the specified `config`, while keeping everything needed to restore it.
+    ///
+    /// `trashed_path` should point to where the files of the series were moved to in the trash, if they were removed.
+    pub fn trash(db: &Database, config: &SeriesConfig, trashed_path: Option<&Path>) -> Result<()> {
+        use crate::database::schema::series_configs::dsl::{id, series_configs};
+
+        let trashed_path = trashed_path.map(|path| path.to_string_lossy().into_owned());
+
+        db.write_transaction(|| {
+            diesel::sql_query(
+                "INSERT OR REPLACE INTO deleted_series (
+                        id, nickname, path, episode_parser, player_args, episode_offset, local_only, remove_watched,
+                        planned_rewatch, title_override, muted, title_preferred, title_romaji, title_english, title_native,
+                        cover_color, sequel_ids, episodes, episode_length_mins, genres, trashed_path, deleted_at
+                    )
+                    SELECT c.id, c.nickname, c.path, c.episode_parser, c.player_args, c.episode_offset, c.local_only,
+                        c.remove_watched, c.planned_rewatch, c.title_override, c.muted, i.title_preferred, i.title_romaji,
+                        i.title_english, i.title_native, i.cover_color, i.sequel_ids, i.episodes, i.episode_length_mins,
+                        i.genres, ?, ?
+                    FROM series_configs c LEFT JOIN series_info i ON i.id = c.id
+                    WHERE c.id = ?",
+            )
+            .bind::<Nullable<Text>, _>(&trashed_path)
+            .bind::<BigInt, _>(Utc::now().timestamp())
+            .bind::<Integer, _>(config.id)
+            .execute(db.conn())
+            .context("saving deleted series")?;
+
+            // The series info is removed along with its config
+            diesel::delete(series_configs.filter(id.eq(config.id)))
+                .execute(db.conn())
+                .context("removing series config")?;
+
+            Ok(())
+        })
+    }
+
+    /// Load the most recently deleted series with the specified `name`.
+    pub fn load_by_name(db: &Database, name: &str) -> diesel::QueryResult<Self> {
+        use crate::database::schema::deleted_series::dsl::{
+            deleted_at, deleted_series, id, nickname, path, trashed_path,
+        };
+
+        deleted_series
+            .select((id, nickname, path, trashed_path))
+            .filter(nickname.eq(name))
+            .order(deleted_at.desc())
+            .first(db.conn())
+    }
+
+    /// Restore the series to the database, along with its files if they were moved to the trash.
+    ///
+    /// Returns the config of the restored series.
+    pub fn restore(&self, db: &Database, config: &Config) -> Result<SeriesConfig> {
+        use crate::database::schema::deleted_series::dsl::{deleted_series, id};
+
+        if let Some(existing) = SeriesConfig::id_exists(db, self.id) {
+            return Err(anyhow!("series already exists as {}", existing));
+        }
+
+        if SeriesConfig::nickname_exists(db, &self.nickname).is_some() {
+            return Err(anyhow!("nickname is already used by {}", self.nickname));
+        }
+
+        db.write_transaction(|| {
+            diesel::sql_query(
+                "INSERT INTO series_configs (
+                        id, nickname, path, episode_parser, player_args, episode_offset, local_only, remove_watched,
+                        planned_rewatch, title_override, muted
+                    )
+                    SELECT id, nickname, path, episode_parser, player_args, episode_offset, local_only, remove_watched,
+                        planned_rewatch, title_override, muted
+                    FROM deleted_series WHERE id = ?",
+            )
+            .bind::<Integer, _>(self.id)
+            .execute(db.conn())
+            .context("restoring series config")?;
+
+            diesel::sql_query(
+                "INSERT INTO series_info (
+                        id, title_preferred, title_romaji, episodes, episode_length_mins, genres, title_english, title_native,
+                        cover_color, sequel_ids
+                    )
+                    SELECT id, title_preferred, title_romaji, episodes, episode_length_mins, genres, title_english, title_native,
+                        cover_color, sequel_ids
+                    FROM deleted_series WHERE id = ? AND title_preferred IS NOT NULL",
+            )
+            .bind::<Integer, _>(self.id)
+            .execute(db.conn())
+            .context("restoring series info")?;
+
+            diesel::delete(deleted_series.filter(id.eq(self.id)))
+                .execute(db.conn())
+                .context("removing deleted series")?;
+
+            Ok(())
+        })?;
+
+        if let Some(trashed_path) = &self.trashed_path {
+            trash::restore(&PathBuf::from(trashed_path), &self.path.absolute(config))
+                .context("the series was restored, but its files could not be")?;
+        }
+
+        SeriesConfig::load_by_name(db, &self.nickname).map_err(Into::into)
+    }
+
+    /// Permanently remove every series that was deleted more than `retention_days` ago.
+    ///
+    /// Files that were moved to the trash are left for the desktop to clean up.
+    ///
+    /// Returns the number of series that were removed.
+    pub fn purge_expired(db: &Database, retention_days: u32) -> Result<usize> {
+        use crate::database::schema::deleted_series::dsl::{deleted_at, deleted_series, id};
+
+        let cutoff = (Utc::now() - Duration::days(retention_days.into())).timestamp();
+
+        let expired = deleted_series
+            .select(id)
+            .filter(deleted_at.lt(cutoff))
+            .load::<i32>(db.conn())?;
+
+        if expired.is_empty() {
+            return Ok(0);
+        }
+
+        db.write_transaction(|| {
+            for &series_id in &expired {
+                // The same series may have been added again since it was deleted
+                if SeriesConfig::id_exists(db, series_id).is_none() {
+                    SeriesEntry::delete_for_series(db, series_id)?;
+                    WatchEvent::delete_for_series(db, series_id)?;
+                    PlaybackSession::delete_for_series(db, series_id)?;
+                    ResumePosition::delete_for_series(db, series_id)?;
+                    SyncFailure::delete_for_series(db, series_id)?;
+                    SeriesAlias::delete_for_series(db, series_id)?;
+                    SeriesCollection::delete_for_series(db, series_id)?;
+                    FillerEpisodes::delete_for_series(db, series_id)?;
+                    EpisodeOverrides::delete_for_series(db, series_id)?;
+                    EpisodeTitles::delete_for_series(db, series_id)?;
+                }
+
+                diesel::delete(deleted_series.filter(id.eq(series_id))).execute(db.conn())?;
+            }
+
+            Ok(expired.len())
+        })
+    }
+}
@@ -1,13 +1,18 @@
 use super::info::SeriesInfo;
-use crate::config::Config;
+use crate::audit_log::{AuditRecord, SentEntry};
+use crate::config::{Config, SyncConfig, SyncWinner};
 use crate::database::schema::series_entries;
 use crate::database::Database;
-use anime::remote::{Remote, RemoteService, SeriesDate, Status};
-use anyhow::Result;
-use chrono::Local;
+use crate::private_session::PrivateSession;
+use crate::saved_event::{EventKind, SavedEvent};
+use crate::util;
+use anime::remote::{Remote, RemoteService, SeriesDate, SeriesID, Status};
+use anime::sync::{self, Pull, SyncedEntry};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 
-#[derive(Queryable, Insertable)]
+#[derive(Clone, PartialEq, Queryable, Insertable)]
 #[table_name = "series_entries"]
 pub struct SeriesEntry {
     id: i32,
@@ -15,30 +20,136 @@ pub struct SeriesEntry {
     score: Option<i16>,
     status: anime::remote::Status,
     times_rewatched: i16,
+    priority: i16,
     start_date: Option<SeriesDate>,
     end_date: Option<SeriesDate>,
     needs_sync: bool,
 }
 
+/// Every column of the `series_entries` table that is stored in a `SeriesEntry`.
+const ENTRY_COLUMNS: (
+    series_entries::id,
+    series_entries::watched_episodes,
+    series_entries::score,
+    series_entries::status,
+    series_entries::times_rewatched,
+    series_entries::priority,
+    series_entries::start_date,
+    series_entries::end_date,
+    series_entries::needs_sync,
+) = (
+    series_entries::id,
+    series_entries::watched_episodes,
+    series_entries::score,
+    series_entries::status,
+    series_entries::times_rewatched,
+    series_entries::priority,
+    series_entries::start_date,
+    series_entries::end_date,
+    series_entries::needs_sync,
+);
+
 impl SeriesEntry {
+    /// Load the entry of the database's current account.
     pub fn load(db: &Database, entry_id: i32) -> diesel::QueryResult<Self> {
-        use crate::database::schema::series_entries::dsl::{id, series_entries};
+        Self::load_for(db, db.account(), entry_id)
+    }
+
+    /// Load the entry of the specified `account`.
+    pub fn load_for(db: &Database, account: &str, entry_id: i32) -> diesel::QueryResult<Self> {
+        use crate::database::schema::series_entries::dsl::{account_id, id, series_entries};
 
-        series_entries.filter(id.eq(entry_id)).get_result(db.conn())
+        series_entries
+            .select(ENTRY_COLUMNS)
+            .filter(id.eq(entry_id))
+            .filter(account_id.eq(account))
+            .get_result(db.conn())
+    }
+
+    /// Load the entry of the database's current account, or create a new one if it doesn't have one yet.
+    pub fn load_or_new(db: &Database, entry_id: i32) -> diesel::QueryResult<Self> {
+        Self::load_or_new_for(db, db.account(), entry_id)
+    }
+
+    /// Load the entry of the specified `account`, or create a new one if it doesn't have one yet.
+    pub fn load_or_new_for(
+        db: &Database,
+        account: &str,
+        entry_id: i32,
+    ) -> diesel::QueryResult<Self> {
+        Self::load_for(db, account, entry_id)
+            .optional()
+            .map(|entry| entry.unwrap_or_else(|| Self::from(entry_id)))
     }
 
     pub fn save(&self, db: &Database) -> diesel::QueryResult<usize> {
-        use crate::database::schema::series_entries::dsl::series_entries;
+        self.save_for(db, db.account())
+    }
 
-        diesel::replace_into(series_entries)
-            .values(self)
-            .execute(db.conn())
+    /// Save the entry to the specified `account` instead of the database's current one.
+    ///
+    /// Any record of the entry in the sync journal is removed, since the saved entry is up to date with it.
+    pub fn save_for(&self, db: &Database, account: &str) -> diesel::QueryResult<usize> {
+        use crate::database::schema::series_entries::dsl::{account_id, series_entries};
+
+        let saved = diesel::replace_into(series_entries)
+            .values((self, account_id.eq(account)))
+            .execute(db.conn())?;
+
+        remove_from_journal(db, account, self.id)?;
+        Ok(saved)
+    }
+
+    /// Delete the entry of the database's current account.
+    pub fn delete(db: &Database, entry_id: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::series_entries::dsl::{account_id, id, series_entries};
+
+        let entry = series_entries
+            .filter(id.eq(entry_id))
+            .filter(account_id.eq(db.account()));
+
+        diesel::delete(entry).execute(db.conn())
     }
 
+    /// Delete the entry of every account for the series with the specified `id`.
+    pub fn delete_for_series(db: &Database, entry_id: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::series_entries::dsl::{id, series_entries};
+
+        diesel::delete(series_entries.filter(id.eq(entry_id))).execute(db.conn())
+    }
+
+    /// Returns every entry of the current account with changes that haven't been synced, excluding series that are only tracked locally.
     pub fn entries_that_need_sync(db: &Database) -> diesel::QueryResult<Vec<Self>> {
-        use crate::database::schema::series_entries::dsl::{needs_sync, series_entries};
+        use crate::database::schema::series_configs::dsl as configs;
+        use crate::database::schema::series_entries::dsl::{
+            account_id, id, needs_sync, series_entries,
+        };
 
-        series_entries.filter(needs_sync.eq(true)).load(db.conn())
+        let local_only = configs::series_configs
+            .select(configs::id)
+            .filter(configs::local_only.eq(true));
+
+        series_entries
+            .select(ENTRY_COLUMNS)
+            .filter(needs_sync.eq(true))
+            .filter(account_id.eq(db.account()))
+            .filter(id.ne_all(local_only))
+            .load(db.conn())
+    }
+
+    /// Returns the scores of every completed series of the current account that has been rated.
+    pub fn completed_scores(db: &Database) -> diesel::QueryResult<Vec<i16>> {
+        use crate::database::schema::series_entries::dsl::{
+            account_id, score, series_entries, status,
+        };
+
+        series_entries
+            .select(score)
+            .filter(account_id.eq(db.account()))
+            .filter(status.eq(Status::Completed))
+            .filter(score.is_not_null())
+            .load::<Option<i16>>(db.conn())
+            .map(|scores| scores.into_iter().flatten().collect())
     }
 
     pub fn from_remote(remote: &Remote, info: &SeriesInfo) -> Result<Self> {
@@ -48,43 +159,259 @@ impl SeriesEntry {
         }
     }
 
-    pub fn force_sync_to_remote(&mut self, remote: &Remote) -> Result<()> {
-        if remote.is_offline() {
-            return Ok(());
+    /// Send the entry to `remote`, even if it doesn't have any changes.
+    ///
+    /// The progress sent is capped at `episodes`, which is the official episode count of the series if it's known.
+    pub fn force_sync_to_remote(
+        &mut self,
+        remote: &Remote,
+        db: &Database,
+        episodes: Option<i16>,
+    ) -> Result<()> {
+        if PrivateSession::is_active() {
+            return Err(anyhow!(
+                "a private session is active\nend it with the private off command to publish your changes"
+            ));
         }
 
-        remote.update_list_entry(&self.into())?;
-        self.needs_sync = false;
-        Ok(())
+        self.push(remote, db, db.account(), episodes, true)
+    }
+
+    /// Send the entry to `remote` if it has changes.
+    ///
+    /// The entry keeps its changes without sending them while a private session is active. The progress sent is
+    /// capped at `episodes`, which is the official episode count of the series if it's known.
+    pub fn sync_to_remote(
+        &mut self,
+        remote: &Remote,
+        db: &Database,
+        episodes: Option<i16>,
+    ) -> Result<()> {
+        self.sync_to_remote_for(remote, db, db.account(), episodes)
     }
 
-    pub fn sync_to_remote(&mut self, remote: &Remote) -> Result<()> {
-        if !self.needs_sync {
+    /// Send the entry of the specified `account` to `remote` if it has changes, like `sync_to_remote`.
+    pub fn sync_to_remote_for(
+        &mut self,
+        remote: &Remote,
+        db: &Database,
+        account: &str,
+        episodes: Option<i16>,
+    ) -> Result<()> {
+        if PrivateSession::is_active() {
             return Ok(());
         }
 
-        self.force_sync_to_remote(remote)
+        self.push(remote, db, account, episodes, false)
     }
 
-    pub fn force_sync_from_remote(&mut self, remote: &Remote) -> Result<()> {
-        if remote.is_offline() {
+    /// Send the entry to `remote` if it has changes or `force` is true, recording it in the sync journal first.
+    ///
+    /// The record is kept until the entry is saved, so a crash after the entry is sent but before it's saved can be
+    /// reconciled with `recover_journal`. What was sent is also kept in the audit log once the remote service accepts it.
+    fn push(
+        &mut self,
+        remote: &Remote,
+        db: &Database,
+        account: &str,
+        episodes: Option<i16>,
+        force: bool,
+    ) -> Result<()> {
+        if remote.is_offline() || !(force || self.needs_sync) {
             return Ok(());
         }
 
-        *self = match remote.get_list_entry(self.id() as u32)? {
-            Some(entry) => Self::from(entry),
-            None => Self::from(self.id()),
+        self.begin_push(db, account)?;
+        let sent = self.send(remote, episodes)?;
+        self.finish_push(db, account, remote, sent)
+    }
+
+    /// Record the entry in the sync journal before it's sent with `send`.
+    ///
+    /// `push` does this along with the rest of sending the entry, while this is for sending it without holding onto
+    /// the database, such as from a background task.
+    pub fn begin_push(&self, db: &Database, account: &str) -> Result<()> {
+        self.add_to_journal(db, account)
+            .context("recording list entry in the sync journal")?;
+
+        Ok(())
+    }
+
+    /// Send the entry to `remote` with its progress capped at `episodes`, and return what was sent.
+    ///
+    /// The entry must be recorded with `begin_push` first, and `finish_push` called once this succeeds.
+    pub fn send(&mut self, remote: &Remote, episodes: Option<i16>) -> Result<SentEntry> {
+        let mut capped = Capped::new(self, episodes);
+        sync::force_push(&mut capped, remote)?;
+        Ok(SentEntry::new(&capped.to_remote(), remote))
+    }
+
+    /// Acknowledge the entry in the sync journal after it was sent with `send`, and record what was sent in the audit
+    /// log.
+    pub fn finish_push(
+        &self,
+        db: &Database,
+        account: &str,
+        remote: &Remote,
+        sent: SentEntry,
+    ) -> Result<()> {
+        acknowledge_in_journal(db, account, self.id)
+            .context("acknowledging list entry in the sync journal")?;
+
+        // The remote service already has the entry, so failing to record it can't be treated as a failed sync
+        if let Err(err) = AuditRecord::record_sent(db, account, remote, self.id, sent) {
+            let message = format!("failed to record {} in the audit log: {:#}", self.id, err);
+            SavedEvent::record(db, EventKind::Warning, &message).ok();
+        }
+
+        Ok(())
+    }
+
+    fn add_to_journal(&self, db: &Database, account: &str) -> diesel::QueryResult<usize> {
+        use crate::database::schema::sync_journal::dsl::{
+            account_id, acknowledged, end_date, id, priority, score, start_date, status,
+            sync_journal, times_rewatched, watched_episodes,
         };
 
+        diesel::replace_into(sync_journal)
+            .values((
+                id.eq(self.id),
+                account_id.eq(account),
+                watched_episodes.eq(self.watched_episodes),
+                score.eq(self.score),
+                status.eq(self.status),
+                times_rewatched.eq(self.times_rewatched),
+                priority.eq(self.priority),
+                start_date.eq(self.start_date),
+                end_date.eq(self.end_date),
+                acknowledged.eq(false),
+            ))
+            .execute(db.conn())
+    }
+
+    /// Save every entry left in the sync journal by a crash between sending the entry and saving it, so local entries
+    /// don't disagree with the remote service.
+    ///
+    /// Entries that the remote service acknowledged are saved as synced, while ones that may not have reached it are
+    /// marked as needing a sync so they're sent again. Entries of series that are no longer added are dropped.
+    ///
+    /// Returns the number of entries that were recovered.
+    pub fn recover_journal(db: &Database) -> Result<usize> {
+        use crate::database::schema::sync_journal::dsl::{
+            account_id, acknowledged, end_date, id, priority, score, start_date, status,
+            sync_journal, times_rewatched, watched_episodes,
+        };
+
+        let journaled = sync_journal
+            .select((
+                account_id,
+                (
+                    id,
+                    watched_episodes,
+                    score,
+                    status,
+                    times_rewatched,
+                    priority,
+                    start_date,
+                    end_date,
+                    acknowledged,
+                ),
+            ))
+            .load::<(String, Self)>(db.conn())?
+            .into_iter()
+            // Entries are loaded with whether they were acknowledged in place of whether they need a sync
+            .map(|(account, mut entry)| {
+                entry.needs_sync = !entry.needs_sync;
+                (account, entry)
+            })
+            .collect::<Vec<_>>();
+
+        if journaled.is_empty() {
+            return Ok(0);
+        }
+
+        db.write_transaction(|| {
+            let mut recovered = 0;
+
+            for (account, entry) in &journaled {
+                let exists = Self::load_for(db, account, entry.id).optional()?.is_some();
+
+                if !exists {
+                    remove_from_journal(db, account, entry.id)?;
+                    continue;
+                }
+
+                entry.save_for(db, account)?;
+                recovered += 1;
+            }
+
+            Ok(recovered)
+        })
+    }
+
+    pub fn force_sync_from_remote(&mut self, remote: &Remote) -> Result<()> {
+        sync::force_pull(self, remote)?;
         Ok(())
     }
 
-    pub fn sync_from_remote(&mut self, remote: &Remote) -> Result<()> {
-        if self.needs_sync {
+    /// Replace the entry with the one on `remote` if it doesn't have unsynced changes, except for the fields that
+    /// `fields` keeps the local value of.
+    ///
+    /// The entry is marked as needing a sync if a kept value differs from the remote one, so the remote service
+    /// catches up.
+    pub fn sync_from_remote(&mut self, remote: &Remote, fields: &SyncConfig) -> Result<()> {
+        let watched = self.watched_episodes;
+        let score = self.score;
+        let status = self.status;
+        let start_date = self.start_date;
+        let end_date = self.end_date;
+
+        if sync::pull(self, remote)? != Pull::Replaced {
             return Ok(());
         }
 
-        self.force_sync_from_remote(remote)
+        let kept_changes = keep_local(fields.progress, watched, &mut self.watched_episodes)
+            | keep_local(fields.score, score, &mut self.score)
+            | keep_local(fields.status, status, &mut self.status)
+            | keep_local(fields.dates, start_date, &mut self.start_date)
+            | keep_local(fields.dates, end_date, &mut self.end_date);
+
+        if kept_changes {
+            self.needs_sync = true;
+        }
+
+        Ok(())
+    }
+
+    /// Bring back the progress the entry had before being replaced with the one on `remote`, if it was past the
+    /// official episode count of the series and so was capped when it was sent.
+    pub fn restore_extra_progress(&mut self, watched: i16, episodes: i16) {
+        if watched > episodes && self.watched_episodes == episodes {
+            self.watched_episodes = watched;
+        }
+    }
+
+    /// Replace the watched episodes and status with those of the entry on `remote`, while keeping everything else,
+    /// such as after watching episodes with another app.
+    ///
+    /// Returns false if the series isn't on the user's list.
+    pub fn pull_progress(&mut self, remote: &Remote) -> Result<bool> {
+        let remote_entry = match remote.get_list_entry(self.id as SeriesID)? {
+            Some(remote_entry) => remote_entry,
+            None => return Ok(false),
+        };
+
+        // Any other unsynced changes still need to be sent, so whether the entry needs to be synced is left alone
+        self.watched_episodes = remote_entry.watched_eps as i16;
+        self.status = remote_entry.status;
+
+        Ok(true)
+    }
+
+    /// Move the entry to the series with the specified `id`, so its progress is synced to that series instead.
+    pub fn relink(&mut self, id: i32) {
+        self.id = id;
+        self.needs_sync = true;
     }
 
     #[inline(always)]
@@ -92,30 +419,74 @@ impl SeriesEntry {
         self.needs_sync
     }
 
+    /// Make the entry be sent to the remote service the next time it's synced, even if it hasn't changed.
+    pub fn mark_needs_sync(&mut self) {
+        self.needs_sync = true;
+    }
+
     pub fn set_status(&mut self, status: Status, config: &Config) {
+        self.set_status_at(status, Utc::now(), config);
+    }
+
+    /// Set the status as if it were changed at `at`, which the start and end dates will be set from in the local time
+    /// zone.
+    pub fn set_status_at(&mut self, status: Status, at: DateTime<Utc>, config: &Config) {
+        if config.dates.auto_set {
+            self.update_dates(status, util::local_date(at), config);
+        }
+
+        self.status = status;
+        self.needs_sync = true;
+    }
+
+    /// Set the start and end dates to `today` as needed for the status changing to `status`.
+    fn update_dates(&mut self, status: Status, today: SeriesDate, config: &Config) {
+        let today = || Some(today);
+
         match status {
             Status::Watching if self.start_date().is_none() => {
-                self.start_date = Some(Local::today().naive_local().into());
+                self.start_date = today();
             }
-            Status::Rewatching
-                if self.start_date().is_none()
-                    || (self.status() == Status::Completed && config.reset_dates_on_rewatch) =>
-            {
-                self.start_date = Some(Local::today().naive_local().into());
+            Status::Rewatching => {
+                let rewatch_started = self.status() == Status::Completed;
+
+                if self.start_date().is_none() || (rewatch_started && config.reset_dates_on_rewatch)
+                {
+                    self.start_date = today();
+                }
+
+                if rewatch_started && config.dates.clear_end_date_on_rewatch {
+                    self.end_date = None;
+                }
             }
             Status::Completed
                 if self.end_date().is_none()
                     || (self.status() == Status::Rewatching && config.reset_dates_on_rewatch) =>
             {
-                self.end_date = Some(Local::today().naive_local().into());
+                self.end_date = today();
             }
-            Status::Dropped if self.end_date.is_none() => {
-                self.end_date = Some(Local::today().naive_local().into());
+            Status::Dropped if self.end_date.is_none() && config.dates.set_end_date_on_drop => {
+                self.end_date = today();
             }
             _ => (),
         }
+    }
 
-        self.status = status;
+    /// Set the watched episodes and status to the same as `other`, such as when someone else watched the episode too.
+    pub fn match_progress(&mut self, other: &Self, config: &Config) {
+        if self.status != other.status {
+            self.set_status(other.status, config);
+        }
+
+        if self.watched_episodes != other.watched_episodes {
+            self.set_watched_episodes(other.watched_episodes);
+        }
+    }
+
+    /// Remove the start and end dates, so they can be set again by the next status change.
+    pub fn reset_dates(&mut self) {
+        self.start_date = None;
+        self.end_date = None;
         self.needs_sync = true;
     }
 }
@@ -151,22 +522,122 @@ impl_series_entry_getters_setters!(
     watched_episodes: i16 => set_watched_episodes,
     score: Option<i16> => set_score,
     times_rewatched: i16 => set_times_rewatched,
+    priority: i16 => set_priority,
     start_date: Option<SeriesDate> => !,
     end_date: Option<SeriesDate> => !,
 );
 
-impl Into<anime::remote::SeriesEntry> for &mut SeriesEntry {
-    fn into(self) -> anime::remote::SeriesEntry {
+impl SyncedEntry for SeriesEntry {
+    fn series_id(&self) -> SeriesID {
+        self.id as u32
+    }
+
+    fn to_remote(&self) -> anime::remote::SeriesEntry {
         anime::remote::SeriesEntry {
             id: self.id as u32,
             watched_eps: self.watched_episodes as u32,
             score: self.score.map(|score| score as u8),
             status: self.status,
             times_rewatched: self.times_rewatched as u32,
+            priority: self.priority as u32,
             start_date: self.start_date,
             end_date: self.end_date,
         }
     }
+
+    fn replace_with_remote(&mut self, entry: anime::remote::SeriesEntry) {
+        *self = Self::from(entry);
+    }
+
+    #[inline(always)]
+    fn needs_sync(&self) -> bool {
+        self.needs_sync
+    }
+
+    #[inline(always)]
+    fn set_needs_sync(&mut self, needs_sync: bool) {
+        self.needs_sync = needs_sync;
+    }
+}
+
+/// Put the `local` value back into `field` if the local value wins, and return true if that changed it.
+/// A list entry whose progress is capped at the official episode count of its series when it's sent to a remote service,
+/// so progress made on extra episodes stays local.
+struct Capped<'a> {
+    entry: &'a mut SeriesEntry,
+    episodes: Option<i16>,
+}
+
+impl<'a> Capped<'a> {
+    fn new(entry: &'a mut SeriesEntry, episodes: Option<i16>) -> Self {
+        Self { entry, episodes }
+    }
+}
+
+impl SyncedEntry for Capped<'_> {
+    fn series_id(&self) -> SeriesID {
+        self.entry.series_id()
+    }
+
+    fn to_remote(&self) -> anime::remote::SeriesEntry {
+        let mut entry = self.entry.to_remote();
+
+        if let Some(episodes) = self.episodes.filter(|&episodes| episodes > 0) {
+            entry.watched_eps = entry.watched_eps.min(episodes as u32);
+        }
+
+        entry
+    }
+
+    fn replace_with_remote(&mut self, entry: anime::remote::SeriesEntry) {
+        self.entry.replace_with_remote(entry);
+    }
+
+    fn needs_sync(&self) -> bool {
+        self.entry.needs_sync
+    }
+
+    fn set_needs_sync(&mut self, needs_sync: bool) {
+        self.entry.needs_sync = needs_sync;
+    }
+}
+
+fn acknowledge_in_journal(
+    db: &Database,
+    account: &str,
+    entry_id: i32,
+) -> diesel::QueryResult<usize> {
+    use crate::database::schema::sync_journal::dsl::{account_id, acknowledged, id, sync_journal};
+
+    let journaled = sync_journal
+        .filter(id.eq(entry_id))
+        .filter(account_id.eq(account));
+
+    diesel::update(journaled)
+        .set(acknowledged.eq(true))
+        .execute(db.conn())
+}
+
+fn remove_from_journal(db: &Database, account: &str, entry_id: i32) -> diesel::QueryResult<usize> {
+    use crate::database::schema::sync_journal::dsl::{account_id, id, sync_journal};
+
+    let journaled = sync_journal
+        .filter(id.eq(entry_id))
+        .filter(account_id.eq(account));
+
+    diesel::delete(journaled).execute(db.conn())
+}
+
+fn keep_local<T>(winner: SyncWinner, local: T, field: &mut T) -> bool
+where
+    T: PartialEq,
+{
+    if winner != SyncWinner::Local || *field == local {
+        return false;
+    }
+
+    *field = local;
+    true
 }
 
 impl From<anime::remote::SeriesEntry> for SeriesEntry {
@@ -178,6 +649,7 @@ impl From<anime::remote::SeriesEntry> for SeriesEntry {
             score: entry.score.map(Into::into),
             status: entry.status,
             times_rewatched: entry.times_rewatched as i16,
+            priority: entry.priority as i16,
             start_date: entry.start_date,
             end_date: entry.end_date,
             needs_sync: false,
@@ -191,3 +663,118 @@ impl From<i32> for SeriesEntry {
         Self::from(remote_entry)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anime::remote::mock::Mock;
+
+    #[test]
+    fn sync_round_trip() {
+        let remote = Remote::from(Mock::demo());
+        let db = Database::open_in_memory().unwrap();
+        let config = Config::default();
+
+        let mut entry = SeriesEntry::from(900_003);
+        entry.force_sync_from_remote(&remote).unwrap();
+        assert_eq!(entry.status(), Status::PlanToWatch);
+
+        entry.set_status(Status::Watching, &config);
+        entry.set_watched_episodes(2);
+        assert!(entry.needs_sync());
+
+        entry.sync_to_remote(&remote, &db, None).unwrap();
+        assert!(!entry.needs_sync());
+
+        let mut synced = SeriesEntry::from(900_003);
+        synced.sync_from_remote(&remote, &config.sync).unwrap();
+
+        assert_eq!(synced.status(), Status::Watching);
+        assert_eq!(synced.watched_episodes(), 2);
+        assert!(synced.start_date().is_some());
+    }
+
+    #[test]
+    fn sync_keeps_winning_local_fields() {
+        let remote = Remote::from(Mock::demo());
+        let db = Database::open_in_memory().unwrap();
+
+        let fields = SyncConfig {
+            score: SyncWinner::Local,
+            ..SyncConfig::default()
+        };
+
+        let mut remote_entry = SeriesEntry::from(900_003);
+        remote_entry.set_watched_episodes(4);
+        remote_entry.set_score(Some(60));
+        remote_entry.sync_to_remote(&remote, &db, None).unwrap();
+
+        let mut entry = SeriesEntry::from(900_003);
+        entry.score = Some(90);
+        entry.sync_from_remote(&remote, &fields).unwrap();
+
+        assert_eq!(entry.watched_episodes(), 4);
+        assert_eq!(entry.score(), Some(90));
+        assert!(entry.needs_sync());
+    }
+
+    #[test]
+    fn extra_progress_stays_local() {
+        let remote = Remote::from(Mock::demo());
+        let db = Database::open_in_memory().unwrap();
+        let config = Config::default();
+
+        let mut entry = SeriesEntry::from(900_003);
+        entry.set_status(Status::Completed, &config);
+        entry.set_watched_episodes(25);
+        entry.sync_to_remote(&remote, &db, Some(24)).unwrap();
+        assert!(!entry.needs_sync());
+
+        let mut synced = SeriesEntry::from(900_003);
+        synced.sync_from_remote(&remote, &config.sync).unwrap();
+        assert_eq!(synced.watched_episodes(), 24);
+
+        synced.restore_extra_progress(25, 24);
+        assert_eq!(synced.watched_episodes(), 25);
+        assert!(!synced.needs_sync());
+    }
+
+    #[test]
+    fn recover_journaled_entries() {
+        let remote = Remote::from(Mock::demo());
+        let db = Database::open_in_memory().unwrap();
+
+        // Saved before a change that was sent, but never saved because of a crash
+        let mut entry = SeriesEntry::from(900_003);
+        entry.save(&db).unwrap();
+        entry.set_watched_episodes(3);
+        entry.sync_to_remote(&remote, &db, None).unwrap();
+
+        // Sent without the remote service acknowledging it
+        let mut unacknowledged = SeriesEntry::from(900_001);
+        unacknowledged.save(&db).unwrap();
+        unacknowledged.set_watched_episodes(5);
+        unacknowledged.add_to_journal(&db, db.account()).unwrap();
+
+        // Never saved, so there's nothing to recover
+        SeriesEntry::from(900_002)
+            .force_sync_to_remote(&remote, &db, None)
+            .unwrap();
+
+        assert_eq!(SeriesEntry::recover_journal(&db).unwrap(), 2);
+        assert_eq!(SeriesEntry::recover_journal(&db).unwrap(), 0);
+
+        let entry = SeriesEntry::load(&db, 900_003).unwrap();
+        assert_eq!(entry.watched_episodes(), 3);
+        assert!(!entry.needs_sync());
+
+        let unacknowledged = SeriesEntry::load(&db, 900_001).unwrap();
+        assert_eq!(unacknowledged.watched_episodes(), 5);
+        assert!(unacknowledged.needs_sync());
+
+        assert!(SeriesEntry::load(&db, 900_002)
+            .optional()
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::database::Database;
+use anime::local::{CategorizedEpisodes, Episode, SortedEpisodes};
+use anime::SeriesKind;
+use anyhow::{Context, Result};
+use diesel::prelude::*;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::mem;
+
+/// Files of a series that were given their episode number by hand, because the episode parser got them wrong.
+///
+/// Overrides are set with the episode number the series shows, so they keep working when its episode offset changes.
+#[derive(Clone, Default)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub struct EpisodeOverrides(BTreeMap<String, i16>);
+
+impl EpisodeOverrides {
+    pub fn load(db: &Database, id: i32) -> diesel::QueryResult<Self> {
+        use crate::database::schema::episode_overrides::dsl::{
+            episode, episode_overrides, filename, series_id,
+        };
+
+        episode_overrides
+            .select((filename, episode))
+            .filter(series_id.eq(id))
+            .load::<(String, i16)>(db.conn())
+            .map(|rows| Self(rows.into_iter().collect()))
+    }
+
+    /// Give `name` the episode number `ep` in the series with the specified `id`, replacing any override it already had.
+    pub fn set(&mut self, db: &Database, id: i32, name: String, ep: i16) -> Result<()> {
+        use crate::database::schema::episode_overrides::dsl::{
+            episode, episode_overrides, filename, series_id,
+        };
+
+        diesel::replace_into(episode_overrides)
+            .values((series_id.eq(id), filename.eq(&name), episode.eq(ep)))
+            .execute(db.conn())
+            .context("saving episode override")?;
+
+        self.0.insert(name, ep);
+        Ok(())
+    }
+
+    pub fn delete_for_series(db: &Database, id: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::episode_overrides::dsl::{episode_overrides, series_id};
+
+        diesel::delete(episode_overrides.filter(series_id.eq(id))).execute(db.conn())
+    }
+
+    /// Move the episode overrides of the series with the ID `from` to the series with the ID `to`.
+    pub fn move_to_series(db: &Database, from: i32, to: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::episode_overrides::dsl::{episode_overrides, series_id};
+
+        diesel::update(episode_overrides.filter(series_id.eq(from)))
+            .set(series_id.eq(to))
+            .execute(db.conn())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number `filename` would have been parsed as to end up as its overridden episode once the series'
+    /// episode `offset` is applied.
+    ///
+    /// Returns None if `filename` doesn't have an override, or it can't be reached with `offset`.
+    pub fn parsed_number(&self, filename: &str, offset: i32) -> Option<u32> {
+        let episode = self.0.get(filename)?;
+        let number = i64::from(*episode) + i64::from(offset);
+
+        if number < 1 || number > i64::from(u32::MAX) {
+            return None;
+        }
+
+        Some(number as u32)
+    }
+
+    /// Give every overridden file in `episodes` the number it was overridden with instead of the one it was parsed as.
+    ///
+    /// Overridden files are always treated as seasonal episodes, and replace any other file parsed as the same episode.
+    pub fn apply(&self, episodes: CategorizedEpisodes, offset: i32) -> CategorizedEpisodes {
+        if self.is_empty() {
+            return episodes;
+        }
+
+        let mut episodes = episodes.take();
+        let mut overridden = Vec::new();
+
+        for category in episodes.values_mut() {
+            let (found, kept): (Vec<_>, Vec<_>) = mem::take(category)
+                .take()
+                .into_iter()
+                .partition(|ep| self.0.contains_key(&ep.filename));
+
+            *category = SortedEpisodes::with_episodes(kept);
+            overridden.extend(found);
+        }
+
+        episodes.retain(|_, category| !category.is_empty());
+
+        for episode in overridden {
+            if let Some(number) = self.parsed_number(&episode.filename, offset) {
+                episodes
+                    .entry(SeriesKind::Season)
+                    .or_default()
+                    .insert(Episode::new(number, episode.filename));
+            }
+        }
+
+        CategorizedEpisodes::with_sorted(episodes)
+    }
+}
+
+impl fmt::Display for EpisodeOverrides {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (filename, episode)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "{} -> {}", filename, episode)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn apply_overrides() {
+        let overrides = EpisodeOverrides(
+            vec![("extra.mkv".into(), 3), ("ep 2.mkv".into(), 4)]
+                .into_iter()
+                .collect(),
+        );
+
+        let season = SortedEpisodes::with_episodes(vec![
+            Episode::new(13, "ep 1.mkv".into()),
+            Episode::new(14, "ep 2.mkv".into()),
+            Episode::new(16, "wrong 4.mkv".into()),
+        ]);
+
+        let special = SortedEpisodes::with_episodes(vec![Episode::new(1, "extra.mkv".into())]);
+
+        let mut map = HashMap::new();
+        map.insert(SeriesKind::Season, season);
+        map.insert(SeriesKind::Special, special);
+
+        let episodes = overrides
+            .apply(CategorizedEpisodes::with_sorted(map), 12)
+            .take_only_category()
+            .unwrap()
+            .with_offset(12);
+
+        let found = episodes
+            .iter()
+            .map(|ep| (ep.number, ep.filename.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(found, [(1, "ep 1.mkv"), (3, "extra.mkv"), (4, "ep 2.mkv")]);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::database::Database;
+use anime::remote::EpisodeTitle;
+use anyhow::{Context, Result};
+use diesel::prelude::*;
+use std::convert::TryFrom;
+
+/// The titles of the episodes of a series that the remote service knows of, in ascending order of episode.
+#[derive(Clone, Default)]
+pub struct EpisodeTitles(Vec<(i16, String)>);
+
+impl EpisodeTitles {
+    pub fn load(db: &Database, id: i32) -> diesel::QueryResult<Self> {
+        use crate::database::schema::episode_titles::dsl::{
+            episode, episode_titles, series_id, title,
+        };
+
+        episode_titles
+            .select((episode, title))
+            .filter(series_id.eq(id))
+            .order(episode.asc())
+            .load(db.conn())
+            .map(Self)
+    }
+
+    /// Replace the episode titles of the series with the specified `id` with these ones.
+    pub fn save(&self, db: &Database, id: i32) -> Result<()> {
+        use crate::database::schema::episode_titles::dsl::{
+            episode, episode_titles, series_id, title,
+        };
+
+        db.write_transaction(|| {
+            Self::delete_for_series(db, id)?;
+
+            let rows = self
+                .0
+                .iter()
+                .map(|(ep, name)| (series_id.eq(id), episode.eq(ep), title.eq(name)))
+                .collect::<Vec<_>>();
+
+            diesel::insert_into(episode_titles)
+                .values(&rows)
+                .execute(db.conn())?;
+
+            Ok(())
+        })
+        .context("saving episode titles")
+    }
+
+    pub fn delete_for_series(db: &Database, id: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::episode_titles::dsl::{episode_titles, series_id};
+
+        diesel::delete(episode_titles.filter(series_id.eq(id))).execute(db.conn())
+    }
+
+    /// Returns the title of `episode`, if it's known.
+    pub fn get(&self, episode: u32) -> Option<&str> {
+        let episode = i16::try_from(episode).ok()?;
+
+        self.0
+            .binary_search_by_key(&episode, |(ep, _)| *ep)
+            .ok()
+            .map(|index| self.0[index].1.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<Vec<EpisodeTitle>> for EpisodeTitles {
+    fn from(titles: Vec<EpisodeTitle>) -> Self {
+        let mut titles = titles
+            .into_iter()
+            .filter_map(|title| Some((i16::try_from(title.episode).ok()?, title.title)))
+            .collect::<Vec<_>>();
+
+        titles.sort_unstable_by_key(|(episode, _)| *episode);
+        titles.dedup_by_key(|(episode, _)| *episode);
+
+        Self(titles)
+    }
+}
@@ -0,0 +1,188 @@
+use crate::database::Database;
+use anyhow::{anyhow, Context, Result};
+use diesel::prelude::*;
+use std::fmt;
+
+/// Episodes of a series that have been marked as filler, in ascending order.
+///
+/// Filler episodes are only kept locally, and are flagged in the info panel.
+#[derive(Clone, Default)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub struct FillerEpisodes(Vec<i16>);
+
+impl FillerEpisodes {
+    /// The separator between ranges in the text of filler episodes.
+    const SEPARATOR: char = ',';
+
+    pub fn load(db: &Database, id: i32) -> diesel::QueryResult<Self> {
+        use crate::database::schema::filler_episodes::dsl::{episode, filler_episodes, series_id};
+
+        filler_episodes
+            .select(episode)
+            .filter(series_id.eq(id))
+            .order(episode.asc())
+            .load(db.conn())
+            .map(Self)
+    }
+
+    /// Replace the filler episodes of the series with the specified `id` with these ones.
+    pub fn save(&self, db: &Database, id: i32) -> Result<()> {
+        use crate::database::schema::filler_episodes::dsl::{episode, filler_episodes, series_id};
+
+        db.write_transaction(|| {
+            Self::delete_for_series(db, id)?;
+
+            let rows = self
+                .0
+                .iter()
+                .map(|&ep| (series_id.eq(id), episode.eq(ep)))
+                .collect::<Vec<_>>();
+
+            diesel::insert_into(filler_episodes)
+                .values(&rows)
+                .execute(db.conn())?;
+
+            Ok(())
+        })
+        .context("saving filler episodes")
+    }
+
+    pub fn delete_for_series(db: &Database, id: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::filler_episodes::dsl::{filler_episodes, series_id};
+
+        diesel::delete(filler_episodes.filter(series_id.eq(id))).execute(db.conn())
+    }
+
+    /// Move the filler episodes of the series with the ID `from` to the series with the ID `to`.
+    pub fn move_to_series(db: &Database, from: i32, to: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::filler_episodes::dsl::{filler_episodes, series_id};
+
+        diesel::update(filler_episodes.filter(series_id.eq(from)))
+            .set(series_id.eq(to))
+            .execute(db.conn())
+    }
+
+    /// Parse a list of episodes and episode ranges, such as `5-7, 12`.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut episodes = Vec::new();
+
+        for part in text.split(Self::SEPARATOR).map(str::trim) {
+            if part.is_empty() {
+                continue;
+            }
+
+            let (start, end) = match part.split_once('-') {
+                Some((start, end)) => (start.trim(), end.trim()),
+                None => (part, part),
+            };
+
+            let parse_episode = |value: &str| match value.parse::<i16>() {
+                Ok(episode) if episode > 0 => Ok(episode),
+                _ => Err(anyhow!("{} is not a valid episode", value)),
+            };
+
+            let start = parse_episode(start)?;
+            let end = parse_episode(end)?;
+
+            if start > end {
+                return Err(anyhow!("{} must end after it starts", part));
+            }
+
+            episodes.extend(start..=end);
+        }
+
+        if episodes.is_empty() {
+            return Err(anyhow!("specify the filler episodes, such as 5-7,12"));
+        }
+
+        episodes.sort_unstable();
+        episodes.dedup();
+
+        Ok(Self(episodes))
+    }
+
+    pub fn contains(&self, episode: i16) -> bool {
+        self.0.binary_search(&episode).is_ok()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of episodes that have to be watched for the next episode to not be filler, starting from `watched`.
+    ///
+    /// When `total` is known, the last episode is never skipped so the series can still be completed by watching it.
+    pub fn skippable_after(&self, watched: i16, total: Option<i16>) -> i16 {
+        let mut skipped = 0;
+
+        loop {
+            let next = watched + skipped + 1;
+
+            if !self.contains(next) || total.map_or(false, |total| next >= total) {
+                return skipped;
+            }
+
+            skipped += 1;
+        }
+    }
+}
+
+impl fmt::Display for FillerEpisodes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut episodes = self.0.iter().copied().peekable();
+        let mut first = true;
+
+        while let Some(start) = episodes.next() {
+            let mut end = start;
+
+            while episodes.peek() == Some(&(end + 1)) {
+                end += 1;
+                episodes.next();
+            }
+
+            if !first {
+                write!(f, "{} ", Self::SEPARATOR)?;
+            }
+
+            first = false;
+
+            if start == end {
+                write!(f, "{}", start)?;
+            } else {
+                write!(f, "{}-{}", start, end)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_display() {
+        let filler = FillerEpisodes::parse("12, 5-7,6 ,20-20").unwrap();
+
+        assert_eq!(filler, FillerEpisodes(vec![5, 6, 7, 12, 20]));
+        assert_eq!(filler.to_string(), "5-7, 12, 20");
+
+        assert!(FillerEpisodes::parse("").is_err());
+        assert!(FillerEpisodes::parse("7-5").is_err());
+        assert!(FillerEpisodes::parse("0").is_err());
+        assert!(FillerEpisodes::parse("a-3").is_err());
+    }
+
+    #[test]
+    fn skippable_after() {
+        let filler = FillerEpisodes(vec![3, 4, 5, 8]);
+
+        assert_eq!(filler.skippable_after(0, None), 0);
+        assert_eq!(filler.skippable_after(2, None), 3);
+        assert_eq!(filler.skippable_after(3, Some(12)), 2);
+        // The last episode is never skipped
+        assert_eq!(filler.skippable_after(2, Some(5)), 2);
+        assert_eq!(filler.skippable_after(7, Some(8)), 0);
+    }
+}
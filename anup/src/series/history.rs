@@ -0,0 +1,574 @@
+use crate::database::schema::{active_playbacks, playback_sessions, watch_history};
+use crate::database::Database;
+use crate::util;
+use chrono::{DateTime, TimeZone, Utc};
+use diesel::prelude::*;
+use std::collections::BTreeMap;
+use std::process;
+
+/// A single episode that was watched to completion.
+#[derive(Insertable)]
+#[table_name = "watch_history"]
+pub struct WatchEvent {
+    pub series_id: i32,
+    pub episode: i16,
+    pub minutes: i16,
+    pub watched_at: i64,
+}
+
+impl WatchEvent {
+    /// Record that `episode` of the series with the specified `series_id` was watched at `watched_at`.
+    pub fn record(
+        db: &Database,
+        series_id: i32,
+        episode: i16,
+        minutes: i16,
+        watched_at: DateTime<Utc>,
+    ) -> diesel::QueryResult<usize> {
+        let event = Self {
+            series_id,
+            episode,
+            minutes,
+            watched_at: watched_at.timestamp(),
+        };
+
+        diesel::insert_into(watch_history::table)
+            .values(&event)
+            .execute(db.conn())
+    }
+
+    /// Returns when `ep` of the series with the specified `id` was last watched, if it has been watched before.
+    pub fn last_watched_at(db: &Database, id: i32, ep: i16) -> diesel::QueryResult<Option<i64>> {
+        use crate::database::schema::watch_history::dsl::{
+            episode, series_id, watch_history, watched_at,
+        };
+
+        watch_history
+            .select(diesel::dsl::max(watched_at))
+            .filter(series_id.eq(id))
+            .filter(episode.eq(ep))
+            .get_result(db.conn())
+    }
+
+    /// Returns when any episode of the series with the specified `id` was last watched.
+    pub fn latest_watched_at(db: &Database, id: i32) -> diesel::QueryResult<Option<i64>> {
+        use crate::database::schema::watch_history::dsl::{series_id, watch_history, watched_at};
+
+        watch_history
+            .select(diesel::dsl::max(watched_at))
+            .filter(series_id.eq(id))
+            .get_result(db.conn())
+    }
+
+    /// Returns the number of episodes watched from `from` up until `to`, as UNIX timestamps.
+    pub fn count_between(db: &Database, from: i64, to: i64) -> diesel::QueryResult<i64> {
+        use crate::database::schema::watch_history::dsl::{watch_history, watched_at};
+
+        watch_history
+            .filter(watched_at.ge(from))
+            .filter(watched_at.lt(to))
+            .count()
+            .get_result(db.conn())
+    }
+
+    /// Rate the most recent time `ep` of the series with the specified `id` was watched.
+    ///
+    /// Returns the number of events that were rated, which will be 0 if the episode hasn't been watched to completion.
+    pub fn rate(db: &Database, id: i32, ep: i16, value: i16) -> diesel::QueryResult<usize> {
+        use crate::database::schema::watch_history::dsl::{
+            episode, id as event_id, rating, series_id, watch_history,
+        };
+
+        let latest = watch_history
+            .select(diesel::dsl::max(event_id))
+            .filter(series_id.eq(id))
+            .filter(episode.eq(ep))
+            .get_result::<Option<i32>>(db.conn())?;
+
+        match latest {
+            Some(latest) => diesel::update(watch_history.filter(event_id.eq(latest)))
+                .set(rating.eq(value))
+                .execute(db.conn()),
+            None => Ok(0),
+        }
+    }
+
+    pub fn delete_for_series(db: &Database, id: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::watch_history::dsl::{series_id, watch_history};
+
+        diesel::delete(watch_history.filter(series_id.eq(id))).execute(db.conn())
+    }
+
+    /// Move every event of the series with the ID `from` to the series with the ID `to`.
+    pub fn move_to_series(db: &Database, from: i32, to: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::watch_history::dsl::{series_id, watch_history};
+
+        diesel::update(watch_history.filter(series_id.eq(from)))
+            .set(series_id.eq(to))
+            .execute(db.conn())
+    }
+}
+
+/// The totals of every watch event recorded for a series.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct WatchTotals {
+    pub episodes: u32,
+    pub mins: u32,
+    pub ratings: Option<EpisodeRatings>,
+}
+
+impl WatchTotals {
+    pub fn load(db: &Database, id: i32) -> diesel::QueryResult<Self> {
+        use crate::database::schema::watch_history::dsl::{minutes, series_id, watch_history};
+
+        let history = watch_history.filter(series_id.eq(id));
+
+        let episodes = history.count().get_result::<i64>(db.conn())?;
+        let mins = history
+            .select(diesel::dsl::sum(minutes))
+            .get_result::<Option<i64>>(db.conn())?;
+
+        Ok(Self {
+            episodes: episodes as u32,
+            mins: mins.unwrap_or(0) as u32,
+            ratings: EpisodeRatings::load(db, id)?,
+        })
+    }
+
+    pub fn add(&mut self, minutes: i16) {
+        self.episodes += 1;
+        self.mins += minutes.max(0) as u32;
+    }
+}
+
+/// A summary of the ratings given to the episodes of a series.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct EpisodeRatings {
+    pub average: f32,
+    /// The episode with the highest rating, along with its rating.
+    pub best: (i16, i16),
+    /// The episode with the lowest rating, along with its rating.
+    pub worst: (i16, i16),
+}
+
+impl EpisodeRatings {
+    /// The lowest rating an episode can be given.
+    pub const MIN: i16 = 1;
+    /// The highest rating an episode can be given.
+    pub const MAX: i16 = 10;
+
+    /// Load the ratings of the series with the specified `id`, or `None` if none of its episodes have been rated.
+    pub fn load(db: &Database, id: i32) -> diesel::QueryResult<Option<Self>> {
+        use crate::database::schema::watch_history::dsl::{
+            episode, id as event_id, rating, series_id, watch_history,
+        };
+
+        let ratings = watch_history
+            .select((episode, rating))
+            .filter(series_id.eq(id))
+            .filter(rating.is_not_null())
+            .order(event_id.asc())
+            .load::<(i16, Option<i16>)>(db.conn())?;
+
+        let ratings = ratings
+            .into_iter()
+            .filter_map(|(ep, value)| value.map(|value| (ep, value)));
+
+        Ok(Self::summarize(ratings))
+    }
+
+    /// Summarize `ratings` of `(episode, rating)`, where the last rating of an episode replaces any before it.
+    fn summarize<I>(ratings: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = (i16, i16)>,
+    {
+        let latest = ratings.into_iter().collect::<BTreeMap<_, _>>();
+
+        if latest.is_empty() {
+            return None;
+        }
+
+        let total = latest
+            .values()
+            .map(|&rating| f32::from(rating))
+            .sum::<f32>();
+        let average = total / latest.len() as f32;
+
+        // Ties go to the earliest episode
+        let best = latest.iter().rev().max_by_key(|(_, &rating)| rating)?;
+        let worst = latest.iter().min_by_key(|(_, &rating)| rating)?;
+
+        Some(Self {
+            average,
+            best: (*best.0, *best.1),
+            worst: (*worst.0, *worst.1),
+        })
+    }
+}
+
+/// A single run of the player for an episode, whether or not it was watched long enough to count.
+#[derive(Queryable, Insertable)]
+#[table_name = "playback_sessions"]
+pub struct PlaybackSession {
+    pub series_id: i32,
+    pub episode: i16,
+    pub started_at: i64,
+    pub seconds: i32,
+    pub exit_code: Option<i32>,
+    pub counted: bool,
+}
+
+impl PlaybackSession {
+    /// The percentage of an episode's length that a session must last to not be considered suspiciously short.
+    const MIN_PCNT_OF_EPISODE: i32 = 10;
+
+    pub fn save(&self, db: &Database) -> diesel::QueryResult<usize> {
+        diesel::insert_into(playback_sessions::table)
+            .values(self)
+            .execute(db.conn())
+    }
+
+    /// Returns true if a session for the same episode that started at the same time has already been saved.
+    pub fn exists(&self, db: &Database) -> diesel::QueryResult<bool> {
+        use crate::database::schema::playback_sessions::dsl::{
+            episode, playback_sessions, series_id, started_at,
+        };
+
+        let existing = playback_sessions
+            .filter(series_id.eq(self.series_id))
+            .filter(episode.eq(self.episode))
+            .filter(started_at.eq(self.started_at));
+
+        diesel::select(diesel::dsl::exists(existing)).get_result(db.conn())
+    }
+
+    /// Load the most recent sessions of the series with the specified `id`, starting with the newest.
+    pub fn load_recent(db: &Database, id: i32, limit: i64) -> diesel::QueryResult<Vec<Self>> {
+        use crate::database::schema::playback_sessions::dsl::{
+            counted, episode, exit_code, playback_sessions, seconds, series_id, started_at,
+        };
+
+        playback_sessions
+            .select((series_id, episode, started_at, seconds, exit_code, counted))
+            .filter(series_id.eq(id))
+            .order(started_at.desc())
+            .limit(limit)
+            .load(db.conn())
+    }
+
+    /// Mark the most recent session of the series with the specified `id` as counted.
+    pub fn mark_latest_counted(db: &Database, id: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::playback_sessions::dsl::{
+            counted, id as session_id, playback_sessions, series_id,
+        };
+
+        let latest = playback_sessions
+            .select(diesel::dsl::max(session_id))
+            .filter(series_id.eq(id))
+            .get_result::<Option<i32>>(db.conn())?;
+
+        match latest {
+            Some(latest) => diesel::update(playback_sessions.filter(session_id.eq(latest)))
+                .set(counted.eq(true))
+                .execute(db.conn()),
+            None => Ok(0),
+        }
+    }
+
+    pub fn delete_for_series(db: &Database, id: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::playback_sessions::dsl::{playback_sessions, series_id};
+
+        diesel::delete(playback_sessions.filter(series_id.eq(id))).execute(db.conn())
+    }
+
+    /// Move every session of the series with the ID `from` to the series with the ID `to`.
+    pub fn move_to_series(db: &Database, from: i32, to: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::playback_sessions::dsl::{playback_sessions, series_id};
+
+        diesel::update(playback_sessions.filter(series_id.eq(from)))
+            .set(series_id.eq(to))
+            .execute(db.conn())
+    }
+
+    /// Returns true if the session lasted less than 10% of an episode with the specified length.
+    pub fn is_suspiciously_short(&self, episode_length_mins: i16) -> bool {
+        self.seconds * 100 < i32::from(episode_length_mins) * 60 * Self::MIN_PCNT_OF_EPISODE
+    }
+}
+
+/// A position to resume an episode from that was imported from mpv.
+///
+/// Positions are only used once, since mpv saves its own from then on.
+pub struct ResumePosition;
+
+impl ResumePosition {
+    /// Set the position of episode `ep` of the series with the specified `id` to `secs`, replacing the one it had.
+    pub fn save(db: &Database, id: i32, ep: i16, secs: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::resume_positions::dsl::{
+            episode, resume_positions, seconds, series_id,
+        };
+
+        diesel::replace_into(resume_positions)
+            .values((series_id.eq(id), episode.eq(ep), seconds.eq(secs)))
+            .execute(db.conn())
+    }
+
+    /// Remove the position of episode `ep` of the series with the specified `id`, and return it if it had one.
+    pub fn take(db: &Database, id: i32, ep: i16) -> diesel::QueryResult<Option<i32>> {
+        use crate::database::schema::resume_positions::dsl::{
+            episode, resume_positions, seconds, series_id,
+        };
+
+        let position = resume_positions
+            .filter(series_id.eq(id))
+            .filter(episode.eq(ep));
+
+        let secs = position
+            .select(seconds)
+            .first::<i32>(db.conn())
+            .optional()?;
+
+        if secs.is_some() {
+            diesel::delete(position).execute(db.conn())?;
+        }
+
+        Ok(secs)
+    }
+
+    pub fn delete_for_series(db: &Database, id: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::resume_positions::dsl::{resume_positions, series_id};
+
+        diesel::delete(resume_positions.filter(series_id.eq(id))).execute(db.conn())
+    }
+
+    /// Move every position of the series with the ID `from` to the series with the ID `to`.
+    pub fn move_to_series(db: &Database, from: i32, to: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::resume_positions::dsl::{resume_positions, series_id};
+
+        diesel::update(resume_positions.filter(series_id.eq(from)))
+            .set(series_id.eq(to))
+            .execute(db.conn())
+    }
+}
+
+/// Tracks when the player was started for an episode, so a `PlaybackSession` can be made once it exits.
+///
+/// The episode is recorded as an `ActivePlayback` until then.
+pub struct PlaybackTimer {
+    series_id: i32,
+    episode: i16,
+    started_at: DateTime<Utc>,
+}
+
+impl PlaybackTimer {
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn start(db: &Database, series_id: i32, episode: i16) -> diesel::QueryResult<Self> {
+        let timer = Self {
+            series_id,
+            episode,
+            started_at: Utc::now(),
+        };
+
+        let playback = ActivePlayback {
+            series_id,
+            episode,
+            started_at: timer.started_at.timestamp(),
+            pid: process::id() as i32,
+        };
+
+        diesel::replace_into(active_playbacks::table)
+            .values(&playback)
+            .execute(db.conn())?;
+
+        Ok(timer)
+    }
+
+    /// Take over the timer of episode `ep` of the series with the specified `id`, which was started at `start` by
+    /// another process, so it isn't seen as interrupted once that process closes.
+    ///
+    /// Returns `Ok(None)` if the episode isn't being played.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn adopt(db: &Database, id: i32, ep: i16, start: i64) -> diesel::QueryResult<Option<Self>> {
+        use crate::database::schema::active_playbacks::dsl::{
+            active_playbacks, episode, pid, series_id, started_at,
+        };
+
+        let playback = active_playbacks
+            .filter(series_id.eq(id))
+            .filter(episode.eq(ep))
+            .filter(started_at.eq(start));
+
+        let updated = diesel::update(playback)
+            .set(pid.eq(process::id() as i32))
+            .execute(db.conn())?;
+
+        if updated == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            series_id: id,
+            episode: ep,
+            started_at: Utc.timestamp(start, 0),
+        }))
+    }
+
+    #[inline(always)]
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    pub fn finish(
+        self,
+        db: &Database,
+        exit_code: Option<i32>,
+        counted: bool,
+    ) -> diesel::QueryResult<PlaybackSession> {
+        self.remove_active(db)?;
+
+        let seconds = (Utc::now() - self.started_at).num_seconds();
+
+        Ok(PlaybackSession {
+            series_id: self.series_id,
+            episode: self.episode,
+            started_at: self.started_at.timestamp(),
+            seconds: seconds.clamp(0, i32::MAX.into()) as i32,
+            exit_code,
+            counted,
+        })
+    }
+
+    /// Stop tracking the episode without making a session, such as when its player was closed on purpose.
+    pub fn cancel(self, db: &Database) -> diesel::QueryResult<()> {
+        self.remove_active(db)
+    }
+
+    fn remove_active(&self, db: &Database) -> diesel::QueryResult<()> {
+        use crate::database::schema::active_playbacks::dsl::{
+            active_playbacks, series_id, started_at,
+        };
+
+        diesel::delete(
+            active_playbacks
+                .filter(series_id.eq(self.series_id))
+                .filter(started_at.eq(self.started_at.timestamp())),
+        )
+        .execute(db.conn())
+        .map(|_| ())
+    }
+}
+
+/// An episode that is being played by any instance of the program.
+///
+/// One that is left over after its process has closed means the program closed before the player did,
+/// such as from a crash, and the episode may need to be counted.
+#[derive(Queryable, Insertable)]
+#[table_name = "active_playbacks"]
+pub struct ActivePlayback {
+    pub series_id: i32,
+    pub episode: i16,
+    pub started_at: i64,
+    /// The ID of the process that started the player.
+    pub pid: i32,
+}
+
+impl ActivePlayback {
+    /// Remove and return every playback that was started by a process that has since closed, starting with the oldest.
+    pub fn take_interrupted(db: &Database) -> diesel::QueryResult<Vec<Self>> {
+        use crate::database::schema::active_playbacks::dsl::{
+            active_playbacks, series_id, started_at,
+        };
+
+        let interrupted = active_playbacks
+            .order(started_at.asc())
+            .load::<Self>(db.conn())?
+            .into_iter()
+            .filter(|playback| !playback.is_process_running())
+            .collect::<Vec<_>>();
+
+        for playback in &interrupted {
+            diesel::delete(active_playbacks.filter(series_id.eq(playback.series_id)))
+                .execute(db.conn())?;
+        }
+
+        Ok(interrupted)
+    }
+
+    #[inline(always)]
+    pub fn started_at(&self) -> DateTime<Utc> {
+        Utc.timestamp(self.started_at, 0)
+    }
+
+    /// Returns an uncounted session for the playback, which is assumed to have lasted until `now`
+    /// or the length of an episode, whichever comes first.
+    pub fn into_session(self, now: DateTime<Utc>, episode_length_mins: i16) -> PlaybackSession {
+        let seconds =
+            (now.timestamp() - self.started_at).clamp(0, i64::from(episode_length_mins) * 60);
+
+        PlaybackSession {
+            series_id: self.series_id,
+            episode: self.episode,
+            started_at: self.started_at,
+            seconds: seconds as i32,
+            exit_code: None,
+            counted: false,
+        }
+    }
+
+    /// Load the playback of the series with the specified `id` if it was started by another instance of the program
+    /// that's still running, such as a TUI that's playing the same series as `--play-one`.
+    pub fn load_elsewhere(db: &Database, id: i32) -> diesel::QueryResult<Option<Self>> {
+        use crate::database::schema::active_playbacks::dsl::{active_playbacks, series_id};
+
+        let playback = active_playbacks
+            .filter(series_id.eq(id))
+            .get_result::<Self>(db.conn())
+            .optional()?;
+
+        Ok(playback.filter(Self::is_process_running))
+    }
+
+    fn is_process_running(&self) -> bool {
+        // The process can't be this one, since it hasn't started playing anything yet when this is checked.
+        // Playbacks are assumed to be interrupted on platforms where processes can't be looked up.
+        self.pid as u32 != process::id() && util::is_process_running(self.pid as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarize_episode_ratings() {
+        assert_eq!(EpisodeRatings::summarize(Vec::new()), None);
+
+        let ratings = EpisodeRatings::summarize(vec![(1, 6), (2, 9), (3, 4), (2, 8), (4, 9)]);
+
+        assert_eq!(
+            ratings,
+            Some(EpisodeRatings {
+                average: 6.75,
+                best: (4, 9),
+                worst: (3, 4),
+            })
+        );
+    }
+
+    #[test]
+    fn interrupted_session_is_capped_to_episode_length() {
+        let playback = ActivePlayback {
+            series_id: 1,
+            episode: 3,
+            started_at: 1_000,
+            pid: 0,
+        };
+
+        let now = Utc.timestamp(1_000 + 60 * 60 * 5, 0);
+        let session = playback.into_session(now, 24);
+
+        assert_eq!(session.seconds, 24 * 60);
+        assert_eq!(session.exit_code, None);
+        assert!(!session.counted);
+    }
+}
@@ -1,8 +1,10 @@
+use super::search_cache::SearchCache;
 use super::SeriesPath;
+use crate::config::Config;
 use crate::database::schema::series_info;
-use crate::database::Database;
+use crate::database::{Database, Genres, SequelIds, SharedStr};
 use anime::remote::{Remote, RemoteService, SeriesID, SeriesInfo as RemoteInfo};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use diesel::prelude::*;
 use std::borrow::Cow;
 
@@ -10,10 +12,17 @@ use std::borrow::Cow;
 #[table_name = "series_info"]
 pub struct SeriesInfo {
     pub id: i32,
-    pub title_preferred: String,
-    pub title_romaji: String,
+    pub title_preferred: SharedStr,
+    pub title_romaji: SharedStr,
     pub episodes: i16,
     pub episode_length_mins: i16,
+    pub genres: Genres,
+    pub title_english: Option<SharedStr>,
+    pub title_native: Option<SharedStr>,
+    /// The average color of the cover image, as a hex code like `#e4a15d`.
+    pub cover_color: Option<SharedStr>,
+    /// The IDs of the sequels, side stories, and other series that follow on from this one.
+    pub sequel_ids: SequelIds,
 }
 
 impl SeriesInfo {
@@ -23,6 +32,18 @@ impl SeriesInfo {
         series_info.filter(id.eq(info_id)).get_result(db.conn())
     }
 
+    /// Returns the number of episodes the series has, or `None` if it isn't known yet (such as when it is still airing).
+    #[inline(always)]
+    pub fn total_episodes(&self) -> Option<i16> {
+        (self.episodes > 0).then_some(self.episodes)
+    }
+
+    /// Returns the number of episodes the series has as text, which is `?` when it isn't known yet.
+    pub fn episodes_text(&self) -> String {
+        self.total_episodes()
+            .map_or_else(|| "?".into(), |episodes| episodes.to_string())
+    }
+
     pub fn save(&self, db: &Database) -> diesel::QueryResult<usize> {
         use crate::database::schema::series_info::dsl::series_info;
 
@@ -31,10 +52,22 @@ impl SeriesInfo {
             .execute(db.conn())
     }
 
-    pub fn from_remote(sel: InfoSelector, remote: &Remote) -> Result<InfoResult> {
+    /// Find the series matching `sel` on `remote`, or in previous search results if `remote` is offline.
+    pub fn from_remote(sel: InfoSelector, config: &Config, remote: &Remote) -> Result<InfoResult> {
+        if remote.is_offline() {
+            return Self::from_cache(sel, config);
+        }
+
         match sel {
-            InfoSelector::ID(id) => Self::from_remote_by_id(id, remote).map(InfoResult::Confident),
-            InfoSelector::Name(name) => Self::from_remote_by_name(name, remote),
+            InfoSelector::ID(id) => {
+                let info = remote.search_info_by_id(id)?;
+
+                // The series can still be added without the cache, so failing to save it isn't worth stopping for
+                SearchCache::save_id(&info).ok();
+
+                Ok(InfoResult::Confident(info.into()))
+            }
+            InfoSelector::Name(name) => Self::from_remote_by_name(name, config, remote),
         }
     }
 
@@ -45,27 +78,91 @@ impl SeriesInfo {
             .map_err(Into::into)
     }
 
-    pub fn from_remote_by_name<S>(name: S, remote: &Remote) -> Result<InfoResult>
+    pub fn from_remote_by_name<S>(name: S, config: &Config, remote: &Remote) -> Result<InfoResult>
     where
         S: Into<String>,
     {
-        const MIN_CONFIDENCE: f32 = 0.85;
-
         let name = name.into();
-        let mut results = remote.search_info_by_name(&name)?;
+        let results = remote.search_info_by_name(&name)?;
+        SearchCache::save_name(&name, &results).ok();
+
+        if results.is_empty() {
+            return Err(anyhow!("no series found matching {}", name));
+        }
+
+        Ok(Self::best_match(name, results, config))
+    }
+
+    /// Find the series matching `sel` in previous search results.
+    ///
+    /// The results of the same search are used when there are any, and every cached result is searched otherwise.
+    fn from_cache(sel: InfoSelector, config: &Config) -> Result<InfoResult> {
+        let name = match sel {
+            InfoSelector::ID(id) => {
+                return SearchCache::find_id(id)?
+                    .map(|info| InfoResult::Confident(info.into()))
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "no cached search results have a series with the ID {}\nmust be online to search for new series",
+                            id
+                        )
+                    })
+            }
+            InfoSelector::Name(name) => name,
+        };
+
+        match SearchCache::load_name(&name)? {
+            Some(results) if !results.is_empty() => {
+                return Ok(Self::best_match(name, results, config))
+            }
+            _ => (),
+        }
+
+        let min_confidence = config.matching.title_confidence.as_multiplier();
+        let all = SearchCache::load_all()?;
+
+        RemoteInfo::closest_match(name.as_str(), min_confidence, all.iter().map(Cow::Borrowed))
+            .map(|(_, info)| InfoResult::Confident(info.into_owned().into()))
+            .ok_or_else(|| {
+                anyhow!(
+                    "no cached search results match {}\nmust be online to search for new series",
+                    name
+                )
+            })
+    }
+
+    fn best_match(name: String, mut results: Vec<RemoteInfo>, config: &Config) -> InfoResult {
+        let min_confidence = config.matching.title_confidence.as_multiplier();
         let found =
-            RemoteInfo::closest_match(name, MIN_CONFIDENCE, results.iter().map(Cow::Borrowed));
+            RemoteInfo::closest_match(name, min_confidence, results.iter().map(Cow::Borrowed));
 
         match found {
             Some((best_match, _)) => {
                 let info = results.swap_remove(best_match).into();
-                Ok(InfoResult::Confident(info))
+                InfoResult::Confident(info)
             }
-            None => Ok(InfoResult::Unconfident(
-                results.into_iter().map(Into::into).collect(),
-            )),
+            None => InfoResult::Unconfident(results.into_iter().map(Into::into).collect()),
         }
     }
+
+    /// Search for the series that likely replaced this one on the remote service, such as when it was merged into another series.
+    ///
+    /// Returns `Ok(None)` if no other series matches the title of this one closely enough.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn find_replacement(&self, config: &Config, remote: &Remote) -> Result<Option<Self>> {
+        let results = remote.search_info_by_name(&self.title_romaji)?;
+        let min_confidence = config.matching.title_confidence.as_multiplier();
+
+        let candidates = results
+            .iter()
+            .filter(|info| info.id as i32 != self.id)
+            .map(Cow::Borrowed);
+
+        let found =
+            RemoteInfo::closest_match(self.title_romaji.as_str(), min_confidence, candidates);
+
+        Ok(found.map(|(_, info)| info.into_owned().into()))
+    }
 }
 
 impl From<anime::remote::SeriesInfo> for SeriesInfo {
@@ -73,10 +170,20 @@ impl From<anime::remote::SeriesInfo> for SeriesInfo {
     fn from(value: anime::remote::SeriesInfo) -> Self {
         Self {
             id: value.id as i32,
-            title_preferred: value.title.preferred,
-            title_romaji: value.title.romaji,
+            title_preferred: value.title.preferred.into(),
+            title_romaji: value.title.romaji.into(),
             episodes: value.episodes as i16,
             episode_length_mins: value.episode_length as i16,
+            genres: value.genres.into(),
+            title_english: value.title.english.map(Into::into),
+            title_native: value.title.native.map(Into::into),
+            cover_color: value.cover_color.map(Into::into),
+            sequel_ids: value
+                .sequels
+                .iter()
+                .map(|sequel| sequel.id as i32)
+                .collect::<Vec<_>>()
+                .into(),
         }
     }
 }
@@ -1,39 +1,81 @@
+pub mod alias;
+pub mod collection;
 pub mod config;
+pub mod deleted;
 pub mod entry;
+pub mod episode_override;
+pub mod episode_title;
+pub mod filler;
+pub mod history;
 pub mod info;
+pub mod search_cache;
+pub mod source;
+pub mod sync_failure;
 
-use crate::config::Config;
-use crate::database::Database;
+use crate::audit_log::AuditRecord;
+use crate::config::{Config, ExtraEpisodes, SyncConfig};
+use crate::database::{Database, SharedStr};
 use crate::file;
 use crate::file::SaveDir;
+use crate::mpv;
+use crate::saved_event::{EventKind, SavedEvent};
+use crate::seeding::SeedingTorrents;
 use crate::try_opt_r;
-use anime::local::{CategorizedEpisodes, EpisodeParser, SortedEpisodes};
-use anime::remote::{Remote, SeriesID, Status};
+use alias::SeriesAlias;
+use anime::local::{CategorizedEpisodes, Episode, EpisodeParser, SortedEpisodes};
+use anime::remote::{anilist, Remote, RemoteService, SeriesID, Status};
+use anime::SeriesKind;
 use anyhow::{anyhow, Context, Error, Result};
 use chrono::{DateTime, Duration, Utc};
-use config::SeriesConfig;
+use collection::SeriesCollection;
+use config::{SeriesConfig, TitleOverride};
 use diesel::deserialize::{self, FromSql};
 use diesel::prelude::*;
 use diesel::serialize::{self, Output, ToSql};
 use diesel::sql_types::Text;
 use entry::SeriesEntry;
+use episode_override::EpisodeOverrides;
+use episode_title::EpisodeTitles;
+use filler::FillerEpisodes;
+use history::{PlaybackSession, ResumePosition, WatchEvent, WatchTotals};
 use info::SeriesInfo;
 use smallvec::SmallVec;
+use source::{EpisodeLocation, EpisodeSource, LocalDir};
 use std::cmp::{Ordering, PartialOrd};
+use std::collections::HashSet;
+use std::convert::TryFrom;
 use std::fs;
-use std::io::Write;
+use std::io::{self, Write};
+use std::iter;
 use std::mem;
+use std::os::unix::process::CommandExt;
 use std::path::{self, Path, PathBuf};
+use std::process::Command as StdCommand;
 use std::result;
 use std::{borrow::Cow, process::Stdio};
+use sync_failure::SyncFailure;
 use thiserror::Error;
 use tokio::process::{Child, Command};
 
 #[derive(Debug, Error)]
 pub enum EpisodeScanError {
+    #[error("{path} doesn't exist")]
+    PathNotFound { path: PathBuf },
+
+    #[error("{path} is a link to a folder that doesn't exist")]
+    BrokenLink { path: PathBuf },
+
+    #[error(
+        "{path} can't be reached: {source}\nif it's on a network drive, make sure it's mounted"
+    )]
+    Unreachable { source: io::Error, path: PathBuf },
+
     #[error("failed to parse episodes at {path}: {source}")]
     EpisodeParseFailed { source: anime::Error, path: PathBuf },
 
+    #[error("the episodes at {url} couldn't be listed: {reason}")]
+    Unlisted { url: String, reason: String },
+
     #[error("no episodes found")]
     NoEpisodes,
 
@@ -41,10 +83,55 @@ pub enum EpisodeScanError {
     SeriesNeedsSplitting,
 }
 
+impl EpisodeScanError {
+    /// Make sure the folder at `path` can be read, so a missing or unreachable folder is reported as such
+    /// instead of failing to parse episodes.
+    fn check_dir(path: &Path) -> result::Result<(), Self> {
+        match fs::metadata(path) {
+            Ok(_) => Ok(()),
+            // Links are followed, so the link itself still existing means it points to nothing
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                let path = path.into();
+
+                if fs::symlink_metadata(&path).is_ok() {
+                    Err(Self::BrokenLink { path })
+                } else {
+                    Err(Self::PathNotFound { path })
+                }
+            }
+            Err(source) => Err(Self::Unreachable {
+                source,
+                path: path.into(),
+            }),
+        }
+    }
+}
+
+/// Reasons the file of an episode can't be used.
+#[derive(Debug, Error)]
+pub enum EpisodePathError {
+    #[error("episode {0} not found")]
+    NotFound(u32),
+
+    #[error("episode {episode} is a link to a file that doesn't exist: {path}")]
+    BrokenLink { episode: u32, path: PathBuf },
+
+    #[error("episode {episode} can't be reached at {path}: {source}")]
+    Unreachable {
+        source: io::Error,
+        episode: u32,
+        path: PathBuf,
+    },
+}
+
 pub struct SeriesData {
     pub config: SeriesConfig,
     pub info: SeriesInfo,
     pub entry: SeriesEntry,
+    pub history: WatchTotals,
+    pub filler: FillerEpisodes,
+    pub episode_titles: EpisodeTitles,
+    pub episode_overrides: EpisodeOverrides,
 }
 
 impl SeriesData {
@@ -55,20 +142,73 @@ impl SeriesData {
             config,
             info,
             entry,
+            history: WatchTotals::default(),
+            filler: FillerEpisodes::default(),
+            episode_titles: EpisodeTitles::default(),
+            episode_overrides: EpisodeOverrides::default(),
         })
     }
 
+    /// Returns the title to show for the series, which is the title in the user's preferred format unless it was overridden.
+    ///
+    /// The preferred title is used if the series doesn't have the title it was overridden with.
+    pub fn title(&self) -> &SharedStr {
+        let info = &self.info;
+
+        let title = match &self.config.title_override {
+            Some(TitleOverride::Romaji) => Some(&info.title_romaji),
+            Some(TitleOverride::English) => info.title_english.as_ref(),
+            Some(TitleOverride::Native) => info.title_native.as_ref(),
+            Some(TitleOverride::Custom(title)) => Some(title),
+            None => None,
+        };
+
+        title.unwrap_or(&info.title_preferred)
+    }
+
+    /// Set the title to show for the series, or show its preferred title again if `title` is None.
+    ///
+    /// Returns an error if the series doesn't have a known title of the requested kind.
+    pub fn set_title_override(&mut self, title: Option<TitleOverride>) -> Result<()> {
+        let info = &self.info;
+
+        let missing = match title {
+            Some(TitleOverride::English) if info.title_english.is_none() => Some("English"),
+            Some(TitleOverride::Native) if info.title_native.is_none() => Some("native"),
+            _ => None,
+        };
+
+        if let Some(missing) = missing {
+            return Err(anyhow!(
+                "{} doesn't have a known {} title; refreshall may find one",
+                self.title(),
+                missing
+            ));
+        }
+
+        self.config.title_override = title;
+        Ok(())
+    }
+
     pub fn load_from_config(db: &Database, config: Cow<SeriesConfig>) -> diesel::QueryResult<Self> {
         use diesel::result::Error as DieselError;
 
         db.conn().transaction::<_, DieselError, _>(|| {
             let info = SeriesInfo::load(db, config.id)?;
-            let entry = SeriesEntry::load(db, config.id)?;
+            let entry = SeriesEntry::load_or_new(db, config.id)?;
+            let history = WatchTotals::load(db, config.id)?;
+            let filler = FillerEpisodes::load(db, config.id)?;
+            let episode_titles = EpisodeTitles::load(db, config.id)?;
+            let episode_overrides = EpisodeOverrides::load(db, config.id)?;
 
             Ok(Self {
                 config: config.into_owned(),
                 info,
                 entry,
+                history,
+                filler,
+                episode_titles,
+                episode_overrides,
             })
         })
     }
@@ -81,15 +221,76 @@ impl SeriesData {
                 .context("getting series info")?;
 
             let entry = SeriesEntry::from_remote(remote, &info).context("getting series entry")?;
+            let history = WatchTotals::load(db, info.id).context("loading watch history")?;
 
             self.info = info;
             self.entry = entry;
+            self.history = history;
         }
 
         Ok(())
     }
 
+    /// Link the series to the remote series with the specified `id`, while keeping its list entry and watch history.
+    ///
+    /// The list entry will be synced to the new series the next time it is synced, and is saved along with everything else.
+    pub fn relink(&mut self, id: SeriesID, db: &Database, remote: &Remote) -> Result<()> {
+        let old_id = self.config.id;
+
+        // We don't want to change the config before knowing the new series exists
+        let info = SeriesInfo::from_remote_by_id(id, remote).context("getting series info")?;
+
+        let params = UpdateParams {
+            id: Some(id),
+            path: None,
+            parser: None,
+            episodes: None,
+        };
+
+        if !self.config.update(params, db, remote)? {
+            return Err(anyhow!(
+                "{} is already linked to {}",
+                self.config.nickname,
+                id
+            ));
+        }
+
+        self.info = info;
+        self.entry.relink(self.config.id);
+        // The titles belong to the old series, and are fetched again the next time the series is refreshed
+        self.episode_titles = EpisodeTitles::default();
+
+        db.write_transaction(|| {
+            let new_id = self.config.id;
+
+            self.config.save(db).context("saving series config")?;
+            self.info.save(db).context("saving series info")?;
+            self.entry.save(db).context("saving list entry")?;
+
+            SeriesEntry::delete(db, old_id).context("removing old list entry")?;
+            SyncFailure::clear(db, old_id).context("clearing sync failures")?;
+            WatchEvent::move_to_series(db, old_id, new_id).context("moving watch history")?;
+            PlaybackSession::move_to_series(db, old_id, new_id)
+                .context("moving playback sessions")?;
+            ResumePosition::move_to_series(db, old_id, new_id)
+                .context("moving resume positions")?;
+            SeriesAlias::move_to_series(db, old_id, new_id).context("moving aliases")?;
+            SeriesCollection::move_to_series(db, old_id, new_id).context("moving collections")?;
+            FillerEpisodes::move_to_series(db, old_id, new_id).context("moving filler episodes")?;
+            EpisodeOverrides::move_to_series(db, old_id, new_id)
+                .context("moving episode overrides")?;
+            EpisodeTitles::delete_for_series(db, old_id).context("removing old episode titles")?;
+
+            Ok(())
+        })
+        .with_context(|| format!("failed to relink {}", self.config.nickname))
+    }
+
     pub fn force_sync_from_remote(&mut self, remote: &Remote) -> Result<()> {
+        if self.config.local_only {
+            return Err(anyhow!("{} is only tracked locally", self.config.nickname));
+        }
+
         // We don't want to set the new info now in case the entry sync fails
         let info = SeriesInfo::from_remote_by_id(self.info.id as SeriesID, remote)?;
 
@@ -99,25 +300,228 @@ impl SeriesData {
         Ok(())
     }
 
-    pub fn save(&self, db: &Database) -> diesel::QueryResult<()> {
-        db.conn()
-            .transaction(|| {
-                self.config.save(db)?;
-                self.info.save(db)?;
-                self.entry.save(db)
+    /// Fetch the titles of the episodes of the series from `remote` and save them.
+    ///
+    /// Nothing is fetched for series that are only tracked locally, and the current titles are kept if `remote`
+    /// doesn't have any.
+    pub fn refresh_episode_titles(&mut self, db: &Database, remote: &Remote) -> Result<()> {
+        if self.config.local_only || remote.is_offline() {
+            return Ok(());
+        }
+
+        let titles: EpisodeTitles = remote
+            .episode_titles(self.info.id as SeriesID)
+            .context("getting episode titles")?
+            .into();
+
+        if titles.is_empty() {
+            return Ok(());
+        }
+
+        titles.save(db, self.config.id)?;
+        self.episode_titles = titles;
+
+        Ok(())
+    }
+
+    pub fn force_sync_to_remote(&mut self, remote: &Remote, db: &Database) -> Result<()> {
+        if self.config.local_only {
+            return Err(anyhow!("{} is only tracked locally", self.config.nickname));
+        }
+
+        self.entry
+            .force_sync_to_remote(remote, db, self.info.total_episodes())
+    }
+
+    /// Remove the list entry of the series from the user's list on `remote`.
+    ///
+    /// The series is only tracked locally afterwards, so the next change doesn't add the entry back. Its entry is
+    /// sent again once syncing is turned back on.
+    ///
+    /// Returns false if the series wasn't on the user's list.
+    pub fn remove_from_remote(&mut self, remote: &Remote, db: &Database) -> Result<bool> {
+        if self.config.local_only {
+            return Err(anyhow!("{} is only tracked locally", self.config.nickname));
+        }
+
+        let removed = remote.delete_list_entry(self.info.id as SeriesID)?;
+
+        if removed {
+            // The entry is already gone from the remote service, so failing to record it can't stop the series from
+            // being tracked locally
+            if let Err(err) = AuditRecord::record_removed(db, remote, self.info.id) {
+                let message = format!(
+                    "failed to record the removal of {} in the audit log: {:#}",
+                    self.config.nickname, err
+                );
+
+                SavedEvent::record(db, EventKind::Warning, &message).ok();
+            }
+        }
+
+        self.config.local_only = true;
+        self.entry.mark_needs_sync();
+
+        Ok(removed)
+    }
+
+    /// Sync the list entry to `remote` if it has changes, unless the series is only tracked locally.
+    pub fn sync_to_remote(&mut self, remote: &Remote, db: &Database) -> Result<()> {
+        if self.config.local_only {
+            return Ok(());
+        }
+
+        self.entry
+            .sync_to_remote(remote, db, self.info.total_episodes())
+    }
+
+    /// Replace the list entry with the one from `remote` if it doesn't have unsynced changes, unless the series is only tracked locally.
+    ///
+    /// Progress past the official episode count is kept, since the remote service only has the capped count.
+    pub fn sync_from_remote(&mut self, remote: &Remote, fields: &SyncConfig) -> Result<()> {
+        if self.config.local_only {
+            return Ok(());
+        }
+
+        let watched = self.entry.watched_episodes();
+        self.entry.sync_from_remote(remote, fields)?;
+
+        if let Some(total) = self.info.total_episodes() {
+            self.entry.restore_extra_progress(watched, total);
+        }
+
+        Ok(())
+    }
+
+    /// Save the series configuration, info, and list entry.
+    ///
+    /// Everything is saved within a single transaction, so if any of them fail to save, none of them will be.
+    pub fn save(&self, db: &Database) -> Result<()> {
+        db.write_transaction(|| {
+            self.config.save(db).context("saving series config")?;
+            self.info.save(db).context("saving series info")?;
+            self.entry.save(db).context("saving list entry")?;
+            Ok(())
+        })
+        .with_context(|| {
+            format!(
+                "failed to save {}; its changes were rolled back",
+                self.config.nickname
+            )
+        })
+    }
+
+    /// Set the watched episodes of the list entry to the progress found in a watched subdirectory of the series,
+    /// if it's further along than the entry.
+    ///
+    /// Returns true if the entry was changed.
+    pub fn import_progress_from_disk(&mut self, config: &Config) -> bool {
+        let path = self.config.path.absolute(config);
+
+        let total = self.info.total_episodes();
+
+        // Watched episodes are numbered like the rest, so they need the same offset
+        let watched = watched_episodes_on_disk(&path, &self.config.episode_parser)
+            .and_then(|watched| {
+                u32::try_from(i64::from(watched) - i64::from(self.config.episode_offset)).ok()
             })
-            .map(|_| ())
+            .filter(|&watched| watched > 0);
+
+        let watched = match watched {
+            Some(watched) => total.map_or(watched, |total| watched.min(total as u32)) as i16,
+            None => return false,
+        };
+
+        let entry = &mut self.entry;
+
+        if watched <= entry.watched_episodes() {
+            return false;
+        }
+
+        entry.set_watched_episodes(watched);
+
+        let status = if total.map_or(false, |total| watched >= total) {
+            Status::Completed
+        } else {
+            Status::Watching
+        };
+
+        entry.set_status(status, config);
+        true
+    }
+
+    /// Returns the episode to say is being watched when sharing it, which is the next one unless the series has been finished.
+    pub fn shared_episode(&self) -> u32 {
+        let next = self.entry.watched_episodes().max(0) + 1;
+
+        match self.info.total_episodes() {
+            Some(total) => next.min(total) as u32,
+            None => next as u32,
+        }
     }
 
-    /// Returns the UTC time threshold for an episode should be counted as watched, assuming that the episode was starting to be watched now.
-    pub fn next_watch_progress_time(&self, config: &Config) -> DateTime<Utc> {
+    /// Returns a message saying that `episode` of the series is being watched, for pasting into a chat.
+    pub fn share_text(&self, episode: u32, with_url: bool) -> String {
+        let text = format!("Watching {} ep {}", self.title(), episode);
+
+        if with_url {
+            let url = anilist::series_url(self.info.id as SeriesID);
+            format!("{} {}", text, url)
+        } else {
+            text
+        }
+    }
+
+    /// Returns when an episode that started being watched at `started_at` should be counted as watched.
+    pub fn next_watch_progress_time(
+        &self,
+        started_at: DateTime<Utc>,
+        config: &Config,
+    ) -> DateTime<Utc> {
         let secs_must_watch =
             (f32::from(self.info.episode_length_mins) * config.episode.pcnt_must_watch) * 60.0;
 
-        Utc::now() + Duration::seconds(secs_must_watch as i64)
+        started_at + Duration::seconds(secs_must_watch as i64)
+    }
+
+    /// Returns the total number of minutes spent watching the series, including rewatches.
+    ///
+    /// Episodes in the watch history use the length they had when they were watched, while any episodes
+    /// watched before the history was recorded are estimated from the list entry.
+    pub fn watched_mins(&self) -> u32 {
+        let episodes = self.info.episodes.max(0) as u32;
+        let entry = &self.entry;
+
+        let mut watched = entry.watched_episodes().max(0) as u32
+            + entry.times_rewatched().max(0) as u32 * episodes;
+
+        // The watched episodes of a rewatch don't include the initial watch
+        if entry.status() == Status::Rewatching {
+            watched += episodes;
+        }
+
+        let unrecorded = watched.saturating_sub(self.history.episodes);
+        let episode_length = self.info.episode_length_mins.max(0) as u32;
+
+        self.history.mins + unrecorded * episode_length
     }
 }
 
+/// Names of subdirectories within a series folder that hold episodes that have already been watched.
+const WATCHED_DIR_NAMES: [&str; 2] = ["watched", ".watched"];
+
+/// Returns the highest episode number found in a watched subdirectory of the series at `path`.
+fn watched_episodes_on_disk(path: &Path, parser: &EpisodeParser) -> Option<u32> {
+    WATCHED_DIR_NAMES
+        .iter()
+        .map(|name| path.join(name))
+        .filter(|dir| dir.is_dir())
+        .filter_map(|dir| CategorizedEpisodes::parse(dir, parser).ok())
+        .filter_map(CategorizedEpisodes::take_season_episodes_or_present)
+        .filter_map(|episodes| episodes.last().map(|episode| episode.number))
+        .max()
+}
+
 pub struct Series {
     pub data: SeriesData,
     pub episodes: SortedEpisodes,
@@ -136,6 +540,31 @@ impl Series {
         Self { data, episodes }
     }
 
+    /// Returns the number of episodes on disk that are numbered past the official episode count of the series.
+    pub fn extra_episodes(&self) -> usize {
+        let total = match self.data.info.total_episodes() {
+            Some(total) => total as u32,
+            None => return 0,
+        };
+
+        self.episodes.iter().filter(|ep| ep.number > total).count()
+    }
+
+    /// Returns true if the next episode is an extra one numbered past the official episode count, which is only played
+    /// when `extra_episodes` in the config isn't set to warn.
+    pub fn next_is_extra(&self, config: &Config) -> bool {
+        let total = match self.data.info.total_episodes() {
+            Some(total) => total,
+            None => return false,
+        };
+
+        let next = self.data.entry.watched_episodes() + 1;
+
+        config.episode.extra_episodes != ExtraEpisodes::Warn
+            && next > total
+            && self.episodes.find(next as u32).is_some()
+    }
+
     /// Sets the specified parameters on the series and reloads any neccessary state.
     pub fn update(
         &mut self,
@@ -160,27 +589,195 @@ impl Series {
         data: &SeriesData,
         config: &Config,
     ) -> result::Result<SortedEpisodes, EpisodeScanError> {
-        let path = data.config.path.absolute(config);
+        let episodes = data
+            .config
+            .path
+            .source(config)
+            .episodes(&data.config.episode_parser)?;
 
-        let episodes =
-            CategorizedEpisodes::parse(&path, &data.config.episode_parser).map_err(|source| {
-                EpisodeScanError::EpisodeParseFailed {
-                    source,
-                    path: path.into(),
-                }
-            })?;
+        let episodes = data
+            .episode_overrides
+            .apply(episodes, data.config.episode_offset);
 
         if episodes.is_empty() {
             return Err(EpisodeScanError::NoEpisodes);
         }
 
-        episodes
+        let episodes = episodes
             .take_season_episodes_or_present()
-            .ok_or(EpisodeScanError::SeriesNeedsSplitting)
+            .ok_or(EpisodeScanError::SeriesNeedsSplitting)?
+            .with_offset(data.config.episode_offset);
+
+        if episodes.is_empty() {
+            return Err(EpisodeScanError::NoEpisodes);
+        }
+
+        Ok(episodes)
+    }
+
+    /// Scan the episodes of the series on disk again.
+    ///
+    /// Returns the numbers of episodes that weren't on disk before.
+    pub fn rescan_episodes(&mut self, config: &Config) -> Result<SmallVec<[u32; 2]>> {
+        // Files are usually only added between scans, so only those need to be parsed
+        if let Some(new) = self.insert_new_episodes(config)? {
+            return Ok(new);
+        }
+
+        let episodes = Self::scan_episodes(&self.data, config)?;
+
+        let new = episodes
+            .iter()
+            .map(|episode| episode.number)
+            .filter(|&number| self.episodes.find(number).is_none())
+            .collect();
+
+        self.episodes = episodes;
+        Ok(new)
+    }
+
+    /// Parse every file on disk that isn't a known episode and add it to the series, without parsing the known ones again.
+    ///
+    /// Returns the numbers of episodes that weren't on disk before, or None if a full scan is needed because
+    /// a known episode is gone or a new file isn't a seasonal episode.
+    fn insert_new_episodes(&mut self, config: &Config) -> Result<Option<SmallVec<[u32; 2]>>> {
+        let mut filenames = self.data.config.path.source(config).filenames()?;
+
+        let known = self
+            .episodes
+            .iter()
+            .map(|ep| ep.filename.as_str())
+            .collect::<HashSet<_>>();
+
+        let still_on_disk = filenames
+            .iter()
+            .filter(|filename| known.contains(filename.as_str()))
+            .count();
+
+        if still_on_disk < known.len() {
+            return Ok(None);
+        }
+
+        filenames.retain(|filename| !known.contains(filename.as_str()));
+
+        let mut parsed = Vec::with_capacity(filenames.len());
+
+        for filename in filenames {
+            let overridden = self
+                .data
+                .episode_overrides
+                .parsed_number(&filename, self.data.config.episode_offset);
+
+            if let Some(number) = overridden {
+                parsed.push(Episode::new(number, filename));
+                continue;
+            }
+
+            let episode = self.data.config.episode_parser.parse(&filename)?;
+
+            if episode.category != SeriesKind::Season {
+                return Ok(None);
+            }
+
+            parsed.push(Episode::new(episode.episode, filename));
+        }
+
+        let parsed =
+            SortedEpisodes::with_episodes(parsed).with_offset(self.data.config.episode_offset);
+        let mut new = SmallVec::new();
+
+        for episode in parsed.take() {
+            if self.episodes.find(episode.number).is_none() {
+                new.push(episode.number);
+            }
+
+            self.episodes.insert(episode);
+        }
+
+        Ok(Some(new))
+    }
+
+    /// Sets the episode number offset of the series and remaps its episodes with it.
+    ///
+    /// The previous offset is kept if no episodes can be found with the new one.
+    pub fn set_episode_offset(&mut self, offset: i32, config: &Config) -> Result<()> {
+        let old_offset = mem::replace(&mut self.data.config.episode_offset, offset);
+
+        match Self::scan_episodes(&self.data, config) {
+            Ok(episodes) => {
+                self.episodes = episodes;
+                Ok(())
+            }
+            Err(err) => {
+                self.data.config.episode_offset = old_offset;
+                Err(err.into())
+            }
+        }
+    }
+
+    /// Give the file on disk with `name` in its filename the episode number `episode`, and remap the episodes of the
+    /// series with it.
+    ///
+    /// `name` is matched without regard to case, and has to match exactly one file. Returns the full name of the file.
+    pub fn override_episode(
+        &mut self,
+        episode: i16,
+        name: &str,
+        db: &Database,
+        config: &Config,
+    ) -> Result<String> {
+        let needle = name.to_lowercase();
+
+        let mut matching = self
+            .data
+            .config
+            .path
+            .source(config)
+            .filenames()?
+            .into_iter()
+            .filter(|filename| filename.to_lowercase().contains(&needle))
+            .collect::<Vec<_>>();
+
+        // A full filename can also be part of a longer one
+        if let Some(index) = matching
+            .iter()
+            .position(|filename| filename.to_lowercase() == needle)
+        {
+            let filename = matching.swap_remove(index);
+            matching = vec![filename];
+        }
+
+        let filename = match matching.len() {
+            1 => matching.remove(0),
+            0 => return Err(anyhow!("no file has {} in its name", name)),
+            count => {
+                return Err(anyhow!(
+                    "{} files have {} in their name; use more of the filename",
+                    count,
+                    name
+                ))
+            }
+        };
+
+        self.data
+            .episode_overrides
+            .set(db, self.data.config.id, filename.clone(), episode)?;
+
+        self.episodes = Self::scan_episodes(&self.data, config)?;
+        Ok(filename)
+    }
+
+    /// Remove every episode override of the series, and remap its episodes with the episode parser alone.
+    pub fn clear_episode_overrides(&mut self, db: &Database, config: &Config) -> Result<()> {
+        EpisodeOverrides::delete_for_series(db, self.data.config.id)?;
+        self.data.episode_overrides = EpisodeOverrides::default();
+
+        self.episodes = Self::scan_episodes(&self.data, config)?;
+        Ok(())
     }
 
     #[inline(always)]
-    pub fn save(&self, db: &Database) -> diesel::QueryResult<()> {
+    pub fn save(&self, db: &Database) -> Result<()> {
         self.data.save(db)
     }
 
@@ -198,27 +795,179 @@ impl Series {
         Self::init(data, config)
     }
 
-    pub fn episode_path(&self, ep_num: u32, config: &Config) -> Option<PathBuf> {
-        let episode = self.episodes.find(ep_num)?;
-        let mut path = self.data.config.path.absolute(config).into_owned();
-        path.push(&episode.filename);
-        path.canonicalize().ok()
+    /// Returns the number of episodes on disk that come after the last watched episode.
+    pub fn unwatched_on_disk(&self) -> u32 {
+        let watched = self.data.entry.watched_episodes().max(0) as u32;
+        self.episodes
+            .iter()
+            .filter(|ep| ep.number > watched)
+            .count() as u32
     }
 
-    pub fn play_episode(&self, episode: u32, config: &Config) -> Result<Child> {
-        let episode_path = self
-            .episode_path(episode, config)
-            .with_context(|| anyhow!("episode {} not found", episode))?;
+    /// Returns where the player can find the specified episode, which is a URL when the series is streamed.
+    pub fn episode_path(
+        &self,
+        ep_num: u32,
+        config: &Config,
+    ) -> result::Result<EpisodeLocation, EpisodePathError> {
+        let episode = self
+            .episodes
+            .find(ep_num)
+            .ok_or(EpisodePathError::NotFound(ep_num))?;
+
+        self.data
+            .config
+            .path
+            .source(config)
+            .locate(&episode.filename, ep_num)
+    }
+
+    /// Remove the files of episodes that were watched at least `remove_watched_after_hours` ago,
+    /// or move them to the archive directory if one is set.
+    ///
+    /// Only episodes that have been recorded as watched are removed. Episodes that are still being seeded by the
+    /// torrent client in the config are kept, and cause an error once every other episode has been removed.
+    ///
+    /// Returns the number of episodes that were removed.
+    pub fn remove_watched_episodes(&mut self, config: &Config, db: &Database) -> Result<usize> {
+        let watched = self.data.entry.watched_episodes().max(0) as u32;
+        let cutoff = (Utc::now()
+            - Duration::hours(config.episode.remove_watched_after_hours.into()))
+        .timestamp();
+
+        let mut expired = SmallVec::<[u32; 4]>::new();
+
+        for episode in self.episodes.iter().filter(|ep| ep.number <= watched) {
+            let watched_at =
+                WatchEvent::last_watched_at(db, self.data.info.id, episode.number as i16)
+                    .context("getting when episode was watched")?;
+
+            if matches!(watched_at, Some(watched_at) if watched_at <= cutoff) {
+                expired.push(episode.number);
+            }
+        }
+
+        if expired.is_empty() {
+            return Ok(0);
+        }
+
+        let seeding =
+            SeedingTorrents::query(&config.torrent).context("checking for seeding torrents")?;
+
+        let mut removed = SmallVec::<[u32; 4]>::new();
+        let mut num_seeding = 0;
+        let mut result = Ok(());
+
+        for &number in &expired {
+            // Episodes that can't be reached or are streamed can't be removed either
+            let path = match self.episode_path(number, config) {
+                Ok(EpisodeLocation::File(path)) => path,
+                Ok(EpisodeLocation::Url(_)) | Err(_) => continue,
+            };
+
+            if matches!(&seeding, Some(seeding) if seeding.contains(&path)) {
+                num_seeding += 1;
+                continue;
+            }
+
+            if let Err(err) = self.remove_episode_file(&path, config) {
+                result = Err(err);
+                break;
+            }
+
+            removed.push(number);
+        }
 
-        let mut cmd = Command::new(&config.episode.player);
-        cmd.arg(episode_path);
+        let episodes = mem::take(&mut self.episodes)
+            .take()
+            .into_iter()
+            .filter(|episode| !removed.contains(&episode.number))
+            .collect();
+
+        self.episodes = SortedEpisodes::with_episodes(episodes);
+
+        if result.is_ok() && num_seeding > 0 {
+            result = Err(anyhow!(
+                "kept {} watched episode(s) that are still seeding",
+                num_seeding
+            ));
+        }
+
+        result.map(|()| removed.len())
+    }
+
+    fn remove_episode_file(&self, path: &Path, config: &Config) -> Result<()> {
+        let archive_dir = match &config.episode.archive_dir {
+            Some(dir) => dir.join(&self.data.config.nickname),
+            None => {
+                return fs::remove_file(path)
+                    .with_context(|| format!("removing {}", path.display()))
+            }
+        };
+
+        fs::create_dir_all(&archive_dir)
+            .with_context(|| format!("creating {}", archive_dir.display()))?;
+
+        let filename = path
+            .file_name()
+            .ok_or_else(|| anyhow!("{} has no filename", path.display()))?;
+
+        let archived = archive_dir.join(filename);
+
+        // Renaming will fail when the archive directory is on a different filesystem
+        if fs::rename(path, &archived).is_err() {
+            fs::copy(path, &archived).with_context(|| {
+                format!("copying {} to {}", path.display(), archive_dir.display())
+            })?;
+
+            fs::remove_file(path).with_context(|| format!("removing {}", path.display()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Play `episode` with the player from `config`, passing `extra_args` after the arguments from `config` and the series.
+    pub fn play_episode(
+        &self,
+        episode: u32,
+        extra_args: &[String],
+        config: &Config,
+        db: &Database,
+    ) -> Result<Child> {
+        let location = self.episode_path(episode, config)?;
+
+        let episode_path = self
+            .data
+            .config
+            .path
+            .source(config)
+            .prepare(location)
+            .with_context(|| anyhow!("failed to get episode {} ready to play", episode))?;
+
+        let mut cmd = StdCommand::new(&config.episode.player);
+        cmd.arg(&episode_path);
+
+        // mpv resumes from the position it saved itself when it has one, so an imported one is only used otherwise.
+        // It's passed first so the player args can override it.
+        if mpv::is_player(&config.episode.player) {
+            let position = ResumePosition::take(db, self.data.info.id, episode as i16)
+                .context("loading resume position")?;
+
+            if let Some(secs) = position.filter(|_| !mpv::has_position(episode_path.as_ref())) {
+                cmd.arg(format!("--start={}", secs));
+            }
+        }
         cmd.args(&config.episode.player_args);
         cmd.args(self.data.config.player_args.as_ref());
+        cmd.args(extra_args);
         cmd.stdout(Stdio::null());
         cmd.stderr(Stdio::null());
         cmd.stdin(Stdio::null());
+        // The player gets its own process group so it keeps playing if the terminal is closed
+        cmd.process_group(0);
 
-        cmd.spawn()
+        Command::from(cmd)
+            .spawn()
             .with_context(|| anyhow!("failed to play episode {}", episode))
     }
 
@@ -228,16 +977,28 @@ impl Series {
         config: &Config,
         db: &Database,
     ) -> Result<()> {
-        self.data.entry.sync_from_remote(remote)?;
+        self.data.sync_from_remote(remote, &config.sync)?;
+
+        // Series that are still airing may not have announced how many episodes they have when they were added.
+        // Failing to look it up shouldn't keep the episode from playing, since the count will just stay unknown
+        if self.data.info.total_episodes().is_none() && !remote.is_offline() && !config.low_data {
+            if let Ok(info) = SeriesInfo::from_remote_by_id(self.data.info.id as SeriesID, remote) {
+                self.data.info = info;
+            }
+        }
 
+        let total = self.data.info.total_episodes();
+        let is_extra = self.next_is_extra(config);
         let entry = &mut self.data.entry;
         let last_status = entry.status();
 
         match last_status {
+            // Extra episodes are played after the last one instead of starting a rewatch
+            _ if is_extra => (),
             Status::Watching | Status::Rewatching => {
                 // There is an edge case where all episodes have been watched, but the status
                 // is still set to watching / rewatching. Here we just start a rewatch
-                if entry.watched_episodes() >= self.data.info.episodes {
+                if total.map_or(false, |total| entry.watched_episodes() >= total) {
                     entry.set_status(Status::Rewatching, config);
                     entry.set_watched_episodes(0);
 
@@ -249,6 +1010,8 @@ impl Series {
             Status::Completed => {
                 entry.set_status(Status::Rewatching, config);
                 entry.set_watched_episodes(0);
+                // The planned rewatch has started
+                self.data.config.planned_rewatch = false;
             }
             Status::PlanToWatch | Status::OnHold => entry.set_status(Status::Watching, config),
             Status::Dropped => {
@@ -257,43 +1020,86 @@ impl Series {
             }
         }
 
-        self.data.entry.sync_to_remote(remote)?;
+        self.data.sync_to_remote(remote, db)?;
         self.save(db)?;
 
         Ok(())
     }
 
-    pub fn episode_completed(
-        &mut self,
-        remote: &Remote,
-        config: &Config,
-        db: &Database,
-    ) -> Result<()> {
+    /// Mark the filler episodes that come next as watched when `skip_filler` is turned on, and return the first and last episode that were skipped.
+    ///
+    /// Skipped episodes aren't added to the watch history. The list entry is saved, but not synced to the remote service.
+    pub fn skip_filler(&mut self, config: &Config, db: &Database) -> Result<Option<(i16, i16)>> {
+        if !config.episode.skip_filler {
+            return Ok(None);
+        }
+
+        let watched = self.data.entry.watched_episodes();
+        let skipped = self
+            .data
+            .filler
+            .skippable_after(watched, self.data.info.total_episodes());
+
+        if skipped == 0 {
+            return Ok(None);
+        }
+
+        self.data.entry.set_watched_episodes(watched + skipped);
+        self.save(db)?;
+
+        Ok(Some((watched + 1, watched + skipped)))
+    }
+
+    /// Mark the next episode as watched.
+    ///
+    /// The list entry is saved, but not synced to the remote service.
+    pub fn episode_completed(&mut self, config: &Config, db: &Database) -> Result<()> {
+        // The watch history and end date should agree on when the episode was watched, even right around midnight
+        let watched_at = Utc::now();
         let new_progress = self.data.entry.watched_episodes() + 1;
 
-        if new_progress >= self.data.info.episodes {
+        // Series with an unknown number of episodes are never completed automatically, since there's no way to
+        // tell if the last episode was just watched
+        let total = self.data.info.total_episodes();
+
+        let is_extra = self.next_is_extra(config);
+        let counts_as_watched = total.map_or(true, |total| new_progress <= total)
+            || (is_extra && config.episode.extra_episodes == ExtraEpisodes::Local);
+
+        if counts_as_watched {
+            let minutes = self.data.info.episode_length_mins;
+
+            WatchEvent::record(db, self.data.info.id, new_progress, minutes, watched_at)?;
+            self.data.history.add(minutes);
+        }
+
+        // The series was already completed by its last official episode
+        if is_extra {
+            self.data.entry.set_watched_episodes(new_progress);
+            self.save(db)?;
+            return Ok(());
+        }
+
+        if let Some(total) = total.filter(|&total| new_progress >= total) {
             // The watched episode range is inclusive, so it's fine to bump the watched count
             // if we're at exactly at the last episode
-            if new_progress == self.data.info.episodes {
+            if new_progress == total {
                 self.data.entry.set_watched_episodes(new_progress);
             }
 
-            return self.series_complete(remote, config, db);
+            return self.series_complete(watched_at, config, db);
         }
 
         self.data.entry.set_watched_episodes(new_progress);
-        self.data.entry.sync_to_remote(remote)?;
         self.save(db)?;
 
         Ok(())
     }
 
-    pub fn episode_regressed(
-        &mut self,
-        remote: &Remote,
-        config: &Config,
-        db: &Database,
-    ) -> Result<()> {
+    /// Mark the last watched episode as unwatched.
+    ///
+    /// The list entry is saved, but not synced to the remote service.
+    pub fn episode_regressed(&mut self, config: &Config, db: &Database) -> Result<()> {
         let entry = &mut self.data.entry;
         entry.set_watched_episodes(entry.watched_episodes().saturating_sub(1));
 
@@ -304,15 +1110,17 @@ impl Series {
         };
 
         entry.set_status(new_status, config);
-        entry.sync_to_remote(remote)?;
         self.save(db)?;
 
         Ok(())
     }
 
+    /// Mark the series as completed at `completed_at`.
+    ///
+    /// The list entry is saved, but not synced to the remote service.
     pub fn series_complete(
         &mut self,
-        remote: &Remote,
+        completed_at: DateTime<Utc>,
         config: &Config,
         db: &Database,
     ) -> Result<()> {
@@ -323,8 +1131,7 @@ impl Series {
             entry.set_times_rewatched(entry.times_rewatched() + 1);
         }
 
-        entry.set_status(Status::Completed, config);
-        entry.sync_to_remote(remote)?;
+        entry.set_status_at(Status::Completed, completed_at, config);
         self.save(db)?;
 
         Ok(())
@@ -338,6 +1145,20 @@ pub enum LoadedSeries {
 }
 
 impl LoadedSeries {
+    /// Scan the episodes of a loaded series again, so it's only partially loaded if its folder can no longer be used.
+    pub fn rescan(&mut self, config: &Config) {
+        let placeholder = Self::None(
+            self.config().clone(),
+            anyhow!("the series is being scanned"),
+        );
+
+        *self = match mem::replace(self, placeholder) {
+            Self::Complete(series) => Series::init(series.data, config),
+            Self::Partial(data, _) => Series::init(data, config),
+            none @ Self::None(_, _) => none,
+        };
+    }
+
     pub fn try_load(&mut self, config: &Config, db: &Database) {
         match self {
             Self::Complete(_) => (),
@@ -346,7 +1167,7 @@ impl LoadedSeries {
         }
     }
 
-    pub fn save(&self, db: &Database) -> diesel::QueryResult<()> {
+    pub fn save(&self, db: &Database) -> Result<()> {
         match self {
             Self::Complete(series) => series.save(db),
             Self::Partial(data, _) => data.save(db),
@@ -363,13 +1184,32 @@ impl LoadedSeries {
     }
 
     pub fn info(&self) -> Option<&SeriesInfo> {
+        self.data().map(|data| &data.info)
+    }
+
+    pub fn data(&self) -> Option<&SeriesData> {
+        match self {
+            Self::Complete(series) => Some(&series.data),
+            Self::Partial(data, _) => Some(data),
+            Self::None(_, _) => None,
+        }
+    }
+
+    pub fn data_mut(&mut self) -> Option<&mut SeriesData> {
         match self {
-            Self::Complete(series) => Some(&series.data.info),
-            Self::Partial(data, _) => Some(&data.info),
+            Self::Complete(series) => Some(&mut series.data),
+            Self::Partial(data, _) => Some(data),
             Self::None(_, _) => None,
         }
     }
 
+    pub fn complete(&self) -> Option<&Series> {
+        match self {
+            Self::Complete(series) => Some(series),
+            Self::Partial(_, _) | Self::None(_, _) => None,
+        }
+    }
+
     pub fn complete_mut(&mut self) -> Option<&mut Series> {
         match self {
             Self::Complete(series) => Some(series),
@@ -570,6 +1410,18 @@ impl SeriesPath {
         Self(path)
     }
 
+    /// Returns where the episodes of the series come from, which is a web server when the path is a URL.
+    pub fn source(&self, config: &Config) -> Box<dyn EpisodeSource> {
+        self.url()
+            .and_then(|url| source::remote(url, config))
+            .unwrap_or_else(|| Box::new(LocalDir(self.absolute(config).into_owned())))
+    }
+
+    /// Returns the path as a URL if it points to a server instead of a local directory.
+    pub fn url(&self) -> Option<&str> {
+        self.0.to_str().filter(|path| source::is_url(path))
+    }
+
     #[inline(always)]
     pub fn absolute(&self, config: &Config) -> Cow<Path> {
         self.absolute_base(&config.series_dir)
@@ -602,16 +1454,57 @@ impl SeriesPath {
     pub fn closest_matching(name: &str, config: &Config) -> Result<Self> {
         use anime::local::detect::dir;
 
-        const MIN_CONFIDENCE: f32 = 0.6;
-
         let dirs = file::subdirectories(&config.series_dir)?;
-
-        dir::closest_match(name, MIN_CONFIDENCE, dirs.into_iter()).map_or_else(
-            || Err(anyhow!("no series found on disk matching {}", name)),
+        let min_confidence = config.matching.path_confidence.as_multiplier();
+
+        dir::closest_match(name, min_confidence, dirs.into_iter()).map_or_else(
+            || {
+                Err(anyhow!(
+                    "no series found on disk matching {}; enter its path manually",
+                    name
+                ))
+            },
             |dir| Ok(Self::new(dir.path(), config)),
         )
     }
 
+    /// Find the directory in `series_dir` that a series with the specified `info` was most likely moved or renamed to.
+    ///
+    /// Directories in `taken` are skipped, since they already belong to other series.
+    pub fn find_moved(
+        info: &SeriesInfo,
+        taken: &[PathBuf],
+        config: &Config,
+    ) -> Result<Option<Self>> {
+        use anime::local::detect::dir;
+
+        let dirs = file::subdirectories(&config.series_dir)?
+            .into_iter()
+            .map(|entry| entry.path())
+            .filter(|path| !taken.contains(path))
+            .collect::<Vec<_>>();
+
+        // The directory could be named after any of the titles of the series
+        let titles = iter::once(&info.title_romaji)
+            .chain(info.title_english.as_ref())
+            .chain(iter::once(&info.title_preferred))
+            .map(|title| anime::normalize_title(title.as_str()))
+            .collect::<SmallVec<[_; 3]>>();
+
+        let min_confidence = config.matching.path_confidence.as_multiplier();
+
+        let found = anime::closest_match(&dirs, min_confidence, |path| {
+            let dir_name = anime::normalize_title(&dir::parse_title(path)?);
+
+            titles
+                .iter()
+                .map(|title| strsim::jaro(&dir_name, title) as f32)
+                .reduce(f32::max)
+        });
+
+        Ok(found.map(|(_, path)| Self::new(path.as_path(), config)))
+    }
+
     #[inline(always)]
     pub fn inner(&self) -> &PathBuf {
         &self.0
@@ -622,7 +1515,8 @@ impl SeriesPath {
     where
         B: AsRef<Path>,
     {
-        self.absolute_base(base).as_ref().exists()
+        // Servers aren't checked, since that would need a request every time the path is changed
+        self.url().is_some() || self.absolute_base(base).as_ref().exists()
     }
 
     #[inline(always)]
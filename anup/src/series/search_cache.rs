@@ -0,0 +1,121 @@
+use crate::cache::CacheKind;
+use crate::file::FileFormat;
+use anime::remote::{SeriesID, SeriesInfo};
+use anyhow::{Context, Result};
+use serde_derive::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// The most characters of a search query that are used in the name of its cache file.
+const MAX_FILENAME_CHARS: usize = 100;
+
+/// Results of a search on the remote service, kept so series can still be found while offline.
+#[derive(Deserialize, Serialize)]
+pub struct SearchCache {
+    /// The normalized search query, which is used to tell apart queries that share a filename.
+    query: String,
+    pub results: Vec<SeriesInfo>,
+}
+
+impl SearchCache {
+    /// Cache `results` as the results of searching for `name`.
+    pub fn save_name(name: &str, results: &[SeriesInfo]) -> Result<()> {
+        let query = normalize_query(name);
+        let path = Self::path(&query)?;
+
+        let cache = Self {
+            query,
+            results: results.to_vec(),
+        };
+
+        FileFormat::Binary.serialize(&cache, path)
+    }
+
+    /// Cache `info` as the result of searching for its ID.
+    pub fn save_id(info: &SeriesInfo) -> Result<()> {
+        let query = format!("id {}", info.id);
+        let path = Self::path(&query)?;
+
+        let cache = Self {
+            query,
+            results: vec![info.clone()],
+        };
+
+        FileFormat::Binary.serialize(&cache, path)
+    }
+
+    /// Returns the cached results of searching for `name`, if it has been searched for before.
+    pub fn load_name(name: &str) -> Result<Option<Vec<SeriesInfo>>> {
+        let query = normalize_query(name);
+        let path = Self::path(&query)?;
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let cache: Self = FileFormat::Binary.deserialize(path)?;
+        Ok((cache.query == query).then_some(cache.results))
+    }
+
+    /// Returns every cached search result, without duplicates.
+    pub fn load_all() -> Result<Vec<SeriesInfo>> {
+        let dir = CacheKind::SearchResults.path()?;
+
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err).context("reading search cache"),
+        };
+
+        let mut all: Vec<SeriesInfo> = Vec::new();
+
+        for entry in entries {
+            let path = entry?.path();
+
+            // Files that can't be read are skipped, since they'll be replaced the next time their query is searched
+            let cache: Self = match FileFormat::Binary.deserialize(&path) {
+                Ok(cache) => cache,
+                Err(_) => continue,
+            };
+
+            for info in cache.results {
+                if !all.iter().any(|existing| existing.id == info.id) {
+                    all.push(info);
+                }
+            }
+        }
+
+        Ok(all)
+    }
+
+    /// Returns the cached info of the series with the specified `id`, if it was in any search results.
+    pub fn find_id(id: SeriesID) -> Result<Option<SeriesInfo>> {
+        let all = Self::load_all()?;
+        Ok(all.into_iter().find(|info| info.id == id))
+    }
+
+    fn path(query: &str) -> Result<PathBuf> {
+        let mut path = CacheKind::SearchResults.path()?;
+        fs::create_dir_all(&path).context("creating search cache directory")?;
+
+        let filename = query
+            .chars()
+            .take(MAX_FILENAME_CHARS)
+            .map(|ch| if ch.is_alphanumeric() { ch } else { '_' })
+            .collect::<String>();
+
+        path.push(filename);
+        path.set_extension(FileFormat::Binary.extension());
+
+        Ok(path)
+    }
+}
+
+/// Returns `name` in lowercase with its whitespace collapsed, so the same query typed slightly differently uses the same cache.
+fn normalize_query(name: &str) -> String {
+    name.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
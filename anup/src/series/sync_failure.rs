@@ -0,0 +1,92 @@
+use crate::database::Database;
+use diesel::prelude::*;
+use diesel::sql_types::{Integer, Text};
+
+/// Tracks how many times in a row the list entry of a series has failed to sync.
+#[derive(Queryable)]
+pub struct SyncFailure {
+    pub failures: i32,
+    pub last_error: String,
+}
+
+impl SyncFailure {
+    /// The number of failures in a row before an entry is skipped when syncing every entry at once.
+    #[allow(clippy::cast_possible_wrap)]
+    pub const MAX_FAILURES: i32 = anime::sync::MAX_FAILURES as i32;
+
+    /// Record that the entry of the series with the specified `id` failed to sync with `error` for the current account.
+    pub fn record(db: &Database, id: i32, error: &str) -> diesel::QueryResult<usize> {
+        diesel::sql_query(
+            "INSERT INTO sync_failures (id, account_id, failures, last_error) VALUES (?, ?, 1, ?)
+                ON CONFLICT(id, account_id) DO UPDATE SET failures = failures + 1, last_error = excluded.last_error",
+        )
+        .bind::<Integer, _>(id)
+        .bind::<Text, _>(db.account())
+        .bind::<Text, _>(error)
+        .execute(db.conn())
+    }
+
+    /// Load the failures of the entry of the series with the specified `id` for the current account.
+    pub fn load(db: &Database, entry_id: i32) -> diesel::QueryResult<Option<Self>> {
+        use crate::database::schema::sync_failures::dsl::{
+            account_id, failures, id, last_error, sync_failures,
+        };
+
+        sync_failures
+            .select((failures, last_error))
+            .filter(id.eq(entry_id))
+            .filter(account_id.eq(db.account()))
+            .get_result(db.conn())
+            .optional()
+    }
+
+    /// Load the failures of every entry for the current account, along with the ID of each entry's series.
+    pub fn load_all(db: &Database) -> diesel::QueryResult<Vec<(i32, Self)>> {
+        use crate::database::schema::sync_failures::dsl::{
+            account_id, failures, id, last_error, sync_failures,
+        };
+
+        let rows = sync_failures
+            .select((id, failures, last_error))
+            .filter(account_id.eq(db.account()))
+            .load::<(i32, i32, String)>(db.conn())?;
+
+        let all = rows
+            .into_iter()
+            .map(|(entry_id, count, error)| {
+                let failure = Self {
+                    failures: count,
+                    last_error: error,
+                };
+
+                (entry_id, failure)
+            })
+            .collect();
+
+        Ok(all)
+    }
+
+    /// Forget the failures of the entry of the series with the specified `id` for the current account.
+    pub fn clear(db: &Database, entry_id: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::sync_failures::dsl::{account_id, id, sync_failures};
+
+        let failure = sync_failures
+            .filter(id.eq(entry_id))
+            .filter(account_id.eq(db.account()));
+
+        diesel::delete(failure).execute(db.conn())
+    }
+
+    /// Delete the failures of every account for the series with the specified `id`.
+    pub fn delete_for_series(db: &Database, entry_id: i32) -> diesel::QueryResult<usize> {
+        use crate::database::schema::sync_failures::dsl::{id, sync_failures};
+
+        diesel::delete(sync_failures.filter(id.eq(entry_id))).execute(db.conn())
+    }
+
+    /// Returns true if the entry has failed to sync too many times in a row to keep retrying it automatically.
+    #[inline(always)]
+    pub fn should_skip(&self) -> bool {
+        self.failures >= Self::MAX_FAILURES
+    }
+}
@@ -0,0 +1,428 @@
+use crate::config::Config;
+use crate::database::Database;
+use crate::file::SerializedFile;
+use crate::ipc::{self, Endpoint, Request as IpcRequest};
+use crate::series::config::SeriesConfig;
+use crate::series::{LoadedSeries, Series, SeriesData};
+use crate::util;
+use crate::{init_remote, open_database, Args};
+use anime::local::SortedEpisodes;
+use anime::remote::Remote;
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+use serde_json::{json, Value};
+use std::fmt::Write;
+use std::rc::Rc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::{self, LocalSet};
+use tokio::time;
+
+/// The most bytes the request line and headers of a request can take up.
+const MAX_HEAD_LEN: usize = 8 * 1024;
+
+/// How long a connected client has to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a connected client has to receive the response to its request.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The number of random bytes in a generated token.
+const TOKEN_LEN: usize = 24;
+
+#[derive(FromArgs)]
+/// Serve an HTTP API that other devices, such as a phone, can use to read the series list and count watched episodes. Requests need the token in serve.token of the config.
+#[argh(subcommand, name = "serve")]
+pub struct ServeArgs {}
+
+pub async fn run(args: &Args, _: &ServeArgs) -> Result<()> {
+    let mut config = Config::load_and_report()?;
+
+    let token = if let Some(token) = &config.serve.token {
+        token.clone()
+    } else {
+        let token = generate_token()?;
+        config.serve.token = Some(token.clone());
+        config.save().context("saving generated token")?;
+
+        println!(
+            "generated a token and saved it to serve.token in the config: {}",
+            token
+        );
+
+        token
+    };
+
+    let db = open_database(args)?;
+    // Counted episodes are still saved without an account, and can be synced later
+    let remote = init_remote(args, &config)?.unwrap_or_else(Remote::offline);
+
+    let listener = TcpListener::bind(&config.serve.address)
+        .await
+        .with_context(|| format!("listening on {}", config.serve.address))?;
+
+    println!("listening on http://{}", config.serve.address);
+
+    let server = Rc::new(Server {
+        config,
+        db,
+        remote,
+        token,
+    });
+
+    // The database can't be shared between threads, so every connection is handled on this one
+    LocalSet::new()
+        .run_until(async move {
+            loop {
+                let (stream, peer) = match listener.accept().await {
+                    Ok(connection) => connection,
+                    Err(err) => {
+                        eprintln!("failed to accept connection: {}", err);
+                        continue;
+                    }
+                };
+
+                let server = Rc::clone(&server);
+
+                // Slow clients would hold up everyone else if connections were handled one at a time
+                task::spawn_local(async move {
+                    if let Err(err) = server.handle(stream).await {
+                        eprintln!("failed to handle request from {}: {:#}", peer, err);
+                    }
+                });
+            }
+        })
+        .await
+}
+
+fn generate_token() -> Result<String> {
+    let mut bytes = [0; TOKEN_LEN];
+    getrandom::getrandom(&mut bytes).context("generating token")?;
+
+    let mut token = String::with_capacity(TOKEN_LEN * 2);
+
+    for byte in &bytes {
+        write!(token, "{:02x}", byte)?;
+    }
+
+    Ok(token)
+}
+
+struct Server {
+    config: Config,
+    db: Database,
+    remote: Remote,
+    token: String,
+}
+
+impl Server {
+    async fn handle(&self, mut stream: TcpStream) -> Result<()> {
+        let head = time::timeout(REQUEST_TIMEOUT, read_head(&mut stream))
+            .await
+            .map_err(|_| anyhow!("timed out waiting for the request"))??;
+
+        let response = match Request::parse(&head) {
+            Some(request) => self.respond(&request),
+            None => Response::error(Status::BadRequest, "malformed request"),
+        };
+
+        time::timeout(
+            RESPONSE_TIMEOUT,
+            stream.write_all(response.to_string().as_bytes()),
+        )
+        .await
+        .map_err(|_| anyhow!("timed out sending the response"))?
+        .context("sending response")?;
+
+        stream.shutdown().await.ok();
+        Ok(())
+    }
+
+    fn respond(&self, request: &Request) -> Response {
+        if !self.is_authorized(request) {
+            return Response::error(Status::Unauthorized, "missing or incorrect token");
+        }
+
+        let path = request.path.split('?').next().unwrap_or_default();
+        let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+        let result = match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["series"]) => self.list(),
+            ("GET", ["series", name]) => self.series(name),
+            ("POST", ["series", name, "progress"]) => self.count_episode(name),
+            (_, ["series"] | ["series", _] | ["series", _, "progress"]) => {
+                return Response::error(Status::MethodNotAllowed, "method not allowed")
+            }
+            _ => return Response::error(Status::NotFound, "not found"),
+        };
+
+        result.unwrap_or_else(|err| Response::error(Status::InternalError, &format!("{:#}", err)))
+    }
+
+    fn is_authorized(&self, request: &Request) -> bool {
+        let token = match request.authorization.as_deref() {
+            Some(value) => value.strip_prefix("Bearer ").unwrap_or_default().trim(),
+            None => return false,
+        };
+
+        // Every byte is compared so the time taken doesn't reveal how much of the token was right
+        token.len() == self.token.len()
+            && token
+                .bytes()
+                .zip(self.token.bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+
+    fn list(&self) -> Result<Response> {
+        let configs = SeriesConfig::load_all(&self.db).context("loading series")?;
+        let mut series = Vec::with_capacity(configs.len());
+
+        for config in configs {
+            let data =
+                SeriesData::load_from_config(&self.db, config.into()).context("loading series")?;
+
+            series.push(series_json(&data));
+        }
+
+        Ok(Response::ok(Value::Array(series)))
+    }
+
+    fn series(&self, name: &str) -> Result<Response> {
+        let response = match self.load(name)? {
+            Some(data) => Response::ok(series_json(&data)),
+            None => Response::not_added(name),
+        };
+
+        Ok(response)
+    }
+
+    fn count_episode(&self, name: &str) -> Result<Response> {
+        let data = match self.load(name)? {
+            Some(data) => data,
+            None => return Ok(Response::not_added(name)),
+        };
+
+        let id = data.config.id;
+        let episode = data.entry.watched_episodes() + 1;
+
+        // An open TUI has its own copy of the series, so it has to be the one to change it
+        if ipc::send(Endpoint::Tui, IpcRequest::CompleteEpisode(id, episode))
+            .context("sending episode to the TUI")?
+        {
+            let data = self
+                .load(name)?
+                .ok_or_else(|| anyhow!("series was removed"))?;
+            return Ok(Response::ok(series_json(&data)));
+        }
+
+        // Episodes aren't needed to count one as watched, so series whose episodes can't be found are fine
+        let mut series = Series::with_episodes(data, SortedEpisodes::default());
+
+        series
+            .episode_completed(&self.config, &self.db)
+            .context("marking episode as completed")?;
+
+        // The entry stays marked as needing a sync if this fails, so it's picked up by the next sync
+        if let Err(err) = series.data.sync_to_remote(&self.remote, &self.db) {
+            eprintln!("failed to sync {}: {:#}", series.data.config.nickname, err);
+        } else {
+            series.save(&self.db)?;
+        }
+
+        println!(
+            "counted episode {} of {} as watched",
+            episode,
+            series.data.title()
+        );
+
+        Ok(Response::ok(series_json(&series.data)))
+    }
+
+    /// Load the series with the nickname or alias `name`, percent-encoded as it was in the path.
+    ///
+    /// Returns `Ok(None)` if there's no such series.
+    fn load(&self, name: &str) -> Result<Option<SeriesData>> {
+        let name = util::percent_decode(name);
+
+        let config = match SeriesConfig::load_by_name_or_alias(&self.db, &name) {
+            Ok(config) => config,
+            Err(diesel::NotFound) => return Ok(None),
+            Err(err) => return Err(err).context("loading series"),
+        };
+
+        match Series::load_from_config(config, &self.config, &self.db) {
+            LoadedSeries::Complete(series) => Ok(Some(series.data)),
+            LoadedSeries::Partial(data, _) => Ok(Some(data)),
+            LoadedSeries::None(_, err) => Err(err),
+        }
+    }
+}
+
+fn series_json(data: &SeriesData) -> Value {
+    let status: &'static str = data.entry.status().into();
+
+    json!({
+        "nickname": data.config.nickname.to_string(),
+        "title": data.title().to_string(),
+        "status": status,
+        "watched_episodes": data.entry.watched_episodes(),
+        "total_episodes": data.info.total_episodes(),
+        "needs_sync": data.entry.needs_sync(),
+    })
+}
+
+/// Read the request line and headers of a request. Request bodies aren't used, so they're never read.
+async fn read_head(stream: &mut TcpStream) -> Result<String> {
+    let mut head = Vec::new();
+    let mut buffer = [0; 1024];
+
+    loop {
+        let read = stream.read(&mut buffer).await.context("reading request")?;
+
+        if read == 0 {
+            return Err(anyhow!(
+                "the connection was closed before a request was sent"
+            ));
+        }
+
+        head.extend_from_slice(&buffer[..read]);
+
+        if let Some(end) = head.windows(4).position(|bytes| bytes == b"\r\n\r\n") {
+            head.truncate(end);
+            return String::from_utf8(head).context("request isn't valid UTF-8");
+        }
+
+        if head.len() > MAX_HEAD_LEN {
+            return Err(anyhow!("request headers are too long"));
+        }
+    }
+}
+
+/// The parts of an HTTP request that the API uses.
+#[derive(Debug, PartialEq)]
+struct Request {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+}
+
+impl Request {
+    fn parse(head: &str) -> Option<Self> {
+        let mut lines = head.split("\r\n");
+        let mut request_line = lines.next()?.split(' ');
+
+        let method = request_line.next()?.to_string();
+        let path = request_line.next()?.to_string();
+
+        if !request_line.next()?.starts_with("HTTP/") {
+            return None;
+        }
+
+        let authorization = lines.find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+
+            if name.eq_ignore_ascii_case("authorization") {
+                Some(value.trim().to_string())
+            } else {
+                None
+            }
+        });
+
+        Some(Self {
+            method,
+            path,
+            authorization,
+        })
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Status {
+    Ok,
+    BadRequest,
+    Unauthorized,
+    NotFound,
+    MethodNotAllowed,
+    InternalError,
+}
+
+impl Status {
+    fn line(self) -> &'static str {
+        match self {
+            Self::Ok => "200 OK",
+            Self::BadRequest => "400 Bad Request",
+            Self::Unauthorized => "401 Unauthorized",
+            Self::NotFound => "404 Not Found",
+            Self::MethodNotAllowed => "405 Method Not Allowed",
+            Self::InternalError => "500 Internal Server Error",
+        }
+    }
+}
+
+struct Response {
+    status: Status,
+    body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Self {
+            status: Status::Ok,
+            body,
+        }
+    }
+
+    fn error(status: Status, message: &str) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message }),
+        }
+    }
+
+    fn not_added(name: &str) -> Self {
+        let message = format!("{} is not added", util::percent_decode(name));
+        Self::error(Status::NotFound, &message)
+    }
+}
+
+impl std::fmt::Display for Response {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let body = self.body.to_string();
+
+        write!(
+            f,
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status.line(),
+            body.len(),
+            body
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_request() {
+        let request = Request::parse(
+            "POST /series/made%20up/progress HTTP/1.1\r\nHost: anup\r\nauthorization:  Bearer abc \r\n",
+        );
+
+        let expected = Request {
+            method: "POST".into(),
+            path: "/series/made%20up/progress".into(),
+            authorization: Some("Bearer abc".into()),
+        };
+
+        assert_eq!(request, Some(expected));
+
+        let request = Request::parse("GET /series HTTP/1.1\r\nHost: anup").unwrap();
+        assert_eq!(request.authorization, None);
+
+        assert_eq!(Request::parse("GET /series"), None);
+        assert_eq!(Request::parse("not a request"), None);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::config::Config;
+use crate::series::config::SeriesConfig;
+use crate::series::SeriesData;
+use crate::{confirm, init_remote, open_database, Args};
+use anime::remote::Status;
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+use std::borrow::Cow;
+
+#[derive(FromArgs)]
+/// Change the list entries of every series matching a filter at once.
+#[argh(subcommand, name = "set")]
+pub struct SetArgs {
+    /// the watch status to set
+    #[argh(option)]
+    status: Option<Status>,
+
+    /// the score to set, from 0 to 100 (0 removes the score)
+    #[argh(option)]
+    score: Option<u8>,
+
+    /// only change series with this watch status
+    #[argh(option)]
+    filter: Option<Status>,
+
+    /// show what would be changed without changing anything
+    #[argh(switch)]
+    dry_run: bool,
+
+    /// don't ask for confirmation before making changes
+    #[argh(switch, short = 'y')]
+    yes: bool,
+}
+
+pub fn run(args: &Args, set_args: &SetArgs) -> Result<()> {
+    if set_args.status.is_none() && set_args.score.is_none() {
+        return Err(anyhow!("nothing to set\nspecify --status or --score"));
+    }
+
+    if matches!(set_args.score, Some(score) if score > 100) {
+        return Err(anyhow!("the score must be between 0 and 100"));
+    }
+
+    let config = Config::load_and_report()?;
+    let db = open_database(args)?;
+
+    let mut matching = SeriesConfig::load_all(&db)
+        .context("loading series configs")?
+        .into_iter()
+        .map(|series_config| SeriesData::load_from_config(&db, Cow::Owned(series_config)))
+        .collect::<Result<Vec<_>, _>>()
+        .context("loading series")?;
+
+    matching.retain(|data| {
+        set_args
+            .filter
+            .map_or(true, |filter| data.entry.status() == filter)
+    });
+
+    matching.sort_unstable_by(|x, y| x.config.nickname.cmp(&y.config.nickname));
+
+    if matching.is_empty() {
+        println!("no series match the filter");
+        return Ok(());
+    }
+
+    for data in &matching {
+        println!("{}", describe_change(data, set_args));
+    }
+
+    if set_args.dry_run {
+        println!("{} series would be changed", matching.len());
+        return Ok(());
+    }
+
+    if !set_args.yes && !confirm(&format!("change {} series?", matching.len()))? {
+        return Ok(());
+    }
+
+    // Changes are saved even when offline, so they can be synced later with --sync
+    let remote = if args.offline {
+        None
+    } else {
+        init_remote(args, &config)?
+    };
+
+    let mut unsynced = 0;
+
+    for data in &mut matching {
+        if let Some(status) = set_args.status {
+            data.entry.set_status(status, &config);
+        }
+
+        if let Some(score) = set_args.score {
+            let score = if score == 0 {
+                None
+            } else {
+                Some(i16::from(score))
+            };
+            data.entry.set_score(score);
+        }
+
+        data.save(&db)?;
+
+        let remote = match &remote {
+            Some(remote) => remote,
+            None => continue,
+        };
+
+        if let Err(err) = data
+            .sync_to_remote(remote, &db)
+            .and_then(|()| data.save(&db))
+        {
+            eprintln!("failed to sync {}: {:#}", data.config.nickname, err);
+            unsynced += 1;
+        }
+    }
+
+    println!("changed {} series", matching.len());
+
+    if remote.is_none() || unsynced > 0 {
+        println!("run anup --sync to send any changes that haven't been synced");
+    }
+
+    Ok(())
+}
+
+/// Describe how the list entry of the series with the specified `data` will be changed.
+fn describe_change(data: &SeriesData, set_args: &SetArgs) -> String {
+    let mut changes = Vec::with_capacity(2);
+
+    if let Some(status) = set_args.status {
+        changes.push(format!("status {} -> {}", data.entry.status(), status));
+    }
+
+    if let Some(score) = set_args.score {
+        let current = data
+            .entry
+            .score()
+            .map_or_else(|| "none".into(), |score| score.to_string());
+
+        let new = if score == 0 {
+            "none".into()
+        } else {
+            score.to_string()
+        };
+
+        changes.push(format!("score {} -> {}", current, new));
+    }
+
+    format!("{}: {}", data.config.nickname, changes.join(", "))
+}
@@ -0,0 +1,327 @@
+use crate::config::Config;
+use crate::database::Database;
+use crate::file::SerializedFile;
+use crate::user::Users;
+use crate::{open_database, Args};
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Binary, Text};
+use ron::ser::PrettyConfig;
+use std::fmt::Write;
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+
+/// The name the settings archive is attached to the database as.
+const ARCHIVE: &str = "archive";
+
+/// The tables that are copied to the settings archive.
+///
+/// Paths in `series_configs` are already relative to the series directory, unless a series is outside of it.
+const TABLES: &[&str] = &[
+    "series_configs",
+    "series_info",
+    "series_aliases",
+    "series_collections",
+    "filler_episodes",
+    "episode_overrides",
+];
+
+#[derive(FromArgs)]
+/// Save the config and every series' settings to one file, so they can be moved to another machine. Episodes, list entries, and the logins and tokens in the config aren't included.
+#[argh(subcommand, name = "export-settings")]
+pub struct ExportSettingsArgs {
+    /// the file to save the settings to
+    #[argh(positional)]
+    path: PathBuf,
+
+    /// include the accounts of every profile, which can be used by anyone with the file
+    #[argh(switch)]
+    include_accounts: bool,
+
+    /// include the logins and tokens in the config, such as the torrent client's password and the token of anup serve
+    #[argh(switch)]
+    include_secrets: bool,
+}
+
+#[derive(FromArgs)]
+/// Load the config and series settings saved with export-settings.
+#[argh(subcommand, name = "import-settings")]
+pub struct ImportSettingsArgs {
+    /// the file to load the settings from
+    #[argh(positional)]
+    path: PathBuf,
+}
+
+pub fn run_export(args: &Args, export_args: &ExportSettingsArgs) -> Result<()> {
+    let path = &export_args.path;
+    let db = open_database(args)?;
+    let conn = db.conn();
+
+    create_archive(
+        path,
+        export_args.include_accounts || export_args.include_secrets,
+    )?;
+
+    if let Err(err) = attach(conn, path) {
+        fs::remove_file(path).ok();
+        return Err(err);
+    }
+
+    let result = export(conn, export_args);
+    detach(conn)?;
+
+    if let Err(err) = result {
+        // Don't leave a partial archive around that could be imported later
+        fs::remove_file(path).ok();
+        return Err(err);
+    }
+
+    println!("settings exported to {}", path.display());
+
+    if !export_args.include_accounts {
+        println!("accounts weren't included; use --include-accounts to include them");
+    }
+
+    if !export_args.include_secrets {
+        println!("logins and tokens in the config weren't included; use --include-secrets to include them");
+    }
+
+    Ok(())
+}
+
+/// Create an empty settings archive at `path`, which fails if something is already there.
+///
+/// The archive can only be read by the current user when it has accounts or secrets in it.
+fn create_archive(path: &Path, private: bool) -> Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+
+    if private {
+        options.mode(0o600);
+    }
+
+    match options.open(path) {
+        Ok(_) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+            Err(anyhow!("{} already exists", path.display()))
+        }
+        Err(err) => Err(err).with_context(|| format!("creating {}", path.display())),
+    }
+}
+
+fn export(conn: &SqliteConnection, export_args: &ExportSettingsArgs) -> Result<()> {
+    let version = Database::user_version(conn, "main").context("getting schema version")?;
+
+    let mut statements = format!(
+        "PRAGMA {archive}.user_version = {};
+        CREATE TABLE {archive}.files (name TEXT NOT NULL PRIMARY KEY, contents BLOB NOT NULL);",
+        version,
+        archive = ARCHIVE
+    );
+
+    for table in TABLES {
+        write!(
+            statements,
+            "CREATE TABLE {archive}.{table} AS SELECT * FROM main.{table};",
+            archive = ARCHIVE,
+            table = table
+        )?;
+    }
+
+    conn.batch_execute(&statements)
+        .context("copying series settings")?;
+
+    let config_path = Config::validated_save_path()?;
+    let mut files = vec![config_path.clone()];
+
+    if export_args.include_accounts {
+        files.push(Users::validated_save_path()?);
+    }
+
+    // Accounts are copied as they are, so encrypted accounts stay encrypted
+    for file in files {
+        if !file.exists() {
+            continue;
+        }
+
+        let contents = if file == config_path && !export_args.include_secrets {
+            config_without_secrets()?
+        } else {
+            fs::read(&file).with_context(|| format!("reading {}", file.display()))?
+        };
+
+        diesel::sql_query(format!(
+            "INSERT INTO {}.files (name, contents) VALUES (?, ?)",
+            ARCHIVE
+        ))
+        .bind::<Text, _>(file_name(&file)?)
+        .bind::<Binary, _>(contents)
+        .execute(conn)
+        .with_context(|| format!("copying {}", file.display()))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the config with its logins and tokens removed.
+fn config_without_secrets() -> Result<Vec<u8>> {
+    let mut config = Config::load().context("loading config")?;
+
+    for secret in config.secrets_mut() {
+        *secret = None;
+    }
+
+    let pretty = PrettyConfig::default().with_decimal_floats(true);
+    let serialized = ron::ser::to_string_pretty(&config, pretty).context("encoding config")?;
+
+    Ok(serialized.into_bytes())
+}
+
+pub fn run_import(args: &Args, import_args: &ImportSettingsArgs) -> Result<()> {
+    let path = &import_args.path;
+
+    if !path.exists() {
+        return Err(anyhow!("{} doesn't exist", path.display()));
+    }
+
+    let db = open_database(args)?;
+
+    attach(db.conn(), path)?;
+    let result = import(&db);
+    detach(db.conn())?;
+
+    let (imported, total) = result?;
+    println!("imported {} of {} series", imported, total);
+
+    if imported < total {
+        println!("series that have the same ID or nickname as one that has already been added were skipped");
+    }
+
+    Ok(())
+}
+
+/// Import the attached settings archive, and return the number of series that were imported along with the number in the archive.
+fn import(db: &Database) -> Result<(i64, i64)> {
+    #[derive(QueryableByName)]
+    struct File {
+        #[sql_type = "Text"]
+        name: String,
+        #[sql_type = "Binary"]
+        contents: Vec<u8>,
+    }
+
+    let conn = db.conn();
+
+    let archive_version =
+        Database::user_version(conn, ARCHIVE).context("getting archive version")?;
+    let version = Database::user_version(conn, "main").context("getting schema version")?;
+
+    if archive_version != version {
+        return Err(anyhow!(
+            "the settings were exported by a different version of anup\nexport them again with the same version as this one"
+        ));
+    }
+
+    let counts = db.write_transaction(|| {
+        // Series that have already been added are left alone, along with the aliases, collections, filler episodes, and episode overrides they have in the archive
+        conn.batch_execute(&format!(
+            "CREATE TEMP TABLE imported AS
+                SELECT a.id FROM {archive}.series_configs a
+                WHERE NOT EXISTS (SELECT 1 FROM main.series_configs c WHERE c.id = a.id OR c.nickname = a.nickname);
+            INSERT INTO main.series_configs SELECT * FROM {archive}.series_configs WHERE id IN temp.imported;
+            INSERT OR IGNORE INTO main.series_info SELECT * FROM {archive}.series_info WHERE id IN temp.imported;
+            INSERT OR IGNORE INTO main.series_aliases SELECT * FROM {archive}.series_aliases WHERE series_id IN temp.imported;
+            INSERT OR IGNORE INTO main.series_collections
+                SELECT * FROM {archive}.series_collections WHERE series_id IN temp.imported;
+            INSERT OR IGNORE INTO main.filler_episodes
+                SELECT * FROM {archive}.filler_episodes WHERE series_id IN temp.imported;
+            INSERT OR IGNORE INTO main.episode_overrides
+                SELECT * FROM {archive}.episode_overrides WHERE series_id IN temp.imported;",
+            archive = ARCHIVE
+        ))
+        .context("importing series settings")?;
+
+        let imported = diesel::select(diesel::dsl::sql::<BigInt>(
+            "(SELECT COUNT(*) FROM temp.imported)",
+        ))
+        .get_result::<i64>(conn)?;
+
+        let total = diesel::select(diesel::dsl::sql::<BigInt>(&format!(
+            "(SELECT COUNT(*) FROM {}.series_configs)",
+            ARCHIVE
+        )))
+        .get_result::<i64>(conn)?;
+
+        conn.batch_execute("DROP TABLE temp.imported")?;
+
+        Ok((imported, total))
+    })?;
+
+    let files = diesel::sql_query(format!("SELECT name, contents FROM {}.files", ARCHIVE))
+        .load::<File>(conn)
+        .context("loading files")?;
+
+    let config_path = Config::validated_save_path()?;
+    let accounts_path = Users::validated_save_path()?;
+
+    for file in files {
+        if file.name == file_name(&config_path)? {
+            // The config of this machine shouldn't be lost, so the imported one is put next to it instead
+            if config_path.exists() {
+                let side_path = config_path.with_extension("imported.ron");
+
+                fs::write(&side_path, file.contents).context("writing imported config")?;
+
+                println!(
+                    "a config already exists, so the imported one was saved to {}\nreplace the config with it to use it",
+                    side_path.display()
+                );
+
+                continue;
+            }
+
+            fs::write(&config_path, file.contents).context("writing config")?;
+
+            let config = Config::load_and_report()?;
+            println!(
+                "config imported; series_dir is {}",
+                config.series_dir.display()
+            );
+        } else if file.name == file_name(&accounts_path)? {
+            // Accounts that were already added shouldn't be lost
+            if !Users::load_or_create()?.is_empty() {
+                println!("accounts weren't imported since accounts have already been added");
+                continue;
+            }
+
+            fs::write(&accounts_path, file.contents).context("writing accounts")?;
+            println!("accounts imported");
+        }
+    }
+
+    Ok(counts)
+}
+
+fn attach(conn: &SqliteConnection, path: &Path) -> Result<()> {
+    conn.batch_execute(&format!(
+        "ATTACH DATABASE '{}' AS {}",
+        path.to_string_lossy().replace('\'', "''"),
+        ARCHIVE
+    ))
+    .with_context(|| format!("opening {}", path.display()))
+}
+
+fn detach(conn: &SqliteConnection) -> Result<()> {
+    conn.batch_execute(&format!("DETACH DATABASE {}", ARCHIVE))
+        .context("closing settings archive")
+}
+
+fn file_name(path: &Path) -> Result<&str> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow!("invalid file name: {}", path.display()))
+}
@@ -0,0 +1,357 @@
+use crate::database::{Database, SharedStr};
+use crate::series::config::SeriesConfig;
+use crate::series::entry::SeriesEntry;
+use crate::series::{Series, SeriesData};
+use crate::util;
+use crate::{open_database, Args};
+use anime::remote::Status;
+use anyhow::{Context, Result};
+use argh::FromArgs;
+use serde_json::json;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+#[derive(FromArgs)]
+/// Print statistics about every added series, like those in the TUI's stats panel.
+#[argh(subcommand, name = "stats")]
+pub struct StatsArgs {
+    /// the number of most watched genres to show
+    #[argh(option, short = 'g', default = "5")]
+    genres: usize,
+
+    /// print the statistics as JSON
+    #[argh(switch)]
+    json: bool,
+}
+
+pub const NUM_SCORE_BUCKETS: usize = 10;
+
+/// Every status in the order they're counted in.
+pub const STATUSES: [Status; 6] = [
+    Status::Watching,
+    Status::Completed,
+    Status::OnHold,
+    Status::Dropped,
+    Status::PlanToWatch,
+    Status::Rewatching,
+];
+
+/// Statistics about every added series.
+#[derive(Debug)]
+pub struct Stats {
+    pub scores: ScoreDistribution,
+    /// Every genre sorted by descending watch time.
+    pub genres: Vec<GenreStats>,
+    pub watched_mins: u32,
+    /// The number of series with each status, in the same order as `STATUSES`.
+    status_counts: [u32; STATUSES.len()],
+}
+
+impl Stats {
+    /// Builds the statistics of `series`, using the scores of completed series from `db`.
+    pub fn load<'a, I>(db: &Database, series: I) -> Result<Self>
+    where
+        I: Iterator<Item = &'a SeriesData> + Clone,
+    {
+        let scores =
+            SeriesEntry::completed_scores(db).context("loading completed series scores")?;
+
+        Ok(Self::new(series, scores))
+    }
+
+    fn new<'a, I>(series: I, completed_scores: Vec<i16>) -> Self
+    where
+        I: Iterator<Item = &'a SeriesData> + Clone,
+    {
+        let mut status_counts = [0; STATUSES.len()];
+
+        for data in series.clone() {
+            status_counts[data.entry.status() as usize] += 1;
+        }
+
+        Self {
+            scores: ScoreDistribution::new(completed_scores),
+            genres: GenreStats::from_series(series.clone()),
+            watched_mins: series.map(SeriesData::watched_mins).sum(),
+            status_counts,
+        }
+    }
+
+    /// Returns the number of series with each status.
+    pub fn status_counts(&self) -> impl Iterator<Item = (Status, u32)> + '_ {
+        STATUSES
+            .iter()
+            .copied()
+            .zip(self.status_counts.iter().copied())
+    }
+
+    /// Returns the total number of series.
+    pub fn total_series(&self) -> u32 {
+        self.status_counts.iter().sum()
+    }
+}
+
+/// The spread of scores given to completed series, grouped into buckets of 10 points.
+#[derive(Debug, Default)]
+pub struct ScoreDistribution {
+    pub buckets: [u64; NUM_SCORE_BUCKETS],
+    pub total: usize,
+    pub mean: Option<f32>,
+    pub median: Option<f32>,
+}
+
+impl ScoreDistribution {
+    fn new(mut scores: Vec<i16>) -> Self {
+        if scores.is_empty() {
+            return Self::default();
+        }
+
+        scores.sort_unstable();
+
+        let mut buckets = [0; NUM_SCORE_BUCKETS];
+
+        for &score in &scores {
+            let index = (score.max(1) as usize - 1) / 10;
+            buckets[index.min(NUM_SCORE_BUCKETS - 1)] += 1;
+        }
+
+        let total = scores.len();
+        let sum = scores.iter().map(|&score| f32::from(score)).sum::<f32>();
+        let mid = total / 2;
+
+        let median = if total % 2 == 0 {
+            (f32::from(scores[mid - 1]) + f32::from(scores[mid])) / 2.0
+        } else {
+            f32::from(scores[mid])
+        };
+
+        Self {
+            buckets,
+            total,
+            mean: Some(sum / total as f32),
+            median: Some(median),
+        }
+    }
+}
+
+/// The total watch time and mean score of every series within a single genre.
+#[derive(Debug)]
+pub struct GenreStats {
+    pub name: String,
+    pub watched_mins: u32,
+    pub mean_score: Option<f32>,
+}
+
+impl GenreStats {
+    /// Builds the statistics for every genre found in `series`, sorted by descending watch time.
+    fn from_series<'a, I>(series: I) -> Vec<Self>
+    where
+        I: Iterator<Item = &'a SeriesData>,
+    {
+        // Genre name -> (watched minutes, score sum, number of scores)
+        let mut totals: HashMap<&str, (u32, f32, u32)> = HashMap::new();
+
+        for data in series {
+            let watched_mins = data.watched_mins();
+            let score = data.entry.score();
+
+            for genre in data.info.genres.iter() {
+                let total = totals.entry(genre.as_str()).or_default();
+                total.0 += watched_mins;
+
+                if let Some(score) = score {
+                    total.1 += f32::from(score);
+                    total.2 += 1;
+                }
+            }
+        }
+
+        let mut genres = totals
+            .into_iter()
+            .map(|(name, (watched_mins, score_sum, num_scores))| Self {
+                name: name.into(),
+                watched_mins,
+                mean_score: (num_scores > 0).then(|| score_sum / num_scores as f32),
+            })
+            .collect::<Vec<_>>();
+
+        genres.sort_unstable_by(|x, y| {
+            y.watched_mins
+                .cmp(&x.watched_mins)
+                .then_with(|| x.name.cmp(&y.name))
+        });
+
+        genres
+    }
+}
+
+/// Episodes that have been downloaded but not watched yet.
+#[derive(Debug, Default)]
+pub struct Backlog {
+    /// The nickname and number of unwatched episodes on disk of each series that has any,
+    /// sorted by descending number of episodes.
+    pub series: Vec<(SharedStr, u32)>,
+    pub episodes: u32,
+    /// The time it would take to watch every unwatched episode.
+    pub mins: u32,
+}
+
+impl Backlog {
+    /// Builds the backlog of every series in `series` that hasn't been dropped.
+    pub fn from_series<'a, I>(series: I) -> Self
+    where
+        I: Iterator<Item = &'a Series>,
+    {
+        let mut backlog = Self::default();
+
+        for series in series {
+            if series.data.entry.status() == Status::Dropped {
+                continue;
+            }
+
+            let unwatched = series.unwatched_on_disk();
+
+            if unwatched == 0 {
+                continue;
+            }
+
+            let episode_length = series.data.info.episode_length_mins.max(0) as u32;
+
+            backlog.episodes += unwatched;
+            backlog.mins += unwatched * episode_length;
+            backlog
+                .series
+                .push((series.data.config.nickname.clone(), unwatched));
+        }
+
+        backlog
+            .series
+            .sort_unstable_by(|(x_name, x_count), (y_name, y_count)| {
+                y_count.cmp(x_count).then_with(|| x_name.cmp(y_name))
+            });
+
+        backlog
+    }
+}
+
+pub fn run(args: &Args, stats_args: &StatsArgs) -> Result<()> {
+    let db = open_database(args)?;
+
+    let series = SeriesConfig::load_all(&db)
+        .context("loading series configs")?
+        .into_iter()
+        .map(|series_config| SeriesData::load_from_config(&db, Cow::Owned(series_config)))
+        .collect::<Result<Vec<_>, _>>()
+        .context("loading series")?;
+
+    let stats = Stats::load(&db, series.iter())?;
+    let genres = &stats.genres[..stats.genres.len().min(stats_args.genres)];
+
+    if stats_args.json {
+        print_json(&stats, genres);
+    } else {
+        print_text(&stats, genres);
+    }
+
+    Ok(())
+}
+
+fn print_json(stats: &Stats, genres: &[GenreStats]) {
+    let statuses = stats
+        .status_counts()
+        .map(|(status, count)| {
+            let name = status.to_string().to_lowercase().replace(' ', "_");
+            (name, count.into())
+        })
+        .collect::<serde_json::Map<_, _>>();
+
+    let genres = genres
+        .iter()
+        .map(|genre| {
+            json!({
+                "name": genre.name,
+                "watched_mins": genre.watched_mins,
+                "mean_score": genre.mean_score,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let output = json!({
+        "total_series": stats.total_series(),
+        "watched_mins": stats.watched_mins,
+        "statuses": statuses,
+        "rated": stats.scores.total,
+        "mean_score": stats.scores.mean,
+        "median_score": stats.scores.median,
+        "top_genres": genres,
+    });
+
+    println!("{}", output);
+}
+
+fn print_text(stats: &Stats, genres: &[GenreStats]) {
+    // Scores are always shown out of 100, since showing them in the user's score format needs a login
+    let score =
+        |score: Option<f32>| score.map_or_else(|| "-".into(), |score| format!("{:.1}", score));
+
+    println!("Series: {}", stats.total_series());
+    println!(
+        "Time Watched: {}",
+        util::hm_from_mins(stats.watched_mins as f32)
+    );
+
+    for (status, count) in stats.status_counts().filter(|&(_, count)| count > 0) {
+        println!("  {}: {}", status, count);
+    }
+
+    println!(
+        "Mean Score: {}   Median: {}   Rated: {}",
+        score(stats.scores.mean),
+        score(stats.scores.median),
+        stats.scores.total
+    );
+
+    if genres.is_empty() {
+        return;
+    }
+
+    println!("Top Genres:");
+
+    let name_width = genres
+        .iter()
+        .map(|genre| genre.name.len())
+        .max()
+        .unwrap_or(0);
+
+    for genre in genres {
+        println!(
+            "  {:width$}   {}   {}",
+            genre.name,
+            util::hm_from_mins(genre.watched_mins as f32),
+            score(genre.mean_score),
+            width = name_width
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_distribution() {
+        let dist = ScoreDistribution::new(vec![100, 75, 70, 5, 80]);
+
+        assert_eq!(dist.buckets, [1, 0, 0, 0, 0, 0, 1, 2, 0, 1]);
+        assert_eq!(dist.total, 5);
+        assert_eq!(dist.mean, Some(66.0));
+        assert_eq!(dist.median, Some(75.0));
+
+        let dist = ScoreDistribution::new(vec![60, 90]);
+        assert_eq!(dist.median, Some(75.0));
+
+        let dist = ScoreDistribution::new(Vec::new());
+        assert_eq!(dist.mean, None);
+        assert_eq!(dist.median, None);
+    }
+}
@@ -0,0 +1,603 @@
+use crate::config::Config;
+use crate::crypt;
+use crate::database::Database;
+use crate::err::Failure;
+use crate::private_session::PrivateSession;
+use crate::series::config::SeriesConfig;
+use crate::series::entry::SeriesEntry;
+use crate::{confirm, init_remote, open_database, Args};
+use anime::remote::{Remote, RemoteService, SeriesInfo as RemoteInfo, Status};
+use anime::SeriesKind;
+use anyhow::{anyhow, Context, Result};
+use argh::FromArgs;
+use chrono::{DateTime, Utc};
+use serde_derive::Deserialize;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const API_URL: &str = "https://api.trakt.tv";
+
+/// The environment variable the client secret of the Trakt API app is read from before it's prompted for, since
+/// arguments can be seen by other users in the list of running processes.
+const CLIENT_SECRET_VAR: &str = "ANUP_TRAKT_SECRET";
+
+/// How long to wait for Trakt to respond to a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(FromArgs)]
+/// Catch up list entries with the shows watched on Trakt, such as when moving from a general TV tracker.
+#[argh(subcommand, name = "import-trakt")]
+pub struct ImportTraktArgs {
+    /// the client ID of a Trakt API app, which can be created at <https://trakt.tv/oauth/applications>. Its client
+    /// secret is read from the `ANUP_TRAKT_SECRET` environment variable or prompted for
+    #[argh(option)]
+    client_id: String,
+
+    /// show what would be changed without changing anything
+    #[argh(switch)]
+    dry_run: bool,
+
+    /// don't ask for confirmation before making changes
+    #[argh(switch, short = 'y')]
+    yes: bool,
+}
+
+#[allow(clippy::cast_possible_wrap)]
+pub fn run(args: &Args, import_args: &ImportTraktArgs) -> Result<()> {
+    if args.offline {
+        return Err(Failure::Offline.error("must be online to import from Trakt"));
+    }
+
+    if PrivateSession::is_active() {
+        return Err(anyhow!(
+            "a private session is active\nend it with the private off command to import from Trakt"
+        ));
+    }
+
+    let config = Config::load_and_report()?;
+    let db = open_database(args)?;
+
+    let remote = init_remote(args, &config)?
+        .ok_or_else(|| Failure::Auth.error("no users found\nadd one in the TUI"))?;
+
+    let client_secret = match env::var(CLIENT_SECRET_VAR) {
+        Ok(secret) => secret,
+        Err(_) => crypt::prompt_hidden("Trakt client secret: ").with_context(|| {
+            format!(
+                "reading client secret\nset {} if a terminal isn't available",
+                CLIENT_SECRET_VAR
+            )
+        })?,
+    };
+
+    let client = Client::authorize(&import_args.client_id, &client_secret)?;
+
+    let shows = client
+        .watched_shows()
+        .context("getting watched shows from Trakt")?;
+
+    // Whether each added series is only tracked locally, keyed by its ID
+    let added = SeriesConfig::load_all(&db)
+        .context("loading series configs")?
+        .into_iter()
+        .map(|config| (config.id, config.local_only))
+        .collect::<HashMap<_, _>>();
+
+    let (matched, unmatched) = match_shows(&shows, &config, &remote)?;
+
+    // Entries of series that haven't been added are all looked up at once, rather than one request per season
+    let listed_ids = matched
+        .iter()
+        .map(|matched| matched.info.id)
+        .filter(|&id| !added.contains_key(&(id as i32)))
+        .collect::<Vec<_>>();
+
+    let remote_entries = remote
+        .get_list_entries(&listed_ids)
+        .context("getting list entries")?
+        .into_iter()
+        .map(|entry| (entry.id, entry))
+        .collect::<HashMap<_, _>>();
+
+    let mut changes = Vec::new();
+
+    for MatchedSeason {
+        title,
+        season,
+        info,
+    } in matched
+    {
+        let id = info.id as i32;
+        let is_added = added.contains_key(&id);
+        let is_local = added.get(&id) == Some(&true);
+
+        let entry = if is_added {
+            SeriesEntry::load(&db, id).context("loading list entry")?
+        } else {
+            remote_entries
+                .get(&info.id)
+                .cloned()
+                .map_or_else(|| SeriesEntry::from(id), SeriesEntry::from)
+        };
+
+        if let Some(change) = Change::new(&season, info, entry, is_added, is_local, &config) {
+            println!("{} season {}: {}", title, season.number, change);
+            changes.push(change);
+        }
+    }
+
+    if unmatched > 0 {
+        println!(
+            "{} season(s) couldn't be matched and can be updated by hand",
+            unmatched
+        );
+    }
+
+    if changes.is_empty() {
+        return Err(Failure::NothingToDo.error("no list entries are behind Trakt"));
+    }
+
+    if import_args.dry_run {
+        println!("{} list entries would be changed", changes.len());
+        return Ok(());
+    }
+
+    if !import_args.yes && !confirm(&format!("change {} list entries?", changes.len()))? {
+        return Ok(());
+    }
+
+    let mut failed = 0;
+
+    for change in &mut changes {
+        if let Err(err) = change.apply(&db, &remote) {
+            eprintln!(
+                "failed to update {}: {:#}",
+                change.info.title.preferred, err
+            );
+            failed += 1;
+        }
+    }
+
+    println!("changed {} list entries", changes.len() - failed);
+
+    if failed > 0 {
+        return Err(Failure::Partial.error(format!("{} list entries failed to update", failed)));
+    }
+
+    Ok(())
+}
+
+/// A season watched on Trakt that was found on the remote service.
+struct MatchedSeason<'a> {
+    title: &'a str,
+    season: WatchedSeason,
+    info: RemoteInfo,
+}
+
+/// Find each season watched in `shows` on `remote`.
+///
+/// Returns the seasons that were found along with the title of their show, and the number of seasons that weren't.
+fn match_shows<'a>(
+    shows: &'a [WatchedShow],
+    config: &Config,
+    remote: &Remote,
+) -> Result<(Vec<MatchedSeason<'a>>, usize)> {
+    let mut matched = Vec::new();
+    let mut unmatched = 0;
+
+    for show in shows {
+        let title = show.show.title.as_str();
+        let seasons = WatchedSeason::from_show(show);
+
+        if seasons.is_empty() {
+            continue;
+        }
+
+        let infos = match_seasons(title, &seasons, config, remote)
+            .with_context(|| format!("finding {} on AniList", title))?;
+
+        for (season, info) in seasons.into_iter().zip(infos) {
+            if let Some(info) = info {
+                matched.push(MatchedSeason {
+                    title,
+                    season,
+                    info,
+                });
+
+                continue;
+            }
+
+            println!(
+                "{} season {}: no match found on AniList",
+                title, season.number
+            );
+            unmatched += 1;
+        }
+    }
+
+    Ok((matched, unmatched))
+}
+
+/// Find the series on `remote` of each season in `seasons`, which belong to the Trakt show titled `title`.
+///
+/// The first season is found by searching for the title of the show, and the ones after it by following the sequels
+/// of the ones before. Seasons that can't be found are `None`.
+fn match_seasons(
+    title: &str,
+    seasons: &[WatchedSeason],
+    config: &Config,
+    remote: &Remote,
+) -> Result<Vec<Option<RemoteInfo>>> {
+    let results = remote.search_info_by_name(title)?;
+    let min_confidence = config.matching.title_confidence.as_multiplier();
+    let name = anime::normalize_title(title);
+
+    // Trakt only lists TV shows, so movies with a similar title shouldn't be picked
+    let candidates = results
+        .into_iter()
+        .filter(|info| matches!(info.kind, SeriesKind::Season | SeriesKind::ONA));
+
+    let confidence =
+        |title: &str| strsim::jaro_winkler(&anime::normalize_title(title), &name) as f32;
+
+    // Trakt usually has the English title, while AniList may only have it in romaji
+    let first = anime::closest_match(candidates, min_confidence, |info| {
+        let english = info.title.english.as_deref().map_or(0.0, confidence);
+        Some(confidence(&info.title.romaji).max(english))
+    })
+    .map(|(_, info)| info);
+
+    let mut found = Vec::with_capacity(seasons.len());
+    let mut current = first;
+    let mut current_number = 1;
+
+    for season in seasons {
+        while current_number < season.number {
+            let sequel = current
+                .as_ref()
+                .and_then(RemoteInfo::direct_sequel)
+                .map(|sequel| sequel.id);
+
+            current = match sequel {
+                Some(id) => Some(remote.search_info_by_id(id)?),
+                None => None,
+            };
+
+            current_number += 1;
+        }
+
+        found.push(current.clone());
+    }
+
+    Ok(found)
+}
+
+/// A list entry that is behind the progress made on Trakt.
+struct Change {
+    info: RemoteInfo,
+    entry: SeriesEntry,
+    /// The number of episodes the entry had watched before the change.
+    watched_before: i16,
+    status_before: Status,
+    /// Whether the series has been added to the program, and so has a copy of its list entry stored locally.
+    is_added: bool,
+    /// Whether the series is only tracked locally, and so shouldn't be sent to the remote service.
+    local_only: bool,
+}
+
+impl Change {
+    /// Catch `entry` up with the progress of `season`.
+    ///
+    /// Returns `None` if the entry is already as far along, or if its status means it shouldn't be changed.
+    #[allow(clippy::cast_possible_wrap)]
+    fn new(
+        season: &WatchedSeason,
+        info: RemoteInfo,
+        mut entry: SeriesEntry,
+        is_added: bool,
+        local_only: bool,
+        config: &Config,
+    ) -> Option<Self> {
+        // Series that are finished or being rewatched already have progress that means something else
+        if matches!(
+            entry.status(),
+            Status::Completed | Status::Rewatching | Status::Dropped
+        ) {
+            return None;
+        }
+
+        let watched = match info.episodes {
+            0 => season.last_episode,
+            episodes => season.last_episode.min(episodes),
+        };
+
+        let watched_before = entry.watched_episodes();
+        let status_before = entry.status();
+
+        if watched as i16 <= watched_before {
+            return None;
+        }
+
+        if status_before == Status::PlanToWatch {
+            entry.set_status_at(Status::Watching, season.first_watched_at, config);
+        }
+
+        if info.episodes > 0 && watched >= info.episodes {
+            entry.set_status_at(Status::Completed, season.last_watched_at, config);
+        }
+
+        entry.set_watched_episodes(watched as i16);
+
+        Some(Self {
+            info,
+            entry,
+            watched_before,
+            status_before,
+            is_added,
+            local_only,
+        })
+    }
+
+    /// Send the changed entry to `remote` unless the series is only tracked locally, and save it locally if the
+    /// series has been added.
+    fn apply(&mut self, db: &Database, remote: &Remote) -> Result<()> {
+        // Series that haven't been added don't have a local copy to send later, so the change has to be sent now
+        if !self.local_only {
+            self.entry.force_sync_to_remote(remote, db, None)?;
+        }
+
+        if self.is_added {
+            self.entry.save(db).context("saving list entry")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} episodes {} -> {}",
+            self.info.title.preferred,
+            self.watched_before,
+            self.entry.watched_episodes()
+        )?;
+
+        if self.status_before != self.entry.status() {
+            write!(f, ", {} -> {}", self.status_before, self.entry.status())?;
+        }
+
+        if self.local_only {
+            write!(f, " (only tracked locally)")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How far along a season of a show was watched on Trakt.
+#[derive(Debug, PartialEq)]
+struct WatchedSeason {
+    number: u32,
+    /// The highest episode number that was watched.
+    last_episode: u32,
+    /// When the first episode of the season was watched. Trakt only sends the last time each episode was watched,
+    /// so this is the earliest of those.
+    first_watched_at: DateTime<Utc>,
+    last_watched_at: DateTime<Utc>,
+}
+
+impl WatchedSeason {
+    /// Returns the watched seasons of `show` in ascending order, leaving out specials since anime trackers keep them in
+    /// series of their own.
+    fn from_show(show: &WatchedShow) -> Vec<Self> {
+        let mut seasons = BTreeMap::new();
+
+        for season in show.seasons.iter().filter(|season| season.number > 0) {
+            for episode in &season.episodes {
+                let watched_at = match parse_time(&episode.last_watched_at) {
+                    Some(watched_at) => watched_at,
+                    None => continue,
+                };
+
+                let watched = seasons.entry(season.number).or_insert(Self {
+                    number: season.number,
+                    last_episode: episode.number,
+                    first_watched_at: watched_at,
+                    last_watched_at: watched_at,
+                });
+
+                watched.last_episode = watched.last_episode.max(episode.number);
+                watched.first_watched_at = watched.first_watched_at.min(watched_at);
+                watched.last_watched_at = watched.last_watched_at.max(watched_at);
+            }
+        }
+
+        seasons.into_values().collect()
+    }
+}
+
+fn parse_time(time: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// An authorized connection to the Trakt API.
+struct Client<'a> {
+    api_key: &'a str,
+    access_token: String,
+}
+
+impl<'a> Client<'a> {
+    /// Have the user authorize the program on Trakt by entering a code on its website, and wait until they do.
+    fn authorize(client_id: &'a str, client_secret: &str) -> Result<Self> {
+        let code = attohttpc::post(format!("{}/oauth/device/code", API_URL))
+            .timeout(REQUEST_TIMEOUT)
+            .json(&json!({ "client_id": client_id }))?
+            .send()
+            .context("requesting Trakt device code")?;
+
+        if !code.is_success() {
+            return Err(anyhow!(
+                "Trakt returned {} when requesting a device code\nmake sure the client ID is correct",
+                code.status()
+            ));
+        }
+
+        let code = code
+            .json::<DeviceAuthorization>()
+            .context("decoding Trakt device code")?;
+
+        println!(
+            "go to {} and enter the code {} to let anup read your Trakt history",
+            code.verification_url, code.user_code
+        );
+
+        let expires_at = Instant::now() + Duration::from_secs(code.expires_in);
+        let mut interval = Duration::from_secs(code.interval.max(1));
+
+        let body = json!({
+            "code": code.device_code,
+            "client_id": client_id,
+            "client_secret": client_secret,
+        });
+
+        while Instant::now() < expires_at {
+            thread::sleep(interval);
+
+            let response = attohttpc::post(format!("{}/oauth/device/token", API_URL))
+                .timeout(REQUEST_TIMEOUT)
+                .json(&body)?
+                .send()
+                .context("requesting Trakt access token")?;
+
+            match response.status().as_u16() {
+                200 => {
+                    let token = response
+                        .json::<AccessToken>()
+                        .context("decoding Trakt access token")?;
+
+                    return Ok(Self {
+                        api_key: client_id,
+                        access_token: token.access_token,
+                    });
+                }
+                // The code hasn't been entered yet
+                400 => (),
+                429 => interval += Duration::from_secs(1),
+                404 => return Err(anyhow!("Trakt didn't recognize the device code")),
+                409 => return Err(anyhow!("the code was already used")),
+                410 => break,
+                418 => return Err(Failure::Auth.error("access was denied on Trakt")),
+                code => return Err(anyhow!("Trakt returned {} when authorizing", code)),
+            }
+        }
+
+        Err(Failure::Auth.error("the code expired before it was entered"))
+    }
+
+    /// Get every show that has had an episode watched, along with the episodes that were.
+    fn watched_shows(&self) -> Result<Vec<WatchedShow>> {
+        let response = attohttpc::get(format!("{}/sync/watched/shows", API_URL))
+            .timeout(REQUEST_TIMEOUT)
+            .header("trakt-api-version", "2")
+            .header("trakt-api-key", self.api_key)
+            .bearer_auth(&self.access_token)
+            .send()?;
+
+        if !response.is_success() {
+            return Err(anyhow!("Trakt returned {}", response.status()));
+        }
+
+        response.json().map_err(Into::into)
+    }
+}
+
+#[derive(Deserialize)]
+struct DeviceAuthorization {
+    device_code: String,
+    user_code: String,
+    verification_url: String,
+    /// The number of seconds until the code expires.
+    expires_in: u64,
+    /// The number of seconds to wait between each check for the access token.
+    interval: u64,
+}
+
+#[derive(Deserialize)]
+struct AccessToken {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct WatchedShow {
+    show: Show,
+    #[serde(default)]
+    seasons: Vec<Season>,
+}
+
+#[derive(Deserialize)]
+struct Show {
+    title: String,
+}
+
+#[derive(Deserialize)]
+struct Season {
+    number: u32,
+    episodes: Vec<Episode>,
+}
+
+#[derive(Deserialize)]
+struct Episode {
+    number: u32,
+    last_watched_at: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn watched_seasons() {
+        let show: WatchedShow = serde_json::from_value(json!({
+            "show": { "title": "Example" },
+            "seasons": [
+                { "number": 0, "episodes": [{ "number": 1, "last_watched_at": "2021-01-01T00:00:00.000Z" }] },
+                { "number": 2, "episodes": [{ "number": 3, "last_watched_at": "2021-02-01T00:00:00.000Z" }] },
+                { "number": 1, "episodes": [
+                    { "number": 2, "last_watched_at": "2021-01-09T12:00:00.000Z" },
+                    { "number": 1, "last_watched_at": "2021-01-08T12:00:00.000Z" },
+                    { "number": 3, "last_watched_at": "invalid" },
+                ] },
+            ],
+        }))
+        .unwrap();
+
+        let seasons = WatchedSeason::from_show(&show);
+
+        assert_eq!(
+            seasons,
+            [
+                WatchedSeason {
+                    number: 1,
+                    last_episode: 2,
+                    first_watched_at: Utc.ymd(2021, 1, 8).and_hms(12, 0, 0),
+                    last_watched_at: Utc.ymd(2021, 1, 9).and_hms(12, 0, 0),
+                },
+                WatchedSeason {
+                    number: 2,
+                    last_episode: 3,
+                    first_watched_at: Utc.ymd(2021, 2, 1).and_hms(0, 0, 0),
+                    last_watched_at: Utc.ymd(2021, 2, 1).and_hms(0, 0, 0),
+                },
+            ]
+        );
+    }
+}
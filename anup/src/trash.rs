@@ -0,0 +1,126 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::Local;
+use std::fmt::Write as _;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+/// Move `path` to the trash of the current user, following the FreeDesktop.org trash specification.
+///
+/// Returns where `path` was moved to.
+pub fn move_to_trash(path: &Path) -> Result<PathBuf> {
+    let trash = trash_dir()?;
+    let files_dir = trash.join("files");
+    let info_dir = trash.join("info");
+
+    fs::create_dir_all(&files_dir).context("creating trash directory")?;
+    fs::create_dir_all(&info_dir).context("creating trash info directory")?;
+
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("{} does not exist", path.display()))?;
+
+    let name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("{} cannot be moved to the trash", path.display()))?
+        .to_string_lossy()
+        .into_owned();
+
+    // Creating the info file first reserves the name in the trash
+    let (trashed_name, info_path, mut info_file) = {
+        let mut i = 1;
+
+        loop {
+            let candidate = match i {
+                1 => name.clone(),
+                i => format!("{}.{}", name, i),
+            };
+
+            i += 1;
+
+            if files_dir.join(&candidate).exists() {
+                continue;
+            }
+
+            let info_path = info_dir.join(format!("{}.trashinfo", candidate));
+
+            match OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&info_path)
+            {
+                Ok(file) => break (candidate, info_path, file),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => (),
+                Err(err) => return Err(err).context("creating trash info file"),
+            }
+        }
+    };
+
+    write!(
+        info_file,
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        encode_path(&path),
+        Local::now().format("%Y-%m-%dT%H:%M:%S")
+    )
+    .context("writing trash info file")?;
+
+    let trashed_path = files_dir.join(&trashed_name);
+
+    if let Err(err) = fs::rename(&path, &trashed_path) {
+        fs::remove_file(&info_path).ok();
+
+        return Err(err).with_context(|| {
+            format!(
+                "moving {} to the trash\nit must be on the same filesystem as {}",
+                path.display(),
+                trash.display()
+            )
+        });
+    }
+
+    Ok(trashed_path)
+}
+
+/// Move `trashed_path` that was returned from `move_to_trash` back to `original`.
+pub fn restore(trashed_path: &Path, original: &Path) -> Result<()> {
+    if original.exists() {
+        return Err(anyhow!("{} already exists", original.display()));
+    }
+
+    fs::rename(trashed_path, original)
+        .with_context(|| format!("moving {} out of the trash", trashed_path.display()))?;
+
+    if let Some(name) = trashed_path.file_name() {
+        let mut info_name = name.to_os_string();
+        info_name.push(".trashinfo");
+
+        fs::remove_file(trash_dir()?.join("info").join(info_name)).ok();
+    }
+
+    Ok(())
+}
+
+fn trash_dir() -> Result<PathBuf> {
+    let mut dir = dirs_next::data_dir().ok_or_else(|| anyhow!("unable to find data directory"))?;
+    dir.push("Trash");
+    Ok(dir)
+}
+
+/// Percent-encode `path` for the `Path` key of a trash info file.
+fn encode_path(path: &Path) -> String {
+    let mut encoded = String::new();
+
+    for &byte in path.as_os_str().as_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                encoded.push(byte as char);
+            }
+            byte => {
+                write!(encoded, "%{:02X}", byte).ok();
+            }
+        }
+    }
+
+    encoded
+}
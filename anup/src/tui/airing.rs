@@ -0,0 +1,78 @@
+use crate::tui::locale::Text;
+use anime::remote::AiringEpisode;
+use chrono::{Datelike, Local, TimeZone, Weekday};
+use std::collections::HashMap;
+
+/// The next episode to air of each series being watched, used to group them by the day of the week they air on.
+#[derive(Default)]
+pub struct AiringSchedule {
+    /// Series ID -> its next episode to air.
+    episodes: HashMap<i32, AiringEpisode>,
+}
+
+impl AiringSchedule {
+    pub fn new(episodes: HashMap<i32, AiringEpisode>) -> Self {
+        Self { episodes }
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, id: i32) -> bool {
+        self.episodes.contains_key(&id)
+    }
+
+    /// Returns the day of the week the series with the specified `id` airs on.
+    pub fn weekday(&self, id: i32) -> Option<Weekday> {
+        let episode = self.episodes.get(&id)?;
+        Some(Local.timestamp(episode.airs_at, 0).weekday())
+    }
+
+    /// Returns the number of episodes of the series with the specified `id` that air before its finale does, if
+    /// `total` episodes are known to air.
+    pub fn episodes_until_finale(&self, id: i32, total: u32) -> Option<u32> {
+        let episode = self.episodes.get(&id)?;
+        (total > 0).then(|| total.saturating_sub(episode.episode))
+    }
+
+    /// Returns the number of days after `today` that the series with the specified `id` airs on, from 0 to 6.
+    ///
+    /// Series air on the same day every week, so one that already aired earlier today still counts as airing today.
+    pub fn days_until(&self, id: i32, today: Weekday) -> Option<u32> {
+        self.weekday(id).map(|weekday| days_between(today, weekday))
+    }
+
+    /// Returns the name of the day the series with the specified `id` airs on, or "Today" if it airs today.
+    pub fn day_label(&self, id: i32, today: Weekday) -> Option<&'static str> {
+        let weekday = self.weekday(id)?;
+
+        let text = match weekday {
+            _ if weekday == today => Text::Today,
+            Weekday::Mon => Text::Monday,
+            Weekday::Tue => Text::Tuesday,
+            Weekday::Wed => Text::Wednesday,
+            Weekday::Thu => Text::Thursday,
+            Weekday::Fri => Text::Friday,
+            Weekday::Sat => Text::Saturday,
+            Weekday::Sun => Text::Sunday,
+        };
+
+        Some(text.get())
+    }
+}
+
+/// Returns the number of days from `from` until the next `to`, or 0 if they're the same day.
+fn days_between(from: Weekday, to: Weekday) -> u32 {
+    (to.num_days_from_monday() + 7 - from.num_days_from_monday()) % 7
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_between_weekdays() {
+        assert_eq!(days_between(Weekday::Mon, Weekday::Mon), 0);
+        assert_eq!(days_between(Weekday::Mon, Weekday::Wed), 2);
+        assert_eq!(days_between(Weekday::Sat, Weekday::Mon), 2);
+        assert_eq!(days_between(Weekday::Tue, Weekday::Mon), 6);
+    }
+}
@@ -0,0 +1,118 @@
+use once_cell::sync::OnceCell;
+use std::env;
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::widgets::Widget;
+
+static UNICODE: OnceCell<bool> = OnceCell::new();
+
+/// Terminals that can only show ASCII characters.
+const ASCII_TERMS: [&str; 6] = ["dumb", "ansi", "vt52", "vt100", "vt102", "vt220"];
+
+/// Set whether borders and symbols are drawn with unicode characters.
+///
+/// This can only be done once, and unicode is used until it is.
+pub fn init(unicode: bool) {
+    UNICODE.set(unicode).ok();
+}
+
+/// Returns true if borders and symbols should be drawn with unicode characters.
+#[inline(always)]
+pub fn unicode() -> bool {
+    UNICODE.get().copied().unwrap_or(true)
+}
+
+/// Returns false if the character set of the locale set by the `LC_ALL`, `LC_CTYPE`, or `LANG` environment variables isn't
+/// UTF-8, or if `TERM` is set to a terminal that can only show ASCII.
+pub fn detect() -> bool {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty());
+
+    let term = env::var("TERM").ok();
+
+    supports_unicode(locale.as_deref(), term.as_deref())
+}
+
+fn supports_unicode(locale: Option<&str>, term: Option<&str>) -> bool {
+    if matches!(term, Some(term) if ASCII_TERMS.contains(&term)) {
+        return false;
+    }
+
+    // Without a locale, the C locale is used, which is ASCII
+    let charset = locale
+        .and_then(|locale| locale.split(&['.', '@'][..]).nth(1))
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    matches!(charset.as_str(), "utf-8" | "utf8")
+}
+
+/// Returns the ASCII character to draw in place of a box-drawing, block, or spinner `symbol`.
+fn ascii_equivalent(symbol: &str) -> Option<&'static str> {
+    let ch = symbol.chars().next()?;
+
+    let ascii = match ch {
+        '─' | '━' | '═' => "-",
+        '│' | '┃' | '║' => "|",
+        '┌' | '┐' | '└' | '┘' | '╭' | '╮' | '╰' | '╯' | '├' | '┤' | '┬' | '┴' | '┼' | '┏' | '┓'
+        | '┗' | '┛' | '┣' | '┫' | '┳' | '┻' | '╋' | '╔' | '╗' | '╚' | '╝' | '╠' | '╣' | '╦'
+        | '╩' | '╬' => "+",
+        '▁' | '▂' | '▃' => "_",
+        '▄' | '▅' | '▆' | '▇' | '█' => "#",
+        '…' => ".",
+        // Braille patterns, which spinners and canvases are commonly drawn with
+        '\u{2800}'..='\u{28FF}' => "*",
+        _ => return None,
+    };
+
+    Some(ascii)
+}
+
+/// A widget that replaces every box-drawing, block, and spinner character in its area with an ASCII equivalent.
+///
+/// It should be rendered last, over the entire frame.
+pub struct AsciiFallback;
+
+impl Widget for AsciiFallback {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let cell = buf.get_mut(x, y);
+
+                if cell.symbol.is_ascii() {
+                    continue;
+                }
+
+                if let Some(ascii) = ascii_equivalent(&cell.symbol) {
+                    cell.set_symbol(ascii);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_unicode_support() {
+        assert!(supports_unicode(Some("en_US.UTF-8"), Some("xterm")));
+        assert!(supports_unicode(Some("ja_JP.utf8"), None));
+        assert!(!supports_unicode(Some("en_US.ISO-8859-1"), Some("xterm")));
+        assert!(!supports_unicode(Some("C"), Some("xterm")));
+        assert!(!supports_unicode(None, Some("xterm")));
+        assert!(!supports_unicode(Some("en_US.UTF-8"), Some("vt100")));
+    }
+
+    #[test]
+    fn ascii_equivalents() {
+        assert_eq!(ascii_equivalent("─"), Some("-"));
+        assert_eq!(ascii_equivalent("╯"), Some("+"));
+        assert_eq!(ascii_equivalent("█"), Some("#"));
+        assert_eq!(ascii_equivalent("⠋"), Some("*"));
+        assert_eq!(ascii_equivalent("視"), None);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::config::ChordAction;
+use crate::tui::locale::Text;
+use crate::tui::state::UIState;
+use std::borrow::Cow;
+use tui::backend::Backend;
+use tui::layout::Rect;
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::{block, text},
+    layout::BasicConstraint,
+    widgets::SimpleTable,
+};
+
+/// A list of the keys that can finish the key chord being entered, along with what each one does.
+pub struct ChordHints;
+
+impl ChordHints {
+    fn action_text(action: &ChordAction) -> Cow<'static, str> {
+        let text = match action {
+            ChordAction::Stats => Text::StatsPanel,
+            ChordAction::History => Text::ChordHistory,
+            ChordAction::Tasks => Text::TasksPanel,
+            ChordAction::Notifications => Text::NotificationsPanel,
+            ChordAction::Events => Text::EventsPanel,
+            ChordAction::AuditLog => Text::AuditLogPanel,
+            ChordAction::Profile => Text::ProfilePanel,
+            ChordAction::Users => Text::ChordUsers,
+            ChordAction::AddSeries => Text::AddSeriesPanel,
+            ChordAction::EditSeries => Text::UpdateSeriesPanel,
+            ChordAction::DeleteSeries => Text::DeleteSeriesPanel,
+            ChordAction::SplitSeries => Text::SplitSeriesPanel,
+            ChordAction::RewatchFilter => Text::PlannedRewatchesPanel,
+            ChordAction::AiringFilter => Text::AiringPanel,
+            ChordAction::NextCollection => Text::ChordNextCollection,
+            ChordAction::Command(command) => return format!(":{}", command).into(),
+        };
+
+        text.get().into()
+    }
+
+    pub fn draw<B: Backend>(state: &UIState, rect: Rect, frame: &mut Frame<B>) {
+        let pressed = match &state.chord {
+            Some(chord) => chord.keys(),
+            None => return,
+        };
+
+        let pressed_text = pressed
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let title = Text::ChordPanel.format(&[&pressed_text]);
+        let block = block::with_borders(title.as_str());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        // Only the key that comes next is shown, since the ones before it have already been pressed
+        let rows = state
+            .config
+            .tui
+            .chords
+            .starting_with(pressed)
+            .map(|binding| {
+                let remaining = &binding.keys.keys()[pressed.len()..];
+
+                let keys = remaining
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                [
+                    text::bold(keys),
+                    Span::raw(Self::action_text(&binding.action)),
+                ]
+            });
+
+        let layout = [
+            BasicConstraint::Percentage(20),
+            BasicConstraint::Percentage(80),
+        ];
+        let table = SimpleTable::new(rows, layout);
+
+        frame.render_widget(table, block_area);
+    }
+}
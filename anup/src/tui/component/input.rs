@@ -1,4 +1,6 @@
 use crate::series::SeriesPath;
+use crate::tui::locale::Text;
+use crate::tui::theme::Tone;
 use crate::{config::Config, key::Key};
 use anime::local::detect::CustomPattern;
 use anime::local::EpisodeParser;
@@ -8,7 +10,6 @@ use crossterm::event::KeyCode;
 use std::borrow::Cow;
 use std::path::PathBuf;
 use tui::layout::{Alignment, Direction, Rect};
-use tui::style::Color;
 use tui::terminal::Frame;
 use tui::{backend::Backend, text::Span};
 use tui_utils::{
@@ -104,13 +105,13 @@ impl Input {
     pub fn draw<B: Backend>(&self, rect: Rect, frame: &mut Frame<B>) {
         let is_disabled = self.flags.contains(InputFlags::DISABLED);
 
-        let block_color = if is_disabled {
-            Some(Color::DarkGray)
+        let block_tone = if is_disabled {
+            Some(Tone::Faded)
         } else {
             match (self.is_selected(), self.has_error()) {
-                (true, true) => Some(Color::LightRed),
-                (true, false) => Some(Color::Blue),
-                (false, true) => Some(Color::Red),
+                (true, true) => Some(Tone::BadSelected),
+                (true, false) => Some(Tone::Accent),
+                (false, true) => Some(Tone::Bad),
                 (false, false) => None,
             }
         };
@@ -122,7 +123,7 @@ impl Input {
 
         let label_widget = {
             let style = if is_disabled {
-                style::bold().fg(Color::DarkGray)
+                Tone::Faded.apply(style::bold())
             } else {
                 style::bold()
             };
@@ -136,8 +137,8 @@ impl Input {
 
         let mut block = block::with_borders(None);
 
-        if let Some(color) = block_color {
-            block = block.border_style(style::fg(color));
+        if let Some(tone) = block_tone {
+            block = block.border_style(tone.style());
         }
 
         let content_area = block.inner(layout[1]);
@@ -147,7 +148,7 @@ impl Input {
         let text: Span = match (self.caret.is_empty(), &self.placeholder) {
             (true, Some(placeholder)) if !self.flags.contains(InputFlags::IGNORE_PLACEHOLDER) => {
                 let slice = &placeholder[self.caret.pos()..];
-                Span::styled(slice, style::fg(Color::DarkGray))
+                Span::styled(slice, Tone::Faded.style())
             }
             _ => {
                 let visible_offset = self.get_visible_offset(content_area.width);
@@ -404,24 +405,24 @@ pub trait DrawInput: ValidatedInput {
 pub struct NameInput(Input);
 
 impl NameInput {
-    const LABEL: &'static str = "Name";
+    const LABEL: Text = Text::NameLabel;
 
     pub fn new(flags: InputFlags) -> Self {
-        Self(Input::new(flags, Self::LABEL))
+        Self(Input::new(flags, Self::LABEL.get()))
     }
 
     pub fn with_placeholder<S>(flags: InputFlags, name: S) -> Self
     where
         S: Into<String>,
     {
-        let input = Input::with_placeholder(flags, Self::LABEL, name);
+        let input = Input::with_placeholder(flags, Self::LABEL.get(), name);
         Self(input)
     }
 }
 
 impl ValidatedInput for NameInput {
     fn label(&self) -> &'static str {
-        Self::LABEL
+        Self::LABEL.get()
     }
 
     fn input(&self) -> &Input {
@@ -438,7 +439,7 @@ impl ValidatedInput for NameInput {
     }
 
     fn error_message(&self) -> Cow<'static, str> {
-        "Name must not be empty".into()
+        Text::NameEmpty.get().into()
     }
 }
 
@@ -458,18 +459,18 @@ pub struct IDInput {
 }
 
 impl IDInput {
-    const LABEL: &'static str = "ID";
+    const LABEL: Text = Text::IdLabel;
 
     pub fn new(flags: InputFlags) -> Self {
         Self {
-            input: Input::new(flags, Self::LABEL),
+            input: Input::new(flags, Self::LABEL.get()),
             id: None,
         }
     }
 
     pub fn with_id(flags: InputFlags, id: SeriesID) -> Self {
         Self {
-            input: Input::with_placeholder(flags, Self::LABEL, id.to_string()),
+            input: Input::with_placeholder(flags, Self::LABEL.get(), id.to_string()),
             id: Some(id),
         }
     }
@@ -477,7 +478,7 @@ impl IDInput {
 
 impl ValidatedInput for IDInput {
     fn label(&self) -> &'static str {
-        Self::LABEL
+        Self::LABEL.get()
     }
 
     fn input(&self) -> &Input {
@@ -507,7 +508,7 @@ impl ValidatedInput for IDInput {
     }
 
     fn error_message(&self) -> Cow<'static, str> {
-        "ID must be a positive number".into()
+        Text::IdNotNumber.get().into()
     }
 }
 
@@ -528,11 +529,11 @@ pub struct PathInput {
 }
 
 impl PathInput {
-    const LABEL: &'static str = "Path";
+    const LABEL: Text = Text::PathLabel;
 
     pub fn new(flags: InputFlags, config: &Config) -> Self {
         Self {
-            input: Input::new(flags, Self::LABEL),
+            input: Input::new(flags, Self::LABEL.get()),
             base_path: config.series_dir.clone(),
             path: None,
         }
@@ -546,7 +547,7 @@ impl PathInput {
         let path_display = path.inner().to_string_lossy();
 
         Self {
-            input: Input::with_placeholder(flags, Self::LABEL, path_display),
+            input: Input::with_placeholder(flags, Self::LABEL.get(), path_display),
             base_path: config.series_dir.clone(),
             path: None,
         }
@@ -554,7 +555,7 @@ impl PathInput {
 
     pub fn with_path(flags: InputFlags, config: &Config, path: SeriesPath) -> Self {
         Self {
-            input: Input::with_text(flags, Self::LABEL, format!("{}", path.display())),
+            input: Input::with_text(flags, Self::LABEL.get(), format!("{}", path.display())),
             base_path: config.series_dir.clone(),
             path: Some(path),
         }
@@ -563,7 +564,7 @@ impl PathInput {
 
 impl ValidatedInput for PathInput {
     fn label(&self) -> &'static str {
-        Self::LABEL
+        Self::LABEL.get()
     }
 
     fn input(&self) -> &Input {
@@ -591,7 +592,7 @@ impl ValidatedInput for PathInput {
     }
 
     fn error_message(&self) -> Cow<'static, str> {
-        "Path must exist".into()
+        Text::PathMissing.get().into()
     }
 }
 
@@ -611,11 +612,11 @@ pub struct ParserInput {
 }
 
 impl ParserInput {
-    const LABEL: &'static str = "Episode Pattern";
+    const LABEL: Text = Text::PatternLabel;
 
     pub fn new(flags: InputFlags) -> Self {
         Self {
-            input: Input::new(flags, Self::LABEL),
+            input: Input::new(flags, Self::LABEL.get()),
             parser: EpisodeParser::default(),
         }
     }
@@ -625,7 +626,7 @@ impl ParserInput {
         S: AsRef<str>,
     {
         Self {
-            input: Input::with_text(flags, Self::LABEL, pattern),
+            input: Input::with_text(flags, Self::LABEL.get(), pattern),
             parser: EpisodeParser::default(),
         }
     }
@@ -638,7 +639,7 @@ impl ParserInput {
 
 impl ValidatedInput for ParserInput {
     fn label(&self) -> &'static str {
-        Self::LABEL
+        Self::LABEL.get()
     }
 
     fn input(&self) -> &Input {
@@ -669,12 +670,9 @@ impl ValidatedInput for ParserInput {
     }
 
     fn error_message(&self) -> Cow<'static, str> {
-        // TODO: use concat! macro if/when it can accept constants, or when a similiar crate doesn't require nightly
-        format!(
-            "Must mark episode location with {}",
-            CustomPattern::EPISODE_MARKER,
-        )
-        .into()
+        Text::PatternMissingMarker
+            .format(&[&CustomPattern::EPISODE_MARKER])
+            .into()
     }
 }
 
@@ -4,20 +4,23 @@ use crate::tui::component::input::{
     ValidatedInput,
 };
 use crate::tui::component::Component;
+use crate::tui::locale::Text;
+use crate::tui::theme::Tone;
 use crate::tui::UIState;
+use crate::util::ScopedTask;
 use crate::{config::Config, key::Key};
 use crate::{file, tui::state::SharedState};
 use crate::{
-    series::info::{InfoSelector, SeriesInfo},
+    series::info::{InfoResult, InfoSelector, SeriesInfo},
     util::ArcMutex,
 };
 use crate::{
     series::{self, LoadedSeries, SeriesParams, SeriesPath, UpdateParams},
     util::arc_mutex,
 };
-use crate::{try_opt_ret, util::ScopedTask};
-use anime::local::{CategorizedEpisodes, EpisodeParser, SortedEpisodes};
-use anime::remote::SeriesID;
+use anime::local::detect::CustomPattern;
+use anime::local::{Episode, EpisodeParser, SortedEpisodes};
+use anime::remote::{Remote, RemoteService, SeriesID};
 use anyhow::{Context, Result};
 use crossterm::event::KeyCode;
 use std::mem;
@@ -26,7 +29,6 @@ use std::{borrow::Cow, sync::Arc, time::Duration};
 use tokio::task;
 use tui::backend::Backend;
 use tui::layout::{Alignment, Direction, Rect};
-use tui::style::Color;
 use tui::terminal::Frame;
 use tui_utils::{
     helpers::{block, text},
@@ -196,6 +198,13 @@ impl AddSeriesPanel {
         })
     }
 
+    /// Initialize the panel to add the series with the specified `id`, such as the sequel of a completed series.
+    pub fn init_with_id(state: &UIState, shared_state: &SharedState, id: SeriesID) -> Result<Self> {
+        let panel = Self::init(state, shared_state, Mode::AddSeries)?;
+        panel.state.lock().inputs.id = IDInput::with_id(InputFlags::empty(), id);
+        Ok(panel)
+    }
+
     fn spawn_update_monitor(
         panel_state: &ArcMutex<SharedPanelState>,
         state: &SharedState,
@@ -272,11 +281,14 @@ impl AddSeriesPanel {
         let (header_text, has_error) =
             match (&panel_state.error, &panel_state.series_builder.params) {
                 (Some(err), Some(_)) | (Some(err), None) => {
-                    (text::bold_with(err.as_ref(), |s| s.fg(Color::Red)), true)
+                    (text::bold_with(err.as_ref(), |s| Tone::Bad.apply(s)), true)
+                }
+                (None, Some(_)) => (text::bold(Text::Detected.get()), false),
+                (None, None) if !panel_state.series_builder.suggestions.is_empty() => {
+                    (text::bold(Text::SuggestedPatterns.get()), false)
                 }
-                (None, Some(_)) => (text::bold("Detected"), false),
                 (None, None) => (
-                    text::bold_with("Nothing Detected", |s| s.fg(Color::Red)),
+                    text::bold_with(Text::NothingDetected.get(), |s| Tone::Bad.apply(s)),
                     false,
                 ),
             };
@@ -305,31 +317,62 @@ impl AddSeriesPanel {
             return;
         }
 
-        let built = try_opt_ret!(&panel_state.series_builder.params);
+        let built = if let Some(built) = &panel_state.series_builder.params {
+            built
+        } else {
+            Self::draw_suggestions(
+                &panel_state.series_builder.suggestions,
+                vert_layout[2],
+                frame,
+            );
+            return;
+        };
 
         info_label!(
-            "Relative Path",
+            Text::RelativePath.get(),
             text::italic(format!("{}", built.params.path.display())),
             fields.left
         );
 
         let episodes_text = match &built.episodes {
             ParsedEpisodes::Parsed(_, range_str) => text::italic(range_str),
-            ParsedEpisodes::NoneFound => text::italic_with("none", |s| s.fg(Color::Yellow)),
+            ParsedEpisodes::NoneFound => {
+                text::italic_with(Text::NoneFound.get(), |s| Tone::Warning.apply(s))
+            }
             ParsedEpisodes::NeedsSplitting => {
-                text::italic_with("needs splitting", |s| s.fg(Color::Yellow))
+                text::italic_with(Text::NeedsSplitting.get(), |s| Tone::Warning.apply(s))
             }
         };
 
-        info_label!("Found Episodes", episodes_text, fields.right);
+        info_label!(Text::FoundEpisodes.get(), episodes_text, fields.right);
+    }
+
+    fn draw_suggestions<B>(suggestions: &[PatternSuggestion], rect: Rect, frame: &mut Frame<B>)
+    where
+        B: Backend,
+    {
+        let mut fragments = Vec::with_capacity(suggestions.len() * 4);
+
+        for (i, suggestion) in suggestions.iter().enumerate() {
+            if i > 0 {
+                fragments.push(Fragment::Line);
+            }
+
+            fragments.push(Fragment::span(text::bold(suggestion.pattern.as_str())));
+            fragments.push(Fragment::span(" -> "));
+            fragments.push(Fragment::span(text::italic(suggestion.episodes.as_str())));
+        }
+
+        let widget = TextFragments::new(&fragments).alignment(Alignment::Center);
+        frame.render_widget(widget, rect);
     }
 
     pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
         let panel_state = self.state.lock();
 
         let title = match panel_state.mode {
-            Mode::AddSeries => "Add Series",
-            Mode::UpdateSeries => "Update Selected Series",
+            Mode::AddSeries => Text::AddSeriesPanel.get(),
+            Mode::UpdateSeries => Text::UpdateSeriesPanel.get(),
         };
 
         let block = block::with_borders(title);
@@ -408,10 +451,72 @@ impl Component for AddSeriesPanel {
 pub enum AddSeriesResult {
     Ok,
     Reset,
-    AddSeries(Box<PartialSeries>),
+    Lookup(Box<SeriesLookup>),
     UpdateSeries(Box<UpdateParams>),
 }
 
+/// A series that needs to be found on the remote service before it can be added.
+pub struct SeriesLookup {
+    selector: InfoSelector,
+    params: SeriesParams,
+    episodes: SortedEpisodes,
+}
+
+impl SeriesLookup {
+    #[inline(always)]
+    pub fn name(&self) -> &str {
+        &self.params.name
+    }
+
+    /// Find the series on `remote`, or in previous search results if it's offline.
+    ///
+    /// This can take a while, so it should be done without holding the state lock.
+    pub fn run(self, config: &Config, remote: &Remote) -> Result<PartialSeries> {
+        let info = SeriesInfo::from_remote(self.selector, config, remote)?;
+
+        let split_sequel = match &info {
+            InfoResult::Confident(info) if !remote.is_offline() => {
+                Self::find_split_sequel(info, &self.episodes, remote)
+            }
+            _ => None,
+        };
+
+        let mut series = PartialSeries::new(info, self.params, self.episodes);
+        series.cached = remote.is_offline();
+        series.split_sequel = split_sequel;
+
+        Ok(series)
+    }
+
+    /// Find the direct sequel of `info` when `episodes` hold both of them numbered as one season, like a split-cour
+    /// series that was released in two parts.
+    ///
+    /// The sequel is only returned when every episode of both parts is on disk, so there's no guessing where the first ends.
+    fn find_split_sequel(
+        info: &SeriesInfo,
+        episodes: &SortedEpisodes,
+        remote: &Remote,
+    ) -> Option<SeriesInfo> {
+        let total = info.total_episodes()? as u32;
+        let found = episodes.len() as u32;
+
+        if found <= total || episodes.highest_episode_number() != found {
+            return None;
+        }
+
+        let sequel_id = remote
+            .search_info_by_id(info.id as SeriesID)
+            .ok()?
+            .direct_sequel()?
+            .id;
+
+        let sequel: SeriesInfo = remote.search_info_by_id(sequel_id).ok()?.into();
+        let sequel_total = sequel.total_episodes()? as u32;
+
+        (total + sequel_total == found).then_some(sequel)
+    }
+}
+
 #[derive(Copy, Clone)]
 pub enum Mode {
     AddSeries,
@@ -420,11 +525,16 @@ pub enum Mode {
 
 struct SeriesBuilder {
     params: Option<BuiltSeriesParams>,
+    /// Patterns that can parse the episodes of the series when the selected parser can't.
+    suggestions: Vec<PatternSuggestion>,
 }
 
 impl SeriesBuilder {
     fn new() -> Self {
-        Self { params: None }
+        Self {
+            params: None,
+            suggestions: Vec::new(),
+        }
     }
 
     fn path<'a>(inputs: &'a PanelInputs, state: &UIState) -> Result<Cow<'a, SeriesPath>> {
@@ -437,14 +547,43 @@ impl SeriesBuilder {
 
     fn update(&mut self, inputs: &PanelInputs, state: &UIState) -> Result<()> {
         match self.update_internal(inputs, state) {
-            ok @ Ok(_) => ok,
-            err @ Err(_) => {
+            ok @ Ok(_) => {
+                self.suggestions.clear();
+                ok
+            }
+            Err(err) => {
                 self.params = None;
-                err
+                self.suggestions = Self::suggest_patterns(&err, inputs, state);
+                Err(err)
             }
         }
     }
 
+    fn suggest_patterns(
+        err: &anyhow::Error,
+        inputs: &PanelInputs,
+        state: &UIState,
+    ) -> Vec<PatternSuggestion> {
+        use anime::Error as AnimeError;
+        use series::EpisodeScanError;
+
+        let parse_failed = matches!(
+            err.downcast_ref(),
+            Some(EpisodeScanError::EpisodeParseFailed {
+                source: AnimeError::EpisodeParseFailed { .. } | AnimeError::MultipleTitles { .. },
+                ..
+            })
+        );
+
+        if !parse_failed {
+            return Vec::new();
+        }
+
+        Self::path(inputs, state)
+            .map(|path| PatternSuggestion::find(&path, &state.config))
+            .unwrap_or_default()
+    }
+
     fn update_internal(&mut self, inputs: &PanelInputs, state: &UIState) -> Result<()> {
         let path = Self::path(inputs, state)?;
 
@@ -484,21 +623,19 @@ impl SeriesBuilder {
 
         match mode {
             Mode::AddSeries => {
-                let remote = state.remote.get_logged_in()?;
-
-                let info = {
-                    let id = inputs.id.parsed_value();
-                    let sel = id.map_or_else(
-                        || InfoSelector::from_path_or_name(&params.path, &params.name),
-                        InfoSelector::ID,
-                    );
-
-                    SeriesInfo::from_remote(sel, remote)?
+                let id = inputs.id.parsed_value();
+                let selector = id.map_or_else(
+                    || InfoSelector::from_path_or_name(&params.path, &params.name),
+                    InfoSelector::ID,
+                );
+
+                let lookup = SeriesLookup {
+                    selector,
+                    params,
+                    episodes,
                 };
 
-                let partial = PartialSeries::new(info, params, episodes);
-
-                Ok(AddSeriesResult::AddSeries(partial.into()))
+                Ok(AddSeriesResult::Lookup(lookup.into()))
             }
             Mode::UpdateSeries => {
                 let params = UpdateParams {
@@ -522,7 +659,7 @@ enum ParsedEpisodes {
 
 impl ParsedEpisodes {
     fn parse(path: &SeriesPath, config: &Config, parser: &EpisodeParser) -> Result<Self> {
-        let episodes = CategorizedEpisodes::parse(path.absolute(config), parser)?;
+        let episodes = path.source(config).episodes(parser)?;
 
         if episodes.is_empty() {
             return Ok(Self::NoneFound);
@@ -595,6 +732,49 @@ impl ParsedEpisodes {
     }
 }
 
+/// A custom pattern that finds the episodes of a series, along with the episodes it finds.
+struct PatternSuggestion {
+    pattern: String,
+    episodes: String,
+}
+
+impl PatternSuggestion {
+    /// The most suggestions that can fit in the detected series panel.
+    const MAX: usize = 2;
+
+    fn find(path: &SeriesPath, config: &Config) -> Vec<Self> {
+        let filenames = match path.source(config).filenames() {
+            Ok(filenames) => filenames,
+            Err(_) => return Vec::new(),
+        };
+
+        CustomPattern::suggest(&filenames)
+            .into_iter()
+            .filter_map(|pattern| Self::preview(&pattern, &filenames))
+            .take(Self::MAX)
+            .collect()
+    }
+
+    fn preview(pattern: &CustomPattern, filenames: &[String]) -> Option<Self> {
+        let episodes = filenames
+            .iter()
+            .map(|filename| {
+                pattern
+                    .detect_episode(filename)
+                    .map(|number| Episode::new(number, filename.clone()))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let episodes = SortedEpisodes::with_episodes(episodes);
+        let episodes = ParsedEpisodes::episode_range_str(&episodes)?;
+
+        Some(Self {
+            pattern: pattern.inner().clone(),
+            episodes,
+        })
+    }
+}
+
 struct BuiltSeriesParams {
     params: SeriesParams,
     episodes: ParsedEpisodes,
@@ -610,7 +790,6 @@ impl BuiltSeriesParams {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use anime::local::{Episode, SortedEpisodes};
     use std::ops::RangeInclusive;
 
     fn insert_range(list: &mut Vec<Episode>, range: RangeInclusive<u32>) {
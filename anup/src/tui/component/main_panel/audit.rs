@@ -0,0 +1,272 @@
+use super::ShouldReset;
+use crate::audit_log::{AuditRecord, EntryField, SentEntry};
+use crate::config::DatesConfig;
+use crate::tui::locale::Text;
+use crate::tui::state::UIState;
+use crate::tui::theme::Tone;
+use crate::{key::Key, tui::component::Component};
+use anime::remote::{Remote, ScoreParser};
+use anyhow::{Context, Result};
+use crossterm::event::KeyCode;
+use std::collections::HashMap;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::{block, style, text},
+    layout::{BasicConstraint, RectExt, SimpleLayout},
+    list::WrappingIndex,
+    widgets::{SimpleTable, SimpleText},
+};
+
+/// A panel that shows every list entry that was sent to or removed from the remote service, newest first, along with
+/// what changed since the last time it was sent.
+pub struct AuditPanel {
+    rows: Vec<AuditRow>,
+    /// The ID of the series that was selected when the panel was opened.
+    selected_series: Option<i32>,
+    only_selected: bool,
+    /// Indices of the rows that are shown.
+    visible: Vec<usize>,
+    selected: WrappingIndex,
+}
+
+impl AuditPanel {
+    pub fn init(state: &UIState) -> Result<Self> {
+        let records = AuditRecord::load_all(&state.db).context("loading the audit log")?;
+        let remote = state.remote.get_logged_in().ok();
+
+        let nicknames = state
+            .series
+            .iter()
+            .map(|series| (series.config().id, series.nickname()))
+            .collect::<HashMap<_, _>>();
+
+        let rows = records
+            .iter()
+            .enumerate()
+            .map(|(i, record)| {
+                let series = match nicknames.get(&record.series_id) {
+                    Some(nickname) => (*nickname).to_string(),
+                    None => format!("#{}", record.series_id),
+                };
+
+                let changes = match &record.sent {
+                    Some(sent) => {
+                        let previous = AuditRecord::previously_sent(&records, i);
+                        describe_changes(sent, previous, remote, &state.config.dates)
+                    }
+                    None => Text::RemovedFromList.get().to_string(),
+                };
+
+                AuditRow {
+                    series_id: record.series_id,
+                    sent_at: state.config.dates.format_timestamp(record.sent_at),
+                    series,
+                    service: record.service.clone(),
+                    changes,
+                    removed: record.sent.is_none(),
+                }
+            })
+            .collect();
+
+        let mut panel = Self {
+            rows,
+            selected_series: state.series.selected().map(|series| series.config().id),
+            only_selected: false,
+            visible: Vec::new(),
+            selected: WrappingIndex::new(0),
+        };
+
+        panel.update_visible();
+        Ok(panel)
+    }
+
+    fn update_visible(&mut self) {
+        let only = if self.only_selected {
+            self.selected_series
+        } else {
+            None
+        };
+
+        self.visible = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| only.map_or(true, |id| row.series_id == id))
+            .map(|(i, _)| i)
+            .collect();
+
+        self.selected = WrappingIndex::new(0);
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = block::with_borders(Text::AuditLogPanel.get());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let vert_split = SimpleLayout::new(Direction::Vertical).split(
+            block_area,
+            [
+                // Records
+                BasicConstraint::MinLenRemaining(1, 1),
+                // Hint text
+                BasicConstraint::Length(1),
+            ],
+        );
+
+        let hint = if self.only_selected {
+            Text::AllSeriesHint
+        } else {
+            Text::SelectedSeriesOnlyHint
+        };
+
+        let hint_widget = SimpleText::new(text::hint(hint.get())).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, vert_split[1]);
+
+        if self.visible.is_empty() {
+            let text = text::hint(Text::NoAuditRecords.get());
+            let widget = SimpleText::new(text).alignment(Alignment::Center);
+            frame.render_widget(widget, vert_split[0].lines_from_top(1));
+            return;
+        }
+
+        let rows = self.visible.iter().map(|&i| {
+            let row = &self.rows[i];
+
+            let changes = if row.removed {
+                Span::styled(row.changes.as_str(), Tone::Bad.style())
+            } else {
+                Span::raw(row.changes.as_str())
+            };
+
+            [
+                Span::raw(row.sent_at.as_str()),
+                Span::raw(row.series.as_str()),
+                Span::raw(row.service.as_str()),
+                changes,
+            ]
+        });
+
+        let header = [
+            text::bold(Text::Sent.get()),
+            text::bold(Text::AuditSeries.get()),
+            text::bold(Text::Service.get()),
+            text::bold(Text::Changes.get()),
+        ];
+
+        let layout = [
+            BasicConstraint::Percentage(18),
+            BasicConstraint::Percentage(17),
+            BasicConstraint::Percentage(10),
+            BasicConstraint::Percentage(55),
+        ];
+
+        let table = SimpleTable::new(rows, layout)
+            .header(&header)
+            .highlight_symbol(Span::styled(">", style::list_selector(true)))
+            .select(Some(self.selected.get() as u16));
+
+        frame.render_widget(table, vert_split[0]);
+    }
+}
+
+impl Component for AuditPanel {
+    type State = UIState;
+    type KeyResult = ShouldReset;
+
+    fn process_key(&mut self, key: Key, _: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => return ShouldReset::Yes,
+            KeyCode::Up => self.selected.decrement(self.visible.len()),
+            KeyCode::Down => self.selected.increment(self.visible.len()),
+            KeyCode::Tab => {
+                self.only_selected = !self.only_selected;
+                self.update_visible();
+            }
+            _ => (),
+        }
+
+        ShouldReset::No
+    }
+}
+
+struct AuditRow {
+    series_id: i32,
+    sent_at: String,
+    series: String,
+    service: String,
+    changes: String,
+    removed: bool,
+}
+
+/// Describe each field of `sent` that changed since `previous` was sent, such as `Progress 4 -> 5`.
+///
+/// Fields are listed with only the value that was sent if nothing was sent before.
+fn describe_changes(
+    sent: &SentEntry,
+    previous: Option<&SentEntry>,
+    remote: Option<&Remote>,
+    dates: &DatesConfig,
+) -> String {
+    let fields = sent.changed_fields(previous);
+
+    if fields.is_empty() {
+        return Text::NoChanges.get().to_string();
+    }
+
+    fields
+        .into_iter()
+        .map(|field| {
+            let value = field_value(sent, field, remote, dates);
+
+            match previous {
+                Some(previous) => format!(
+                    "{} {} -> {}",
+                    field_name(field),
+                    field_value(previous, field, remote, dates),
+                    value
+                ),
+                None => format!("{} {}", field_name(field), value),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn field_name(field: EntryField) -> &'static str {
+    let text = match field {
+        EntryField::Progress => Text::Progress,
+        EntryField::Score => Text::Score,
+        EntryField::Status => Text::Status,
+        EntryField::Rewatched => Text::Rewatched,
+        EntryField::Priority => Text::Priority,
+        EntryField::StartDate => Text::StartDate,
+        EntryField::FinishDate => Text::FinishDate,
+    };
+
+    text.get()
+}
+
+fn field_value(
+    entry: &SentEntry,
+    field: EntryField,
+    remote: Option<&Remote>,
+    dates: &DatesConfig,
+) -> String {
+    match field {
+        EntryField::Progress => entry.watched_episodes.to_string(),
+        EntryField::Score => match (entry.score, remote) {
+            (Some(score), Some(remote)) => remote.score_to_str(score as u8).into_owned(),
+            (Some(score), None) => score.to_string(),
+            (None, _) => "-".into(),
+        },
+        EntryField::Status => Text::from(entry.status).get().to_string(),
+        EntryField::Rewatched => entry.times_rewatched.to_string(),
+        EntryField::Priority => entry.priority.to_string(),
+        EntryField::StartDate => dates.format_date(entry.start_date).into_owned(),
+        EntryField::FinishDate => dates.format_date(entry.end_date).into_owned(),
+    }
+}
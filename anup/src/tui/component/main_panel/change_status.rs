@@ -0,0 +1,176 @@
+use super::delete_series::{self, RemoveFiles};
+use super::ShouldReset;
+use crate::series::Series;
+use crate::tui::locale::Text;
+use crate::tui::state::UIState;
+use crate::tui::theme::Tone;
+use crate::{key::Key, tui::component::Component};
+use anime::remote::Status;
+use anyhow::{anyhow, Context, Result};
+use crossterm::event::KeyCode;
+use std::fs;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui_utils::{
+    helpers::{block, text},
+    layout::{RectExt, SimpleLayout},
+    widgets::{OverflowMode, SimpleText},
+};
+
+/// A panel to confirm setting the selected series to a status that ends watching it, such as dropped or on hold.
+pub struct ChangeStatusPanel {
+    status: Status,
+    remove_files: RemoveFiles,
+    /// Whether the user has been told that the files of the series are still seeding.
+    seeding_warned: bool,
+    title: String,
+    status_text: String,
+}
+
+impl ChangeStatusPanel {
+    pub fn init(status: Status, state: &UIState) -> Result<Self> {
+        let series = match state.series.selected() {
+            Some(series) => series,
+            None => return Err(anyhow!("must select a series to change the status of")),
+        };
+
+        if series.info().is_none() {
+            return Err(anyhow!("{} is not loaded", series.nickname()));
+        }
+
+        let title = match status {
+            Status::Dropped => Text::DropSeriesPanel.get(),
+            Status::OnHold => Text::HoldSeriesPanel.get(),
+            _ => Text::ChangeStatusPanel.get(),
+        };
+
+        let status_text =
+            Text::StatusWillBeSet.format(&[&series.nickname(), &Text::from(status).get()]);
+
+        Ok(Self {
+            status,
+            remove_files: RemoveFiles::default(),
+            seeding_warned: false,
+            title: title.into(),
+            status_text,
+        })
+    }
+
+    fn apply(&self, state: &mut UIState) -> Result<()> {
+        let series = state
+            .series
+            .get_valid_sel_series_mut()
+            .ok_or_else(|| anyhow!("no valid series selected"))?;
+
+        if let RemoveFiles::Yes = self.remove_files {
+            if series.data.config.path.url().is_some() {
+                return Err(anyhow!("episodes streamed from a URL can't be removed"));
+            }
+        }
+
+        let remote = state.remote.get_logged_in()?;
+
+        series.data.entry.set_status(self.status, &state.config);
+        series.data.sync_to_remote(remote, &state.db)?;
+        series.save(&state.db)?;
+
+        if let RemoveFiles::Yes = self.remove_files {
+            let path = series.data.config.path.absolute(&state.config);
+            fs::remove_dir_all(path).context("failed to remove directory")?;
+
+            // The series has no episodes left, so it needs to be reloaded to reflect that
+            let reloaded =
+                Series::load_from_config(series.data.config.clone(), &state.config, &state.db);
+
+            if let Some(selected) = state.series.selected_mut() {
+                *selected = reloaded;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn files_seeding(state: &UIState) -> Result<bool> {
+        let series = state
+            .series
+            .selected()
+            .ok_or_else(|| anyhow!("no series selected"))?;
+
+        let path = series.path().absolute(&state.config);
+        delete_series::is_seeding(&path, state)
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = block::with_borders(self.title.as_str());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let vert_fields = SimpleLayout::new(Direction::Vertical)
+            .horizontal_margin(2)
+            .vertical_margin(1)
+            .split_quarters(block_area);
+
+        let status_text = text::bold(&self.status_text);
+        let status_widget = SimpleText::new(status_text)
+            .alignment(Alignment::Center)
+            .overflow(OverflowMode::Truncate);
+
+        frame.render_widget(status_widget, vert_fields.first);
+
+        let files_text = match self.remove_files {
+            RemoveFiles::Yes if self.seeding_warned => {
+                text::bold_with(Text::FilesStillSeeding.get(), |s| Tone::Bad.apply(s))
+            }
+            RemoveFiles::Yes => {
+                text::bold_with(Text::FilesWillBeDeleted.get(), |s| Tone::Bad.apply(s))
+            }
+            RemoveFiles::No => text::bold(Text::FilesWillBeKept.get()),
+        };
+
+        let files_widget = SimpleText::new(files_text)
+            .alignment(Alignment::Center)
+            .overflow(OverflowMode::Truncate);
+
+        frame.render_widget(files_widget, vert_fields.second);
+
+        let horiz_layout = SimpleLayout::new(Direction::Horizontal)
+            .split_evenly(vert_fields.fourth.lines_from_bottom(1));
+
+        let hint_text = text::hint(Text::ToggleFileDeletionHint.get());
+        let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.left);
+
+        let hint_text = text::hint(Text::ConfirmHint.get());
+        let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.right);
+    }
+}
+
+impl Component for ChangeStatusPanel {
+    type State = UIState;
+    type KeyResult = Result<ShouldReset>;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => Ok(ShouldReset::Yes),
+            KeyCode::Char('d') => {
+                self.remove_files.toggle();
+                Ok(ShouldReset::No)
+            }
+            KeyCode::Enter => {
+                if let RemoveFiles::Yes = self.remove_files {
+                    if !self.seeding_warned && Self::files_seeding(state)? {
+                        self.seeding_warned = true;
+                        return Ok(ShouldReset::No);
+                    }
+                }
+
+                self.apply(state)?;
+                Ok(ShouldReset::Yes)
+            }
+            _ => Ok(ShouldReset::No),
+        }
+    }
+}
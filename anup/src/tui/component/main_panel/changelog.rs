@@ -0,0 +1,104 @@
+use super::ShouldReset;
+use crate::tui::locale::Text;
+use crate::tui::state::UIState;
+use crate::{key::Key, tui::component::Component};
+use anyhow::{anyhow, Result};
+use crossterm::event::KeyCode;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::{block, text},
+    layout::{BasicConstraint, SimpleLayout},
+    widgets::{SimpleList, SimpleText},
+};
+
+/// A panel that shows the notes of a newer release of the program, and lets it be dismissed.
+pub struct ChangelogPanel {
+    title: String,
+    lines: Vec<String>,
+    /// The index of the first line shown.
+    scroll: usize,
+}
+
+impl ChangelogPanel {
+    pub fn init(state: &UIState) -> Result<Self> {
+        let release = state
+            .update
+            .as_ref()
+            .ok_or_else(|| anyhow!("no newer version was found"))?;
+
+        let mut lines = release
+            .notes
+            .lines()
+            .map(|line| line.trim_end().to_string())
+            .collect::<Vec<_>>();
+
+        if lines.iter().all(String::is_empty) {
+            lines = vec![Text::NoReleaseNotes.get().into()];
+        }
+
+        lines.push(String::new());
+        lines.push(release.url.clone());
+
+        Ok(Self {
+            title: Text::ChangelogPanel.format(&[&release.version, &env!("CARGO_PKG_VERSION")]),
+            lines,
+            scroll: 0,
+        })
+    }
+
+    pub fn draw<B: Backend>(&self, rect: Rect, frame: &mut Frame<B>) {
+        let block = block::with_borders(self.title.as_str());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let vert_split = SimpleLayout::new(Direction::Vertical).split(
+            block_area,
+            [
+                // Release notes
+                BasicConstraint::MinLenRemaining(1, 1),
+                // Hint text
+                BasicConstraint::Length(1),
+            ],
+        );
+
+        let lines = self
+            .lines
+            .iter()
+            .skip(self.scroll)
+            .map(|line| Span::raw(line.as_str()));
+
+        frame.render_widget(SimpleList::new(lines).select(None), vert_split[0]);
+
+        let hint_widget =
+            SimpleText::new(text::hint(Text::DismissUpdateHint.get())).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, vert_split[1]);
+    }
+}
+
+impl Component for ChangelogPanel {
+    type State = UIState;
+    type KeyResult = Result<ShouldReset>;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => return Ok(ShouldReset::Yes),
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down => self.scroll = (self.scroll + 1).min(self.lines.len() - 1),
+            KeyCode::Char('x') => {
+                if let Some(release) = state.update.take() {
+                    state.log.set_update(None);
+                    release.dismiss()?;
+                }
+
+                return Ok(ShouldReset::Yes);
+            }
+            _ => (),
+        }
+
+        Ok(ShouldReset::No)
+    }
+}
@@ -1,11 +1,14 @@
 use super::ShouldReset;
+use crate::seeding::SeedingTorrents;
+use crate::tui::locale::Text;
 use crate::tui::state::UIState;
+use crate::tui::theme::Tone;
 use crate::{key::Key, tui::component::Component};
 use anyhow::{anyhow, Context, Result};
 use crossterm::event::KeyCode;
-use std::{array::IntoIter, fs};
+use std::array::IntoIter;
+use std::path::Path;
 use tui::layout::{Alignment, Direction, Rect};
-use tui::style::Color;
 use tui::terminal::Frame;
 use tui::{backend::Backend, text::Span};
 use tui_utils::{
@@ -17,6 +20,8 @@ use tui_utils::{
 
 pub struct DeleteSeriesPanel {
     remove_files: RemoveFiles,
+    /// Whether the user has been told that the files of the series are still seeding.
+    seeding_warned: bool,
     removal_warning_text: String,
     series_path_text: String,
 }
@@ -28,25 +33,25 @@ impl DeleteSeriesPanel {
             None => return Err(anyhow!("must select a series to delete")),
         };
 
-        let removal_warning_text = format!("{} will be removed", series.nickname());
+        let removal_warning_text = format!(
+            "{} will be removed (undo with :undelete {} within {} days)",
+            series.nickname(),
+            series.nickname(),
+            state.config.trash.retention_days
+        );
         let series_path_text = series.path().inner().to_string_lossy().into_owned();
 
         Ok(Self {
             remove_files: RemoveFiles::default(),
+            seeding_warned: false,
             removal_warning_text,
             series_path_text,
         })
     }
 
     fn delete_selected_series(&self, state: &mut UIState) -> Result<()> {
-        let series = state.delete_selected_series()?;
-
-        if let RemoveFiles::Yes = self.remove_files {
-            let path = series.config().path.absolute(&state.config);
-            fs::remove_dir_all(path).context("failed to remove directory")?;
-        }
-
-        Ok(())
+        let remove_files = matches!(self.remove_files, RemoveFiles::Yes);
+        state.delete_selected_series(remove_files)
     }
 
     fn draw_remove_files_warning<B: Backend>(
@@ -56,7 +61,7 @@ impl DeleteSeriesPanel {
         frame: &mut Frame<B>,
     ) {
         let path_fragments = [
-            Fragment::span(text::bold("Series Path:")),
+            Fragment::span(text::bold(Text::SeriesPath.get())),
             Fragment::Line,
             Fragment::Span(
                 text::italic(&self.series_path_text),
@@ -70,13 +75,18 @@ impl DeleteSeriesPanel {
         frame.render_widget(path_widget, path_rect);
 
         let delete_status_text = match self.remove_files {
-            RemoveFiles::Yes => text::bold_with("will be deleted.", |s| s.fg(Color::Red)),
-            RemoveFiles::No => text::bold("will not be deleted."),
+            RemoveFiles::Yes if self.seeding_warned => {
+                text::bold_with(Text::PathStillSeeding.get(), |s| Tone::Bad.apply(s))
+            }
+            RemoveFiles::Yes => {
+                text::bold_with(Text::PathWillBeTrashed.get(), |s| Tone::Bad.apply(s))
+            }
+            RemoveFiles::No => text::bold(Text::PathWillBeKept.get()),
         };
 
         let full_status_frags = {
             let frags = [
-                Fragment::span(Span::raw("The series path on disk ")),
+                Fragment::span(Span::raw(Text::PathOnDisk.get())),
                 Fragment::Span(
                     delete_status_text,
                     SpanOptions::new().overflow(OverflowMode::Truncate),
@@ -94,17 +104,17 @@ impl DeleteSeriesPanel {
         let horiz_layout =
             SimpleLayout::new(Direction::Horizontal).split_evenly(rect.lines_from_bottom(1));
 
-        let hint_text = text::hint("D - Toggle path deletion");
+        let hint_text = text::hint(Text::TogglePathDeletionHint.get());
         let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
         frame.render_widget(hint_widget, horiz_layout.left);
 
-        let hint_text = text::hint("Enter - Confirm");
+        let hint_text = text::hint(Text::ConfirmHint.get());
         let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
         frame.render_widget(hint_widget, horiz_layout.right);
     }
 
     pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
-        let block = block::with_borders("Delete Series");
+        let block = block::with_borders(Text::DeleteSeriesPanel.get());
         let block_area = block.inner(rect);
 
         frame.render_widget(block, rect);
@@ -114,7 +124,7 @@ impl DeleteSeriesPanel {
             .vertical_margin(1)
             .split_quarters(block_area);
 
-        let warning_text = text::bold_with(&self.removal_warning_text, |s| s.fg(Color::Red));
+        let warning_text = text::bold_with(&self.removal_warning_text, |s| Tone::Bad.apply(s));
         let warning_widget = SimpleText::new(warning_text)
             .alignment(Alignment::Center)
             .overflow(OverflowMode::Truncate);
@@ -143,6 +153,22 @@ impl Component for DeleteSeriesPanel {
                 Ok(ShouldReset::No)
             }
             KeyCode::Enter => {
+                if let RemoveFiles::Yes = self.remove_files {
+                    if !self.seeding_warned {
+                        let series = state
+                            .series
+                            .selected()
+                            .ok_or_else(|| anyhow!("must select a series to delete"))?;
+
+                        let path = series.path().absolute(&state.config);
+
+                        if is_seeding(&path, state)? {
+                            self.seeding_warned = true;
+                            return Ok(ShouldReset::No);
+                        }
+                    }
+                }
+
                 self.delete_selected_series(state)?;
                 Ok(ShouldReset::Yes)
             }
@@ -151,8 +177,16 @@ impl Component for DeleteSeriesPanel {
     }
 }
 
+/// Returns true if the files at `path` are part of a torrent that the torrent client in the config is still seeding.
+pub(super) fn is_seeding(path: &Path, state: &UIState) -> Result<bool> {
+    let seeding =
+        SeedingTorrents::query(&state.config.torrent).context("checking for seeding torrents")?;
+
+    Ok(matches!(seeding, Some(seeding) if seeding.contains(path)))
+}
+
 #[derive(Copy, Clone)]
-enum RemoveFiles {
+pub(super) enum RemoveFiles {
     Yes,
     No,
 }
@@ -166,7 +200,7 @@ impl RemoveFiles {
     }
 
     #[inline(always)]
-    fn toggle(&mut self) {
+    pub(super) fn toggle(&mut self) {
         *self = self.next();
     }
 }
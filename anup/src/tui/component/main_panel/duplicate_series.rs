@@ -0,0 +1,144 @@
+use super::ShouldReset;
+use crate::series::config::SeriesConfig;
+use crate::series::{info::SeriesInfo, SeriesParams};
+use crate::tui::locale::Text;
+use crate::tui::state::UIState;
+use crate::tui::theme::Tone;
+use crate::{key::Key, tui::component::Component};
+use anime::local::SortedEpisodes;
+use anyhow::Result;
+use crossterm::event::KeyCode;
+use std::array::IntoIter;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui_utils::{
+    helpers::{block, text},
+    layout::{RectExt, SimpleLayout},
+    widgets::{Fragment, OverflowMode, SimpleText, SpanOptions, TextFragments},
+    wrap,
+};
+
+/// A panel shown when a series being added resolves to the same remote ID as a series that has already been added.
+pub struct DuplicateSeriesPanel {
+    info: SeriesInfo,
+    params: SeriesParams,
+    episodes: Option<SortedEpisodes>,
+    existing_nickname: String,
+    warning_text: String,
+}
+
+impl DuplicateSeriesPanel {
+    pub fn new(
+        info: SeriesInfo,
+        params: SeriesParams,
+        episodes: Option<SortedEpisodes>,
+        existing_nickname: String,
+    ) -> Self {
+        let warning_text =
+            Text::AlreadyAddedAs.format(&[&info.title_preferred, &existing_nickname]);
+
+        Self {
+            info,
+            params,
+            episodes,
+            existing_nickname,
+            warning_text,
+        }
+    }
+
+    fn open_existing(&self, state: &mut UIState) {
+        let index = state
+            .series
+            .iter()
+            .position(|series| series.nickname() == self.existing_nickname);
+
+        if let Some(index) = index {
+            state.series.set_selected(index);
+            state.init_selected_series();
+        }
+    }
+
+    /// Add the new series under its own nickname in place of the existing one.
+    ///
+    /// Both series would sync to the same list entry, so the existing one is replaced.
+    fn replace_existing(&mut self, state: &mut UIState) -> Result<()> {
+        let config = SeriesConfig::new_replacing(self.info.id, self.params.clone(), &state.db)?;
+
+        let id = self.info.id;
+        state
+            .series
+            .items_mut()
+            .retain(|series| series.config().id != id);
+
+        state.add_series(config, self.info.clone(), self.episodes.take())
+    }
+
+    fn draw_hints<B: Backend>(rect: Rect, frame: &mut Frame<B>) {
+        let horiz_layout =
+            SimpleLayout::new(Direction::Horizontal).split_evenly(rect.lines_from_bottom(1));
+
+        let hint_text = text::hint(Text::OpenExistingHint.get());
+        let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.left);
+
+        let hint_text = text::hint(Text::ReplaceHint.get());
+        let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.right);
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = block::with_borders(Text::DuplicateSeriesPanel.get());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let vert_fields = SimpleLayout::new(Direction::Vertical)
+            .horizontal_margin(2)
+            .vertical_margin(1)
+            .split_quarters(block_area);
+
+        let warning_text = text::bold_with(&self.warning_text, |s| Tone::Warning.apply(s));
+        let warning_widget = SimpleText::new(warning_text)
+            .alignment(Alignment::Center)
+            .overflow(OverflowMode::Truncate);
+
+        frame.render_widget(warning_widget, vert_fields.first);
+
+        let nickname_fragments = [
+            Fragment::span(text::bold(Text::ReplaceWith.get())),
+            Fragment::Line,
+            Fragment::Span(
+                text::italic(&self.params.name),
+                SpanOptions::new().overflow(OverflowMode::Truncate),
+            ),
+        ];
+
+        let wrapped = wrap::by_letters(IntoIter::new(nickname_fragments), vert_fields.second.width);
+        let nickname_widget = TextFragments::new(&wrapped).alignment(Alignment::Center);
+
+        frame.render_widget(nickname_widget, vert_fields.second);
+
+        Self::draw_hints(vert_fields.fourth, frame);
+    }
+}
+
+impl Component for DuplicateSeriesPanel {
+    type State = UIState;
+    type KeyResult = Result<ShouldReset>;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => Ok(ShouldReset::Yes),
+            KeyCode::Char('o') | KeyCode::Enter => {
+                self.open_existing(state);
+                Ok(ShouldReset::Yes)
+            }
+            KeyCode::Char('c') => {
+                self.replace_existing(state)?;
+                Ok(ShouldReset::Yes)
+            }
+            _ => Ok(ShouldReset::No),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use super::ShouldReset;
+use crate::config::DatesConfig;
+use crate::saved_event::{EventKind, SavedEvent};
+use crate::tui::locale::Text;
+use crate::tui::state::UIState;
+use crate::tui::theme::Tone;
+use crate::{key::Key, tui::component::Component};
+use anyhow::{Context, Result};
+use crossterm::event::KeyCode;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::style::{Modifier, Style};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::{block, style, text},
+    layout::{BasicConstraint, RectExt, SimpleLayout},
+    list::WrappingIndex,
+    widgets::{SimpleTable, SimpleText},
+};
+
+/// A panel that shows saved errors and notifications, and marks them as read.
+pub struct EventsPanel {
+    rows: Vec<EventRow>,
+    selected: WrappingIndex,
+}
+
+impl EventsPanel {
+    pub fn init(state: &mut UIState) -> Result<Self> {
+        let events = SavedEvent::load_all(&state.db).context("loading saved events")?;
+        let rows = events
+            .iter()
+            .map(|event| EventRow::new(event, &state.config.dates))
+            .collect();
+
+        // Events are marked as read after loading them so the ones that weren't read yet can still be highlighted
+        SavedEvent::mark_all_read(&state.db).context("marking events as read")?;
+        state.log.set_unread(0);
+
+        Ok(Self {
+            rows,
+            selected: WrappingIndex::new(0),
+        })
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = block::with_borders(Text::EventsPanel.get());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        if self.rows.is_empty() {
+            let text = text::hint(Text::NoEvents.get());
+            let widget = SimpleText::new(text).alignment(Alignment::Center);
+            frame.render_widget(widget, block_area.lines_from_top(1));
+            return;
+        }
+
+        let vert_split = SimpleLayout::new(Direction::Vertical).split(
+            block_area,
+            [
+                // Events
+                BasicConstraint::MinLenRemaining(1, 1),
+                // Hint text
+                BasicConstraint::Length(1),
+            ],
+        );
+
+        let rows = self.rows.iter().map(|row| {
+            let style = if row.unread {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+
+            let (kind, tone) = row.kind();
+
+            [
+                Span::styled(row.logged.as_str(), style),
+                Span::styled(kind, tone.style()),
+                Span::styled(row.message.as_str(), style),
+            ]
+        });
+
+        let header = [
+            text::bold(Text::Logged.get()),
+            text::bold(Text::EventKind.get()),
+            text::bold(Text::Message.get()),
+        ];
+
+        let layout = [
+            BasicConstraint::Percentage(20),
+            BasicConstraint::Percentage(15),
+            BasicConstraint::Percentage(65),
+        ];
+
+        let table = SimpleTable::new(rows, layout)
+            .header(&header)
+            .highlight_symbol(Span::styled(">", style::list_selector(true)))
+            .select(Some(self.selected.get() as u16));
+
+        frame.render_widget(table, vert_split[0]);
+
+        let hint_widget =
+            SimpleText::new(text::hint(Text::ClearEventsHint.get())).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, vert_split[1]);
+    }
+}
+
+impl Component for EventsPanel {
+    type State = UIState;
+    type KeyResult = Result<ShouldReset>;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => return Ok(ShouldReset::Yes),
+            KeyCode::Up => self.selected.decrement(self.rows.len()),
+            KeyCode::Down => self.selected.increment(self.rows.len()),
+            KeyCode::Char('x') => {
+                SavedEvent::delete_all(&state.db).context("clearing saved events")?;
+                self.rows.clear();
+                self.selected.update_bounds(0);
+            }
+            _ => (),
+        }
+
+        Ok(ShouldReset::No)
+    }
+}
+
+struct EventRow {
+    logged: String,
+    kind: EventKind,
+    message: String,
+    unread: bool,
+}
+
+impl EventRow {
+    fn new(event: &SavedEvent, dates: &DatesConfig) -> Self {
+        Self {
+            logged: dates.format_timestamp(event.logged_at),
+            kind: event.kind,
+            // Rows are a single line, so messages that span several lines are joined
+            message: event.message.replace('\n', " - "),
+            unread: !event.read,
+        }
+    }
+
+    fn kind(&self) -> (&'static str, Tone) {
+        match self.kind {
+            EventKind::Error => (Text::EventError.get(), Tone::Bad),
+            EventKind::Warning => (Text::EventWarning.get(), Tone::Warning),
+            EventKind::Notification => (Text::Notification.get(), Tone::Good),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use super::ShouldReset;
+use crate::config::DatesConfig;
+use crate::series::history::PlaybackSession;
+use crate::tui::locale::Text;
+use crate::tui::state::UIState;
+use crate::tui::theme::Tone;
+use crate::util;
+use crate::{key::Key, tui::component::Component};
+use anyhow::{anyhow, Context, Result};
+use crossterm::event::KeyCode;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Rect};
+use tui::style::Style;
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::{block, text},
+    layout::{BasicConstraint, RectExt},
+    widgets::{SimpleTable, SimpleText},
+};
+
+/// A panel that shows each time the player was started for the selected series, and how long it ran for.
+pub struct HistoryPanel {
+    title: String,
+    rows: Vec<SessionRow>,
+}
+
+impl HistoryPanel {
+    pub fn init(state: &UIState) -> Result<Self> {
+        let data = state
+            .series
+            .selected()
+            .and_then(|series| series.data())
+            .ok_or_else(|| anyhow!("no valid series selected"))?;
+
+        let info = &data.info;
+
+        let rows = PlaybackSession::load_recent(
+            &state.db,
+            info.id,
+            state.config.tui.limits.history_sessions.into(),
+        )
+        .context("loading playback sessions")?
+        .into_iter()
+        .map(|session| SessionRow::new(&session, info.episode_length_mins, &state.config.dates))
+        .collect();
+
+        Ok(Self {
+            title: Text::HistoryPanel.format(&[data.title()]),
+            rows,
+        })
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = block::with_borders(self.title.as_str());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        if self.rows.is_empty() {
+            let text = text::hint(Text::NoEpisodesPlayed.get());
+            let widget = SimpleText::new(text).alignment(Alignment::Center);
+            frame.render_widget(widget, block_area.lines_from_top(1));
+            return;
+        }
+
+        let rows = self.rows.iter().map(|row| {
+            let style = if row.short {
+                Tone::Warning.style()
+            } else {
+                Style::default()
+            };
+
+            [
+                Span::styled(row.episode.as_str(), style),
+                Span::styled(row.started.as_str(), style),
+                Span::styled(row.played.as_str(), style),
+                Span::styled(row.result.as_str(), style),
+            ]
+        });
+
+        let header = [
+            text::bold(Text::Episode.get()),
+            text::bold(Text::Started.get()),
+            text::bold(Text::Played.get()),
+            text::bold(Text::Result.get()),
+        ];
+
+        let layout = [
+            BasicConstraint::Percentage(15),
+            BasicConstraint::Percentage(30),
+            BasicConstraint::Percentage(15),
+            BasicConstraint::Percentage(40),
+        ];
+
+        let table = SimpleTable::new(rows, layout).header(&header);
+        frame.render_widget(table, block_area);
+    }
+}
+
+impl Component for HistoryPanel {
+    type State = UIState;
+    type KeyResult = Result<ShouldReset>;
+
+    fn process_key(&mut self, key: Key, _: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => Ok(ShouldReset::Yes),
+            _ => Ok(ShouldReset::No),
+        }
+    }
+}
+
+struct SessionRow {
+    episode: String,
+    started: String,
+    played: String,
+    result: String,
+    short: bool,
+}
+
+impl SessionRow {
+    fn new(session: &PlaybackSession, episode_length_mins: i16, dates: &DatesConfig) -> Self {
+        let short = session.is_suspiciously_short(episode_length_mins);
+
+        let result = match (session.counted, session.exit_code) {
+            (true, _) => Text::Counted.get().into(),
+            (false, _) if short => Text::NotCountedShort.get().into(),
+            (false, Some(0)) => Text::NotCounted.get().into(),
+            (false, Some(code)) => Text::NotCountedExitCode.format(&[&code]),
+            (false, None) => Text::NotCountedKilled.get().into(),
+        };
+
+        Self {
+            episode: session.episode.to_string(),
+            started: dates.format_timestamp(session.started_at),
+            played: util::hm_from_mins(session.seconds as f32 / 60.0),
+            result,
+            short,
+        }
+    }
+}
@@ -1,26 +1,23 @@
 use crate::remote::RemoteStatus;
+use crate::tui::locale::Text;
 use crate::tui::state::ProgressTime;
 use crate::tui::state::SharedState;
+use crate::tui::theme::{self, Tone};
 use crate::tui::{state::StateEvent, UIState};
 use crate::util;
 use crate::{
     series::{LoadedSeries, Series},
     tui::component::Component,
 };
-use anime::remote::{ScoreParser, SeriesDate};
+use anime::remote::ScoreParser;
 use chrono::Utc;
 use smallvec::{smallvec, SmallVec};
-use std::{
-    array::IntoIter,
-    borrow::Cow,
-    sync::{atomic::Ordering, Arc},
-    time::Duration,
-};
-use std::{fmt, sync::atomic::AtomicU32};
+use std::fmt;
+use std::{array::IntoIter, borrow::Cow, collections::HashMap, time::Duration};
 use tokio::task;
 use tui::backend::Backend;
 use tui::layout::{Alignment, Direction, Rect};
-use tui::style::Color;
+use tui::style::Style;
 use tui::terminal::Frame;
 use tui::text::Span;
 use tui_utils::{
@@ -32,27 +29,18 @@ use tui_utils::{
 use util::ScopedTask;
 
 pub struct InfoPanel {
-    progress_remaining_secs: Arc<AtomicU32>,
     #[allow(dead_code)]
     event_monitor_task: ScopedTask<()>,
 }
 
 impl InfoPanel {
     pub fn new(state: &SharedState) -> Self {
-        let progress_remaining_secs = Arc::new(AtomicU32::default());
-        let event_monitor_task =
-            Self::spawn_episode_event_monitor(state, Arc::clone(&progress_remaining_secs)).into();
+        let event_monitor_task = Self::spawn_episode_event_monitor(state).into();
 
-        Self {
-            progress_remaining_secs,
-            event_monitor_task,
-        }
+        Self { event_monitor_task }
     }
 
-    fn spawn_episode_event_monitor(
-        state: &SharedState,
-        progress_remaining_secs: Arc<AtomicU32>,
-    ) -> task::JoinHandle<()> {
+    fn spawn_episode_event_monitor(state: &SharedState) -> task::JoinHandle<()> {
         let state = state.clone();
 
         task::spawn(async move {
@@ -61,25 +49,19 @@ impl InfoPanel {
                 state.events.subscribe()
             };
 
-            #[allow(unused_variables)]
-            let mut progress_task: Option<ScopedTask<_>> = None;
+            // Each episode being played has its own task, so the time until progression is redrawn for every one of them
+            let mut progress_tasks: HashMap<i32, ScopedTask<()>> = HashMap::new();
 
             while let Ok(event) = events.recv().await {
-                #[allow(unused_assignments)]
                 match event {
-                    StateEvent::StartedEpisode(progress_time) => {
-                        let state = state.clone();
-                        let remaining_secs = Arc::clone(&progress_remaining_secs);
-
+                    StateEvent::StartedEpisode(id, progress_time) => {
                         let task =
-                            Self::spawn_progress_monitor_task(state, remaining_secs, progress_time)
-                                .into();
+                            Self::spawn_progress_monitor_task(state.clone(), progress_time).into();
 
-                        progress_task = Some(task);
+                        progress_tasks.insert(id, task);
                     }
-                    StateEvent::FinishedEpisode => {
-                        progress_remaining_secs.store(0, Ordering::SeqCst);
-                        progress_task = None;
+                    StateEvent::FinishedEpisode(id) => {
+                        progress_tasks.remove(&id);
 
                         let mut state = state.lock();
                         state.mark_dirty();
@@ -89,44 +71,37 @@ impl InfoPanel {
         })
     }
 
+    /// Redraw every minute until `progress_time` is reached.
     fn spawn_progress_monitor_task(
         state: SharedState,
-        remaining_secs: Arc<AtomicU32>,
         progress_time: ProgressTime,
     ) -> task::JoinHandle<()> {
         task::spawn(async move {
-            let mut first_iter = true;
-
             loop {
-                let cur_progress = remaining_secs.load(Ordering::SeqCst);
+                state.lock().mark_dirty();
 
-                if cur_progress == 0 && !first_iter {
-                    remaining_secs.store(0, Ordering::SeqCst);
-                    state.lock().mark_dirty();
+                if progress_time <= Utc::now() {
                     break;
                 }
 
-                let diff = progress_time - Utc::now();
-                let secs = diff.num_seconds();
-
-                if secs <= 0 {
-                    remaining_secs.store(0, Ordering::SeqCst);
-                    state.lock().mark_dirty();
-                    break;
-                }
-
-                remaining_secs.store(secs as u32, Ordering::SeqCst);
-                first_iter = false;
-
-                {
-                    state.lock().mark_dirty();
-                }
-
                 tokio::time::sleep(Duration::from_secs(60)).await;
             }
         })
     }
 
+    /// Returns the number of seconds until the episode being played of the selected series is counted as watched.
+    fn progress_remaining_secs(state: &UIState) -> Option<i64> {
+        let id = state.series.selected().and_then(LoadedSeries::id)?;
+
+        let watch = match state.watching.get(&id) {
+            Some(watch) if !watch.force_completed => watch,
+            Some(_) | None => return None,
+        };
+
+        let secs = (watch.progress_time - Utc::now()).num_seconds();
+        Some(secs).filter(|&secs| secs > 0)
+    }
+
     fn header_body_layout(rect: Rect) -> (Rect, Rect) {
         let layout = SimpleLayout::new(Direction::Vertical).margin(2).split(
             rect,
@@ -155,73 +130,55 @@ impl InfoPanel {
         frame.render_widget(body_widget, body_pos);
     }
 
+    /// Returns a fragment for each line of `text`, since translated text can have a different number of lines.
+    fn body_fragments(text: &str) -> Vec<Fragment> {
+        let mut fragments = Vec::new();
+
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                fragments.push(Fragment::Line);
+            }
+
+            if !line.is_empty() {
+                fragments.push(Fragment::Span(
+                    Span::raw(line),
+                    SpanOptions::new().overflow(OverflowMode::Truncate),
+                ));
+            }
+        }
+
+        fragments
+    }
+
     fn draw_no_users_info<B>(rect: Rect, frame: &mut Frame<B>)
     where
         B: Backend,
     {
-        let span = |text| {
+        let mut body = Self::body_fragments(Text::NoAccountsBody.get());
+
+        body.extend_from_slice(&[
+            Fragment::Line,
             Fragment::Span(
-                Span::raw(text),
+                Span::raw("https://github.com/Acizza/anup#adding-an-account"),
                 SpanOptions::new().overflow(OverflowMode::Truncate),
-            )
-        };
+            ),
+        ]);
 
-        let body = [
-            span("Add an account by pressing 'u' to open"),
-            Fragment::Line,
-            span("user management and then by pressing tab"),
-            Fragment::Line,
-            span("to switch to the add user panel."),
-            Fragment::Line,
-            Fragment::Line,
-            span("Then open the auth URL in your browser"),
-            Fragment::Line,
-            span("by pressing Ctrl + O, and follow its instructions."),
-            Fragment::Line,
-            Fragment::Line,
-            span("Once you have a token, paste it in with either"),
-            Fragment::Line,
-            span("Ctrl + Shift + V or Ctrl + V."),
-            Fragment::Line,
-            Fragment::Line,
-            span("More detailed instructions here:"),
-            Fragment::Line,
-            span("https://github.com/Acizza/anup#adding-an-account"),
-        ];
+        let header = text::bold(Text::NoAccountsHeader.get());
 
         let (h_pos, b_pos) = Self::header_body_layout(rect);
-        Self::draw_text_panel(text::bold("No Accounts Added"), &body, h_pos, b_pos, frame);
+        Self::draw_text_panel(header, &body, h_pos, b_pos, frame);
     }
 
     fn draw_no_series_found<B>(rect: Rect, frame: &mut Frame<B>)
     where
         B: Backend,
     {
-        let span = |text| {
-            Fragment::Span(
-                Span::raw(text),
-                SpanOptions::new().overflow(OverflowMode::Truncate),
-            )
-        };
-
-        let body = [
-            span("Add one by pressing the 'a' key."),
-            Fragment::Line,
-            Fragment::Line,
-            span("The opened panel will require you to specify"),
-            Fragment::Line,
-            span("a name for the series you want to add."),
-            Fragment::Line,
-            Fragment::Line,
-            span("For automatic detection, the name should be"),
-            Fragment::Line,
-            span("similar to the name of the folder the series"),
-            Fragment::Line,
-            span("is in on disk."),
-        ];
+        let body = Self::body_fragments(Text::NoSeriesBody.get());
+        let header = text::bold(Text::NoSeriesHeader.get());
 
         let (h_pos, b_pos) = Self::header_body_layout(rect);
-        Self::draw_text_panel(text::bold("No Series Found"), &body, h_pos, b_pos, frame);
+        Self::draw_text_panel(header, &body, h_pos, b_pos, frame);
     }
 
     fn draw_series_error<B, E>(err: E, rect: Rect, frame: &mut Frame<B>)
@@ -229,10 +186,10 @@ impl InfoPanel {
         B: Backend,
         E: fmt::Display,
     {
-        let header = text::bold_with("Error Loading Series", |s| s.fg(Color::Red));
+        let header = text::bold_with(Text::SeriesErrorHeader.get(), |s| Tone::Bad.apply(s));
 
         let body = [Fragment::Span(
-            text::with_color(err.to_string(), Color::Red),
+            Span::styled(err.to_string(), Tone::Bad.style()),
             SpanOptions::new().overflow(OverflowMode::Truncate),
         )];
 
@@ -243,13 +200,8 @@ impl InfoPanel {
     }
 
     #[allow(clippy::too_many_lines)]
-    fn draw_series_info<B>(
-        &self,
-        state: &UIState,
-        series: &Series,
-        rect: Rect,
-        frame: &mut Frame<B>,
-    ) where
+    fn draw_series_info<B>(state: &UIState, series: &Series, rect: Rect, frame: &mut Frame<B>)
+    where
         B: Backend,
     {
         let layout = SimpleLayout::new(Direction::Vertical).margin(2).split(
@@ -267,16 +219,46 @@ impl InfoPanel {
         // Series title
         {
             let mut fragments: SmallVec<[Fragment; 2]> = smallvec![Fragment::Span(
-                text::bold(&info.title_preferred),
+                text::bold(series.data.title()),
                 SpanOptions::new().overflow(OverflowMode::Truncate)
             )];
 
-            if entry.needs_sync() {
+            if series.data.config.local_only {
+                fragments.push(Fragment::span(text::italic(" [local]")));
+            } else if entry.needs_sync() {
                 fragments.push(Fragment::span(text::italic(" [*]")));
             }
 
+            if series.data.config.planned_rewatch {
+                fragments.push(Fragment::span(text::italic(" [rewatch planned]")));
+            }
+
+            if series.data.config.muted {
+                fragments.push(Fragment::span(text::italic(" [muted]")));
+            }
+
+            if entry.priority() > 0 {
+                fragments.push(Fragment::span(text::italic(format!(
+                    " [priority {}]",
+                    entry.priority()
+                ))));
+            }
+
+            let title_layout = SimpleLayout::new(Direction::Vertical).split(
+                layout[0],
+                [BasicConstraint::Length(1), BasicConstraint::Length(1)],
+            );
+
             let title_widget = TextFragments::new(&fragments).alignment(Alignment::Center);
-            frame.render_widget(title_widget, layout[0]);
+            frame.render_widget(title_widget, title_layout[0]);
+
+            let next = entry.watched_episodes().max(0) as u32 + 1;
+
+            if let Some(ep_title) = series.data.episode_titles.get(next) {
+                let text = Text::NextEpisodeTitle.format(&[&next, &ep_title]);
+                let next_widget = SimpleText::new(text::italic(text)).alignment(Alignment::Center);
+                frame.render_widget(next_widget, title_layout[1]);
+            }
         }
 
         // Items in panel
@@ -289,7 +271,7 @@ impl InfoPanel {
                     x: $x_column,
                     y: $y_column,
                     width: content.width / 3,
-                    height: content.height / 3,
+                    height: content.height / 4,
                 });
 
                 Self::draw_stat($header, $value, pos, frame);
@@ -298,51 +280,80 @@ impl InfoPanel {
 
         // Left panel items
 
-        draw_stat!(0, 0 => "Watch Time", {
-            let watch_time_mins = info.episodes * info.episode_length_mins;
-            util::hm_from_mins(f32::from(watch_time_mins))
+        draw_stat!(0, 0 => Text::WatchTime.get(), {
+            info.total_episodes().map_or_else(
+                || "??".into(),
+                |total| util::hm_from_mins(f32::from(total * info.episode_length_mins)),
+            )
         });
 
-        draw_stat!(0, 1 => "Time Left", {
-            let eps_left = info.episodes - entry.watched_episodes().min(info.episodes);
-            let time_left_mins = eps_left * info.episode_length_mins;
-            util::hm_from_mins(f32::from(time_left_mins))
+        draw_stat!(0, 1 => Text::TimeLeft.get(), {
+            info.total_episodes().map_or_else(
+                || "??".into(),
+                |total| {
+                    let eps_left = total - entry.watched_episodes().min(total);
+                    util::hm_from_mins(f32::from(eps_left * info.episode_length_mins))
+                },
+            )
         });
 
-        draw_stat!(0, 2 => "Episode Length", format!("{}M", info.episode_length_mins));
+        draw_stat!(0, 2 => Text::TimeWatched.get(), util::hm_from_mins(series.data.watched_mins() as f32));
+
+        draw_stat!(0, 3 => Text::EpisodeLength.get(), format!("{}M", info.episode_length_mins));
 
         // Middle panel items
 
-        draw_stat!(1, 0 => "Progress", format!("{}|{}", entry.watched_episodes(), info.episodes));
+        draw_stat!(1, 0 => Text::Progress.get(), format!("{}|{}", entry.watched_episodes(), info.episodes_text()));
 
-        draw_stat!(1, 1 => "Score", {
+        draw_stat!(1, 1 => Text::Score.get(), {
             match (entry.score(), &state.remote) {
+                _ if state.presentation_lock => Text::Hidden.get().into(),
                 (Some(score), RemoteStatus::LoggedIn(remote)) => remote.score_to_str(score as u8),
                 (Some(score), RemoteStatus::LoggingIn(_)) => score.to_string().into(),
                 (None, _) => "??".into(),
             }
         });
 
-        draw_stat!(1, 2 => "Status", {
-            let status: &'static str = entry.status().into();
-            status
+        draw_stat!(1, 2 => Text::Status.get(), {
+            if state.presentation_lock {
+                Text::Hidden.get()
+            } else {
+                Text::from(entry.status()).get()
+            }
         });
 
+        {
+            let content = layout[1];
+
+            let pos = content.grid_pos(Rect {
+                x: 1,
+                y: 3,
+                width: content.width / 3,
+                height: content.height / 4,
+            });
+
+            Self::draw_episode_ranges(series, pos, frame);
+        }
+
         // Right panel items
 
-        // TODO: allow the format to be changed in the config
-        let format_date = |date: Option<SeriesDate>| {
-            date.map_or_else(
-                || Cow::Borrowed("??"),
-                |date| format!("{:02}/{:02}/{:02}", date.month, date.day, date.year % 100).into(),
-            )
-        };
+        let dates = &state.config.dates;
 
-        draw_stat!(2, 0 => "Start Date", format_date(entry.start_date()));
-        draw_stat!(2, 1 => "Finish Date", format_date(entry.end_date()));
-        draw_stat!(2, 2 => "Rewatched", entry.times_rewatched().to_string());
+        draw_stat!(2, 0 => Text::StartDate.get(), dates.format_date(entry.start_date()));
+        draw_stat!(2, 1 => Text::FinishDate.get(), dates.format_date(entry.end_date()));
+        draw_stat!(2, 2 => Text::Rewatched.get(), entry.times_rewatched().to_string());
 
-        self.draw_status_text(state, layout[2], frame);
+        draw_stat!(2, 3 => Text::EpisodeRatings.get(), {
+            match series.data.history.ratings {
+                _ if state.presentation_lock => Text::Hidden.get().into(),
+                Some(ratings) => Text::RatingSummary
+                    .format(&[&format!("{:.1}", ratings.average), &ratings.best.0, &ratings.worst.0])
+                    .into(),
+                None => Cow::Borrowed("??"),
+            }
+        });
+
+        Self::draw_status_text(state, layout[2], frame);
     }
 
     fn draw_stat<B, S>(header: &str, value: S, rect: Rect, frame: &mut Frame<B>)
@@ -360,27 +371,63 @@ impl InfoPanel {
         frame.render_widget(widget, rect);
     }
 
-    fn draw_status_text<B: Backend>(&self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
-        let progress_remaining_secs = self.progress_remaining_secs.load(Ordering::SeqCst);
+    fn draw_episode_ranges<B>(series: &Series, rect: Rect, frame: &mut Frame<B>)
+    where
+        B: Backend,
+    {
+        let watched = series.data.entry.watched_episodes().max(0) as u32;
+
+        // Episodes beyond the known count can still be on disk, such as when the count hasn't been announced yet
+        let total =
+            (series.data.info.episodes.max(0) as u32).max(series.episodes.highest_episode_number());
+
+        let filler = &series.data.filler;
+
+        let ranges = EpisodeRange::group(total, |episode| {
+            let on_disk = series.episodes.find(episode).is_some();
+            let state = EpisodeState::new(episode <= watched, on_disk);
 
+            (state, filler.contains(episode as i16))
+        });
+
+        let mut fragments = vec![
+            Fragment::span(text::bold(Text::Episodes.get())),
+            Fragment::Line,
+        ];
+
+        for (i, range) in ranges.iter().enumerate() {
+            if i > 0 {
+                fragments.push(Fragment::span(Span::raw(" ")));
+            }
+
+            let span = match range.state.tone() {
+                Some(tone) => text::italic_with(range.to_string(), |s| tone.apply(s)),
+                None => text::italic(range.to_string()),
+            };
+
+            fragments.push(Fragment::span(span));
+        }
+
+        let wrapped = wrap::by_letters(fragments.into_iter(), rect.width);
+        let widget = TextFragments::new(&wrapped).alignment(Alignment::Center);
+        frame.render_widget(widget, rect);
+    }
+
+    fn draw_status_text<B: Backend>(state: &UIState, rect: Rect, frame: &mut Frame<B>) {
         // Remaining time until progression
-        if progress_remaining_secs > 0 {
+        if let Some(progress_remaining_secs) = Self::progress_remaining_secs(state) {
             let mins = (progress_remaining_secs as f32 / 60.0).round() as u32;
 
-            let fragments = [
-                Fragment::span(text::bold(mins.to_string())),
-                Fragment::span(text::bold(" Minutes Until Progression")),
-            ];
-
-            let widget = TextFragments::new(&fragments).alignment(Alignment::Center);
+            let text = text::bold(Text::MinutesUntilProgression.format(&[&mins]));
+            let widget = SimpleText::new(text).alignment(Alignment::Center);
             frame.render_widget(widget, rect);
         }
         // Login message
         else if let RemoteStatus::LoggingIn(username) = &state.remote {
             let fragments = [
-                Fragment::span(text::bold("Logging In As ")),
+                Fragment::span(text::bold(Text::LoggingInAs.get())),
                 Fragment::Span(
-                    text::bold_with(username, |s| s.fg(Color::Blue)),
+                    text::bold_with(username, |s| Tone::Accent.apply(s)),
                     SpanOptions::new().overflow(OverflowMode::Truncate),
                 ),
             ];
@@ -390,17 +437,39 @@ impl InfoPanel {
         }
     }
 
-    pub fn draw<B: Backend>(&mut self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
-        let info_block = block::with_borders("Info");
+    /// Returns the style the panel is accented with for the selected series, which comes from the color of its cover.
+    fn accent(state: &UIState) -> Option<Style> {
+        match state.series.selected() {
+            Some(LoadedSeries::Complete(series)) => series
+                .data
+                .info
+                .cover_color
+                .as_deref()
+                .and_then(theme::cover_accent),
+            _ => None,
+        }
+    }
+
+    // The panel only needs to exist to keep its event monitor running
+    #[allow(clippy::unused_self)]
+    pub fn draw<B: Backend>(&self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
+        let mut info_block = block::with_borders(Text::InfoPanel.get());
+
+        if let Some(accent) = Self::accent(state) {
+            info_block = info_block
+                .border_style(accent)
+                .title(Span::styled(Text::InfoPanel.get(), accent));
+        }
+
         frame.render_widget(info_block, rect);
 
-        if state.users.get().is_empty() {
+        if state.users.is_empty() {
             return Self::draw_no_users_info(rect, frame);
         }
 
         match state.series.selected() {
             Some(LoadedSeries::Complete(series)) => {
-                self.draw_series_info(state, series, rect, frame)
+                Self::draw_series_info(state, series, rect, frame);
             }
             Some(LoadedSeries::Partial(_, err)) => Self::draw_series_error(err, rect, frame),
             Some(LoadedSeries::None(_, err)) => Self::draw_series_error(err, rect, frame),
@@ -409,9 +478,141 @@ impl InfoPanel {
     }
 }
 
+/// Where an episode is in relation to the files on disk and the watched progress of its series.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum EpisodeState {
+    /// The episode is on disk and has been watched.
+    Watched,
+    /// The episode is on disk and hasn't been watched yet.
+    Unwatched,
+    /// The episode has been watched, but is no longer on disk.
+    Removed,
+    /// The episode hasn't been watched and isn't on disk.
+    Missing,
+}
+
+impl EpisodeState {
+    fn new(watched: bool, on_disk: bool) -> Self {
+        match (watched, on_disk) {
+            (true, true) => Self::Watched,
+            (false, true) => Self::Unwatched,
+            (true, false) => Self::Removed,
+            (false, false) => Self::Missing,
+        }
+    }
+
+    fn tone(self) -> Option<Tone> {
+        match self {
+            Self::Watched => Some(Tone::Good),
+            Self::Unwatched => Some(Tone::Accent),
+            Self::Removed => Some(Tone::Faded),
+            Self::Missing => None,
+        }
+    }
+}
+
+/// A run of consecutive episodes that share the same `EpisodeState`, and are either all filler or not filler.
+#[derive(Debug, PartialEq)]
+struct EpisodeRange {
+    start: u32,
+    end: u32,
+    state: EpisodeState,
+    filler: bool,
+}
+
+impl EpisodeRange {
+    /// Group episodes 1 through `total` into ranges, using `state_of` to get the state of each episode and whether it's filler.
+    fn group<F>(total: u32, state_of: F) -> SmallVec<[Self; 4]>
+    where
+        F: Fn(u32) -> (EpisodeState, bool),
+    {
+        let mut ranges: SmallVec<[Self; 4]> = SmallVec::new();
+
+        for episode in 1..=total {
+            let (state, filler) = state_of(episode);
+
+            match ranges.last_mut() {
+                Some(last) if last.state == state && last.filler == filler => last.end = episode,
+                _ => ranges.push(Self {
+                    start: episode,
+                    end: episode,
+                    state,
+                    filler,
+                }),
+            }
+        }
+
+        ranges
+    }
+}
+
+impl fmt::Display for EpisodeRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Filler is put in parentheses so it can be told apart without relying on color
+        if self.filler {
+            write!(f, "(")?;
+        }
+
+        if self.start == self.end {
+            write!(f, "{}", self.start)?;
+        } else {
+            write!(f, "{}-{}", self.start, self.end)?;
+        }
+
+        if self.filler {
+            write!(f, ")")?;
+        }
+
+        Ok(())
+    }
+}
+
 impl Component for InfoPanel {
     type State = ();
     type KeyResult = ();
 
     fn process_key(&mut self, _: crate::key::Key, _: &mut Self::State) -> Self::KeyResult {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn episode_ranges() {
+        let on_disk = [3, 4, 5, 7];
+        let ranges = EpisodeRange::group(8, |episode| {
+            let state = EpisodeState::new(episode <= 4, on_disk.contains(&episode));
+            (state, false)
+        });
+
+        let expected = [
+            (1, 2, EpisodeState::Removed),
+            (3, 4, EpisodeState::Watched),
+            (5, 5, EpisodeState::Unwatched),
+            (6, 6, EpisodeState::Missing),
+            (7, 7, EpisodeState::Unwatched),
+            (8, 8, EpisodeState::Missing),
+        ];
+
+        assert_eq!(ranges.len(), expected.len());
+
+        for (range, &(start, end, state)) in ranges.iter().zip(&expected) {
+            let expected = EpisodeRange {
+                start,
+                end,
+                state,
+                filler: false,
+            };
+
+            assert_eq!(*range, expected);
+        }
+
+        // Filler splits up ranges that would otherwise share a state
+        let ranges = EpisodeRange::group(4, |episode| (EpisodeState::Watched, episode >= 3));
+        let texts = ranges.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(texts, ["1-2", "(3-4)"]);
+
+        assert!(EpisodeRange::group(0, |_| (EpisodeState::Missing, false)).is_empty());
+    }
+}
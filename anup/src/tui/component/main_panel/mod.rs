@@ -1,25 +1,59 @@
 mod add_series;
+mod audit;
+mod change_status;
+mod changelog;
 mod delete_series;
+mod duplicate_series;
+mod events;
+mod history;
 mod info;
+mod notifications;
+mod profile;
+mod quick_switch;
+mod score;
 mod select_series;
+mod split_cour;
 mod split_series;
+mod stats;
+mod summary;
+mod tasks;
 mod user_panel;
 
 use super::Component;
-use crate::series::info::InfoResult;
+use crate::series::info::{InfoResult, SeriesInfo};
 use crate::try_opt_r;
+use crate::tui::component::prompt::log::{ActivityId, LogKind};
+use crate::tui::locale::Text;
+use crate::tui::session::SessionPanel;
 use crate::tui::state::{InputState, UIState};
 use crate::{key::Key, series::config::SeriesConfig};
 use crate::{series::SeriesParams, tui::state::SharedState};
-use add_series::{AddSeriesPanel, AddSeriesResult};
+use add_series::{AddSeriesPanel, AddSeriesResult, SeriesLookup};
 use anime::local::SortedEpisodes;
-use anime::remote::RemoteService;
+use anime::remote::{RemoteService, SeriesID, Status};
 use anyhow::{anyhow, Result};
+use audit::AuditPanel;
+use change_status::ChangeStatusPanel;
+use changelog::ChangelogPanel;
 use delete_series::DeleteSeriesPanel;
+use duplicate_series::DuplicateSeriesPanel;
+use events::EventsPanel;
+use history::HistoryPanel;
 use info::InfoPanel;
+use notifications::NotificationsPanel;
+use profile::ProfilePanel;
+use quick_switch::QuickSwitchPanel;
+use score::ScorePanel;
 use select_series::{SelectSeriesPanel, SelectSeriesResult, SelectState};
+use split_cour::SplitCourPanel;
 use split_series::{SplitPanelResult, SplitSeriesPanel};
+use stats::StatsPanel;
 use std::mem;
+use std::sync::Arc;
+use summary::{SummaryPanel, SummaryResult};
+use tasks::TasksPanel;
+use tokio::sync::oneshot::{self, error::TryRecvError};
+use tokio::task;
 use tui::backend::Backend;
 use tui::layout::Rect;
 use tui::terminal::Frame;
@@ -28,6 +62,13 @@ use user_panel::UserPanel;
 pub struct MainPanel {
     current: Panel,
     state: SharedState,
+    lookup: Option<PendingLookup>,
+}
+
+/// A series being looked up on the remote service in the background.
+struct PendingLookup {
+    result: oneshot::Receiver<Result<PartialSeries>>,
+    activity: ActivityId,
 }
 
 impl MainPanel {
@@ -35,6 +76,7 @@ impl MainPanel {
         Self {
             current: Panel::info(&state),
             state,
+            lookup: None,
         }
     }
 
@@ -43,11 +85,8 @@ impl MainPanel {
     }
 
     pub fn switch_to_add_series(&mut self, state: &mut UIState) -> Result<()> {
-        let remote = state.remote.get_logged_in()?;
-
-        if remote.is_offline() {
-            return Err(anyhow!("must be online to add a series"));
-        }
+        // Series are found in previous search results while offline
+        state.remote.get_logged_in()?;
 
         self.current = Panel::add_series(state, &self.state)?;
         state.input_state = InputState::FocusedOnMainPanel;
@@ -67,6 +106,12 @@ impl MainPanel {
         Ok(())
     }
 
+    pub fn switch_to_change_status(&mut self, status: Status, state: &mut UIState) -> Result<()> {
+        self.current = Panel::change_status(status, state)?;
+        state.input_state = InputState::FocusedOnMainPanel;
+        Ok(())
+    }
+
     fn switch_to_select_series(&mut self, select: SelectState, state: &mut UIState) {
         self.current = Panel::select_series(select);
         state.input_state = InputState::FocusedOnMainPanel;
@@ -91,27 +136,247 @@ impl MainPanel {
         Ok(())
     }
 
+    pub fn switch_to_stats(&mut self, state: &mut UIState) -> Result<()> {
+        self.current = Panel::stats(state)?;
+        state.input_state = InputState::FocusedOnMainPanel;
+        Ok(())
+    }
+
+    pub fn switch_to_history(&mut self, state: &mut UIState) -> Result<()> {
+        self.current = Panel::history(state)?;
+        state.input_state = InputState::FocusedOnMainPanel;
+        Ok(())
+    }
+
+    pub fn switch_to_tasks(&mut self, state: &mut UIState) {
+        self.current = Panel::Tasks(TasksPanel::new());
+        state.input_state = InputState::FocusedOnMainPanel;
+    }
+
+    /// Switch to the panel that was focused in the last session.
+    ///
+    /// The info panel stays focused if the panel can't be shown anymore, like the history of a series that's since been removed.
+    pub fn restore_session_panel(&mut self, panel: SessionPanel, state: &mut UIState) {
+        match panel {
+            SessionPanel::Info => (),
+            SessionPanel::Stats => {
+                self.switch_to_stats(state).ok();
+            }
+            SessionPanel::History => {
+                self.switch_to_history(state).ok();
+            }
+            SessionPanel::Tasks => self.switch_to_tasks(state),
+        }
+    }
+
+    /// Returns the currently focused panel, if it's one that can be restored in the next session.
+    pub fn session_panel(&self) -> SessionPanel {
+        match self.current {
+            Panel::Stats(_) => SessionPanel::Stats,
+            Panel::History(_) => SessionPanel::History,
+            Panel::Tasks(_) => SessionPanel::Tasks,
+            _ => SessionPanel::Info,
+        }
+    }
+
+    pub fn switch_to_notifications(&mut self, state: &mut UIState) -> Result<()> {
+        let panel = NotificationsPanel::init(state, &self.state)?;
+        self.current = Panel::Notifications(panel);
+        state.input_state = InputState::FocusedOnMainPanel;
+        Ok(())
+    }
+
+    pub fn switch_to_events(&mut self, state: &mut UIState) -> Result<()> {
+        let panel = EventsPanel::init(state)?;
+        self.current = Panel::Events(panel);
+        state.input_state = InputState::FocusedOnMainPanel;
+        Ok(())
+    }
+
+    pub fn switch_to_audit_log(&mut self, state: &mut UIState) -> Result<()> {
+        let panel = AuditPanel::init(state)?;
+        self.current = Panel::Audit(panel);
+        state.input_state = InputState::FocusedOnMainPanel;
+        Ok(())
+    }
+
+    pub fn switch_to_profile(&mut self, state: &mut UIState) -> Result<()> {
+        let panel = ProfilePanel::init(state, &self.state)?;
+        self.current = Panel::Profile(panel);
+        state.input_state = InputState::FocusedOnMainPanel;
+        Ok(())
+    }
+
+    pub fn switch_to_quick_switch(&mut self, state: &mut UIState) -> Result<()> {
+        let panel = QuickSwitchPanel::init(state, self.state.clone())?;
+        self.current = Panel::QuickSwitch(panel);
+        state.input_state = InputState::FocusedOnMainPanel;
+        Ok(())
+    }
+
+    pub fn switch_to_changelog(&mut self, state: &mut UIState) -> Result<()> {
+        let panel = ChangelogPanel::init(state)?;
+        self.current = Panel::Changelog(panel);
+        state.input_state = InputState::FocusedOnMainPanel;
+        Ok(())
+    }
+
+    /// Show the summary of the selected series after it was completed, or ask for its score first if enabled.
+    pub fn switch_to_completed_series(&mut self, state: &mut UIState) -> Result<()> {
+        let can_score = state
+            .remote
+            .get_logged_in()
+            .map_or(false, |remote| remote.capabilities().supports_scores());
+
+        self.current = if state.config.tui.score_on_complete && can_score {
+            Panel::score(state, true)?
+        } else {
+            Panel::summary(state, &self.state)?
+        };
+
+        state.input_state = InputState::FocusedOnMainPanel;
+        Ok(())
+    }
+
+    fn switch_to_summary(&mut self, state: &mut UIState) -> Result<()> {
+        self.current = Panel::summary(state, &self.state)?;
+        state.input_state = InputState::FocusedOnMainPanel;
+        Ok(())
+    }
+
+    fn switch_to_add_sequel(&mut self, id: SeriesID, state: &mut UIState) -> Result<()> {
+        let remote = state.remote.get_logged_in()?;
+
+        if remote.is_offline() {
+            return Err(anyhow!("must be online to add a series"));
+        }
+
+        let panel = AddSeriesPanel::init_with_id(state, &self.state, id)?;
+        self.current = Panel::AddSeries(panel.into());
+        state.input_state = InputState::FocusedOnMainPanel;
+
+        Ok(())
+    }
+
     fn add_partial_series(&mut self, series: PartialSeries, state: &mut UIState) -> Result<()> {
         match series.info {
             InfoResult::Confident(info) => {
+                if let Some(existing) = SeriesConfig::id_exists(&state.db, info.id) {
+                    let panel =
+                        DuplicateSeriesPanel::new(info, series.params, series.episodes, existing);
+
+                    self.current = Panel::DuplicateSeries(panel);
+                    state.input_state = InputState::FocusedOnMainPanel;
+                    return Ok(());
+                }
+
+                // The sequel might have been added on its own already, in which case its offset is up to the user
+                if let Some(sequel) = series.split_sequel {
+                    if SeriesConfig::id_exists(&state.db, sequel.id).is_none() {
+                        let panel = SplitCourPanel::new(
+                            info,
+                            sequel,
+                            series.params,
+                            series.episodes,
+                            &state.db,
+                        );
+
+                        self.current = Panel::SplitCour(panel);
+                        state.input_state = InputState::FocusedOnMainPanel;
+                        return Ok(());
+                    }
+                }
+
                 self.reset(state);
 
+                let title = info.title_preferred.clone();
                 let config = SeriesConfig::new(info.id, series.params, &state.db)?;
                 state.add_series(config, info, series.episodes)?;
 
+                if series.cached {
+                    state
+                        .log
+                        .push(LogKind::Info, Text::AddedFromCache.format(&[&title]));
+                }
+
                 Ok(())
             }
             InfoResult::Unconfident(info_list) => {
-                let select = SelectState::new(info_list, series.params);
+                let mut select = SelectState::new(info_list, series.params);
+                select.cached = series.cached;
+
                 self.switch_to_select_series(select, state);
                 Ok(())
             }
         }
     }
 
+    /// Look up a series on the remote service in the background, so the UI can keep drawing while it's being found.
+    fn start_lookup(&mut self, lookup: SeriesLookup, state: &mut UIState) -> Result<()> {
+        if self.lookup.is_some() {
+            state.log.push(LogKind::Info, Text::LookupInProgress.get());
+            return Ok(());
+        }
+
+        let remote = state.remote.get_logged_in_shared()?;
+        let config = Arc::clone(&state.config);
+
+        let text = if remote.is_offline() {
+            Text::LookupCachedActivity
+        } else {
+            Text::LookupActivity
+        };
+
+        let activity = state.log.start_activity(text.format(&[&lookup.name()]));
+
+        let (sender, result) = oneshot::channel();
+        let shared_state = self.state.clone();
+
+        task::spawn_blocking(move || {
+            sender.send(lookup.run(&config, &remote)).ok();
+            // Wake up the UI so the result can be handled
+            shared_state.lock().mark_dirty();
+        });
+
+        self.lookup = Some(PendingLookup { result, activity });
+        Ok(())
+    }
+
+    /// Add the series that was being looked up once it has been found.
+    pub fn poll_lookup(&mut self, state: &mut UIState) -> Result<()> {
+        let pending = match &mut self.lookup {
+            Some(pending) => pending,
+            None => return Ok(()),
+        };
+
+        let result = match pending.result.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return Ok(()),
+            Err(TryRecvError::Closed) => Err(anyhow!("the series lookup stopped unexpectedly")),
+        };
+
+        state.log.finish_activity(pending.activity);
+        self.lookup = None;
+
+        self.add_partial_series(result?, state)
+    }
+
     fn reset(&mut self, state: &mut UIState) {
         self.current = self.default_panel();
         state.input_state.reset();
+
+        // A lookup that hasn't finished yet would otherwise add its series after the panel was closed
+        if let Some(pending) = self.lookup.take() {
+            state.log.finish_activity(pending.activity);
+        }
+    }
+
+    fn reset_if_needed(&mut self, result: Result<ShouldReset>, state: &mut UIState) -> Result<()> {
+        if let ShouldReset::Yes = result? {
+            self.reset(state);
+        }
+
+        Ok(())
     }
 
     pub fn draw<B: Backend>(&mut self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
@@ -120,8 +385,22 @@ impl MainPanel {
             Panel::AddSeries(add) => add.draw(rect, frame),
             Panel::SelectSeries(panel) => panel.draw(rect, frame),
             Panel::DeleteSeries(panel) => panel.draw(rect, frame),
+            Panel::ChangeStatus(panel) => panel.draw(rect, frame),
+            Panel::DuplicateSeries(panel) => panel.draw(rect, frame),
+            Panel::SplitCour(panel) => panel.draw(rect, frame),
             Panel::User(user) => user.draw(state, rect, frame),
             Panel::SplitSeries(split) => split.draw(rect, frame),
+            Panel::Stats(panel) => panel.draw(state, rect, frame),
+            Panel::History(panel) => panel.draw(rect, frame),
+            Panel::Tasks(panel) => panel.draw(state, rect, frame),
+            Panel::Notifications(panel) => panel.draw(rect, frame),
+            Panel::Events(panel) => panel.draw(rect, frame),
+            Panel::Audit(panel) => panel.draw(rect, frame),
+            Panel::Profile(panel) => panel.draw(rect, frame),
+            Panel::Changelog(panel) => panel.draw(rect, frame),
+            Panel::QuickSwitch(panel) => panel.draw(rect, frame),
+            Panel::Score(panel) => panel.draw(rect, frame),
+            Panel::Summary(panel) => panel.draw(rect, frame),
         }
     }
 }
@@ -130,6 +409,7 @@ impl Component for MainPanel {
     type State = UIState;
     type KeyResult = Result<()>;
 
+    #[allow(clippy::too_many_lines)]
     fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
         match &mut self.current {
             Panel::Info(_) => Ok(()),
@@ -139,7 +419,7 @@ impl Component for MainPanel {
                     self.reset(state);
                     Ok(())
                 }
-                Ok(AddSeriesResult::AddSeries(partial)) => self.add_partial_series(*partial, state),
+                Ok(AddSeriesResult::Lookup(lookup)) => self.start_lookup(*lookup, state),
                 Ok(AddSeriesResult::UpdateSeries(params)) => {
                     let selected = try_opt_r!(state.series.selected_mut());
                     let remote = state.remote.get_logged_in()?;
@@ -156,12 +436,17 @@ impl Component for MainPanel {
                 SelectSeriesResult::AddSeries(info) => {
                     let default_panel = self.default_panel();
 
-                    let params = match mem::replace(&mut self.current, default_panel) {
-                        Panel::SelectSeries(panel) => panel.take_params(),
+                    let (params, cached) = match mem::replace(&mut self.current, default_panel) {
+                        Panel::SelectSeries(panel) => {
+                            let cached = panel.cached();
+                            (panel.take_params(), cached)
+                        }
                         _ => unreachable!(),
                     };
 
-                    let series = PartialSeries::new(InfoResult::Confident(info), params, None);
+                    let mut series = PartialSeries::new(InfoResult::Confident(info), params, None);
+                    series.cached = cached;
+
                     self.add_partial_series(series, state)?;
 
                     Ok(())
@@ -171,32 +456,96 @@ impl Component for MainPanel {
                     Ok(())
                 }
             },
-            Panel::DeleteSeries(panel) => match panel.process_key(key, state) {
-                Ok(ShouldReset::Yes) => {
+            Panel::DeleteSeries(panel) => {
+                let result = panel.process_key(key, state);
+                self.reset_if_needed(result, state)
+            }
+            Panel::ChangeStatus(panel) => {
+                let result = panel.process_key(key, state);
+                self.reset_if_needed(result, state)
+            }
+            Panel::DuplicateSeries(panel) => {
+                let result = panel.process_key(key, state);
+                self.reset_if_needed(result, state)
+            }
+            Panel::SplitCour(panel) => {
+                let result = panel.process_key(key, state);
+                self.reset_if_needed(result, state)
+            }
+            Panel::User(user) => {
+                let result = user.process_key(key, state);
+                self.reset_if_needed(result, state)
+            }
+            Panel::SplitSeries(split) => match split.process_key(key, state) {
+                Ok(SplitPanelResult::Ok) => Ok(()),
+                Ok(SplitPanelResult::Reset) => {
                     self.reset(state);
                     Ok(())
                 }
-                Ok(ShouldReset::No) => Ok(()),
+                Ok(SplitPanelResult::AddSeries(info, cfg)) => {
+                    state.add_series(*cfg, (*info).into(), None)
+                }
                 Err(err) => Err(err),
             },
-            Panel::User(user) => match user.process_key(key, state) {
-                Ok(ShouldReset::Yes) => {
+            Panel::Stats(panel) => {
+                let result = panel.process_key(key, state);
+                self.reset_if_needed(result, state)
+            }
+            Panel::History(panel) => {
+                let result = panel.process_key(key, state);
+                self.reset_if_needed(result, state)
+            }
+            Panel::Tasks(panel) => {
+                let result = panel.process_key(key, state);
+                self.reset_if_needed(Ok(result), state)
+            }
+            Panel::Notifications(panel) => {
+                let result = panel.process_key(key, state);
+                self.reset_if_needed(Ok(result), state)
+            }
+            Panel::Events(panel) => {
+                let result = panel.process_key(key, state);
+                self.reset_if_needed(result, state)
+            }
+            Panel::Audit(panel) => {
+                let result = panel.process_key(key, state);
+                self.reset_if_needed(Ok(result), state)
+            }
+            Panel::Profile(panel) => {
+                let result = panel.process_key(key, state);
+                self.reset_if_needed(Ok(result), state)
+            }
+            Panel::Changelog(panel) => {
+                let result = panel.process_key(key, state);
+                self.reset_if_needed(result, state)
+            }
+            Panel::QuickSwitch(panel) => {
+                let result = panel.process_key(key, state);
+                self.reset_if_needed(result, state)
+            }
+            Panel::Score(panel) => match panel.process_key(key, state)? {
+                ShouldReset::Yes if panel.completed() => {
+                    let result = self.switch_to_summary(state);
+
+                    if result.is_err() {
+                        self.reset(state);
+                    }
+
+                    result
+                }
+                ShouldReset::Yes => {
                     self.reset(state);
                     Ok(())
                 }
-                Ok(ShouldReset::No) => Ok(()),
-                Err(err) => Err(err),
+                ShouldReset::No => Ok(()),
             },
-            Panel::SplitSeries(split) => match split.process_key(key, state) {
-                Ok(SplitPanelResult::Ok) => Ok(()),
-                Ok(SplitPanelResult::Reset) => {
+            Panel::Summary(panel) => match panel.process_key(key, state) {
+                SummaryResult::Ok => Ok(()),
+                SummaryResult::Reset => {
                     self.reset(state);
                     Ok(())
                 }
-                Ok(SplitPanelResult::AddSeries(info, cfg)) => {
-                    state.add_series(*cfg, (*info).into(), None)
-                }
-                Err(err) => Err(err),
+                SummaryResult::AddSequel(id) => self.switch_to_add_sequel(id, state),
             },
         }
     }
@@ -207,8 +556,22 @@ enum Panel {
     AddSeries(Box<AddSeriesPanel>),
     SelectSeries(SelectSeriesPanel),
     DeleteSeries(DeleteSeriesPanel),
+    ChangeStatus(ChangeStatusPanel),
+    DuplicateSeries(DuplicateSeriesPanel),
+    SplitCour(SplitCourPanel),
     User(UserPanel),
     SplitSeries(SplitSeriesPanel),
+    Stats(StatsPanel),
+    History(HistoryPanel),
+    Tasks(TasksPanel),
+    Notifications(NotificationsPanel),
+    Events(EventsPanel),
+    Audit(AuditPanel),
+    Profile(ProfilePanel),
+    Changelog(ChangelogPanel),
+    QuickSwitch(QuickSwitchPanel),
+    Score(ScorePanel),
+    Summary(SummaryPanel),
 }
 
 impl Panel {
@@ -234,6 +597,11 @@ impl Panel {
         Ok(Self::DeleteSeries(panel))
     }
 
+    fn change_status(status: Status, state: &UIState) -> Result<Self> {
+        let panel = ChangeStatusPanel::init(status, state)?;
+        Ok(Self::ChangeStatus(panel))
+    }
+
     fn select_series(select: SelectState) -> Self {
         Self::SelectSeries(SelectSeriesPanel::new(select))
     }
@@ -246,6 +614,26 @@ impl Panel {
         let panel = SplitSeriesPanel::new(state);
         Self::SplitSeries(panel)
     }
+
+    fn stats(state: &UIState) -> Result<Self> {
+        let panel = StatsPanel::init(state)?;
+        Ok(Self::Stats(panel))
+    }
+
+    fn history(state: &UIState) -> Result<Self> {
+        let panel = HistoryPanel::init(state)?;
+        Ok(Self::History(panel))
+    }
+
+    fn score(state: &UIState, completed: bool) -> Result<Self> {
+        let panel = ScorePanel::init(state, completed)?;
+        Ok(Self::Score(panel))
+    }
+
+    fn summary(state: &UIState, shared_state: &SharedState) -> Result<Self> {
+        let panel = SummaryPanel::init(state, shared_state)?;
+        Ok(Self::Summary(panel))
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -258,6 +646,10 @@ pub struct PartialSeries {
     info: InfoResult,
     params: SeriesParams,
     episodes: Option<SortedEpisodes>,
+    /// Whether or not the series was found in previous search results, rather than on the remote service.
+    cached: bool,
+    /// The direct sequel of the series, when its episodes are in the same folder and numbered after the series' own.
+    split_sequel: Option<SeriesInfo>,
 }
 
 impl PartialSeries {
@@ -270,6 +662,8 @@ impl PartialSeries {
             info,
             params,
             episodes: episodes.into(),
+            cached: false,
+            split_sequel: None,
         }
     }
 }
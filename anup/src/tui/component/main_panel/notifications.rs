@@ -0,0 +1,258 @@
+use super::ShouldReset;
+use crate::config::DatesConfig;
+use crate::database::SharedStr;
+use crate::series::LoadedSeries;
+use crate::tui::locale::Text;
+use crate::tui::state::{SharedState, UIState};
+use crate::tui::theme::Tone;
+use crate::util::{arc_mutex, ArcMutex, ScopedTask};
+use crate::{key::Key, tui::component::Component};
+use anime::remote::{Notification, NotificationKind, Remote, RemoteService};
+use anyhow::{anyhow, Result};
+use crossterm::event::KeyCode;
+use std::sync::Arc;
+use tokio::task;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::style::{Modifier, Style};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::{block, style, text},
+    layout::{BasicConstraint, RectExt, SimpleLayout},
+    list::WrappingIndex,
+    widgets::{SimpleTable, SimpleText},
+};
+
+/// A panel that shows the most recent notifications from the remote service, and marks them as read.
+pub struct NotificationsPanel {
+    state: ArcMutex<PanelState>,
+    selected: WrappingIndex,
+    #[allow(dead_code)]
+    fetch_task: ScopedTask<()>,
+}
+
+impl NotificationsPanel {
+    pub fn init(state: &UIState, shared_state: &SharedState) -> Result<Self> {
+        let remote = state.remote.get_logged_in_shared()?;
+
+        if remote.is_offline() {
+            return Err(anyhow!("must be online to view notifications"));
+        }
+
+        let panel_state = arc_mutex(PanelState::Loading);
+        let limit = state.config.tui.limits.notifications.into();
+        let fetch_task = Self::spawn_fetch_task(&panel_state, remote, limit, shared_state).into();
+
+        Ok(Self {
+            state: panel_state,
+            selected: WrappingIndex::new(0),
+            fetch_task,
+        })
+    }
+
+    fn spawn_fetch_task(
+        panel_state: &ArcMutex<PanelState>,
+        remote: Arc<Remote>,
+        limit: usize,
+        state: &SharedState,
+    ) -> task::JoinHandle<()> {
+        let panel_state = Arc::clone(panel_state);
+        let state = state.clone();
+
+        task::spawn_blocking(move || {
+            let result = remote.notifications(true, limit);
+            let mut state = state.lock();
+
+            let new_state = match result {
+                Ok(notifications) => {
+                    let rows = notifications
+                        .iter()
+                        .map(|notification| {
+                            NotificationRow::new(
+                                notification,
+                                &state.config.dates,
+                                state.series.iter(),
+                            )
+                        })
+                        .collect();
+
+                    PanelState::Loaded(rows)
+                }
+                Err(err) => PanelState::Failed(err.to_string()),
+            };
+
+            *panel_state.lock() = new_state;
+            state.mark_dirty();
+        })
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = block::with_borders(Text::NotificationsPanel.get());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let panel_state = self.state.lock();
+
+        let rows = match &*panel_state {
+            PanelState::Loading => {
+                let widget =
+                    SimpleText::new(text::bold(Text::Loading.get())).alignment(Alignment::Center);
+                frame.render_widget(widget, block_area.lines_from_top(1));
+                return;
+            }
+            PanelState::Failed(err) => {
+                let text =
+                    Span::styled(Text::NotificationsFailed.format(&[err]), Tone::Bad.style());
+
+                let widget = SimpleText::new(text).alignment(Alignment::Center);
+                frame.render_widget(widget, block_area.lines_from_top(1));
+                return;
+            }
+            PanelState::Loaded(rows) if rows.is_empty() => {
+                let text = text::hint(Text::NoNotifications.get());
+                let widget = SimpleText::new(text).alignment(Alignment::Center);
+                frame.render_widget(widget, block_area.lines_from_top(1));
+                return;
+            }
+            PanelState::Loaded(rows) => rows,
+        };
+
+        self.selected.update_bounds(rows.len());
+
+        let vert_split = SimpleLayout::new(Direction::Vertical).split(
+            block_area,
+            [
+                // Notifications
+                BasicConstraint::MinLenRemaining(1, 1),
+                // Hint text
+                BasicConstraint::Length(1),
+            ],
+        );
+
+        let table_rows = rows.iter().map(|row| {
+            let style = if row.unread {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+
+            let tracked = match &row.tracked {
+                Some((_, nickname)) => Span::styled(nickname.as_str(), Tone::Accent.style()),
+                None => Span::raw(""),
+            };
+
+            [
+                Span::styled(row.sent.as_str(), style),
+                Span::styled(row.message.as_str(), style),
+                tracked,
+            ]
+        });
+
+        let header = [
+            text::bold(Text::Sent.get()),
+            text::bold(Text::Notification.get()),
+            text::bold(Text::TrackedSeries.get()),
+        ];
+
+        let layout = [
+            BasicConstraint::Percentage(20),
+            BasicConstraint::Percentage(55),
+            BasicConstraint::Percentage(25),
+        ];
+
+        let table = SimpleTable::new(table_rows, layout)
+            .header(&header)
+            .highlight_symbol(Span::styled(">", style::list_selector(true)))
+            .select(Some(self.selected.get() as u16));
+
+        frame.render_widget(table, vert_split[0]);
+
+        let hint_widget = SimpleText::new(text::hint(Text::SelectTrackedSeriesHint.get()))
+            .alignment(Alignment::Center);
+        frame.render_widget(hint_widget, vert_split[1]);
+    }
+}
+
+impl Component for NotificationsPanel {
+    type State = UIState;
+    type KeyResult = ShouldReset;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        let panel_state = self.state.lock();
+
+        let rows = match &*panel_state {
+            PanelState::Loaded(rows) => rows.as_slice(),
+            PanelState::Loading | PanelState::Failed(_) => &[],
+        };
+
+        match *key {
+            KeyCode::Esc => return ShouldReset::Yes,
+            KeyCode::Up => self.selected.decrement(rows.len()),
+            KeyCode::Down => self.selected.increment(rows.len()),
+            KeyCode::Enter => {
+                let tracked = rows
+                    .get(self.selected.get())
+                    .and_then(|row| row.tracked.as_ref());
+
+                if let Some((id, _)) = tracked {
+                    state.select_series_by_id(*id);
+                    return ShouldReset::Yes;
+                }
+            }
+            _ => (),
+        }
+
+        ShouldReset::No
+    }
+}
+
+enum PanelState {
+    Loading,
+    Loaded(Vec<NotificationRow>),
+    Failed(String),
+}
+
+struct NotificationRow {
+    sent: String,
+    message: String,
+    /// The ID and nickname of the series being tracked that the notification is about.
+    tracked: Option<(i32, SharedStr)>,
+    unread: bool,
+}
+
+impl NotificationRow {
+    #[allow(clippy::cast_possible_wrap)]
+    fn new<'a, I>(notification: &Notification, dates: &DatesConfig, series: I) -> Self
+    where
+        I: IntoIterator<Item = &'a LoadedSeries>,
+    {
+        let title = notification
+            .series
+            .as_ref()
+            .map_or("??", |series| series.title.as_str());
+
+        let message = match &notification.kind {
+            NotificationKind::Airing { episode } => {
+                Text::AiringNotification.format(&[episode, &title])
+            }
+            NotificationKind::RelatedMediaAdded => Text::RelatedMediaNotification.format(&[&title]),
+            NotificationKind::Following { user } => Text::FollowingNotification.format(&[user]),
+        };
+
+        let tracked = notification.series.as_ref().and_then(|notified| {
+            series
+                .into_iter()
+                .find(|series| series.id() == Some(notified.id as i32))
+                .map(|series| (notified.id as i32, series.config().nickname.clone()))
+        });
+
+        Self {
+            sent: dates.format_timestamp(notification.created_at),
+            message,
+            tracked,
+            unread: notification.unread,
+        }
+    }
+}
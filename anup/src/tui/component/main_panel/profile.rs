@@ -0,0 +1,200 @@
+use super::ShouldReset;
+use crate::series::LoadedSeries;
+use crate::stats::STATUSES;
+use crate::tui::locale::Text;
+use crate::tui::state::{SharedState, UIState};
+use crate::tui::theme::Tone;
+use crate::util::{arc_mutex, ArcMutex, ScopedTask};
+use crate::{key::Key, tui::component::Component};
+use anime::remote::{Remote, RemoteService, UserStats};
+use anyhow::{anyhow, Result};
+use crossterm::event::KeyCode;
+use std::sync::Arc;
+use tokio::task;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::{block, text},
+    layout::{BasicConstraint, RectExt, SimpleLayout},
+    widgets::{Fragment, SimpleTable, SimpleText, TextFragments},
+};
+
+/// A panel that shows the statistics the remote service has for the user's list, next to the same counts from
+/// every added series, to make it easy to tell if changes have been synced.
+pub struct ProfilePanel {
+    state: ArcMutex<PanelState>,
+    /// The number of added series with each status, in the same order as `STATUSES`.
+    local_counts: [u32; STATUSES.len()],
+    #[allow(dead_code)]
+    fetch_task: ScopedTask<()>,
+}
+
+impl ProfilePanel {
+    pub fn init(state: &UIState, shared_state: &SharedState) -> Result<Self> {
+        let remote = state.remote.get_logged_in_shared()?;
+
+        if remote.is_offline() {
+            return Err(anyhow!("must be online to view your profile"));
+        }
+
+        let mut local_counts = [0; STATUSES.len()];
+
+        for data in state.series.iter().filter_map(LoadedSeries::data) {
+            local_counts[data.entry.status() as usize] += 1;
+        }
+
+        let panel_state = arc_mutex(PanelState::Loading);
+        let fetch_task = Self::spawn_fetch_task(&panel_state, remote, shared_state).into();
+
+        Ok(Self {
+            state: panel_state,
+            local_counts,
+            fetch_task,
+        })
+    }
+
+    fn spawn_fetch_task(
+        panel_state: &ArcMutex<PanelState>,
+        remote: Arc<Remote>,
+        state: &SharedState,
+    ) -> task::JoinHandle<()> {
+        let panel_state = Arc::clone(panel_state);
+        let state = state.clone();
+
+        task::spawn_blocking(move || {
+            let new_state = match remote.user_stats() {
+                Ok(Some(user_stats)) => PanelState::Loaded(user_stats),
+                Ok(None) => PanelState::Failed(Text::NoProfileStats.get().into()),
+                Err(err) => PanelState::Failed(Text::ProfileFailed.format(&[&err])),
+            };
+
+            *panel_state.lock() = new_state;
+            state.lock().mark_dirty();
+        })
+    }
+
+    fn draw_summary<B: Backend>(stats: &UserStats, rect: Rect, frame: &mut Frame<B>) {
+        let days = format!("{:.1}", stats.minutes_watched as f32 / 60.0 / 24.0);
+
+        let mean = stats
+            .mean_score
+            .map_or_else(|| "??".into(), |score| format!("{:.1}", score));
+
+        let fragments = [
+            Fragment::span(text::bold(Text::DaysWatched.get())),
+            Fragment::span(Span::raw(days)),
+            Fragment::span(text::bold(Text::EpisodesWatched.get())),
+            Fragment::span(Span::raw(stats.episodes_watched.to_string())),
+            Fragment::span(text::bold(Text::MeanScore.get())),
+            Fragment::span(Span::raw(mean)),
+        ];
+
+        let widget = TextFragments::new(&fragments).alignment(Alignment::Center);
+        frame.render_widget(widget, rect);
+    }
+
+    fn draw_status_table<B: Backend>(&self, stats: &UserStats, rect: Rect, frame: &mut Frame<B>) {
+        let remote_count = |status| {
+            stats
+                .statuses
+                .iter()
+                .filter(|&&(other, _)| other == status)
+                .map(|&(_, count)| count)
+                .sum::<u32>()
+        };
+
+        // Counts that don't match are highlighted, since they usually mean something hasn't been synced
+        let count_cells = |remote: u32, local: u32| {
+            let style = if remote == local {
+                Default::default()
+            } else {
+                Tone::Warning.style()
+            };
+
+            [
+                Span::styled(remote.to_string(), style),
+                Span::styled(local.to_string(), style),
+            ]
+        };
+
+        let mut rows = STATUSES
+            .iter()
+            .zip(self.local_counts.iter())
+            .map(|(&status, &local)| {
+                let [remote_cell, local_cell] = count_cells(remote_count(status), local);
+                [Span::raw(Text::from(status).get()), remote_cell, local_cell]
+            })
+            .collect::<Vec<_>>();
+
+        let [remote_cell, local_cell] = count_cells(stats.series, self.local_counts.iter().sum());
+        rows.push([text::bold(Text::Total.get()), remote_cell, local_cell]);
+
+        let header = [
+            text::bold(Text::Status.get()),
+            text::bold(Text::OnRemote.get()),
+            text::bold(Text::AddedLocally.get()),
+        ];
+
+        let layout = [
+            BasicConstraint::Percentage(40),
+            BasicConstraint::Percentage(30),
+            BasicConstraint::Percentage(30),
+        ];
+
+        let table = SimpleTable::new(rows.into_iter(), layout).header(&header);
+        frame.render_widget(table, rect);
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = block::with_borders(Text::ProfilePanel.get());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let panel_state = self.state.lock();
+
+        let stats = match &*panel_state {
+            PanelState::Loading => {
+                let widget =
+                    SimpleText::new(text::bold(Text::Loading.get())).alignment(Alignment::Center);
+                frame.render_widget(widget, block_area.lines_from_top(1));
+                return;
+            }
+            PanelState::Failed(err) => {
+                let text = Span::styled(err.as_str(), Tone::Bad.style());
+                let widget = SimpleText::new(text).alignment(Alignment::Center);
+                frame.render_widget(widget, block_area.lines_from_top(1));
+                return;
+            }
+            PanelState::Loaded(stats) => stats,
+        };
+
+        let layout = SimpleLayout::new(Direction::Vertical).margin(1).split(
+            block_area,
+            [BasicConstraint::Length(2), BasicConstraint::Percentage(100)],
+        );
+
+        Self::draw_summary(stats, layout[0], frame);
+        self.draw_status_table(stats, layout[1], frame);
+    }
+}
+
+impl Component for ProfilePanel {
+    type State = UIState;
+    type KeyResult = ShouldReset;
+
+    fn process_key(&mut self, key: Key, _: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => ShouldReset::Yes,
+            _ => ShouldReset::No,
+        }
+    }
+}
+
+enum PanelState {
+    Loading,
+    Loaded(UserStats),
+    Failed(String),
+}
@@ -0,0 +1,278 @@
+use super::ShouldReset;
+use crate::series::alias::SeriesAlias;
+use crate::tui::component::input::{Input, InputFlags};
+use crate::tui::locale::Text;
+use crate::tui::state::{SharedState, UIState};
+use crate::tui::theme::Tone;
+use crate::{key::Key, tui::component::Component};
+use anyhow::{Context, Result};
+use crossterm::event::KeyCode;
+use std::collections::HashMap;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui::{backend::Backend, text::Span};
+use tui_utils::{
+    helpers::{block, text},
+    layout::{BasicConstraint, RectExt, SimpleLayout},
+    list::WrappedSelection,
+    widgets::{SimpleList, SimpleText},
+};
+
+/// The most series that are listed at once, since the rest would be too far down to be worth scrolling to.
+const MAX_MATCHES: usize = 100;
+
+/// A panel to jump to any series by typing part of its nickname, title, or one of its aliases, even if the series
+/// list is filtered.
+pub struct QuickSwitchPanel {
+    state: SharedState,
+    query: Input,
+    candidates: Vec<Candidate>,
+    matches: WrappedSelection<Vec<Match>, Match>,
+}
+
+/// A series that can be switched to, along with every name it can be found by.
+struct Candidate {
+    id: i32,
+    label: String,
+    names: Vec<String>,
+}
+
+struct Match {
+    id: i32,
+    label: String,
+}
+
+impl QuickSwitchPanel {
+    pub fn init(state: &UIState, shared_state: SharedState) -> Result<Self> {
+        let mut aliases = HashMap::<_, Vec<_>>::new();
+
+        for (id, alias) in SeriesAlias::all(&state.db).context("loading aliases")? {
+            aliases.entry(id).or_default().push(alias);
+        }
+
+        let candidates = state
+            .series
+            .iter()
+            .map(|series| {
+                let id = series.config().id;
+                let nickname = series.nickname().to_string();
+                let title = series.data().map(|data| data.title().to_string());
+
+                let label = match &title {
+                    Some(title) => format!("{} - {}", nickname, title),
+                    None => nickname.clone(),
+                };
+
+                let mut names = vec![nickname];
+                names.extend(title);
+                names.extend(aliases.remove(&id).unwrap_or_default());
+
+                Candidate { id, label, names }
+            })
+            .collect();
+
+        let mut panel = Self {
+            state: shared_state,
+            query: Input::new(InputFlags::SELECTED, Text::QuickSwitchSearch.get()),
+            candidates,
+            matches: WrappedSelection::new(Vec::new()),
+        };
+
+        panel.update_matches();
+        Ok(panel)
+    }
+
+    /// List the series that match the query, with the best matches first.
+    ///
+    /// Every series is listed in the same order as the series list while nothing has been typed.
+    fn update_matches(&mut self) {
+        let query = self.query.text().to_lowercase();
+
+        let mut scored = self
+            .candidates
+            .iter()
+            .filter_map(|candidate| {
+                let score = candidate
+                    .names
+                    .iter()
+                    .filter_map(|name| fuzzy_score(&query, name))
+                    .max()?;
+
+                Some((score, candidate))
+            })
+            .collect::<Vec<_>>();
+
+        // The sort is stable, so series that match equally well stay in the order of the series list
+        scored.sort_by(|(left, _), (right, _)| right.cmp(left));
+
+        let matches = scored
+            .into_iter()
+            .take(MAX_MATCHES)
+            .map(|(_, candidate)| Match {
+                id: candidate.id,
+                label: candidate.label.clone(),
+            })
+            .collect();
+
+        self.matches = WrappedSelection::new(matches);
+    }
+
+    /// Select the highlighted series in the series list, and play its next episode if `play` is true.
+    fn switch(&self, play: bool, state: &mut UIState) -> Result<()> {
+        let selected = match self.matches.selected() {
+            Some(selected) => selected,
+            None => return Ok(()),
+        };
+
+        if !state.select_series_by_id(selected.id) {
+            return Ok(());
+        }
+
+        if play {
+            state.play_next_series_episode(&self.state, &[])?;
+        }
+
+        Ok(())
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = block::with_borders(Text::QuickSwitchPanel.get());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let vert_split = SimpleLayout::new(Direction::Vertical)
+            .horizontal_margin(2)
+            .vertical_margin(1)
+            .split(
+                block_area,
+                [
+                    // Search input
+                    BasicConstraint::Length(Input::DRAW_LINES_REQUIRED),
+                    // Matching series
+                    BasicConstraint::MinLenRemaining(1, 1),
+                    // Hint text
+                    BasicConstraint::Length(1),
+                ],
+            );
+
+        self.query.draw(vert_split[0], frame);
+
+        if self.matches.is_empty() {
+            let text = text::italic_with(Text::NoMatchingSeries.get(), |s| Tone::Faded.apply(s));
+            let widget = SimpleText::new(text).alignment(Alignment::Center);
+            frame.render_widget(widget, vert_split[1].lines_from_top(1));
+        } else {
+            let labels = self
+                .matches
+                .iter()
+                .map(|series| Span::raw(series.label.as_str()));
+
+            let list = SimpleList::new(labels)
+                .highlight_symbol(text::italic_with(">", |s| Tone::Good.apply(s)))
+                .select(Some(self.matches.index() as u16));
+
+            frame.render_widget(list, vert_split[1]);
+        }
+
+        let horiz_layout =
+            SimpleLayout::new(Direction::Horizontal).split_evenly(vert_split[2].lines_from_top(1));
+
+        let hint_text = text::hint(Text::SwitchHint.get());
+        let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.left);
+
+        let hint_text = text::hint(Text::SwitchAndPlayHint.get());
+        let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.right);
+    }
+}
+
+impl Component for QuickSwitchPanel {
+    type State = UIState;
+    type KeyResult = Result<ShouldReset>;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => Ok(ShouldReset::Yes),
+            KeyCode::Enter => {
+                self.switch(key.alt_pressed(), state)?;
+                Ok(ShouldReset::Yes)
+            }
+            KeyCode::Up => {
+                self.matches.dec_selected();
+                Ok(ShouldReset::No)
+            }
+            KeyCode::Down => {
+                self.matches.inc_selected();
+                Ok(ShouldReset::No)
+            }
+            _ => {
+                self.query.process_key(key);
+                self.update_matches();
+                Ok(ShouldReset::No)
+            }
+        }
+    }
+}
+
+/// Returns how well `query` matches `name`, or None if the characters of `query` don't all appear in `name` in the
+/// same order.
+///
+/// `query` must be lowercase. Characters that follow the previous matching one, or start a word, are worth more.
+fn fuzzy_score(query: &str, name: &str) -> Option<u32> {
+    let mut wanted = query.chars().peekable();
+    let mut score = 0;
+    let mut last_matched = false;
+    let mut last_char = None;
+
+    for ch in name.chars().flat_map(char::to_lowercase) {
+        let next = match wanted.peek() {
+            Some(&next) => next,
+            None => break,
+        };
+
+        if ch == next {
+            let starts_word = last_char.map_or(true, |last: char| !last.is_alphanumeric());
+
+            score += 1;
+            score += if last_matched { 3 } else { 0 };
+            score += if starts_word { 2 } else { 0 };
+
+            wanted.next();
+            last_matched = true;
+        } else {
+            last_matched = false;
+        }
+
+        last_char = Some(ch);
+    }
+
+    if wanted.peek().is_some() {
+        return None;
+    }
+
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_score_prefers_close_matches() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert_eq!(fuzzy_score("xyz", "kaguya"), None);
+        assert_eq!(fuzzy_score("ak", "kaguya"), None);
+
+        let word_starts = fuzzy_score("ygo", "yuru camp gochiusa").unwrap();
+        let scattered = fuzzy_score("ygo", "yagate kimi ni naru sono").unwrap();
+        assert!(word_starts > scattered);
+
+        let together = fuzzy_score("camp", "yuru camp").unwrap();
+        let apart = fuzzy_score("camp", "cat ambush party").unwrap();
+        assert!(together > apart);
+
+        assert_eq!(fuzzy_score("kag", "Kaguya"), fuzzy_score("kag", "kaguya"));
+    }
+}
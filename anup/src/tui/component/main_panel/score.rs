@@ -0,0 +1,134 @@
+use super::ShouldReset;
+use crate::remote::ensure_scores_supported;
+use crate::tui::component::input::{Input, InputFlags};
+use crate::tui::locale::Text;
+use crate::tui::state::UIState;
+use crate::{key::Key, tui::component::Component};
+use anime::remote::ScoreParser;
+use anyhow::{anyhow, Result};
+use crossterm::event::KeyCode;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui_utils::{
+    helpers::{block, text},
+    layout::{BasicConstraint, RectExt, SimpleLayout},
+    widgets::SimpleText,
+};
+
+/// A panel to score the selected series, such as when it was just completed.
+pub struct ScorePanel {
+    title: String,
+    score_input: Input,
+    /// Indicates whether the series was just completed, so its summary should be shown afterwards.
+    completed: bool,
+}
+
+impl ScorePanel {
+    pub fn init(state: &UIState, completed: bool) -> Result<Self> {
+        let series = state
+            .series
+            .selected()
+            .and_then(|series| series.data())
+            .ok_or_else(|| anyhow!("no valid series selected"))?;
+
+        let remote = state.remote.get_logged_in()?;
+        ensure_scores_supported(remote)?;
+
+        let score = match series.entry.score() {
+            Some(score) => remote.score_to_str(score as u8).into_owned(),
+            None => String::new(),
+        };
+
+        Ok(Self {
+            title: Text::ScorePanel.format(&[series.title()]),
+            score_input: Input::with_text(InputFlags::SELECTED, Text::Score.get(), score),
+            completed,
+        })
+    }
+
+    #[inline(always)]
+    pub fn completed(&self) -> bool {
+        self.completed
+    }
+
+    fn apply(&self, state: &mut UIState) -> Result<()> {
+        let series = state
+            .series
+            .get_valid_sel_series_mut()
+            .ok_or_else(|| anyhow!("no valid series selected"))?;
+
+        let remote = state.remote.get_logged_in()?;
+        let current = series.data.entry.score().map(|score| score as u8);
+
+        let score = match remote.parse_relative_score(self.score_input.text(), current) {
+            Some(0) => None,
+            Some(score) => Some(score),
+            None => return Err(anyhow!("invalid score")),
+        };
+
+        series.data.entry.set_score(score.map(i16::from));
+        series.data.sync_to_remote(remote, &state.db)?;
+        series.save(&state.db)?;
+
+        Ok(())
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = block::with_borders(self.title.as_str());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let vert_split = SimpleLayout::new(Direction::Vertical)
+            .horizontal_margin(2)
+            .vertical_margin(1)
+            .split(
+                block_area,
+                [
+                    // Score input
+                    BasicConstraint::Length(Input::DRAW_LINES_REQUIRED),
+                    // Spacer
+                    BasicConstraint::MinLenRemaining(1, 1),
+                    // Hint text
+                    BasicConstraint::Length(1),
+                ],
+            );
+
+        self.score_input.draw(vert_split[0], frame);
+
+        let horiz_layout =
+            SimpleLayout::new(Direction::Horizontal).split_evenly(vert_split[2].lines_from_top(1));
+
+        let hint_text = text::hint(Text::SkipHint.get());
+        let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.left);
+
+        let hint_text = text::hint(Text::ConfirmHint.get());
+        let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.right);
+    }
+}
+
+impl Component for ScorePanel {
+    type State = UIState;
+    type KeyResult = Result<ShouldReset>;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => Ok(ShouldReset::Yes),
+            KeyCode::Enter => match self.apply(state) {
+                Ok(()) => Ok(ShouldReset::Yes),
+                Err(err) => {
+                    self.score_input.set_error(true);
+                    Err(err)
+                }
+            },
+            _ => {
+                self.score_input.process_key(key);
+                self.score_input.set_error(false);
+                Ok(ShouldReset::No)
+            }
+        }
+    }
+}
@@ -1,9 +1,10 @@
 use crate::series::SeriesParams;
 use crate::tui::component::Component;
+use crate::tui::locale::Text;
+use crate::tui::theme::Tone;
 use crate::{key::Key, series::info::SeriesInfo};
 use crossterm::event::KeyCode;
 use tui::layout::Rect;
-use tui::style::Color;
 use tui::terminal::Frame;
 use tui::{backend::Backend, text::Span};
 use tui_utils::{
@@ -27,8 +28,19 @@ impl SelectSeriesPanel {
         self.state.params
     }
 
+    #[inline(always)]
+    pub fn cached(&self) -> bool {
+        self.state.cached
+    }
+
     pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
-        let block = block::with_borders("Select a series from the list");
+        let title = if self.state.cached {
+            Text::SelectCachedSeriesPanel
+        } else {
+            Text::SelectSeriesPanel
+        };
+
+        let block = block::with_borders(title.get());
         let block_area = block.inner(rect);
 
         frame.render_widget(block, rect);
@@ -40,7 +52,7 @@ impl SelectSeriesPanel {
             .map(|info| Span::raw(info.title_preferred.as_str()));
 
         let items = SimpleList::new(names)
-            .highlight_symbol(text::italic_with(">", |s| s.fg(Color::Green)))
+            .highlight_symbol(text::italic_with(">", |s| Tone::Good.apply(s)))
             .select(Some(self.state.series_list.index() as u16));
 
         frame.render_widget(items, block_area);
@@ -80,6 +92,8 @@ impl Component for SelectSeriesPanel {
 pub struct SelectState {
     pub series_list: WrappedSelection<Vec<SeriesInfo>, SeriesInfo>,
     pub params: SeriesParams,
+    /// Whether or not the series to select from are previous search results.
+    pub cached: bool,
 }
 
 impl SelectState {
@@ -87,6 +101,7 @@ impl SelectState {
         Self {
             series_list: WrappedSelection::new(series_list),
             params,
+            cached: false,
         }
     }
 }
@@ -0,0 +1,184 @@
+use super::ShouldReset;
+use crate::database::Database;
+use crate::series::config::SeriesConfig;
+use crate::series::{self, info::SeriesInfo, SeriesParams};
+use crate::tui::locale::Text;
+use crate::tui::state::UIState;
+use crate::tui::theme::Tone;
+use crate::{key::Key, tui::component::Component};
+use anime::local::SortedEpisodes;
+use anyhow::Result;
+use crossterm::event::KeyCode;
+use std::array::IntoIter;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui_utils::{
+    helpers::{block, text},
+    layout::{RectExt, SimpleLayout},
+    widgets::{Fragment, OverflowMode, SimpleText, SpanOptions, TextFragments},
+    wrap,
+};
+
+/// A panel shown when the episodes of a series being added continue into its direct sequel, like a split-cour
+/// series numbered as one season.
+///
+/// Both series can be added at once, with the sequel offset past the episodes of the first.
+pub struct SplitCourPanel {
+    info: SeriesInfo,
+    sequel: SeriesInfo,
+    params: SeriesParams,
+    episodes: Option<SortedEpisodes>,
+    sequel_name: String,
+    warning_text: String,
+    only_first_hint: String,
+}
+
+impl SplitCourPanel {
+    pub fn new(
+        info: SeriesInfo,
+        sequel: SeriesInfo,
+        params: SeriesParams,
+        episodes: Option<SortedEpisodes>,
+        db: &Database,
+    ) -> Self {
+        let sequel_name = Self::sequel_nickname(&sequel, &params.name, db);
+
+        let warning_text =
+            Text::ContinuesInto.format(&[&info.title_preferred, &sequel.title_preferred]);
+        let only_first_hint = Text::AddOnlyFirstHint.format(&[&params.name]);
+
+        Self {
+            info,
+            sequel,
+            params,
+            episodes,
+            sequel_name,
+            warning_text,
+            only_first_hint,
+        }
+    }
+
+    /// Returns a nickname for the sequel that isn't used by any series, including the one it's being added with.
+    fn sequel_nickname(sequel: &SeriesInfo, name: &str, db: &Database) -> String {
+        series::generate_nickname(sequel.title_preferred.to_string())
+            .filter(|nickname| {
+                nickname != name && SeriesConfig::nickname_exists(db, nickname).is_none()
+            })
+            .unwrap_or_else(|| format!("{}_part2", name))
+    }
+
+    /// Add the series and its sequel, with the sequel's episodes renumbered to start after the last one of the series.
+    fn add_both(&mut self, state: &mut UIState) -> Result<()> {
+        let config = SeriesConfig::new(self.info.id, self.params.clone(), &state.db)?;
+
+        let mut sequel_params = self.params.clone();
+        sequel_params.name.clone_from(&self.sequel_name);
+
+        let mut sequel_config = SeriesConfig::new(self.sequel.id, sequel_params, &state.db)?;
+        sequel_config.episode_offset = i32::from(self.info.episodes);
+
+        state.add_series(config, self.info.clone(), self.episodes.take())?;
+        state.add_series(sequel_config, self.sequel.clone(), None)?;
+
+        // The first series is where watching starts, so it's selected over the sequel that was added last
+        let index = state
+            .series
+            .iter()
+            .position(|series| series.nickname() == self.params.name);
+
+        if let Some(index) = index {
+            state.series.set_selected(index);
+            state.init_selected_series();
+        }
+
+        Ok(())
+    }
+
+    fn add_only_first(&mut self, state: &mut UIState) -> Result<()> {
+        let config = SeriesConfig::new(self.info.id, self.params.clone(), &state.db)?;
+        state.add_series(config, self.info.clone(), self.episodes.take())
+    }
+
+    fn draw_hints<B: Backend>(&self, rect: Rect, frame: &mut Frame<B>) {
+        let horiz_layout =
+            SimpleLayout::new(Direction::Horizontal).split_evenly(rect.lines_from_bottom(1));
+
+        let hint_text = text::hint(Text::AddBothHint.get());
+        let hint_widget = SimpleText::new(hint_text).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.left);
+
+        let hint_text = text::hint(&self.only_first_hint);
+        let hint_widget = SimpleText::new(hint_text)
+            .alignment(Alignment::Center)
+            .overflow(OverflowMode::Truncate);
+        frame.render_widget(hint_widget, horiz_layout.right);
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = block::with_borders(Text::SplitCourPanel.get());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let vert_fields = SimpleLayout::new(Direction::Vertical)
+            .horizontal_margin(2)
+            .vertical_margin(1)
+            .split_quarters(block_area);
+
+        let warning_text = text::bold_with(&self.warning_text, |s| Tone::Warning.apply(s));
+        let warning_widget = SimpleText::new(warning_text)
+            .alignment(Alignment::Center)
+            .overflow(OverflowMode::Truncate);
+
+        frame.render_widget(warning_widget, vert_fields.first);
+
+        let total = self.info.episodes;
+        let sequel_total = total + self.sequel.episodes;
+
+        let first_range = Text::EpisodeRange.format(&[&self.params.name, &1, &total]);
+        let sequel_range =
+            Text::EpisodeRange.format(&[&self.sequel_name, &(total + 1), &sequel_total]);
+
+        let series_fragments = [
+            Fragment::span(text::bold(Text::AddBothAs.get())),
+            Fragment::Line,
+            Fragment::Span(
+                text::italic(&first_range),
+                SpanOptions::new().overflow(OverflowMode::Truncate),
+            ),
+            Fragment::Line,
+            Fragment::Span(
+                text::italic(&sequel_range),
+                SpanOptions::new().overflow(OverflowMode::Truncate),
+            ),
+        ];
+
+        let wrapped = wrap::by_letters(IntoIter::new(series_fragments), vert_fields.second.width);
+        let series_widget = TextFragments::new(&wrapped).alignment(Alignment::Center);
+
+        frame.render_widget(series_widget, vert_fields.second);
+
+        self.draw_hints(vert_fields.fourth, frame);
+    }
+}
+
+impl Component for SplitCourPanel {
+    type State = UIState;
+    type KeyResult = Result<ShouldReset>;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => Ok(ShouldReset::Yes),
+            KeyCode::Enter => {
+                self.add_both(state)?;
+                Ok(ShouldReset::Yes)
+            }
+            KeyCode::Char('o') => {
+                self.add_only_first(state)?;
+                Ok(ShouldReset::Yes)
+            }
+            _ => Ok(ShouldReset::No),
+        }
+    }
+}
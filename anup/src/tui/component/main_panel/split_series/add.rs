@@ -5,6 +5,8 @@ use crate::tui::component::input::{
     DrawInput, Input, InputFlags, NameInput, ParsedValue, ValidatedInput,
 };
 use crate::tui::component::Component;
+use crate::tui::locale::Text;
+use crate::tui::theme::Tone;
 use crate::tui::UIState;
 use crate::{key::Key, series::config::SeriesConfig};
 use anime::local::EpisodeParser;
@@ -13,7 +15,6 @@ use anyhow::Result;
 use crossterm::event::KeyCode;
 use tui::backend::Backend;
 use tui::layout::{Alignment, Direction, Rect};
-use tui::style::Color;
 use tui::terminal::Frame;
 use tui_utils::{
     helpers::{block, text},
@@ -42,7 +43,7 @@ impl AddPanel {
     pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
         let data = try_opt_ret!(&self.data);
 
-        let block = block::with_borders("Enter Name For Series");
+        let block = block::with_borders(Text::SplitNamePanel.get());
         let block_area = block.inner(rect);
 
         frame.render_widget(block, rect);
@@ -83,7 +84,7 @@ impl AddPanel {
         self.name_input.draw(name_layout[1], frame);
 
         if let Some(error) = self.name_input.error() {
-            let error_text = text::bold_with(error, |s| s.fg(Color::Red));
+            let error_text = text::bold_with(error, |s| Tone::Bad.apply(s));
             let error_widget = SimpleText::new(error_text)
                 .alignment(Alignment::Center)
                 .overflow(OverflowMode::Truncate);
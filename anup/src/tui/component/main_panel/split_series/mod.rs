@@ -1,6 +1,7 @@
 mod add;
 mod split;
 
+use crate::tui::locale::Text;
 use crate::tui::UIState;
 use crate::util::arc_mutex;
 use crate::{config::Config, key::Key};
@@ -12,7 +13,8 @@ use crate::{
 };
 use crate::{tui::component::Component, util::ArcMutex};
 use add::AddPanel;
-use anime::local::{CategorizedEpisodes, SortedEpisodes};
+use anime::local::{CategorizedEpisodes, Episode, SortedEpisodes};
+use anime::numbering::{NumberedSeries, SeasonNumbering};
 use anime::remote::{Remote, RemoteService, SeriesInfo as RemoteInfo};
 use anime::SeriesKind;
 use anyhow::{anyhow, Context, Result};
@@ -81,7 +83,7 @@ impl SplitSeriesPanel {
             let merged_series = match MergedSeries::resolve(series, remote, &state.config) {
                 Ok(merged) => merged,
                 Err(err) => {
-                    state.get_mut().log.push_error(&err);
+                    state.get_mut().push_error(&err);
                     return;
                 }
             };
@@ -97,14 +99,14 @@ impl SplitSeriesPanel {
     where
         B: Backend,
     {
-        let outline = block::with_borders("Split Series");
+        let outline = block::with_borders(Text::SplitSeriesPanel.get());
         frame.render_widget(outline, rect);
 
         let layout = SimpleLayout::new(Direction::Vertical)
             .margin(1)
             .split_evenly(rect);
 
-        let text = text::bold("Loading..");
+        let text = text::bold(Text::Loading.get());
         let widget = SimpleText::new(text).alignment(Alignment::Center);
 
         frame.render_widget(widget, layout.right);
@@ -134,7 +136,7 @@ impl Component for SplitSeriesPanel {
                 Ok(SplitResult::Ok) => Ok(SplitPanelResult::Ok),
                 Ok(SplitResult::Reset) => Ok(SplitPanelResult::Reset),
                 Ok(SplitResult::AddSeries(info, path)) => {
-                    let add_panel = AddPanel::new(info, path);
+                    let add_panel = AddPanel::new(*info, path);
                     let split_panel = mem::take(split_panel);
 
                     *panel_state = PanelState::AddingSeries(add_panel.into(), split_panel);
@@ -201,7 +203,7 @@ impl MergedSeries {
 
         let base_info = remote.search_info_by_id(data.info.id as u32)?;
 
-        if base_info.sequels.is_empty() {
+        if base_info.sequels.is_empty() && base_info.prequels.is_empty() {
             return Ok(Vec::new());
         }
 
@@ -227,22 +229,118 @@ impl MergedSeries {
                 continue;
             }
 
+            Self::resolve_related(
+                &base_info,
+                *cat,
+                &data.config.path,
+                remote,
+                eps,
+                config,
+                &mut results,
+            );
+        }
+
+        if let Some(eps) = episodes.get(&SeriesKind::Season) {
+            Self::resolve_episode_zero(
+                &base_info,
+                &episodes,
+                &data.config.path,
+                remote,
+                eps,
+                config,
+                &mut results,
+            );
+        }
+
+        Ok(results)
+    }
+
+    /// Resolve every related series of the specified `kind`.
+    ///
+    /// When there are several related series of the same kind, `episodes` are allocated to each of them in order.
+    /// For example, with two single-episode OVA's, "OVA 1" will go to the first and "OVA 2" to the second.
+    fn resolve_related(
+        base_info: &RemoteInfo,
+        kind: SeriesKind,
+        base_path: &SeriesPath,
+        remote: &Remote,
+        episodes: &SortedEpisodes,
+        config: &Config,
+        results: &mut Vec<Self>,
+    ) {
+        let highest_episode = episodes.highest_episode_number();
+        let mut numbering = SeasonNumbering::default();
+
+        for sequel in base_info.sequels_by_kind(kind) {
             thread::sleep(Duration::from_millis(250));
 
-            let sequel_info = if let Ok(info) = remote.search_info_by_id(sequel.id) {
+            let info = if let Ok(info) = remote.search_info_by_id(sequel.id) {
                 info
             } else {
                 results.push(Self::Failed(sequel.kind));
                 continue;
             };
 
+            numbering.push(info.id, info.episodes);
+
             let resolved =
-                ResolvedSeries::new(sequel_info, data.config.path.clone(), eps, 0, config);
+                ResolvedSeries::new(info, base_path.clone(), episodes, &numbering, config);
 
             results.push(Self::resolved(resolved));
+
+            if numbering.total_episodes() >= highest_episode {
+                break;
+            }
         }
+    }
 
-        Ok(results)
+    /// Resolve an "episode 0" found alongside the seasonal episodes of a series.
+    ///
+    /// These are almost always an OVA, special, or movie that is bundled with the season, so it is allocated
+    /// to the first related series of one of those kinds that doesn't already have episodes of its own in the folder.
+    fn resolve_episode_zero(
+        base_info: &RemoteInfo,
+        categorized: &CategorizedEpisodes,
+        base_path: &SeriesPath,
+        remote: &Remote,
+        season_episodes: &SortedEpisodes,
+        config: &Config,
+        results: &mut Vec<Self>,
+    ) {
+        const EPISODE_ZERO_KINDS: [SeriesKind; 3] =
+            [SeriesKind::OVA, SeriesKind::Special, SeriesKind::Movie];
+
+        let episode = match season_episodes.find(0) {
+            Some(episode) => episode,
+            None => return,
+        };
+
+        let sequel = EPISODE_ZERO_KINDS
+            .iter()
+            .filter(|&kind| categorized.get(kind).is_none())
+            .find_map(|&kind| base_info.related_by_kind(kind));
+
+        let sequel = match sequel {
+            Some(sequel) => sequel,
+            None => return,
+        };
+
+        thread::sleep(Duration::from_millis(250));
+
+        let info = if let Ok(info) = remote.search_info_by_id(sequel.id) {
+            info
+        } else {
+            results.push(Self::Failed(sequel.kind));
+            return;
+        };
+
+        // The episode needs to be renumbered so it maps to the first episode of the related series
+        let episodes =
+            SortedEpisodes::with_episodes(vec![Episode::new(1, episode.filename.clone())]);
+
+        let numbering = SeasonNumbering::new(vec![NumberedSeries::new(info.id, info.episodes)]);
+        let resolved = ResolvedSeries::new(info, base_path.clone(), &episodes, &numbering, config);
+        results.push(Self::resolved(resolved));
     }
 
     fn resolve_merged_season(
@@ -261,30 +359,31 @@ impl MergedSeries {
             return;
         }
 
-        let mut episode_offset = info.episodes;
+        let mut numbering = SeasonNumbering::new(vec![NumberedSeries::new(info.id, info.episodes)]);
 
         while let Some(sequel) = info.direct_sequel() {
             info = if let Ok(info) = remote.search_info_by_id(sequel.id) {
                 info.into()
             } else {
+                // The rest of the seasons can't be numbered without this one
                 results.push(Self::Failed(sequel.kind));
-                continue;
+                break;
             };
 
+            numbering.push(info.id, info.episodes);
+
             let resolved = ResolvedSeries::new(
                 info.clone().into_owned(),
                 base_path.clone(),
                 episodes,
-                episode_offset,
+                &numbering,
                 config,
             );
 
             results.push(Self::resolved(resolved));
 
-            episode_offset += info.episodes;
-
             // We can stop if we don't have anymore sequels or if we don't have enough episodes locally to have another merged season
-            if episode_offset > highest_episode || info.direct_sequel().is_none() {
+            if numbering.total_episodes() > highest_episode || info.direct_sequel().is_none() {
                 break;
             }
 
@@ -308,8 +407,6 @@ impl MergedSeries {
     }
 }
 
-pub type EpisodeOffset = u32;
-
 struct ResolvedSeries {
     info: RemoteInfo,
     base_dir: SeriesPath,
@@ -322,10 +419,10 @@ impl ResolvedSeries {
         info: RemoteInfo,
         base_dir: SeriesPath,
         episodes: &SortedEpisodes,
-        offset: EpisodeOffset,
+        numbering: &SeasonNumbering,
         config: &Config,
     ) -> Self {
-        let actions = SplitAction::from_merged_seasons(&info, episodes, offset);
+        let actions = SplitAction::from_merged_seasons(&info, episodes, numbering);
         let out_dir = PathBuf::from(&info.title.preferred);
         let out_dir = SeriesPath::new(out_dir, config);
 
@@ -398,17 +495,14 @@ impl SplitAction {
     fn from_merged_seasons(
         info: &RemoteInfo,
         episodes: &SortedEpisodes,
-        offset: EpisodeOffset,
+        numbering: &SeasonNumbering,
     ) -> Vec<Self> {
         let mut actions = Vec::new();
 
-        let sequel_start = 1 + offset;
-        let sequel_end = offset + info.episodes;
-
-        for real_ep_num in sequel_start..=sequel_end {
-            let episode = match episodes.find(real_ep_num) {
-                Some(episode) => episode,
-                None => continue,
+        for episode in episodes.iter() {
+            let relative_ep_num = match numbering.to_relative(episode.number) {
+                Some((id, relative)) if id == info.id => relative,
+                _ => continue,
             };
 
             let extension = PathBuf::from(&episode.filename).extension().map_or_else(
@@ -418,9 +512,7 @@ impl SplitAction {
 
             let new_filename = format!(
                 "{} - {:02}{}",
-                info.title.preferred,
-                real_ep_num - offset,
-                extension
+                info.title.preferred, relative_ep_num, extension
             );
 
             let action = Self::new(&episode.filename, new_filename);
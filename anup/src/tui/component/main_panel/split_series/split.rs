@@ -1,16 +1,17 @@
 use super::MergedSeries;
 use crate::tui::component::Component;
+use crate::tui::locale::Text;
+use crate::tui::theme::Tone;
 use crate::tui::UIState;
 use crate::{key::Key, series::SeriesPath};
 use anime::remote::SeriesInfo as RemoteInfo;
 use anyhow::Result;
 use crossterm::event::KeyCode;
 use tui::layout::{Alignment, Direction, Rect};
-use tui::style::Color;
 use tui::terminal::Frame;
 use tui::{backend::Backend, text::Span};
 use tui_utils::{
-    helpers::{block, color, style, text},
+    helpers::{block, style, text},
     layout::{BasicConstraint, SimpleLayout},
     list::WrappingIndex,
     widgets::{SimpleTable, SimpleText},
@@ -36,28 +37,35 @@ impl SplitPanel {
     where
         B: Backend,
     {
-        let row_color = color::either(self.has_split_series, Color::Blue, Color::Yellow);
+        let row_tone = if self.has_split_series {
+            Tone::Accent
+        } else {
+            Tone::Warning
+        };
 
         let rows = self.merged_series.iter().map(|merged| match merged {
             &MergedSeries::Failed(kind) => {
                 let kind: &'static str = kind.into();
 
                 [
-                    text::with_color(kind, Color::Red),
-                    text::with_color("Failed..", Color::Red),
+                    Span::styled(kind, Tone::Bad.style()),
+                    Span::styled(Text::LoadingFailed.get(), Tone::Bad.style()),
                 ]
             }
             MergedSeries::Resolved(series) => {
                 let kind: &'static str = series.info.kind.into();
 
                 [
-                    text::with_color(kind, row_color),
-                    text::with_color(series.info.title.preferred.as_str(), row_color),
+                    Span::styled(kind, row_tone.style()),
+                    Span::styled(series.info.title.preferred.as_str(), row_tone.style()),
                 ]
             }
         });
 
-        let header = [Span::raw("Type"), Span::raw("Series")];
+        let header = [
+            Span::raw(Text::SplitType.get()),
+            Span::raw(Text::SeriesPanel.get()),
+        ];
         let layout = [BasicConstraint::Length(8), BasicConstraint::Percentage(100)];
 
         let table = SimpleTable::new(rows, layout)
@@ -75,7 +83,8 @@ impl SplitPanel {
             .split_evenly(area)
             .right;
 
-        let msg = SimpleText::new(text::bold("No Series To Split")).alignment(Alignment::Center);
+        let msg =
+            SimpleText::new(text::bold(Text::NoSeriesToSplit.get())).alignment(Alignment::Center);
         frame.render_widget(msg, center);
     }
 
@@ -102,10 +111,12 @@ impl SplitPanel {
 
         let hint_layout = SimpleLayout::new(Direction::Horizontal).split_evenly(vert_split[1]);
 
-        let hint = SimpleText::new(text::hint("S - Split All")).alignment(Alignment::Center);
+        let hint =
+            SimpleText::new(text::hint(Text::SplitAllHint.get())).alignment(Alignment::Center);
         frame.render_widget(hint, hint_layout.left);
 
-        let hint = SimpleText::new(text::hint("Enter - Add Series")).alignment(Alignment::Center);
+        let hint = SimpleText::new(text::hint(Text::AddSplitSeriesHint.get()))
+            .alignment(Alignment::Center);
         frame.render_widget(hint, hint_layout.right);
     }
 }
@@ -139,7 +150,7 @@ impl Component for SplitPanel {
                 };
 
                 Ok(SplitResult::AddSeries(
-                    series.info.clone(),
+                    Box::new(series.info.clone()),
                     series.out_dir.clone(),
                 ))
             }
@@ -163,5 +174,5 @@ impl Component for SplitPanel {
 pub enum SplitResult {
     Ok,
     Reset,
-    AddSeries(RemoteInfo, SeriesPath),
+    AddSeries(Box<RemoteInfo>, SeriesPath),
 }
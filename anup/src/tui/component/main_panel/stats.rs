@@ -0,0 +1,212 @@
+use super::ShouldReset;
+use crate::goal::WeeklyGoal;
+use crate::remote::RemoteStatus;
+use crate::series::LoadedSeries;
+use crate::stats::{Backlog, Stats, NUM_SCORE_BUCKETS};
+use crate::tui::locale::Text;
+use crate::tui::state::UIState;
+use crate::tui::theme::Tone;
+use crate::util;
+use crate::{key::Key, tui::component::Component};
+use anime::remote::ScoreParser;
+use anyhow::Result;
+use crossterm::event::KeyCode;
+use std::borrow::Cow;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui::widgets::BarChart;
+use tui_utils::{
+    helpers::{block, text},
+    layout::{BasicConstraint, SimpleLayout},
+    widgets::{Fragment, SimpleTable, TextFragments},
+};
+
+const SCORE_BUCKET_LABELS: [&str; NUM_SCORE_BUCKETS] =
+    ["10", "20", "30", "40", "50", "60", "70", "80", "90", "100"];
+
+pub struct StatsPanel {
+    stats: Stats,
+    backlog: Backlog,
+    goal: Option<WeeklyGoal>,
+}
+
+impl StatsPanel {
+    pub fn init(state: &UIState) -> Result<Self> {
+        let series = state.series.iter().filter_map(LoadedSeries::data);
+
+        Ok(Self {
+            stats: Stats::load(&state.db, series)?,
+            backlog: Backlog::from_series(state.series.iter().filter_map(LoadedSeries::complete)),
+            goal: WeeklyGoal::load(&state.config, &state.db)?,
+        })
+    }
+
+    fn score_text(score: Option<f32>, state: &UIState) -> Cow<str> {
+        let score = match score {
+            Some(score) => score.round() as u8,
+            None => return "??".into(),
+        };
+
+        match &state.remote {
+            RemoteStatus::LoggedIn(remote) => remote.score_to_str(score),
+            RemoteStatus::LoggingIn(_) => score.to_string().into(),
+        }
+    }
+
+    fn draw_score_summary<B: Backend>(&self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
+        let mean = Self::score_text(self.stats.scores.mean, state);
+        let median = Self::score_text(self.stats.scores.median, state);
+
+        let mut fragments = vec![
+            Fragment::span(text::bold(Text::Mean.get())),
+            Fragment::span(Span::raw(mean)),
+            Fragment::span(text::bold("   Median: ")),
+            Fragment::span(Span::raw(median)),
+            Fragment::span(text::bold("   Rated: ")),
+            Fragment::span(Span::raw(self.stats.scores.total.to_string())),
+            Fragment::span(text::bold("   Time Watched: ")),
+            Fragment::span(Span::raw(util::hm_from_mins(
+                self.stats.watched_mins as f32,
+            ))),
+        ];
+
+        if let Some(goal) = self.goal {
+            let tone = if goal.is_met() {
+                Tone::Good
+            } else {
+                Tone::Accent
+            };
+
+            fragments.push(Fragment::span(text::bold(Text::WeeklyGoal.get())));
+            fragments.push(Fragment::span(Span::styled(goal.to_string(), tone.style())));
+        }
+
+        let widget = TextFragments::new(&fragments).alignment(Alignment::Center);
+        frame.render_widget(widget, rect);
+    }
+
+    fn draw_score_chart<B: Backend>(&self, rect: Rect, frame: &mut Frame<B>) {
+        let data = SCORE_BUCKET_LABELS
+            .iter()
+            .zip(self.stats.scores.buckets.iter())
+            .map(|(&label, &count)| (label, count))
+            .collect::<Vec<_>>();
+
+        let bar_gap = 1;
+        let bar_width = (rect.width / NUM_SCORE_BUCKETS as u16)
+            .saturating_sub(bar_gap)
+            .max(1);
+
+        let chart = BarChart::default()
+            .block(block::with_borders(Text::ScoresPanel.get()))
+            .data(&data)
+            .bar_width(bar_width)
+            .bar_gap(bar_gap)
+            .bar_style(Tone::Accent.style())
+            .value_style(Tone::Accent.inverted());
+
+        frame.render_widget(chart, rect);
+    }
+
+    fn draw_genre_table<B: Backend>(&self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
+        let block = block::with_borders(Text::GenresPanel.get());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let rows = self.stats.genres.iter().map(|genre| {
+            [
+                Span::raw(genre.name.as_str()),
+                Span::raw(util::hm_from_mins(genre.watched_mins as f32)),
+                Span::raw(Self::score_text(genre.mean_score, state)),
+            ]
+        });
+
+        let header = [
+            text::bold(Text::Genre.get()),
+            text::bold(Text::Watched.get()),
+            text::bold(Text::Score.get()),
+        ];
+
+        let layout = [
+            BasicConstraint::Percentage(50),
+            BasicConstraint::Percentage(30),
+            BasicConstraint::Percentage(20),
+        ];
+
+        let table = SimpleTable::new(rows, layout).header(&header);
+        frame.render_widget(table, block_area);
+    }
+
+    fn draw_backlog_table<B: Backend>(&self, rect: Rect, frame: &mut Frame<B>) {
+        let title = Text::BacklogPanel.format(&[
+            &self.backlog.episodes,
+            &util::hm_from_mins(self.backlog.mins as f32),
+        ]);
+
+        let block = block::with_borders(title.as_str());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let rows =
+            self.backlog.series.iter().map(|(nickname, episodes)| {
+                [Span::raw(&**nickname), Span::raw(episodes.to_string())]
+            });
+
+        let header = [
+            text::bold(Text::SeriesPanel.get()),
+            text::bold(Text::Unwatched.get()),
+        ];
+
+        let layout = [
+            BasicConstraint::Percentage(70),
+            BasicConstraint::Percentage(30),
+        ];
+
+        let table = SimpleTable::new(rows, layout).header(&header);
+        frame.render_widget(table, block_area);
+    }
+
+    pub fn draw<B: Backend>(&mut self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
+        let block = block::with_borders(Text::StatsPanel.get());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let layout = SimpleLayout::new(Direction::Vertical).margin(1).split(
+            block_area,
+            [BasicConstraint::Length(2), BasicConstraint::Percentage(100)],
+        );
+
+        self.draw_score_summary(state, layout[0], frame);
+
+        let body = SimpleLayout::new(Direction::Horizontal).split_evenly(layout[1]);
+
+        self.draw_score_chart(body.left, frame);
+        let right = SimpleLayout::new(Direction::Vertical).split(
+            body.right,
+            [
+                BasicConstraint::Percentage(50),
+                BasicConstraint::Percentage(50),
+            ],
+        );
+
+        self.draw_genre_table(state, right[0], frame);
+        self.draw_backlog_table(right[1], frame);
+    }
+}
+
+impl Component for StatsPanel {
+    type State = UIState;
+    type KeyResult = Result<ShouldReset>;
+
+    fn process_key(&mut self, key: Key, _: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => Ok(ShouldReset::Yes),
+            _ => Ok(ShouldReset::No),
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use crate::database::Database;
+use crate::series::config::SeriesConfig;
+use crate::series::SeriesData;
+use crate::tui::locale::Text;
+use crate::tui::state::{SharedState, UIState};
+use crate::util::{self, arc_mutex, ArcMutex, ScopedTask};
+use crate::{key::Key, tui::component::Component};
+use anime::remote::{Remote, RemoteService, SeriesID};
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use crossterm::event::KeyCode;
+use std::borrow::Cow;
+use std::sync::Arc;
+use tokio::task;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::{block, text},
+    layout::{BasicConstraint, RectExt, SimpleLayout},
+    widgets::{Fragment, SimpleText, TextFragments},
+};
+
+/// A panel that summarizes a series that was just completed.
+pub struct SummaryPanel {
+    title: String,
+    time_spent: String,
+    dates: String,
+    pace: String,
+    sequel: ArcMutex<SequelState>,
+    #[allow(dead_code)]
+    fetch_task: Option<ScopedTask<()>>,
+}
+
+impl SummaryPanel {
+    pub fn init(state: &UIState, shared_state: &SharedState) -> Result<Self> {
+        let series = state
+            .series
+            .selected()
+            .and_then(|series| series.data())
+            .ok_or_else(|| anyhow!("no valid series selected"))?;
+
+        let entry = &series.entry;
+
+        let dates = match (entry.start_date(), entry.end_date()) {
+            (None, None) => "??".into(),
+            (start, end) => {
+                let dates = &state.config.dates;
+                format!("{} -> {}", dates.format_date(start), dates.format_date(end))
+            }
+        };
+
+        let prefetched = state
+            .prefetched
+            .get(&series.info.id)
+            .and_then(|prefetched| prefetched.sequel.as_ref());
+
+        let (sequel, fetch_task) = match (prefetched, state.remote.get_logged_in_shared()) {
+            (Some((id, title)), _) => {
+                let sequel = SequelInfo::new(*id, title.clone(), &state.db);
+                (arc_mutex(SequelState::Loaded(Some(sequel))), None)
+            }
+            (None, Ok(remote)) if !remote.is_offline() && !state.config.low_data => {
+                let sequel = arc_mutex(SequelState::Loading);
+                let id = series.info.id as SeriesID;
+                let task = Self::spawn_fetch_task(&sequel, remote, id, shared_state);
+
+                (sequel, Some(task.into()))
+            }
+            _ => (arc_mutex(SequelState::Loaded(None)), None),
+        };
+
+        Ok(Self {
+            title: Text::CompletedPanel.format(&[series.title()]),
+            time_spent: util::hm_from_mins(series.watched_mins() as f32),
+            dates,
+            pace: pace(series).unwrap_or_else(|| "??".into()),
+            sequel,
+            fetch_task,
+        })
+    }
+
+    /// Look up the sequel of the series with the specified `id` without holding up the UI.
+    fn spawn_fetch_task(
+        sequel: &ArcMutex<SequelState>,
+        remote: Arc<Remote>,
+        id: SeriesID,
+        state: &SharedState,
+    ) -> task::JoinHandle<()> {
+        let sequel = Arc::clone(sequel);
+        let state = state.clone();
+
+        task::spawn_blocking(move || {
+            let found = find_sequel(&remote, id);
+            let mut state = state.lock();
+
+            let info = found.map(|(id, title)| SequelInfo::new(id, title, &state.db));
+
+            *sequel.lock() = SequelState::Loaded(info);
+            state.mark_dirty();
+        })
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: Rect, frame: &mut Frame<B>) {
+        let block = block::with_borders(self.title.as_str());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        let vert_split = SimpleLayout::new(Direction::Vertical)
+            .horizontal_margin(2)
+            .vertical_margin(1)
+            .split(
+                block_area,
+                [
+                    // Stats
+                    BasicConstraint::Length(8),
+                    // Spacer
+                    BasicConstraint::MinLenRemaining(1, 1),
+                    // Hint text
+                    BasicConstraint::Length(1),
+                ],
+            );
+
+        let sequel_state = self.sequel.lock();
+
+        let sequel = match &*sequel_state {
+            SequelState::Loading => Text::Loading.get().into(),
+            SequelState::Loaded(Some(sequel)) if sequel.added => {
+                format!("{} (already added)", sequel.title).into()
+            }
+            SequelState::Loaded(Some(sequel)) => Cow::Borrowed(sequel.title.as_str()),
+            SequelState::Loaded(None) => Text::NoSequel.get().into(),
+        };
+
+        let fragments = [
+            Fragment::span(text::bold(Text::TimeSpent.get())),
+            Fragment::Line,
+            Fragment::span(Span::raw(self.time_spent.as_str())),
+            Fragment::Line,
+            Fragment::span(text::bold(Text::Watched.get())),
+            Fragment::Line,
+            Fragment::span(Span::raw(self.dates.as_str())),
+            Fragment::Line,
+            Fragment::span(text::bold(Text::Pace.get())),
+            Fragment::Line,
+            Fragment::span(Span::raw(self.pace.as_str())),
+            Fragment::Line,
+            Fragment::span(text::bold(Text::Sequel.get())),
+            Fragment::Line,
+            Fragment::span(Span::raw(sequel)),
+        ];
+
+        let widget = TextFragments::new(&fragments).alignment(Alignment::Center);
+        frame.render_widget(widget, vert_split[0]);
+
+        let hint_rect = vert_split[2].lines_from_top(1);
+
+        match &*sequel_state {
+            SequelState::Loaded(Some(sequel)) if !sequel.added => {
+                let horiz_layout = SimpleLayout::new(Direction::Horizontal).split_evenly(hint_rect);
+
+                let hint_widget = SimpleText::new(text::hint(Text::AddSequelHint.get()))
+                    .alignment(Alignment::Center);
+                frame.render_widget(hint_widget, horiz_layout.left);
+
+                let hint_widget =
+                    SimpleText::new(text::hint(Text::CloseHint.get())).alignment(Alignment::Center);
+                frame.render_widget(hint_widget, horiz_layout.right);
+            }
+            _ => {
+                let hint_widget =
+                    SimpleText::new(text::hint(Text::CloseHint.get())).alignment(Alignment::Center);
+                frame.render_widget(hint_widget, hint_rect);
+            }
+        }
+    }
+}
+
+impl Component for SummaryPanel {
+    type State = UIState;
+    type KeyResult = SummaryResult;
+
+    fn process_key(&mut self, key: Key, _: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => SummaryResult::Reset,
+            KeyCode::Char('a') => match &*self.sequel.lock() {
+                SequelState::Loaded(Some(sequel)) if !sequel.added => {
+                    SummaryResult::AddSequel(sequel.id)
+                }
+                _ => SummaryResult::Ok,
+            },
+            _ => SummaryResult::Ok,
+        }
+    }
+}
+
+pub enum SummaryResult {
+    Ok,
+    Reset,
+    AddSequel(SeriesID),
+}
+
+/// The sequel of the completed series, which may still be being looked up.
+enum SequelState {
+    Loading,
+    Loaded(Option<SequelInfo>),
+}
+
+struct SequelInfo {
+    id: SeriesID,
+    title: String,
+    /// Indicates whether the sequel has already been added to the program.
+    added: bool,
+}
+
+impl SequelInfo {
+    #[allow(clippy::cast_possible_wrap)]
+    fn new(id: SeriesID, title: String, db: &Database) -> Self {
+        Self {
+            id,
+            title,
+            added: SeriesConfig::id_exists(db, id as i32).is_some(),
+        }
+    }
+}
+
+/// Returns the ID and title of the direct sequel of the series with the specified `id`.
+fn find_sequel(remote: &Remote, id: SeriesID) -> Option<(SeriesID, String)> {
+    let info = remote.search_info_by_id(id).ok()?;
+    let sequel = info.direct_sequel()?;
+    let sequel_info = remote.search_info_by_id(sequel.id).ok()?;
+
+    Some((sequel.id, sequel_info.title.preferred))
+}
+
+/// Returns how quickly the series was watched, based on its start and end date.
+fn pace(series: &SeriesData) -> Option<String> {
+    let start: NaiveDate = series.entry.start_date()?.into();
+    let end: NaiveDate = series.entry.end_date()?.into();
+
+    // Both dates are inclusive
+    let days = (end - start).num_days().max(0) + 1;
+    let episodes = series
+        .info
+        .total_episodes()
+        .unwrap_or_else(|| series.entry.watched_episodes());
+
+    let episodes = f32::from(episodes.max(1));
+
+    let per_day = episodes / days as f32;
+
+    let pace = if per_day >= 1.0 {
+        format!("{:.1} episodes per day", per_day)
+    } else {
+        format!("{:.1} days per episode", 1.0 / per_day)
+    };
+
+    Some(pace)
+}
@@ -0,0 +1,167 @@
+use super::ShouldReset;
+use crate::tui::locale::Text;
+use crate::tui::state::{BackgroundTask, TaskStatus, UIState};
+use crate::tui::theme::Tone;
+use crate::util;
+use crate::{key::Key, tui::component::Component};
+use crossterm::event::KeyCode;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Direction, Rect};
+use tui::style::Style;
+use tui::terminal::Frame;
+use tui::text::Span;
+use tui_utils::{
+    helpers::{block, style, text},
+    layout::{BasicConstraint, RectExt, SimpleLayout},
+    list::WrappingIndex,
+    widgets::{SimpleTable, SimpleText},
+};
+
+/// The width of the progress bar of running tasks, in characters.
+const PROGRESS_WIDTH: usize = 10;
+
+/// A panel that shows the tasks running in the background, along with the results of ones that finished.
+pub struct TasksPanel {
+    selected: WrappingIndex,
+}
+
+impl TasksPanel {
+    pub fn new() -> Self {
+        Self {
+            selected: WrappingIndex::new(0),
+        }
+    }
+
+    pub fn draw<B: Backend>(&mut self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
+        let block = block::with_borders(Text::TasksPanel.get());
+        let block_area = block.inner(rect);
+
+        frame.render_widget(block, rect);
+
+        if state.tasks.is_empty() {
+            let text = text::hint(Text::NoTasks.get());
+            let widget = SimpleText::new(text).alignment(Alignment::Center);
+            frame.render_widget(widget, block_area.lines_from_top(1));
+            return;
+        }
+
+        // Tasks can be cleared or removed once enough newer ones finish
+        self.selected.update_bounds(state.tasks.len());
+
+        let vert_split = SimpleLayout::new(Direction::Vertical).split(
+            block_area,
+            [
+                // Tasks
+                BasicConstraint::MinLenRemaining(1, 1),
+                // Hint text
+                BasicConstraint::Length(1),
+            ],
+        );
+
+        let rows = state.tasks.iter().map(|task| {
+            let (status, tone) = Self::status(task);
+
+            let style = if task.is_running() {
+                Style::default()
+            } else {
+                Tone::Faded.style()
+            };
+
+            [
+                Span::styled(task.label.as_str(), style),
+                Span::styled(Self::progress(task), style),
+                Span::styled(status, tone.style()),
+                Span::styled(Self::result(task), style),
+            ]
+        });
+
+        let header = [
+            text::bold(Text::Task.get()),
+            text::bold(Text::Progress.get()),
+            text::bold(Text::Status.get()),
+            text::bold(Text::Result.get()),
+        ];
+
+        let layout = [
+            BasicConstraint::Percentage(25),
+            BasicConstraint::Percentage(20),
+            BasicConstraint::Percentage(15),
+            BasicConstraint::Percentage(40),
+        ];
+
+        let table = SimpleTable::new(rows, layout)
+            .header(&header)
+            .highlight_symbol(Span::styled(">", style::list_selector(true)))
+            .select(Some(self.selected.get() as u16));
+
+        frame.render_widget(table, vert_split[0]);
+
+        let horiz_layout = SimpleLayout::new(Direction::Horizontal).split_evenly(vert_split[1]);
+
+        let hint_widget =
+            SimpleText::new(text::hint(Text::CancelTaskHint.get())).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.left);
+
+        let hint_widget =
+            SimpleText::new(text::hint(Text::ClearTasksHint.get())).alignment(Alignment::Center);
+        frame.render_widget(hint_widget, horiz_layout.right);
+    }
+
+    fn status(task: &BackgroundTask) -> (&'static str, Tone) {
+        match &task.status {
+            TaskStatus::Running if task.is_cancelling() => {
+                (Text::TaskCancelling.get(), Tone::Warning)
+            }
+            TaskStatus::Running => (Text::TaskRunning.get(), Tone::Accent),
+            TaskStatus::Finished(_) => (Text::TaskDone.get(), Tone::Good),
+            TaskStatus::Cancelled(_) => (Text::TaskCancelledStatus.get(), Tone::Warning),
+            TaskStatus::Failed(_) => (Text::TaskFailed.get(), Tone::Bad),
+        }
+    }
+
+    fn progress(task: &BackgroundTask) -> String {
+        match task.progress {
+            Some((done, total)) if task.is_running() => format!(
+                "{} {}/{}",
+                util::progress_bar(done, total, PROGRESS_WIDTH),
+                done,
+                total
+            ),
+            Some(_) | None => String::new(),
+        }
+    }
+
+    fn result(task: &BackgroundTask) -> &str {
+        match &task.status {
+            TaskStatus::Running => "",
+            TaskStatus::Finished(summary)
+            | TaskStatus::Cancelled(summary)
+            | TaskStatus::Failed(summary) => summary,
+        }
+    }
+}
+
+impl Component for TasksPanel {
+    type State = UIState;
+    type KeyResult = ShouldReset;
+
+    fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
+        match *key {
+            KeyCode::Esc => return ShouldReset::Yes,
+            KeyCode::Up => self.selected.decrement(state.tasks.len()),
+            KeyCode::Down => self.selected.increment(state.tasks.len()),
+            KeyCode::Char('c') => {
+                if let Some(task) = state.tasks.get(self.selected.get()) {
+                    task.cancel();
+                }
+            }
+            KeyCode::Char('x') => {
+                state.tasks.clear_finished();
+                self.selected.update_bounds(state.tasks.len());
+            }
+            _ => (),
+        }
+
+        ShouldReset::No
+    }
+}
@@ -1,20 +1,19 @@
 use super::{Component, ShouldReset};
-use crate::try_opt_r;
 use crate::tui::component::input::{Input, InputFlags};
+use crate::tui::component::prompt::log::LogKind;
+use crate::tui::locale::Text;
+use crate::tui::theme::Tone;
 use crate::tui::UIState;
-use crate::user::{RemoteType, UserInfo};
+use crate::user::{RemoteType, UserAccount, UserInfo};
 use crate::{file::SerializedFile, key::Key};
-use crate::{
-    remote::{RemoteLogin, RemoteStatus},
-    tui::state::SharedState,
-};
-use anime::remote::anilist::AniList;
+use crate::{remote::RemoteStatus, tui::state::SharedState};
+use crate::{try_opt_r, try_opt_ret};
 use anime::remote::{AccessToken, Remote, RemoteService};
 use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
 use crossterm::event::KeyCode;
 use std::process::Command;
 use tui::layout::{Alignment, Direction, Rect};
-use tui::style::Color;
 use tui::terminal::Frame;
 use tui::text::Span;
 use tui::{backend::Backend, style::Style};
@@ -25,6 +24,9 @@ use tui_utils::{
     widgets::{Fragment, SimpleList, SimpleTable, SimpleText, TextFragments},
 };
 
+/// How close a token has to be to expiring before it is highlighted.
+const TOKEN_EXPIRY_WARNING_SECS: i64 = 60 * 60 * 24 * 30;
+
 pub struct UserPanel {
     selected_user: WrappingIndex,
     selected_service: SelectableEnum<RemoteType>,
@@ -38,7 +40,7 @@ impl UserPanel {
         Self {
             selected_user: WrappingIndex::new(0),
             selected_service: SelectableEnum::new(),
-            token_input: Input::new(InputFlags::empty(), "Paste Token"),
+            token_input: Input::new(InputFlags::empty(), Text::PasteToken.get()),
             current_panel: SelectedPanel::SelectUser,
             state,
         }
@@ -60,9 +62,11 @@ impl UserPanel {
 
                 let info = UserInfo::new(service, &auth.user.name);
 
-                state.remote = RemoteStatus::LoggedIn(AniList::Authenticated(auth).into());
-                state.users.add_and_set_last(info, token);
+                state
+                    .users
+                    .add(info.clone(), token.clone(), state.profile.as_str());
                 state.users.save().context("failed to save new user")?;
+                state.switch_to_user(info, token, &self.state)?;
 
                 self.token_input.clear();
                 Ok(())
@@ -70,9 +74,9 @@ impl UserPanel {
         }
     }
 
-    fn selected_user<'a>(&'a self, state: &'a UIState) -> Option<(&'a UserInfo, &'a AccessToken)> {
+    fn selected_user<'a>(&'a self, state: &'a UIState) -> Option<(&'a UserInfo, &'a UserAccount)> {
         let index = self.selected_user.get();
-        state.users.get().iter().nth(index)
+        state.users.sorted().get(index).copied()
     }
 
     fn remove_selected_user(&mut self, state: &mut UIState) -> Result<()> {
@@ -84,11 +88,17 @@ impl UserPanel {
         let remote = state.remote.get_logged_in()?;
 
         if user.is_logged_in(remote) {
-            state.remote = RemoteStatus::LoggedIn(Remote::offline());
+            state.remote = RemoteStatus::LoggedIn(Remote::offline().into());
         }
 
         state.users.remove(&user);
 
+        if let Some(party) = &mut state.watch_party {
+            if party.remove(&user) {
+                party.save().context("failed to save watch party")?;
+            }
+        }
+
         // Since our user table has been changed, we should make sure our selected user is still valid
         self.selected_user.update_bounds(state.users.len());
 
@@ -96,28 +106,46 @@ impl UserPanel {
     }
 
     fn login_as_selected_user(&mut self, state: &mut UIState) -> Result<()> {
-        let (info, token) = try_opt_r!(self.selected_user(state));
+        let (info, account) = try_opt_r!(self.selected_user(state));
+        let info = info.to_owned();
+        let token = account.token.clone();
 
-        match info.service {
-            RemoteType::AniList => {
-                self.state.login_to_remote_async(RemoteLogin::AniList(
-                    info.username.clone(),
-                    token.clone(),
-                ));
+        state.switch_to_user(info, token, &self.state)
+    }
 
-                state.users.last_used = Some(info.to_owned());
-                state.users.save()?;
-            }
-        }
+    fn toggle_selected_watch_party(&mut self, state: &mut UIState) -> Result<()> {
+        let user = {
+            let (user, _) = try_opt_r!(self.selected_user(state));
+            user.to_owned()
+        };
+
+        let party = state
+            .watch_party
+            .as_mut()
+            .ok_or_else(|| anyhow!("the watch party can't be changed in the demo"))?;
+
+        let text = if party.toggle(&user) {
+            Text::JoinedWatchParty
+        } else {
+            Text::LeftWatchParty
+        };
+
+        party.save().context("failed to save watch party")?;
+        state
+            .log
+            .push(LogKind::Info, text.format(&[&user.username]));
 
         Ok(())
     }
 
-    fn open_auth_url(&self) -> Result<()> {
+    fn open_auth_url(&self, state: &mut UIState) -> Result<()> {
         let url = match self.selected_service.selected() {
             RemoteType::AniList => anime::remote::anilist::auth_url(crate::ANILIST_CLIENT_ID),
         };
 
+        // A browser may not be available, such as over SSH, so the URL can be opened on another device instead
+        state.log.push(LogKind::Info, format!("auth URL: {}", url));
+
         #[cfg(target_os = "linux")]
         let opener = "xdg-open";
         #[cfg(target_os = "macos")]
@@ -138,7 +166,7 @@ impl UserPanel {
     {
         let is_panel_selected = self.current_panel == SelectedPanel::AddUser;
 
-        let block = block::selectable("Add User", is_panel_selected);
+        let block = block::selectable(Text::AddUserPanel.get(), is_panel_selected);
         let block_area = block.inner(rect);
 
         frame.render_widget(block, rect);
@@ -165,7 +193,7 @@ impl UserPanel {
         self.token_input.set_selected(is_panel_selected);
         self.token_input.draw(vert_split[0], frame);
 
-        let services_block = block::selectable("Service", is_panel_selected);
+        let services_block = block::selectable(Text::Service.get(), is_panel_selected);
         let services_block_area = services_block.inner(vert_split[2]);
 
         frame.render_widget(services_block, vert_split[2]);
@@ -187,7 +215,7 @@ impl UserPanel {
             Fragment::Line,
             Fragment::span(text::hint("-")),
             Fragment::Line,
-            Fragment::span(text::hint("Open auth URL")),
+            Fragment::span(text::hint(Text::OpenAuthUrlHint.get())),
         ];
 
         let hint_widget = TextFragments::new(&hint_fragments).alignment(Alignment::Center);
@@ -209,47 +237,54 @@ impl UserPanel {
             block_area,
             [
                 // User table
-                BasicConstraint::MinLenRemaining(5, 8),
+                BasicConstraint::MinLenRemaining(5, 13),
+                // Spacer
+                BasicConstraint::Length(1),
+                // Token details
+                BasicConstraint::Length(2),
                 // Spacer
                 BasicConstraint::Length(1),
                 // Hints
-                BasicConstraint::Length(5),
+                BasicConstraint::Length(7),
                 // Status Text
                 BasicConstraint::Length(2),
             ],
         );
 
         self.draw_users_table(is_panel_selected, state, layout[0], frame);
+        self.draw_token_details(state, layout[2], frame);
 
         let key_hints_fragments = [
-            Fragment::span(text::hint("O - Go offline")),
+            Fragment::span(text::hint(Text::GoOfflineHint.get())),
+            Fragment::Line,
+            Fragment::span(text::hint(Text::RemoveAccountHint.get())),
             Fragment::Line,
-            Fragment::span(text::hint("D - Remove account")),
+            Fragment::span(text::hint(Text::LoginHint.get())),
             Fragment::Line,
-            Fragment::span(text::hint("Enter - Login as selected")),
+            Fragment::span(text::hint(Text::WatchPartyHint.get())),
         ];
 
         let key_hints_widget =
             TextFragments::new(&key_hints_fragments).alignment(Alignment::Center);
 
-        frame.render_widget(key_hints_widget, layout[2]);
+        frame.render_widget(key_hints_widget, layout[4]);
 
-        let yellow_text = |value| text::with_color(value, Color::Yellow);
+        let yellow_text = |value| Span::styled(value, Tone::Warning.style());
 
         match &state.remote {
             RemoteStatus::LoggingIn(username) => {
                 let fragments = [
-                    Fragment::span(yellow_text("Logging In As ")),
+                    Fragment::span(yellow_text(Text::LoggingInAs.get())),
                     Fragment::span(yellow_text(&username)),
                 ];
 
                 let widget = TextFragments::new(&fragments).alignment(Alignment::Center);
-                frame.render_widget(widget, layout[3]);
+                frame.render_widget(widget, layout[5]);
             }
             RemoteStatus::LoggedIn(remote) if remote.is_offline() => {
-                let widget =
-                    SimpleText::new(yellow_text("Currently Offline")).alignment(Alignment::Center);
-                frame.render_widget(widget, layout[3]);
+                let widget = SimpleText::new(yellow_text(Text::CurrentlyOffline.get()))
+                    .alignment(Alignment::Center);
+                frame.render_widget(widget, layout[5]);
             }
             RemoteStatus::LoggedIn(_) => (),
         }
@@ -266,29 +301,78 @@ impl UserPanel {
     {
         let remote = state.remote.get_logged_in();
 
-        let users = state.users.get().keys().map(|user| {
+        let now = Utc::now().timestamp();
+        let dates = &state.config.dates;
+
+        let users = state.users.sorted().into_iter().map(|(user, account)| {
             let is_logged_in = remote
                 .as_ref()
                 .map(|remote| user.is_logged_in(remote))
                 .unwrap_or(false);
 
             let style = if is_logged_in {
-                style::fg(Color::Blue)
+                Tone::Accent.style()
             } else {
                 Style::default()
             };
 
+            let last_used = if state.failed_logins.contains(user) {
+                Span::styled(Text::LoginFailed.get(), Tone::Bad.style())
+            } else {
+                let last_used = account.last_used.map_or_else(
+                    || Text::Never.get().into(),
+                    |last_used| dates.format_timestamp_date(last_used),
+                );
+
+                Span::styled(last_used, style)
+            };
+
+            let expires = match account.token.claims().and_then(|claims| claims.expires_at) {
+                Some(expires_at) if expires_at <= now => {
+                    Span::styled(Text::Expired.get(), Tone::Bad.style())
+                }
+                Some(expires_at) if expires_at - now <= TOKEN_EXPIRY_WARNING_SECS => Span::styled(
+                    dates.format_timestamp_date(expires_at),
+                    Tone::Warning.style(),
+                ),
+                Some(expires_at) => Span::styled(dates.format_timestamp_date(expires_at), style),
+                None => Span::styled(Text::Unknown.get(), style),
+            };
+
+            let in_party = state
+                .watch_party
+                .as_ref()
+                .map_or(false, |party| party.contains(user));
+
             [
                 Span::styled(user.username.as_str(), style),
                 Span::styled(user.service.as_str(), style),
+                Span::styled(account.profile.as_str(), style),
+                Span::styled(dates.format_timestamp_date(account.added), style),
+                last_used,
+                expires,
+                Span::styled(if in_party { "*" } else { "" }, Tone::Good.style()),
             ]
         });
 
-        let header = [Span::raw("Username"), Span::raw("Service")];
+        let header = [
+            Span::raw(Text::Username.get()),
+            Span::raw(Text::Service.get()),
+            Span::raw(Text::Profile.get()),
+            Span::raw(Text::Added.get()),
+            Span::raw(Text::LastUsed.get()),
+            Span::raw(Text::Expires.get()),
+            Span::raw(Text::WatchParty.get()),
+        ];
 
         let layout = [
-            BasicConstraint::Percentage(50),
-            BasicConstraint::Percentage(50),
+            BasicConstraint::Percentage(20),
+            BasicConstraint::Percentage(11),
+            BasicConstraint::Percentage(14),
+            BasicConstraint::Percentage(15),
+            BasicConstraint::Percentage(15),
+            BasicConstraint::Percentage(15),
+            BasicConstraint::Percentage(10),
         ];
 
         let users_widget = SimpleTable::new(users, layout)
@@ -299,6 +383,50 @@ impl UserPanel {
         frame.render_widget(users_widget, rect);
     }
 
+    /// Draw the information stored in the token of the selected user.
+    fn draw_token_details<B>(&self, state: &UIState, rect: Rect, frame: &mut Frame<B>)
+    where
+        B: Backend,
+    {
+        let (_, account) = try_opt_ret!(self.selected_user(state));
+
+        let claims = if let Some(claims) = account.token.claims() {
+            claims
+        } else {
+            let widget = SimpleText::new(text::hint(Text::NoTokenDetails.get()))
+                .alignment(Alignment::Center);
+
+            frame.render_widget(widget, rect);
+            return;
+        };
+
+        let user_id = claims.user_id.unwrap_or_else(|| Text::Unknown.get().into());
+
+        let created = claims.issued_at.map_or_else(
+            || Text::Unknown.get().into(),
+            |issued_at| state.config.dates.format_timestamp_date(issued_at),
+        );
+
+        let scope = if claims.scopes.is_empty() {
+            Text::FullAccess.get().into()
+        } else {
+            claims.scopes.join(", ")
+        };
+
+        let fragments = [
+            Fragment::span(text::hint(Text::UserId.get())),
+            Fragment::span(Span::raw(user_id)),
+            Fragment::span(text::hint(Text::Created.get())),
+            Fragment::span(Span::raw(created)),
+            Fragment::Line,
+            Fragment::span(text::hint(Text::Scope.get())),
+            Fragment::span(Span::raw(scope)),
+        ];
+
+        let widget = TextFragments::new(&fragments).alignment(Alignment::Center);
+        frame.render_widget(widget, rect);
+    }
+
     pub fn draw<B: Backend>(&mut self, state: &UIState, rect: Rect, frame: &mut Frame<B>) {
         let horiz_split = SimpleLayout::new(Direction::Horizontal).split(
             rect,
@@ -343,8 +471,12 @@ impl Component for UserPanel {
                         self.remove_selected_user(state)?;
                         Ok(ShouldReset::No)
                     }
+                    KeyCode::Char('p') => {
+                        self.toggle_selected_watch_party(state)?;
+                        Ok(ShouldReset::No)
+                    }
                     KeyCode::Char('o') => {
-                        state.remote = RemoteStatus::LoggedIn(Remote::offline());
+                        state.remote = RemoteStatus::LoggedIn(Remote::offline().into());
                         Ok(ShouldReset::Yes)
                     }
                     _ => Ok(ShouldReset::No),
@@ -360,7 +492,7 @@ impl Component for UserPanel {
                         Ok(ShouldReset::No)
                     }
                     KeyCode::Char('o') if key.ctrl_pressed() => {
-                        self.open_auth_url()?;
+                        self.open_auth_url(state)?;
                         Ok(ShouldReset::No)
                     }
                     KeyCode::Enter => {
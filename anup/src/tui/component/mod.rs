@@ -1,3 +1,4 @@
+pub mod chord_hints;
 pub mod main_panel;
 pub mod prompt;
 pub mod series_list;
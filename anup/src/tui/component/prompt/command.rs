@@ -1,7 +1,14 @@
+use crate::series::config::TitleOverride;
+use crate::series::filler::FillerEpisodes;
+use crate::series::history::EpisodeRatings;
 use crate::tui::component::input::Input;
 use crate::tui::component::Component;
+use crate::tui::locale::Text;
+use crate::tui::state::SeriesSort;
+use crate::tui::theme::Tone;
 use crate::tui::UIState;
 use crate::{config::Config, key::Key};
+use anime::remote::{ScoreParser, SeriesID, Status};
 use anyhow::{anyhow, Result};
 use crossterm::event::KeyCode;
 use smallvec::{smallvec, SmallVec};
@@ -9,12 +16,12 @@ use std::convert::TryFrom;
 use std::result;
 use tui::backend::Backend;
 use tui::layout::Rect;
-use tui::style::Color;
 use tui::text::Span;
 use tui::Frame;
 use tui_utils::{
-    helpers::{block, style},
-    widgets::{Fragment, TextFragments},
+    helpers::block,
+    layout::RectExt,
+    widgets::{Fragment, SimpleText, TextFragments},
     wrap,
 };
 use unicode_width::UnicodeWidthChar;
@@ -24,6 +31,8 @@ pub struct CommandPrompt {
     buffer: String,
     hint_cmd: Option<HintCommand<'static>>,
     width: usize,
+    /// Why the command being typed can't be run, shown under it before it's entered.
+    error: Option<String>,
 }
 
 impl CommandPrompt {
@@ -32,6 +41,7 @@ impl CommandPrompt {
             buffer: String::with_capacity(32),
             hint_cmd: None,
             width: 0,
+            error: None,
         }
     }
 
@@ -84,8 +94,23 @@ impl CommandPrompt {
         Ok(InputResult::Continue)
     }
 
+    /// Check the command being typed, so mistakes can be shown before it's entered.
+    fn validate(&mut self, state: &UIState) {
+        self.error = match Command::validate(&self.buffer, &state.config) {
+            // Scores depend on the format the user picked on the remote service
+            Ok(Some(Command::Score(score))) => match state.remote.get_logged_in() {
+                Ok(remote) if remote.parse_relative_score(&score, None).is_none() => {
+                    Some(format!("invalid score: {}", score))
+                }
+                _ => None,
+            },
+            Ok(_) => None,
+            Err(err) => Some(err.to_string()),
+        };
+    }
+
     pub fn draw<B: Backend>(&self, rect: Rect, frame: &mut Frame<B>) {
-        let block = block::with_borders("Enter Command");
+        let block = block::with_borders(Text::CommandPanel.get());
         let block_area = block.inner(rect);
 
         frame.render_widget(block, rect);
@@ -95,7 +120,12 @@ impl CommandPrompt {
 
         frame.render_widget(widget, block_area);
 
-        if Input::will_cursor_fit(rect) {
+        if let Some(error) = &self.error {
+            let text = Span::styled(error.as_str(), Tone::Bad.style());
+            frame.render_widget(SimpleText::new(text), block_area.lines_from_bottom(1));
+        }
+
+        if Input::will_cursor_fit(block_area) {
             let (x, y) = Input::calculate_cursor_pos(self.width() as u16, block_area);
             frame.set_cursor(x, y);
         }
@@ -105,6 +135,7 @@ impl CommandPrompt {
         self.buffer.clear();
         self.hint_cmd = None;
         self.width = 0;
+        self.error = None;
     }
 
     #[inline(always)]
@@ -117,10 +148,7 @@ impl CommandPrompt {
         let mut items = smallvec![Fragment::span(Span::raw(self.buffer.as_str()))];
 
         if let Some(hint_cmd) = &self.hint_cmd {
-            let span = Span::styled(
-                hint_cmd.remaining_name_and_usage(),
-                style::fg(Color::DarkGray),
-            );
+            let span = Span::styled(hint_cmd.remaining_name_and_usage(), Tone::Faded.style());
 
             items.push(Fragment::span(span));
         }
@@ -134,7 +162,13 @@ impl Component for CommandPrompt {
     type KeyResult = Result<InputResult>;
 
     fn process_key(&mut self, key: Key, state: &mut Self::State) -> Self::KeyResult {
-        self.process_key(key, &state.config)
+        let result = self.process_key(key, &state.config);
+
+        if let Ok(InputResult::Continue) = result {
+            self.validate(state);
+        }
+
+        result
     }
 }
 
@@ -270,6 +304,42 @@ macro_rules! impl_command_matching {
                     _ => Err(anyhow!("command not found: {}", value)),
                 }
             }
+
+            /// Parse `value` the same way as `from_str`, while it's still being typed.
+            ///
+            /// Returns `None` if `value` could still become a valid command, such as when its name or
+            /// arguments haven't been fully entered yet.
+            pub fn validate(value: &str, config: &Config) -> Result<Option<Self>> {
+                let fragments = split_shell_words(value);
+
+                let name = match fragments.first() {
+                    Some(name) => name.to_ascii_lowercase(),
+                    None => return Ok(None),
+                };
+
+                let args = &fragments[1..];
+
+                // The name is still being typed until a space follows it
+                if args.is_empty() && !value.ends_with(' ') {
+                    if Self::COMMANDS.iter().any(|cmd| cmd.name.starts_with(name.as_str())) {
+                        return Ok(None);
+                    }
+
+                    return Err(anyhow!("command not found: {}", name));
+                }
+
+                match name.as_ref() {
+                    $($name => {
+                        #[allow(unused_comparisons)]
+                        if args.len() < $min_args {
+                            return Ok(None);
+                        }
+
+                        $parse_fn(args, config).map(Some)
+                    },)+
+                    _ => Err(anyhow!("command not found: {}", name)),
+                }
+            }
         }
     };
 }
@@ -288,10 +358,117 @@ pub enum Command {
     /// Rate the selected season.
     Score(String),
     /// Set the watch status of the selected season.
-    Status(anime::remote::Status),
+    Status(Status),
+    /// Set the episode number offset of the selected season.
+    Offset(i32),
+    /// Set the title shown for the selected season, or show its preferred title again if None.
+    Title(Option<TitleOverride>),
+    /// Set whether or not the selected season is synced with the remote service.
+    RemoteSync(bool),
+    /// Restore a deleted season by its nickname.
+    Undelete(String),
+    /// Set whether or not episodes of the selected season are removed once they have been watched.
+    RemoveWatched(bool),
+    /// Set whether or not new episodes of the selected season are kept from sending notifications.
+    Mute(bool),
+    /// Forget that the selected season failed to sync, so it's no longer skipped when syncing every season at once.
+    ResetSync,
+    /// Link the selected season to a different series on the remote service while keeping its progress.
+    Relink(SeriesID),
+    /// Syncronize every season with unsynced changes to the remote service in the background.
+    SyncAll,
+    /// Replace the info of every season with the info from the remote service in the background.
+    RefreshAll,
+    /// Start or end a private session, where changes to list entries are only saved locally until it ends.
+    Private(bool),
+    /// Set whether or not the selected season is planned to be rewatched.
+    RewatchPlan(bool),
+    /// Rate an episode of the selected season that has been watched, or the last watched episode if one isn't given.
+    RateEpisode(i16, Option<i16>),
+    /// Remove the start and end dates of the selected season.
+    ResetDates,
+    /// Change or show the aliases of the selected season.
+    Alias(AliasAction),
+    /// Change or show the collections the selected season is in.
+    Collection(CollectionAction),
+    /// Remove the selected season from the user's list on the remote service, and stop syncing it.
+    RemoveEntry,
+    /// Select the season that seems best to watch next.
+    Suggest,
+    /// Replace the watched episodes and status of the selected season with those on the remote service, leaving everything else alone.
+    PullProgress,
+    /// Set the priority of the selected season's list entry.
+    Priority(i16),
+    /// Change the order of the series list.
+    Sort(SeriesSort),
+    /// Play an episode of the selected season, or its next episode if one isn't given, with player arguments that are only used once.
+    Play(Option<u32>, SmallVec<[String; 2]>),
+    /// Change or show the filler episodes of the selected season.
+    Filler(FillerAction),
+    /// Give a file of the selected season the episode number the episode parser should have found, or show the files that have one.
+    FixEpisode(FixEpisodeAction),
+    /// Copy a message saying which episode of the selected season is being watched, optionally with a link to its page.
+    Share(bool),
 }
 
-impl_command_matching!(Command, 6,
+impl_command_matching!(Command, 31,
+    PullProgress => {
+        name: "pullprogress",
+        usage: "",
+        min_args: 0,
+        fn: |_, _| Ok(Command::PullProgress),
+    },
+    Priority(_) => {
+        name: "priority",
+        usage: "<0-99>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let priority = args[0]
+                .parse()
+                .ok()
+                .filter(|priority| (0..=99).contains(priority))
+                .ok_or_else(|| anyhow!("invalid priority: {}", args[0]))?;
+
+            Ok(Command::Priority(priority))
+        },
+    },
+    Sort(_) => {
+        name: "sort",
+        usage: "<name | priority>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let sort = SeriesSort::try_from(args[0])?;
+            Ok(Command::Sort(sort))
+        },
+    },
+    Play(_, _) => {
+        name: "play",
+        usage: "[episode] [-- player args]",
+        min_args: 0,
+        fn: |args: &[&str], _| {
+            let split = args.iter().position(|&arg| arg == "--").unwrap_or(args.len());
+            let (episode, player_args) = args.split_at(split);
+
+            let episode = match episode {
+                [] => None,
+                [episode] => Some(
+                    episode
+                        .parse()
+                        .map_err(|_| anyhow!("invalid episode: {}", episode))?,
+                ),
+                _ => return Err(anyhow!("player arguments must come after --")),
+            };
+
+            // The -- separating the player arguments is skipped
+            let player_args = player_args
+                .iter()
+                .skip(1)
+                .map(|&arg| arg.to_string())
+                .collect();
+
+            Ok(Command::Play(episode, player_args))
+        },
+    },
     PlayerArgs(_) => {
         name: "args",
         usage: "<player args>",
@@ -327,7 +504,7 @@ impl_command_matching!(Command, 6,
     },
     Score(_) => {
         name: "rate",
-        usage: "<0-100>",
+        usage: "<0-100 | +amount | -amount>",
         min_args: 1,
         fn: |args: &[&str], _| {
             let score = args[0].into();
@@ -339,21 +516,213 @@ impl_command_matching!(Command, 6,
         usage: "<w, watching | c, completed | h, hold | d, drop | p, plan | r, rewatch>",
         min_args: 1,
         fn: |args: &[&str], _| {
-            use anime::remote::Status;
-
-            let status = match args[0].to_ascii_lowercase().as_ref() {
-                "w" | "watching" => Status::Watching,
-                "c" | "completed" => Status::Completed,
-                "h" | "hold" => Status::OnHold,
-                "d" | "drop" => Status::Dropped,
-                "p" | "plan" => Status::PlanToWatch,
-                "r" | "rewatch" => Status::Rewatching,
-                _ => {
-                    return Err(anyhow!("unknown argument: {}", args[0]))
+            let status = args[0].parse::<Status>()?;
+            Ok(Command::Status(status))
+        },
+    },
+    Offset(_) => {
+        name: "offset",
+        usage: "<episode offset>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let offset = args[0]
+                .parse()
+                .map_err(|_| anyhow!("invalid offset: {}", args[0]))?;
+
+            Ok(Command::Offset(offset))
+        },
+    },
+    Title(_) => {
+        name: "title",
+        usage: "<preferred | romaji | english | native | custom title>",
+        min_args: 1,
+        fn: |args: &[&str], _| Ok(Command::Title(TitleOverride::parse(&args.join(" ")))),
+    },
+    RemoteSync(_) => {
+        name: "remotesync",
+        usage: "<on | off>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let enabled = match args[0].to_ascii_lowercase().as_ref() {
+                "on" => true,
+                "off" => false,
+                _ => return Err(anyhow!("unknown argument: {}", args[0])),
+            };
+
+            Ok(Command::RemoteSync(enabled))
+        },
+    },
+    Undelete(_) => {
+        name: "undelete",
+        usage: "<nickname>",
+        min_args: 1,
+        fn: |args: &[&str], _| Ok(Command::Undelete(args[0].into())),
+    },
+    RemoveWatched(_) => {
+        name: "removewatched",
+        usage: "<on | off>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let enabled = match args[0].to_ascii_lowercase().as_ref() {
+                "on" => true,
+                "off" => false,
+                _ => return Err(anyhow!("unknown argument: {}", args[0])),
+            };
+
+            Ok(Command::RemoveWatched(enabled))
+        },
+    },
+    Mute(_) => {
+        name: "mute",
+        usage: "<on | off>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let muted = match args[0].to_ascii_lowercase().as_ref() {
+                "on" => true,
+                "off" => false,
+                _ => return Err(anyhow!("unknown argument: {}", args[0])),
+            };
+
+            Ok(Command::Mute(muted))
+        },
+    },
+    ResetSync => {
+        name: "resetsync",
+        usage: "",
+        min_args: 0,
+        fn: |_, _| Ok(Command::ResetSync),
+    },
+    Relink(_) => {
+        name: "relink",
+        usage: "<series id>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let id = args[0]
+                .parse()
+                .map_err(|_| anyhow!("invalid series id: {}", args[0]))?;
+
+            Ok(Command::Relink(id))
+        },
+    },
+    SyncAll => {
+        name: "syncall",
+        usage: "",
+        min_args: 0,
+        fn: |_, _| Ok(Command::SyncAll),
+    },
+    RefreshAll => {
+        name: "refreshall",
+        usage: "",
+        min_args: 0,
+        fn: |_, _| Ok(Command::RefreshAll),
+    },
+    Private(_) => {
+        name: "private",
+        usage: "<on | off>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let active = match args[0].to_ascii_lowercase().as_ref() {
+                "on" => true,
+                "off" => false,
+                _ => return Err(anyhow!("unknown argument: {}", args[0])),
+            };
+
+            Ok(Command::Private(active))
+        },
+    },
+    RewatchPlan(_) => {
+        name: "rewatchplan",
+        usage: "<on | off>",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let planned = match args[0].to_ascii_lowercase().as_ref() {
+                "on" => true,
+                "off" => false,
+                _ => return Err(anyhow!("unknown argument: {}", args[0])),
+            };
+
+            Ok(Command::RewatchPlan(planned))
+        },
+    },
+    RateEpisode(_, _) => {
+        name: "rateep",
+        usage: "<1-10> [episode]",
+        min_args: 1,
+        fn: |args: &[&str], _| {
+            let rating = args[0]
+                .parse()
+                .ok()
+                .filter(|rating| (EpisodeRatings::MIN..=EpisodeRatings::MAX).contains(rating))
+                .ok_or_else(|| anyhow!("invalid rating: {}", args[0]))?;
+
+            let episode = match args.get(1) {
+                Some(episode) => {
+                    let episode = episode
+                        .parse()
+                        .map_err(|_| anyhow!("invalid episode: {}", episode))?;
+
+                    Some(episode)
                 }
+                None => None,
             };
 
-            Ok(Command::Status(status))
+            Ok(Command::RateEpisode(rating, episode))
+        },
+    },
+    ResetDates => {
+        name: "resetdates",
+        usage: "",
+        min_args: 0,
+        fn: |_, _| Ok(Command::ResetDates),
+    },
+    Alias(_) => {
+        name: "alias",
+        usage: "<add | remove> <alias>",
+        min_args: 0,
+        fn: |args: &[&str], _| Ok(Command::Alias(AliasAction::parse(args)?)),
+    },
+    Collection(_) => {
+        name: "collection",
+        usage: "<add | remove> <name>",
+        min_args: 0,
+        fn: |args: &[&str], _| Ok(Command::Collection(CollectionAction::parse(args)?)),
+    },
+    Filler(_) => {
+        name: "filler",
+        usage: "<episodes like 5-7,12 | clear>",
+        min_args: 0,
+        fn: |args: &[&str], _| Ok(Command::Filler(FillerAction::parse(args)?)),
+    },
+    FixEpisode(_) => {
+        name: "fixep",
+        usage: "<episode> <part of filename> | clear",
+        min_args: 0,
+        fn: |args: &[&str], _| Ok(Command::FixEpisode(FixEpisodeAction::parse(args)?)),
+    },
+    RemoveEntry => {
+        name: "removeentry",
+        usage: "",
+        min_args: 0,
+        fn: |_, _| Ok(Command::RemoveEntry),
+    },
+    Suggest => {
+        name: "suggest",
+        usage: "",
+        min_args: 0,
+        fn: |_, _| Ok(Command::Suggest),
+    },
+    Share(_) => {
+        name: "share",
+        usage: "[url]",
+        min_args: 0,
+        fn: |args: &[&str], _| {
+            let with_url = match args.first() {
+                Some(arg) if arg.eq_ignore_ascii_case("url") => true,
+                Some(arg) => return Err(anyhow!("unknown argument: {}", arg)),
+                None => false,
+            };
+
+            Ok(Command::Share(with_url))
         },
     },
 );
@@ -373,6 +742,106 @@ impl Command {
     }
 }
 
+/// A change to the aliases of a season.
+#[cfg_attr(test, derive(Debug))]
+pub enum AliasAction {
+    Add(String),
+    Remove(String),
+    /// Show every alias of the season.
+    List,
+}
+
+impl AliasAction {
+    fn parse(args: &[&str]) -> Result<Self> {
+        let (action, alias) = match args.split_first() {
+            Some((action, alias)) => (action.to_ascii_lowercase(), alias.join(" ")),
+            None => return Ok(Self::List),
+        };
+
+        if alias.is_empty() {
+            return Err(anyhow!("specify an alias to {}", action));
+        }
+
+        match action.as_ref() {
+            "add" | "a" => Ok(Self::Add(alias)),
+            "remove" | "r" => Ok(Self::Remove(alias)),
+            _ => Err(anyhow!("unknown argument: {}", action)),
+        }
+    }
+}
+
+/// A change to the collections a season is in.
+#[cfg_attr(test, derive(Debug))]
+pub enum CollectionAction {
+    Add(String),
+    Remove(String),
+    /// Show every collection the season is in.
+    List,
+}
+
+impl CollectionAction {
+    fn parse(args: &[&str]) -> Result<Self> {
+        let (action, name) = match args.split_first() {
+            Some((action, name)) => (action.to_ascii_lowercase(), name.join(" ")),
+            None => return Ok(Self::List),
+        };
+
+        if name.is_empty() {
+            return Err(anyhow!("specify a collection to {}", action));
+        }
+
+        match action.as_ref() {
+            "add" | "a" => Ok(Self::Add(name)),
+            "remove" | "r" => Ok(Self::Remove(name)),
+            _ => Err(anyhow!("unknown argument: {}", action)),
+        }
+    }
+}
+
+/// A change to the filler episodes of a season.
+#[cfg_attr(test, derive(Debug))]
+pub enum FillerAction {
+    /// Replace the filler episodes of the season.
+    Set(FillerEpisodes),
+    Clear,
+    /// Show the filler episodes of the season.
+    List,
+}
+
+impl FillerAction {
+    fn parse(args: &[&str]) -> Result<Self> {
+        match args {
+            [] => Ok(Self::List),
+            [action] if action.eq_ignore_ascii_case("clear") => Ok(Self::Clear),
+            episodes => FillerEpisodes::parse(&episodes.join(" ")).map(Self::Set),
+        }
+    }
+}
+
+/// A change to the episode overrides of a season.
+#[cfg_attr(test, derive(Debug))]
+pub enum FixEpisodeAction {
+    /// Give the file with the text in its name the episode number.
+    Set(i16, String),
+    Clear,
+    /// Show the episode overrides of the season.
+    List,
+}
+
+impl FixEpisodeAction {
+    fn parse(args: &[&str]) -> Result<Self> {
+        match args {
+            [] => Ok(Self::List),
+            [action] if action.eq_ignore_ascii_case("clear") => Ok(Self::Clear),
+            [_] => Err(anyhow!("specify part of the filename to give the episode")),
+            [episode, name @ ..] => match episode.parse::<i16>() {
+                Ok(episode) if episode > 0 => Ok(Self::Set(episode, name.join(" "))),
+                _ => Err(anyhow!("{} is not a valid episode", episode)),
+            },
+        }
+    }
+}
+
 /// Indicates which way to advance the episode count of a season.
 #[derive(Copy, Clone)]
 #[cfg_attr(test, derive(Debug))]
@@ -403,6 +872,7 @@ mod tests {
     use smallvec::smallvec;
 
     #[test]
+    #[allow(clippy::too_many_lines)]
     fn test_commands() {
         use anime::remote::Status;
 
@@ -466,12 +936,96 @@ mod tests {
             other => expected!(other, Command::PlayerArgs(expected_args)),
         }
 
+        match enter_command("play 5 -- --aid=2") {
+            Command::Play(Some(5), args) if args.as_slice() == ["--aid=2"] => (),
+            other => expected!(other, Command::Play(Some(5), smallvec!["--aid=2".into()])),
+        }
+
+        test_command!("play", Command::Play(None, _));
+        test_command!("priority 3", Command::Priority(3));
+        test_command!("pullprogress", Command::PullProgress);
+        test_command!("sort priority", Command::Sort(SeriesSort::Priority));
+
         test_command!(
             "progress forward",
             Command::Progress(ProgressDirection::Forwards)
         );
 
         test_command!("status watching", Command::Status(Status::Watching));
+        test_command!("offset -12", Command::Offset(-12));
+        test_command!(
+            "title english",
+            Command::Title(Some(TitleOverride::English))
+        );
+        test_command!("title preferred", Command::Title(None));
+        test_command!("remotesync off", Command::RemoteSync(false));
+        test_command!("removewatched on", Command::RemoveWatched(true));
+        test_command!("mute off", Command::Mute(false));
+        test_command!("relink 1234", Command::Relink(1234));
+        test_command!("syncall", Command::SyncAll);
+        test_command!("private on", Command::Private(true));
+        test_command!("rewatchplan on", Command::RewatchPlan(true));
+        test_command!("rateep 8", Command::RateEpisode(8, None));
+        test_command!("rateep 10 3", Command::RateEpisode(10, Some(3)));
+        test_command!("resetdates", Command::ResetDates);
+        test_command!("alias", Command::Alias(AliasAction::List));
+        test_command!("suggest", Command::Suggest);
+        test_command!("share url", Command::Share(true));
+        test_command!("removeentry", Command::RemoveEntry);
+        test_command!("collection", Command::Collection(CollectionAction::List));
+        test_command!("filler", Command::Filler(FillerAction::List));
+        test_command!("filler clear", Command::Filler(FillerAction::Clear));
+        test_command!("filler 5-7, 12", Command::Filler(FillerAction::Set(_)));
+        test_command!("fixep", Command::FixEpisode(FixEpisodeAction::List));
+        test_command!("fixep clear", Command::FixEpisode(FixEpisodeAction::Clear));
+        test_command!(
+            "fixep 12 [group] ep 11v2",
+            Command::FixEpisode(FixEpisodeAction::Set(12, _))
+        );
+
+        match enter_command("title Some Title") {
+            Command::Title(Some(TitleOverride::Custom(title))) if title == "Some Title" => (),
+            other => expected!(other, "Command::Title(Some(TitleOverride::Custom(..)))"),
+        }
+
+        match enter_command("alias add shingeki no kyojin") {
+            Command::Alias(AliasAction::Add(alias)) if alias == "shingeki no kyojin" => (),
+            other => expected!(other, "Command::Alias(AliasAction::Add(..))"),
+        }
+
+        match enter_command("alias remove aot") {
+            Command::Alias(AliasAction::Remove(alias)) if alias == "aot" => (),
+            other => expected!(other, "Command::Alias(AliasAction::Remove(..))"),
+        }
+
+        match enter_command("collection add with friends") {
+            Command::Collection(CollectionAction::Add(name)) if name == "with friends" => (),
+            other => expected!(other, "Command::Collection(CollectionAction::Add(..))"),
+        }
+
+        match enter_command("rate +5") {
+            Command::Score(score) if score == "+5" => (),
+            other => expected!(other, Command::Score("+5".into())),
+        }
+
+        match enter_command("undelete \"some series\"") {
+            Command::Undelete(name) if name == "some series" => (),
+            other => expected!(other, Command::Undelete("some series".into())),
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        let config = Config::default();
+        let validate = |value| Command::validate(value, &config);
+
+        assert!(matches!(validate(""), Ok(None)));
+        assert!(matches!(validate("sta"), Ok(None)));
+        assert!(matches!(validate("status "), Ok(None)));
+        assert!(matches!(validate("status w"), Ok(Some(Command::Status(_)))));
+        assert!(validate("xyz").is_err());
+        assert!(validate("status bogus").is_err());
+        assert!(validate("rateep 11").is_err());
     }
 
     #[test]
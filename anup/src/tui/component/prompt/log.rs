@@ -1,28 +1,31 @@
-use std::{array::IntoIter, collections::VecDeque};
+use std::time::Instant;
+use std::{array::IntoIter, borrow::Cow, collections::VecDeque};
 
+use crate::tui::charset;
+use crate::tui::locale::Text;
+use crate::tui::theme::Tone;
 use anyhow::Error;
 use tui::backend::Backend;
 use tui::layout::Rect;
-use tui::style::Color;
 use tui::text::Span;
 use tui::Frame;
-use tui_utils::{
-    helpers::{block, style},
-    widgets::Fragment,
-    wrap,
-};
+use tui_utils::{helpers::block, widgets::Fragment, wrap};
 
 #[derive(Copy, Clone)]
 pub enum LogKind {
     Error,
     Context,
+    Warning,
+    Info,
 }
 
 impl<'a> Into<Span<'a>> for LogKind {
     fn into(self) -> Span<'a> {
         match self {
-            Self::Error => Span::styled("error: ", style::fg(Color::Red)),
-            Self::Context => Span::styled("^ ", style::fg(Color::Yellow)),
+            Self::Error => Span::styled("error: ", Tone::Bad.style()),
+            Self::Context => Span::styled("^ ", Tone::Warning.style()),
+            Self::Warning => Span::styled("warning: ", Tone::Warning.style()),
+            Self::Info => Span::styled("info: ", Tone::Good.style()),
         }
     }
 }
@@ -57,25 +60,178 @@ impl<'a> Into<[Fragment<'a>; 2]> for &'a LogEntry<'a> {
     }
 }
 
+/// The frames of the spinner shown while an activity is running.
+const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// The frames of the spinner when unicode is turned off.
+const ASCII_SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+
+/// How long each frame of the spinner is shown for, in milliseconds.
+const SPINNER_FRAME_MS: u128 = 100;
+
+/// Identifies an activity started with [`Log::start_activity`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ActivityId(u32);
+
+/// A long-running operation that is shown with a spinner in place of the log's title until it finishes.
+struct Activity {
+    id: ActivityId,
+    label: String,
+    started: Instant,
+}
+
 /// A scrolling status log.
 pub struct Log<'a> {
     items: VecDeque<LogEntry<'a>>,
     max_items: u8,
     title: String,
+    activities: Vec<Activity>,
+    next_activity_id: u32,
+    /// The number of saved events that haven't been read yet.
+    unread: i64,
+    private_session: bool,
+    presentation_lock: bool,
+    /// Shows that the remote service is having problems or can't be reached.
+    service_health: Option<String>,
+    /// The version of a newer release of the program, if one was found.
+    update: Option<String>,
 }
 
 impl<'a> Log<'a> {
     pub fn new(max_items: u8) -> Self {
-        let title = format!(
-            "Error Log [press '{}' for command entry]",
-            super::COMMAND_KEY
-        );
+        let title = Text::LogPanel.format(&[&super::COMMAND_KEY]);
+        // The newest message always has to fit
+        let max_items = max_items.max(1);
 
         Self {
             items: VecDeque::with_capacity(max_items as usize),
             max_items,
             title,
+            activities: Vec::new(),
+            next_activity_id: 0,
+            unread: 0,
+            private_session: false,
+            presentation_lock: false,
+            service_health: None,
+            update: None,
+        }
+    }
+
+    /// Show a spinner with `label` until [`Self::finish_activity`] is called with the returned ID.
+    pub fn start_activity<S>(&mut self, label: S) -> ActivityId
+    where
+        S: Into<String>,
+    {
+        let id = ActivityId(self.next_activity_id);
+        self.next_activity_id = self.next_activity_id.wrapping_add(1);
+
+        self.activities.push(Activity {
+            id,
+            label: label.into(),
+            started: Instant::now(),
+        });
+
+        id
+    }
+
+    pub fn finish_activity(&mut self, id: ActivityId) {
+        self.activities.retain(|activity| activity.id != id);
+    }
+
+    /// Returns true if any activity is running, which means the spinner needs to be redrawn.
+    pub fn is_busy(&self) -> bool {
+        !self.activities.is_empty()
+    }
+
+    /// Set the number of unread saved events shown in the log's title.
+    pub fn set_unread(&mut self, unread: i64) {
+        self.unread = unread;
+    }
+
+    /// Set whether the log's title shows that a private session is active.
+    pub fn set_private_session(&mut self, active: bool) {
+        self.private_session = active;
+    }
+
+    /// Set whether the log's title shows that the presentation lock is on.
+    pub fn set_presentation_lock(&mut self, active: bool) {
+        self.presentation_lock = active;
+    }
+
+    /// Set the tag shown in the log's title for the health of the remote service, or `None` while it's healthy.
+    pub fn set_service_health(&mut self, tag: Option<String>) {
+        self.service_health = tag;
+    }
+
+    /// Set the version of the newer release shown in the log's title, or `None` to stop showing it.
+    pub fn set_update(&mut self, version: Option<String>) {
+        self.update = version;
+    }
+
+    /// Returns the title to draw the log with, which shows the most recent activity while any are running.
+    ///
+    /// Otherwise, whether a private session is active, whether the presentation lock is on, any problems with the remote
+    /// service, the number of unread saved events, and whether a newer release is available are shown after the title.
+    fn current_title(&self) -> Cow<str> {
+        let activity = match self.activities.last() {
+            Some(activity) => activity,
+            None if !self.private_session
+                && !self.presentation_lock
+                && self.service_health.is_none()
+                && self.unread == 0
+                && self.update.is_none() =>
+            {
+                return Cow::Borrowed(&self.title)
+            }
+            None => {
+                let mut title = self.title.clone();
+
+                if self.private_session {
+                    title.push(' ');
+                    title.push_str(Text::PrivateSessionTag.get());
+                }
+
+                if self.presentation_lock {
+                    title.push(' ');
+                    title.push_str(Text::PresentationLockTag.get());
+                }
+
+                if let Some(health) = &self.service_health {
+                    title.push(' ');
+                    title.push_str(health);
+                }
+
+                if self.unread > 0 {
+                    title.push(' ');
+                    title.push_str(&Text::UnreadEvents.format(&[&self.unread]));
+                }
+
+                if let Some(version) = &self.update {
+                    title.push(' ');
+                    title.push_str(&Text::UpdateAvailable.format(&[version]));
+                }
+
+                return title.into();
+            }
+        };
+
+        let frames: &[char] = if charset::unicode() {
+            &SPINNER_FRAMES
+        } else {
+            &ASCII_SPINNER_FRAMES
+        };
+
+        let frame_num = activity.started.elapsed().as_millis() / SPINNER_FRAME_MS;
+        let frame = frames[(frame_num % frames.len() as u128) as usize];
+
+        let mut title = format!("{} {}", frame, activity.label);
+
+        if self.activities.len() > 1 {
+            title.push(' ');
+            title.push_str(&Text::MoreActivities.format(&[&(self.activities.len() - 1)]));
         }
+
+        title.into()
     }
 
     pub fn push<S>(&mut self, kind: LogKind, msg: S)
@@ -99,7 +255,8 @@ impl<'a> Log<'a> {
     }
 
     pub fn draw<B: Backend>(&self, rect: Rect, frame: &mut Frame<B>) {
-        let block = block::with_borders(self.title.as_str());
+        let title = self.current_title();
+        let block = block::with_borders(title.as_ref());
         let block_area = block.inner(rect);
 
         frame.render_widget(block, rect);
@@ -1,70 +1,220 @@
-use crate::tui::state::{InputState, UIState};
+use crate::config::ProgressColumn;
+use crate::tui::franchise::Franchises;
+use crate::tui::locale::Text;
+use crate::tui::state::{InputState, SeriesFilter, UIState};
+use crate::tui::theme::Tone;
 use crate::{key::Key, series::LoadedSeries};
 use anime::remote::Status;
+use chrono::{Datelike, Local, Weekday};
 use crossterm::event::KeyCode;
 use tui::backend::Backend;
-use tui::layout::Rect;
-use tui::style::Color;
+use tui::layout::{Direction, Rect};
 use tui::terminal::Frame;
 use tui::text::Span;
 use tui_utils::{
     helpers::{block, style, text},
+    layout::{BasicConstraint, SimpleLayout},
     widgets::SimpleList,
 };
+use unicode_width::UnicodeWidthStr;
+
+/// The number of cells progress bars are drawn with.
+const PROGRESS_BAR_WIDTH: u32 = 6;
 
 pub struct SeriesList;
 
 impl SeriesList {
-    fn series_text(series: &LoadedSeries) -> Span {
+    fn series_text<'a>(series: &'a LoadedSeries, state: &UIState) -> Span<'a> {
         match series {
             LoadedSeries::Complete(series) => {
-                let color = match series.data.entry.status() {
-                    Status::Watching | Status::Rewatching => Color::Blue,
-                    Status::Completed => Color::Green,
-                    Status::OnHold => Color::Yellow,
-                    Status::Dropped => Color::Red,
-                    Status::PlanToWatch => Color::Gray,
+                let tone = match series.data.entry.status() {
+                    // The color of a series gives away its status
+                    _ if state.presentation_lock => Tone::Neutral,
+                    Status::Watching | Status::Rewatching => Tone::Accent,
+                    Status::Completed => Tone::Good,
+                    Status::OnHold => Tone::Warning,
+                    Status::Dropped => Tone::Bad,
+                    Status::PlanToWatch => Tone::Neutral,
                 };
 
-                text::with_color(series.data.config.nickname.as_str(), color)
+                let nickname = series.data.config.nickname.as_str();
+
+                // Series with an episode being played are shown in bold
+                if state.watching.contains_key(&series.data.info.id) {
+                    text::bold_with(nickname, |s| tone.apply(s))
+                } else {
+                    Span::styled(nickname, tone.style())
+                }
             }
             LoadedSeries::Partial(data, _) => {
-                text::with_color(data.config.nickname.as_str(), Color::LightRed)
+                Span::styled(data.config.nickname.as_str(), Tone::BadSelected.style())
+            }
+            LoadedSeries::None(cfg, _) => {
+                Span::styled(cfg.nickname.as_str(), Tone::BadSelected.style())
             }
-            LoadedSeries::None(cfg, _) => text::with_color(cfg.nickname.as_str(), Color::LightRed),
         }
     }
 
-    pub fn process_key(key: Key, state: &mut UIState) {
-        if !matches!(*key, KeyCode::Up | KeyCode::Down) {
-            return;
-        }
+    /// Returns `span` with the day of the week `series` airs on in front of it.
+    fn with_airing_day<'a>(
+        span: Span<'a>,
+        series: &LoadedSeries,
+        state: &UIState,
+        today: Weekday,
+    ) -> Span<'a> {
+        let label = match series.id().and_then(|id| state.airing.day_label(id, today)) {
+            Some(label) => label,
+            None => return span,
+        };
+
+        // Every weekday has a label of the same width, so padding them to the width of today's lines up every name
+        let width = Text::Today.get().width().max(Text::Monday.get().width());
+        let padding = width.saturating_sub(label.width());
+
+        Span::styled(
+            format!("{}{} {}", label, " ".repeat(padding), span.content),
+            span.style,
+        )
+    }
+
+    /// Returns `span` indented by where `series` is in its franchise, with a marker in front of series that start one.
+    ///
+    /// Franchises whose other series are hidden show how many series they have.
+    fn with_franchise_marker(
+        span: &Span,
+        series: &LoadedSeries,
+        franchises: &Franchises,
+    ) -> Span<'static> {
+        let text = match (series.id(), franchises.size_of_started(series)) {
+            (Some(id), Some(_)) if franchises.is_expanded(id) => format!("- {}", span.content),
+            (_, Some(size)) => format!("+ {} ({})", span.content, size),
+            _ if franchises.is_nested(series) => format!("    {}", span.content),
+            _ => format!("  {}", span.content),
+        };
+
+        Span::styled(text, span.style)
+    }
+
+    /// Returns the progress of `series` drawn as `column`, or None if it isn't loaded.
+    ///
+    /// The progress is shown in green if the next episode is on disk, and in gray if it isn't.
+    fn progress_text(series: &LoadedSeries, column: ProgressColumn) -> Option<Span<'static>> {
+        let series = match series {
+            LoadedSeries::Complete(series) => series,
+            LoadedSeries::Partial(_, _) | LoadedSeries::None(_, _) => return None,
+        };
+
+        let watched = series.data.entry.watched_episodes().max(0) as u32;
+        let total = series.data.info.episodes.max(0) as u32;
+
+        // Series with an unknown number of episodes report 0
+        let tone = if total > 0 && watched >= total {
+            Tone::Neutral
+        } else if series.episodes.find(watched + 1).is_some() {
+            Tone::Good
+        } else {
+            Tone::Faded
+        };
+
+        let text = match column {
+            ProgressColumn::None => return None,
+            ProgressColumn::Count | ProgressColumn::Bar if total == 0 => format!("{}/?", watched),
+            ProgressColumn::Count => format!("{}/{}", watched, total),
+            ProgressColumn::Bar => {
+                let filled = (watched.min(total) * PROGRESS_BAR_WIDTH / total) as usize;
+                let empty = PROGRESS_BAR_WIDTH as usize - filled;
+
+                format!("{}{}", "█".repeat(filled), "─".repeat(empty))
+            }
+        };
+
+        Some(Span::styled(text, tone.style()))
+    }
 
+    pub fn process_key(key: Key, state: &mut UIState) {
         match *key {
-            KeyCode::Up => state.series.dec_selected(),
-            KeyCode::Down => state.series.inc_selected(),
+            KeyCode::Up | KeyCode::Down => state.select_next_visible(*key == KeyCode::Down),
+            KeyCode::Left | KeyCode::Right => {
+                state.set_selected_franchise_expanded(*key == KeyCode::Right);
+            }
             _ => (),
         }
-
-        state.init_selected_series();
     }
 
     pub fn draw<B: Backend>(state: &UIState, rect: Rect, frame: &mut Frame<B>) {
         let highlight_style = match state.input_state {
-            InputState::Idle => style::italic().fg(Color::Green),
-            _ => style::italic().fg(Color::DarkGray),
+            InputState::Idle => Tone::Good.apply(style::italic()),
+            _ => Tone::Faded.apply(style::italic()),
         };
 
-        let block = block::with_borders("Series");
+        let title = match state.series_filter {
+            SeriesFilter::All => Text::SeriesPanel.get().into(),
+            SeriesFilter::PlannedRewatch => Text::PlannedRewatchesPanel.get().into(),
+            SeriesFilter::Airing => Text::AiringPanel.get().into(),
+            SeriesFilter::Collection => Text::CollectionPanel.format(&[&state.collection.name]),
+        };
+
+        let block = block::with_borders(title.as_str());
         let list_area = block.inner(rect);
 
-        let series_names = state.series.iter().map(Self::series_text);
+        let height = list_area.height as usize;
+        let (window, selected) = state.visible_series_window(height);
+
+        let progress = state
+            .visible_series_window(height)
+            .0
+            .map(|series| Self::progress_text(series, state.config.tui.progress_column))
+            .collect::<Vec<_>>();
+
+        let progress_width = progress
+            .iter()
+            .flatten()
+            .map(|span| span.content.width())
+            .max()
+            .unwrap_or(0) as u16;
+
+        frame.render_widget(block, rect);
+
+        // There's no point in drawing the progress column when it would take up most of the list
+        let list_area = if progress_width > 0 && progress_width * 2 < list_area.width {
+            let layout = SimpleLayout::new(Direction::Horizontal).split(
+                list_area,
+                [
+                    BasicConstraint::Percentage(100),
+                    // Keeps long names from running into the progress
+                    BasicConstraint::Length(1),
+                    BasicConstraint::Length(progress_width),
+                ],
+            );
+
+            let progress = progress
+                .into_iter()
+                .map(|span| span.unwrap_or_else(|| Span::raw("")));
+
+            frame.render_widget(SimpleList::new(progress).select(None), layout[2]);
+            layout[0]
+        } else {
+            list_area
+        };
+
+        let today = Local::now().weekday();
+
+        let series_names = window.map(|series| {
+            let span = Self::series_text(series, state);
+
+            match state.series_filter {
+                SeriesFilter::Airing => Self::with_airing_day(span, series, state, today),
+                SeriesFilter::All if state.franchises.grouped => {
+                    Self::with_franchise_marker(&span, series, &state.franchises)
+                }
+                SeriesFilter::All | SeriesFilter::PlannedRewatch | SeriesFilter::Collection => span,
+            }
+        });
 
         let list = SimpleList::new(series_names)
-            .select(state.series.index() as u16)
+            .select(selected as u16)
             .highlight_symbol(Span::styled(">", highlight_style));
 
-        frame.render_widget(block, rect);
         frame.render_widget(list, list_area);
     }
 }
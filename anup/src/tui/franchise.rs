@@ -0,0 +1,282 @@
+use crate::series::LoadedSeries;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::mem;
+
+/// Added series grouped by the franchise they belong to, so the series list can nest sequels under the series that
+/// starts their franchise.
+///
+/// Series are grouped together when one is stored as a sequel of another, so a franchise can be split in two when a
+/// series that connects them hasn't been added.
+#[derive(Default)]
+pub struct Franchises {
+    /// Whether the series list is grouped by franchise.
+    pub grouped: bool,
+    /// Series ID -> the franchise it belongs to.
+    members: HashMap<i32, Member>,
+    /// The ID of the first series of every franchise with more than one series, along with how many series it has.
+    sizes: HashMap<i32, usize>,
+    /// The first series of every franchise whose other series are shown.
+    expanded: HashSet<i32>,
+}
+
+impl Franchises {
+    /// Group `series` by franchise, and sort it to match if `grouped` is true.
+    pub fn new(series: &mut Vec<LoadedSeries>, grouped: bool) -> Self {
+        let mut franchises = Self {
+            grouped,
+            ..Self::default()
+        };
+
+        franchises.rebuild(series.iter());
+
+        if grouped {
+            franchises.sort(series);
+        }
+
+        franchises
+    }
+
+    /// Group `series` by franchise, keeping the franchises that were expanded before.
+    pub fn rebuild<'a, I>(&mut self, series: I)
+    where
+        I: IntoIterator<Item = &'a LoadedSeries>,
+    {
+        let sequels = series
+            .into_iter()
+            .filter_map(LoadedSeries::data)
+            .map(|data| (data.info.id, data.info.sequel_ids.as_slice()))
+            .collect::<HashMap<_, _>>();
+
+        self.members = group(&sequels);
+        self.sizes.clear();
+
+        for member in self.members.values() {
+            *self.sizes.entry(member.head).or_default() += 1;
+        }
+
+        self.sizes.retain(|_, size| *size > 1);
+        let sizes = &self.sizes;
+        self.expanded.retain(|head| sizes.contains_key(head));
+    }
+
+    /// Move the series of each franchise in `series` to where the first one of them already is, ordered by how many
+    /// sequels away from the first series of the franchise they are.
+    ///
+    /// Series that are just as far away keep their order, so the order `series` was already sorted in still applies.
+    pub fn sort(&self, series: &mut Vec<LoadedSeries>) {
+        let mut first_index = HashMap::new();
+
+        for (index, series) in series.iter().enumerate() {
+            if let Some((head, _)) = self.position(series) {
+                first_index.entry(head).or_insert(index);
+            }
+        }
+
+        let mut keyed = mem::take(series)
+            .into_iter()
+            .enumerate()
+            .map(|(index, series)| {
+                let key = match self.position(&series) {
+                    Some((head, depth)) => (first_index[&head], depth, index),
+                    None => (index, 0, index),
+                };
+
+                (key, series)
+            })
+            .collect::<Vec<_>>();
+
+        keyed.sort_unstable_by_key(|(key, _)| *key);
+        series.extend(keyed.into_iter().map(|(_, series)| series));
+    }
+
+    /// Returns the ID of the first series of the franchise `series` belongs to, along with how far away from it
+    /// `series` is by sequel.
+    ///
+    /// Series that don't belong to a franchise with other added series are their own head.
+    pub fn position(&self, series: &LoadedSeries) -> Option<(i32, u32)> {
+        let id = series.id()?;
+
+        let position = self
+            .members
+            .get(&id)
+            .map_or((id, 0), |member| (member.head, member.depth));
+
+        Some(position)
+    }
+
+    /// Returns true if `series` should be shown in the series list.
+    ///
+    /// Every series is shown when the list isn't grouped, and otherwise only the first series of each franchise and
+    /// the rest of the series of expanded ones are.
+    pub fn is_shown(&self, series: &LoadedSeries) -> bool {
+        if !self.grouped {
+            return true;
+        }
+
+        match self.position(series) {
+            Some((head, _)) => series.id() == Some(head) || self.expanded.contains(&head),
+            None => true,
+        }
+    }
+
+    /// Returns the number of series in the franchise started by `series`, or `None` if it doesn't start one.
+    pub fn size_of_started(&self, series: &LoadedSeries) -> Option<usize> {
+        series.id().and_then(|id| self.sizes.get(&id).copied())
+    }
+
+    /// Returns true if `series` belongs to a franchise without being the first series of it.
+    pub fn is_nested(&self, series: &LoadedSeries) -> bool {
+        self.position(series)
+            .map_or(false, |(head, _)| series.id() != Some(head))
+    }
+
+    #[inline(always)]
+    pub fn is_expanded(&self, head: i32) -> bool {
+        self.expanded.contains(&head)
+    }
+
+    /// Show or hide the series of the franchise `series` belongs to.
+    ///
+    /// Returns the ID of the first series of the franchise, or `None` if `series` isn't in one.
+    pub fn set_expanded(&mut self, series: &LoadedSeries, expanded: bool) -> Option<i32> {
+        let (head, _) = self.position(series)?;
+
+        if !self.sizes.contains_key(&head) {
+            return None;
+        }
+
+        if expanded {
+            self.expanded.insert(head);
+        } else {
+            self.expanded.remove(&head);
+        }
+
+        Some(head)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Member {
+    /// The ID of the first series of the franchise.
+    head: i32,
+    /// How many sequels away from the first series of the franchise the series is.
+    depth: u32,
+}
+
+/// Group every series in `sequels`, which maps the ID of each series to the IDs of its sequels, into franchises.
+///
+/// The first series of each franchise is the one that isn't a sequel of any other, or the one with the lowest ID when
+/// every series is (which happens when a movie lists a season as its sequel and the season lists the movie as well).
+fn group(sequels: &HashMap<i32, &[i32]>) -> HashMap<i32, Member> {
+    let mut neighbors: HashMap<i32, Vec<i32>> = HashMap::with_capacity(sequels.len());
+
+    for (&id, ids) in sequels {
+        neighbors.entry(id).or_default();
+
+        for &sequel in ids.iter().filter(|sequel| sequels.contains_key(sequel)) {
+            neighbors.entry(id).or_default().push(sequel);
+            neighbors.entry(sequel).or_default().push(id);
+        }
+    }
+
+    let is_sequel = sequels
+        .values()
+        .flat_map(|ids| ids.iter().copied())
+        .collect::<HashSet<_>>();
+
+    let mut ids = sequels.keys().copied().collect::<Vec<_>>();
+    ids.sort_unstable();
+
+    let mut members = HashMap::with_capacity(sequels.len());
+    let mut seen = HashSet::with_capacity(sequels.len());
+
+    for &id in &ids {
+        if seen.contains(&id) {
+            continue;
+        }
+
+        let mut franchise = Vec::new();
+        let mut pending = vec![id];
+        seen.insert(id);
+
+        while let Some(current) = pending.pop() {
+            franchise.push(current);
+
+            for &next in &neighbors[&current] {
+                if seen.insert(next) {
+                    pending.push(next);
+                }
+            }
+        }
+
+        let head = franchise
+            .iter()
+            .copied()
+            .filter(|id| !is_sequel.contains(id))
+            .min()
+            .or_else(|| franchise.iter().copied().min())
+            .unwrap_or(id);
+
+        for (id, depth) in depths(head, &franchise, sequels) {
+            members.insert(id, Member { head, depth });
+        }
+    }
+
+    members
+}
+
+/// Returns how many sequels away from `head` each series in `franchise` is.
+///
+/// Series that can only be reached by going back through prequels are placed after every other one.
+fn depths(head: i32, franchise: &[i32], sequels: &HashMap<i32, &[i32]>) -> Vec<(i32, u32)> {
+    let mut depths = HashMap::with_capacity(franchise.len());
+    let mut pending = VecDeque::new();
+
+    depths.insert(head, 0);
+    pending.push_back(head);
+
+    while let Some(current) = pending.pop_front() {
+        let depth = depths[&current] + 1;
+
+        for &sequel in sequels.get(&current).copied().unwrap_or_default() {
+            if sequels.contains_key(&sequel) && !depths.contains_key(&sequel) {
+                depths.insert(sequel, depth);
+                pending.push_back(sequel);
+            }
+        }
+    }
+
+    franchise
+        .iter()
+        .map(|id| (*id, depths.get(id).copied().unwrap_or(u32::MAX)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_franchises() {
+        // A season with a sequel season and a movie, the movie listing the first season back, and a separate series
+        let sequels = [
+            (1, &[2, 3][..]),
+            (2, &[4][..]),
+            (3, &[1][..]),
+            (4, &[][..]),
+            (10, &[99][..]),
+        ]
+        .iter()
+        .copied()
+        .collect::<HashMap<_, _>>();
+
+        let members = group(&sequels);
+        let member = |id| members[&id];
+
+        assert_eq!(member(1), Member { head: 1, depth: 0 });
+        assert_eq!(member(2), Member { head: 1, depth: 1 });
+        assert_eq!(member(3), Member { head: 1, depth: 1 });
+        assert_eq!(member(4), Member { head: 1, depth: 2 });
+        assert_eq!(member(10), Member { head: 10, depth: 0 });
+    }
+}
@@ -0,0 +1,98 @@
+use crate::config::DatesConfig;
+use crate::tui::locale::Text;
+use anime::remote::{Remote, RemoteService};
+use anime::ErrorKind;
+use std::time::{Duration, Instant};
+
+/// How long the remote service can take to answer before it's considered to be having problems.
+const SLOW_RESPONSE: Duration = Duration::from_secs(5);
+
+/// How the remote service answered the last time it was pinged.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Health {
+    /// The service answered quickly.
+    Healthy,
+    /// The service answered, but slowly or with an error on its end, so the problem is most likely with the service.
+    Degraded,
+    /// The service couldn't be reached at all, which usually means the local network is down.
+    Unreachable,
+}
+
+impl Health {
+    /// Ping `remote` and judge its health by how it answers.
+    ///
+    /// This makes a request, so it shouldn't be done while holding the state lock.
+    pub fn check(remote: &Remote) -> Self {
+        let started = Instant::now();
+        let result = remote.ping();
+
+        Self::from_response(&result, started.elapsed())
+    }
+
+    fn from_response(result: &anime::Result<()>, elapsed: Duration) -> Self {
+        let err = match result {
+            Ok(()) if elapsed >= SLOW_RESPONSE => return Self::Degraded,
+            Ok(()) => return Self::Healthy,
+            Err(err) => err,
+        };
+
+        // The service sends errors like these when it's down or overloaded, so it was at least reachable
+        if err.is_service_unavailable() {
+            return Self::Degraded;
+        }
+
+        match err.kind() {
+            ErrorKind::RateLimited | ErrorKind::Parse => Self::Degraded,
+            // Refusing the request still means the service is up and answering
+            ErrorKind::Auth | ErrorKind::NotFound => Self::Healthy,
+            _ => Self::Unreachable,
+        }
+    }
+
+    /// Returns the tag to show in the log's title, followed by when a list entry was last synced if any were.
+    ///
+    /// Returns `None` while the service is healthy.
+    pub fn tag(self, last_sync: Option<i64>, dates: &DatesConfig) -> Option<String> {
+        let mut tag = match self {
+            Self::Healthy => return None,
+            Self::Degraded => Text::ServiceDegradedTag.get().to_string(),
+            Self::Unreachable => Text::ServiceUnreachableTag.get().to_string(),
+        };
+
+        if let Some(timestamp) = last_sync {
+            tag.push(' ');
+            tag.push_str(&Text::LastSyncedTag.format(&[&dates.format_timestamp(timestamp)]));
+        }
+
+        Some(tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn health_from_response() {
+        let quick = Duration::from_millis(200);
+
+        assert_eq!(Health::from_response(&Ok(()), quick), Health::Healthy);
+        assert_eq!(
+            Health::from_response(&Ok(()), SLOW_RESPONSE),
+            Health::Degraded
+        );
+
+        let down = anime::Error::ServiceUnavailable { code: 503 };
+        assert_eq!(Health::from_response(&Err(down), quick), Health::Degraded);
+
+        let refused = anime::Error::NeedAuthentication;
+        assert_eq!(Health::from_response(&Err(refused), quick), Health::Healthy);
+
+        let offline = anime::Error::IO(io::Error::new(io::ErrorKind::NotConnected, "offline"));
+        assert_eq!(
+            Health::from_response(&Err(offline), quick),
+            Health::Unreachable
+        );
+    }
+}
@@ -0,0 +1,477 @@
+use anime::remote::Status;
+use once_cell::sync::OnceCell;
+use serde_derive::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
+
+static LANGUAGE: OnceCell<Language> = OnceCell::new();
+
+/// A language the TUI can be shown in.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Language {
+    #[serde(alias = "en")]
+    English,
+    #[serde(alias = "ja")]
+    Japanese,
+}
+
+impl Language {
+    /// Returns the language set by the `LC_ALL`, `LC_MESSAGES`, or `LANG` environment variables, in that order.
+    ///
+    /// English is used when none of them are set to a supported language.
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_locale(&value))
+            .unwrap_or(Self::English)
+    }
+
+    /// Parse the language of a locale like `ja_JP.UTF-8`.
+    fn from_locale(locale: &str) -> Option<Self> {
+        let code = locale.split(&['_', '.'][..]).next()?;
+
+        match code.to_ascii_lowercase().as_str() {
+            "en" | "c" | "posix" => Some(Self::English),
+            "ja" => Some(Self::Japanese),
+            _ => None,
+        }
+    }
+
+    fn current() -> Self {
+        LANGUAGE.get().copied().unwrap_or(Self::English)
+    }
+}
+
+/// Set the language text is shown in.
+///
+/// This can only be done once, and text is shown in English until it is.
+pub fn init(language: Language) {
+    LANGUAGE.set(language).ok();
+}
+
+macro_rules! texts {
+    ($($name:ident => $english:expr, $japanese:expr;)+) => {
+        /// Text shown in the TUI.
+        ///
+        /// Text containing `{0}`, `{1}`, and so on should be shown with `Text::format`.
+        #[derive(Copy, Clone, Debug)]
+        pub enum Text {
+            $($name,)+
+        }
+
+        impl Text {
+            #[cfg(test)]
+            const ALL: &'static [Self] = &[$(Self::$name,)+];
+
+            fn in_language(self, language: Language) -> &'static str {
+                match (self, language) {
+                    $(
+                    (Self::$name, Language::English) => $english,
+                    (Self::$name, Language::Japanese) => $japanese,
+                    )+
+                }
+            }
+        }
+    };
+}
+
+impl Text {
+    /// Returns the text in the current language.
+    #[inline(always)]
+    pub fn get(self) -> &'static str {
+        self.in_language(Language::current())
+    }
+
+    /// Returns the text in the current language with `{0}` replaced by the first item of `args`, `{1}` by the second, and so on.
+    pub fn format(self, args: &[&dyn fmt::Display]) -> String {
+        args.iter()
+            .enumerate()
+            .fold(self.get().to_string(), |text, (i, arg)| {
+                text.replace(&format!("{{{}}}", i), &arg.to_string())
+            })
+    }
+}
+
+impl From<Status> for Text {
+    fn from(status: Status) -> Self {
+        match status {
+            Status::Watching => Self::Watching,
+            Status::Completed => Self::Completed,
+            Status::OnHold => Self::OnHold,
+            Status::Dropped => Self::Dropped,
+            Status::PlanToWatch => Self::PlanToWatch,
+            Status::Rewatching => Self::Rewatching,
+        }
+    }
+}
+
+texts! {
+    // Panel titles
+    SeriesPanel => "Series", "シリーズ";
+    PlannedRewatchesPanel => "Planned Rewatches", "再視聴予定";
+    AiringPanel => "Airing", "放送中";
+    CollectionPanel => "Collection: {0}", "コレクション: {0}";
+    InfoPanel => "Info", "情報";
+    CommandPanel => "Enter Command", "コマンドを入力";
+    TerminalTooSmall => "Too small: needs {0}x{1}", "小さすぎます：{0}x{1} 必要";
+    PlayingUntracked => "Playing episode {0} of {1}, which won't count as watched", "{1} の第{0}話を再生中（視聴済みにはなりません）";
+    SkippedFiller => "Skipped filler episodes {0} to {1} of {2}", "{2} のフィラー第{0}話〜第{1}話をスキップしました";
+    ExtraEpisodesFound => "{0} has {1} episode(s) on disk past its official count, which can be played by setting episode.extra_episodes", "{0} には公式の話数を超えるエピソードが {1} 本あります。episode.extra_episodes を設定すると再生できます";
+    LogPanel => "Error Log [press '{0}' for command entry]", "ログ ['{0}' でコマンド入力]";
+    ScoresPanel => "Completed Series Scores", "完了したシリーズのスコア";
+    GenresPanel => "Genres", "ジャンル";
+    StatsPanel => "Statistics", "統計";
+    AddSeriesPanel => "Add Series", "シリーズを追加";
+    UpdateSeriesPanel => "Update Selected Series", "選択したシリーズを更新";
+    SelectSeriesPanel => "No confident match found - select a series from the list", "確実な一致が見つかりません - 一覧からシリーズを選んでください";
+    SelectCachedSeriesPanel => "No confident match found in cached search results - select a series from the list", "キャッシュされた検索結果に確実な一致が見つかりません - 一覧からシリーズを選んでください";
+    DeleteSeriesPanel => "Delete Series", "シリーズを削除";
+    DuplicateSeriesPanel => "Series Already Added", "追加済みのシリーズ";
+    SplitCourPanel => "Sequel Found In Folder", "フォルダ内に続編を検出";
+    SplitSeriesPanel => "Split Series", "シリーズを分割";
+    SplitNamePanel => "Enter Name For Series", "シリーズの名前を入力";
+    DropSeriesPanel => "Drop Series", "シリーズを中止";
+    HoldSeriesPanel => "Put Series On Hold", "シリーズを保留";
+    ChangeStatusPanel => "Change Status", "ステータスを変更";
+    CompletedPanel => "Completed - {0}", "完了 - {0}";
+    ScorePanel => "Score - {0}", "スコア - {0}";
+    HistoryPanel => "Playback History - {0}", "再生履歴 - {0}";
+    AddUserPanel => "Add User", "ユーザーを追加";
+    TasksPanel => "Background Tasks", "バックグラウンドタスク";
+    NotificationsPanel => "AniList Notifications", "AniListの通知";
+    EventsPanel => "Saved Errors & Notifications", "保存されたエラーと通知";
+    AuditLogPanel => "Sent To AniList", "AniListへの送信履歴";
+    ProfilePanel => "AniList Profile", "AniListのプロフィール";
+    ChordPanel => "Keys After {0}", "{0} の後のキー";
+    ChangelogPanel => "What's New In {0} (Running {1})", "{0} の新機能（現在 {1}）";
+    QuickSwitchPanel => "Jump To Series", "シリーズへ移動";
+
+    // Hints
+    ConfirmHint => "Enter - Confirm", "Enter - 確定";
+    SkipHint => "Esc - Skip", "Esc - スキップ";
+    CloseHint => "Esc - Close", "Esc - 閉じる";
+    AddSequelHint => "A - Add sequel", "A - 続編を追加";
+    ToggleFileDeletionHint => "D - Toggle file deletion", "D - ファイル削除の切り替え";
+    TogglePathDeletionHint => "D - Toggle path deletion", "D - パス削除の切り替え";
+    OpenExistingHint => "O - Open existing", "O - 既存のシリーズを開く";
+    ReplaceHint => "C - Continue and replace", "C - 続行して置き換え";
+    AddBothHint => "Enter - Add both", "Enter - 両方を追加";
+    AddOnlyFirstHint => "O - Only add {0}", "O - {0} のみ追加";
+    SplitAllHint => "S - Split All", "S - すべて分割";
+    SwitchHint => "Enter - Select", "Enter - 選択";
+    SwitchAndPlayHint => "Alt + Enter - Select and play", "Alt + Enter - 選択して再生";
+    AddSplitSeriesHint => "Enter - Add Series", "Enter - シリーズを追加";
+    OpenAuthUrlHint => "Open auth URL", "認証URLを開く";
+    GoOfflineHint => "O - Go offline", "O - オフラインにする";
+    RemoveAccountHint => "D - Remove account", "D - アカウントを削除";
+    LoginHint => "Enter - Login as selected", "Enter - 選択したアカウントでログイン";
+    WatchPartyHint => "P - Toggle watch party", "P - ウォッチパーティーの切り替え";
+    CancelTaskHint => "C - Cancel selected", "C - 選択したタスクを中止";
+    ClearTasksHint => "X - Clear finished", "X - 完了したタスクを消去";
+    ClearEventsHint => "X - Clear all", "X - すべて消去";
+    SelectedSeriesOnlyHint => "Tab - Only show the selected series", "Tab - 選択したシリーズのみ表示";
+    AllSeriesHint => "Tab - Show every series", "Tab - すべてのシリーズを表示";
+    DismissUpdateHint => "X - Don't show this version again | Esc - Close", "X - このバージョンを再表示しない | Esc - 閉じる";
+    NoReleaseNotes => "This release doesn't have any notes", "このリリースにはノートがありません";
+
+    // Info panel
+    NoAccountsHeader => "No Accounts Added", "アカウントがありません";
+    NoAccountsBody => "Add an account by pressing 'u' to open\nuser management and then by pressing tab\nto switch to the add user panel.\n\nThen open the auth URL in your browser\nby pressing Ctrl + O, and follow its instructions.\n\nOnce you have a token, paste it in with either\nCtrl + Shift + V or Ctrl + V.\n\nMore detailed instructions here:",
+        "'u' でユーザー管理を開き、tab で\nユーザー追加パネルに切り替えて\nアカウントを追加してください。\n\n次に Ctrl + O で認証URLをブラウザで開き、\n表示される手順に従ってください。\n\nトークンを取得したら、Ctrl + Shift + V か\nCtrl + V で貼り付けてください。\n\n詳しい手順はこちら:";
+    NoSeriesHeader => "No Series Found", "シリーズがありません";
+    NoSeriesBody => "Add one by pressing the 'a' key.\n\nThe opened panel will require you to specify\na name for the series you want to add.\n\nFor automatic detection, the name should be\nsimilar to the name of the folder the series\nis in on disk.",
+        "'a' キーでシリーズを追加してください。\n\n開いたパネルで、追加したいシリーズの\n名前を入力する必要があります。\n\n自動検出のため、名前はディスク上の\nシリーズのフォルダ名に\n近いものにしてください。";
+    SeriesErrorHeader => "Error Loading Series", "シリーズの読み込みエラー";
+    NextEpisodeTitle => "Next - Ep {0}: {1}", "次 - 第{0}話: {1}";
+    WatchTime => "Watch Time", "視聴時間";
+    TimeLeft => "Time Left", "残り時間";
+    TimeWatched => "Time Watched", "視聴済み時間";
+    EpisodeLength => "Episode Length", "エピソードの長さ";
+    Progress => "Progress", "進捗";
+    Score => "Score", "スコア";
+    QuickSwitchSearch => "Nickname, Title, or Alias", "ニックネーム、タイトル、または別名";
+    NoMatchingSeries => "No series match", "一致するシリーズはありません";
+    Status => "Status", "ステータス";
+    StartDate => "Start Date", "開始日";
+    FinishDate => "Finish Date", "終了日";
+    Rewatched => "Rewatched", "再視聴回数";
+    EpisodeRatings => "Episode Ratings", "エピソード評価";
+    RatingSummary => "{0} avg (best #{1}, worst #{2})", "平均 {0}（最高 #{1}、最低 #{2}）";
+    Episodes => "Episodes", "エピソード";
+    MinutesUntilProgression => "{0} Minutes Until Progression", "進捗まであと {0} 分";
+    LoggingInAs => "Logging In As ", "ログイン中: ";
+    CurrentlyOffline => "Currently Offline", "オフライン";
+
+    // Watch statuses
+    Watching => "Watching", "視聴中";
+    Completed => "Completed", "視聴完了";
+    OnHold => "On Hold", "保留";
+    Dropped => "Dropped", "中止";
+    PlanToWatch => "Plan To Watch", "視聴予定";
+    Rewatching => "Rewatching", "再視聴中";
+
+    // Statistics and summary panels
+    Mean => "Mean: ", "平均: ";
+    WeeklyGoal => "   This Week: ", "   今週: ";
+    Genre => "Genre", "ジャンル";
+    Watched => "Watched", "視聴済み";
+    TimeSpent => "Time Spent", "かかった時間";
+    Pace => "Pace", "ペース";
+    Sequel => "Sequel", "続編";
+    NoSequel => "None", "なし";
+    BacklogPanel => "Downloaded But Unwatched - {0} Episodes ({1})", "ダウンロード済み未視聴 - {0}話 ({1})";
+    Unwatched => "Unwatched", "未視聴";
+
+    // Status and deletion panels
+    FilesWillBeDeleted => "Local files will be deleted.", "ローカルのファイルは削除されます。";
+    FilesWillBeKept => "Local files will be kept.", "ローカルのファイルは残されます。";
+    FilesStillSeeding => "Local files are still seeding! Press enter again to delete them anyway.", "ローカルのファイルはまだシード中です！それでも削除するにはもう一度Enterを押してください。";
+    StatusWillBeSet => "{0} will be set to {1}", "{0} は{1}に変更されます";
+    SeriesPath => "Series Path:", "シリーズのパス:";
+    PathOnDisk => "The series path on disk ", "ディスク上のシリーズのパスは";
+    PathWillBeTrashed => "will be moved to the trash.", "ゴミ箱に移動されます。";
+    PathWillBeKept => "will not be deleted.", "削除されません。";
+    PathStillSeeding => "is still seeding! Press enter again to trash it anyway.", "まだシード中です！それでもゴミ箱に移動するにはもう一度Enterを押してください。";
+    AlreadyAddedAs => "{0} has already been added as {1}", "{0} は {1} として追加済みです";
+    ReplaceWith => "Continuing will replace it with:", "続行すると次のものに置き換えられます:";
+    ContinuesInto => "The episodes of {0} continue into {1}", "{0} のエピソードは {1} に続いています";
+    AddBothAs => "Both can be added with their episodes renumbered:", "エピソード番号を振り直して両方を追加できます:";
+    EpisodeRange => "{0}: episodes {1} - {2}", "{0}: エピソード {1} - {2}";
+
+    // Playback history panel
+    NoEpisodesPlayed => "No episodes have been played yet", "まだエピソードが再生されていません";
+    Episode => "Episode", "エピソード";
+    Started => "Started", "開始";
+    Played => "Played", "再生時間";
+    Result => "Result", "結果";
+    Counted => "Counted", "カウント済み";
+    NotCounted => "Not counted", "カウントなし";
+    NotCountedShort => "Not counted (too short)", "カウントなし (短すぎる)";
+    NotCountedExitCode => "Not counted (player exited with {0})", "カウントなし (プレーヤーが {0} で終了)";
+    NotCountedKilled => "Not counted (player was killed)", "カウントなし (プレーヤーが強制終了)";
+
+    // Tasks panel
+    NoTasks => "No background tasks have been started", "バックグラウンドタスクはまだありません";
+    NoNotifications => "You don't have any notifications", "通知はありません";
+    NotificationsFailed => "Failed to get notifications: {0}", "通知を取得できませんでした：{0}";
+    AiringNotification => "Episode {0} of {1} aired", "{1}の第{0}話が放送されました";
+    RelatedMediaNotification => "{0}, which is related to a series on your list, was added", "リストにあるシリーズの関連作品「{0}」が追加されました";
+    FollowingNotification => "{0} started following you", "{0}さんにフォローされました";
+    Sent => "Sent", "日時";
+    Notification => "Notification", "通知";
+    TrackedSeries => "Tracked Series", "追跡中のシリーズ";
+    SelectTrackedSeriesHint => "Enter - Select the tracked series", "Enter - 追跡中のシリーズを選択";
+
+    // Audit log panel
+    NoAuditRecords => "Nothing has been sent to AniList yet", "まだAniListに何も送信されていません";
+    AuditSeries => "Series", "シリーズ";
+    Changes => "Changes", "変更";
+    NoChanges => "sent again without changes", "変更なしで再送信";
+    RemovedFromList => "removed from list", "リストから削除";
+    Priority => "Priority", "優先度";
+
+    // Saved events panel
+    NoEvents => "No errors or notifications have been saved", "保存されたエラーや通知はありません";
+    UnreadEvents => "[{0} unread]", "[未読 {0} 件]";
+    PrivateSessionTag => "[private]", "[プライベート]";
+    PresentationLockTag => "[locked]", "[ロック中]";
+    ServiceDegradedTag => "[AniList degraded]", "[AniList 不調]";
+    ServiceUnreachableTag => "[AniList unreachable]", "[AniList 接続不可]";
+    LastSyncedTag => "(last synced {0})", "(最終同期 {0})";
+    Hidden => "hidden", "非表示";
+    UpdateAvailable => "[v{0} available: Shift + V]", "[v{0} が利用可能：Shift + V]";
+    Logged => "Logged", "日時";
+    EventKind => "Kind", "種類";
+    Message => "Message", "メッセージ";
+    EventError => "Error", "エラー";
+    EventWarning => "Warning", "警告";
+
+    // Profile panel
+    NoProfileStats => "AniList doesn't have any statistics for your list yet", "AniListにはまだリストの統計がありません";
+    ProfileFailed => "Failed to get profile statistics: {0}", "プロフィールの統計を取得できませんでした：{0}";
+    DaysWatched => "Days Watched: ", "視聴日数: ";
+    EpisodesWatched => "   Episodes Watched: ", "   視聴済みエピソード: ";
+    MeanScore => "   Mean Score: ", "   平均スコア: ";
+    OnRemote => "On AniList", "AniList上";
+    AddedLocally => "Added Here", "追加済み";
+    Total => "Total", "合計";
+    Task => "Task", "タスク";
+    TaskRunning => "Running", "実行中";
+    TaskCancelling => "Cancelling..", "中止中..";
+    TaskDone => "Done", "完了";
+    TaskCancelledStatus => "Cancelled", "中止";
+    TaskFailed => "Failed", "失敗";
+
+    // Key chord hints
+    ChordHistory => "Playback History", "再生履歴";
+    ChordUsers => "Users", "ユーザー";
+    ChordNextCollection => "Next Collection", "次のコレクション";
+
+    // User panel
+    PasteToken => "Paste Token", "トークンを貼り付け";
+    Service => "Service", "サービス";
+    Username => "Username", "ユーザー名";
+    Profile => "Profile", "プロファイル";
+    Added => "Added", "追加日";
+    LastUsed => "Last Used", "最終使用";
+    Expires => "Expires", "有効期限";
+    WatchParty => "Party", "パーティー";
+    LoginFailed => "Failed", "失敗";
+    Never => "Never", "なし";
+    Expired => "Expired", "期限切れ";
+    Unknown => "Unknown", "不明";
+    Today => "Today", "今日";
+    Monday => "Mon", "月";
+    Tuesday => "Tue", "火";
+    Wednesday => "Wed", "水";
+    Thursday => "Thu", "木";
+    Friday => "Fri", "金";
+    Saturday => "Sat", "土";
+    Sunday => "Sun", "日";
+    NoTokenDetails => "No token details available", "トークンの詳細はありません";
+    FullAccess => "Full access", "フルアクセス";
+    UserId => "User ID ", "ユーザーID ";
+    Created => " | Created ", " | 作成日 ";
+    Scope => "Scope ", "スコープ ";
+
+    // Series inputs
+    NameLabel => "Name", "名前";
+    IdLabel => "ID", "ID";
+    PathLabel => "Path", "パス";
+    PatternLabel => "Episode Pattern", "エピソードのパターン";
+    RelativePath => "Relative Path", "相対パス";
+    FoundEpisodes => "Found Episodes", "見つかったエピソード";
+    Detected => "Detected", "検出済み";
+    NothingDetected => "Nothing Detected", "検出なし";
+    SuggestedPatterns => "Try One Of These Patterns", "次のパターンを試してください";
+    NoneFound => "none", "なし";
+    NeedsSplitting => "needs splitting", "分割が必要";
+    NameEmpty => "Name must not be empty", "名前を入力してください";
+    IdNotNumber => "ID must be a positive number", "IDは正の数にしてください";
+    PathMissing => "Path must exist", "パスが存在しません";
+    PatternMissingMarker => "Must mark episode location with {0}", "エピソードの位置を {0} で示してください";
+
+    // Split series panel
+    Loading => "Loading..", "読み込み中..";
+    LoadingFailed => "Failed..", "失敗..";
+    SplitType => "Type", "種類";
+    NoSeriesToSplit => "No Series To Split", "分割するシリーズがありません";
+
+    // Log messages
+    RemoteUnavailable => "AniList is unavailable, switched to offline mode", "AniList に接続できないため、オフラインモードに切り替えました";
+    SyncedLater => "unsynced changes will be synced once back online", "未同期の変更はオンラインに戻ったときに同期されます";
+    EpisodeReady => "episode {0} of {1} is ready to play", "{1} のエピソード {0} を再生できます";
+    TitledEpisodeReady => "episode {0} of {1} is ready to play: {2}", "{1} のエピソード {0}「{2}」を再生できます";
+    EpisodeStillMissing => "episode {0} of {1} was found, but episode {2} is still missing", "{1} のエピソード {0} が見つかりましたが、エピソード {2} がまだありません";
+    AlreadyPlaying => "episode {0} of {1} is already being played", "{1} のエピソード {0} はすでに再生中です";
+    ConfirmStop => "stop playing episode {0} of {1}? it won't be recorded (y/N)", "{1} のエピソード {0} の再生を停止しますか？記録されません (y/N)";
+    StoppedPlaying => "stopped playing episode {0} of {1}", "{1} のエピソード {0} の再生を停止しました";
+    PlayerExitedWith => "the player exited with status {0}", "プレーヤーがステータス {0} で終了しました";
+    PlayerKilled => "the player was killed", "プレーヤーが強制終了されました";
+    ConfirmCount => "{0}; count episode {1} of {2} anyway? (y/N)", "{0}。{2} のエピソード {1} を視聴済みにしますか？ (y/N)";
+    ClosedWhilePlaying => "anup closed while the episode was playing", "エピソードの再生中に anup が終了しました";
+    PlaybackInterrupted => "anup closed while episode {0} of {1} was playing, so it was not counted", "{1} のエピソード {0} の再生中に anup が終了したため、視聴済みになりませんでした";
+    PlayedTooShort => "episode {0} of {1} was only played for {2}s, so it was not counted as watched", "{1} のエピソード {0} は {2} 秒しか再生されなかったため、視聴済みになりませんでした";
+    TimerPausedForSleep => "paused the timer of episode {0} of {1} while the system was asleep", "システムのスリープ中は {1} のエピソード {0} のタイマーを一時停止しました";
+    ExitWhilePlaying => "episode {0} of {1} is still being played; anup daemon will count it once the player closes", "{1} のエピソード {0} が再生中です。プレーヤーが閉じると anup daemon が視聴済みにします";
+    CountedByDaemon => "counted episode {0} of {1} as watched after its player closed", "プレーヤーが閉じたため {1} のエピソード {0} を視聴済みにしました";
+    ExitWithUnsynced => "{0} list entries have changes that won't be synced on exit", "{0} 件のリスト項目に終了時に同期されない変更があります";
+    ExitWithTasks => "{0} background tasks are still running and will be stopped", "{0} 件のバックグラウンドタスクが実行中で、停止されます";
+    ConfirmExit => "exit anyway? (y/N)", "それでも終了しますか？ (y/N)";
+    ConfirmMoved => "the folder of {0} wasn't found, but {1} looks like it; use it instead? (y/N)", "{0} のフォルダが見つかりませんが、{1} が該当するようです。代わりに使いますか？ (y/N)";
+    SeriesMoved => "{0} now uses {1}", "{0} は {1} を使うようになりました";
+    ConfirmOrphans => "found {0} rows left behind by series that no longer exist ({1}); remove them? (y/N)", "存在しないシリーズの行が {0} 件残っています ({1})。削除しますか？ (y/N)";
+    ConfirmSyncConflict => "{0} is a conflicting copy of the database; {1}; merge it? use `anup merge-conflicts --dry-run` to review it first (y/N)", "{0} はデータベースの競合コピーです。{1}。統合しますか？ 先に確認するには `anup merge-conflicts --dry-run` を使ってください (y/N)";
+    SyncConflictMerged => "merged {0} into the database", "{0} をデータベースに統合しました";
+    OrphansRemoved => "removed {0} rows left behind by series that no longer exist", "存在しないシリーズの行を {0} 件削除しました";
+    SeriesMerged => "{0} no longer exists on AniList, but was likely merged into {1}; use `relink {2}` to link to it", "{0} は AniList に存在しませんが、{1} に統合された可能性があります。`relink {2}` でリンクしてください";
+    SeriesRemoved => "{0} no longer exists on AniList; use the relink command to link it to another series", "{0} は AniList に存在しません。relink コマンドで別のシリーズにリンクしてください";
+    JoinedWatchParty => "{0} joined the watch party; their list will be updated whenever an episode is completed", "{0} がウォッチパーティーに参加しました。エピソードを視聴するたびにリストが更新されます";
+    LeftWatchParty => "{0} left the watch party", "{0} がウォッチパーティーから抜けました";
+    AliasAdded => "{0} is now an alias of {1}", "{0} を {1} の別名に追加しました";
+    AliasRemoved => "{0} is no longer an alias of {1}", "{0} を {1} の別名から削除しました";
+    Aliases => "aliases of {0}: {1}", "{0} の別名: {1}";
+    NoAliases => "{0} has no aliases", "{0} には別名がありません";
+    CollectionAdded => "added {0} to {1}", "{0} を {1} に追加しました";
+    CollectionRemoved => "removed {0} from {1}", "{0} を {1} から削除しました";
+    Collections => "collections with {0}: {1}", "{0} を含むコレクション: {1}";
+    EntryRemoved => "removed {0} from your list; use remotesync on to add it back", "{0} をリストから削除しました。remotesync on で元に戻せます";
+    ProgressPulled => "pulled the progress of {0} from your list: {1} episodes watched, {2}", "リストから {0} の進捗を取得しました：{1}話視聴済み、{2}";
+    EntryNotListed => "{0} wasn't on your list; it will no longer be synced", "{0} はリストにありませんでした。今後は同期されません";
+    NoCollections => "{0} isn't in any collections", "{0} はどのコレクションにも含まれていません";
+    FillerSet => "filler episodes of {0} set to {1}", "{0} のフィラーを {1} に設定しました";
+    FillerCleared => "{0} no longer has filler episodes", "{0} のフィラーを削除しました";
+    FillerEpisodes => "filler episodes of {0}: {1}", "{0} のフィラー: {1}";
+    NoFiller => "{0} has no filler episodes", "{0} にはフィラーがありません";
+    EpisodeOverrideSet => "{0} is now episode {1} of {2}", "{0} を {2} の第{1}話にしました";
+    EpisodeOverridesCleared => "{0} no longer has episode overrides", "{0} のエピソード指定を削除しました";
+    EpisodeOverrides => "episode overrides of {0}: {1}", "{0} のエピソード指定: {1}";
+    NoEpisodeOverrides => "{0} has no episode overrides", "{0} にはエピソード指定がありません";
+    Suggested => "try watching {0} next, since {1}", "次は {0} を見てみましょう：{1}";
+    ShareCopied => "copied \"{0}\" to the clipboard", "「{0}」をクリップボードにコピーしました";
+    SuggestReady => "its next episode is ready", "次のエピソードの準備ができています";
+    SuggestAlmostDone => "it's almost finished", "もうすぐ見終わります";
+    SuggestFinaleAiring => "its finale is airing soon", "もうすぐ最終回が放送されます";
+    SuggestNeglected => "it hasn't been watched in a while", "しばらく見ていません";
+    LoggingInActivity => "logging in as {0}", "{0} としてログイン中";
+    SyncingActivity => "syncing list entries", "リストを同期中";
+    LookupActivity => "looking up {0} on AniList", "AniList で {0} を検索中";
+    LookupCachedActivity => "looking up {0} in cached search results", "キャッシュされた検索結果で {0} を検索中";
+    AddedFromCache => "{0} was found in cached search results since you're offline", "オフラインのため、キャッシュされた検索結果から {0} が見つかりました";
+    MoreActivities => "(+{0} more)", "(他 {0} 件)";
+    LookupInProgress => "a series is already being looked up", "すでにシリーズを検索中です";
+    SyncAllTask => "syncing every series", "すべてのシリーズを同期";
+    SyncAllSummary => "synced {0} of {1} series", "{1} 件中 {0} 件のシリーズを同期しました";
+    PrivateSessionStarted => "private session started; changes will only be saved locally until you enter private off", "プライベートセッションを開始しました。private off を入力するまで変更はローカルにのみ保存されます";
+    PresentationLocked => "presentation lock on; scores and statuses are hidden and the list won't change until you press {0} again", "プレゼンテーションロックを有効にしました。{0} をもう一度押すまでスコアとステータスは非表示になり、リストは変更されません";
+    PresentationUnlocked => "presentation lock off", "プレゼンテーションロックを解除しました";
+    PrivateSessionEnded => "private session ended; changes made during it will be synced", "プライベートセッションを終了しました。その間の変更は同期されます";
+    RefreshAllTask => "refreshing series info", "シリーズ情報を更新";
+    RefreshAllSummary => "refreshed the info of {0} of {1} series", "{1} 件中 {0} 件のシリーズ情報を更新しました";
+    AiringTask => "looking up airing schedules", "放送スケジュールを検索";
+    AiringSummary => "{0} of {1} series being watched are airing", "視聴中の {1} 件中 {0} 件が放送中です";
+    TaskFinished => "{0}: {1}", "{0}: {1}";
+    TaskCancelled => "{0} was cancelled: {1}", "{0} を中止しました: {1}";
+    ConfigProblem => "config problem: {0}", "設定の問題: {0}";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_locales() {
+        assert_eq!(
+            Language::from_locale("ja_JP.UTF-8"),
+            Some(Language::Japanese)
+        );
+        assert_eq!(Language::from_locale("en_US"), Some(Language::English));
+        assert_eq!(Language::from_locale("C"), Some(Language::English));
+        assert_eq!(Language::from_locale("de_DE.UTF-8"), None);
+    }
+
+    #[test]
+    fn translations_have_same_placeholders() {
+        let placeholders = |text: &str| {
+            (0..10)
+                .filter(|i| text.contains(&format!("{{{}}}", i)))
+                .collect::<Vec<_>>()
+        };
+
+        for &text in Text::ALL {
+            let english = text.in_language(Language::English);
+            let japanese = text.in_language(Language::Japanese);
+
+            assert!(!japanese.is_empty(), "{:?} is not translated", text);
+
+            assert_eq!(
+                placeholders(english),
+                placeholders(japanese),
+                "{:?} has different placeholders",
+                text
+            );
+        }
+    }
+}
@@ -1,35 +1,91 @@
+mod airing;
+pub mod charset;
 mod component;
+mod franchise;
+mod health;
+pub mod locale;
+mod session;
 mod state;
+mod suggest;
+pub mod theme;
 
-use self::state::{InputState, Reactive, UIEvents, UIState};
+pub use component::prompt::command;
+
+use self::state::{InputState, Reactive, SeriesFilter, UIEvents, UIState};
+use crate::config::ChordAction;
+use crate::ipc::{self, Endpoint, Request};
 use crate::key::Key;
+use crate::private_session::PrivateSession;
+use crate::series::alias::SeriesAlias;
+use crate::series::collection::SeriesCollection;
+use crate::series::filler::FillerEpisodes;
+use crate::series::history::{WatchEvent, WatchTotals};
+use crate::series::sync_failure::SyncFailure;
+use crate::series::LoadedSeries;
+use crate::util::ScopedTask;
 use crate::Args;
-use crate::{file::SerializedFile, remote::RemoteLogin, try_opt_r, user::Users};
-use anime::remote::ScoreParser;
+use crate::{
+    file::SerializedFile,
+    remote::{ensure_scores_supported, RemoteLogin},
+    try_opt_r,
+};
+use anime::remote::{RemoteService, ScoreParser, Status};
 use anyhow::{anyhow, Context, Result};
-use component::prompt::command::Command;
+use chrono::Utc;
+use component::chord_hints::ChordHints;
 use component::prompt::command::InputResult;
+use component::prompt::command::{
+    AliasAction, CollectionAction, Command, FillerAction, FixEpisodeAction,
+};
+use component::prompt::log::LogKind;
 use component::prompt::COMMAND_KEY;
 use component::series_list::SeriesList;
 use component::Component;
 use component::{main_panel::MainPanel, prompt::command::CommandPrompt};
 use crossterm::{event::KeyCode, terminal};
-use state::{SharedState, UIErrorKind, UIEvent};
+use locale::Text;
+use session::Session;
+use state::{SeriesSort, SharedState, UIErrorKind, UIEvent};
 use std::{
-    io,
+    io, mem,
     ops::{Deref, DerefMut},
     sync::Arc,
+    time::{Duration, Instant},
 };
-use tokio::sync::Notify;
+use suggest::Candidate;
+use tokio::{sync::Notify, time};
 use tui::{backend::CrosstermBackend, layout::Direction, Terminal};
 use tui_utils::layout::{BasicConstraint, SimpleLayout};
+use tui_utils::widgets::{OverflowMode, SimpleText};
 
 pub async fn run(args: &Args) -> Result<()> {
     let mut ui = UI::init(&args).context("failed to init UI")?;
     let result = ui.run().await;
 
+    // The demo has its own series, so it shouldn't change where the real ones were left
+    let saved_session = if args.demo {
+        Ok(())
+    } else {
+        ui.save_session().context("saving session")
+    };
+
+    let detached = {
+        let mut state = ui.state.lock();
+        let state = state.get_mut();
+
+        // Changes that are still waiting to be sent shouldn't have to wait until the next sync
+        state.flush_remote_writes(true);
+
+        // The demo's series can't be played, so there's nothing to hand over
+        if args.demo {
+            Ok(())
+        } else {
+            state.detach(args).context("handing over to the daemon")
+        }
+    };
+
     ui.exit()?;
-    result
+    result.and(saved_session).and(detached)
 }
 
 struct UI {
@@ -38,28 +94,107 @@ struct UI {
     state: SharedState,
     dirty_state_notify: Arc<Notify>,
     panels: Panels,
+    #[allow(dead_code)]
+    episode_watcher: ScopedTask<()>,
+    #[allow(dead_code)]
+    remote_write_flusher: ScopedTask<()>,
+    #[allow(dead_code)]
+    activity_ticker: ScopedTask<()>,
+    #[allow(dead_code)]
+    sleep_watcher: ScopedTask<()>,
+    #[allow(dead_code)]
+    health_checker: ScopedTask<()>,
+    last_draw: Instant,
+    /// Set when something changed since the last draw.
+    pending_draw: bool,
 }
 
+/// The shortest time allowed between draws, which caps drawing at about 30 frames per second.
+const MIN_DRAW_INTERVAL: Duration = Duration::from_millis(33);
+
+/// The smallest terminal size that every panel can be laid out in.
+const MIN_TERMINAL_SIZE: (u16, u16) = (40, 12);
+
 impl UI {
     fn init(args: &Args) -> Result<UI> {
-        let events = UIEvents::new().context("UI events init")?;
+        // The state needs to be initialized first, as it may need to prompt for the accounts passphrase
+        let mut state = UIState::init(args).context("UI state init")?;
 
-        let mut state = UIState::init().context("UI state init")?;
+        // Requests are only listened for by the first TUI that's opened, and the demo doesn't have the real series to play
+        let ipc_server = if args.demo {
+            None
+        } else {
+            ipc::Server::bind(Endpoint::Tui).unwrap_or_else(|err| {
+                state.push_error(&err.context("failed to listen for requests"));
+                None
+            })
+        };
+
+        let events = UIEvents::new(ipc_server).context("UI events init")?;
+
+        // A missing or unreadable session just means the TUI starts from the top of the list again
+        let session = if args.demo {
+            Session::default()
+        } else {
+            Session::load().unwrap_or_default()
+        };
 
         state
-            .select_initial_series(args)
+            .select_initial_series(args, &session)
             .context("selecting initial series")?;
 
+        state
+            .recover_interrupted_playback()
+            .context("recovering interrupted playback")?;
+
+        let last_user = if args.offline || args.demo {
+            None
+        } else {
+            state
+                .users
+                .last_used(&args.profile)
+                .map(|(user, account)| (user.clone(), account.token.clone()))
+        };
+
+        if let Some((user, _)) = &last_user {
+            state.users.mark_used(user);
+            state.users.save().context("saving users")?;
+        }
+
         let dirty_state_notify = Arc::new(Notify::const_new());
         let shared_state = SharedState::new(Reactive::new(state, Arc::clone(&dirty_state_notify)));
 
-        let panels = Panels::init(&shared_state);
+        let mut panels = Panels::init(&shared_state);
+
+        {
+            let mut state = shared_state.lock();
+            let state = state.get_mut();
 
-        if !args.offline {
-            if let Some((user, token)) = Users::load_or_create()?.take_last_used_user() {
-                shared_state.login_to_remote_async(RemoteLogin::AniList(user.username, token));
+            // Anything that's waiting to be confirmed needs to stay in focus
+            if state.input_state == InputState::Idle {
+                panels
+                    .main_panel
+                    .restore_session_panel(session.panel, state);
             }
         }
+        let episode_watcher = shared_state.spawn_episode_watcher().into();
+        let remote_write_flusher = shared_state.spawn_remote_write_flusher().into();
+        let activity_ticker = shared_state.spawn_activity_ticker().into();
+        let sleep_watcher = shared_state.spawn_sleep_watcher().into();
+        let health_checker = shared_state.spawn_health_checker().into();
+
+        if let Some((user, token)) = last_user {
+            shared_state.login_to_remote_async(RemoteLogin::AniList(user.username, token));
+        }
+
+        let check_updates = {
+            let config = &shared_state.lock().config;
+            config.updates.check_on_startup && !config.low_data && !args.offline && !args.demo
+        };
+
+        if check_updates {
+            shared_state.spawn_update_check();
+        }
 
         let terminal = CrosstermTerminal::safe_init().context("initializing terminal")?;
 
@@ -69,17 +204,18 @@ impl UI {
             state: shared_state,
             dirty_state_notify,
             panels,
+            episode_watcher,
+            remote_write_flusher,
+            activity_ticker,
+            sleep_watcher,
+            health_checker,
+            last_draw: Instant::now(),
+            pending_draw: false,
         })
     }
 
     async fn run(&mut self) -> Result<()> {
-        {
-            let mut state = self.state.lock();
-
-            if let Err(err) = self.panels.draw(state.get_mut(), &mut self.terminal) {
-                return Err(err);
-            }
-        }
+        self.draw()?;
 
         loop {
             match self.next_cycle().await {
@@ -90,29 +226,108 @@ impl UI {
         }
     }
 
+    /// Draw every panel, regardless of when they were last drawn.
+    fn draw(&mut self) -> Result<()> {
+        let state = self.state.lock();
+
+        self.panels.draw(state.get(), &mut self.terminal)?;
+        self.last_draw = Instant::now();
+        self.pending_draw = false;
+
+        Ok(())
+    }
+
     async fn next_cycle(&mut self) -> CycleResult {
-        let event = match self.events.next(&self.dirty_state_notify).await {
+        let next_event = self.events.next(&self.dirty_state_notify);
+
+        // Changes made too soon after the last draw are drawn together once enough time has passed
+        let event = if self.pending_draw {
+            match time::timeout_at((self.last_draw + MIN_DRAW_INTERVAL).into(), next_event).await {
+                Ok(event) => event,
+                Err(_) => {
+                    return match self.draw() {
+                        Ok(()) => CycleResult::Ok,
+                        Err(err) => CycleResult::Error(err),
+                    }
+                }
+            }
+        } else {
+            next_event.await
+        };
+
+        let event = match event {
             Ok(Some(event)) => event,
             Ok(None) => return CycleResult::Ok,
             Err(UIErrorKind::ExitRequest) => return CycleResult::Exit,
             Err(UIErrorKind::Other(err)) => return CycleResult::Error(err),
         };
 
-        let mut state = self.state.lock();
-        let state = state.get_mut();
+        let resized = matches!(event, UIEvent::Resize);
+
+        // Whatever was drawn before the resize can be left behind where the new layout doesn't draw over it
+        if resized {
+            if let Err(err) = self.terminal.clear() {
+                return CycleResult::Error(err.into());
+            }
+        }
+
+        let mut state_guard = self.state.lock();
+        let state = state_guard.get_mut();
 
         let result = match event {
-            UIEvent::Key(key) => self.panels.process_key(key, state).await,
+            UIEvent::Key(key) => self.panels.process_key(key, state),
             UIEvent::StateChange | UIEvent::Resize => CycleResult::Ok,
+            UIEvent::Request(request, conn) => {
+                let result = match request {
+                    Request::PlayNext => state.play_last_watched_episode(&self.state),
+                    Request::CompleteEpisode(id, episode) => {
+                        state.complete_adopted_episode(id, episode)
+                    }
+                    Request::ReloadSeries(id) => state.reload_series(id),
+                    Request::AdoptPlayback(_) | Request::Sync => {
+                        Err(anyhow!("only the daemon can handle {} requests", request))
+                    }
+                };
+
+                if let Err(err) = &result {
+                    state.push_error(err);
+                }
+
+                conn.respond(&result).await.ok();
+                CycleResult::Ok
+            }
         };
 
-        if let Err(err) = self.panels.draw(state, &mut self.terminal) {
-            return CycleResult::Error(err);
+        if let Err(err) = self.panels.main_panel.poll_lookup(state) {
+            state.push_error(&err);
+        }
+
+        // A series can be completed while an episode is playing, so we need to wait until nothing else is happening to show it
+        if state.input_state == InputState::Idle && mem::take(&mut state.series_completed) {
+            if let Err(err) = self.panels.main_panel.switch_to_completed_series(state) {
+                state.push_error(&err);
+            }
+        }
+
+        drop(state_guard);
+        self.pending_draw = true;
+
+        // The cleared terminal would stay blank until the next draw otherwise
+        if resized || self.last_draw.elapsed() >= MIN_DRAW_INTERVAL {
+            if let Err(err) = self.draw() {
+                return CycleResult::Error(err);
+            }
         }
 
         result
     }
 
+    fn save_session(&self) -> Result<()> {
+        let state = self.state.lock();
+        let session = state.get().session(self.panels.main_panel.session_panel());
+        session.save()
+    }
+
     pub fn exit(mut self) -> Result<()> {
         self.terminal.clear().ok();
         terminal::disable_raw_mode().map_err(Into::into)
@@ -140,13 +355,14 @@ impl Panels {
         }
     }
 
-    async fn process_key(&mut self, key: Key, state: &mut UIState) -> CycleResult {
+    #[allow(clippy::too_many_lines)]
+    fn process_key(&mut self, key: Key, state: &mut UIState) -> CycleResult {
         macro_rules! capture {
             ($result:expr) => {
                 match $result {
                     Ok(value) => value,
                     Err(err) => {
-                        state.log.push_error(&err);
+                        state.push_error(&err);
                         return CycleResult::Ok;
                     }
                 }
@@ -161,9 +377,28 @@ impl Panels {
 
         match state.input_state {
             InputState::Idle => match *key {
-                KeyCode::Char('q') => return CycleResult::Exit,
+                _ if key == state.config.tui.keys.presentation_lock => {
+                    state.toggle_presentation_lock();
+                }
+                KeyCode::Char('q') => {
+                    if state.request_exit() {
+                        return CycleResult::Exit;
+                    }
+                }
+                // Only moving through the series list is allowed, since everything else could change the list or
+                // show a score
+                _ if state.presentation_lock => SeriesList::process_key(key, state),
                 _ if key == state.config.tui.keys.play_next_episode => {
-                    capture!(state.play_next_series_episode(&self.state).await)
+                    capture!(state.play_next_series_episode(&self.state, &[]));
+                }
+                _ if key == state.config.tui.keys.force_complete_episode => {
+                    capture!(state.force_complete_episode());
+                }
+                _ if key == state.config.tui.keys.stop_player => {
+                    capture!(state.request_stop_episode());
+                }
+                _ if key == state.config.tui.keys.quick_switch => {
+                    capture!(self.main_panel.switch_to_quick_switch(state));
                 }
                 KeyCode::Char('a') => {
                     capture!(self.main_panel.switch_to_add_series(state))
@@ -175,13 +410,79 @@ impl Panels {
                     capture!(self.main_panel.switch_to_delete_series(state))
                 }
                 KeyCode::Char('u') => self.main_panel.switch_to_user_panel(state),
+                KeyCode::Char('U') => capture!(state.switch_to_next_user(&self.state)),
                 KeyCode::Char('s') => {
                     capture!(self.main_panel.switch_to_split_series(state))
                 }
+                KeyCode::Char('t') => capture!(self.main_panel.switch_to_stats(state)),
+                KeyCode::Char('h') => capture!(self.main_panel.switch_to_history(state)),
+                KeyCode::Char('b') => self.main_panel.switch_to_tasks(state),
+                KeyCode::Char('n') => capture!(self.main_panel.switch_to_notifications(state)),
+                KeyCode::Char('L') => capture!(self.main_panel.switch_to_events(state)),
+                KeyCode::Char('S') => capture!(self.main_panel.switch_to_audit_log(state)),
+                KeyCode::Char('p') => capture!(self.main_panel.switch_to_profile(state)),
+                KeyCode::Char('R') => capture!(state.toggle_rewatch_filter()),
+                KeyCode::Char('A') => capture!(self.state.toggle_airing_filter(state)),
+                KeyCode::Char('C') => capture!(state.cycle_collection()),
+                KeyCode::Char('G') => state.toggle_franchise_grouping(),
+                KeyCode::Char('V') => capture!(self.main_panel.switch_to_changelog(state)),
+                KeyCode::Char('x') => {
+                    capture!(self
+                        .main_panel
+                        .switch_to_change_status(Status::Dropped, state));
+                }
+                KeyCode::Char('o') => {
+                    capture!(self
+                        .main_panel
+                        .switch_to_change_status(Status::OnHold, state));
+                }
                 KeyCode::Char(COMMAND_KEY) => state.input_state = InputState::EnteringCommand,
+                _ if state
+                    .config
+                    .tui
+                    .chords
+                    .starting_with(&[key])
+                    .next()
+                    .is_some() =>
+                {
+                    if let Some(action) = state.push_chord_key(key) {
+                        capture!(self.run_chord_action(action, state));
+                    }
+                }
                 _ => SeriesList::process_key(key, state),
             },
-            InputState::Locked => (),
+            InputState::EnteringChord => {
+                if let Some(action) = state.push_chord_key(key) {
+                    capture!(self.run_chord_action(action, state));
+                }
+            }
+            InputState::ConfirmingEpisode(id) => {
+                let count = matches!(*key, KeyCode::Char('y' | 'Y'));
+                capture!(state.confirm_episode(id, count));
+            }
+            InputState::ConfirmingStop(id) => {
+                let stop = matches!(*key, KeyCode::Char('y' | 'Y'));
+                state.confirm_stop_episode(id, stop);
+            }
+            InputState::ConfirmingMoved(id) => {
+                let use_path = matches!(*key, KeyCode::Char('y' | 'Y'));
+                capture!(state.confirm_moved(id, use_path));
+            }
+            InputState::ConfirmingOrphans => {
+                let remove = matches!(*key, KeyCode::Char('y' | 'Y'));
+                capture!(state.confirm_orphans(remove));
+            }
+            InputState::ConfirmingConflict => {
+                let merge = matches!(*key, KeyCode::Char('y' | 'Y'));
+                capture!(state.confirm_conflict(merge));
+            }
+            InputState::ConfirmingExit => {
+                if matches!(*key, KeyCode::Char('y' | 'Y')) {
+                    return CycleResult::Exit;
+                }
+
+                state.input_state.reset();
+            }
             InputState::FocusedOnMainPanel => process_key!(main_panel),
             InputState::EnteringCommand => {
                 let result = self.command_prompt.process_key(key, state);
@@ -192,9 +493,7 @@ impl Panels {
                 }
 
                 match capture!(result) {
-                    InputResult::Command(cmd) => {
-                        capture!(Self::process_command(cmd, state))
-                    }
+                    InputResult::Command(cmd) => capture!(self.process_command(cmd, state)),
                     InputResult::Done | InputResult::Continue => (),
                 }
             }
@@ -203,8 +502,49 @@ impl Panels {
         CycleResult::Ok
     }
 
+    fn run_chord_action(&mut self, action: ChordAction, state: &mut UIState) -> Result<()> {
+        match action {
+            ChordAction::Stats => self.main_panel.switch_to_stats(state),
+            ChordAction::History => self.main_panel.switch_to_history(state),
+            ChordAction::Tasks => {
+                self.main_panel.switch_to_tasks(state);
+                Ok(())
+            }
+            ChordAction::Notifications => self.main_panel.switch_to_notifications(state),
+            ChordAction::Events => self.main_panel.switch_to_events(state),
+            ChordAction::AuditLog => self.main_panel.switch_to_audit_log(state),
+            ChordAction::Profile => self.main_panel.switch_to_profile(state),
+            ChordAction::Users => {
+                self.main_panel.switch_to_user_panel(state);
+                Ok(())
+            }
+            ChordAction::AddSeries => self.main_panel.switch_to_add_series(state),
+            ChordAction::EditSeries => self.main_panel.switch_to_update_series(state),
+            ChordAction::DeleteSeries => self.main_panel.switch_to_delete_series(state),
+            ChordAction::SplitSeries => self.main_panel.switch_to_split_series(state),
+            ChordAction::RewatchFilter => state.toggle_rewatch_filter(),
+            ChordAction::AiringFilter => self.state.toggle_airing_filter(state),
+            ChordAction::NextCollection => state.cycle_collection(),
+            ChordAction::Command(command) => {
+                let command = Command::from_str(&command, &state.config)?;
+                self.process_command(command, state)
+            }
+        }
+    }
+
     fn draw(&mut self, state: &UIState, terminal: &mut CrosstermTerminal) -> Result<()> {
         terminal.draw(|mut frame| {
+            let (min_width, min_height) = MIN_TERMINAL_SIZE;
+            let size = frame.size();
+
+            // Panels can't be laid out correctly in less space, so only a warning is drawn until the terminal is made bigger
+            if size.width < min_width || size.height < min_height {
+                let text = Text::TerminalTooSmall.format(&[&min_width, &min_height]);
+                let widget = SimpleText::new(text).overflow(OverflowMode::Truncate);
+                frame.render_widget(widget, size);
+                return;
+            }
+
             let horiz_splitter = SimpleLayout::new(Direction::Horizontal).split(
                 frame.size(),
                 [
@@ -231,14 +571,22 @@ impl Panels {
                 InputState::EnteringCommand => {
                     self.command_prompt.draw(info_panel_splitter[1], frame)
                 }
+                InputState::EnteringChord => {
+                    ChordHints::draw(state, info_panel_splitter[1], frame);
+                }
                 _ => state.log.draw(info_panel_splitter[1], frame),
             }
+
+            if !charset::unicode() {
+                frame.render_widget(charset::AsciiFallback, frame.size());
+            }
         })?;
 
         Ok(())
     }
 
-    fn process_command(command: Command, state: &mut UIState) -> Result<()> {
+    #[allow(clippy::too_many_lines)]
+    fn process_command(&self, command: Command, state: &mut UIState) -> Result<()> {
         let remote = &mut state.remote;
         let config = &state.config;
         let db = &state.db;
@@ -254,21 +602,29 @@ impl Panels {
             Command::Progress(direction) => {
                 use component::prompt::command::ProgressDirection;
 
+                if let ProgressDirection::Forwards = direction {
+                    return state.complete_selected_episode();
+                }
+
                 let series = try_opt_r!(state.series.get_valid_sel_series_mut());
-                let remote = remote.get_logged_in()?;
+                series.episode_regressed(config, db)?;
 
-                match direction {
-                    ProgressDirection::Forwards => series.episode_completed(remote, config, db),
-                    ProgressDirection::Backwards => series.episode_regressed(remote, config, db),
-                }
+                state.remote_writes.push(series.data.info.id);
+                Ok(())
             }
             cmd @ Command::SyncFromRemote | cmd @ Command::SyncToRemote => {
                 let series = try_opt_r!(state.series.get_valid_sel_series_mut());
                 let remote = remote.get_logged_in()?;
 
                 match cmd {
-                    Command::SyncFromRemote => series.data.force_sync_from_remote(remote)?,
-                    Command::SyncToRemote => series.data.entry.force_sync_to_remote(remote)?,
+                    Command::SyncFromRemote => {
+                        series.data.force_sync_from_remote(remote)?;
+                        series.data.refresh_episode_titles(db, remote)?;
+                    }
+                    Command::SyncToRemote => {
+                        series.data.force_sync_to_remote(remote, db)?;
+                        SyncFailure::clear(db, series.data.info.id)?;
+                    }
                     _ => unreachable!(),
                 }
 
@@ -278,15 +634,18 @@ impl Panels {
             Command::Score(raw_score) => {
                 let series = try_opt_r!(state.series.get_valid_sel_series_mut());
                 let remote = remote.get_logged_in()?;
+                ensure_scores_supported(remote)?;
 
-                let score = match remote.parse_score(&raw_score) {
+                let current = series.data.entry.score().map(|score| score as u8);
+
+                let score = match remote.parse_relative_score(&raw_score, current) {
                     Some(score) if score == 0 => None,
                     Some(score) => Some(score),
                     None => return Err(anyhow!("invalid score")),
                 };
 
                 series.data.entry.set_score(score.map(i16::from));
-                series.data.entry.sync_to_remote(remote)?;
+                series.data.sync_to_remote(remote, db)?;
                 series.save(db)?;
 
                 Ok(())
@@ -296,11 +655,439 @@ impl Panels {
                 let remote = remote.get_logged_in()?;
 
                 series.data.entry.set_status(status, config);
-                series.data.entry.sync_to_remote(remote)?;
+                series.data.sync_to_remote(remote, db)?;
+                series.save(db)?;
+
+                Ok(())
+            }
+            Command::Offset(offset) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+
+                series.set_episode_offset(offset, config)?;
+                series.save(db)?;
+
+                Ok(())
+            }
+            Command::RemoteSync(enabled) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+
+                series.data.config.local_only = !enabled;
+                series.save(db)?;
+
+                // Push any changes that were made while the series wasn't synced
+                if enabled {
+                    let remote = remote.get_logged_in()?;
+                    series.data.sync_to_remote(remote, db)?;
+                    series.save(db)?;
+                }
+
+                Ok(())
+            }
+            Command::Title(title) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+
+                series.data.set_title_override(title)?;
+                series.save(db)?;
+
+                Ok(())
+            }
+            Command::Undelete(name) => state.undelete_series(&name),
+            Command::RemoveWatched(enabled) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+
+                series.data.config.remove_watched = enabled;
+                series.save(db)?;
+
+                if enabled {
+                    series.remove_watched_episodes(config, db)?;
+                }
+
+                Ok(())
+            }
+            Command::Mute(muted) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+
+                series.data.config.muted = muted;
+                series.save(db)?;
+
+                Ok(())
+            }
+            Command::ResetSync => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+                SyncFailure::clear(db, series.data.info.id)?;
+
+                let remote = remote.get_logged_in()?;
+                series.data.sync_to_remote(remote, db)?;
+                series.save(db)?;
+
+                Ok(())
+            }
+            Command::Filler(action) => {
+                let data = try_opt_r!(state.series.selected_mut().and_then(LoadedSeries::data_mut));
+                let id = data.config.id;
+
+                let text = match action {
+                    FillerAction::Set(filler) => {
+                        filler.save(db, id)?;
+                        data.filler = filler;
+                        Text::FillerSet.format(&[&data.config.nickname, &data.filler])
+                    }
+                    FillerAction::Clear => {
+                        FillerEpisodes::delete_for_series(db, id)?;
+                        data.filler = FillerEpisodes::default();
+                        Text::FillerCleared.format(&[&data.config.nickname])
+                    }
+                    FillerAction::List if data.filler.is_empty() => {
+                        Text::NoFiller.format(&[&data.config.nickname])
+                    }
+                    FillerAction::List => {
+                        Text::FillerEpisodes.format(&[&data.config.nickname, &data.filler])
+                    }
+                };
+
+                state.log.push(LogKind::Info, text);
+                Ok(())
+            }
+            Command::FixEpisode(action) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+                let nickname = series.data.config.nickname.clone();
+
+                let text = match action {
+                    FixEpisodeAction::Set(episode, name) => {
+                        let filename = series.override_episode(episode, &name, db, config)?;
+                        Text::EpisodeOverrideSet.format(&[&filename, &episode, &nickname])
+                    }
+                    FixEpisodeAction::Clear => {
+                        series.clear_episode_overrides(db, config)?;
+                        Text::EpisodeOverridesCleared.format(&[&nickname])
+                    }
+                    FixEpisodeAction::List if series.data.episode_overrides.is_empty() => {
+                        Text::NoEpisodeOverrides.format(&[&nickname])
+                    }
+                    FixEpisodeAction::List => {
+                        Text::EpisodeOverrides.format(&[&nickname, &series.data.episode_overrides])
+                    }
+                };
+
+                state.log.push(LogKind::Info, text);
+                Ok(())
+            }
+            Command::Relink(id) => {
+                let data = try_opt_r!(state.series.selected_mut().and_then(LoadedSeries::data_mut));
+                let remote = remote.get_logged_in()?;
+
+                data.relink(id, db, remote)?;
+                data.sync_to_remote(remote, db)?;
+                data.save(db)?;
+
+                Ok(())
+            }
+            Command::RewatchPlan(planned) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+
+                if planned && series.data.entry.status() != Status::Completed {
+                    return Err(anyhow!(
+                        "only completed series can be planned to be rewatched"
+                    ));
+                }
+
+                series.data.config.planned_rewatch = planned;
+                series.save(db)?;
+
+                state.ensure_visible_selection();
+                Ok(())
+            }
+            Command::RateEpisode(rating, episode) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+                let episode = episode.unwrap_or_else(|| series.data.entry.watched_episodes());
+
+                if episode < 1 {
+                    return Err(anyhow!("no episodes have been watched"));
+                }
+
+                let id = series.data.info.id;
+
+                if WatchEvent::rate(db, id, episode, rating)? == 0 {
+                    return Err(anyhow!(
+                        "episode {} hasn't been watched to completion",
+                        episode
+                    ));
+                }
+
+                series.data.history = WatchTotals::load(db, id)?;
+                Ok(())
+            }
+            Command::ResetDates => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+                let remote = remote.get_logged_in()?;
+
+                series.data.entry.reset_dates();
+                series.data.sync_to_remote(remote, db)?;
+                series.save(db)?;
+
+                Ok(())
+            }
+            Command::Alias(action) => {
+                let series = try_opt_r!(state.series.selected());
+                let id = series.config().id;
+                let nickname = series.nickname();
+
+                let text = match action {
+                    AliasAction::Add(alias) => {
+                        SeriesAlias::add(db, id, &alias)?;
+                        Text::AliasAdded.format(&[&alias, &nickname])
+                    }
+                    AliasAction::Remove(alias) => {
+                        if SeriesAlias::remove(db, id, &alias)? == 0 {
+                            return Err(anyhow!("{} isn't an alias of {}", alias, nickname));
+                        }
+
+                        Text::AliasRemoved.format(&[&alias, &nickname])
+                    }
+                    AliasAction::List => {
+                        let aliases = SeriesAlias::for_series(db, id)?;
+
+                        if aliases.is_empty() {
+                            Text::NoAliases.format(&[&nickname])
+                        } else {
+                            Text::Aliases.format(&[&nickname, &aliases.join(", ")])
+                        }
+                    }
+                };
+
+                state.log.push(LogKind::Info, text);
+                Ok(())
+            }
+            Command::Collection(action) => {
+                let series = try_opt_r!(state.series.selected());
+                let id = series.config().id;
+                let nickname = series.nickname();
+
+                let text = match action {
+                    CollectionAction::Add(name) => {
+                        if !SeriesCollection::add(db, id, &name)? {
+                            return Err(anyhow!("{} is already in {}", nickname, name));
+                        }
+
+                        Text::CollectionAdded.format(&[&nickname, &name])
+                    }
+                    CollectionAction::Remove(name) => {
+                        if SeriesCollection::remove(db, id, &name)? == 0 {
+                            return Err(anyhow!("{} isn't in {}", nickname, name));
+                        }
+
+                        Text::CollectionRemoved.format(&[&nickname, &name])
+                    }
+                    CollectionAction::List => {
+                        let names = SeriesCollection::for_series(db, id)?;
+
+                        if names.is_empty() {
+                            Text::NoCollections.format(&[&nickname])
+                        } else {
+                            Text::Collections.format(&[&nickname, &names.join(", ")])
+                        }
+                    }
+                };
+
+                // The shown collection has to be reloaded for the series list to reflect the change
+                if state.series_filter == SeriesFilter::Collection {
+                    let name = state.collection.name.clone();
+                    state.show_collection(name)?;
+                }
+
+                state.log.push(LogKind::Info, text);
+                Ok(())
+            }
+            Command::RemoveEntry => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+                let remote = remote.get_logged_in()?;
+
+                if remote.is_offline() {
+                    return Err(anyhow!("must be online to remove a list entry"));
+                }
+
+                let removed = series.data.remove_from_remote(remote, db)?;
+                series.save(db)?;
+
+                let text = if removed {
+                    Text::EntryRemoved
+                } else {
+                    Text::EntryNotListed
+                };
+
+                let text = text.format(&[series.data.title()]);
+                state.log.push(LogKind::Info, text);
+                Ok(())
+            }
+            Command::Suggest => {
+                let now = Utc::now().timestamp();
+                let mut candidates = Vec::new();
+
+                for series in state.series.iter() {
+                    let data = match series.data() {
+                        Some(data) => data,
+                        None => continue,
+                    };
+
+                    let status = data.entry.status();
+
+                    if !matches!(
+                        status,
+                        Status::Watching | Status::Rewatching | Status::PlanToWatch
+                    ) {
+                        continue;
+                    }
+
+                    let id = data.info.id;
+                    let watched = data.entry.watched_episodes().max(0) as u32;
+                    let total = data.info.episodes.max(0) as u32;
+                    let episode_length = data.info.episode_length_mins.max(0) as u32;
+
+                    let next_on_disk = series
+                        .complete()
+                        .map_or(false, |series| series.episodes.find(watched + 1).is_some());
+
+                    let idle_days = WatchEvent::latest_watched_at(db, id)?
+                        .map(|watched_at| (now - watched_at) as f32 / (60.0 * 60.0 * 24.0));
+
+                    candidates.push(Candidate {
+                        id,
+                        next_on_disk,
+                        mins_left: (total > 0)
+                            .then(|| total.saturating_sub(watched) * episode_length),
+                        eps_until_finale: state.airing.episodes_until_finale(id, total),
+                        idle_days,
+                    });
+                }
+
+                let (id, reason) = suggest::best(&candidates).ok_or_else(|| {
+                    anyhow!("no series are being watched or planned to be watched")
+                })?;
+
+                state.select_series_by_id(id);
+
+                let nickname = try_opt_r!(state.series.selected()).nickname().to_string();
+                let text = Text::Suggested.format(&[&nickname, &reason.text().get()]);
+
+                state.log.push(LogKind::Info, text);
+                Ok(())
+            }
+            Command::PullProgress => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+                let remote = remote.get_logged_in()?;
+
+                if remote.is_offline() {
+                    return Err(anyhow!("must be online to pull progress"));
+                }
+
+                if !series.data.entry.pull_progress(remote)? {
+                    return Err(anyhow!("{} isn't on your list", series.data.title()));
+                }
+
                 series.save(db)?;
 
+                let entry = &series.data.entry;
+                let text = Text::ProgressPulled.format(&[
+                    series.data.title(),
+                    &entry.watched_episodes(),
+                    &Text::from(entry.status()).get(),
+                ]);
+
+                state.log.push(LogKind::Info, text);
+                Ok(())
+            }
+            Command::Priority(priority) => {
+                let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+                let remote = remote.get_logged_in()?;
+
+                series.data.entry.set_priority(priority);
+                series.data.sync_to_remote(remote, db)?;
+                series.save(db)?;
+
+                // The selected series would otherwise stay where it was until the list is sorted again
+                if state.series_sort == SeriesSort::Priority {
+                    state.sort_series();
+                }
+
+                Ok(())
+            }
+            Command::Sort(sort) => {
+                state.set_series_sort(sort);
+                Ok(())
+            }
+            Command::Play(episode, args) => {
+                let next_episode = {
+                    let series = try_opt_r!(state.series.get_valid_sel_series_mut());
+                    series.data.entry.watched_episodes() as u32 + 1
+                };
+
+                // Only the next episode is tracked, since watching any other one shouldn't change the progress of the series
+                match episode {
+                    Some(episode) if episode != next_episode => {
+                        state.play_untracked_episode(episode, &args)
+                    }
+                    Some(_) | None => state.play_next_series_episode(&self.state, &args),
+                }
+            }
+            Command::Share(with_url) => {
+                let text = {
+                    let series = try_opt_r!(state.series.selected());
+
+                    let data = series
+                        .data()
+                        .ok_or_else(|| anyhow!("{} isn't loaded", series.nickname()))?;
+
+                    // The episode being played is the one being watched, even if it was played again
+                    let episode = state
+                        .watching
+                        .get(&data.info.id)
+                        .map_or_else(|| data.shared_episode(), |watch| watch.episode);
+
+                    data.share_text(episode, with_url)
+                };
+
+                match state.copy_to_clipboard(text.clone()) {
+                    Ok(()) => state
+                        .log
+                        .push(LogKind::Info, Text::ShareCopied.format(&[&text])),
+                    // The message can still be copied from the log when there's no clipboard, such as over SSH
+                    Err(err) => {
+                        state.log.push_error(&err);
+                        state.log.push(LogKind::Info, text);
+                    }
+                }
+
+                Ok(())
+            }
+            Command::Private(true) => {
+                PrivateSession::start()?;
+                state.log.set_private_session(true);
+                state
+                    .log
+                    .push(LogKind::Info, Text::PrivateSessionStarted.get());
+                Ok(())
+            }
+            Command::Private(false) => {
+                PrivateSession::end()?;
+                state.log.set_private_session(false);
+                state
+                    .log
+                    .push(LogKind::Info, Text::PrivateSessionEnded.get());
+
+                let has_changes = state
+                    .series
+                    .iter()
+                    .filter_map(LoadedSeries::data)
+                    .any(|data| !data.config.local_only && data.entry.needs_sync());
+
+                if has_changes {
+                    self.state.spawn_sync_all(state)?;
+                }
+
                 Ok(())
             }
+            Command::SyncAll => self.state.spawn_sync_all(state),
+            Command::RefreshAll => self.state.spawn_refresh_all_info(state),
         }
     }
 }
@@ -0,0 +1,49 @@
+use crate::file::{FileFormat, SaveDir, SerializedFile};
+use serde_derive::{Deserialize, Serialize};
+
+/// Where the TUI was left when it was last closed, so it can be reopened in the same place.
+#[derive(Default, Deserialize, Serialize)]
+pub struct Session {
+    /// The nickname of the series that was selected.
+    pub selected: Option<String>,
+    pub filter: SessionFilter,
+    pub panel: SessionPanel,
+}
+
+impl SerializedFile for Session {
+    fn filename() -> &'static str {
+        "tui_session"
+    }
+
+    fn save_dir() -> SaveDir {
+        SaveDir::LocalData
+    }
+
+    fn format() -> FileFormat {
+        FileFormat::Binary
+    }
+}
+
+/// The series that were shown in the series list.
+///
+/// Airing series aren't restored, since their schedule has to be looked up again from the remote service.
+#[derive(Default, Deserialize, Serialize)]
+pub enum SessionFilter {
+    #[default]
+    All,
+    PlannedRewatch,
+    /// The series in the collection with the specified name.
+    Collection(String),
+}
+
+/// The panel that was focused next to the series list.
+///
+/// Only panels that show information are restored, as the rest are in the middle of changing something.
+#[derive(Copy, Clone, Default, Deserialize, Serialize)]
+pub enum SessionPanel {
+    #[default]
+    Info,
+    Stats,
+    History,
+    Tasks,
+}
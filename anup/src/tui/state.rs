@@ -1,33 +1,64 @@
-use super::component::prompt::log::Log;
-use crate::user::Users;
-use crate::{config::Config, util::ArcMutex};
-use crate::{database::Database, series::LastWatched};
+use super::airing::AiringSchedule;
+use super::component::prompt::log::{ActivityId, Log, LogKind};
+use super::franchise::Franchises;
+use super::health::Health;
+use super::locale::{self, Language, Text};
+use super::session::{Session, SessionFilter, SessionPanel};
+use super::{charset, theme};
+use crate::config::{ChordAction, Config, ExtraEpisodes};
+use crate::conflict::SyncConflict;
+use crate::database::{Database, SharedStr};
+use crate::integrity::Orphans;
+use crate::ipc::{self, AdoptedPlayback, Endpoint, Request};
+use crate::private_session::PrivateSession;
+use crate::saved_event::{EventKind, SavedEvent};
+use crate::series::collection::SeriesCollection;
+use crate::series::episode_title::EpisodeTitles;
+use crate::update::Release;
+use crate::user::{RemoteType, UserInfo, Users};
+use crate::{backup, err, util::ArcMutex};
+use crate::{daemon, demo, series::LastWatched, watch_party::WatchParty};
 use crate::{file::SerializedFile, key::Key};
 use crate::{remote::RemoteLogin, series::info::SeriesInfo};
 use crate::{
     remote::RemoteStatus,
-    series::{LoadedSeries, Series, SeriesData},
+    series::{
+        entry::SeriesEntry,
+        history::{ActivePlayback, PlaybackSession, PlaybackTimer},
+        sync_failure::SyncFailure,
+        EpisodePathError, EpisodeScanError, LoadedSeries, Series, SeriesData, SeriesPath,
+    },
 };
-use crate::{series::config::SeriesConfig, Args};
-use crate::{try_opt_ret, util::arc_mutex};
-use anime::remote::{anilist::AniList, Remote};
+use crate::{series::alias::SeriesAlias, series::config::SeriesConfig, Args};
+use crate::{series::deleted::DeletedSeries, trash};
+use crate::{try_opt_r, try_opt_ret, util::arc_mutex, util::SleepDetector};
+use anime::remote::{anilist::AniList, AccessToken, Remote, RemoteService, SeriesID, Status};
+use anime::sync::WriteQueue;
 use anime::{local::SortedEpisodes, remote::anilist::Auth};
 use anyhow::{anyhow, Context, Result};
-use chrono::{DateTime, Utc};
+use arboard::Clipboard;
+use chrono::{DateTime, Datelike, Local, Utc};
 use crossterm::event::{Event, EventStream};
-use futures::{select, FutureExt, StreamExt};
+use futures::{future, select, FutureExt, StreamExt};
 use parking_lot::MutexGuard;
+use smallvec::SmallVec;
 use std::{
     borrow::Cow,
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
     mem,
     ops::{Deref, DerefMut},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 use tokio::{
     process::Child,
     signal::unix::{signal, Signal, SignalKind},
     sync::{broadcast, Notify},
-    task,
+    task, time,
 };
 use tui_utils::list::WrappedSelection;
 
@@ -59,21 +90,104 @@ impl DerefMut for WrappedSeriesSelection {
 
 pub struct UIState {
     pub series: WrappedSeriesSelection,
+    /// Which series are shown in the series list.
+    pub series_filter: SeriesFilter,
+    /// The order of the series list, when it isn't grouped by the day series air on.
+    pub series_sort: SeriesSort,
+    /// When the series being watched air, as of the last time the airing filter was turned on.
+    pub airing: AiringSchedule,
+    /// The collection shown by the collection filter, as of the last time it was switched to.
+    pub collection: ShownCollection,
+    /// The franchise each series belongs to, as of the last time the series list was sorted.
+    pub franchises: Franchises,
     pub last_watched: LastWatched,
     pub input_state: InputState,
+    /// The key chord being entered, if the first key of one has been pressed.
+    pub chord: Option<PendingChord>,
+    /// Series whose folder wasn't found when they were loaded, but was likely moved or renamed in `series_dir`.
+    pub moved: Vec<MovedSeries>,
+    /// Rows left behind by series that no longer exist, until the user decides whether to remove them.
+    pub orphans: Option<Orphans>,
+    /// Copies of the database made by a sync program, until the user decides whether to merge each of them.
+    pub sync_conflicts: Vec<SyncConflict>,
+    /// Episodes that weren't counted since their player exited abnormally or the program closed while they played,
+    /// until the user decides whether to count each of them.
+    pub unconfirmed_episodes: Vec<UnconfirmedEpisode>,
+    /// A newer release of the program, if one was found when the TUI was started and it hasn't been dismissed.
+    pub update: Option<Release>,
+    /// Whether scores and statuses are hidden and the list is kept from changing, such as while sharing the screen.
+    pub presentation_lock: bool,
+    /// Series that had an episode finish while the presentation lock was on, which are counted once it's lifted.
+    held_episodes: Vec<i32>,
+    /// The system clipboard, once something has been copied to it.
+    clipboard: Option<Clipboard>,
+    /// Episodes that are being played, keyed by the ID of their series.
+    pub watching: HashMap<i32, ActiveWatch>,
+    /// What was fetched while an episode was being played for when it finishes, keyed by the ID of its series.
+    pub prefetched: HashMap<i32, Prefetched>,
+    /// Set when the selected series was just completed, so its summary can be shown once nothing else is happening.
+    pub series_completed: bool,
+    /// List entries that have been changed, but not sent to the remote service yet.
+    pub remote_writes: WriteQueue<i32>,
+    /// How the remote service answered the last time it was pinged.
+    health: Health,
+    /// When a list entry was last sent to the remote service, as a UNIX timestamp.
+    last_sync: Option<i64>,
     pub events: broadcast::Sender<StateEvent>,
     pub log: Log<'static>,
-    pub config: Config,
+    pub tasks: Tasks,
+    pub config: Arc<Config>,
     pub users: Users,
+    /// Accounts that have their lists updated along with the current one, or None when running the demo.
+    pub watch_party: Option<WatchParty>,
+    /// Users that failed to log in during this session.
+    pub failed_logins: HashSet<UserInfo>,
+    /// The profile new accounts are added to.
+    pub profile: String,
     pub remote: RemoteStatus,
     pub db: Database,
 }
 
 impl UIState {
-    pub fn init() -> Result<Self> {
+    #[allow(clippy::too_many_lines)]
+    pub fn init(args: &Args) -> Result<Self> {
         let config = Config::load_or_create().context("failed to load / create config")?;
+        locale::init(config.tui.language.unwrap_or_else(Language::from_env));
+        theme::init(config.tui.palette);
+        charset::init(config.tui.unicode.unwrap_or_else(charset::detect));
+
         let users = Users::load_or_create().context("failed to load / create users")?;
-        let db = Database::open().context("failed to open database")?;
+
+        // This needs to happen before the database is opened so a backup is made before any migrations are applied
+        let backup_result = if args.demo {
+            Ok(false)
+        } else {
+            backup::create_if_due(&config.backup)
+        };
+
+        let (remote, db) = if args.demo {
+            demo::init(&config, args).context("failed to set up demo")?
+        } else {
+            let mut db = Database::open().context("failed to open database")?;
+
+            if let Some((user, _)) = users.last_used(&args.profile) {
+                db.set_account(user.account_id())
+                    .context("setting database account")?;
+            }
+
+            SeriesEntry::recover_journal(&db)
+                .context("recovering list entries from the sync journal")?;
+
+            (Remote::offline(), db)
+        };
+
+        // The demo's series and accounts aren't real, so they shouldn't be shared with anyone
+        let watch_party = if args.demo {
+            None
+        } else {
+            Some(WatchParty::load_or_create().context("failed to load / create watch party")?)
+        };
+
         let last_watched = LastWatched::load().context("last watched series")?;
 
         let mut series = SeriesConfig::load_all(&db)
@@ -84,23 +198,136 @@ impl UIState {
 
         series.sort_unstable();
 
+        let franchises = Franchises::new(&mut series, config.tui.group_franchises);
+
         let (events_tx, _) = broadcast::channel(8);
+        let mut log = Log::new(config.tui.limits.log_messages);
+
+        for problem in config.problems() {
+            log.push(LogKind::Warning, Text::ConfigProblem.format(&[&problem]));
+        }
+
+        log.set_private_session(PrivateSession::is_active());
+
+        match SavedEvent::unread_count(&db) {
+            Ok(unread) => log.set_unread(unread),
+            Err(err) => {
+                let err = anyhow::Error::from(err).context("failed to count unread events");
+                log.push_error(&err);
+            }
+        }
+
+        let moved = Self::find_moved_series(&series, &config);
+
+        let orphans = match Orphans::find(&db) {
+            Ok(orphans) if !orphans.is_empty() => Some(orphans),
+            Ok(_) => None,
+            Err(err) => {
+                let err = anyhow::Error::from(err).context("failed to check for orphaned rows");
+                log.push_error(&err);
+                None
+            }
+        };
+
+        // The demo's database has nothing to do with the copies of the real one
+        let sync_conflicts = if args.demo {
+            Vec::new()
+        } else {
+            Self::find_sync_conflicts(&db, &mut log)
+        };
+
+        for series in series.iter_mut().filter_map(LoadedSeries::complete_mut) {
+            if !series.data.config.remove_watched {
+                continue;
+            }
+
+            if let Err(err) = series.remove_watched_episodes(&config, &db) {
+                log.push_error(&err.context("failed to remove watched episodes"));
+            }
+        }
+
+        if let Err(err) = backup_result {
+            log.push_error(&err.context("failed to back up data"));
+        }
+
+        if let Err(err) = DeletedSeries::purge_expired(&db, config.trash.retention_days) {
+            log.push_error(&err.context("failed to remove expired deleted series"));
+        }
 
         Ok(Self {
             series: WrappedSeriesSelection::new(series),
+            series_filter: SeriesFilter::All,
+            series_sort: SeriesSort::Name,
+            airing: AiringSchedule::default(),
+            collection: ShownCollection::default(),
+            franchises,
             last_watched,
             input_state: InputState::default(),
+            chord: None,
+            moved,
+            unconfirmed_episodes: Vec::new(),
+            orphans,
+            sync_conflicts,
+            update: None,
+            presentation_lock: false,
+            held_episodes: Vec::new(),
+            clipboard: None,
+            watching: HashMap::new(),
+            prefetched: HashMap::new(),
+            series_completed: false,
+            remote_writes: WriteQueue::new(REMOTE_WRITE_DELAY),
+            health: Health::Healthy,
+            last_sync: None,
             events: events_tx,
-            log: Log::new(15),
-            config,
+            log,
+            tasks: Tasks::new(config.tui.limits.finished_tasks.into()),
+            config: Arc::new(config),
             users,
-            remote: RemoteStatus::LoggedIn(Remote::offline()),
+            watch_party,
+            failed_logins: HashSet::new(),
+            profile: args.profile.clone(),
+            remote: RemoteStatus::LoggedIn(remote.into()),
             db,
         })
     }
 
-    pub fn select_initial_series(&mut self, args: &Args) -> Result<()> {
-        let mut desired_series = args.series.as_ref().map(Cow::Borrowed);
+    /// Find where the folders of series that no longer exist were most likely moved or renamed to.
+    fn find_moved_series(series: &[LoadedSeries], config: &Config) -> Vec<MovedSeries> {
+        let taken = series
+            .iter()
+            .map(|series| series.path().absolute(config).into_owned())
+            .collect::<Vec<_>>();
+
+        series
+            .iter()
+            .filter_map(|series| {
+                if let LoadedSeries::Partial(data, EpisodeScanError::PathNotFound { .. }) = series {
+                    Some(data)
+                } else {
+                    None
+                }
+            })
+            .filter_map(|data| {
+                // Failing to look isn't worth reporting, since the series will still show that its folder is missing
+                let path = SeriesPath::find_moved(&data.info, &taken, config).ok()??;
+
+                Some(MovedSeries {
+                    id: data.config.id,
+                    path,
+                })
+            })
+            .collect()
+    }
+
+    /// Select the series specified in `args`, or the one selected in the last `session`, or the one that was last watched.
+    ///
+    /// The series list is also filtered the same way it was in the last session, unless a series was specified.
+    pub fn select_initial_series(&mut self, args: &Args, session: &Session) -> Result<()> {
+        let mut desired_series = args
+            .series
+            .as_ref()
+            .or(session.selected.as_ref())
+            .map(Cow::Borrowed);
 
         if desired_series.is_none() {
             let last_watched = LastWatched::load().context("loading last watched series")?;
@@ -108,17 +335,103 @@ impl UIState {
         }
 
         let selected = match desired_series {
-            Some(desired) => self
-                .series
-                .iter()
-                .position(|series| series.nickname() == desired.as_ref())
-                .unwrap_or(0),
+            Some(desired) => {
+                let nickname = SeriesAlias::resolve(&self.db, &desired)
+                    .context("resolving series name")?
+                    .unwrap_or_else(|| desired.into_owned());
+
+                self.series
+                    .iter()
+                    .position(|series| series.nickname() == nickname)
+                    .unwrap_or(0)
+            }
             None => 0,
         };
 
         self.series.set_selected(selected);
+        self.reveal_selected_franchise();
         self.init_selected_series();
 
+        // Restoring the filter could hide the series that was asked for
+        if args.series.is_some() {
+            return Ok(());
+        }
+
+        match &session.filter {
+            SessionFilter::All => (),
+            SessionFilter::PlannedRewatch => self.set_series_filter(SeriesFilter::PlannedRewatch),
+            // The collection may have been emptied since then, in which case every series is still shown
+            SessionFilter::Collection(name) => {
+                self.show_collection(name.clone()).ok();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns where the TUI currently is, with `panel` being the focused main panel.
+    pub fn session(&self, panel: SessionPanel) -> Session {
+        let filter = match self.series_filter {
+            SeriesFilter::All | SeriesFilter::Airing => SessionFilter::All,
+            SeriesFilter::PlannedRewatch => SessionFilter::PlannedRewatch,
+            SeriesFilter::Collection => SessionFilter::Collection(self.collection.name.clone()),
+        };
+
+        Session {
+            selected: self
+                .series
+                .selected()
+                .map(|series| series.nickname().to_string()),
+            filter,
+            panel,
+        }
+    }
+
+    /// Record every episode that was still being played when the program last closed, and ask whether to count each one.
+    ///
+    /// Episodes are only offered to be counted if they're the next one for their series and were played for long enough.
+    pub fn recover_interrupted_playback(&mut self) -> Result<()> {
+        let interrupted =
+            ActivePlayback::take_interrupted(&self.db).context("loading interrupted playback")?;
+
+        let now = Utc::now();
+
+        for playback in interrupted {
+            let data = self
+                .series
+                .iter()
+                .filter_map(LoadedSeries::data)
+                .find(|data| data.info.id == playback.series_id);
+
+            let data = match data {
+                Some(data) => data,
+                None => continue,
+            };
+
+            let is_next_episode = playback.episode == data.entry.watched_episodes() + 1;
+            let watched_enough =
+                now >= data.next_watch_progress_time(playback.started_at(), &self.config);
+            let title = data.title().clone();
+
+            let session = playback.into_session(now, data.info.episode_length_mins);
+            session.save(&self.db).context("saving playback session")?;
+
+            if is_next_episode && watched_enough {
+                let message = Text::ConfirmCount.format(&[
+                    &Text::ClosedWhilePlaying.get(),
+                    &session.episode,
+                    &title,
+                ]);
+
+                self.queue_episode_confirmation(session.series_id, message);
+            } else {
+                self.log.push(
+                    LogKind::Info,
+                    Text::PlaybackInterrupted.format(&[&session.episode, &title]),
+                );
+            }
+        }
+
         Ok(())
     }
 
@@ -132,183 +445,2280 @@ impl UIState {
         E: Into<Option<SortedEpisodes>>,
     {
         let remote = self.remote.get_logged_in()?;
-        let data = SeriesData::from_remote(config, info, remote)?;
+        let mut data = SeriesData::from_remote(config, info, remote)?;
+
+        if self.config.episode.import_progress_on_add
+            && data.import_progress_from_disk(&self.config)
+        {
+            data.sync_to_remote(remote, &self.db)?;
+        }
+
+        // The series is still worth adding without its episode titles, since they're fetched again when it's refreshed
+        if !self.config.low_data {
+            if let Err(err) = data.refresh_episode_titles(&self.db, remote) {
+                self.log.push_error(&err);
+            }
+        }
 
         let series = match episodes.into() {
             Some(episodes) => LoadedSeries::Complete(Series::with_episodes(data, episodes)),
             None => Series::init(data, &self.config),
         };
 
-        series.save(&self.db)?;
+        series.save(&self.db)?;
+        self.push_and_select_series(series);
+
+        Ok(())
+    }
+
+    /// Restore the most recently deleted series with the specified `name`.
+    pub fn undelete_series(&mut self, name: &str) -> Result<()> {
+        let deleted = DeletedSeries::load_by_name(&self.db, name)
+            .with_context(|| format!("no deleted series named {}", name))?;
+
+        let config = deleted.restore(&self.db, &self.config)?;
+        let series = Series::load_from_config(config, &self.config, &self.db);
+
+        self.push_and_select_series(series);
+        Ok(())
+    }
+
+    fn push_and_select_series(&mut self, series: LoadedSeries) {
+        let nickname = series.config().nickname.clone();
+
+        self.series.push(series);
+        self.sort_series();
+
+        let selected = self
+            .series
+            .iter()
+            .position(|s| nickname == s.nickname())
+            .unwrap_or(0);
+
+        self.series.set_selected(selected);
+        self.reveal_selected_franchise();
+    }
+
+    /// Log the specified `err`.
+    ///
+    /// If the error was caused by the remote service being unavailable, the remote will be switched to offline mode
+    /// and the selected series will be saved so its changes can be synced later.
+    pub fn push_error(&mut self, err: &anyhow::Error) {
+        if !err::is_service_unavailable(err) {
+            self.log.push_error(err);
+            return;
+        }
+
+        self.remote = RemoteStatus::LoggedIn(Remote::offline().into());
+
+        self.log
+            .push(LogKind::Warning, Text::RemoteUnavailable.get());
+
+        self.save_event(EventKind::Warning, Text::RemoteUnavailable.get());
+
+        if let Some(series) = self.series.selected() {
+            match series.save(&self.db) {
+                Ok(()) => self.log.push(LogKind::Context, Text::SyncedLater.get()),
+                Err(err) => self.log.push_error(&err),
+            }
+        }
+    }
+
+    /// Save an event that should still be readable after it scrolls out of the log, and update the unread count shown
+    /// in the log's title.
+    ///
+    /// The event isn't pushed to the log, so it has to be logged separately.
+    pub fn save_event(&mut self, kind: EventKind, message: &str) {
+        let result = SavedEvent::record(&self.db, kind, message)
+            .and_then(|()| SavedEvent::unread_count(&self.db).map_err(Into::into));
+
+        match result {
+            Ok(unread) => self.log.set_unread(unread),
+            Err(err) => self.log.push_error(&err),
+        }
+    }
+
+    /// Reload the number of unread saved events, which the daemon can add to while the TUI is open.
+    pub fn refresh_unread_events(&mut self) {
+        match SavedEvent::unread_count(&self.db) {
+            Ok(unread) => self.log.set_unread(unread),
+            Err(err) => self.log.push_error(&err.into()),
+        }
+    }
+
+    /// Send the list entries queued in `remote_writes` to the remote service.
+    ///
+    /// Only entries that haven't changed for a moment are sent, unless `all` is true.
+    /// Their progress is also shared with the rest of the watch party.
+    pub fn flush_remote_writes(&mut self, all: bool) {
+        let remote = match &self.remote {
+            RemoteStatus::LoggedIn(remote) => remote,
+            RemoteStatus::LoggingIn(_) => return,
+        };
+
+        let ids = if all {
+            self.remote_writes.take_all()
+        } else {
+            self.remote_writes.take_ready(Instant::now())
+        };
+
+        let db = &self.db;
+        let mut errors = SmallVec::<[anyhow::Error; 1]>::new();
+        let mut missing = SmallVec::<[SeriesInfo; 1]>::new();
+        let mut synced = false;
+
+        for id in ids {
+            let data = self
+                .series
+                .iter_mut()
+                .filter_map(LoadedSeries::data_mut)
+                .find(|data| data.info.id == id);
+
+            let data = match data {
+                Some(data) => data,
+                None => continue,
+            };
+
+            match data.sync_to_remote(remote, db).and_then(|()| data.save(db)) {
+                Ok(()) => synced = true,
+                Err(err) => {
+                    if err::is_series_missing(&err) {
+                        missing.push(data.info.clone());
+                    }
+
+                    errors.push(err.context(format!("failed to sync {}", data.title())));
+                }
+            }
+
+            if let Some(party) = &mut self.watch_party {
+                errors.extend(party.share_progress(data, &self.users, &self.config, db));
+            }
+        }
+
+        for err in errors {
+            // Being unavailable is saved by `push_error` instead, since it's reported once for every entry
+            if !err::is_service_unavailable(&err) {
+                self.save_event(EventKind::Error, &format!("{:#}", err));
+            }
+
+            self.push_error(&err);
+        }
+
+        for info in missing {
+            self.suggest_relink(&info);
+        }
+
+        if synced {
+            self.last_sync = Some(Utc::now().timestamp());
+            // The service answered, so whatever was wrong with it has passed
+            self.set_health(Health::Healthy);
+        }
+    }
+
+    /// Set the health of the remote service, and show it in the log's title if it's having problems.
+    fn set_health(&mut self, health: Health) {
+        self.health = health;
+
+        let tag = health.tag(self.last_sync, &self.config.dates);
+        self.log.set_service_health(tag);
+    }
+
+    /// Log the series that likely replaced the series with the specified `info` on the remote service, so it can be relinked.
+    fn suggest_relink(&mut self, info: &SeriesInfo) {
+        let remote = match self.remote.get_logged_in() {
+            Ok(remote) => remote,
+            Err(_) => return,
+        };
+
+        let message = match info.find_replacement(&self.config, remote) {
+            Ok(Some(replacement)) => Text::SeriesMerged.format(&[
+                &info.title_preferred,
+                &replacement.title_preferred,
+                &replacement.id,
+            ]),
+            Ok(None) => Text::SeriesRemoved.format(&[&info.title_preferred]),
+            Err(err) => return self.push_error(&err),
+        };
+
+        self.save_event(EventKind::Warning, &message);
+        self.log.push(LogKind::Warning, message);
+    }
+
+    /// Mark the next episode of the selected series as watched.
+    ///
+    /// The change is sent to the remote service once the series hasn't been changed again for a moment.
+    pub fn complete_selected_episode(&mut self) -> Result<()> {
+        let id = try_opt_r!(self.series.get_valid_sel_series_mut())
+            .data
+            .info
+            .id;
+
+        self.complete_episode(id)
+    }
+
+    /// Mark the next episode of the series with the specified `id` as watched.
+    ///
+    /// While the presentation lock is on, the episode is only counted once the lock is lifted.
+    pub fn complete_episode(&mut self, id: i32) -> Result<()> {
+        if self.presentation_lock {
+            self.held_episodes.push(id);
+            return Ok(());
+        }
+
+        let is_selected = self.series.selected().and_then(LoadedSeries::id) == Some(id);
+
+        let series = self
+            .series
+            .iter_mut()
+            .filter_map(LoadedSeries::complete_mut)
+            .find(|series| series.data.info.id == id);
+
+        let series = try_opt_r!(series);
+        let was_completed = series.data.entry.status() == Status::Completed;
+
+        series
+            .episode_completed(&self.config, &self.db)
+            .context("marking episode as completed")?;
+
+        self.remote_writes.push(series.data.info.id);
+
+        if series.data.config.remove_watched {
+            if let Err(err) = series.remove_watched_episodes(&self.config, &self.db) {
+                self.log
+                    .push_error(&err.context("failed to remove watched episodes"));
+            }
+        }
+
+        // The summary is only shown for the selected series, since completing a different one means it was played in the background
+        if is_selected && !was_completed && series.data.entry.status() == Status::Completed {
+            self.series_completed = true;
+        }
+
+        if !was_completed
+            && series.data.entry.status() == Status::Completed
+            && self.config.episode.extra_episodes == ExtraEpisodes::Warn
+        {
+            let extra = series.extra_episodes();
+
+            if extra > 0 {
+                self.log.push(
+                    LogKind::Warning,
+                    Text::ExtraEpisodesFound.format(&[series.data.title(), &extra]),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Turn the presentation lock on or off.
+    ///
+    /// Episodes that finished while it was on are counted when it's turned off.
+    pub fn toggle_presentation_lock(&mut self) {
+        self.presentation_lock = !self.presentation_lock;
+        self.log.set_presentation_lock(self.presentation_lock);
+
+        if self.presentation_lock {
+            self.log.push(
+                LogKind::Info,
+                Text::PresentationLocked.format(&[&self.config.tui.keys.presentation_lock]),
+            );
+
+            return;
+        }
+
+        self.log
+            .push(LogKind::Info, Text::PresentationUnlocked.get());
+
+        for id in mem::take(&mut self.held_episodes) {
+            if let Err(err) = self.complete_episode(id) {
+                self.push_error(&err);
+            }
+        }
+    }
+
+    /// Mark the episode being played of the selected series, or its next episode if one isn't being played, as watched
+    /// regardless of how long it has been played for.
+    pub fn force_complete_episode(&mut self) -> Result<()> {
+        let id = try_opt_r!(self.series.get_valid_sel_series_mut())
+            .data
+            .info
+            .id;
+
+        // The episode being played can only be completed once
+        if matches!(self.watching.get(&id), Some(watch) if watch.force_completed) {
+            return Ok(());
+        }
+
+        self.complete_episode(id)?;
+
+        if let Some(watch) = self.watching.get_mut(&id) {
+            watch.force_completed = true;
+            self.events.send(StateEvent::FinishedEpisode(id)).ok();
+        }
+
+        Ok(())
+    }
+
+    /// Count `episode` of the series with the specified `id` as watched, now that the daemon has seen its player exit.
+    ///
+    /// Nothing is done if the episode was already counted some other way.
+    pub fn complete_adopted_episode(&mut self, id: i32, episode: i16) -> Result<()> {
+        let data = self
+            .series
+            .iter()
+            .filter_map(LoadedSeries::data)
+            .find(|data| data.info.id == id);
+
+        let data = try_opt_r!(data);
+
+        if data.entry.watched_episodes() + 1 != episode {
+            return Ok(());
+        }
+
+        let title = data.title().clone();
+        self.complete_episode(id)?;
+
+        self.log.push(
+            LogKind::Info,
+            Text::CountedByDaemon.format(&[&episode, &title]),
+        );
+
+        Ok(())
+    }
+
+    /// Load the series with the specified `id` from the database again, after another instance of the program changed it.
+    pub fn reload_series(&mut self, id: i32) -> Result<()> {
+        // The TUI's own copy is the newest one while it's playing the series
+        if self.watching.contains_key(&id) {
+            return Ok(());
+        }
+
+        let index = self
+            .series
+            .iter()
+            .position(|series| series.id() == Some(id));
+        let index = try_opt_r!(index);
+
+        let config = SeriesConfig::load_by_id(&self.db, id).context("loading series")?;
+        self.series.items_mut()[index] = Series::load_from_config(config, &self.config, &self.db);
+
+        // The series may have moved if its status or priority changed
+        self.sort_series();
+        Ok(())
+    }
+
+    /// Hand every episode that's still being played over to the daemon, so they're still counted once their player
+    /// exits, and have it sync any changes that couldn't be sent.
+    ///
+    /// A daemon is started if one isn't already running, but only for episodes that are being played.
+    pub fn detach(&mut self, args: &Args) -> Result<()> {
+        let mut daemon_running = ipc::is_listening(Endpoint::Daemon);
+
+        for (&id, watch) in &self.watching {
+            // The daemon can only tell when a player exits on Linux, so everywhere else the episode is checked the next
+            // time the TUI starts instead
+            let pid = match watch.pid.filter(|_| cfg!(target_os = "linux")) {
+                Some(pid) => pid,
+                None => continue,
+            };
+
+            if !daemon_running {
+                daemon::start_until_idle(args).context("starting daemon")?;
+                daemon_running = true;
+            }
+
+            let playback = AdoptedPlayback {
+                series_id: id,
+                episode: watch.episode as i16,
+                started_at: watch.started_at.timestamp(),
+                progress_at: watch.progress_time.timestamp(),
+                pid,
+            };
+
+            ipc::send(Endpoint::Daemon, Request::AdoptPlayback(playback)).with_context(|| {
+                format!(
+                    "handing episode {} of {} over to the daemon",
+                    watch.episode, watch.title
+                )
+            })?;
+        }
+
+        let unsynced = self
+            .series
+            .iter()
+            .filter_map(LoadedSeries::data)
+            .any(|data| !data.config.local_only && data.entry.needs_sync());
+
+        if daemon_running && unsynced {
+            ipc::send(Endpoint::Daemon, Request::Sync).context("asking daemon to sync")?;
+        }
+
+        Ok(())
+    }
+
+    /// Ask if the player of the selected series should be closed.
+    pub fn request_stop_episode(&mut self) -> Result<()> {
+        let id = self
+            .series
+            .selected()
+            .and_then(LoadedSeries::id)
+            .filter(|id| self.watching.contains_key(id))
+            .ok_or_else(|| anyhow!("no episode of the selected series is being played"))?;
+
+        let watch = &self.watching[&id];
+
+        self.log.push(
+            LogKind::Warning,
+            Text::ConfirmStop.format(&[&watch.episode, &watch.title]),
+        );
+
+        self.input_state = InputState::ConfirmingStop(id);
+        Ok(())
+    }
+
+    /// Check if exiting would leave anything unfinished, and if so, list what would be and ask to confirm.
+    ///
+    /// Returns true if nothing is pending and the program can exit right away.
+    pub fn request_exit(&mut self) -> bool {
+        let mut pending = SmallVec::<[String; 4]>::new();
+
+        // Episodes that are being played are handed over to the daemon on exit
+        for watch in self.watching.values() {
+            pending.push(Text::ExitWhilePlaying.format(&[&watch.episode, &watch.title]));
+        }
+
+        // Queued entries are sent on exit, so only the ones that failed to sync or were changed offline are left behind
+        let unsynced = self
+            .series
+            .iter()
+            .filter_map(LoadedSeries::data)
+            .filter(|data| !data.config.local_only && data.entry.needs_sync())
+            .filter(|data| !self.remote_writes.contains(data.info.id))
+            .count();
+
+        if unsynced > 0 {
+            pending.push(Text::ExitWithUnsynced.format(&[&unsynced]));
+        }
+
+        let running = self.tasks.iter().filter(|task| task.is_running()).count();
+
+        if running > 0 {
+            pending.push(Text::ExitWithTasks.format(&[&running]));
+        }
+
+        if pending.is_empty() {
+            return true;
+        }
+
+        for item in pending {
+            self.log.push(LogKind::Warning, item);
+        }
+
+        self.log.push(LogKind::Warning, Text::ConfirmExit.get());
+        self.input_state = InputState::ConfirmingExit;
+
+        false
+    }
+
+    /// Copy `text` to the system clipboard.
+    ///
+    /// The clipboard is kept open afterwards, since on some platforms the copied text is lost once it's closed.
+    pub fn copy_to_clipboard(&mut self, text: String) -> Result<()> {
+        let clipboard = match &mut self.clipboard {
+            Some(clipboard) => clipboard,
+            None => self
+                .clipboard
+                .insert(Clipboard::new().context("opening clipboard")?),
+        };
+
+        clipboard.set_text(text).context("copying to clipboard")
+    }
+
+    /// Ask if the next series whose folder was found somewhere else should use it.
+    fn offer_next_moved(&mut self) {
+        let moved = match self.moved.first() {
+            Some(moved) => moved,
+            None => return,
+        };
+
+        let series = self
+            .series
+            .iter()
+            .find(|series| series.config().id == moved.id);
+
+        let nickname = if let Some(series) = series {
+            series.nickname()
+        } else {
+            // The series was deleted before it could be asked about
+            self.moved.remove(0);
+            return;
+        };
+
+        self.log.push(
+            LogKind::Warning,
+            Text::ConfirmMoved.format(&[&nickname, &moved.path.display()]),
+        );
+
+        self.input_state = InputState::ConfirmingMoved(moved.id);
+    }
+
+    /// Ask if the rows left behind by series that no longer exist should be removed.
+    fn offer_orphan_cleanup(&mut self) {
+        let orphans = try_opt_ret!(&self.orphans);
+
+        self.log.push(
+            LogKind::Warning,
+            Text::ConfirmOrphans.format(&[&orphans.total(), orphans]),
+        );
+
+        self.input_state = InputState::ConfirmingOrphans;
+    }
+
+    /// Handle the answer to whether or not the rows left behind by series that no longer exist should be removed.
+    pub fn confirm_orphans(&mut self, remove: bool) -> Result<()> {
+        self.input_state.reset();
+
+        if self.orphans.take().is_none() || !remove {
+            return Ok(());
+        }
+
+        let removed = Orphans::remove(&self.db)?;
+        self.log
+            .push(LogKind::Info, Text::OrphansRemoved.format(&[&removed]));
+
+        Ok(())
+    }
+
+    fn find_sync_conflicts(db: &Database, log: &mut Log) -> Vec<SyncConflict> {
+        let paths = match SyncConflict::find_all() {
+            Ok(paths) => paths,
+            Err(err) => {
+                log.push_error(
+                    &err.context("failed to look for conflicting copies of the database"),
+                );
+                return Vec::new();
+            }
+        };
+
+        paths
+            .into_iter()
+            .filter_map(|path| {
+                let display = path.display().to_string();
+
+                match SyncConflict::load(db, path) {
+                    Ok(conflict) => Some(conflict),
+                    Err(err) => {
+                        log.push_error(&err.context(format!("failed to compare {}", display)));
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Show what would change by merging the oldest conflicting copy of the database, and ask if it should be merged.
+    fn offer_next_conflict(&mut self) {
+        let conflict = try_opt_ret!(self.sync_conflicts.first());
+
+        for change in &conflict.changes {
+            self.log.push(LogKind::Context, change.to_string());
+        }
+
+        self.log.push(
+            LogKind::Warning,
+            Text::ConfirmSyncConflict.format(&[&conflict.path.display(), conflict]),
+        );
+
+        self.input_state = InputState::ConfirmingConflict;
+    }
+
+    /// Handle the answer to whether or not the oldest conflicting copy of the database should be merged.
+    ///
+    /// A copy that isn't merged is left alone, and is asked about again the next time the program starts.
+    pub fn confirm_conflict(&mut self, merge: bool) -> Result<()> {
+        self.input_state.reset();
+
+        if self.sync_conflicts.is_empty() {
+            return Ok(());
+        }
+
+        let conflict = self.sync_conflicts.remove(0);
+
+        if !merge {
+            return Ok(());
+        }
+
+        conflict.merge(&self.db)?;
+
+        let mut ids = conflict
+            .changes
+            .iter()
+            .filter(|change| change.take_other)
+            .map(|change| change.id)
+            .collect::<Vec<_>>();
+
+        ids.sort_unstable();
+        ids.dedup();
+
+        for id in ids {
+            if self.series.iter().any(|series| series.id() == Some(id)) {
+                self.reload_series(id)?;
+                continue;
+            }
+
+            // The series was only added on the other machine
+            let config = SeriesConfig::load_by_id(&self.db, id).context("loading merged series")?;
+            self.series
+                .push(Series::load_from_config(config, &self.config, &self.db));
+        }
+
+        self.sort_series();
+
+        self.log.push(
+            LogKind::Info,
+            Text::SyncConflictMerged.format(&[&conflict.path.display()]),
+        );
+
+        Ok(())
+    }
+
+    /// Handle the answer to whether or not the series with the specified `id` should use the folder it was likely moved to.
+    pub fn confirm_moved(&mut self, id: i32, use_path: bool) -> Result<()> {
+        self.input_state.reset();
+
+        let moved = match self.moved.iter().position(|moved| moved.id == id) {
+            Some(index) => self.moved.remove(index),
+            None => return Ok(()),
+        };
+
+        if !use_path {
+            return Ok(());
+        }
+
+        // Another series could have been moved to the same folder first
+        if self
+            .series
+            .iter()
+            .any(|series| *series.path() == moved.path)
+        {
+            return Err(anyhow!(
+                "{} is already used by another series",
+                moved.path.display()
+            ));
+        }
+
+        let config = &self.config;
+        let db = &self.db;
+
+        let series = self
+            .series
+            .iter_mut()
+            .find(|series| series.config().id == id)
+            .ok_or_else(|| anyhow!("the series was deleted"))?;
+
+        let data = series
+            .data_mut()
+            .ok_or_else(|| anyhow!("the series isn't loaded"))?;
+
+        data.config.path = moved.path;
+        data.config.save(db).context("saving series config")?;
+
+        self.log.push(
+            LogKind::Info,
+            Text::SeriesMoved.format(&[&data.config.nickname, &data.config.path.display()]),
+        );
+
+        series.try_load(config, db);
+        Ok(())
+    }
+
+    /// Handle the answer to whether or not the player of the series with the specified `id` should be closed.
+    pub fn confirm_stop_episode(&mut self, id: i32, stop: bool) {
+        self.input_state.reset();
+
+        if !stop {
+            return;
+        }
+
+        if let Some(watch) = self.watching.get(&id) {
+            watch.stop.notify_one();
+        }
+    }
+
+    /// Queue asking whether an episode of the series with the specified `id` should be counted, with `message` being
+    /// the question shown in the log.
+    ///
+    /// It's asked right away if nothing else is being asked or entered, and otherwise once the user is done.
+    fn queue_episode_confirmation(&mut self, id: i32, message: String) {
+        self.unconfirmed_episodes
+            .push(UnconfirmedEpisode { id, message });
+
+        if self.input_state == InputState::Idle {
+            self.offer_next_episode();
+        }
+    }
+
+    /// Ask if the next episode that wasn't counted should be.
+    fn offer_next_episode(&mut self) {
+        if self.unconfirmed_episodes.is_empty() {
+            return;
+        }
+
+        let episode = self.unconfirmed_episodes.remove(0);
+
+        self.log.push(LogKind::Warning, episode.message);
+        self.input_state = InputState::ConfirmingEpisode(episode.id);
+    }
+
+    /// Handle the answer to whether or not an episode of the series with the specified `id` should be counted after the player exited abnormally.
+    ///
+    /// The next episode waiting to be confirmed is asked about afterwards.
+    pub fn confirm_episode(&mut self, id: i32, count: bool) -> Result<()> {
+        self.input_state.reset();
+
+        let result = if count {
+            PlaybackSession::mark_latest_counted(&self.db, id)
+                .context("updating playback session")
+                .and_then(|_| self.complete_episode(id))
+        } else {
+            Ok(())
+        };
+
+        if self.input_state == InputState::Idle {
+            self.offer_next_episode();
+        }
+
+        result
+    }
+
+    /// Log in to the account after the current one in the selected profile.
+    pub fn switch_to_next_user(&mut self, shared: &SharedState) -> Result<()> {
+        let remote = self.remote.get_logged_in()?;
+        let users = self.users.in_profile(&self.profile);
+
+        if users.is_empty() {
+            return Err(anyhow!("no accounts in the {} profile", self.profile));
+        }
+
+        let next = users
+            .iter()
+            .position(|(user, _)| user.is_logged_in(remote))
+            .map_or(0, |current| (current + 1) % users.len());
+
+        let (user, account) = users[next];
+
+        if user.is_logged_in(remote) {
+            return Err(anyhow!("no other accounts in the {} profile", self.profile));
+        }
+
+        let user = user.clone();
+        let token = account.token.clone();
+
+        self.switch_to_user(user, token, shared)
+    }
+
+    /// Log in to `user` and load its list entries.
+    ///
+    /// Every list entry without unsynced changes is re-resolved against the new account once logged in.
+    pub fn switch_to_user(
+        &mut self,
+        user: UserInfo,
+        token: AccessToken,
+        shared: &SharedState,
+    ) -> Result<()> {
+        self.set_account(&user)?;
+
+        self.users.mark_used(&user);
+        self.users.save().context("saving users")?;
+
+        match user.service {
+            RemoteType::AniList => {
+                shared.switch_account_async(RemoteLogin::AniList(user.username, token));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Make `user` the account that list entries are loaded from and saved to, and reload the entry of every series.
+    fn set_account(&mut self, user: &UserInfo) -> Result<()> {
+        self.db
+            .set_account(user.account_id())
+            .context("setting database account")?;
+
+        for data in self.series.iter_mut().filter_map(LoadedSeries::data_mut) {
+            data.entry =
+                SeriesEntry::load_or_new(&self.db, data.info.id).context("loading series entry")?;
+        }
+
+        Ok(())
+    }
+
+    /// Switch between showing every series and only series that are planned to be rewatched.
+    pub fn toggle_rewatch_filter(&mut self) -> Result<()> {
+        let filter = match self.series_filter {
+            SeriesFilter::All | SeriesFilter::Airing | SeriesFilter::Collection => {
+                SeriesFilter::PlannedRewatch
+            }
+            SeriesFilter::PlannedRewatch => SeriesFilter::All,
+        };
+
+        if !self
+            .series
+            .iter()
+            .any(|series| filter.matches(series, &self.airing, &self.collection, &self.franchises))
+        {
+            return Err(anyhow!(
+                "no series are planned to be rewatched; use the rewatchplan command to plan one"
+            ));
+        }
+
+        self.set_series_filter(filter);
+        Ok(())
+    }
+
+    /// Show the series matched by `filter`, and sort the series list to suit it.
+    pub fn set_series_filter(&mut self, filter: SeriesFilter) {
+        if self.series_filter == filter {
+            return;
+        }
+
+        self.series_filter = filter;
+        self.sort_series();
+        self.ensure_visible_selection();
+    }
+
+    /// Sort the series list by `sort` from now on.
+    pub fn set_series_sort(&mut self, sort: SeriesSort) {
+        self.series_sort = sort;
+        self.sort_series();
+    }
+
+    /// Add `key` to the key chord being entered, or start a new one with it.
+    ///
+    /// Returns the action of the chord once every key of it has been pressed. The chord is cancelled if none
+    /// continue with `key`.
+    pub fn push_chord_key(&mut self, key: Key) -> Option<ChordAction> {
+        let chord = self.chord.get_or_insert_with(PendingChord::new);
+        chord.keys.push(key);
+
+        let chords = &self.config.tui.chords;
+
+        if let Some(binding) = chords.find(&chord.keys) {
+            let action = binding.action.clone();
+            self.cancel_chord();
+            return Some(action);
+        }
+
+        if chords.starting_with(&chord.keys).next().is_some() {
+            self.input_state = InputState::EnteringChord;
+        } else {
+            self.cancel_chord();
+        }
+
+        None
+    }
+
+    pub fn cancel_chord(&mut self) {
+        self.chord = None;
+
+        if self.input_state == InputState::EnteringChord {
+            self.input_state.reset();
+        }
+    }
+
+    /// Returns true if the key chord being entered has waited too long for its next key.
+    fn chord_timed_out(&self) -> bool {
+        let timeout = Duration::from_millis(self.config.tui.chords.timeout_ms.into());
+
+        self.chord
+            .as_ref()
+            .map_or(false, |chord| chord.started.elapsed() >= timeout)
+    }
+
+    /// Switch the series list to the next collection that has any added series in it, in alphabetical order.
+    ///
+    /// Every series is shown again after the last collection.
+    pub fn cycle_collection(&mut self) -> Result<()> {
+        let mut names = SeriesCollection::names(&self.db).context("loading collections")?;
+
+        let added = self
+            .series
+            .iter()
+            .map(|series| series.config().id)
+            .collect::<HashSet<_>>();
+
+        // Deleted series stay in their collections, which could leave nothing to show
+        names.retain(|name| {
+            SeriesCollection::members(&self.db, name)
+                .map_or(false, |members| members.iter().any(|id| added.contains(id)))
+        });
+
+        if names.is_empty() {
+            return Err(anyhow!(
+                "no series are in a collection; use the collection command to add one"
+            ));
+        }
+
+        let next = match self.series_filter {
+            SeriesFilter::Collection => names
+                .iter()
+                .position(|name| name.eq_ignore_ascii_case(&self.collection.name))
+                .map_or(0, |index| index + 1),
+            SeriesFilter::All | SeriesFilter::PlannedRewatch | SeriesFilter::Airing => 0,
+        };
+
+        if let Some(name) = names.into_iter().nth(next) {
+            return self.show_collection(name);
+        }
+
+        self.set_series_filter(SeriesFilter::All);
+        Ok(())
+    }
+
+    /// Switch the series list to only show the series in the collection called `name`.
+    pub fn show_collection(&mut self, name: String) -> Result<()> {
+        self.collection = ShownCollection::load(&self.db, name).context("loading collection")?;
+
+        if self.series_filter == SeriesFilter::Collection {
+            self.ensure_visible_selection();
+        } else {
+            self.set_series_filter(SeriesFilter::Collection);
+        }
+
+        Ok(())
+    }
+
+    /// Switch between grouping the series list by franchise and showing every series on its own.
+    ///
+    /// Every series is shown again if the list was filtered, since only the full list is grouped.
+    pub fn toggle_franchise_grouping(&mut self) {
+        self.franchises.grouped = !self.franchises.grouped;
+
+        if self.series_filter == SeriesFilter::All {
+            self.sort_series();
+        } else {
+            self.set_series_filter(SeriesFilter::All);
+        }
+
+        self.reveal_selected_franchise();
+    }
+
+    /// Show or hide the rest of the series of the franchise the selected series belongs to.
+    pub fn set_selected_franchise_expanded(&mut self, expanded: bool) {
+        if !self.franchises.grouped || self.series_filter != SeriesFilter::All {
+            return;
+        }
+
+        let selected = try_opt_ret!(self.series.selected());
+        let head = try_opt_ret!(self.franchises.set_expanded(selected, expanded));
+
+        // The selected series would be hidden if it was one of the series that were collapsed
+        if !expanded {
+            self.select_series_by_id(head);
+        }
+    }
+
+    /// Expand the franchise of the selected series if the series is hidden inside of it.
+    fn reveal_selected_franchise(&mut self) {
+        let selected = try_opt_ret!(self.series.selected());
+
+        if !self.franchises.is_shown(selected) {
+            self.franchises.set_expanded(selected, true);
+        }
+    }
+
+    /// Sort the series list while keeping the same series selected.
+    ///
+    /// Series are grouped by the day of the week they air on when only airing series are shown,
+    /// starting from today, and by franchise when every series is shown and the list is grouped.
+    pub fn sort_series(&mut self) {
+        let selected = self
+            .series
+            .selected()
+            .map(|series| series.config().nickname.clone());
+
+        if self.series_filter == SeriesFilter::Airing {
+            let airing = &self.airing;
+            let today = Local::now().weekday();
+            let day = |series: &LoadedSeries| {
+                series
+                    .id()
+                    .and_then(|id| airing.days_until(id, today))
+                    .unwrap_or(u32::MAX)
+            };
+
+            self.series
+                .items_mut()
+                .sort_unstable_by(|x, y| day(x).cmp(&day(y)).then_with(|| x.cmp(y)));
+        } else if self.series_sort == SeriesSort::Priority {
+            // Series that can't be loaded don't have a list entry to take a priority from
+            let priority =
+                |series: &LoadedSeries| series.data().map_or(0, |data| data.entry.priority());
+
+            self.series
+                .items_mut()
+                .sort_unstable_by(|x, y| priority(y).cmp(&priority(x)).then_with(|| x.cmp(y)));
+        } else {
+            self.series.items_mut().sort_unstable();
+        }
+
+        self.franchises.rebuild(self.series.iter());
+
+        if self.franchises.grouped && self.series_filter == SeriesFilter::All {
+            self.franchises.sort(self.series.items_mut());
+        }
+
+        let index = selected.and_then(|nickname| {
+            self.series
+                .iter()
+                .position(|series| series.config().nickname == nickname)
+        });
+
+        if let Some(index) = index {
+            self.series.set_selected(index);
+        }
+    }
+
+    /// Select the next series shown by the current filter, or the previous one if `forwards` is false.
+    pub fn select_next_visible(&mut self, forwards: bool) {
+        for _ in 0..self.series.len() {
+            if forwards {
+                self.series.inc_selected();
+            } else {
+                self.series.dec_selected();
+            }
+
+            if self.series_filter.matches_selected(
+                &self.series,
+                &self.airing,
+                &self.collection,
+                &self.franchises,
+            ) {
+                break;
+            }
+        }
+
+        self.init_selected_series();
+    }
+
+    /// Returns the series shown by the current filter that fit in a list `height` rows tall, along with the
+    /// position of the selected series among them.
+    ///
+    /// The window scrolls just enough to keep the selected series in view, so only the series that are
+    /// actually drawn need to be turned into list items.
+    pub fn visible_series_window(
+        &self,
+        height: usize,
+    ) -> (impl Iterator<Item = &LoadedSeries>, usize) {
+        let filter = self.series_filter;
+        let airing = &self.airing;
+        let collection = &self.collection;
+        let franchises = &self.franchises;
+
+        let visible = self
+            .series
+            .iter()
+            .enumerate()
+            .filter(move |(_, series)| filter.matches(series, airing, collection, franchises));
+
+        // Every series is shown without a filter, so there's no need to search for the selected one
+        let selected = match filter {
+            SeriesFilter::All if !franchises.grouped => self.series.index(),
+            SeriesFilter::All
+            | SeriesFilter::PlannedRewatch
+            | SeriesFilter::Airing
+            | SeriesFilter::Collection => visible
+                .clone()
+                .position(|(index, _)| index == self.series.index())
+                .unwrap_or(0),
+        };
+
+        let offset = (selected + 1).saturating_sub(height.max(1));
+        let window = visible.skip(offset).take(height).map(|(_, series)| series);
+
+        (window, selected - offset)
+    }
+
+    /// Make sure the selected series is shown by the current filter.
+    ///
+    /// The filter is removed if it doesn't match any series anymore.
+    pub fn ensure_visible_selection(&mut self) {
+        if self.series_filter.matches_selected(
+            &self.series,
+            &self.airing,
+            &self.collection,
+            &self.franchises,
+        ) {
+            return;
+        }
+
+        let filter = self.series_filter;
+        let airing = &self.airing;
+        let collection = &self.collection;
+        let franchises = &self.franchises;
+
+        let index = self
+            .series
+            .iter()
+            .position(|series| filter.matches(series, airing, collection, franchises));
+
+        if let Some(index) = index {
+            self.series.set_selected(index);
+        } else {
+            self.series_filter = SeriesFilter::All;
+            self.sort_series();
+        }
+
+        self.init_selected_series();
+    }
+
+    /// Select the series with the specified `id`, and stop filtering the series list if it would be hidden.
+    ///
+    /// Returns false if no series has the `id`.
+    pub fn select_series_by_id(&mut self, id: i32) -> bool {
+        let index = match self
+            .series
+            .iter()
+            .position(|series| series.config().id == id)
+        {
+            Some(index) => index,
+            None => return false,
+        };
+
+        self.series.set_selected(index);
+        self.reveal_selected_franchise();
+
+        if !self.series_filter.matches_selected(
+            &self.series,
+            &self.airing,
+            &self.collection,
+            &self.franchises,
+        ) {
+            self.set_series_filter(SeriesFilter::All);
+        }
+
+        self.init_selected_series();
+        true
+    }
+
+    pub fn init_selected_series(&mut self) {
+        let selected = try_opt_ret!(self.series.selected_mut());
+        selected.try_load(&self.config, &self.db)
+    }
+
+    /// Move the selected series to the trash so it can be restored later with the `undelete` command.
+    ///
+    /// When `remove_files` is true, the files of the series will be moved to the trash as well.
+    pub fn delete_selected_series(&mut self, remove_files: bool) -> Result<()> {
+        let series = match self.series.selected() {
+            Some(series) => series,
+            None => return Err(anyhow!("must select series to delete")),
+        };
+
+        if matches!(series.id(), Some(id) if self.watching.contains_key(&id)) {
+            return Err(anyhow!("an episode of this series is being played"));
+        }
+
+        let trashed_path = if remove_files {
+            if series.path().url().is_some() {
+                return Err(anyhow!("episodes streamed from a URL can't be removed"));
+            }
+
+            let path = series.path().absolute(&self.config);
+            Some(trash::move_to_trash(&path)?)
+        } else {
+            None
+        };
+
+        if let Err(err) = DeletedSeries::trash(&self.db, series.config(), trashed_path.as_deref()) {
+            if let Some(trashed_path) = &trashed_path {
+                let path = series.path().absolute(&self.config);
+                trash::restore(trashed_path, &path).ok();
+            }
+
+            return Err(err);
+        }
+
+        let index = self.series.index();
+        self.series.items_mut().remove(index);
+
+        self.series.update_bounds();
+        // The series may have started a franchise, so the rest of it needs a new first series
+        self.sort_series();
+        // Since we changed our selected series, we need to make sure the new one is shown and initialized
+        self.ensure_visible_selection();
+        self.init_selected_series();
+
+        Ok(())
+    }
+
+    fn start_next_series_episode(
+        &mut self,
+        extra_args: &[String],
+    ) -> Result<(i32, Child, ActiveWatch, PlaybackTimer)> {
+        let series = match self.series.get_valid_sel_series_mut() {
+            Some(series) => series,
+            None => return Err(anyhow!("no series selected")),
+        };
+
+        let is_diff_series = self.last_watched.set(&series.data.config.nickname);
+
+        if is_diff_series {
+            self.last_watched
+                .save()
+                .context("setting last watched series")?;
+        }
+
+        let remote = self.remote.get_logged_in()?;
+
+        if let Some(playback) = ActivePlayback::load_elsewhere(&self.db, series.data.info.id)? {
+            return Err(anyhow!(
+                "episode {} of {} is already being played by another instance of the program",
+                playback.episode,
+                series.data.title()
+            ));
+        }
+
+        series
+            .begin_watching(remote, &self.config, &self.db)
+            .context("updating series status")?;
+
+        let skipped = series
+            .skip_filler(&self.config, &self.db)
+            .context("skipping filler episodes")?;
+
+        if let Some((first, last)) = skipped {
+            self.log.push(
+                LogKind::Info,
+                Text::SkippedFiller.format(&[&first, &last, series.data.title()]),
+            );
+        }
+
+        let next_ep = series.data.entry.watched_episodes() + 1;
+
+        let child = match series.play_episode(next_ep as u32, extra_args, &self.config, &self.db) {
+            Ok(child) => child,
+            Err(err) => {
+                let unreachable = matches!(
+                    err.downcast_ref::<EpisodePathError>(),
+                    Some(EpisodePathError::Unreachable { .. })
+                );
+
+                // The folder of the series usually can't be reached either, such as when a network drive was unmounted
+                if unreachable {
+                    if let Some(selected) = self.series.selected_mut() {
+                        selected.rescan(&self.config);
+                    }
+                }
+
+                return Err(err.context("playing episode"));
+            }
+        };
+
+        let id = series.data.info.id;
+        let timer =
+            PlaybackTimer::start(&self.db, id, next_ep).context("recording active playback")?;
+
+        let watch = ActiveWatch {
+            title: series.data.title().clone(),
+            episode: next_ep as u32,
+            stop: Arc::new(Notify::new()),
+            progress_time: series
+                .data
+                .next_watch_progress_time(timer.started_at(), &self.config),
+            force_completed: false,
+            started_at: timer.started_at(),
+            pid: child.id(),
+        };
+
+        Ok((id, child, watch, timer))
+    }
+
+    /// Play `episode` of the selected series without tracking it, so it won't count as watched once the player exits.
+    pub fn play_untracked_episode(&mut self, episode: u32, extra_args: &[String]) -> Result<()> {
+        let series = match self.series.get_valid_sel_series_mut() {
+            Some(series) => series,
+            None => return Err(anyhow!("no series selected")),
+        };
+
+        // The player is left to exit on its own, since nothing has to be done once it does
+        series
+            .play_episode(episode, extra_args, &self.config, &self.db)
+            .context("playing episode")?;
+
+        self.log.push(
+            LogKind::Info,
+            Text::PlayingUntracked.format(&[&episode, series.data.title()]),
+        );
+
+        Ok(())
+    }
+
+    /// Push back the progress time of every episode being played by the time the system was just asleep for,
+    /// so it doesn't count towards watching them.
+    ///
+    /// Episodes that were already played for long enough before the system went to sleep are left alone.
+    pub fn pause_timers_for_sleep(&mut self, slept: chrono::Duration) {
+        let fell_asleep = Utc::now() - slept;
+
+        let watches = self
+            .watching
+            .iter_mut()
+            .filter(|(_, watch)| !watch.force_completed && watch.progress_time > fell_asleep);
+
+        for (&id, watch) in watches {
+            watch.progress_time += slept;
+
+            self.log.push(
+                LogKind::Info,
+                Text::TimerPausedForSleep.format(&[&watch.episode, &watch.title]),
+            );
+
+            // Restarts the countdown of the episode with its new progress time
+            self.events
+                .send(StateEvent::StartedEpisode(id, watch.progress_time))
+                .ok();
+        }
+    }
+
+    /// Select the last watched series and play its next episode, for requests made with `anup play-next`.
+    pub fn play_last_watched_episode(&mut self, shared_state: &SharedState) -> Result<()> {
+        let nickname = self
+            .last_watched
+            .get()
+            .ok_or_else(|| anyhow!("no series has been watched yet"))?;
+
+        let index = self
+            .series
+            .iter()
+            .position(|series| series.nickname() == nickname)
+            .ok_or_else(|| anyhow!("{} no longer exists", nickname))?;
+
+        self.series.set_selected(index);
+
+        if !self.series_filter.matches_selected(
+            &self.series,
+            &self.airing,
+            &self.collection,
+            &self.franchises,
+        ) {
+            self.set_series_filter(SeriesFilter::All);
+        }
+
+        self.init_selected_series();
+        self.play_next_series_episode(shared_state, &[])
+    }
+
+    /// Play the next episode of the selected series, with `extra_args` passed to the player along with its usual arguments.
+    ///
+    /// Episodes of other series can be played at the same time, and each one is tracked separately.
+    /// Nothing is played if an episode of the selected series is already being played.
+    pub fn play_next_series_episode(
+        &mut self,
+        shared_state: &SharedState,
+        extra_args: &[String],
+    ) -> Result<()> {
+        let playing = self
+            .series
+            .selected()
+            .and_then(LoadedSeries::id)
+            .and_then(|id| self.watching.get(&id));
+
+        if let Some(watch) = playing {
+            self.log.push(
+                LogKind::Info,
+                Text::AlreadyPlaying.format(&[&watch.episode, &watch.title]),
+            );
+
+            return Ok(());
+        }
+
+        let (id, ep_process, watch, timer) = self.start_next_series_episode(extra_args)?;
+
+        self.events
+            .send(StateEvent::StartedEpisode(id, watch.progress_time))
+            .ok();
+
+        let stop = Arc::clone(&watch.stop);
+        let episode = watch.episode;
+        self.watching.insert(id, watch);
+
+        shared_state.spawn_prefetch(id, episode);
+
+        let shared_state = shared_state.clone();
+
+        task::spawn(async move {
+            let result = shared_state
+                .track_episode_finish(id, ep_process, &stop, timer)
+                .await;
+
+            let mut state = shared_state.lock();
+            let state = state.get_mut();
+
+            if let Err(err) = result {
+                state.push_error(&err);
+            }
+
+            state.events.send(StateEvent::FinishedEpisode(id)).ok();
+        });
+
+        Ok(())
+    }
+}
+
+pub type ReactiveState = Reactive<UIState>;
+
+/// How long to wait for more changes to a list entry before sending it to the remote service.
+const REMOTE_WRITE_DELAY: Duration = Duration::from_secs(3);
+
+/// How often to check for list entries that are ready to be sent to the remote service.
+const REMOTE_WRITE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often to look for new episodes of series that are being watched.
+const EPISODE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often to redraw while an activity is running, so its spinner keeps moving.
+const ACTIVITY_REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often to check if the system was asleep.
+const SLEEP_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often to ping the remote service to check that it's reachable.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(2 * 60);
+
+/// How long an episode has to be playing before what's needed once it finishes is fetched, so episodes that are
+/// closed right away don't send any requests.
+const PREFETCH_DELAY: Duration = Duration::from_secs(30);
+
+/// How long what was fetched for a series is used for, so playing several of its episodes in a row only fetches it once.
+const PREFETCH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Clone)]
+pub struct SharedState(ArcMutex<ReactiveState>);
+
+impl SharedState {
+    pub fn new(state: ReactiveState) -> Self {
+        Self(arc_mutex(state))
+    }
+
+    /// Periodically send list entries that have stopped changing to the remote service.
+    pub fn spawn_remote_write_flusher(&self) -> task::JoinHandle<()> {
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let mut interval = time::interval(REMOTE_WRITE_CHECK_INTERVAL);
+
+            loop {
+                interval.tick().await;
+
+                if !shared_state.lock().remote_writes.has_ready(Instant::now()) {
+                    continue;
+                }
+
+                let shared_state = shared_state.clone();
+
+                task::spawn_blocking(move || {
+                    shared_state.lock().get_mut().flush_remote_writes(false);
+                })
+                .await
+                .ok();
+            }
+        })
+    }
+
+    /// Periodically look for new episodes of series that are being watched, and log when the next one to watch appears.
+    pub fn spawn_episode_watcher(&self) -> task::JoinHandle<()> {
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let mut interval = time::interval(EPISODE_CHECK_INTERVAL);
+            // The first tick completes immediately, and the episodes were just scanned when the series were loaded
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+
+                let shared_state = shared_state.clone();
+                task::spawn_blocking(move || shared_state.check_new_episodes())
+                    .await
+                    .ok();
+            }
+        })
+    }
+
+    /// Periodically check if the system was asleep, so it can be excluded from the timers of episodes being played.
+    pub fn spawn_sleep_watcher(&self) -> task::JoinHandle<()> {
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let mut interval = time::interval(SLEEP_CHECK_INTERVAL);
+            let mut detector = SleepDetector::new();
+
+            loop {
+                interval.tick().await;
+
+                if let Some(slept) = detector.take_slept() {
+                    shared_state.lock().get_mut().pause_timers_for_sleep(slept);
+                }
+            }
+        })
+    }
+
+    /// Periodically ping the remote service, so the log's title can show when it's having problems or can't be reached.
+    pub fn spawn_health_checker(&self) -> task::JoinHandle<()> {
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let mut interval = time::interval(HEALTH_CHECK_INTERVAL);
+
+            loop {
+                interval.tick().await;
+
+                let shared_state = shared_state.clone();
+                task::spawn_blocking(move || shared_state.check_health())
+                    .await
+                    .ok();
+            }
+        })
+    }
+
+    fn check_health(&self) {
+        let remote = {
+            let state = self.lock();
+
+            match state.remote.get_logged_in_shared() {
+                Ok(remote) if !remote.is_offline() && !state.config.low_data => Some(remote),
+                _ => None,
+            }
+        };
+
+        // Nothing is sent while offline or saving data, so there's no problem worth showing
+        let health = remote.map_or(Health::Healthy, |remote| Health::check(&remote));
+
+        let mut state = self.lock();
+
+        if state.health != health {
+            state.get_mut().set_health(health);
+        }
+    }
+
+    /// Periodically redraw while the log is showing an activity, so its spinner is animated.
+    ///
+    /// Key chords that have waited too long for their next key are cancelled here as well.
+    pub fn spawn_activity_ticker(&self) -> task::JoinHandle<()> {
+        let shared_state = self.clone();
+
+        task::spawn(async move {
+            let mut interval = time::interval(ACTIVITY_REDRAW_INTERVAL);
+
+            loop {
+                interval.tick().await;
+
+                let mut state = shared_state.lock();
+
+                if state.chord_timed_out() {
+                    state.get_mut().cancel_chord();
+                }
+
+                // Unconfirmed episodes and moved series are asked about one at a time, once nothing else is being asked
+                if state.input_state == InputState::Idle && !state.presentation_lock {
+                    if !state.unconfirmed_episodes.is_empty() {
+                        state.get_mut().offer_next_episode();
+                    } else if !state.moved.is_empty() {
+                        state.get_mut().offer_next_moved();
+                    } else if state.orphans.is_some() {
+                        state.get_mut().offer_orphan_cleanup();
+                    } else if !state.sync_conflicts.is_empty() {
+                        state.get_mut().offer_next_conflict();
+                    }
+                }
+
+                if state.log.is_busy() {
+                    state.mark_dirty();
+                }
+            }
+        })
+    }
+
+    fn check_new_episodes(&self) {
+        let mut state = self.lock();
+        let state = state.get_mut();
+        let config = &state.config;
+
+        let series = state
+            .series
+            .iter_mut()
+            .filter_map(LoadedSeries::complete_mut)
+            .filter(|series| {
+                matches!(
+                    series.data.entry.status(),
+                    Status::Watching | Status::Rewatching
+                )
+            });
+
+        let mut ready = Vec::new();
+
+        for series in series {
+            // Errors are ignored since they would be logged every time the episodes are checked
+            let new = match series.rescan_episodes(config) {
+                Ok(new) if !new.is_empty() => new,
+                Ok(_) | Err(_) => continue,
+            };
+
+            // Muted series still have their new episodes picked up, just without telling anyone
+            if series.data.config.muted {
+                continue;
+            }
+
+            let next = series.data.entry.watched_episodes().max(0) as u32 + 1;
+            let title = series.data.title();
+
+            if new.contains(&next) {
+                let message = match series.data.episode_titles.get(next) {
+                    Some(ep_title) => Text::TitledEpisodeReady.format(&[&next, &title, &ep_title]),
+                    None => Text::EpisodeReady.format(&[&next, &title]),
+                };
+
+                ready.push(message);
+            } else if series.episodes.find(next).is_none() {
+                let highest = new.iter().max().copied().unwrap_or(next);
+
+                state.log.push(
+                    LogKind::Warning,
+                    Text::EpisodeStillMissing.format(&[&highest, &title, &next]),
+                );
+            }
+        }
+
+        // New episodes are saved so they aren't missed while away, which can only be done once the series are released
+        for message in ready {
+            state.save_event(EventKind::Notification, &message);
+            state.log.push(LogKind::Info, message);
+        }
+
+        // The daemon can save events of its own while the TUI is open
+        state.refresh_unread_events();
+    }
+
+    pub fn login_to_remote_async(&self, login: RemoteLogin) {
+        self.spawn_login(login, false);
+    }
+
+    /// Log in to a different account and re-resolve every list entry without unsynced changes against it.
+    ///
+    /// Entries that are not on the new account's list are reset.
+    pub fn switch_account_async(&self, login: RemoteLogin) {
+        self.spawn_login(login, true);
+    }
+
+    fn spawn_login(&self, login: RemoteLogin, reset_missing: bool) {
+        let shared_state = self.clone();
+
+        task::spawn_blocking(move || match login {
+            RemoteLogin::AniList(username, token) => {
+                let user = UserInfo::new(RemoteType::AniList, &username);
+
+                let activity = {
+                    let mut state = shared_state.lock();
+                    let state = state.get_mut();
+
+                    let activity = state
+                        .log
+                        .start_activity(Text::LoggingInActivity.format(&[&username]));
+
+                    state.remote = RemoteStatus::LoggingIn(username);
+                    activity
+                };
+
+                let remote = match Auth::retrieve(token) {
+                    Ok(auth) => {
+                        let rounding = shared_state.lock().get_mut().config.score_rounding;
+                        let auth = auth.with_score_rounding(rounding);
+
+                        shared_state.lock().get_mut().failed_logins.remove(&user);
+
+                        let anilist = AniList::Authenticated(auth);
+                        shared_state.refresh_list_entries(&anilist, reset_missing);
+                        RemoteStatus::LoggedIn(Arc::new(anilist.into()))
+                    }
+                    Err(err) => {
+                        let mut state = shared_state.lock();
+                        let state = state.get_mut();
+
+                        state.failed_logins.insert(user);
+                        state.push_error(&err.into());
+
+                        RemoteStatus::LoggedIn(Remote::offline().into())
+                    }
+                };
+
+                let mut state = shared_state.lock();
+                let state = state.get_mut();
+
+                state.remote = remote;
+                state.log.finish_activity(activity);
+            }
+        });
+    }
+
+    /// Replace the list entry of every series without unsynced changes with the one from `remote`.
+    ///
+    /// If `reset_missing` is true, series that are not on the list of `remote` will have their entry reset.
+    ///
+    /// All entries are requested at once, without holding the state lock.
+    fn refresh_list_entries<R>(&self, remote: &R, reset_missing: bool)
+    where
+        R: RemoteService,
+    {
+        let ids = {
+            let state = self.lock();
+
+            state
+                .series
+                .iter()
+                .filter_map(LoadedSeries::data)
+                .filter(|data| !data.config.local_only && !data.entry.needs_sync())
+                .map(|data| data.info.id as SeriesID)
+                .collect::<Vec<_>>()
+        };
+
+        if ids.is_empty() {
+            return;
+        }
+
+        let activity = self
+            .lock()
+            .get_mut()
+            .log
+            .start_activity(Text::SyncingActivity.get());
+
+        let result = remote.get_list_entries(&ids);
+
+        let mut state = self.lock();
+        let state = state.get_mut();
+
+        state.log.finish_activity(activity);
+
+        let mut entries = match result {
+            Ok(entries) => entries
+                .into_iter()
+                .map(|entry| {
+                    let entry = SeriesEntry::from(entry);
+                    (entry.id(), entry)
+                })
+                .collect::<HashMap<_, _>>(),
+            Err(err) => return state.push_error(&err.into()),
+        };
+
+        let series = state
+            .series
+            .iter_mut()
+            .filter_map(LoadedSeries::data_mut)
+            .filter(|data| !data.config.local_only && !data.entry.needs_sync());
+
+        for data in series {
+            data.entry = match entries.remove(&data.info.id) {
+                Some(entry) => entry,
+                None if reset_missing => SeriesEntry::from(data.info.id),
+                None => continue,
+            };
+
+            if let Err(err) = data.save(&state.db) {
+                state.log.push_error(&err);
+            }
+        }
+    }
+
+    /// Run `run` in the background as a task that can be followed and cancelled from the tasks panel.
+    ///
+    /// `run` returns a summary of what it did, which is logged once it finishes.
+    pub fn spawn_task<F>(&self, state: &mut UIState, label: String, run: F)
+    where
+        F: FnOnce(&TaskContext) -> Result<String> + Send + 'static,
+    {
+        let (id, cancelled) = state.tasks.start(label.clone(), &mut state.log);
+
+        let context = TaskContext {
+            id,
+            cancelled,
+            state: self.clone(),
+        };
+
+        task::spawn_blocking(move || {
+            let result = run(&context);
+
+            let mut state = context.state.lock();
+            let state = state.get_mut();
+
+            let status = match result {
+                Ok(summary) if context.is_cancelled() => TaskStatus::Cancelled(summary),
+                Ok(summary) => TaskStatus::Finished(summary),
+                Err(err) => {
+                    state.push_error(&err);
+                    TaskStatus::Failed(format!("{:#}", err))
+                }
+            };
+
+            let message = match &status {
+                TaskStatus::Finished(summary) => Text::TaskFinished.format(&[&label, summary]),
+                TaskStatus::Cancelled(summary) => Text::TaskCancelled.format(&[&label, summary]),
+                TaskStatus::Running | TaskStatus::Failed(_) => String::new(),
+            };
+
+            state.tasks.finish(id, status, &mut state.log);
+
+            if !message.is_empty() {
+                state.log.push(LogKind::Info, message);
+            }
+        });
+    }
+
+    /// Send the list entry of every series with unsynced changes to the remote service in the background.
+    ///
+    /// Series that failed to sync too many times in a row are skipped, like when syncing from the command line. Each
+    /// entry is sent without holding the state lock, so the TUI stays usable while they're being synced.
+    pub fn spawn_sync_all(&self, state: &mut UIState) -> Result<()> {
+        let remote = state.remote.get_logged_in_shared()?;
+
+        if remote.is_offline() {
+            return Err(anyhow!("must be online to sync"));
+        }
+
+        if PrivateSession::is_active() {
+            return Err(anyhow!(
+                "a private session is active\nend it with the private off command to sync"
+            ));
+        }
+
+        let ids = state
+            .series
+            .iter()
+            .filter_map(LoadedSeries::data)
+            .filter(|data| !data.config.local_only && data.entry.needs_sync())
+            .map(|data| data.info.id)
+            .collect::<Vec<_>>();
+
+        if ids.is_empty() {
+            return Err(anyhow!("no series have unsynced changes"));
+        }
+
+        self.spawn_task(state, Text::SyncAllTask.get().into(), move |task| {
+            let mut synced = 0;
+
+            for (i, &id) in ids.iter().enumerate() {
+                if task.is_cancelled() {
+                    break;
+                }
+
+                task.set_progress(i, ids.len());
+
+                let (unsent, account, episodes) = {
+                    let mut state = task.state.lock();
+                    let state = state.get_mut();
+                    let db = &state.db;
+
+                    if PrivateSession::is_active() {
+                        break;
+                    }
+
+                    if SyncFailure::load(db, id)?.map_or(false, |failure| failure.should_skip()) {
+                        continue;
+                    }
+
+                    let data = state
+                        .series
+                        .iter()
+                        .filter_map(LoadedSeries::data)
+                        .find(|data| data.info.id == id);
+
+                    // The series may have been deleted or synced since the task started
+                    let data = match data {
+                        Some(data) if !data.config.local_only && data.entry.needs_sync() => data,
+                        _ => continue,
+                    };
+
+                    data.entry.begin_push(db, db.account())?;
+
+                    (
+                        data.entry.clone(),
+                        db.account().to_string(),
+                        data.info.total_episodes(),
+                    )
+                };
+
+                let mut synced_entry = unsent.clone();
+                let sent = synced_entry.send(&remote, episodes);
+
+                let mut state = task.state.lock();
+                let state = state.get_mut();
+                let db = &state.db;
+
+                let sent = match sent {
+                    Ok(sent) => sent,
+                    Err(err) => {
+                        SyncFailure::record(db, id, &format!("{:#}", err))?;
+                        state.push_error(&err);
+                        continue;
+                    }
+                };
+
+                synced_entry.finish_push(db, &account, &remote, sent)?;
+
+                let data = state
+                    .series
+                    .iter_mut()
+                    .filter_map(LoadedSeries::data_mut)
+                    .find(|data| data.info.id == id);
+
+                // Saving the series removes it from the sync journal. If it changed while it was being sent, its
+                // newer changes are kept and still need to be synced
+                if let Some(data) = data.filter(|_| db.account() == account) {
+                    if data.entry == unsent {
+                        data.entry = synced_entry;
+                    }
+
+                    if let Err(err) = data.save(db) {
+                        SyncFailure::record(db, id, &format!("{:#}", err))?;
+                        state.push_error(&err);
+                        continue;
+                    }
+                }
+
+                SyncFailure::clear(db, id)?;
+                synced += 1;
+            }
+
+            Ok(Text::SyncAllSummary.format(&[&synced, &ids.len()]))
+        });
+
+        Ok(())
+    }
+
+    /// Replace the info of every series with the latest info from the remote service in the background.
+    ///
+    /// The info is requested without holding the state lock, so the TUI stays usable while it's being refreshed.
+    /// Switch between showing every series and only the series being watched that are still airing, grouped by the
+    /// day of the week they air on.
+    ///
+    /// The airing schedule of every series being watched is looked up in the background first.
+    pub fn toggle_airing_filter(&self, state: &mut UIState) -> Result<()> {
+        if state.series_filter == SeriesFilter::Airing {
+            state.set_series_filter(SeriesFilter::All);
+            return Ok(());
+        }
 
-        let nickname = series.nickname().to_string();
+        let remote = state.remote.get_logged_in_shared()?;
 
-        self.series.push(series);
-        self.series.items_mut().sort_unstable();
+        if remote.is_offline() {
+            return Err(anyhow!("must be online to look up airing schedules"));
+        }
 
-        let selected = self
+        let ids = state
             .series
             .iter()
-            .position(|s| s.nickname() == nickname)
-            .unwrap_or(0);
+            .filter_map(LoadedSeries::data)
+            .filter(|data| matches!(data.entry.status(), Status::Watching | Status::Rewatching))
+            .map(|data| data.info.id)
+            .collect::<Vec<_>>();
 
-        self.series.set_selected(selected);
-        Ok(())
-    }
+        if ids.is_empty() {
+            return Err(anyhow!("no series are being watched"));
+        }
 
-    pub fn init_selected_series(&mut self) {
-        let selected = try_opt_ret!(self.series.selected_mut());
-        selected.try_load(&self.config, &self.db)
-    }
+        self.spawn_task(state, Text::AiringTask.get().into(), move |task| {
+            let mut episodes = HashMap::with_capacity(ids.len());
 
-    pub fn delete_selected_series(&mut self) -> Result<LoadedSeries> {
-        if !self.series.is_valid_index() {
-            return Err(anyhow!("must select series to delete"));
-        }
+            for (i, &id) in ids.iter().enumerate() {
+                if task.is_cancelled() {
+                    break;
+                }
 
-        let index = self.series.index();
-        let series = self.series.items_mut().remove(index);
+                task.set_progress(i, ids.len());
 
-        self.series.update_bounds();
-        // Since we changed our selected series, we need to make sure the new one is initialized
-        self.init_selected_series();
+                match remote.next_airing_episode(id as SeriesID) {
+                    Ok(Some(episode)) => {
+                        episodes.insert(id, episode);
+                    }
+                    Ok(None) => (),
+                    Err(err) => task.state.lock().get_mut().push_error(&err.into()),
+                }
+            }
 
-        series.config().delete(&self.db)?;
-        Ok(series)
-    }
+            let found = episodes.len();
+            let summary = Text::AiringSummary.format(&[&found, &ids.len()]);
 
-    async fn start_next_series_episode(&mut self) -> Result<(Child, ProgressTime)> {
-        let series = match self.series.get_valid_sel_series_mut() {
-            Some(series) => series,
-            None => return Err(anyhow!("no series selected")),
-        };
+            if task.is_cancelled() {
+                return Ok(summary);
+            }
 
-        let is_diff_series = self.last_watched.set(&series.data.config.nickname);
+            if found == 0 {
+                return Err(anyhow!("none of the series being watched are airing"));
+            }
 
-        if is_diff_series {
-            self.last_watched
-                .save()
-                .context("setting last watched series")?;
-        }
+            let mut state = task.state.lock();
+            let state = state.get_mut();
 
-        let remote = self.remote.get_logged_in()?;
+            state.airing = AiringSchedule::new(episodes);
+            state.set_series_filter(SeriesFilter::Airing);
 
-        series
-            .begin_watching(remote, &self.config, &self.db)
-            .context("updating series status")?;
+            Ok(summary)
+        });
 
-        let next_ep = series.data.entry.watched_episodes() + 1;
+        Ok(())
+    }
 
-        let child = series
-            .play_episode(next_ep as u32, &self.config)
-            .context("playing episode")?;
+    /// Fetch what's needed once `episode` of the series with the specified `id` finishes in the background, so
+    /// counting it is instant when the player exits.
+    ///
+    /// The series info and episode titles are refreshed and its folder is scanned for the next episode. When `episode`
+    /// is the last one, the sequel shown in the summary of the series is looked up as well. Nothing is sent to the
+    /// remote service if the same series was prefetched recently.
+    pub fn spawn_prefetch(&self, id: i32, episode: u32) {
+        let shared_state = self.clone();
 
-        let progress_time = series.data.next_watch_progress_time(&self.config);
+        task::spawn(async move {
+            time::sleep(PREFETCH_DELAY).await;
 
-        Ok((child, progress_time))
+            task::spawn_blocking(move || shared_state.prefetch(id, episode))
+                .await
+                .ok();
+        });
     }
 
-    pub async fn play_next_series_episode(&mut self, shared_state: &SharedState) -> Result<()> {
-        let (ep_process, progress_time) = self.start_next_series_episode().await?;
+    fn prefetch(&self, id: i32, episode: u32) {
+        let remote = {
+            let mut state = self.lock();
+            let state = state.get_mut();
 
-        self.events
-            .send(StateEvent::StartedEpisode(progress_time))
-            .ok();
+            if !state.watching.contains_key(&id) {
+                return;
+            }
 
-        self.input_state = InputState::Locked;
+            let recent = state.prefetched.get(&id).map_or(false, |prefetched| {
+                prefetched.fetched_at.elapsed() < PREFETCH_INTERVAL
+            });
 
-        let shared_state = shared_state.clone();
+            match state.remote.get_logged_in_shared() {
+                Ok(remote) if !recent && !remote.is_offline() && !state.config.low_data => {
+                    Some(remote)
+                }
+                _ => None,
+            }
+        };
 
-        task::spawn(async move {
-            let result = shared_state
-                .track_episode_finish(ep_process, progress_time)
-                .await;
+        // Requests are made without holding the state, so the TUI stays responsive while they run
+        let fetched = remote.map(|remote| Prefetched::fetch(id, episode, &remote));
 
-            let mut state = shared_state.lock();
-            let state = state.get_mut();
+        let mut state = self.lock();
+        let state = state.get_mut();
 
-            if let Err(err) = result {
+        let series = state
+            .series
+            .iter_mut()
+            .filter_map(LoadedSeries::complete_mut)
+            .find(|series| series.data.info.id == id);
+
+        let series = try_opt_ret!(series);
+
+        // Errors are ignored since the episode will be found once it's played, or by the next check for new episodes
+        series.rescan_episodes(&state.config).ok();
+
+        let (info, titles, prefetched) = try_opt_ret!(fetched);
+        let data = &mut series.data;
+
+        if let Some(info) = info {
+            data.info = info;
+
+            if let Err(err) = data.save(&state.db) {
                 state.log.push_error(&err);
             }
+        }
 
-            state.input_state.reset();
-            state.events.send(StateEvent::FinishedEpisode).ok();
-        });
+        if !titles.is_empty() && !data.config.local_only {
+            match titles.save(&state.db, data.config.id) {
+                Ok(()) => data.episode_titles = titles,
+                Err(err) => state.log.push_error(&err),
+            }
+        }
 
-        Ok(())
+        state.prefetched.insert(id, prefetched);
     }
-}
 
-pub type ReactiveState = Reactive<UIState>;
+    /// Look for a newer release of the program, and show that one is available in the log's title if it is.
+    ///
+    /// Nothing is shown if the check fails, since it isn't something the user asked for.
+    pub fn spawn_update_check(&self) {
+        let shared_state = self.clone();
 
-#[derive(Clone)]
-pub struct SharedState(ArcMutex<ReactiveState>);
+        task::spawn_blocking(move || {
+            let release = try_opt_ret!(Release::check().ok().flatten());
 
-impl SharedState {
-    pub fn new(state: ReactiveState) -> Self {
-        Self(arc_mutex(state))
+            let mut state = shared_state.lock();
+            let state = state.get_mut();
+
+            state.log.set_update(Some(release.version.clone()));
+            state.update = Some(release);
+        });
     }
 
-    pub fn login_to_remote_async(&self, login: RemoteLogin) {
-        let shared_state = self.clone();
+    pub fn spawn_refresh_all_info(&self, state: &mut UIState) -> Result<()> {
+        let remote = state.remote.get_logged_in_shared()?;
 
-        task::spawn_blocking(move || match login {
-            RemoteLogin::AniList(username, token) => {
-                {
-                    let mut state = shared_state.lock();
-                    state.get_mut().remote = RemoteStatus::LoggingIn(username);
+        if remote.is_offline() {
+            return Err(anyhow!("must be online to refresh series info"));
+        }
+
+        let ids = state
+            .series
+            .iter()
+            .filter_map(LoadedSeries::id)
+            .collect::<Vec<_>>();
+
+        if ids.is_empty() {
+            return Err(anyhow!("no series have been added"));
+        }
+
+        self.spawn_task(state, Text::RefreshAllTask.get().into(), move |task| {
+            let mut refreshed = 0;
+
+            for (i, &id) in ids.iter().enumerate() {
+                if task.is_cancelled() {
+                    break;
                 }
 
-                let auth = Auth::retrieve(token);
-                let mut state = shared_state.lock();
+                task.set_progress(i, ids.len());
+
+                let info = SeriesInfo::from_remote_by_id(id as SeriesID, &remote);
+                let titles = remote.episode_titles(id as SeriesID);
+
+                let mut state = task.state.lock();
                 let state = state.get_mut();
 
-                let remote = match auth {
-                    Ok(auth) => {
-                        let anilist = AniList::Authenticated(auth);
-                        RemoteStatus::LoggedIn(anilist.into())
-                    }
+                let info = match info {
+                    Ok(info) => info,
                     Err(err) => {
-                        state.log.push_error(&err.into());
-                        RemoteStatus::LoggedIn(Remote::offline())
+                        state.push_error(&err);
+                        continue;
                     }
                 };
 
-                state.remote = remote;
+                let data = state
+                    .series
+                    .iter_mut()
+                    .filter_map(LoadedSeries::data_mut)
+                    .find(|data| data.info.id == id);
+
+                let data = match data {
+                    Some(data) => data,
+                    None => continue,
+                };
+
+                data.info = info;
+                data.save(&state.db)?;
+
+                // Titles are optional, so the info is still refreshed without them
+                match titles.map(EpisodeTitles::from) {
+                    Ok(titles) if !titles.is_empty() => {
+                        titles.save(&state.db, data.config.id)?;
+                        data.episode_titles = titles;
+                    }
+                    Ok(_) => (),
+                    Err(err) => state.log.push_error(&err.into()),
+                }
+
+                refreshed += 1;
             }
+
+            Ok(Text::RefreshAllSummary.format(&[&refreshed, &ids.len()]))
         });
+
+        Ok(())
     }
 
     async fn track_episode_finish(
         &self,
+        id: i32,
         mut ep_process: Child,
-        progress_time: ProgressTime,
+        stop: &Notify,
+        timer: PlaybackTimer,
     ) -> Result<()> {
-        ep_process
-            .wait()
-            .await
-            .context("waiting for episode to finish")?;
+        let status = {
+            tokio::pin! {
+                let exited = ep_process.wait().fuse();
+                let stopped = stop.notified().fuse();
+            }
+
+            select! {
+                status = exited => status.map(Some),
+                () = stopped => Ok(None),
+            }
+        };
+
+        // A status of None means the episode was stopped
+        let status = if let Ok(None) = status {
+            ep_process.kill().await.map(|()| None)
+        } else {
+            status
+        };
 
         let mut state = self.lock();
         let state = state.get_mut();
 
-        state.input_state.reset();
+        let watch = state
+            .watching
+            .remove(&id)
+            .ok_or_else(|| anyhow!("episode being played was not tracked"))?;
 
-        if Utc::now() < progress_time {
-            return Ok(());
+        let status = if let Some(status) = status.context("waiting for episode to finish")? {
+            status
+        } else {
+            state.log.push(
+                LogKind::Info,
+                Text::StoppedPlaying.format(&[&watch.episode, &watch.title]),
+            );
+
+            return timer.cancel(&state.db).context("removing active playback");
+        };
+
+        if watch.force_completed {
+            return timer
+                .finish(&state.db, status.code(), true)?
+                .save(&state.db)
+                .map(|_| ())
+                .context("saving playback session");
         }
 
-        let series = if let Some(series) = state.series.get_valid_sel_series_mut() {
-            series
-        } else {
+        let exited_normally = status.success();
+        let counted = exited_normally && Utc::now() >= watch.progress_time;
+        let session = timer
+            .finish(&state.db, status.code(), counted)
+            .context("removing active playback")?;
+
+        session.save(&state.db).context("saving playback session")?;
+
+        if !exited_normally {
+            let reason = match status.code() {
+                Some(code) => Text::PlayerExitedWith.format(&[&code]),
+                None => Text::PlayerKilled.get().into(),
+            };
+
+            let message = Text::ConfirmCount.format(&[&reason, &session.episode, &watch.title]);
+            state.queue_episode_confirmation(id, message);
             return Ok(());
-        };
+        }
 
-        let remote = state.remote.get_logged_in()?;
+        if !counted {
+            state.log.push(
+                LogKind::Warning,
+                Text::PlayedTooShort.format(&[&session.episode, &watch.title, &session.seconds]),
+            );
 
-        series
-            .episode_completed(remote, &state.config, &state.db)
-            .context("marking episode as completed")
+            return Ok(());
+        }
+
+        state.complete_episode(id)
     }
 
     #[inline(always)]
@@ -320,9 +2730,22 @@ impl SharedState {
 #[derive(Clone, Copy)]
 pub enum InputState {
     Idle,
-    Locked,
     FocusedOnMainPanel,
     EnteringCommand,
+    /// Waiting for the user to decide if an episode of the series with the contained ID should be counted after the player exited abnormally.
+    ConfirmingEpisode(i32),
+    /// Waiting for the user to decide if the player of the series with the contained ID should be closed.
+    ConfirmingStop(i32),
+    /// Waiting for the user to decide if the program should exit while work is still pending.
+    ConfirmingExit,
+    /// Waiting for the next key of a key chord.
+    EnteringChord,
+    /// Waiting for the user to decide if the series with the contained ID should use the folder it was likely moved to.
+    ConfirmingMoved(i32),
+    /// Waiting for the user to decide if the rows left behind by series that no longer exist should be removed.
+    ConfirmingOrphans,
+    /// Waiting for the user to decide if the oldest conflicting copy of the database should be merged.
+    ConfirmingConflict,
 }
 
 impl InputState {
@@ -346,10 +2769,339 @@ impl PartialEq for InputState {
 
 pub type ProgressTime = DateTime<Utc>;
 
+/// An episode that is being played.
+pub struct ActiveWatch {
+    pub title: SharedStr,
+    pub episode: u32,
+    /// Notified when the player should be closed.
+    pub stop: Arc<Notify>,
+    /// When the episode will be counted as watched once the player exits.
+    pub progress_time: ProgressTime,
+    /// Set when the episode was marked as watched before the player exited.
+    pub force_completed: bool,
+    pub started_at: DateTime<Utc>,
+    /// The ID of the player's process, which is only missing if it exited right away.
+    pub pid: Option<u32>,
+}
+
+/// What was fetched for a series while one of its episodes was being played.
+pub struct Prefetched {
+    fetched_at: Instant,
+    /// The ID and title of the direct sequel of the series, if the episode being played was its last one.
+    pub sequel: Option<(SeriesID, String)>,
+}
+
+impl Prefetched {
+    /// Fetch the info and episode titles of the series with the specified `id` from `remote`, along with its sequel if
+    /// `episode` is its last one.
+    ///
+    /// Anything that fails to be fetched is left out, since it will just be fetched again when it's needed.
+    fn fetch(id: i32, episode: u32, remote: &Remote) -> (Option<SeriesInfo>, EpisodeTitles, Self) {
+        let info = remote.search_info_by_id(id as SeriesID).ok();
+
+        let titles = remote
+            .episode_titles(id as SeriesID)
+            .map(EpisodeTitles::from)
+            .unwrap_or_default();
+
+        let is_last = info
+            .as_ref()
+            .map_or(false, |info| info.episodes > 0 && episode >= info.episodes);
+
+        let sequel = info
+            .as_ref()
+            .filter(|_| is_last)
+            .and_then(|info| info.direct_sequel())
+            .and_then(|sequel| {
+                let sequel_info = remote.search_info_by_id(sequel.id).ok()?;
+                Some((sequel.id, sequel_info.title.preferred))
+            });
+
+        let prefetched = Self {
+            fetched_at: Instant::now(),
+            sequel,
+        };
+
+        (info.map(Into::into), titles, prefetched)
+    }
+}
+
+/// Identifies a task started with `SharedState::spawn_task`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TaskId(u32);
+
+/// An operation that runs in the background, such as syncing every series.
+pub struct BackgroundTask {
+    id: TaskId,
+    pub label: String,
+    /// The number of items that have been processed, and the total number of items.
+    pub progress: Option<(usize, usize)>,
+    pub status: TaskStatus,
+    cancelled: Arc<AtomicBool>,
+    activity: ActivityId,
+}
+
+impl BackgroundTask {
+    #[inline(always)]
+    pub fn is_running(&self) -> bool {
+        matches!(self.status, TaskStatus::Running)
+    }
+
+    /// Returns true if the task was asked to stop, but hasn't stopped yet.
+    pub fn is_cancelling(&self) -> bool {
+        self.is_running() && self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Ask the task to stop.
+    ///
+    /// The task stops once it finishes the item it's currently working on.
+    pub fn cancel(&self) {
+        if self.is_running() {
+            self.cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+pub enum TaskStatus {
+    Running,
+    /// The task finished, with a summary of what it did.
+    Finished(String),
+    /// The task stopped early after being cancelled, with a summary of what it did before then.
+    Cancelled(String),
+    /// The task stopped because of the contained error.
+    Failed(String),
+}
+
+/// Tasks that are running in the background or finished recently, in the order they were started.
+pub struct Tasks {
+    items: Vec<BackgroundTask>,
+    next_id: u32,
+    /// The maximum number of finished tasks to keep, so their results can still be viewed.
+    max_finished: usize,
+}
+
+impl Tasks {
+    fn new(max_finished: usize) -> Self {
+        Self {
+            items: Vec::new(),
+            next_id: 0,
+            max_finished,
+        }
+    }
+
+    fn start(&mut self, label: String, log: &mut Log) -> (TaskId, Arc<AtomicBool>) {
+        let id = TaskId(self.next_id);
+        self.next_id = self.next_id.wrapping_add(1);
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        self.items.push(BackgroundTask {
+            id,
+            activity: log.start_activity(label.clone()),
+            label,
+            progress: None,
+            status: TaskStatus::Running,
+            cancelled: Arc::clone(&cancelled),
+        });
+
+        (id, cancelled)
+    }
+
+    fn get_mut(&mut self, id: TaskId) -> Option<&mut BackgroundTask> {
+        self.items.iter_mut().find(|task| task.id == id)
+    }
+
+    /// Set the `status` of the task with the specified `id` once it stops running.
+    fn finish(&mut self, id: TaskId, status: TaskStatus, log: &mut Log) {
+        let task = try_opt_ret!(self.get_mut(id));
+
+        log.finish_activity(task.activity);
+        task.status = status;
+
+        self.remove_oldest_finished();
+    }
+
+    fn remove_oldest_finished(&mut self) {
+        let finished = self.items.iter().filter(|task| !task.is_running()).count();
+        let mut excess = finished.saturating_sub(self.max_finished);
+
+        self.items.retain(|task| {
+            if excess == 0 || task.is_running() {
+                return true;
+            }
+
+            excess -= 1;
+            false
+        });
+    }
+
+    /// Remove every task that isn't running anymore.
+    pub fn clear_finished(&mut self) {
+        self.items.retain(BackgroundTask::is_running);
+    }
+
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&BackgroundTask> {
+        self.items.get(index)
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &BackgroundTask> {
+        self.items.iter()
+    }
+}
+
+/// Given to a background task so it can report its progress and check if it was cancelled.
+pub struct TaskContext {
+    id: TaskId,
+    cancelled: Arc<AtomicBool>,
+    pub state: SharedState,
+}
+
+impl TaskContext {
+    #[inline(always)]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Set the number of items the task has processed out of `total`.
+    pub fn set_progress(&self, done: usize, total: usize) {
+        let mut state = self.state.lock();
+
+        if let Some(task) = state.get_mut().tasks.get_mut(self.id) {
+            task.progress = Some((done, total));
+        }
+    }
+}
+
+/// The order series are shown in the series list.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SeriesSort {
+    /// Sorted by nickname.
+    Name,
+    /// Sorted from the highest list entry priority to the lowest, and then by nickname.
+    Priority,
+}
+
+impl TryFrom<&str> for SeriesSort {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "name" => Ok(Self::Name),
+            "priority" => Ok(Self::Priority),
+            _ => Err(anyhow!("unknown sort: {}", value)),
+        }
+    }
+}
+
+/// Which series are shown in the series list.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SeriesFilter {
+    All,
+    /// Only completed series that are planned to be rewatched.
+    PlannedRewatch,
+    /// Only series being watched that are still airing, grouped by the day of the week they air on.
+    Airing,
+    /// Only series in a collection the user made.
+    Collection,
+}
+
+impl SeriesFilter {
+    pub fn matches(
+        self,
+        series: &LoadedSeries,
+        airing: &AiringSchedule,
+        collection: &ShownCollection,
+        franchises: &Franchises,
+    ) -> bool {
+        match self {
+            Self::All => franchises.is_shown(series),
+            Self::PlannedRewatch => series.config().planned_rewatch,
+            Self::Airing => series.id().map_or(false, |id| airing.contains(id)),
+            Self::Collection => collection.contains(series.config().id),
+        }
+    }
+
+    fn matches_selected(
+        self,
+        series: &WrappedSeriesSelection,
+        airing: &AiringSchedule,
+        collection: &ShownCollection,
+        franchises: &Franchises,
+    ) -> bool {
+        series.selected().map_or(false, |series| {
+            self.matches(series, airing, collection, franchises)
+        })
+    }
+}
+
+/// A collection of series, as of the last time the series list switched to it.
+#[derive(Default)]
+pub struct ShownCollection {
+    pub name: String,
+    /// The ID of every series in the collection.
+    members: HashSet<i32>,
+}
+
+impl ShownCollection {
+    fn load(db: &Database, name: String) -> diesel::QueryResult<Self> {
+        let members = SeriesCollection::members(db, &name)?.into_iter().collect();
+        Ok(Self { name, members })
+    }
+
+    #[inline(always)]
+    fn contains(&self, id: i32) -> bool {
+        self.members.contains(&id)
+    }
+}
+
+/// A series whose folder wasn't found, along with the folder it was most likely moved or renamed to.
+pub struct MovedSeries {
+    pub id: i32,
+    pub path: SeriesPath,
+}
+
+/// An episode that wasn't counted, along with the question to ask about whether it should be.
+pub struct UnconfirmedEpisode {
+    pub id: i32,
+    pub message: String,
+}
+
+/// The keys of a key chord that have been pressed so far.
+pub struct PendingChord {
+    keys: SmallVec<[Key; 3]>,
+    started: Instant,
+}
+
+impl PendingChord {
+    fn new() -> Self {
+        Self {
+            keys: SmallVec::new(),
+            started: Instant::now(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn keys(&self) -> &[Key] {
+        &self.keys
+    }
+}
+
+/// Episode events, with the ID of the series the episode belongs to.
 #[derive(Debug, Clone)]
 pub enum StateEvent {
-    StartedEpisode(ProgressTime),
-    FinishedEpisode,
+    StartedEpisode(i32, ProgressTime),
+    FinishedEpisode(i32),
 }
 
 pub struct Reactive<T> {
@@ -391,6 +3143,8 @@ pub enum UIEvent {
     Key(Key),
     StateChange,
     Resize,
+    /// A request sent with `anup play-next` or similar, which must be responded to once handled.
+    Request(ipc::Request, ipc::Connection),
 }
 
 pub enum UIErrorKind {
@@ -403,16 +3157,24 @@ pub type UIEventError<T> = std::result::Result<T, UIErrorKind>;
 pub struct UIEvents {
     reader: EventStream,
     resize_event_stream: Signal,
+    /// Sent when the terminal is closed.
+    hangup_stream: Signal,
+    /// Receives requests from other instances of the program, if no other TUI is already receiving them.
+    ipc_server: Option<ipc::Server>,
 }
 
 impl UIEvents {
-    pub fn new() -> Result<Self> {
+    pub fn new(ipc_server: Option<ipc::Server>) -> Result<Self> {
         let resize_event_stream =
             signal(SignalKind::window_change()).context("SIGWINCH signal capture failed")?;
 
+        let hangup_stream = signal(SignalKind::hangup()).context("SIGHUP signal capture failed")?;
+
         Ok(Self {
             reader: EventStream::new(),
             resize_event_stream,
+            hangup_stream,
+            ipc_server,
         })
     }
 
@@ -421,19 +3183,37 @@ impl UIEvents {
         tokio::pin! {
             let state_change = state_change.notified().fuse();
             let window_resize = self.resize_event_stream.recv().fuse();
+            let hangup = self.hangup_stream.recv().fuse();
         }
 
         let mut next_event = self.reader.next().fuse();
 
+        let ipc_server = &self.ipc_server;
+        let ipc_request = async move {
+            match ipc_server {
+                Some(server) => server.accept().await,
+                None => future::pending().await,
+            }
+        }
+        .fuse();
+
+        tokio::pin!(ipc_request);
+
         select! {
             _ = state_change => Ok(Some(UIEvent::StateChange)),
             _ = window_resize => Ok(Some(UIEvent::Resize)),
+            // Exiting normally hands anything still in progress over to the daemon
+            _ = hangup => Err(UIErrorKind::ExitRequest),
             event = next_event => match event {
                 Some(Ok(Event::Key(key))) => Ok(Some(UIEvent::Key(Key::new(key)))),
+                // Not every terminal sends SIGWINCH, such as those on Windows
+                Some(Ok(Event::Resize(_, _))) => Ok(Some(UIEvent::Resize)),
                 Some(Ok(_)) => Ok(None),
                 Some(Err(err)) => Err(UIErrorKind::Other(err.into())),
                 None => Err(UIErrorKind::ExitRequest),
-            }
+            },
+            // Requests that can't be read are ignored, since there's nobody to report them to
+            request = ipc_request => Ok(request.ok().map(|(request, conn)| UIEvent::Request(request, conn))),
         }
     }
 }
@@ -0,0 +1,160 @@
+use crate::tui::locale::Text;
+
+/// How much each heuristic counts towards the rank of a series. Having the next episode ready matters the most,
+/// since a suggestion that can't be played right away isn't very useful.
+const READY_WEIGHT: f32 = 3.0;
+const TIME_LEFT_WEIGHT: f32 = 1.0;
+const FINALE_WEIGHT: f32 = 1.5;
+const IDLE_WEIGHT: f32 = 1.0;
+
+/// The number of days without watching a series after which it counts as fully neglected.
+const MAX_IDLE_DAYS: f32 = 30.0;
+
+/// The weighted score another heuristic needs for it to be given as the reason a ready series was suggested,
+/// since most suggestions are ready to be played.
+const MIN_REASON_SCORE: f32 = 0.5;
+
+/// A series that could be watched next, along with everything it's ranked by.
+#[derive(Clone, Debug)]
+pub struct Candidate {
+    pub id: i32,
+    /// Whether or not the next episode of the series is on disk.
+    pub next_on_disk: bool,
+    /// The time it would take to finish the series, if its number of episodes is known.
+    pub mins_left: Option<u32>,
+    /// The number of episodes that still have to air before the finale airs, if the series is airing.
+    pub eps_until_finale: Option<u32>,
+    /// The number of days since an episode of the series was last watched, if one ever was.
+    pub idle_days: Option<f32>,
+}
+
+impl Candidate {
+    /// Returns the score of each heuristic, weighted by how much it counts towards the rank of the series.
+    fn scores(&self) -> [(Reason, f32); 4] {
+        let ready = if self.next_on_disk { 1.0 } else { 0.0 };
+        let time_left = self
+            .mins_left
+            .map_or(0.0, |mins| 1.0 / (1.0 + mins as f32 / 60.0));
+
+        let finale = self
+            .eps_until_finale
+            .map_or(0.0, |eps| 1.0 / (1.0 + eps as f32));
+
+        let idle = self
+            .idle_days
+            .map_or(0.0, |days| (days / MAX_IDLE_DAYS).clamp(0.0, 1.0));
+
+        [
+            (Reason::Ready, ready * READY_WEIGHT),
+            (Reason::AlmostDone, time_left * TIME_LEFT_WEIGHT),
+            (Reason::FinaleAiring, finale * FINALE_WEIGHT),
+            (Reason::Neglected, idle * IDLE_WEIGHT),
+        ]
+    }
+
+    fn score(&self) -> f32 {
+        self.scores().iter().map(|(_, score)| score).sum()
+    }
+
+    /// Returns the heuristic other than having the next episode ready that counts the most towards the rank of
+    /// the series, unless it barely counts and the next episode is ready.
+    fn reason(&self) -> Reason {
+        let (reason, score) = self
+            .scores()
+            .iter()
+            .copied()
+            .filter(|&(reason, _)| reason != Reason::Ready)
+            .max_by(|(_, x), (_, y)| x.total_cmp(y))
+            .unwrap_or((Reason::Ready, 0.0));
+
+        if self.next_on_disk && score < MIN_REASON_SCORE {
+            Reason::Ready
+        } else {
+            reason
+        }
+    }
+}
+
+/// The main reason a series was suggested.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Reason {
+    Ready,
+    AlmostDone,
+    FinaleAiring,
+    Neglected,
+}
+
+impl Reason {
+    pub fn text(self) -> Text {
+        match self {
+            Self::Ready => Text::SuggestReady,
+            Self::AlmostDone => Text::SuggestAlmostDone,
+            Self::FinaleAiring => Text::SuggestFinaleAiring,
+            Self::Neglected => Text::SuggestNeglected,
+        }
+    }
+}
+
+/// Returns the ID of the best series to watch next out of `candidates`, and the main reason it was picked.
+pub fn best(candidates: &[Candidate]) -> Option<(i32, Reason)> {
+    candidates
+        .iter()
+        .max_by(|x, y| x.score().total_cmp(&y.score()))
+        .map(|candidate| (candidate.id, candidate.reason()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: i32, next_on_disk: bool, mins_left: u32) -> Candidate {
+        Candidate {
+            id,
+            next_on_disk,
+            mins_left: Some(mins_left),
+            eps_until_finale: None,
+            idle_days: None,
+        }
+    }
+
+    #[test]
+    fn suggestion_ranking() {
+        assert_eq!(best(&[]), None);
+
+        let ready = candidate(1, true, 12 * 24);
+        let almost_done = candidate(2, false, 24);
+
+        assert_eq!(
+            best(&[almost_done.clone(), ready.clone()]),
+            Some((1, Reason::Ready))
+        );
+
+        let short = candidate(3, true, 24);
+        assert_eq!(best(&[ready.clone(), short]), Some((3, Reason::AlmostDone)));
+
+        let finale = Candidate {
+            id: 4,
+            eps_until_finale: Some(0),
+            ..ready.clone()
+        };
+
+        let neglected = Candidate {
+            id: 5,
+            idle_days: Some(60.0),
+            ..ready
+        };
+
+        assert_eq!(
+            best(&[neglected.clone(), finale]),
+            Some((4, Reason::FinaleAiring))
+        );
+
+        let neglected = Candidate {
+            next_on_disk: false,
+            ..neglected
+        };
+
+        assert_eq!(best(&[neglected]), Some((5, Reason::Neglected)));
+        assert_eq!(best(&[almost_done]), Some((2, Reason::AlmostDone)));
+    }
+}
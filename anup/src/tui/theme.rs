@@ -0,0 +1,175 @@
+use once_cell::sync::OnceCell;
+use serde_derive::{Deserialize, Serialize};
+use tui::style::{Color, Modifier, Style};
+
+static PALETTE: OnceCell<Palette> = OnceCell::new();
+
+/// The set of colors the TUI is drawn with.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub enum Palette {
+    /// Red for problems and green for things that are done.
+    #[default]
+    #[serde(alias = "default")]
+    Default,
+    /// Orange and light blue instead of red and green, which are hard to tell apart with red-green color blindness.
+    #[serde(alias = "deuteranopia")]
+    Deuteranopia,
+    /// No colors at all. Text is bolded, underlined, or reversed instead.
+    #[serde(alias = "monochrome")]
+    Monochrome,
+}
+
+impl Palette {
+    fn current() -> Self {
+        PALETTE.get().copied().unwrap_or_default()
+    }
+}
+
+/// Set the palette the TUI is drawn with.
+///
+/// This can only be done once, and the default palette is used until it is.
+pub fn init(palette: Palette) {
+    PALETTE.set(palette).ok();
+}
+
+/// What a piece of the TUI is meant to stand out as.
+#[derive(Copy, Clone, Debug)]
+pub enum Tone {
+    /// Something that is selected or in progress.
+    Accent,
+    /// Something that is finished or succeeded.
+    Good,
+    /// Something that needs attention.
+    Warning,
+    /// Something that failed or will be destroyed.
+    Bad,
+    /// Something that failed and is selected.
+    BadSelected,
+    /// Something that is disabled or no longer relevant.
+    Faded,
+    /// Something that is waiting to be started.
+    Neutral,
+}
+
+impl Tone {
+    fn color(self, palette: Palette) -> Option<Color> {
+        let color = match (palette, self) {
+            (Palette::Monochrome, _) => return None,
+            (_, Self::Accent) => Color::Blue,
+            (_, Self::Warning) => Color::Yellow,
+            (_, Self::Faded) => Color::DarkGray,
+            (_, Self::Neutral) => Color::Gray,
+            (Palette::Default, Self::Good) => Color::Green,
+            (Palette::Default, Self::Bad) => Color::Red,
+            (Palette::Default, Self::BadSelected) => Color::LightRed,
+            (Palette::Deuteranopia, Self::Good) => Color::LightCyan,
+            (Palette::Deuteranopia, Self::Bad) => Color::Indexed(202),
+            (Palette::Deuteranopia, Self::BadSelected) => Color::Indexed(209),
+        };
+
+        Some(color)
+    }
+
+    fn modifier(self) -> Modifier {
+        match self {
+            Self::Accent => Modifier::BOLD,
+            Self::Good => Modifier::UNDERLINED,
+            Self::Warning => Modifier::ITALIC | Modifier::UNDERLINED,
+            Self::Bad => Modifier::REVERSED,
+            Self::BadSelected => Modifier::REVERSED | Modifier::BOLD,
+            Self::Faded => Modifier::DIM,
+            Self::Neutral => Modifier::empty(),
+        }
+    }
+
+    /// Returns the style of the tone in the current palette.
+    pub fn style(self) -> Style {
+        let palette = Palette::current();
+
+        match self.color(palette) {
+            Some(color) => Style::default().fg(color),
+            None => Style::default().add_modifier(self.modifier()),
+        }
+    }
+
+    /// Returns `style` with the style of the tone added on top.
+    pub fn apply(self, style: Style) -> Style {
+        style.patch(self.style())
+    }
+
+    /// Returns the style of the tone used as a background.
+    pub fn inverted(self) -> Style {
+        let palette = Palette::current();
+
+        match self.color(palette) {
+            Some(color) => Style::default().fg(Color::Black).bg(color),
+            None => Style::default().add_modifier(Modifier::REVERSED),
+        }
+    }
+}
+
+/// Returns the style used to accent a series with the color of its cover image, given as a hex code like `#e4a15d`.
+///
+/// Returns `None` if the code is malformed or the palette has no colors.
+pub fn cover_accent(hex: &str) -> Option<Style> {
+    if Palette::current() == Palette::Monochrome {
+        return None;
+    }
+
+    parse_hex(hex).map(|color| Style::default().fg(color))
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    let hex = hex.strip_prefix('#')?;
+
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |range| u8::from_str_radix(&hex[range], 16).ok();
+    Some(Color::Rgb(channel(0..2)?, channel(2..4)?, channel(4..6)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tones_are_distinct() {
+        let tones = [
+            Tone::Accent,
+            Tone::Good,
+            Tone::Warning,
+            Tone::Bad,
+            Tone::BadSelected,
+            Tone::Faded,
+            Tone::Neutral,
+        ];
+
+        for &palette in &[Palette::Default, Palette::Deuteranopia] {
+            for (i, x) in tones.iter().enumerate() {
+                for y in &tones[i + 1..] {
+                    assert_ne!(x.color(palette), y.color(palette), "{:?}", palette);
+                }
+            }
+        }
+
+        for (i, x) in tones.iter().enumerate() {
+            assert_eq!(x.color(Palette::Monochrome), None);
+
+            for y in &tones[i + 1..] {
+                assert_ne!(x.modifier(), y.modifier());
+            }
+        }
+    }
+
+    #[test]
+    fn parse_cover_colors() {
+        assert_eq!(parse_hex("#e4a15d"), Some(Color::Rgb(0xe4, 0xa1, 0x5d)));
+        assert_eq!(parse_hex("#E4A15D"), Some(Color::Rgb(0xe4, 0xa1, 0x5d)));
+        assert_eq!(parse_hex("e4a15d"), None);
+        assert_eq!(parse_hex("#e4a15"), None);
+        assert_eq!(parse_hex("#e4a15g"), None);
+        assert_eq!(parse_hex("#e4a1\u{e9}"), None);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::file::{FileFormat, SaveDir, SerializedFile};
+use anyhow::{anyhow, Context, Result};
+use serde_derive::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The GitHub API endpoint of the latest release of the program.
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/Acizza/anup/releases/latest";
+
+/// How long to wait for GitHub before giving up, since the check isn't worth holding anything up for.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A release of the program that is newer than the one running.
+pub struct Release {
+    /// The version of the release, without a leading `v`.
+    pub version: String,
+    /// The changes made in the release, as written in its notes on GitHub.
+    pub notes: String,
+    /// The page of the release on GitHub.
+    pub url: String,
+}
+
+impl Release {
+    /// Look up the latest release of the program on GitHub.
+    ///
+    /// Returns `Ok(None)` if it isn't newer than the running version, or if it was dismissed before.
+    pub fn check() -> Result<Option<Self>> {
+        let response = attohttpc::get(LATEST_RELEASE_URL)
+            .header("Accept", "application/vnd.github.v3+json")
+            // GitHub rejects requests without a user agent
+            .header("User-Agent", env!("CARGO_PKG_NAME"))
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .context("getting latest release")?;
+
+        if !response.is_success() {
+            return Err(anyhow!(
+                "GitHub returned {} when getting the latest release",
+                response.status()
+            ));
+        }
+
+        let latest = response
+            .json::<LatestRelease>()
+            .context("decoding latest release")?;
+
+        let version = latest.tag_name.trim_start_matches('v');
+
+        if !is_newer(version, env!("CARGO_PKG_VERSION")) {
+            return Ok(None);
+        }
+
+        let dismissed =
+            DismissedUpdate::load().map_or(false, |dismissed| dismissed.version == version);
+
+        if dismissed {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            version: version.into(),
+            notes: latest.body.unwrap_or_default(),
+            url: latest.html_url,
+        }))
+    }
+
+    /// Stop showing that this release is available. Newer releases are still shown.
+    pub fn dismiss(&self) -> Result<()> {
+        let dismissed = DismissedUpdate {
+            version: self.version.clone(),
+        };
+
+        dismissed.save().context("saving dismissed update")
+    }
+}
+
+#[derive(Deserialize)]
+struct LatestRelease {
+    tag_name: String,
+    body: Option<String>,
+    html_url: String,
+}
+
+/// The last release the user chose to stop being told about.
+#[derive(Default, Deserialize, Serialize)]
+struct DismissedUpdate {
+    version: String,
+}
+
+impl SerializedFile for DismissedUpdate {
+    fn filename() -> &'static str {
+        "dismissed_update"
+    }
+
+    fn save_dir() -> SaveDir {
+        SaveDir::LocalData
+    }
+
+    fn format() -> FileFormat {
+        FileFormat::Binary
+    }
+}
+
+/// Returns true if `version` comes after `current`. Versions that can't be parsed are never newer.
+fn is_newer(version: &str, current: &str) -> bool {
+    match (parse_version(version), parse_version(current)) {
+        (Some(version), Some(current)) => version > current,
+        _ => false,
+    }
+}
+
+/// Parse the major, minor, and patch numbers of a version like `0.4.1`, ignoring anything after a `-` or `+`.
+fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let version = version.split(&['-', '+'][..]).next()?;
+    let mut parts = version.split('.').map(str::parse);
+
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+
+    Some((major, minor, patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_versions() {
+        assert!(is_newer("0.5.0", "0.4.0"));
+        assert!(is_newer("0.4.10", "0.4.9"));
+        assert!(is_newer("1.0", "0.9.9"));
+        assert!(is_newer("0.4.1-beta", "0.4.0"));
+        assert!(!is_newer("0.4.0", "0.4.0"));
+        assert!(!is_newer("0.3.9", "0.4.0"));
+        assert!(!is_newer("nightly", "0.4.0"));
+    }
+}
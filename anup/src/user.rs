@@ -1,7 +1,13 @@
 use crate::file::{FileFormat, SaveDir, SerializedFile};
+use crate::{crypt, err};
 use anime::remote::{AccessToken, Remote};
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
 use serde_derive::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::str::FromStr;
 use tui_utils::list::EnumListItems;
 
 /// Represents all (non-offline) remote types from the anime library.
@@ -24,6 +30,17 @@ impl RemoteType {
     }
 }
 
+impl FromStr for RemoteType {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "anilist" => Ok(Self::AniList),
+            _ => Err(anyhow!("unknown service: {}", value)),
+        }
+    }
+}
+
 impl EnumListItems for RemoteType {
     fn items<'a>() -> &'a [Self] {
         &[Self::AniList]
@@ -50,6 +67,11 @@ impl UserInfo {
         }
     }
 
+    /// Returns an ID that uniquely identifies the user across every service.
+    pub fn account_id(&self) -> String {
+        format!("{}/{}", self.service.as_str(), self.username)
+    }
+
     pub fn is_logged_in(&self, remote: &Remote) -> bool {
         use anime::remote::anilist::AniList;
 
@@ -58,18 +80,48 @@ impl UserInfo {
                 AniList::Authenticated(auth) => auth.user.name == self.username,
                 AniList::Unauthenticated => false,
             },
-            (RemoteType::AniList, Remote::Offline(_)) => false,
+            (RemoteType::AniList, Remote::Mock(_) | Remote::Offline(_)) => false,
         }
     }
 }
 
-pub type UserMap = HashMap<UserInfo, AccessToken>;
+/// The name of the profile used when one isn't specified.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The access token of a user along with information about its usage.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct UserAccount {
+    pub token: AccessToken,
+    /// The profile the account belongs to.
+    pub profile: String,
+    /// The UTC timestamp of when the account was added.
+    pub added: i64,
+    /// The UTC timestamp of when the account was last logged in to.
+    pub last_used: Option<i64>,
+}
 
-/// A map containing all users along with the last used one.
+impl UserAccount {
+    fn new<S>(token: AccessToken, profile: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let now = Utc::now().timestamp();
+
+        Self {
+            token,
+            profile: profile.into(),
+            added: now,
+            last_used: Some(now),
+        }
+    }
+}
+
+pub type UserMap = HashMap<UserInfo, UserAccount>;
+
+/// A map containing all users along with their account information.
 #[derive(Default, Deserialize, Serialize)]
 pub struct Users {
     users: UserMap,
-    pub last_used: Option<UserInfo>,
 }
 
 impl Users {
@@ -77,44 +129,97 @@ impl Users {
     pub fn new() -> Self {
         Self {
             users: UserMap::new(),
-            last_used: None,
         }
     }
 
-    /// Adds a new (unique) `user` to the user map and sets the last used user to `user`.
-    pub fn add_and_set_last(&mut self, user: UserInfo, token: AccessToken) {
-        self.last_used = Some(user.clone());
-        self.users.insert(user, token);
+    /// Adds a new (unique) `user` to the user map under the specified `profile` and marks it as used.
+    ///
+    /// If `user` already exists, its token and profile will be replaced.
+    pub fn add<S>(&mut self, user: UserInfo, token: AccessToken, profile: S)
+    where
+        S: Into<String>,
+    {
+        let mut account = UserAccount::new(token, profile);
+
+        if let Some(existing) = self.users.get(&user) {
+            account.added = existing.added;
+        }
+
+        self.users.insert(user, account);
+    }
+
+    /// Marks the specified `user` as being used right now.
+    pub fn mark_used(&mut self, user: &UserInfo) {
+        if let Some(account) = self.users.get_mut(user) {
+            account.last_used = Some(Utc::now().timestamp());
+        }
     }
 
     /// Removes the specified `user` from the user map.
-    ///
-    /// This also unsets the last used user if it was set to `user`.
     pub fn remove(&mut self, user: &UserInfo) {
         self.users.remove(user);
+    }
 
-        if let Some(last) = &self.last_used {
-            if user == last {
-                self.last_used = None;
-            }
-        }
+    #[inline]
+    pub fn get(&self, user: &UserInfo) -> Option<&UserAccount> {
+        self.users.get(user)
+    }
+
+    /// Returns the most recently used user that belongs to `profile`.
+    pub fn last_used(&self, profile: &str) -> Option<(&UserInfo, &UserAccount)> {
+        self.users
+            .iter()
+            .filter(|(_, account)| account.profile == profile && account.last_used.is_some())
+            .max_by_key(|(_, account)| account.last_used)
+    }
+
+    /// Returns the access token of the most recently used user that belongs to `profile`.
+    pub fn take_last_used_token(self, profile: &str) -> Option<AccessToken> {
+        self.take_last_used_user(profile).map(|(_, token)| token)
     }
 
-    /// Returns the last used user's access token if it was set.
-    pub fn take_last_used_token(mut self) -> Option<AccessToken> {
-        let last = self.last_used?;
-        self.users.remove(&last)
+    /// Consumes the `Users` struct and returns the most recently used user that belongs to `profile`.
+    pub fn take_last_used_user(mut self, profile: &str) -> Option<(UserInfo, AccessToken)> {
+        let last = self.last_used(profile)?.0.clone();
+
+        self.users
+            .remove_entry(&last)
+            .map(|(user, account)| (user, account.token))
     }
 
-    /// Consumes the `Users` struct and returns the last used user.
-    pub fn take_last_used_user(mut self) -> Option<(UserInfo, AccessToken)> {
-        let last = self.last_used?;
-        self.users.remove_entry(&last)
+    /// Returns every user sorted by when they were last used, starting with the most recent.
+    pub fn sorted(&self) -> Vec<(&UserInfo, &UserAccount)> {
+        let mut users = self.users.iter().collect::<Vec<_>>();
+
+        users.sort_unstable_by(|(x_info, x), (y_info, y)| {
+            y.last_used
+                .cmp(&x.last_used)
+                .then_with(|| x_info.username.cmp(&y_info.username))
+        });
+
+        users
+    }
+
+    /// Returns every user that belongs to `profile`, sorted by their username.
+    pub fn in_profile(&self, profile: &str) -> Vec<(&UserInfo, &UserAccount)> {
+        let mut users = self
+            .users
+            .iter()
+            .filter(|(_, account)| account.profile == profile)
+            .collect::<Vec<_>>();
+
+        users.sort_unstable_by(|(x, _), (y, _)| {
+            x.username
+                .cmp(&y.username)
+                .then_with(|| x.service.as_str().cmp(y.service.as_str()))
+        });
+
+        users
     }
 
     #[inline(always)]
-    pub fn get(&self) -> &UserMap {
-        &self.users
+    pub fn is_empty(&self) -> bool {
+        self.users.is_empty()
     }
 
     #[inline(always)]
@@ -124,6 +229,106 @@ impl Users {
 }
 
 impl SerializedFile for Users {
+    fn filename() -> &'static str {
+        "accounts"
+    }
+
+    fn save_dir() -> SaveDir {
+        SaveDir::LocalData
+    }
+
+    fn format() -> FileFormat {
+        FileFormat::Binary
+    }
+
+    /// Load the accounts file, decrypting it with the passphrase of the current session if it's encrypted.
+    fn load() -> Result<Self> {
+        let path = Self::validated_save_path().context("getting path")?;
+
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return match LegacyUsers::migrate() {
+                    Ok(Some(users)) => Ok(users),
+                    Ok(None) => Err(err.into()),
+                    Err(legacy_err) => Err(legacy_err.context("migrating legacy users file")),
+                }
+            }
+            Err(err) => return Err(err).context("reading file"),
+        };
+
+        let data = if crypt::is_encrypted(&data) {
+            crypt::decrypt_for_session(&data).context("decrypting file")?
+        } else {
+            data
+        };
+
+        bincode::deserialize(&data).context("decoding bincode")
+    }
+
+    /// Save the accounts file, encrypting it if a passphrase was entered this session.
+    fn save(&self) -> Result<()> {
+        let path = Self::validated_save_path()?;
+        let data = bincode::serialize(self).context("encoding binary file")?;
+
+        let data = match crypt::session_passphrase() {
+            Some(passphrase) => crypt::encrypt(&data, &passphrase).context("encrypting file")?,
+            None => data,
+        };
+
+        fs::write(path, data).context("writing file")
+    }
+}
+
+/// The format users were stored in before account metadata was tracked.
+#[derive(Default, Deserialize, Serialize)]
+struct LegacyUsers {
+    users: HashMap<UserInfo, AccessToken>,
+    last_used: Option<UserInfo>,
+}
+
+impl LegacyUsers {
+    /// Converts the legacy users file to the current format and removes it.
+    ///
+    /// Returns `Ok(None)` if there is no legacy users file.
+    fn migrate() -> Result<Option<Users>> {
+        let legacy = match Self::load() {
+            Ok(legacy) => legacy,
+            Err(err) if err::is_file_nonexistant(&err) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        let now = Utc::now().timestamp();
+        let last_used_user = legacy.last_used;
+
+        let users = legacy
+            .users
+            .into_iter()
+            .map(|(info, token)| {
+                let last_used = (last_used_user.as_ref() == Some(&info)).then(|| now);
+
+                let account = UserAccount {
+                    token,
+                    profile: DEFAULT_PROFILE.into(),
+                    added: now,
+                    last_used,
+                };
+
+                (info, account)
+            })
+            .collect();
+
+        let users = Users { users };
+        users.save().context("saving migrated users")?;
+
+        let legacy_path = Self::validated_save_path()?;
+        fs::remove_file(legacy_path).context("removing legacy users file")?;
+
+        Ok(Some(users))
+    }
+}
+
+impl SerializedFile for LegacyUsers {
     fn filename() -> &'static str {
         "users"
     }
@@ -148,14 +353,57 @@ mod tests {
         let user1 = UserInfo::new(RemoteType::AniList, "User 1");
         let user1_duplicate = user1.clone();
 
-        users.add_and_set_last(user1, AccessToken::encode("token1"));
-        users.add_and_set_last(user1_duplicate, AccessToken::encode("token2"));
+        users.add(user1, AccessToken::encode("token1"), DEFAULT_PROFILE);
+        users.add(
+            user1_duplicate,
+            AccessToken::encode("token2"),
+            DEFAULT_PROFILE,
+        );
 
         assert_eq!(users.len(), 1);
 
         let user2 = UserInfo::new(RemoteType::AniList, "User 2");
-        users.add_and_set_last(user2, AccessToken::encode("token3"));
+        users.add(user2, AccessToken::encode("token3"), DEFAULT_PROFILE);
 
         assert_eq!(users.len(), 2);
     }
+
+    #[test]
+    fn last_used_per_profile() {
+        let mut users = Users::new();
+
+        let recent = UserInfo::new(RemoteType::AniList, "User 1");
+        let older = UserInfo::new(RemoteType::AniList, "User 2");
+        let work = UserInfo::new(RemoteType::AniList, "User 3");
+
+        users.add(
+            recent.clone(),
+            AccessToken::encode("token1"),
+            DEFAULT_PROFILE,
+        );
+        users.add(
+            older.clone(),
+            AccessToken::encode("token2"),
+            DEFAULT_PROFILE,
+        );
+        users.add(work.clone(), AccessToken::encode("token3"), "work profile");
+
+        users.users.get_mut(&recent).unwrap().last_used = Some(20);
+        users.users.get_mut(&older).unwrap().last_used = Some(10);
+        users.users.get_mut(&work).unwrap().last_used = Some(30);
+
+        let last_used =
+            |users: &Users, profile| users.last_used(profile).map(|(user, _)| user.clone());
+
+        assert!(last_used(&users, DEFAULT_PROFILE) == Some(recent.clone()));
+        assert!(last_used(&users, "work profile") == Some(work.clone()));
+        assert!(last_used(&users, "unknown").is_none());
+
+        let sorted = users
+            .sorted()
+            .into_iter()
+            .map(|(user, _)| user.clone())
+            .collect::<Vec<_>>();
+        assert!(sorted == vec![work, recent, older]);
+    }
 }
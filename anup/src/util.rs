@@ -1,7 +1,11 @@
+use anime::remote::SeriesDate;
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
 use parking_lot::Mutex;
 use std::{
     ops::{Deref, DerefMut},
+    path::Path,
     sync::Arc,
+    time::Instant,
 };
 use tokio::task;
 
@@ -31,12 +35,113 @@ where
 {
     let total_mins = total_mins.into();
 
-    let hours = (total_mins / 60.0).floor() as u8;
+    let hours = (total_mins / 60.0).floor() as u32;
     let minutes = (total_mins % 60.0).floor() as u8;
 
     format!("{:02}:{:02}H", hours, minutes)
 }
 
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Returns a text progress bar `width` characters wide that is `done / total` of the way filled.
+pub fn progress_bar(done: usize, total: usize, width: usize) -> String {
+    let filled = (done.min(total) * width)
+        .checked_div(total)
+        .unwrap_or(width);
+
+    format!("[{}{}]", "#".repeat(filled), "-".repeat(width - filled))
+}
+
+/// Returns the date `instant` falls on in the local time zone.
+pub fn local_date(instant: DateTime<Utc>) -> SeriesDate {
+    instant.with_timezone(&Local).date().naive_local().into()
+}
+
+/// Returns the instant `date` starts at in the local time zone.
+///
+/// `None` will be returned if `date` is invalid, or if midnight doesn't exist on it in the local time zone.
+pub fn local_midnight(date: SeriesDate) -> Option<DateTime<Utc>> {
+    let date =
+        chrono::NaiveDate::from_ymd_opt(date.year.into(), date.month.into(), date.day.into())?;
+
+    Local
+        .from_local_datetime(&date.and_hms(0, 0, 0))
+        .earliest()
+        .map(|midnight| midnight.with_timezone(&Utc))
+}
+
+/// Returns true if a process with the specified `pid` is running.
+///
+/// Processes can only be looked up on Linux, so this always returns false everywhere else.
+pub fn is_process_running(pid: u32) -> bool {
+    cfg!(target_os = "linux") && Path::new("/proc").join(pid.to_string()).exists()
+}
+
+/// Encode every byte of `value` that isn't allowed in a URL path segment as `%XX`.
+pub fn percent_encode(value: &str) -> String {
+    const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+    let mut encoded = String::with_capacity(value.len());
+
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char);
+            }
+            _ => {
+                encoded.push('%');
+                encoded.push(HEX_DIGITS[usize::from(byte >> 4)] as char);
+                encoded.push(HEX_DIGITS[usize::from(byte & 0xF)] as char);
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Decode every `%XX` sequence in `value`. Sequences that aren't valid are left as they are.
+pub fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
 pub type ArcMutex<T> = Arc<Mutex<T>>;
 
 pub fn arc_mutex<T>(value: T) -> ArcMutex<T> {
@@ -70,3 +175,57 @@ impl<T> Drop for ScopedTask<T> {
         self.0.abort();
     }
 }
+
+/// Detects when the system was asleep, by comparing the time that passed on the system clock with
+/// the time that passed on a clock that stops while the system is asleep.
+pub struct SleepDetector {
+    last_instant: Instant,
+    last_time: DateTime<Utc>,
+}
+
+impl SleepDetector {
+    /// Differences between the clocks smaller than this are ignored, since the system clock can be adjusted slightly at any time.
+    const MIN_SLEEP_SECS: i64 = 30;
+
+    pub fn new() -> Self {
+        Self {
+            last_instant: Instant::now(),
+            last_time: Utc::now(),
+        }
+    }
+
+    /// Returns how long the system was asleep since the last call.
+    pub fn take_slept(&mut self) -> Option<Duration> {
+        let now_instant = Instant::now();
+        let now_time = Utc::now();
+
+        let awake = Duration::from_std(now_instant - self.last_instant)
+            .unwrap_or_else(|_| Duration::zero());
+
+        let slept = (now_time - self.last_time) - awake;
+
+        self.last_instant = now_instant;
+        self.last_time = now_time;
+
+        Some(slept).filter(|slept| slept.num_seconds() >= Self::MIN_SLEEP_SECS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_sleep() {
+        let mut detector = SleepDetector::new();
+        assert_eq!(detector.take_slept(), None);
+
+        detector.last_time -= Duration::hours(2);
+
+        let slept = detector.take_slept().unwrap();
+        // The clocks are read at slightly different times, so they won't differ by exactly two hours
+        assert!((slept - Duration::hours(2)).num_seconds().abs() < 5);
+
+        assert_eq!(detector.take_slept(), None);
+    }
+}
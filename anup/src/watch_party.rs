@@ -0,0 +1,177 @@
+use crate::config::Config;
+use crate::database::Database;
+use crate::file::{FileFormat, SaveDir, SerializedFile};
+use crate::series::entry::SeriesEntry;
+use crate::series::SeriesData;
+use crate::user::{RemoteType, UserInfo, Users};
+use anime::remote::anilist::{AniList, Auth};
+use anime::remote::Remote;
+use anyhow::{anyhow, Context, Result};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+/// Accounts that watch together, whose lists are all updated whenever one of them completes an episode.
+#[derive(Default, Deserialize, Serialize)]
+pub struct WatchParty {
+    members: Vec<UserInfo>,
+    /// Members that have been logged in to this session.
+    #[serde(skip)]
+    remotes: HashMap<UserInfo, Remote>,
+}
+
+impl WatchParty {
+    #[inline]
+    pub fn contains(&self, user: &UserInfo) -> bool {
+        self.members.contains(user)
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Add `user` to the party, or remove them if they're already in it.
+    ///
+    /// Returns true if `user` is now in the party.
+    pub fn toggle(&mut self, user: &UserInfo) -> bool {
+        if let Some(pos) = self.members.iter().position(|member| member == user) {
+            self.members.remove(pos);
+            self.remotes.remove(user);
+            return false;
+        }
+
+        self.members.push(user.clone());
+        true
+    }
+
+    /// Remove `user` from the party, such as when their account is removed.
+    ///
+    /// Returns true if they were in it.
+    pub fn remove(&mut self, user: &UserInfo) -> bool {
+        self.remotes.remove(user);
+
+        let len = self.members.len();
+        self.members.retain(|member| member != user);
+        self.members.len() != len
+    }
+
+    /// Give every member of the party other than the database's current account the same progress as `data`.
+    ///
+    /// Each member's list entry is saved locally even if it can't be sent to their remote service,
+    /// so it can be sent once they log in.
+    ///
+    /// Returns an error for each member that couldn't be updated.
+    pub fn share_progress(
+        &mut self,
+        data: &SeriesData,
+        users: &Users,
+        config: &Config,
+        db: &Database,
+    ) -> Vec<anyhow::Error> {
+        let current = db.account();
+        let mut errors = Vec::new();
+
+        for member in &self.members {
+            if member.account_id() == current {
+                continue;
+            }
+
+            let result = Self::share_with(member, &mut self.remotes, data, users, config, db);
+
+            if let Err(err) = result {
+                errors.push(err.context(format!("failed to update {}'s list", member.username)));
+            }
+        }
+
+        errors
+    }
+
+    fn share_with(
+        member: &UserInfo,
+        remotes: &mut HashMap<UserInfo, Remote>,
+        data: &SeriesData,
+        users: &Users,
+        config: &Config,
+        db: &Database,
+    ) -> Result<()> {
+        let account_id = member.account_id();
+
+        let mut entry = SeriesEntry::load_or_new_for(db, &account_id, data.info.id)
+            .context("loading list entry")?;
+
+        if data.config.local_only {
+            entry.match_progress(&data.entry, config);
+            return entry
+                .save_for(db, &account_id)
+                .map(|_| ())
+                .map_err(Into::into);
+        }
+
+        let remote = match remotes.entry(member.clone()) {
+            Entry::Occupied(remote) => remote.into_mut(),
+            Entry::Vacant(vacant) => vacant.insert(Self::login(member, users, config)?),
+        };
+
+        entry.sync_from_remote(remote, &config.sync)?;
+        entry.match_progress(&data.entry, config);
+
+        // The entry should be saved even if it can't be sent right now
+        let sync_result =
+            entry.sync_to_remote_for(remote, db, &account_id, data.info.total_episodes());
+        entry.save_for(db, &account_id)?;
+
+        sync_result
+    }
+
+    fn login(member: &UserInfo, users: &Users, config: &Config) -> Result<Remote> {
+        let account = users
+            .get(member)
+            .ok_or_else(|| anyhow!("the account was removed"))?;
+
+        match member.service {
+            RemoteType::AniList => {
+                let auth = Auth::retrieve(account.token.clone())
+                    .context("logging in")?
+                    .with_score_rounding(config.score_rounding);
+
+                Ok(AniList::Authenticated(auth).into())
+            }
+        }
+    }
+}
+
+impl SerializedFile for WatchParty {
+    fn filename() -> &'static str {
+        "watch_party"
+    }
+
+    fn save_dir() -> SaveDir {
+        SaveDir::LocalData
+    }
+
+    fn format() -> FileFormat {
+        FileFormat::Config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggle_members() {
+        let mut party = WatchParty::default();
+        let user = UserInfo::new(RemoteType::AniList, "User 1");
+
+        assert!(party.toggle(&user));
+        assert!(party.contains(&user));
+
+        assert!(!party.toggle(&user));
+        assert!(party.is_empty());
+
+        party.toggle(&user);
+        assert!(party.remove(&user));
+        assert!(!party.remove(&user));
+    }
+}